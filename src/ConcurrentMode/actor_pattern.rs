@@ -12,15 +12,20 @@
  * 3. 无共享状态 - 避免了数据竞争
  * 4. 监督策略 - 处理Actor故障和重启
  * 5. 位置透明 - Actor可以在不同位置运行
+ * 6. 持久化 - 事件溯源Actor记录产生的事件，重启后重放事件恢复状态
+ * 7. 优雅关闭 - 观察共享的 ShutdownSignal，处理完邮箱中已有的消息后停止
  */
 
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 
+use super::shutdown::ShutdownSignal;
+
 // =================
 // 核心Actor特质和消息
 // =================
@@ -46,12 +51,19 @@ pub trait Actor: Send + 'static {
 }
 
 /// Actor引用，用于向Actor发送消息
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct ActorRef<M: Message> {
     sender: Sender<M>,
     name: String,
 }
 
+// 手动实现Clone，避免派生时要求消息类型也实现Clone
+impl<M: Message> Clone for ActorRef<M> {
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone(), name: self.name.clone() }
+    }
+}
+
 impl<M: Message> ActorRef<M> {
     pub fn new(sender: Sender<M>, name: String) -> Self {
         Self { sender, name }
@@ -105,11 +117,13 @@ impl<M: Message> ActorContext<M> {
 // =================
 
 /// Actor错误类型
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum ActorError {
     MailboxClosed(String),
     ActorNotFound(String),
     SystemShutdown,
+    /// 子Actor在时间窗口内重启次数超限，监督者放弃并停止
+    RestartLimitExceeded(String),
 }
 
 impl fmt::Display for ActorError {
@@ -118,6 +132,7 @@ impl fmt::Display for ActorError {
             ActorError::MailboxClosed(name) => write!(f, "Actor {} 的邮箱已关闭", name),
             ActorError::ActorNotFound(name) => write!(f, "未找到Actor: {}", name),
             ActorError::SystemShutdown => write!(f, "Actor系统正在关闭"),
+            ActorError::RestartLimitExceeded(name) => write!(f, "子Actor {} 重启过于频繁，监督者已停止", name),
         }
     }
 }
@@ -125,14 +140,26 @@ impl fmt::Display for ActorError {
 /// Actor系统，管理所有Actor
 pub struct ActorSystem {
     actors: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
-    shutdown: Arc<Mutex<bool>>,
+    /// 外部共享的关闭信号，可能同时被其他组件观察
+    shutdown: ShutdownSignal,
+    /// Actor系统自己的关闭信号，外部信号触发时随之触发；`shutdown()` 只触发它
+    local_shutdown: ShutdownSignal,
 }
 
 impl ActorSystem {
     pub fn new() -> Self {
+        Self::with_shutdown_signal(ShutdownSignal::new())
+    }
+    
+    /// 创建观察共享关闭信号的Actor系统
+    pub fn with_shutdown_signal(shutdown: ShutdownSignal) -> Self {
+        let local_shutdown = ShutdownSignal::new();
+        let observer = local_shutdown.clone();
+        shutdown.on_trigger(move || observer.trigger());
         Self {
             actors: Arc::new(Mutex::new(HashMap::new())),
-            shutdown: Arc::new(Mutex::new(false)),
+            shutdown,
+            local_shutdown,
         }
     }
     
@@ -145,11 +172,28 @@ impl ActorSystem {
         let actor_ref = ActorRef::new(sender.clone(), name.clone());
         let mut context = ActorContext::new();
         context.self_ref = Some(ActorRef::new(sender.clone(), name.clone()));
+        let shutdown = self.local_shutdown.clone();
+        // 同时登记到外部信号，共享信号的 shutdown_all 也会等待这些线程
+        let shutdown_guards = (self.shutdown.guard(), self.local_shutdown.guard());
         
         let handle = thread::spawn(move || {
+            let _shutdown_guards = shutdown_guards;
             actor.pre_start(&mut context);
             
-            while let Ok(message) = receiver.recv() {
+            // 定期检查关闭信号；信号触发后只处理邮箱中剩余的消息，邮箱为空时停止
+            loop {
+                let message = if shutdown.is_triggered() {
+                    match receiver.try_recv() {
+                        Ok(message) => message,
+                        Err(_) => break,
+                    }
+                } else {
+                    match receiver.recv_timeout(Duration::from_millis(10)) {
+                        Ok(message) => message,
+                        Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                };
                 actor.receive(message, &mut context);
                 
                 if context.should_stop() {
@@ -166,9 +210,9 @@ impl ActorSystem {
         Ok(actor_ref)
     }
     
-    /// 关闭Actor系统
+    /// 关闭Actor系统，只停止本系统的Actor，共享信号的其他观察者不受影响
     pub fn shutdown(&self) {
-        *self.shutdown.lock().unwrap() = true;
+        self.local_shutdown.trigger();
         
         // 等待所有Actor完成
         let mut actors = self.actors.lock().unwrap();
@@ -371,6 +415,531 @@ impl Actor for RouterActor {
     }
 }
 
+// =================
+// 持久化Actor（事件溯源）
+// =================
+
+/// 持久化Actor特质
+///
+/// 命令处理只产生事件，状态只能通过 `apply_event` 修改，
+/// 因此重放日志中的事件就能得到与崩溃前完全相同的状态。
+pub trait PersistentActor: Send + 'static {
+    type Command: Message;
+    type Event: Clone + Send + 'static;
+    type Snapshot: Clone + Send + 'static;
+    
+    /// 日志中标识该Actor的持久化ID，重建的Actor需使用相同的ID
+    fn persistence_id(&self) -> &str;
+    
+    /// 处理命令，返回需要持久化的事件
+    fn handle_command(&self, command: Self::Command, context: &mut ActorContext<Self::Command>) -> Vec<Self::Event>;
+    
+    /// 把事件应用到状态上
+    fn apply_event(&mut self, event: &Self::Event);
+    
+    /// 生成快照
+    fn snapshot(&self) -> Self::Snapshot;
+    
+    /// 从快照恢复状态
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
+
+struct JournalStream<E, S> {
+    events: Vec<(u64, E)>,
+    snapshot: Option<(u64, S)>,
+}
+
+/// 事件日志，可在多个Actor实例之间共享，模拟持久化存储
+pub struct EventJournal<E, S> {
+    streams: Arc<Mutex<HashMap<String, JournalStream<E, S>>>>,
+}
+
+impl<E, S> Clone for EventJournal<E, S> {
+    fn clone(&self) -> Self {
+        Self { streams: Arc::clone(&self.streams) }
+    }
+}
+
+impl<E: Clone, S: Clone> EventJournal<E, S> {
+    pub fn new() -> Self {
+        Self { streams: Arc::new(Mutex::new(HashMap::new())) }
+    }
+    
+    /// 追加事件
+    pub fn append(&self, persistence_id: &str, sequence: u64, event: E) {
+        let mut streams = self.streams.lock().unwrap();
+        streams.entry(persistence_id.to_string())
+            .or_insert_with(|| JournalStream { events: Vec::new(), snapshot: None })
+            .events.push((sequence, event));
+    }
+    
+    /// 保存快照，之后的恢复只需重放快照之后的事件
+    pub fn save_snapshot(&self, persistence_id: &str, sequence: u64, snapshot: S) {
+        let mut streams = self.streams.lock().unwrap();
+        streams.entry(persistence_id.to_string())
+            .or_insert_with(|| JournalStream { events: Vec::new(), snapshot: None })
+            .snapshot = Some((sequence, snapshot));
+    }
+    
+    /// 最新快照及其对应的事件序号
+    pub fn latest_snapshot(&self, persistence_id: &str) -> Option<(u64, S)> {
+        let streams = self.streams.lock().unwrap();
+        streams.get(persistence_id).and_then(|stream| stream.snapshot.clone())
+    }
+    
+    /// 序号大于 `after` 的事件
+    pub fn events_after(&self, persistence_id: &str, after: u64) -> Vec<(u64, E)> {
+        let streams = self.streams.lock().unwrap();
+        streams.get(persistence_id)
+            .map(|stream| stream.events.iter().filter(|(seq, _)| *seq > after).cloned().collect())
+            .unwrap_or_default()
+    }
+    
+    /// 日志中的事件总数
+    pub fn event_count(&self, persistence_id: &str) -> usize {
+        let streams = self.streams.lock().unwrap();
+        streams.get(persistence_id).map_or(0, |stream| stream.events.len())
+    }
+}
+
+impl<E: Clone, S: Clone> Default for EventJournal<E, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 把持久化Actor适配为普通Actor：启动时恢复，处理命令后记录事件
+struct PersistentActorAdapter<A: PersistentActor> {
+    actor: A,
+    journal: EventJournal<A::Event, A::Snapshot>,
+    sequence: u64,
+    snapshot_every: u64,
+    events_since_snapshot: u64,
+}
+
+impl<A: PersistentActor> Actor for PersistentActorAdapter<A> {
+    type Message = A::Command;
+    
+    fn receive(&mut self, message: Self::Message, context: &mut ActorContext<Self::Message>) {
+        let events = self.actor.handle_command(message, context);
+        let persistence_id = self.actor.persistence_id().to_string();
+        
+        for event in events {
+            // 先写日志再修改状态，崩溃时不会丢失已生效的事件
+            self.sequence += 1;
+            self.journal.append(&persistence_id, self.sequence, event.clone());
+            self.actor.apply_event(&event);
+            self.events_since_snapshot += 1;
+            
+            if self.snapshot_every > 0 && self.events_since_snapshot >= self.snapshot_every {
+                self.journal.save_snapshot(&persistence_id, self.sequence, self.actor.snapshot());
+                self.events_since_snapshot = 0;
+            }
+        }
+    }
+    
+    fn pre_start(&mut self, _context: &mut ActorContext<Self::Message>) {
+        let persistence_id = self.actor.persistence_id().to_string();
+        
+        if let Some((sequence, snapshot)) = self.journal.latest_snapshot(&persistence_id) {
+            self.actor.restore(snapshot);
+            self.sequence = sequence;
+        }
+        
+        let events = self.journal.events_after(&persistence_id, self.sequence);
+        for (sequence, event) in &events {
+            self.actor.apply_event(event);
+            self.sequence = *sequence;
+        }
+        self.events_since_snapshot = events.len() as u64;
+        
+        println!("[{}] 恢复完成: 快照之后重放 {} 个事件，当前序号 {}",
+                 persistence_id, events.len(), self.sequence);
+    }
+}
+
+impl ActorSystem {
+    /// 启动持久化Actor，每产生 `snapshot_every` 个事件保存一次快照（0表示不保存快照）
+    pub fn spawn_persistent<A>(
+        &self,
+        actor: A,
+        name: String,
+        journal: EventJournal<A::Event, A::Snapshot>,
+        snapshot_every: u64,
+    ) -> Result<ActorRef<A::Command>, ActorError>
+    where
+        A: PersistentActor,
+    {
+        let adapter = PersistentActorAdapter {
+            actor,
+            journal,
+            sequence: 0,
+            snapshot_every,
+            events_since_snapshot: 0,
+        };
+        self.spawn(adapter, name)
+    }
+}
+
+/// 账本命令
+#[derive(Debug)]
+pub enum LedgerCommand {
+    Deposit(i64),
+    Withdraw(i64),
+    GetState(Sender<LedgerState>),
+    /// 模拟进程崩溃
+    Crash,
+    Stop,
+}
+
+impl Message for LedgerCommand {}
+
+/// 账本事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerEvent {
+    Deposited(i64),
+    Withdrawn(i64),
+}
+
+/// 账本状态，同时用作快照
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LedgerState {
+    pub balance: i64,
+    pub transaction_count: u64,
+}
+
+/// 事件溯源的账本Actor
+pub struct LedgerActor {
+    ledger_id: String,
+    state: LedgerState,
+}
+
+impl LedgerActor {
+    pub fn new(ledger_id: String) -> Self {
+        Self { ledger_id, state: LedgerState::default() }
+    }
+}
+
+impl PersistentActor for LedgerActor {
+    type Command = LedgerCommand;
+    type Event = LedgerEvent;
+    type Snapshot = LedgerState;
+    
+    fn persistence_id(&self) -> &str {
+        &self.ledger_id
+    }
+    
+    fn handle_command(&self, command: Self::Command, context: &mut ActorContext<Self::Command>) -> Vec<Self::Event> {
+        match command {
+            LedgerCommand::Deposit(amount) if amount > 0 => vec![LedgerEvent::Deposited(amount)],
+            LedgerCommand::Withdraw(amount) if amount > 0 && amount <= self.state.balance => {
+                vec![LedgerEvent::Withdrawn(amount)]
+            }
+            LedgerCommand::Deposit(_) | LedgerCommand::Withdraw(_) => {
+                println!("[{}] 拒绝无效的交易命令", self.ledger_id);
+                Vec::new()
+            }
+            LedgerCommand::GetState(sender) => {
+                let _ = sender.send(self.state.clone());
+                Vec::new()
+            }
+            LedgerCommand::Crash => panic!("账本 {} 崩溃", self.ledger_id),
+            LedgerCommand::Stop => {
+                context.stop();
+                Vec::new()
+            }
+        }
+    }
+    
+    fn apply_event(&mut self, event: &Self::Event) {
+        match event {
+            LedgerEvent::Deposited(amount) => self.state.balance += amount,
+            LedgerEvent::Withdrawn(amount) => self.state.balance -= amount,
+        }
+        self.state.transaction_count += 1;
+    }
+    
+    fn snapshot(&self) -> Self::Snapshot {
+        self.state.clone()
+    }
+    
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.state = snapshot;
+    }
+}
+
+// =================
+// 监督树
+// =================
+
+/// 重启策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartStrategy {
+    /// 只重启失败的子Actor
+    OneForOne,
+    /// 任一子Actor失败时重启全部子Actor
+    OneForAll,
+}
+
+/// 重启强度：`within` 时间窗口内最多允许 `max_restarts` 次失败重启
+#[derive(Debug, Clone, Copy)]
+pub struct RestartIntensity {
+    pub max_restarts: usize,
+    pub within: Duration,
+}
+
+/// 创建子Actor的工厂，每次重启都会得到一个全新的实例
+pub type ChildFactory<M> = Box<dyn Fn() -> Box<dyn Actor<Message = M>> + Send>;
+
+/// 子Actor线程退出通知
+struct ChildExit {
+    name: String,
+    generation: u64,
+    panicked: bool,
+}
+
+struct SupervisedChild<M: Message> {
+    name: String,
+    factory: ChildFactory<M>,
+    actor_ref: ActorRef<M>,
+    generation: u64,
+    stop_flag: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// 监督者 - 管理一组子Actor，在子Actor恐慌时按策略重启
+///
+/// 正常停止（调用 `context.stop()`）的子Actor不会被重启。
+/// 重启后子Actor使用新的邮箱，调用方应通过 `child_ref` 获取最新引用。
+pub struct Supervisor<M: Message> {
+    strategy: RestartStrategy,
+    intensity: RestartIntensity,
+    children: Vec<SupervisedChild<M>>,
+    exit_sender: Sender<ChildExit>,
+    exit_receiver: Receiver<ChildExit>,
+    recent_failures: VecDeque<Instant>,
+    stopped: bool,
+}
+
+impl<M: Message> Supervisor<M> {
+    pub fn new(strategy: RestartStrategy, intensity: RestartIntensity) -> Self {
+        let (exit_sender, exit_receiver) = mpsc::channel();
+        Self {
+            strategy,
+            intensity,
+            children: Vec::new(),
+            exit_sender,
+            exit_receiver,
+            recent_failures: VecDeque::new(),
+            stopped: false,
+        }
+    }
+    
+    /// 添加并启动子Actor
+    pub fn add_child<F>(&mut self, name: &str, factory: F) -> ActorRef<M>
+    where
+        F: Fn() -> Box<dyn Actor<Message = M>> + Send + 'static,
+    {
+        let factory: ChildFactory<M> = Box::new(factory);
+        let (actor_ref, stop_flag, handle) = Self::start_child(name, &factory, 1, &self.exit_sender);
+        self.children.push(SupervisedChild {
+            name: name.to_string(),
+            factory,
+            actor_ref: actor_ref.clone(),
+            generation: 1,
+            stop_flag,
+            handle: Some(handle),
+        });
+        actor_ref
+    }
+    
+    /// 子Actor的当前引用
+    pub fn child_ref(&self, name: &str) -> Option<ActorRef<M>> {
+        self.children.iter().find(|child| child.name == name).map(|child| child.actor_ref.clone())
+    }
+    
+    /// 子Actor被重启的次数
+    pub fn restart_count(&self, name: &str) -> usize {
+        self.children.iter()
+            .find(|child| child.name == name)
+            .map_or(0, |child| (child.generation - 1) as usize)
+    }
+    
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+    
+    /// 处理子Actor的退出通知：最多等待 `wait` 直到第一个通知到达，再处理已到达的其余通知
+    ///
+    /// 返回本次重启的子Actor数量；重启强度超限时停止全部子Actor并上报错误。
+    pub fn supervise(&mut self, wait: Duration) -> Result<usize, ActorError> {
+        if self.stopped {
+            return Err(ActorError::SystemShutdown);
+        }
+        
+        let mut exits = Vec::new();
+        if let Ok(exit) = self.exit_receiver.recv_timeout(wait) {
+            exits.push(exit);
+            exits.extend(self.exit_receiver.try_iter());
+        }
+        
+        let mut restarted = 0;
+        for exit in exits {
+            restarted += self.handle_exit(exit)?;
+        }
+        Ok(restarted)
+    }
+    
+    /// 停止全部子Actor
+    pub fn stop_all(&mut self) {
+        for child in &mut self.children {
+            Self::stop_child(child);
+        }
+        self.stopped = true;
+    }
+    
+    fn handle_exit(&mut self, exit: ChildExit) -> Result<usize, ActorError> {
+        // 被监督者主动停止的旧实例也会发出通知，按代数过滤
+        let Some(index) = self.children.iter().position(|child| child.name == exit.name && child.generation == exit.generation) else {
+            return Ok(0);
+        };
+        if !exit.panicked {
+            return Ok(0);
+        }
+        
+        let now = Instant::now();
+        self.recent_failures.push_back(now);
+        while let Some(&oldest) = self.recent_failures.front() {
+            if now.duration_since(oldest) > self.intensity.within {
+                self.recent_failures.pop_front();
+            } else {
+                break;
+            }
+        }
+        if self.recent_failures.len() > self.intensity.max_restarts {
+            println!("监督者: {} 在 {:?} 内失败 {} 次，放弃重启", exit.name, self.intensity.within, self.recent_failures.len());
+            self.stop_all();
+            return Err(ActorError::RestartLimitExceeded(exit.name));
+        }
+        
+        let targets: Vec<usize> = match self.strategy {
+            RestartStrategy::OneForOne => vec![index],
+            RestartStrategy::OneForAll => (0..self.children.len()).collect(),
+        };
+        for &target in &targets {
+            self.restart_child(target);
+        }
+        Ok(targets.len())
+    }
+    
+    fn restart_child(&mut self, index: usize) {
+        let child = &mut self.children[index];
+        Self::stop_child(child);
+        
+        child.generation += 1;
+        println!("监督者: 重启 {} (第 {} 次)", child.name, child.generation - 1);
+        let (actor_ref, stop_flag, handle) = Self::start_child(&child.name, &child.factory, child.generation, &self.exit_sender);
+        child.actor_ref = actor_ref;
+        child.stop_flag = stop_flag;
+        child.handle = Some(handle);
+    }
+    
+    fn stop_child(child: &mut SupervisedChild<M>) {
+        child.stop_flag.store(true, Ordering::SeqCst);
+        if let Some(handle) = child.handle.take() {
+            let _ = handle.join();
+        }
+    }
+    
+    fn start_child(
+        name: &str,
+        factory: &ChildFactory<M>,
+        generation: u64,
+        exit_sender: &Sender<ChildExit>,
+    ) -> (ActorRef<M>, Arc<AtomicBool>, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel();
+        let actor_ref = ActorRef::new(sender.clone(), name.to_string());
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let mut actor = factory();
+        
+        let child_name = name.to_string();
+        let child_stop = Arc::clone(&stop_flag);
+        let exit_sender = exit_sender.clone();
+        let handle = thread::spawn(move || {
+            let mut context = ActorContext::new();
+            context.self_ref = Some(ActorRef::new(sender, child_name.clone()));
+            
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                actor.pre_start(&mut context);
+                if generation > 1 {
+                    actor.post_restart(&mut context);
+                }
+                
+                // 定期检查停止标志，以便监督者能停止阻塞在邮箱上的子Actor
+                while !child_stop.load(Ordering::SeqCst) {
+                    match receiver.recv_timeout(Duration::from_millis(10)) {
+                        Ok(message) => {
+                            actor.receive(message, &mut context);
+                            if context.should_stop() {
+                                break;
+                            }
+                        }
+                        Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                actor.pre_stop(&mut context);
+            }));
+            
+            let _ = exit_sender.send(ChildExit { name: child_name, generation, panicked: result.is_err() });
+        });
+        
+        (actor_ref, stop_flag, handle)
+    }
+}
+
+/// 工作者消息
+#[derive(Debug)]
+pub enum WorkerMessage {
+    Work(u32),
+    /// 模拟处理中的故障，工作者会恐慌
+    Crash,
+    Stop,
+}
+
+impl Message for WorkerMessage {}
+
+/// 受监督的工作者Actor
+pub struct WorkerActor {
+    name: String,
+    processed: u32,
+}
+
+impl WorkerActor {
+    pub fn new(name: String) -> Self {
+        Self { name, processed: 0 }
+    }
+}
+
+impl Actor for WorkerActor {
+    type Message = WorkerMessage;
+    
+    fn receive(&mut self, message: Self::Message, context: &mut ActorContext<Self::Message>) {
+        match message {
+            WorkerMessage::Work(job) => {
+                self.processed += 1;
+                println!("[{}] 处理任务 {}，已处理 {} 个", self.name, job, self.processed);
+            }
+            WorkerMessage::Crash => panic!("[{}] 处理任务时发生故障", self.name),
+            WorkerMessage::Stop => context.stop(),
+        }
+    }
+    
+    fn post_restart(&mut self, _context: &mut ActorContext<Self::Message>) {
+        println!("[{}] 重启完成，状态已重置", self.name);
+    }
+}
+
 // =================
 // 演示函数
 // =================
@@ -467,8 +1036,52 @@ pub fn demo_actor_pattern() {
         println!("平均每秒处理: {:.0} 条消息", final_count as f64 / elapsed.as_secs_f64());
     }
     
+    // 持久化Actor：崩溃后通过日志重放恢复
+    println!("\n5. 持久化Actor演示:");
+    let journal = EventJournal::new();
+    let ledger_ref = system.spawn_persistent(LedgerActor::new("ledger-1".to_string()), "ledger".to_string(), journal.clone(), 3).unwrap();
+    for amount in [100, 50, 30, 20] {
+        ledger_ref.tell(LedgerCommand::Deposit(amount)).unwrap();
+    }
+    ledger_ref.tell(LedgerCommand::Withdraw(70)).unwrap();
+    ledger_ref.tell(LedgerCommand::Stop).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    
+    let recovered_ref = system.spawn_persistent(LedgerActor::new("ledger-1".to_string()), "ledger".to_string(), journal.clone(), 3).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    recovered_ref.tell(LedgerCommand::GetState(sender)).unwrap();
+    if let Ok(state) = receiver.recv_timeout(Duration::from_secs(1)) {
+        println!("恢复后的余额: {}，交易数: {}，日志事件数: {}", state.balance, state.transaction_count, journal.event_count("ledger-1"));
+    }
+    recovered_ref.tell(LedgerCommand::Stop).unwrap();
+    
+    // 监督树：子Actor恐慌后由监督者重启
+    println!("\n6. 监督树演示:");
+    let mut supervisor = Supervisor::new(
+        RestartStrategy::OneForOne,
+        RestartIntensity { max_restarts: 3, within: Duration::from_secs(5) },
+    );
+    for name in ["工作者1", "工作者2"] {
+        supervisor.add_child(name, move || Box::new(WorkerActor::new(name.to_string())));
+    }
+    if let Some(worker) = supervisor.child_ref("工作者1") {
+        worker.tell(WorkerMessage::Work(1)).unwrap();
+        worker.tell(WorkerMessage::Crash).unwrap();
+    }
+    match supervisor.supervise(Duration::from_millis(100)) {
+        Ok(0) => println!("没有子Actor失败"),
+        Ok(restarted) => println!("重启了 {} 个子Actor", restarted),
+        Err(e) => println!("监督者停止: {}", e),
+    }
+    if let Some(worker) = supervisor.child_ref("工作者1") {
+        println!("重启次数: {}", supervisor.restart_count("工作者1"));
+        worker.tell(WorkerMessage::Work(2)).unwrap();
+        thread::sleep(Duration::from_millis(50));
+    }
+    supervisor.stop_all();
+    
     // 停止所有Actor
-    println!("\n5. 停止所有Actor:");
+    println!("\n7. 停止所有Actor:");
     counter_ref.tell(CounterMessage::Stop).unwrap();
     account1_ref.tell(BankMessage::Stop).unwrap();
     account2_ref.tell(BankMessage::Stop).unwrap();
@@ -487,4 +1100,145 @@ pub fn demo_actor_pattern() {
     println!("✓ 容错处理 - 支持Actor监督和重启策略");
     println!("✓ 位置透明 - Actor可以在不同位置运行");
     println!("✓ 背压处理 - 通过邮箱大小控制消息流量");
-} 
\ No newline at end of file
+    println!("✓ 事件溯源 - 持久化Actor重放日志恢复状态，快照限制重放长度");
+    println!("✓ 监督树 - OneForOne/OneForAll重启策略，重启过于频繁时上报");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_state(ledger: &ActorRef<LedgerCommand>) -> LedgerState {
+        let (sender, receiver) = mpsc::channel();
+        ledger.tell(LedgerCommand::GetState(sender)).unwrap();
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    /// 等待Actor线程退出，返回线程是否因 panic 结束
+    fn join_actor(system: &ActorSystem, name: &str) -> bool {
+        let handle = system.actors.lock().unwrap().remove(name).unwrap();
+        handle.join().is_err()
+    }
+
+    #[test]
+    fn test_recreated_actor_recovers_state_from_journal() {
+        let system = ActorSystem::new();
+        let journal = EventJournal::new();
+
+        let ledger = system.spawn_persistent(LedgerActor::new("acc-1".to_string()), "ledger".to_string(), journal.clone(), 0).unwrap();
+        for command in [LedgerCommand::Deposit(100), LedgerCommand::Withdraw(30), LedgerCommand::Withdraw(500), LedgerCommand::Deposit(5)] {
+            ledger.tell(command).unwrap();
+        }
+        let before_crash = query_state(&ledger);
+        assert_eq!((before_crash.balance, before_crash.transaction_count), (75, 3));
+
+        // 崩溃后邮箱关闭，日志仍然保留
+        ledger.tell(LedgerCommand::Crash).unwrap();
+        assert!(join_actor(&system, "ledger"));
+        assert!(ledger.tell(LedgerCommand::Deposit(1)).is_err());
+
+        let recovered = system.spawn_persistent(LedgerActor::new("acc-1".to_string()), "ledger".to_string(), journal.clone(), 0).unwrap();
+        let after_recovery = query_state(&recovered);
+
+        assert_eq!(after_recovery, before_crash);
+        // 恢复后继续处理新命令
+        recovered.tell(LedgerCommand::Deposit(25)).unwrap();
+        assert_eq!(query_state(&recovered).balance, 100);
+        assert_eq!(journal.event_count("acc-1"), 4);
+    }
+
+    #[test]
+    fn test_snapshot_bounds_replay_length() {
+        let system = ActorSystem::new();
+        let journal = EventJournal::new();
+
+        let ledger = system.spawn_persistent(LedgerActor::new("acc-2".to_string()), "ledger".to_string(), journal.clone(), 4).unwrap();
+        for _ in 0..10 {
+            ledger.tell(LedgerCommand::Deposit(10)).unwrap();
+        }
+        let before = query_state(&ledger);
+        ledger.tell(LedgerCommand::Stop).unwrap();
+        assert!(!join_actor(&system, "ledger"));
+
+        let recovered = system.spawn_persistent(LedgerActor::new("acc-2".to_string()), "ledger".to_string(), journal.clone(), 4).unwrap();
+        let after = query_state(&recovered);
+
+        assert_eq!(after, before);
+        // 第8个事件时保存了快照，恢复只需重放之后的2个事件
+        let (snapshot_seq, snapshot) = journal.latest_snapshot("acc-2").unwrap();
+        assert_eq!(snapshot_seq, 8);
+        assert_eq!(snapshot.balance, 80);
+        assert_eq!(journal.events_after("acc-2", snapshot_seq).len(), 2);
+        assert_eq!(journal.event_count("acc-2"), 10);
+    }
+
+    // 启动三个工作者，返回各自被工厂创建的次数
+    fn supervised_workers(strategy: RestartStrategy, max_restarts: usize) -> (Supervisor<WorkerMessage>, HashMap<&'static str, Arc<Mutex<usize>>>) {
+        let mut supervisor = Supervisor::new(strategy, RestartIntensity { max_restarts, within: Duration::from_secs(10) });
+        let mut starts = HashMap::new();
+        for name in ["a", "b", "c"] {
+            let counter = Arc::new(Mutex::new(0));
+            let factory_counter = Arc::clone(&counter);
+            supervisor.add_child(name, move || {
+                *factory_counter.lock().unwrap() += 1;
+                Box::new(WorkerActor::new(name.to_string()))
+            });
+            starts.insert(name, counter);
+        }
+        (supervisor, starts)
+    }
+
+    fn start_counts(starts: &HashMap<&'static str, Arc<Mutex<usize>>>) -> Vec<usize> {
+        ["a", "b", "c"].iter().map(|name| *starts[name].lock().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_one_for_one_restarts_only_failed_child() {
+        let (mut supervisor, starts) = supervised_workers(RestartStrategy::OneForOne, 3);
+
+        let old_b = supervisor.child_ref("b").unwrap();
+        old_b.tell(WorkerMessage::Crash).unwrap();
+
+        assert_eq!(supervisor.supervise(Duration::from_secs(5)), Ok(1));
+        assert_eq!(start_counts(&starts), vec![1, 2, 1]);
+        assert_eq!(supervisor.restart_count("b"), 1);
+        assert_eq!(supervisor.restart_count("a"), 0);
+
+        // 重启后的子Actor使用新邮箱，需要通过监督者获取最新引用
+        supervisor.child_ref("b").unwrap().tell(WorkerMessage::Work(1)).unwrap();
+        supervisor.stop_all();
+    }
+
+    #[test]
+    fn test_one_for_all_restarts_every_child_and_ignores_normal_stop() {
+        let (mut supervisor, starts) = supervised_workers(RestartStrategy::OneForAll, 3);
+
+        supervisor.child_ref("a").unwrap().tell(WorkerMessage::Crash).unwrap();
+        assert_eq!(supervisor.supervise(Duration::from_secs(5)), Ok(3));
+        assert_eq!(start_counts(&starts), vec![2, 2, 2]);
+
+        // 被监督者停止的旧实例不会再次触发重启；正常停止的子Actor也不会重启
+        supervisor.child_ref("c").unwrap().tell(WorkerMessage::Stop).unwrap();
+        assert_eq!(supervisor.supervise(Duration::from_millis(200)), Ok(0));
+        assert_eq!(start_counts(&starts), vec![2, 2, 2]);
+        supervisor.stop_all();
+    }
+
+    #[test]
+    fn test_supervisor_escalates_when_restart_intensity_exceeded() {
+        let (mut supervisor, starts) = supervised_workers(RestartStrategy::OneForOne, 2);
+
+        for _ in 0..2 {
+            supervisor.child_ref("a").unwrap().tell(WorkerMessage::Crash).unwrap();
+            assert_eq!(supervisor.supervise(Duration::from_secs(5)), Ok(1));
+        }
+        supervisor.child_ref("a").unwrap().tell(WorkerMessage::Crash).unwrap();
+
+        assert_eq!(supervisor.supervise(Duration::from_secs(5)), Err(ActorError::RestartLimitExceeded("a".to_string())));
+        assert!(supervisor.is_stopped());
+        assert_eq!(start_counts(&starts), vec![3, 1, 1]);
+        // 监督者停止后所有子Actor都已退出
+        assert!(supervisor.child_ref("b").unwrap().tell(WorkerMessage::Work(1)).is_err());
+        assert_eq!(supervisor.supervise(Duration::ZERO), Err(ActorError::SystemShutdown));
+    }
+}
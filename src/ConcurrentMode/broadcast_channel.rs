@@ -0,0 +1,387 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/ConcurrentMode/broadcast_channel.rs
+ *
+ * 有界广播通道 (Bounded Broadcast Channel)
+ *
+ * 发布订阅和观察者都需要把同一条消息分发给多个接收方。广播通道为每个订阅者维护独立的有界缓冲区，
+ * 订阅者按自己的节奏消费；缓冲区满时的行为由订阅者各自选择：
+ *
+ * 1. 落后丢弃 (DropOldest) - 丢弃最旧的消息，下一次接收时报告丢弃的条数，发送方从不因它等待
+ * 2. 背压 (Block) - 发送方阻塞到该订阅者腾出空间，保证不丢消息
+ *
+ * 快速订阅者不受慢速订阅者影响：落后的订阅者只会丢自己的消息，
+ * 只有选择背压模式的订阅者才会拖慢发送方。
+ */
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// 订阅者缓冲区满时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// 丢弃最旧的消息并记录落后条数
+    DropOldest,
+    /// 阻塞发送方直到有空间
+    Block,
+}
+
+/// 广播通道错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastError {
+    /// 订阅者落后，期间丢弃了这么多条最旧的消息
+    Lagged(u64),
+    /// 背压模式的订阅者缓冲区已满（仅 `try_send`）
+    Full,
+    /// 缓冲区为空（仅 `try_recv`）
+    Empty,
+    /// 通道已关闭
+    Closed,
+}
+
+impl fmt::Display for BroadcastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BroadcastError::Lagged(count) => write!(f, "订阅者落后，丢弃了 {} 条消息", count),
+            BroadcastError::Full => write!(f, "订阅者缓冲区已满"),
+            BroadcastError::Empty => write!(f, "缓冲区为空"),
+            BroadcastError::Closed => write!(f, "通道已关闭"),
+        }
+    }
+}
+
+struct SubscriberQueue<T> {
+    buffer: VecDeque<T>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// 尚未报告给订阅者的丢弃条数
+    lagged: u64,
+    dropped: u64,
+}
+
+impl<T> SubscriberQueue<T> {
+    fn is_full(&self) -> bool {
+        self.buffer.len() >= self.capacity
+    }
+}
+
+struct ChannelState<T> {
+    subscribers: HashMap<usize, SubscriberQueue<T>>,
+    next_id: usize,
+    closed: bool,
+}
+
+impl<T> ChannelState<T> {
+    /// 是否有背压模式的订阅者缓冲区已满
+    fn blocked(&self) -> bool {
+        self.subscribers.values().any(|queue| queue.policy == OverflowPolicy::Block && queue.is_full())
+    }
+}
+
+struct Shared<T> {
+    state: Mutex<ChannelState<T>>,
+    /// 订阅者取走消息或退订后通知发送方
+    space_available: Condvar,
+    /// 发送消息或关闭通道后通知订阅者
+    message_available: Condvar,
+}
+
+/// 有界广播通道，可克隆给多个发送方
+pub struct BroadcastChannel<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for BroadcastChannel<T> {
+    fn clone(&self) -> Self {
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T: Clone> Default for BroadcastChannel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> BroadcastChannel<T> {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(ChannelState { subscribers: HashMap::new(), next_id: 0, closed: false }),
+                space_available: Condvar::new(),
+                message_available: Condvar::new(),
+            }),
+        }
+    }
+
+    /// 订阅之后发送的消息，`capacity` 为该订阅者的缓冲区大小
+    pub fn subscribe(&self, capacity: usize, policy: OverflowPolicy) -> Subscriber<T> {
+        let mut state = self.shared.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.subscribers.insert(id, SubscriberQueue {
+            buffer: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            policy,
+            lagged: 0,
+            dropped: 0,
+        });
+        Subscriber { id, shared: Arc::clone(&self.shared) }
+    }
+
+    /// 发送给所有订阅者，返回接收到消息的订阅者数量
+    ///
+    /// 有背压模式的订阅者缓冲区已满时阻塞，直到它腾出空间、退订或通道关闭。
+    pub fn send(&self, message: T) -> Result<usize, BroadcastError> {
+        let state = self.shared.state.lock().unwrap();
+        let state = self.shared.space_available
+            .wait_while(state, |state| !state.closed && state.blocked())
+            .unwrap();
+        self.deliver(state, message)
+    }
+
+    /// 非阻塞发送，有背压模式的订阅者缓冲区已满时返回 `Full`，消息不会发给任何订阅者
+    pub fn try_send(&self, message: T) -> Result<usize, BroadcastError> {
+        let state = self.shared.state.lock().unwrap();
+        if !state.closed && state.blocked() {
+            return Err(BroadcastError::Full);
+        }
+        self.deliver(state, message)
+    }
+
+    /// 关闭通道：之后的发送失败，订阅者取完缓冲区中的消息后收到 `Closed`
+    pub fn close(&self) {
+        self.shared.state.lock().unwrap().closed = true;
+        self.shared.space_available.notify_all();
+        self.shared.message_available.notify_all();
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.state.lock().unwrap().subscribers.len()
+    }
+
+    fn deliver(&self, mut state: MutexGuard<'_, ChannelState<T>>, message: T) -> Result<usize, BroadcastError> {
+        if state.closed {
+            return Err(BroadcastError::Closed);
+        }
+        let receivers = state.subscribers.len();
+        for queue in state.subscribers.values_mut() {
+            if queue.is_full() {
+                // 只有落后丢弃模式的缓冲区会在这里是满的
+                queue.buffer.pop_front();
+                queue.lagged += 1;
+                queue.dropped += 1;
+            }
+            queue.buffer.push_back(message.clone());
+        }
+        drop(state);
+        self.shared.message_available.notify_all();
+        Ok(receivers)
+    }
+}
+
+/// 订阅者，丢弃时自动退订
+pub struct Subscriber<T> {
+    id: usize,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Subscriber<T> {
+    /// 阻塞接收下一条消息；落后时先返回一次 `Lagged`，之后继续接收剩余的消息
+    pub fn recv(&self) -> Result<T, BroadcastError> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            match self.take(&mut state) {
+                Err(BroadcastError::Empty) => state = self.shared.message_available.wait(state).unwrap(),
+                result => return result,
+            }
+        }
+    }
+
+    /// 非阻塞接收
+    pub fn try_recv(&self) -> Result<T, BroadcastError> {
+        let mut state = self.shared.state.lock().unwrap();
+        self.take(&mut state)
+    }
+
+    /// 缓冲区中待接收的消息数
+    pub fn pending(&self) -> usize {
+        self.shared.state.lock().unwrap().subscribers[&self.id].buffer.len()
+    }
+
+    /// 累计丢弃的消息数
+    pub fn dropped_count(&self) -> u64 {
+        self.shared.state.lock().unwrap().subscribers[&self.id].dropped
+    }
+
+    fn take(&self, state: &mut MutexGuard<'_, ChannelState<T>>) -> Result<T, BroadcastError> {
+        let closed = state.closed;
+        let queue = state.subscribers.get_mut(&self.id).expect("订阅者在退订前始终存在");
+        if queue.lagged > 0 {
+            return Err(BroadcastError::Lagged(std::mem::take(&mut queue.lagged)));
+        }
+        match queue.buffer.pop_front() {
+            Some(message) => {
+                if queue.policy == OverflowPolicy::Block {
+                    self.shared.space_available.notify_all();
+                }
+                Ok(message)
+            }
+            None if closed => Err(BroadcastError::Closed),
+            None => Err(BroadcastError::Empty),
+        }
+    }
+}
+
+impl<T> Drop for Subscriber<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.subscribers.remove(&self.id);
+        drop(state);
+        // 退订的背压订阅者不再阻塞发送方
+        self.shared.space_available.notify_all();
+    }
+}
+
+/// 有界广播通道演示
+pub fn demo_broadcast_channel() {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    println!("=== 有界广播通道演示 ===\n");
+
+    let channel = BroadcastChannel::new();
+    let dashboard = channel.subscribe(16, OverflowPolicy::DropOldest);
+    let mobile = channel.subscribe(2, OverflowPolicy::DropOldest);
+    let audit = channel.subscribe(2, OverflowPolicy::Block);
+
+    // 审计订阅者处理较慢且不能丢消息，对发送方施加背压
+    let audit_thread = thread::spawn(move || {
+        let mut received = Vec::new();
+        while let Ok(message) = audit.recv() {
+            thread::sleep(Duration::from_millis(5));
+            received.push(message);
+        }
+        received
+    });
+
+    let start = Instant::now();
+    for price in 100..110 {
+        let receivers = channel.send(price).unwrap();
+        if price == 100 {
+            println!("行情 {} 发送给 {} 个订阅者", price, receivers);
+        }
+    }
+    println!("发送10条行情耗时 {:?}（受审计订阅者背压限制）", start.elapsed());
+    // 非阻塞发送在审计订阅者缓冲区满时直接失败，不会投递给任何订阅者
+    match channel.try_send(110) {
+        Ok(receivers) => println!("非阻塞发送成功，{} 个订阅者收到", receivers),
+        Err(e) => println!("非阻塞发送失败: {}", e),
+    }
+    println!("手机缓冲区待接收: {} 条", mobile.pending());
+    channel.close();
+
+    let drain = |name: &str, subscriber: &Subscriber<i32>| {
+        let mut received = Vec::new();
+        loop {
+            match subscriber.try_recv() {
+                Ok(message) => received.push(message),
+                Err(BroadcastError::Lagged(count)) => println!("{} 落后，丢弃 {} 条", name, count),
+                Err(_) => break,
+            }
+        }
+        println!("{} 收到: {:?}", name, received);
+    };
+    drain("看板", &dashboard);
+    drain("手机", &mobile);
+    println!("手机累计丢弃: {}, 剩余订阅者: {}", mobile.dropped_count(), channel.subscriber_count());
+    println!("审计收到: {:?}", audit_thread.join().unwrap_or_default());
+
+    println!("\n【有界广播通道特点】");
+    println!("✓ 独立缓冲 - 每个订阅者有自己的有界缓冲区");
+    println!("✓ 落后丢弃 - 慢速订阅者丢弃最旧消息并报告丢弃条数");
+    println!("✓ 背压 - 不能丢消息的订阅者让发送方等待");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_lagging_subscriber_drops_oldest_and_reports_count() {
+        let channel = BroadcastChannel::new();
+        let slow = channel.subscribe(3, OverflowPolicy::DropOldest);
+        let fast = channel.subscribe(10, OverflowPolicy::DropOldest);
+
+        for message in 1..=5 {
+            assert_eq!(channel.try_send(message), Ok(2));
+        }
+
+        assert_eq!(slow.pending(), 3);
+        assert_eq!(slow.try_recv(), Err(BroadcastError::Lagged(2)));
+        assert_eq!((1..=3).map(|_| slow.try_recv().unwrap()).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(slow.try_recv(), Err(BroadcastError::Empty));
+        assert_eq!(slow.dropped_count(), 2);
+
+        // 快速订阅者不受影响
+        assert_eq!((1..=5).map(|_| fast.try_recv().unwrap()).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(fast.dropped_count(), 0);
+    }
+
+    #[test]
+    fn test_backpressure_subscriber_blocks_sender_until_it_catches_up() {
+        let channel = BroadcastChannel::new();
+        let strict = channel.subscribe(2, OverflowPolicy::Block);
+        let fast = channel.subscribe(100, OverflowPolicy::DropOldest);
+
+        channel.send(1).unwrap();
+        channel.send(2).unwrap();
+        assert_eq!(channel.try_send(3), Err(BroadcastError::Full));
+
+        let (done_sender, done) = mpsc::channel();
+        let sender = channel.clone();
+        let handle = thread::spawn(move || {
+            let result = sender.send(3);
+            done_sender.send(()).unwrap();
+            result
+        });
+
+        // 发送方阻塞期间，快速订阅者照常接收已投递的消息
+        assert!(done.recv_timeout(Duration::from_millis(50)).is_err());
+        assert_eq!(fast.try_recv(), Ok(1));
+        assert_eq!(fast.try_recv(), Ok(2));
+        assert_eq!(fast.try_recv(), Err(BroadcastError::Empty));
+
+        assert_eq!(strict.recv(), Ok(1));
+        done.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(handle.join().unwrap(), Ok(2));
+        assert_eq!(strict.recv(), Ok(2));
+        assert_eq!(strict.recv(), Ok(3));
+        assert_eq!(fast.try_recv(), Ok(3));
+        assert_eq!(strict.dropped_count(), 0);
+    }
+
+    #[test]
+    fn test_close_and_unsubscribe_release_blocked_sender() {
+        let channel = BroadcastChannel::new();
+        let strict = channel.subscribe(1, OverflowPolicy::Block);
+        channel.send("a").unwrap();
+
+        let sender = channel.clone();
+        let handle = thread::spawn(move || sender.send("b"));
+        thread::sleep(Duration::from_millis(20));
+        // 退订后发送方不再等待
+        drop(strict);
+        assert_eq!(handle.join().unwrap(), Ok(0));
+
+        let late = channel.subscribe(4, OverflowPolicy::DropOldest);
+        channel.send("c").unwrap();
+        channel.close();
+        assert_eq!(channel.send("d"), Err(BroadcastError::Closed));
+        assert_eq!(late.recv(), Ok("c"));
+        assert_eq!(late.recv(), Err(BroadcastError::Closed));
+    }
+}
@@ -12,6 +12,9 @@
  * 3. 结果合并 - 将子任务结果合并为最终结果
  * 4. 工作窃取 - 空闲线程可以窃取其他线程的任务
  * 5. 动态负载均衡 - 自动平衡工作负载
+ * 
+ * 除了基于ForkJoinTask特质的线程池外，还提供了par_map_reduce，
+ * 直接在借用的切片上递归拆分、并行映射并归约，无需实现任务特质。
  */
 
 use std::sync::{Arc, Mutex, Condvar};
@@ -650,6 +653,60 @@ impl ForkJoinPool {
     }
 }
 
+// =================
+// 切片并行映射归约
+// =================
+
+/// 并行映射归约
+///
+/// 递归地把切片一分为二，左半部分在新的作用域线程中处理，右半部分在当前线程处理，
+/// 长度不超过 `threshold` 的片段顺序计算。同时运行的线程数不超过
+/// `available_parallelism`，名额用完后剩余片段在当前线程顺序计算，
+/// 因此很小的 `threshold` 也不会创建大量线程。
+/// `identity` 必须是 `reduce_fn` 的单位元，且 `reduce_fn` 需满足结合律，否则结果会依赖拆分方式。
+pub fn par_map_reduce<T, A, M, R>(data: &[T], map_fn: M, reduce_fn: R, identity: A, threshold: usize) -> A
+where
+    T: Sync,
+    A: Send + Sync + Clone,
+    M: Fn(&T) -> A + Sync,
+    R: Fn(A, A) -> A + Sync,
+{
+    let parallelism = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    // 当前线程本身占一个名额
+    split_map_reduce(data, &map_fn, &reduce_fn, &identity, threshold.max(1), parallelism - 1)
+}
+
+/// `spawn_budget` 为这一段还可以额外创建的线程数
+fn split_map_reduce<T, A, M, R>(
+    data: &[T],
+    map_fn: &M,
+    reduce_fn: &R,
+    identity: &A,
+    threshold: usize,
+    spawn_budget: usize,
+) -> A
+where
+    T: Sync,
+    A: Send + Sync + Clone,
+    M: Fn(&T) -> A + Sync,
+    R: Fn(A, A) -> A + Sync,
+{
+    if data.len() <= threshold || spawn_budget == 0 {
+        return data.iter().fold(identity.clone(), |acc, item| reduce_fn(acc, map_fn(item)));
+    }
+    
+    // 新线程用掉一个名额，剩余名额两半平分
+    let remaining = spawn_budget - 1;
+    let (left_budget, right_budget) = (remaining / 2, remaining - remaining / 2);
+    let (left, right) = data.split_at(data.len() / 2);
+    let (left_result, right_result) = thread::scope(|scope| {
+        let left_handle = scope.spawn(|| split_map_reduce(left, map_fn, reduce_fn, identity, threshold, left_budget));
+        let right_result = split_map_reduce(right, map_fn, reduce_fn, identity, threshold, right_budget);
+        (left_handle.join().expect("par_map_reduce 子任务恐慌"), right_result)
+    });
+    reduce_fn(left_result, right_result)
+}
+
 // =================
 // 演示函数
 // =================
@@ -816,6 +873,27 @@ pub fn demo_fork_join() {
         pool.shutdown();
     }
     
+    println!("\n{}\n", "=".repeat(50));
+    
+    // 5. 切片并行映射归约演示
+    println!("5. 切片并行映射归约演示:");
+    {
+        let data: Vec<u64> = (1..=1_000_000).collect();
+        
+        let start_time = Instant::now();
+        let sum_of_squares = par_map_reduce(&data, |x| x * x, |a, b| a + b, 0u64, 50_000);
+        println!("平方和: {}，耗时: {:?}", sum_of_squares, start_time.elapsed());
+        
+        let max_digit_sum = par_map_reduce(
+            &data,
+            |x| x.to_string().bytes().map(|b| (b - b'0') as u32).sum::<u32>(),
+            |a, b| a.max(b),
+            0,
+            50_000,
+        );
+        println!("最大数位和: {}", max_digit_sum);
+    }
+    
     println!("\n【Fork-Join模式特点】");
     println!("✓ 分而治之 - 递归地将大任务分解为小任务");
     println!("✓ 并行执行 - 子任务可以并行执行");
@@ -823,4 +901,50 @@ pub fn demo_fork_join() {
     println!("✓ 工作窃取 - 空闲线程可以窃取其他线程的任务");
     println!("✓ 动态负载均衡 - 自动平衡工作负载");
     println!("✓ 高效并行 - 充分利用多核处理器性能");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_sum_of_squares_matches_sequential() {
+        let data: Vec<u64> = (1..=200_000).collect();
+
+        let parallel = par_map_reduce(&data, |x| x * x, |a, b| a + b, 0u64, 1_000);
+        let sequential: u64 = data.iter().map(|x| x * x).sum();
+
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_par_max_matches_sequential() {
+        // 伪随机数据，最大值位于中间
+        let data: Vec<i64> = (0..100_000).map(|i| (i * 7919 + 13) % 100_003).collect();
+
+        let parallel = par_map_reduce(&data, |x| *x, |a, b| a.max(b), i64::MIN, 4_096);
+
+        assert_eq!(parallel, *data.iter().max().unwrap());
+        // 低于阈值时顺序计算，空切片返回单位元
+        assert_eq!(par_map_reduce(&data[..10], |x| *x, |a, b| a.max(b), i64::MIN, 4_096), *data[..10].iter().max().unwrap());
+        assert_eq!(par_map_reduce(&[] as &[i64], |x| *x, |a, b| a.max(b), i64::MIN, 0), i64::MIN);
+    }
+
+    #[test]
+    fn test_tiny_threshold_does_not_exceed_available_parallelism() {
+        use std::collections::HashSet;
+
+        let data: Vec<u64> = (1..=100_000).collect();
+        let threads = Mutex::new(HashSet::new());
+
+        // 阈值为1时若每次拆分都创建线程，会产生约十万个线程
+        let sum = par_map_reduce(&data, |x| {
+            threads.lock().unwrap().insert(thread::current().id());
+            *x
+        }, |a, b| a + b, 0u64, 1);
+
+        assert_eq!(sum, data.iter().sum::<u64>());
+        let parallelism = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+        assert!(threads.lock().unwrap().len() <= parallelism);
+    }
+}
@@ -15,6 +15,8 @@
  * 6. Reactor模式 - 事件驱动的异步I/O模式
  * 7. Future-Promise模式 - 异步计算模式
  * 8. Fork-Join模式 - 分而治之的并行模式
+ * 9. 优雅关闭 - 多个并发组件共享的关闭信号
+ * 10. 有界广播通道 - 每个订阅者独立缓冲，支持落后丢弃和背压
  */
 
 pub mod actor_pattern;
@@ -25,6 +27,8 @@ pub mod master_worker;
 pub mod reactor_pattern;
 pub mod future_promise;
 pub mod fork_join;
+pub mod shutdown;
+pub mod broadcast_channel;
 
 /// 演示所有并发模式
 pub fn demo_all_concurrent_patterns() {
@@ -69,5 +73,15 @@ pub fn demo_all_concurrent_patterns() {
     worker_pool::demo_worker_pool();
     println!("\n{}\n", "=".repeat(80));
     
+    // 优雅关闭演示
+    println!("【9. 优雅关闭】");
+    shutdown::demo_shutdown();
+    println!("\n{}\n", "=".repeat(80));
+    
+    // 有界广播通道演示
+    println!("【10. 有界广播通道】");
+    broadcast_channel::demo_broadcast_channel();
+    println!("\n{}\n", "=".repeat(80));
+    
     println!("\n=== 并发模式演示完成 ===");
 } 
\ No newline at end of file
@@ -12,6 +12,7 @@
  * 3. 背压控制 - 防止快速阶段压垮慢速阶段
  * 4. 错误隔离 - 错误处理局限在特定阶段
  * 5. 可扩展性 - 可以动态添加或移除阶段
+ * 6. 优雅关闭 - 共享的 ShutdownSignal 触发时向首个阶段投放毒丸，各阶段排空后退出
  */
 
 use std::sync::{Arc, Mutex};
@@ -22,6 +23,8 @@ use std::collections::VecDeque;
 use std::fmt;
 use std::marker::PhantomData;
 
+use super::shutdown::ShutdownSignal;
+
 // =================
 // 流水线阶段特质
 // =================
@@ -67,6 +70,32 @@ impl fmt::Display for ProcessError {
     }
 }
 
+/// 阶段错误，经由专用的错误通道上报
+///
+/// 单个数据项处理失败不会终止阶段线程，阶段记录错误后继续处理后续数据。
+#[derive(Debug)]
+pub struct StageError {
+    pub stage: String,
+    /// 出错的数据项，初始化、清理等与数据无关的错误为None
+    pub item_id: Option<u64>,
+    pub error: ProcessError,
+}
+
+impl StageError {
+    pub fn new(stage: &str, item_id: Option<u64>, error: ProcessError) -> Self {
+        Self { stage: stage.to_string(), item_id, error }
+    }
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.item_id {
+            Some(id) => write!(f, "[{}] 数据项 {}: {}", self.stage, id, self.error),
+            None => write!(f, "[{}] {}", self.stage, self.error),
+        }
+    }
+}
+
 // =================
 // 数据流和控制信号
 // =================
@@ -103,6 +132,7 @@ impl<T> DataItem<T> {
 /// 流水线控制信号
 #[derive(Debug)]
 pub enum ControlSignal {
+    /// 毒丸：阶段处理完它之前的所有数据后，把它转发给下一阶段再退出
     Stop,
     Pause,
     Resume,
@@ -127,11 +157,12 @@ pub struct PipelineStage<Input, Output> {
     processor: Option<Box<dyn StageProcessor<Input, Output>>>,
     input_receiver: Option<Receiver<StageMessage<Input>>>,
     output_sender: Option<Sender<StageMessage<Output>>>,
-    error_sender: Sender<ProcessError>,
+    error_sender: Sender<StageError>,
     buffer_size: usize,
     processed_count: Arc<Mutex<u64>>,
     error_count: Arc<Mutex<u64>>,
     handle: Option<JoinHandle<()>>,
+    shutdown_signal: Option<ShutdownSignal>,
 }
 
 impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output> {
@@ -140,7 +171,7 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
         processor: Box<dyn StageProcessor<Input, Output>>,
         input_receiver: Receiver<StageMessage<Input>>,
         output_sender: Option<Sender<StageMessage<Output>>>,
-        error_sender: Sender<ProcessError>,
+        error_sender: Sender<StageError>,
         buffer_size: usize,
     ) -> Self {
         Self {
@@ -153,9 +184,19 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
             processed_count: Arc::new(Mutex::new(0)),
             error_count: Arc::new(Mutex::new(0)),
             handle: None,
+            shutdown_signal: None,
         }
     }
     
+    /// 登记到关闭信号，`start()` 之后 `shutdown_all` 会等待阶段线程退出
+    ///
+    /// 未启动的阶段不登记，不会拖住 `shutdown_all`。
+    /// 阶段本身仍靠毒丸停止，需要配合 `stop_on_shutdown` 在信号触发时向首个阶段发送毒丸。
+    pub fn with_shutdown_signal(mut self, signal: &ShutdownSignal) -> Self {
+        self.shutdown_signal = Some(signal.clone());
+        self
+    }
+    
     /// 启动阶段处理
     pub fn start(&mut self) {
         let name = self.name.clone();
@@ -179,13 +220,15 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
         let error_sender = self.error_sender.clone();
         let processed_count = Arc::clone(&self.processed_count);
         let error_count = Arc::clone(&self.error_count);
+        let shutdown_guard = self.shutdown_signal.as_ref().map(ShutdownSignal::guard);
         
         let handle = thread::spawn(move || {
+            let _shutdown_guard = shutdown_guard;
             println!("流水线阶段 '{}' 启动", name);
             
             if let Err(e) = processor.initialize() {
                 println!("阶段 '{}' 初始化失败: {}", name, e);
-                let _ = error_sender.send(e);
+                let _ = error_sender.send(StageError::new(&name, None, e));
                 return;
             }
             
@@ -198,11 +241,17 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
                             continue;
                         }
                         
-                        match processor.process(data_item.data) {
+                        // 处理器恐慌同样作为错误上报，阶段线程继续运行
+                        let item_id = data_item.id;
+                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            processor.process(data_item.data)
+                        })).unwrap_or_else(|_| Err(ProcessError::ProcessingFailed("处理器恐慌".to_string())));
+                        
+                        match result {
                             Ok(output) => {
                                 let output_item = DataItem {
                                     data: output,
-                                    id: data_item.id,
+                                    id: item_id,
                                     timestamp: data_item.timestamp,
                                     metadata: data_item.metadata,
                                 };
@@ -219,7 +268,7 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
                             }
                             Err(e) => {
                                 println!("阶段 '{}' 处理错误: {}", name, e);
-                                let _ = error_sender.send(e);
+                                let _ = error_sender.send(StageError::new(&name, Some(item_id), e));
                                 
                                 let mut count = error_count.lock().unwrap();
                                 *count += 1;
@@ -229,7 +278,11 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
                     StageMessage::Control(signal) => {
                         match signal {
                             ControlSignal::Stop => {
+                                // 通道按顺序投递，收到毒丸时之前的数据都已处理完毕
                                 println!("阶段 '{}' 收到停止信号", name);
+                                if let Some(ref sender) = output_sender {
+                                    let _ = sender.send(StageMessage::Control(ControlSignal::Stop));
+                                }
                                 break;
                             }
                             ControlSignal::Pause => {
@@ -253,14 +306,14 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
                     }
                     StageMessage::Error(e) => {
                         println!("阶段 '{}' 收到错误信号: {}", name, e);
-                        let _ = error_sender.send(e);
+                        let _ = error_sender.send(StageError::new(&name, None, e));
                     }
                 }
             }
             
             if let Err(e) = processor.cleanup() {
                 println!("阶段 '{}' 清理失败: {}", name, e);
-                let _ = error_sender.send(e);
+                let _ = error_sender.send(StageError::new(&name, None, e));
             }
             
             println!("阶段 '{}' 停止", name);
@@ -275,6 +328,21 @@ impl<Input: Send + 'static, Output: Send + 'static> PipelineStage<Input, Output>
         let errors = *self.error_count.lock().unwrap();
         (processed, errors)
     }
+    
+    /// 等待阶段线程退出，线程正常结束时返回true
+    pub fn join(&mut self) -> bool {
+        match self.handle.take() {
+            Some(handle) => handle.join().is_ok(),
+            None => true,
+        }
+    }
+}
+
+/// 关闭信号触发时向流水线入口发送毒丸，各阶段处理完已有数据后逐级停止
+pub fn stop_on_shutdown<T: Send + 'static>(signal: &ShutdownSignal, input: Sender<StageMessage<T>>) {
+    signal.on_trigger(move || {
+        let _ = input.send(StageMessage::Control(ControlSignal::Stop));
+    });
 }
 
 /// 虚拟处理器（用于占位）
@@ -506,7 +574,7 @@ impl StageProcessor<usize, String> for FormatProcessor {
 pub struct SimpleTextPipeline {
     input_sender: Sender<StageMessage<String>>,
     output_receiver: Receiver<String>,
-    error_receiver: Receiver<ProcessError>,
+    error_receiver: Receiver<StageError>,
     stages: Vec<Box<dyn std::any::Any + Send>>,
 }
 
@@ -552,7 +620,7 @@ impl SimpleTextPipeline {
         let stage3_handle = thread::spawn(move || {
             let mut processor = length_processor;
             if let Err(e) = processor.initialize() {
-                let _ = stage3_error_sender.send(e);
+                let _ = stage3_error_sender.send(StageError::new("长度计算阶段", None, e));
                 return;
             }
             
@@ -567,7 +635,7 @@ impl SimpleTextPipeline {
                                 }
                             }
                             Err(e) => {
-                                let _ = stage3_error_sender.send(e);
+                                let _ = stage3_error_sender.send(StageError::new("长度计算阶段", Some(data_item.id), e));
                             }
                         }
                     }
@@ -606,7 +674,7 @@ impl SimpleTextPipeline {
     }
     
     /// 检查错误
-    pub fn check_errors(&self) -> Vec<ProcessError> {
+    pub fn check_errors(&self) -> Vec<StageError> {
         let mut errors = Vec::new();
         while let Ok(error) = self.error_receiver.try_recv() {
             errors.push(error);
@@ -784,6 +852,57 @@ pub fn demo_pipeline() {
         let _ = input_sender.send(StageMessage::Control(ControlSignal::Stop));
     }
     
+    println!("\n{}\n", "=".repeat(50));
+    
+    // 4. 错误通道与毒丸关闭
+    println!("4. 错误通道与毒丸关闭:");
+    {
+        let (input_sender, stage1_receiver) = mpsc::channel();
+        let (stage1_sender, stage2_receiver) = mpsc::channel();
+        let (stage2_sender, output_receiver) = mpsc::channel();
+        let (error_sender, error_receiver) = mpsc::channel();
+        
+        let mut validation_stage = PipelineStage::new(
+            "验证".to_string(),
+            Box::new(ValidationProcessor::new(3)),
+            stage1_receiver,
+            Some(stage1_sender),
+            error_sender.clone(),
+            10,
+        );
+        let mut length_stage = PipelineStage::new(
+            "长度".to_string(),
+            Box::new(LengthProcessor::new(Duration::from_millis(1))),
+            stage2_receiver,
+            Some(stage2_sender),
+            error_sender,
+            10,
+        );
+        validation_stage.start();
+        length_stage.start();
+        
+        for (id, text) in ["pipeline", "no", "rust"].iter().enumerate() {
+            let _ = input_sender.send(StageMessage::Data(DataItem::new(text.to_string(), id as u64 + 1)));
+        }
+        let _ = input_sender.send(StageMessage::Control(ControlSignal::Stop));
+        
+        // 毒丸在所有数据之后到达末端
+        while let Ok(message) = output_receiver.recv() {
+            match message {
+                StageMessage::Data(item) => println!("  数据项 {} 长度: {}", item.id, item.data),
+                StageMessage::Control(ControlSignal::Stop) => {
+                    println!("  收到毒丸，流水线已排空");
+                    break;
+                }
+                _ => {}
+            }
+        }
+        println!("  所有阶段已退出: {}", validation_stage.join() && length_stage.join());
+        for error in error_receiver.try_iter() {
+            println!("  错误通道: {}", error);
+        }
+    }
+    
     println!("\n【Pipeline模式特点】");
     println!("✓ 分阶段处理 - 将复杂任务分解为简单阶段");
     println!("✓ 并行执行 - 多个阶段同时处理不同数据");
@@ -791,4 +910,94 @@ pub fn demo_pipeline() {
     println!("✓ 错误隔离 - 错误处理局限在特定阶段");
     println!("✓ 可扩展性 - 可以动态添加或移除阶段");
     println!("✓ 高吞吐量 - 提高系统整体处理能力");
-} 
\ No newline at end of file
+    println!("✓ 优雅关闭 - 毒丸逐级传递，各阶段排空后退出");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 把字符串解析为整数，无法解析时报错
+    struct ParseProcessor;
+
+    impl StageProcessor<String, i32> for ParseProcessor {
+        fn process(&mut self, input: String) -> Result<i32, ProcessError> {
+            input.parse().map_err(|_| ProcessError::InvalidInput(input))
+        }
+
+        fn name(&self) -> &str {
+            "解析"
+        }
+    }
+
+    struct TwoStagePipeline {
+        input: Sender<StageMessage<String>>,
+        output: Receiver<StageMessage<i64>>,
+        errors: Receiver<StageError>,
+        parse: PipelineStage<String, i32>,
+        square: PipelineStage<i32, i64>,
+    }
+
+    fn two_stage_pipeline() -> TwoStagePipeline {
+        let (input, parse_receiver) = mpsc::channel();
+        let (parse_sender, square_receiver) = mpsc::channel();
+        let (output_sender, output) = mpsc::channel();
+        let (error_sender, errors) = mpsc::channel();
+
+        let mut parse = PipelineStage::new("解析".to_string(), Box::new(ParseProcessor), parse_receiver, Some(parse_sender), error_sender.clone(), 10);
+        let mut square = PipelineStage::new("平方".to_string(), Box::new(SquareProcessor::new(Duration::ZERO)), square_receiver, Some(output_sender), error_sender, 10);
+        parse.start();
+        square.start();
+
+        TwoStagePipeline { input, output, errors, parse, square }
+    }
+
+    #[test]
+    fn test_stage_error_is_reported_and_processing_continues() {
+        let TwoStagePipeline { input, output, errors, .. } = two_stage_pipeline();
+
+        for (id, text) in ["1", "x", "3"].iter().enumerate() {
+            input.send(StageMessage::Data(DataItem::new(text.to_string(), id as u64 + 1))).unwrap();
+        }
+
+        let results: Vec<(u64, i64)> = (0..2)
+            .map(|_| match output.recv_timeout(Duration::from_secs(5)).unwrap() {
+                StageMessage::Data(item) => (item.id, item.data),
+                other => panic!("意外的消息: {:?}", other),
+            })
+            .collect();
+        assert_eq!(results, vec![(1, 1), (3, 9)]);
+
+        let error = errors.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(error.stage, "解析");
+        assert_eq!(error.item_id, Some(2));
+        assert!(matches!(error.error, ProcessError::InvalidInput(ref input) if input == "x"));
+    }
+
+    #[test]
+    fn test_poison_pill_drains_and_stops_every_stage() {
+        let TwoStagePipeline { input, output, errors, mut parse, mut square } = two_stage_pipeline();
+
+        for (id, text) in ["2", "4"].iter().enumerate() {
+            input.send(StageMessage::Data(DataItem::new(text.to_string(), id as u64 + 1))).unwrap();
+        }
+        input.send(StageMessage::Control(ControlSignal::Stop)).unwrap();
+
+        let mut drained = Vec::new();
+        loop {
+            match output.recv_timeout(Duration::from_secs(5)).unwrap() {
+                StageMessage::Data(item) => drained.push(item.data),
+                StageMessage::Control(ControlSignal::Stop) => break,
+                other => panic!("意外的消息: {:?}", other),
+            }
+        }
+
+        // 毒丸之前的数据全部处理完毕，之后各阶段线程正常退出
+        assert_eq!(drained, vec![4, 16]);
+        assert!(parse.join());
+        assert!(square.join());
+        assert!(errors.try_recv().is_err());
+        // 阶段已退出，输入通道不再被消费
+        assert!(input.send(StageMessage::Data(DataItem::new("5".to_string(), 3))).is_err());
+    }
+}
@@ -12,15 +12,22 @@
  * 3. 同步 - 协调多个生产者和消费者
  * 4. 背压控制 - 防止缓冲区溢出
  * 5. 流量控制 - 平衡系统负载
+ * 6. 优雅关闭 - 观察共享的 ShutdownSignal，生产者停止生产，消费者排空缓冲区后退出
  */
 
 use std::sync::{Arc, Condvar, Mutex};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+use std::cmp::Ordering;
 use std::fmt;
 
+use super::shutdown::ShutdownSignal;
+
+/// 消费者等待数据的最长时间，超时后重新检查关闭信号
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 // =================
 // 有界缓冲区实现
 // =================
@@ -83,6 +90,23 @@ impl<T> BoundedBuffer<T> {
         Ok(item)
     }
     
+    /// 消费者取出数据，最多等待 `timeout`，超时返回 `Timeout`
+    pub fn take_timeout(&self, timeout: Duration) -> Result<T, ProducerConsumerError> {
+        let buffer = self.buffer.lock().unwrap();
+        let (mut buffer, _) = self.not_empty
+            .wait_timeout_while(buffer, timeout, |buffer| buffer.is_empty())
+            .unwrap();
+        
+        let was_full = buffer.len() >= self.capacity;
+        let item = buffer.pop_front().ok_or(ProducerConsumerError::Timeout)?;
+        
+        if was_full {
+            self.not_full.notify_one();
+        }
+        
+        Ok(item)
+    }
+    
     /// 获取当前缓冲区大小
     pub fn size(&self) -> usize {
         self.buffer.lock().unwrap().len()
@@ -105,6 +129,116 @@ impl<T> BoundedBuffer<T> {
     }
 }
 
+// =================
+// 有界优先级缓冲区实现
+// =================
+
+/// 优先级缓冲区中的条目，序号用于保证同优先级按插入顺序出队
+struct PriorityEntry<T> {
+    priority: u8,
+    sequence: u64,
+    item: T,
+}
+
+impl<T> PartialEq for PriorityEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.sequence == other.sequence
+    }
+}
+
+impl<T> Eq for PriorityEntry<T> {}
+
+impl<T> PartialOrd for PriorityEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for PriorityEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // 优先级高的先出队，同优先级序号小的先出队
+        self.priority.cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct PriorityState<T> {
+    heap: BinaryHeap<PriorityEntry<T>>,
+    next_sequence: u64,
+    consumed: BTreeMap<u8, usize>,
+}
+
+/// 有界优先级缓冲区，消费者总是取到当前优先级最高的数据
+///
+/// 容量限制与BoundedBuffer相同：缓冲区满时生产者阻塞，提供同样的背压。
+pub struct PriorityBoundedBuffer<T> {
+    state: Mutex<PriorityState<T>>,
+    not_full: Condvar,
+    not_empty: Condvar,
+    capacity: usize,
+}
+
+impl<T> PriorityBoundedBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(PriorityState {
+                heap: BinaryHeap::with_capacity(capacity),
+                next_sequence: 0,
+                consumed: BTreeMap::new(),
+            }),
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+            capacity,
+        }
+    }
+    
+    /// 生产者按优先级放入数据（阻塞直到有空间，数值越大优先级越高）
+    pub fn put(&self, item: T, priority: u8) -> Result<(), ProducerConsumerError> {
+        let mut state = self.state.lock().unwrap();
+        
+        while state.heap.len() >= self.capacity {
+            state = self.not_full.wait(state).unwrap();
+        }
+        
+        let sequence = state.next_sequence;
+        state.next_sequence += 1;
+        state.heap.push(PriorityEntry { priority, sequence, item });
+        self.not_empty.notify_one();
+        
+        Ok(())
+    }
+    
+    /// 消费者取出优先级最高的数据（阻塞直到有数据）
+    pub fn take(&self) -> Result<T, ProducerConsumerError> {
+        let mut state = self.state.lock().unwrap();
+        
+        while state.heap.is_empty() {
+            state = self.not_empty.wait(state).unwrap();
+        }
+        
+        let entry = state.heap.pop().unwrap();
+        *state.consumed.entry(entry.priority).or_insert(0) += 1;
+        self.not_full.notify_one();
+        
+        Ok(entry.item)
+    }
+    
+    /// 各优先级已被消费的数量
+    pub fn consumed_by_priority(&self) -> BTreeMap<u8, usize> {
+        self.state.lock().unwrap().consumed.clone()
+    }
+    
+    /// 获取当前缓冲区大小
+    pub fn size(&self) -> usize {
+        self.state.lock().unwrap().heap.len()
+    }
+    
+    /// 获取缓冲区容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
 // =================
 // 错误处理
 // =================
@@ -344,16 +478,28 @@ pub struct ProducerConsumerSystem<T> {
     buffer: Arc<BoundedBuffer<T>>,
     producer_handles: Vec<JoinHandle<()>>,
     consumer_handles: Vec<JoinHandle<()>>,
-    shutdown_signal: Arc<Mutex<bool>>,
+    /// 外部共享的关闭信号，可能同时被其他组件观察
+    shutdown_signal: ShutdownSignal,
+    /// 本系统自己的关闭信号，外部信号触发时随之触发；`shutdown()` 只触发它
+    local_shutdown: ShutdownSignal,
 }
 
 impl<T: Send + 'static> ProducerConsumerSystem<T> {
     pub fn new(buffer_capacity: usize) -> Self {
+        Self::with_shutdown_signal(buffer_capacity, ShutdownSignal::new())
+    }
+    
+    /// 创建观察共享关闭信号的系统
+    pub fn with_shutdown_signal(buffer_capacity: usize, shutdown_signal: ShutdownSignal) -> Self {
+        let local_shutdown = ShutdownSignal::new();
+        let observer = local_shutdown.clone();
+        shutdown_signal.on_trigger(move || observer.trigger());
         Self {
             buffer: Arc::new(BoundedBuffer::new(buffer_capacity)),
             producer_handles: Vec::new(),
             consumer_handles: Vec::new(),
-            shutdown_signal: Arc::new(Mutex::new(false)),
+            shutdown_signal,
+            local_shutdown,
         }
     }
     
@@ -363,16 +509,18 @@ impl<T: Send + 'static> ProducerConsumerSystem<T> {
         P: Producer<T> + 'static,
     {
         let buffer = Arc::clone(&self.buffer);
-        let shutdown = Arc::clone(&self.shutdown_signal);
+        let shutdown = self.local_shutdown.clone();
+        let shutdown_guards = (self.shutdown_signal.guard(), self.local_shutdown.guard());
         let producer_name = producer.name().to_string();
         
         let handle = thread::spawn(move || {
+            let _shutdown_guards = shutdown_guards;
             println!("生产者 {} 启动", producer_name);
             let mut produced_count = 0;
             
             loop {
                 // 检查关闭信号
-                if *shutdown.lock().unwrap() {
+                if shutdown.is_triggered() {
                     break;
                 }
                 
@@ -405,20 +553,22 @@ impl<T: Send + 'static> ProducerConsumerSystem<T> {
         C: Consumer<T> + 'static,
     {
         let buffer = Arc::clone(&self.buffer);
-        let shutdown = Arc::clone(&self.shutdown_signal);
+        let shutdown = self.local_shutdown.clone();
+        let shutdown_guards = (self.shutdown_signal.guard(), self.local_shutdown.guard());
         let consumer_name = consumer.name().to_string();
         
         let handle = thread::spawn(move || {
+            let _shutdown_guards = shutdown_guards;
             println!("消费者 {} 启动", consumer_name);
             let mut consumed_count = 0;
             
             loop {
                 // 检查关闭信号和缓冲区状态
-                if *shutdown.lock().unwrap() && buffer.is_empty() {
+                if shutdown.is_triggered() && buffer.is_empty() {
                     break;
                 }
                 
-                match buffer.take() {
+                match buffer.take_timeout(SHUTDOWN_POLL_INTERVAL) {
                     Ok(item) => {
                         match consumer.consume(item) {
                             Ok(_) => {
@@ -429,9 +579,8 @@ impl<T: Send + 'static> ProducerConsumerSystem<T> {
                             }
                         }
                     }
-                    Err(ProducerConsumerError::BufferEmpty) => {
-                        // 如果缓冲区为空，稍等一下
-                        thread::sleep(Duration::from_millis(10));
+                    Err(ProducerConsumerError::Timeout) => {
+                        // 等待超时，回到循环开头检查关闭信号
                     }
                     Err(e) => {
                         println!("消费者 {} 错误: {}", consumer_name, e);
@@ -458,8 +607,8 @@ impl<T: Send + 'static> ProducerConsumerSystem<T> {
         // 等待生产者完成
         self.wait_producers();
         
-        // 发送关闭信号
-        *self.shutdown_signal.lock().unwrap() = true;
+        // 只触发本系统的信号，共享信号的其他观察者不受影响
+        self.local_shutdown.trigger();
         
         // 等待消费者完成
         for handle in self.consumer_handles {
@@ -576,8 +725,76 @@ pub fn demo_producer_consumer() {
     producer.join().unwrap();
     consumer.join().unwrap();
     
+    // 优先级缓冲区
+    println!("\n优先级缓冲区:");
+    let priority_buffer = Arc::new(PriorityBoundedBuffer::new(4));
+    let producer_buffer = Arc::clone(&priority_buffer);
+    let producer = thread::spawn(move || {
+        for i in 1..=8u8 {
+            let priority = i % 3;
+            producer_buffer.put(format!("消息{}", i), priority).unwrap();
+            println!("生产: 消息{} (优先级 {})", i, priority);
+        }
+    });
+    // 缓冲区装满后生产者阻塞，消费者每取走一个才放入下一个
+    thread::sleep(Duration::from_millis(50));
+    let consumed: Vec<String> = (0..8).map(|_| priority_buffer.take().unwrap()).collect();
+    producer.join().unwrap();
+    println!("消费顺序: {:?}", consumed);
+    println!("各优先级消费数量: {:?}", priority_buffer.consumed_by_priority());
+    
     println!("\n【Producer-Consumer模式特点】");
     println!("✓ 解耦 - 生产者和消费者独立工作");
     println!("✓ 缓冲 - 平衡生产和消费速度差异");
     println!("✓ 并发 - 支持多生产者多消费者");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_then_insertion_order() {
+        let buffer = PriorityBoundedBuffer::new(10);
+        for (item, priority) in [("a", 1), ("b", 3), ("c", 2), ("d", 3)] {
+            buffer.put(item, priority).unwrap();
+        }
+        assert_eq!(buffer.take().unwrap(), "b");
+
+        // 消费过程中继续混合生产
+        for (item, priority) in [("e", 3), ("f", 1), ("g", 2)] {
+            buffer.put(item, priority).unwrap();
+        }
+        let order: Vec<&str> = (0..6).map(|_| buffer.take().unwrap()).collect();
+
+        assert_eq!(order, vec!["d", "e", "c", "g", "a", "f"]);
+        let counts = buffer.consumed_by_priority();
+        assert_eq!(counts.get(&3), Some(&3));
+        assert_eq!(counts.get(&2), Some(&2));
+        assert_eq!(counts.get(&1), Some(&2));
+    }
+
+    #[test]
+    fn test_priority_buffer_applies_backpressure() {
+        let buffer = Arc::new(PriorityBoundedBuffer::new(2));
+        buffer.put(1, 0).unwrap();
+        buffer.put(2, 0).unwrap();
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let producer_buffer = Arc::clone(&buffer);
+        let producer = thread::spawn(move || {
+            producer_buffer.put(3, 9).unwrap();
+            done_tx.send(()).unwrap();
+        });
+
+        // 缓冲区已满，生产者应被阻塞
+        assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+        assert_eq!(buffer.size(), 2);
+
+        assert_eq!(buffer.take().unwrap(), 1);
+        done_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        producer.join().unwrap();
+        assert_eq!(buffer.take().unwrap(), 3);
+        assert_eq!(buffer.take().unwrap(), 2);
+    }
+}
@@ -12,6 +12,11 @@
  * 3. 非阻塞 - 所有I/O操作都是非阻塞的
  * 4. 多路复用 - 同时监听多个事件源
  * 5. 事件分发 - 将事件分发给对应的处理器
+ * 
+ * 协作式任务调度：
+ * 异步运行时本质上就是Reactor加上任务调度器。任务是可恢复的闭包，
+ * 每次被轮询时推进一步并返回Poll::Ready或Poll::Pending；等待某个事件源的
+ * 任务在对应事件就绪后才会被重新唤醒，其余时间不占用事件循环。
  */
 
 use std::sync::{Arc, Mutex};
@@ -20,6 +25,7 @@ use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::task::Poll;
 
 // =================
 // 事件和处理器定义
@@ -658,6 +664,220 @@ impl Reactor {
     }
 }
 
+// =================
+// 协作式任务调度
+// =================
+
+/// 任务标识
+pub type TaskId = u64;
+
+/// 任务轮询上下文 - 任务通过它声明等待的事件源或主动让出
+pub struct TaskContext {
+    task_id: TaskId,
+    event: Option<Event>,
+    waiting_on: Option<String>,
+}
+
+impl TaskContext {
+    pub fn task_id(&self) -> TaskId {
+        self.task_id
+    }
+    
+    /// 取出唤醒本任务的事件
+    pub fn take_event(&mut self) -> Option<Event> {
+        self.event.take()
+    }
+    
+    /// 等待指定事件源的下一个事件，事件就绪前任务不会被再次轮询
+    pub fn wait_for(&mut self, source_id: &str) -> Poll<()> {
+        self.waiting_on = Some(source_id.to_string());
+        Poll::Pending
+    }
+    
+    /// 主动让出执行权，下一轮调度时继续
+    pub fn yield_now(&mut self) -> Poll<()> {
+        Poll::Pending
+    }
+}
+
+/// 可恢复的任务闭包，状态保存在闭包捕获的变量中
+pub type TaskFn = Box<dyn FnMut(&mut TaskContext) -> Poll<()>>;
+
+struct CooperativeTask {
+    name: String,
+    poll_fn: TaskFn,
+    pending_event: Option<Event>,
+}
+
+/// 协作式任务调度器
+///
+/// 调度器不会抢占任务：任务在每次轮询中只推进一步然后返回，
+/// 调度器据此把任务放回就绪队列或挂到事件源的等待队列上。
+pub struct CooperativeScheduler {
+    tasks: HashMap<TaskId, CooperativeTask>,
+    ready: VecDeque<TaskId>,
+    waiting: HashMap<String, VecDeque<TaskId>>,
+    // 已有任务关注的事件源；这些事件源的事件在无人等待时先缓存起来
+    interests: HashMap<String, VecDeque<Event>>,
+    completed: Vec<String>,
+    next_id: TaskId,
+}
+
+impl CooperativeScheduler {
+    pub fn new() -> Self {
+        Self {
+            tasks: HashMap::new(),
+            ready: VecDeque::new(),
+            waiting: HashMap::new(),
+            interests: HashMap::new(),
+            completed: Vec::new(),
+            next_id: 0,
+        }
+    }
+    
+    /// 创建任务，新任务立即进入就绪队列
+    pub fn spawn<F>(&mut self, name: &str, poll_fn: F) -> TaskId
+    where
+        F: FnMut(&mut TaskContext) -> Poll<()> + 'static,
+    {
+        self.next_id += 1;
+        let task_id = self.next_id;
+        self.tasks.insert(task_id, CooperativeTask {
+            name: name.to_string(),
+            poll_fn: Box::new(poll_fn),
+            pending_event: None,
+        });
+        self.ready.push_back(task_id);
+        task_id
+    }
+    
+    /// 事件就绪时唤醒等待该事件源的任务，返回事件是否被调度器接收
+    pub fn wake(&mut self, event: Event) -> bool {
+        let Some(buffered) = self.interests.get_mut(&event.source_id) else {
+            return false;
+        };
+        
+        match self.waiting.get_mut(&event.source_id).and_then(|queue| queue.pop_front()) {
+            Some(task_id) => {
+                if let Some(task) = self.tasks.get_mut(&task_id) {
+                    task.pending_event = Some(event);
+                }
+                self.ready.push_back(task_id);
+            }
+            None => buffered.push_back(event),
+        }
+        true
+    }
+    
+    /// 轮询本轮开始时已就绪的所有任务各一次，返回轮询的任务数
+    pub fn run_ready(&mut self) -> usize {
+        let batch = self.ready.len();
+        for _ in 0..batch {
+            let Some(task_id) = self.ready.pop_front() else { break };
+            let Some(mut task) = self.tasks.remove(&task_id) else { continue };
+            
+            let mut context = TaskContext {
+                task_id,
+                event: task.pending_event.take(),
+                waiting_on: None,
+            };
+            
+            match (task.poll_fn)(&mut context) {
+                Poll::Ready(()) => {
+                    self.completed.push(task.name);
+                }
+                Poll::Pending => {
+                    if let Some(source_id) = context.waiting_on {
+                        let buffered = self.interests.entry(source_id.clone()).or_default();
+                        match buffered.pop_front() {
+                            Some(event) => {
+                                task.pending_event = Some(event);
+                                self.ready.push_back(task_id);
+                            }
+                            None => self.waiting.entry(source_id).or_default().push_back(task_id),
+                        }
+                    } else {
+                        self.ready.push_back(task_id);
+                    }
+                    self.tasks.insert(task_id, task);
+                }
+            }
+        }
+        batch
+    }
+    
+    /// 是否还有就绪任务
+    pub fn has_ready(&self) -> bool {
+        !self.ready.is_empty()
+    }
+    
+    /// 未完成的任务数
+    pub fn pending_count(&self) -> usize {
+        self.tasks.len()
+    }
+    
+    /// 按完成顺序排列的任务名称
+    pub fn completed_tasks(&self) -> &[String] {
+        &self.completed
+    }
+}
+
+impl Default for CooperativeScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reactor {
+    /// 以事件循环驱动协作式任务
+    ///
+    /// 每轮先轮询就绪任务，再收集事件：任务关注的事件源的事件用于唤醒任务，
+    /// 其余事件照常分发给处理器。所有任务完成后返回；若任务仍在等待
+    /// 但已没有任何事件来源，则返回错误而不是无限空转。
+    pub fn run_tasks(&mut self, scheduler: &mut CooperativeScheduler) -> Result<(), EventError> {
+        self.running = true;
+        
+        while self.running && scheduler.pending_count() > 0 {
+            let polled = scheduler.run_ready();
+            
+            self.collect_events_from_sources();
+            let events: Vec<Event> = self.event_queue.drain(..).collect();
+            for event in events {
+                if let Err(event) = Self::offer_to_scheduler(scheduler, event) {
+                    self.event_queue.push_back(*event);
+                }
+            }
+            let processed = self.process_events();
+            
+            self.stats.loops_executed += 1;
+            self.event_sources.retain(|source| source.has_more_events());
+            
+            if !scheduler.has_ready() && scheduler.pending_count() > 0 {
+                if self.event_sources.is_empty() && self.event_queue.is_empty() {
+                    return Err(EventError::ProcessingFailed(format!(
+                        "{} 个任务仍在等待事件，但已没有事件源", scheduler.pending_count()
+                    )));
+                }
+                if polled == 0 && processed == 0 {
+                    thread::sleep(self.config.loop_timeout);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 把事件交给等待它的任务，没有任务关注时原样退回
+    fn offer_to_scheduler(scheduler: &mut CooperativeScheduler, event: Event) -> Result<(), Box<Event>> {
+        if scheduler.interests.contains_key(&event.source_id) {
+            scheduler.wake(event);
+            Ok(())
+        } else {
+            Err(Box::new(event))
+        }
+    }
+}
+
 // =================
 // 演示函数
 // =================
@@ -810,6 +1030,44 @@ pub fn demo_reactor() {
                  stats.total_processing_time / stats.loops_executed as u32);
     }
     
+    println!("\n{}\n", "=".repeat(50));
+    
+    // 4. 协作式任务调度演示
+    println!("4. 协作式任务调度演示:");
+    {
+        let mut reactor = Reactor::with_default_config();
+        reactor.add_event_source(Box::new(TimerEventSource::new(
+            "心跳".to_string(),
+            Duration::from_millis(10),
+            3
+        )));
+        
+        let mut scheduler = CooperativeScheduler::new();
+        
+        // 任务A：等待三次心跳事件
+        let mut beats = 0;
+        scheduler.spawn("心跳监听", move |cx| {
+            if cx.take_event().is_some() {
+                beats += 1;
+                println!("  [任务{}] 收到第 {} 次心跳", cx.task_id(), beats);
+            }
+            if beats == 3 { Poll::Ready(()) } else { cx.wait_for("心跳") }
+        });
+        
+        // 任务B：分步计算，每步之后主动让出
+        let mut step = 0;
+        scheduler.spawn("分步计算", move |cx| {
+            step += 1;
+            println!("  [任务{}] 计算第 {} 步", cx.task_id(), step);
+            if step == 3 { Poll::Ready(()) } else { cx.yield_now() }
+        });
+        
+        match reactor.run_tasks(&mut scheduler) {
+            Ok(()) => println!("任务完成顺序: {:?}", scheduler.completed_tasks()),
+            Err(e) => println!("调度失败: {}", e),
+        }
+    }
+    
     println!("\n【Reactor模式特点】");
     println!("✓ 事件驱动 - 基于事件的异步处理");
     println!("✓ 单线程 - 使用单个事件循环线程");
@@ -817,4 +1075,70 @@ pub fn demo_reactor() {
     println!("✓ 多路复用 - 同时监听多个事件源");
     println!("✓ 事件分发 - 将事件分发给对应的处理器");
     println!("✓ 高效率 - 避免线程切换开销");
-} 
\ No newline at end of file
+    println!("✓ 协作调度 - 任务在事件就绪时被唤醒，无需真正的async");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_yielding_tasks_interleave_until_completion() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut scheduler = CooperativeScheduler::new();
+
+        for (name, steps) in [("A", 3), ("B", 2)] {
+            let log = Rc::clone(&log);
+            let mut progress = 0;
+            scheduler.spawn(name, move |cx| {
+                progress += 1;
+                log.borrow_mut().push(format!("{}{}", name, progress));
+                if progress == steps { Poll::Ready(()) } else { cx.yield_now() }
+            });
+        }
+
+        let mut reactor = Reactor::with_default_config();
+        reactor.run_tasks(&mut scheduler).unwrap();
+
+        assert_eq!(*log.borrow(), vec!["A1", "B1", "A2", "B2", "A3"]);
+        assert_eq!(scheduler.completed_tasks(), ["B", "A"]);
+        assert_eq!(scheduler.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_waiting_task_resumes_on_event_readiness() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let mut scheduler = CooperativeScheduler::new();
+
+        let sink = Rc::clone(&received);
+        scheduler.spawn("reader", move |cx| {
+            if let Some(event) = cx.take_event() {
+                sink.borrow_mut().push(String::from_utf8_lossy(&event.data).to_string());
+            }
+            if sink.borrow().len() == 2 { Poll::Ready(()) } else { cx.wait_for("socket") }
+        });
+
+        let mut reactor = Reactor::with_default_config();
+        // 两个事件在同一轮到达，第二个先缓存，任务再次等待时立即拿到
+        for payload in ["hello", "world"] {
+            reactor.submit_event(Event::new(0, EventType::Read, "socket".to_string()).with_data(payload.as_bytes().to_vec()));
+        }
+        reactor.run_tasks(&mut scheduler).unwrap();
+
+        assert_eq!(*received.borrow(), vec!["hello", "world"]);
+        assert_eq!(scheduler.completed_tasks(), ["reader"]);
+    }
+
+    #[test]
+    fn test_waiting_without_event_sources_reports_error() {
+        let mut scheduler = CooperativeScheduler::new();
+        scheduler.spawn("stuck", |cx| cx.wait_for("never"));
+
+        let mut reactor = Reactor::with_default_config();
+
+        assert!(matches!(reactor.run_tasks(&mut scheduler), Err(EventError::ProcessingFailed(_))));
+        assert_eq!(scheduler.pending_count(), 1);
+    }
+}
@@ -0,0 +1,346 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/ConcurrentMode/shutdown.rs
+ *
+ * 优雅关闭协调 (Graceful Shutdown)
+ *
+ * 线程池、Actor系统、流水线和生产者消费者系统各自管理后台线程，
+ * 应用退出时需要让它们处理完手头的工作再停止。ShutdownSignal 是这些模块共享的关闭信号：
+ *
+ * 1. 广播 - 一次 trigger() 通知所有持有信号克隆的组件
+ * 2. 唤醒 - 组件通过 on_trigger() 注册钩子，唤醒阻塞在条件变量或通道上的线程
+ * 3. 跟踪 - 每个后台线程持有一个 ShutdownGuard，线程结束时自动注销
+ * 4. 限时等待 - shutdown_all(timeout) 触发信号并等待所有线程结束，返回是否按时停止
+ */
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct SignalInner {
+    triggered: bool,
+    active: usize,
+    hooks: Vec<ShutdownHook>,
+}
+
+#[derive(Default)]
+struct SignalState {
+    inner: Mutex<SignalInner>,
+    changed: Condvar,
+}
+
+/// 可克隆的关闭信号，所有克隆共享同一状态
+#[derive(Clone, Default)]
+pub struct ShutdownSignal {
+    state: Arc<SignalState>,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 触发关闭并执行已注册的钩子，重复触发没有效果
+    pub fn trigger(&self) {
+        let hooks = {
+            let mut inner = self.state.inner.lock().unwrap();
+            if inner.triggered {
+                return;
+            }
+            inner.triggered = true;
+            std::mem::take(&mut inner.hooks)
+        };
+        self.state.changed.notify_all();
+        // 钩子在锁外执行，可以安全地获取组件自己的锁
+        for hook in hooks {
+            hook();
+        }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.state.inner.lock().unwrap().triggered
+    }
+
+    /// 阻塞直到信号被触发
+    pub fn wait(&self) {
+        let inner = self.state.inner.lock().unwrap();
+        let _inner = self.state.changed.wait_while(inner, |inner| !inner.triggered).unwrap();
+    }
+
+    /// 最多等待 `timeout`，返回信号是否已触发
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let inner = self.state.inner.lock().unwrap();
+        let (inner, _) = self.state.changed.wait_timeout_while(inner, timeout, |inner| !inner.triggered).unwrap();
+        inner.triggered
+    }
+
+    /// 注册触发时执行的钩子，用于唤醒阻塞中的线程；信号已触发时立即执行
+    pub fn on_trigger<F>(&self, hook: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut inner = self.state.inner.lock().unwrap();
+        if inner.triggered {
+            drop(inner);
+            hook();
+        } else {
+            inner.hooks.push(Box::new(hook));
+        }
+    }
+
+    /// 登记一个需要等待结束的后台线程，守卫被丢弃时注销
+    pub fn guard(&self) -> ShutdownGuard {
+        self.state.inner.lock().unwrap().active += 1;
+        ShutdownGuard { state: Arc::clone(&self.state) }
+    }
+
+    /// 仍在运行的已登记线程数
+    pub fn active_count(&self) -> usize {
+        self.state.inner.lock().unwrap().active
+    }
+
+    /// 触发关闭并等待所有已登记的线程结束，按时全部停止返回true
+    pub fn shutdown_all(&self, timeout: Duration) -> bool {
+        self.trigger();
+        let deadline = Instant::now() + timeout;
+        let mut inner = self.state.inner.lock().unwrap();
+        while inner.active > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            inner = self.state.changed.wait_timeout(inner, deadline - now).unwrap().0;
+        }
+        true
+    }
+}
+
+/// 后台线程持有的守卫，线程结束（包括恐慌）时自动注销
+pub struct ShutdownGuard {
+    state: Arc<SignalState>,
+}
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        // 恐慌线程持有的守卫也要注销，锁中毒时照常取出数据
+        let mut inner = self.state.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        inner.active -= 1;
+        drop(inner);
+        self.state.changed.notify_all();
+    }
+}
+
+/// 优雅关闭演示
+pub fn demo_shutdown() {
+    use std::sync::mpsc;
+
+    use super::actor_pattern::{ActorSystem, CounterActor, CounterMessage};
+    use super::pipeline_pattern::{stop_on_shutdown, DataItem, LengthProcessor, PipelineStage, StageMessage, UppercaseProcessor};
+    use super::producer_consumer::{NumberConsumer, NumberProducer, ProducerConsumerSystem};
+    use super::worker_pool::{MathOperation, MathTask, PoolConfig, WorkerPool};
+
+    println!("=== 优雅关闭演示 ===\n");
+
+    let signal = ShutdownSignal::new();
+
+    let pool = WorkerPool::with_shutdown_signal(
+        PoolConfig { core_pool_size: 2, max_pool_size: 2, ..Default::default() },
+        signal.clone(),
+    ).unwrap();
+    for i in 1..=4 {
+        pool.submit(MathTask::new(i, MathOperation::Fibonacci(20), 1)).unwrap();
+    }
+
+    let (input, upper_receiver) = mpsc::channel();
+    let (upper_sender, length_receiver) = mpsc::channel();
+    let (output_sender, output) = mpsc::channel();
+    let (error_sender, _errors) = mpsc::channel();
+    let mut upper = PipelineStage::new("大写".to_string(), Box::new(UppercaseProcessor::new(Duration::from_millis(10))), upper_receiver, Some(upper_sender), error_sender.clone(), 10)
+        .with_shutdown_signal(&signal);
+    let mut length = PipelineStage::new("长度".to_string(), Box::new(LengthProcessor::new(Duration::ZERO)), length_receiver, Some(output_sender), error_sender, 10)
+        .with_shutdown_signal(&signal);
+    upper.start();
+    length.start();
+    for (id, text) in ["shutdown", "signal", "drain"].iter().enumerate() {
+        let _ = input.send(StageMessage::Data(DataItem::new(text.to_string(), id as u64 + 1)));
+    }
+    stop_on_shutdown(&signal, input);
+
+    let actors = ActorSystem::with_shutdown_signal(signal.clone());
+    let counter = actors.spawn(CounterActor::new("关闭计数器".to_string()), "counter".to_string()).unwrap();
+    for _ in 0..3 {
+        let _ = counter.tell(CounterMessage::Increment);
+    }
+
+    let mut numbers = ProducerConsumerSystem::with_shutdown_signal(5, signal.clone());
+    numbers.start_producer(NumberProducer::new("生产者".to_string(), 1, 1000, Duration::from_millis(5)));
+    numbers.start_consumer(NumberConsumer::new("消费者".to_string(), Duration::from_millis(1)));
+
+    // 监控线程周期性采样，信号触发后立即结束等待
+    let monitor_signal = signal.clone();
+    let monitor_guard = signal.guard();
+    let monitor = thread::spawn(move || {
+        let _monitor_guard = monitor_guard;
+        let mut samples = 0;
+        while !monitor_signal.wait_timeout(Duration::from_millis(10)) {
+            samples += 1;
+        }
+        samples
+    });
+    let notifier_signal = signal.clone();
+    thread::spawn(move || {
+        notifier_signal.wait();
+        println!("[通知] 收到关闭信号");
+    });
+
+    thread::sleep(Duration::from_millis(30));
+    println!("\n发出关闭信号，等待 {} 个线程排空后退出...", signal.active_count());
+    let stopped = signal.shutdown_all(Duration::from_secs(5));
+
+    let lengths: Vec<usize> = output.try_iter()
+        .filter_map(|message| match message {
+            StageMessage::Data(item) => Some(item.data),
+            _ => None,
+        })
+        .collect();
+    println!("全部按时停止: {}, 剩余线程: {}", stopped, signal.active_count());
+    println!("线程池完成任务: {}, 流水线输出: {:?}", pool.get_stats().tasks_completed, lengths);
+    println!("流水线阶段已退出: {}", upper.join() && length.join());
+    println!("监控线程采样次数: {}", monitor.join().unwrap_or(0));
+
+    println!("\n【优雅关闭特点】");
+    println!("✓ 单一信号 - 线程池、流水线、Actor和生产者消费者共享同一个关闭信号");
+    println!("✓ 排空后退出 - 已接收的任务、消息和数据处理完毕才停止");
+    println!("✓ 限时等待 - shutdown_all 返回是否所有线程都在限时内退出");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use crate::ConcurrentMode::actor_pattern::{ActorSystem, CounterActor, CounterMessage};
+    use crate::ConcurrentMode::pipeline_pattern::{stop_on_shutdown, DataItem, LengthProcessor, PipelineStage, StageMessage, UppercaseProcessor};
+    use crate::ConcurrentMode::worker_pool::{MathOperation, MathTask, PoolConfig, WorkerPool};
+
+    #[test]
+    fn test_worker_pool_and_pipeline_drain_on_single_signal() {
+        let signal = ShutdownSignal::new();
+
+        let pool = WorkerPool::with_shutdown_signal(
+            PoolConfig { core_pool_size: 2, max_pool_size: 2, ..Default::default() },
+            signal.clone(),
+        ).unwrap();
+        for i in 1..=8 {
+            pool.submit(MathTask::new(i, MathOperation::Add(i as i64, 1), 1)).unwrap();
+        }
+
+        let (input, upper_receiver) = mpsc::channel();
+        let (upper_sender, length_receiver) = mpsc::channel();
+        let (output_sender, output) = mpsc::channel();
+        let (error_sender, errors) = mpsc::channel();
+        let mut upper = PipelineStage::new("大写".to_string(), Box::new(UppercaseProcessor::new(Duration::from_millis(20))), upper_receiver, Some(upper_sender), error_sender.clone(), 10)
+            .with_shutdown_signal(&signal);
+        let mut length = PipelineStage::new("长度".to_string(), Box::new(LengthProcessor::new(Duration::ZERO)), length_receiver, Some(output_sender), error_sender, 10)
+            .with_shutdown_signal(&signal);
+        upper.start();
+        length.start();
+        for (id, text) in ["a", "bb", "ccc", "dddd"].iter().enumerate() {
+            input.send(StageMessage::Data(DataItem::new(text.to_string(), id as u64 + 1))).unwrap();
+        }
+        stop_on_shutdown(&signal, input);
+        assert_eq!(signal.active_count(), 4);
+
+        // 关闭时流水线和线程池中仍有未处理的数据
+        assert!(signal.shutdown_all(Duration::from_secs(5)));
+        assert_eq!(signal.active_count(), 0);
+
+        // 线程池排空队列后退出，关闭后拒绝新任务
+        assert_eq!(pool.get_stats().tasks_completed, 8);
+        assert_eq!(pool.queue_size(), 0);
+        assert!(pool.submit(MathTask::new(9, MathOperation::Add(9, 1), 1)).is_err());
+
+        // 流水线处理完信号前的所有数据，毒丸最后到达
+        let drained: Vec<usize> = output.try_iter()
+            .map_while(|message| match message {
+                StageMessage::Data(item) => Some(item.data),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(drained, vec![1, 2, 3, 4]);
+        assert!(upper.join() && length.join());
+        assert!(errors.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_actor_processes_queued_messages_before_stopping() {
+        let signal = ShutdownSignal::new();
+        let system = ActorSystem::with_shutdown_signal(signal.clone());
+        let counter = system.spawn(CounterActor::new("计数器".to_string()), "counter".to_string()).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..3 {
+            counter.tell(CounterMessage::Increment).unwrap();
+        }
+        counter.tell(CounterMessage::GetCount(sender)).unwrap();
+
+        assert!(signal.shutdown_all(Duration::from_secs(5)));
+        assert_eq!(receiver.try_recv(), Ok(3));
+        system.shutdown();
+    }
+
+    #[test]
+    fn test_actor_system_shutdown_leaves_shared_signal_untouched() {
+        let signal = ShutdownSignal::new();
+        let first = ActorSystem::with_shutdown_signal(signal.clone());
+        let second = ActorSystem::with_shutdown_signal(signal.clone());
+        first.spawn(CounterActor::new("计数器A".to_string()), "a".to_string()).unwrap();
+        let counter = second.spawn(CounterActor::new("计数器B".to_string()), "b".to_string()).unwrap();
+
+        // 关闭一个Actor系统不会触发共享信号，另一个系统的Actor照常工作
+        first.shutdown();
+        assert!(!signal.is_triggered());
+        let (sender, receiver) = mpsc::channel();
+        counter.tell(CounterMessage::Increment).unwrap();
+        counter.tell(CounterMessage::GetCount(sender)).unwrap();
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(1));
+
+        assert!(signal.shutdown_all(Duration::from_secs(5)));
+        second.shutdown();
+    }
+
+    #[test]
+    fn test_unstarted_pipeline_stage_does_not_stall_shutdown() {
+        let signal = ShutdownSignal::new();
+        let (_input, receiver) = mpsc::channel::<StageMessage<String>>();
+        let (error_sender, _errors) = mpsc::channel();
+        let _stage = PipelineStage::new("未启动".to_string(), Box::new(UppercaseProcessor::new(Duration::ZERO)), receiver, None, error_sender, 10)
+            .with_shutdown_signal(&signal);
+
+        assert_eq!(signal.active_count(), 0);
+        assert!(signal.shutdown_all(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn test_shutdown_all_times_out_while_participant_is_running() {
+        let signal = ShutdownSignal::new();
+        let guard = signal.guard();
+
+        assert!(!signal.shutdown_all(Duration::from_millis(20)));
+        assert!(signal.is_triggered());
+        assert_eq!(signal.active_count(), 1);
+
+        // 信号触发后注册的钩子立即执行
+        let ran = Arc::new(AtomicBool::new(false));
+        let hook_ran = Arc::clone(&ran);
+        signal.on_trigger(move || hook_ran.store(true, Ordering::SeqCst));
+        assert!(ran.load(Ordering::SeqCst));
+
+        drop(guard);
+        assert!(signal.shutdown_all(Duration::from_millis(20)));
+        assert!(signal.wait_timeout(Duration::ZERO));
+    }
+}
@@ -12,9 +12,12 @@
  * 3. 任务排队 - 支持任务缓冲和优先级处理
  * 4. 负载均衡 - 自动分配任务给空闲工作者
  * 5. 动态扩缩 - 根据负载动态调整线程数量
+ * 6. 协作式取消 - 任务携带取消令牌和超时时间，在检查点主动停止
+ * 7. 优雅关闭 - 观察共享的 ShutdownSignal，处理完队列中的任务后退出
  */
 
 use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{self, AtomicBool};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
@@ -22,6 +25,8 @@ use std::collections::{BinaryHeap, HashMap, VecDeque};
 use std::cmp::Ordering;
 use std::fmt;
 
+use super::shutdown::ShutdownSignal;
+
 // =================
 // 任务定义和特质
 // =================
@@ -210,6 +215,132 @@ where
     }
 }
 
+// =================
+// 可取消任务
+// =================
+
+/// 协作式取消令牌
+///
+/// 克隆后与任务共享同一个标志，任务在检查点读取它自行停止。
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 发出取消信号
+    pub fn cancel(&self) {
+        self.cancelled.store(true, atomic::Ordering::SeqCst);
+    }
+    
+    /// 是否已被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(atomic::Ordering::SeqCst)
+    }
+}
+
+/// 任务被中断的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobInterrupt {
+    Cancelled,
+    TimedOut,
+}
+
+/// 可取消任务的最终结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobOutcome<T> {
+    Completed(T),
+    Cancelled,
+    TimedOut,
+    /// 任务执行时恐慌，或线程池在执行前被关闭
+    Failed,
+}
+
+/// 任务执行上下文，任务在检查点通过它判断是否应该停止
+pub struct JobContext {
+    token: CancellationToken,
+    deadline: Option<Instant>,
+}
+
+impl JobContext {
+    /// 检查点：已取消或已超时则返回中断原因
+    pub fn checkpoint(&self) -> Result<(), JobInterrupt> {
+        if self.token.is_cancelled() {
+            return Err(JobInterrupt::Cancelled);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(JobInterrupt::TimedOut),
+            _ => Ok(()),
+        }
+    }
+    
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+/// 可取消任务的结果句柄
+pub struct JobHandle<T> {
+    token: CancellationToken,
+    result: Receiver<JobOutcome<T>>,
+}
+
+impl<T> JobHandle<T> {
+    /// 请求任务在下一个检查点停止
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+    
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+    
+    /// 阻塞等待任务结束
+    pub fn wait(self) -> JobOutcome<T> {
+        // 发送端在任务恐慌或未执行就被丢弃时断开
+        self.result.recv().unwrap_or(JobOutcome::Failed)
+    }
+}
+
+/// 把可取消的闭包适配为线程池任务
+struct CancellableJob<T, F> {
+    job: F,
+    context: JobContext,
+    result: Sender<JobOutcome<T>>,
+    priority: u8,
+}
+
+impl<T, F> Task for CancellableJob<T, F>
+where
+    T: Send + 'static,
+    F: FnOnce(&JobContext) -> Result<T, JobInterrupt> + Send + 'static,
+{
+    type Output = ();
+    
+    fn execute(self: Box<Self>) -> Self::Output {
+        let CancellableJob { job, context, result, .. } = *self;
+        // 在队列中等待期间就已取消或超时的任务不再执行
+        let outcome = match context.checkpoint().and_then(|_| job(&context)) {
+            Ok(value) => JobOutcome::Completed(value),
+            Err(JobInterrupt::Cancelled) => JobOutcome::Cancelled,
+            Err(JobInterrupt::TimedOut) => JobOutcome::TimedOut,
+        };
+        let _ = result.send(outcome);
+    }
+    
+    fn priority(&self) -> u8 {
+        self.priority
+    }
+    
+    fn description(&self) -> String {
+        "可取消任务".to_string()
+    }
+}
+
 // =================
 // 工作线程池错误处理
 // =================
@@ -266,7 +397,10 @@ pub struct WorkerPool {
     task_available: Arc<Condvar>,
     workers: Arc<Mutex<Vec<WorkerHandle>>>,
     worker_id_counter: Arc<Mutex<usize>>,
-    shutdown: Arc<Mutex<bool>>,
+    /// 外部共享的关闭信号，可能同时被其他组件观察
+    shutdown: ShutdownSignal,
+    /// 线程池自己的关闭信号，外部信号触发时随之触发；`shutdown()` 只触发它
+    local_shutdown: ShutdownSignal,
     pool_stats: Arc<Mutex<PoolStats>>,
 }
 
@@ -303,6 +437,11 @@ impl Default for PoolStats {
 impl WorkerPool {
     /// 创建新的工作线程池
     pub fn new(config: PoolConfig) -> Result<Self, WorkerPoolError> {
+        Self::with_shutdown_signal(config, ShutdownSignal::new())
+    }
+    
+    /// 创建观察共享关闭信号的线程池，信号触发后工作线程处理完队列中的任务再退出
+    pub fn with_shutdown_signal(config: PoolConfig, shutdown: ShutdownSignal) -> Result<Self, WorkerPoolError> {
         if config.core_pool_size > config.max_pool_size {
             return Err(WorkerPoolError::InvalidConfiguration);
         }
@@ -313,10 +452,23 @@ impl WorkerPool {
             task_available: Arc::new(Condvar::new()),
             workers: Arc::new(Mutex::new(Vec::new())),
             worker_id_counter: Arc::new(Mutex::new(0)),
-            shutdown: Arc::new(Mutex::new(false)),
+            shutdown,
+            local_shutdown: ShutdownSignal::new(),
             pool_stats: Arc::new(Mutex::new(PoolStats::default())),
         };
         
+        // 外部信号触发时关闭本线程池；本线程池单独关闭时不影响共享信号的其他观察者
+        let local_shutdown = pool.local_shutdown.clone();
+        pool.shutdown.on_trigger(move || local_shutdown.trigger());
+        
+        // 信号触发时唤醒等待任务的工作线程，持有队列锁避免唤醒丢失
+        let task_queue = Arc::clone(&pool.task_queue);
+        let task_available = Arc::clone(&pool.task_available);
+        pool.local_shutdown.on_trigger(move || {
+            let _queue = task_queue.lock().unwrap();
+            task_available.notify_all();
+        });
+        
         // 启动核心工作线程
         for _ in 0..config.core_pool_size {
             pool.spawn_worker(true)?;
@@ -336,7 +488,7 @@ impl WorkerPool {
         T: Task + 'static,
         T::Output: 'static,
     {
-        if *self.shutdown.lock().unwrap() {
+        if self.local_shutdown.is_triggered() {
             return Err(WorkerPoolError::PoolShutdown);
         }
         
@@ -360,6 +512,26 @@ impl WorkerPool {
         Ok(())
     }
     
+    /// 提交可取消的任务，超时从提交时开始计算
+    ///
+    /// 任务需要在循环等可中断的位置调用 `JobContext::checkpoint`，
+    /// 取消或超时后任务尽快返回，工作线程随即可以处理下一个任务。
+    pub fn submit_cancellable<T, F>(&self, timeout: Option<Duration>, job: F) -> Result<JobHandle<T>, WorkerPoolError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> Result<T, JobInterrupt> + Send + 'static,
+    {
+        let token = CancellationToken::new();
+        let (sender, receiver) = mpsc::channel();
+        let context = JobContext {
+            token: token.clone(),
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        };
+        
+        self.submit(CancellableJob { job, context, result: sender, priority: 0 })?;
+        Ok(JobHandle { token, result: receiver })
+    }
+    
     /// 批量提交任务
     pub fn submit_batch<T>(&self, tasks: Vec<T>) -> Result<(), WorkerPoolError>
     where
@@ -395,12 +567,15 @@ impl WorkerPool {
         
         let task_queue = Arc::clone(&self.task_queue);
         let task_available = Arc::clone(&self.task_available);
-        let shutdown = Arc::clone(&self.shutdown);
+        let shutdown = self.local_shutdown.clone();
+        // 同时登记到外部信号，共享信号的 shutdown_all 也会等待这些线程
+        let shutdown_guards = (self.shutdown.guard(), self.local_shutdown.guard());
         let pool_stats = Arc::clone(&self.pool_stats);
         let keep_alive_time = self.config.keep_alive_time;
         let allow_core_timeout = self.config.allow_core_timeout;
         
         let handle = thread::spawn(move || {
+            let _shutdown_guards = shutdown_guards;
             println!("工作线程 {} 启动 ({})", worker_id, if is_core { "核心" } else { "临时" });
             
             let start_time = Instant::now();
@@ -423,6 +598,9 @@ impl WorkerPool {
                     if !queue.is_empty() {
                         println!("工作线程 {} 发现队列中有任务，立即处理", worker_id);
                         Some(queue.pop().unwrap())
+                    } else if shutdown.is_triggered() {
+                        println!("工作线程 {} 队列已排空，收到关闭信号，退出", worker_id);
+                        return;
                     } else {
                         println!("工作线程 {} 开始等待任务...", worker_id);
                         // 等待任务或超时
@@ -432,7 +610,7 @@ impl WorkerPool {
                             .wait_timeout(queue, timeout)
                             .unwrap();
                         
-                        if *shutdown.lock().unwrap() {
+                        if shutdown.is_triggered() && queue_result.is_empty() {
                             println!("工作线程 {} 收到关闭信号，退出", worker_id);
                             return;
                         }
@@ -475,7 +653,7 @@ impl WorkerPool {
                             task_count += 1; // 即使失败也增加计数
                         }
                     }
-                } else if *shutdown.lock().unwrap() {
+                } else if shutdown.is_triggered() {
                     break;
                 }
             }
@@ -518,19 +696,13 @@ impl WorkerPool {
     pub fn shutdown(self) {
         println!("开始关闭线程池...");
         
-        // 设置关闭标志
-        *self.shutdown.lock().unwrap() = true;
-        
-        // 通知所有等待的线程
-        self.task_available.notify_all();
-        
-        // 等待所有工作线程完成（限制等待时间）
-        let workers = self.workers.lock().unwrap();
-        let worker_count = workers.len();
-        drop(workers);
+        let worker_count = self.workers.lock().unwrap().len();
         
-        // 等待线程退出，但不超过2秒
-        thread::sleep(Duration::from_millis(200));
+        // 只触发线程池自己的信号（由钩子唤醒等待的线程），共享信号的其他观察者不受影响；
+        // 等待线程排空队列后退出，但不超过2秒
+        if !self.local_shutdown.shutdown_all(Duration::from_secs(2)) {
+            println!("仍有 {} 个线程未在限时内退出", self.local_shutdown.active_count());
+        }
         
         let final_stats = self.get_stats();
         println!("线程池已关闭 (原有{}个工作线程)", worker_count);
@@ -767,6 +939,36 @@ pub fn demo_worker_pool() {
         pool.shutdown();
     }
     
+    println!("\n{}\n", "=".repeat(50));
+    
+    // 5. 超时与取消演示
+    println!("5. 超时与取消演示:");
+    {
+        let pool = WorkerPool::new(PoolConfig { core_pool_size: 2, ..Default::default() }).unwrap();
+        
+        let long_job = |ctx: &JobContext| {
+            let mut steps = 0u64;
+            loop {
+                ctx.checkpoint()?;
+                steps += 1;
+                thread::sleep(Duration::from_millis(5));
+                if steps == 1_000 {
+                    return Ok(steps);
+                }
+            }
+        };
+        
+        let cancelled = pool.submit_cancellable(None, long_job).unwrap();
+        let timed_out = pool.submit_cancellable(Some(Duration::from_millis(30)), long_job).unwrap();
+        
+        thread::sleep(Duration::from_millis(20));
+        cancelled.cancel();
+        println!("取消的任务结果: {:?}", cancelled.wait());
+        println!("超时的任务结果: {:?}", timed_out.wait());
+        
+        pool.shutdown();
+    }
+    
     println!("\n【Worker Pool模式特点】");
     println!("✓ 线程复用 - 避免频繁创建销毁线程");
     println!("✓ 资源控制 - 限制并发线程数量");
@@ -774,4 +976,71 @@ pub fn demo_worker_pool() {
     println!("✓ 负载均衡 - 自动分配任务给空闲线程");
     println!("✓ 动态扩缩 - 根据负载调整线程数量");
     println!("✓ 容错处理 - 处理任务执行异常");
-} 
\ No newline at end of file
+    println!("✓ 协作式取消 - 任务在检查点响应取消和超时");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每步检查一次令牌的长任务，不被中断时需要约10秒
+    fn cooperative_long_job(ctx: &JobContext) -> Result<u32, JobInterrupt> {
+        for _ in 0..1_000 {
+            ctx.checkpoint()?;
+            thread::sleep(Duration::from_millis(10));
+        }
+        Ok(1_000)
+    }
+
+    fn single_worker_pool() -> WorkerPool {
+        WorkerPool::new(PoolConfig { core_pool_size: 1, max_pool_size: 1, ..Default::default() }).unwrap()
+    }
+
+    #[test]
+    fn test_cancelled_job_releases_worker() {
+        let pool = single_worker_pool();
+
+        let handle = pool.submit_cancellable(None, cooperative_long_job).unwrap();
+        thread::sleep(Duration::from_millis(30));
+        let started = Instant::now();
+        handle.cancel();
+
+        assert_eq!(handle.wait(), JobOutcome::Cancelled);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // 唯一的工作线程没有被占住，后续任务可以正常完成
+        let next = pool.submit_cancellable(None, |_| Ok("done")).unwrap();
+        assert_eq!(next.wait(), JobOutcome::Completed("done"));
+        pool.shutdown();
+    }
+
+    #[test]
+    fn test_job_times_out_at_checkpoint() {
+        let pool = single_worker_pool();
+
+        let handle = pool.submit_cancellable(Some(Duration::from_millis(50)), cooperative_long_job).unwrap();
+        assert_eq!(handle.wait(), JobOutcome::TimedOut);
+
+        let quick = pool.submit_cancellable(Some(Duration::from_secs(5)), |ctx| ctx.checkpoint().map(|_| 42)).unwrap();
+        assert_eq!(quick.wait(), JobOutcome::Completed(42));
+        pool.shutdown();
+    }
+
+    #[test]
+    fn test_pool_shutdown_leaves_shared_signal_untouched() {
+        let signal = ShutdownSignal::new();
+        let config = PoolConfig { core_pool_size: 1, max_pool_size: 1, ..Default::default() };
+        let first = WorkerPool::with_shutdown_signal(config.clone(), signal.clone()).unwrap();
+        let second = WorkerPool::with_shutdown_signal(config, signal.clone()).unwrap();
+
+        // 关闭一个线程池不会触发共享信号，也不会停掉另一个线程池
+        first.shutdown();
+        assert!(!signal.is_triggered());
+        let handle = second.submit_cancellable(None, |_| Ok("still running")).unwrap();
+        assert_eq!(handle.wait(), JobOutcome::Completed("still running"));
+
+        // 共享信号触发后，剩下的线程池也随之关闭，并等待它的工作线程退出
+        assert!(signal.shutdown_all(Duration::from_secs(5)));
+        assert!(matches!(second.submit_cancellable(None, |_| Ok(())), Err(WorkerPoolError::PoolShutdown)));
+    }
+}
@@ -13,13 +13,24 @@
  * 4. 限流控制 - 防止系统过载，保护后端服务
  * 5. 监控日志 - 收集请求指标和日志信息
  * 6. 响应缓存 - 缓存常用数据以提高性能
+ * 7. 请求去重 - 携带相同幂等键的请求在TTL内只转发一次，并发的重复请求合并到首个请求上；
+ *    键按客户端、方法和路径隔离，同一个键配上不同的请求体返回422，5xx响应不缓存
+ * 8. 熔断路由 - 每个后端服务一个熔断器，熔断打开时转发到备用服务或直接返回503
  */
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock, Mutex};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, RwLock, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::fmt;
 
+use crate::DistributedSystemMode::ResiliencePatterns::circuit_breaker::{
+    CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerRegistry, CircuitBreakerRegistrySnapshot,
+};
+use crate::DistributedSystemMode::ResiliencePatterns::retry::is_safe_to_retry;
+
 // =================
 // 基础数据结构
 // =================
@@ -36,6 +47,13 @@ pub struct HttpRequest {
     pub timestamp: u64,
 }
 
+impl HttpRequest {
+    /// 请求失败后是否可以安全地自动重试
+    pub fn is_safe_to_retry(&self) -> bool {
+        is_safe_to_retry(&self.method, self.headers.contains_key(IDEMPOTENCY_KEY_HEADER))
+    }
+}
+
 /// HTTP响应结构
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
@@ -55,6 +73,8 @@ pub enum GatewayError {
     ServiceTimeout,
     RouteNotFound,
     InternalError(String),
+    /// 幂等键已被请求体不同的请求使用
+    IdempotencyKeyReused,
 }
 
 impl fmt::Display for GatewayError {
@@ -67,6 +87,7 @@ impl fmt::Display for GatewayError {
             GatewayError::ServiceTimeout => write!(f, "服务超时"),
             GatewayError::RouteNotFound => write!(f, "路由未找到"),
             GatewayError::InternalError(msg) => write!(f, "内部错误: {}", msg),
+            GatewayError::IdempotencyKeyReused => write!(f, "幂等键已用于不同的请求"),
         }
     }
 }
@@ -94,7 +115,8 @@ pub struct Route {
 #[derive(Debug, Clone)]
 pub struct RateLimit {
     pub requests_per_minute: u32,
-    pub requests_per_hour: u32,
+    /// 用 u64 存放，按分钟配额乘以60推算时不会溢出
+    pub requests_per_hour: u64,
 }
 
 /// 路由管理器
@@ -243,7 +265,7 @@ impl AuthManager {
 #[derive(Debug, Clone)]
 pub struct RateLimitRecord {
     pub requests_per_minute: u32,
-    pub requests_per_hour: u32,
+    pub requests_per_hour: u64,
     pub last_minute_reset: u64,
     pub last_hour_reset: u64,
 }
@@ -356,6 +378,122 @@ impl ResponseCache {
     }
 }
 
+// =================
+// 请求去重
+// =================
+
+/// 幂等键请求头
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// 重放的响应会带上该响应头
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+enum IdempotencyEntry {
+    /// 首个请求仍在处理中
+    InFlight { fingerprint: u64 },
+    Completed { response: HttpResponse, expires_at: Instant, fingerprint: u64 },
+}
+
+impl IdempotencyEntry {
+    fn fingerprint(&self) -> u64 {
+        match self {
+            IdempotencyEntry::InFlight { fingerprint } | IdempotencyEntry::Completed { fingerprint, .. } => *fingerprint,
+        }
+    }
+}
+
+/// 请求体的指纹，同一个幂等键只能配同一个请求体
+pub fn body_fingerprint(body: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 去重决策
+pub enum IdempotencyDecision<'a> {
+    /// 首次出现的键，调用方负责转发，成功后调用 `IdempotencyGuard::complete`
+    Execute(IdempotencyGuard<'a>),
+    /// TTL内已处理过的键，直接返回之前的响应
+    Replay(HttpResponse),
+    /// 键已被请求体不同的请求使用
+    Mismatch,
+}
+
+/// 首个请求持有的登记
+///
+/// 没有调用 `complete` 就被丢弃时（请求失败或处理过程中 panic）移除登记，
+/// 等待中的重复请求会重新尝试转发，不会一直阻塞。
+pub struct IdempotencyGuard<'a> {
+    store: &'a IdempotencyStore,
+    key: String,
+    fingerprint: u64,
+    completed: bool,
+}
+
+impl IdempotencyGuard<'_> {
+    /// 记录首个请求的响应，唤醒等待中的重复请求
+    pub fn complete(mut self, response: HttpResponse) {
+        let expires_at = Instant::now() + self.store.ttl;
+        self.store.entries.lock().unwrap_or_else(|e| e.into_inner())
+            .insert(self.key.clone(), IdempotencyEntry::Completed { response, expires_at, fingerprint: self.fingerprint });
+        self.store.changed.notify_all();
+        self.completed = true;
+    }
+}
+
+impl Drop for IdempotencyGuard<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.store.entries.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+            self.store.changed.notify_all();
+        }
+    }
+}
+
+/// 幂等键存储
+pub struct IdempotencyStore {
+    entries: Mutex<HashMap<String, IdempotencyEntry>>,
+    changed: Condvar,
+    ttl: Duration,
+}
+
+impl IdempotencyStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            changed: Condvar::new(),
+            ttl,
+        }
+    }
+
+    /// 登记幂等键；同一个键正在处理时阻塞，直到首个请求完成或放弃
+    ///
+    /// `fingerprint` 是请求体的指纹（见 [`body_fingerprint`]），与登记时不一致时返回 `Mismatch`。
+    /// 每次登记时顺带清理已过期的响应，存储大小受 TTL 内出现的键数限制。
+    pub fn begin(&self, key: &str, fingerprint: u64) -> IdempotencyDecision<'_> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        entries.retain(|_, entry| !matches!(entry, IdempotencyEntry::Completed { expires_at, .. } if *expires_at <= now));
+        loop {
+            match entries.get(key) {
+                Some(entry) if entry.fingerprint() != fingerprint => {
+                    return IdempotencyDecision::Mismatch;
+                }
+                Some(IdempotencyEntry::InFlight { .. }) => {
+                    entries = self.changed.wait(entries).unwrap_or_else(|e| e.into_inner());
+                }
+                Some(IdempotencyEntry::Completed { response, expires_at, .. }) if *expires_at > Instant::now() => {
+                    return IdempotencyDecision::Replay(response.clone());
+                }
+                _ => {
+                    entries.insert(key.to_string(), IdempotencyEntry::InFlight { fingerprint });
+                    return IdempotencyDecision::Execute(IdempotencyGuard { store: self, key: key.to_string(), fingerprint, completed: false });
+                }
+            }
+        }
+    }
+}
+
 // =================
 // 监控和指标
 // =================
@@ -447,6 +585,7 @@ pub struct MockService {
     name: String,
     response_time: Duration,
     success_rate: f32,
+    handled: AtomicU64,
 }
 
 impl MockService {
@@ -455,17 +594,25 @@ impl MockService {
             name,
             response_time,
             success_rate,
+            handled: AtomicU64::new(0),
         }
     }
     
+    /// 服务实际收到的请求数
+    pub fn handled_count(&self) -> u64 {
+        self.handled.load(Ordering::SeqCst)
+    }
+    
     pub fn handle_request(&self, request: &HttpRequest) -> GatewayResult<HttpResponse> {
+        self.handled.fetch_add(1, Ordering::SeqCst);
         // 模拟处理时间
         std::thread::sleep(self.response_time);
         
         // 模拟成功率
         let random_value = (SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() % 100) as f32 / 100.0;
         
-        if random_value > self.success_rate {
+        // random_value 取值 0.00..=0.99，用 >= 使成功概率恰为 success_rate，成功率为0时必定失败
+        if random_value >= self.success_rate {
             return Err(GatewayError::ServiceUnavailable);
         }
         
@@ -495,6 +642,10 @@ pub struct ApiGateway {
     auth_manager: AuthManager,
     rate_limiter: RateLimiter,
     response_cache: ResponseCache,
+    idempotency: IdempotencyStore,
+    circuit_breakers: CircuitBreakerRegistry,
+    /// 主服务 -> 备用服务
+    fallback_services: HashMap<String, String>,
     monitoring: MonitoringManager,
     services: HashMap<String, MockService>,
 }
@@ -506,11 +657,38 @@ impl ApiGateway {
             auth_manager: AuthManager::new(),
             rate_limiter: RateLimiter::new(),
             response_cache: ResponseCache::new(),
+            idempotency: IdempotencyStore::new(Duration::from_secs(24 * 60 * 60)),
+            circuit_breakers: CircuitBreakerRegistry::new(CircuitBreakerConfig::default()),
+            fallback_services: HashMap::new(),
             monitoring: MonitoringManager::new(),
             services: HashMap::new(),
         }
     }
     
+    /// 设置幂等键的有效期，默认24小时
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency = IdempotencyStore::new(ttl);
+        self
+    }
+    
+    /// 设置后端服务熔断器的配置
+    ///
+    /// 会替换整个熔断器注册表，已创建的熔断器及其统计一并丢弃，应在处理请求前调用
+    pub fn with_circuit_breaker_config(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breakers = CircuitBreakerRegistry::new(config);
+        self
+    }
+    
+    /// 主服务熔断时把请求转发到备用服务
+    pub fn add_fallback_service(&mut self, primary: &str, fallback: &str) {
+        self.fallback_services.insert(primary.to_string(), fallback.to_string());
+    }
+    
+    /// 各后端服务熔断器的状态
+    pub fn circuit_breaker_snapshot(&self) -> CircuitBreakerRegistrySnapshot {
+        self.circuit_breakers.snapshot()
+    }
+    
     pub fn add_route(&mut self, route: Route) {
         self.route_manager.add_route(route);
     }
@@ -562,13 +740,39 @@ impl ApiGateway {
         }
         
         // 3. 限流检查
+        let client_id = request.headers.get("X-Client-ID")
+            .unwrap_or(&request.client_ip);
         if let Some(rate_limit) = &route.rate_limit {
-            let client_id = request.headers.get("X-Client-ID")
-                .unwrap_or(&request.client_ip);
             self.rate_limiter.check_rate_limit(client_id, rate_limit)?;
         }
         
-        // 4. 缓存检查
+        // 4. 幂等键去重，键按客户端、方法和路径隔离
+        let idempotency_key = request.headers.get(IDEMPOTENCY_KEY_HEADER)
+            .map(|key| format!("{}:{}:{}:{}", client_id, request.method, request.path, key));
+        let Some(idempotency_key) = idempotency_key else {
+            return self.forward_request(&route, request);
+        };
+        let guard = match self.idempotency.begin(&idempotency_key, body_fingerprint(&request.body)) {
+            IdempotencyDecision::Execute(guard) => guard,
+            IdempotencyDecision::Replay(mut response) => {
+                response.headers.insert(IDEMPOTENT_REPLAYED_HEADER.to_string(), "true".to_string());
+                return Ok(response);
+            }
+            IdempotencyDecision::Mismatch => return Err(GatewayError::IdempotencyKeyReused),
+        };
+        
+        // 转发失败、返回5xx或 panic 时 guard 被丢弃，登记随之移除，重试会重新转发
+        let result = self.forward_request(&route, request);
+        if let Ok(response) = &result {
+            if response.status_code < 500 {
+                guard.complete(response.clone());
+            }
+        }
+        result
+    }
+    
+    fn forward_request(&self, route: &Route, request: &HttpRequest) -> GatewayResult<HttpResponse> {
+        // 5. 缓存检查
         if let Some(_cache_ttl) = route.cache_ttl {
             let cache_key = self.response_cache.generate_cache_key(request);
             if let Some(cached_response) = self.response_cache.get(&cache_key) {
@@ -576,13 +780,22 @@ impl ApiGateway {
             }
         }
         
-        // 5. 转发请求到目标服务
-        let service = self.services.get(&route.target_service)
-            .ok_or(GatewayError::ServiceUnavailable)?;
-        
-        let response = service.handle_request(request)?;
+        // 6. 经熔断器转发请求到目标服务
+        let response = match self.call_service(&route.target_service, request) {
+            Err(CircuitBreakerError::CircuitOpen) => match self.fallback_services.get(&route.target_service) {
+                // 备用服务只尝试一层，不再继续查找它的备用服务
+                Some(fallback) => self.call_service(fallback, request),
+                None => Err(CircuitBreakerError::CircuitOpen),
+            },
+            result => result,
+        };
+        let response = response.map_err(|error| match error {
+            CircuitBreakerError::CircuitOpen => GatewayError::ServiceUnavailable,
+            CircuitBreakerError::CallTimeout => GatewayError::ServiceTimeout,
+            CircuitBreakerError::ServiceError(error) => error,
+        })?;
         
-        // 6. 缓存响应
+        // 7. 缓存响应
         if let Some(cache_ttl) = route.cache_ttl {
             let cache_key = self.response_cache.generate_cache_key(request);
             self.response_cache.put(cache_key, response.clone(), cache_ttl);
@@ -591,6 +804,13 @@ impl ApiGateway {
         Ok(response)
     }
     
+    /// 通过服务对应的熔断器调用，调用结果记录回熔断器
+    fn call_service(&self, name: &str, request: &HttpRequest) -> Result<HttpResponse, CircuitBreakerError<GatewayError>> {
+        let service = self.services.get(name)
+            .ok_or(CircuitBreakerError::ServiceError(GatewayError::ServiceUnavailable))?;
+        self.circuit_breakers.call(name, || service.handle_request(request))
+    }
+    
     fn create_error_response(&self, error: GatewayError) -> HttpResponse {
         let (status_code, message) = match error {
             GatewayError::RouteNotFound => (404, "路由未找到"),
@@ -600,6 +820,7 @@ impl ApiGateway {
             GatewayError::ServiceTimeout => (504, "服务超时"),
             GatewayError::BadRequest(_) => (400, "错误请求"),
             GatewayError::InternalError(_) => (500, "内部错误"),
+            GatewayError::IdempotencyKeyReused => (422, "幂等键已用于不同的请求"),
         };
         
         let mut headers = HashMap::new();
@@ -753,8 +974,39 @@ pub fn demo_api_gateway() {
     let response4 = gateway.handle_request(request4);
     println!("未知路由请求: {} - {}", response4.status_code, response4.body);
     
-    // 3. 监控统计
-    println!("\n3. 监控统计:");
+    // 3. 幂等键去重
+    println!("\n3. 幂等键去重演示:");
+    for attempt in 1..=2 {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+        headers.insert(IDEMPOTENCY_KEY_HEADER.to_string(), "order-create-001".to_string());
+        let request = HttpRequest {
+            method: "POST".to_string(),
+            path: "/api/orders/new".to_string(),
+            headers,
+            body: r#"{"item": "book"}"#.to_string(),
+            query_params: HashMap::new(),
+            client_ip: "192.168.1.100".to_string(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        };
+        let response = gateway.handle_request(request);
+        println!("第{}次提交: {} 重放={}", attempt, response.status_code,
+                 response.headers.contains_key(IDEMPOTENT_REPLAYED_HEADER));
+    }
+    
+    // 4. 熔断路由
+    println!("\n4. 熔断路由演示:");
+    gateway.add_service("product-service-backup".to_string(),
+                       MockService::new("product-service-backup".to_string(), Duration::from_millis(10), 1.0));
+    gateway.add_fallback_service("product-service", "product-service-backup");
+    let snapshot = gateway.circuit_breaker_snapshot();
+    for (service, stats) in &snapshot.breakers {
+        println!("  {} 熔断器: {} (调用 {} 次)", service, stats.state, stats.total_calls);
+    }
+    println!("  product-service 熔断后请求将转发到 product-service-backup");
+    
+    // 5. 监控统计
+    println!("\n5. 监控统计:");
     let metrics = gateway.get_metrics();
     println!("总请求数: {}", metrics.total_requests);
     println!("成功请求数: {}", metrics.successful_requests);
@@ -771,8 +1023,8 @@ pub fn demo_api_gateway() {
         println!("  {}: {} 次", path, count);
     }
     
-    // 4. 最近日志
-    println!("\n4. 最近请求日志:");
+    // 6. 最近日志
+    println!("\n6. 最近请求日志:");
     let logs = gateway.get_recent_logs(5);
     for log in logs {
         println!("  {}", log);
@@ -785,4 +1037,228 @@ pub fn demo_api_gateway() {
     println!("✓ 限流控制 - 防止系统过载，保护后端服务");
     println!("✓ 监控日志 - 收集请求指标和日志信息");
     println!("✓ 响应缓存 - 缓存常用数据以提高性能");
-} 
\ No newline at end of file
+    println!("✓ 请求去重 - 相同幂等键的请求只转发一次");
+    println!("✓ 熔断路由 - 后端熔断时快速失败或转发到备用服务");
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_gateway(ttl: Duration, response_time: Duration) -> ApiGateway {
+        let mut gateway = ApiGateway::new().with_idempotency_ttl(ttl);
+        gateway.add_service("order-service".to_string(),
+                           MockService::new("order-service".to_string(), response_time, 1.0));
+        gateway.add_route(Route {
+            path_pattern: "/api/orders/*".to_string(),
+            target_service: "order-service".to_string(),
+            target_path: "/orders/*".to_string(),
+            methods: vec!["POST".to_string()],
+            require_auth: false,
+            rate_limit: None,
+            timeout: Duration::from_secs(1),
+            cache_ttl: None,
+        });
+        gateway
+    }
+
+    fn order_request(idempotency_key: &str) -> HttpRequest {
+        let mut headers = HashMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER.to_string(), idempotency_key.to_string());
+        HttpRequest {
+            method: "POST".to_string(),
+            path: "/api/orders/new".to_string(),
+            headers,
+            body: String::new(),
+            query_params: HashMap::new(),
+            client_ip: "10.0.0.1".to_string(),
+            timestamp: 0,
+        }
+    }
+
+    fn backend_hits(gateway: &ApiGateway) -> u64 {
+        gateway.services["order-service"].handled_count()
+    }
+
+    /// 主服务总是失败，3次失败后熔断
+    fn failing_primary_gateway() -> ApiGateway {
+        let mut gateway = order_gateway(Duration::from_secs(60), Duration::ZERO)
+            .with_circuit_breaker_config(CircuitBreakerConfig {
+                failure_threshold: 3,
+                recovery_timeout: Duration::from_secs(60),
+                ..CircuitBreakerConfig::default()
+            });
+        gateway.add_service("order-service".to_string(),
+                           MockService::new("order-service".to_string(), Duration::ZERO, 0.0));
+        gateway
+    }
+
+    fn plain_order_request() -> HttpRequest {
+        HttpRequest { headers: HashMap::new(), ..order_request("") }
+    }
+
+    #[test]
+    fn test_request_retry_safety_honours_idempotency_key() {
+        assert!(!plain_order_request().is_safe_to_retry());
+        assert!(order_request("key-1").is_safe_to_retry());
+        assert!(HttpRequest { method: "GET".to_string(), ..plain_order_request() }.is_safe_to_retry());
+    }
+
+    #[test]
+    fn test_replayed_key_returns_cached_response() {
+        let gateway = order_gateway(Duration::from_millis(100), Duration::ZERO);
+
+        let first = gateway.handle_request(order_request("key-1"));
+        let replayed = gateway.handle_request(order_request("key-1"));
+        assert_eq!(first.status_code, 200);
+        assert_eq!(replayed.body, first.body);
+        assert_eq!(replayed.headers.get(IDEMPOTENT_REPLAYED_HEADER).map(String::as_str), Some("true"));
+        assert!(!first.headers.contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        assert_eq!(backend_hits(&gateway), 1);
+
+        // 不同的键正常转发
+        gateway.handle_request(order_request("key-2"));
+        assert_eq!(backend_hits(&gateway), 2);
+
+        // TTL过期后同一个键重新转发
+        std::thread::sleep(Duration::from_millis(150));
+        let after_ttl = gateway.handle_request(order_request("key-1"));
+        assert!(!after_ttl.headers.contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        assert_eq!(backend_hits(&gateway), 3);
+    }
+
+    fn ok_response(body: &str) -> HttpResponse {
+        HttpResponse { status_code: 200, headers: HashMap::new(), body: body.to_string(), processing_time: Duration::ZERO }
+    }
+
+    #[test]
+    fn test_panicking_first_request_releases_key_for_waiters() {
+        let store = IdempotencyStore::new(Duration::from_secs(60));
+        let (entered, leader_may_panic) = (std::sync::Barrier::new(2), std::sync::Barrier::new(2));
+
+        std::thread::scope(|scope| {
+            let leader = scope.spawn(|| {
+                let _guard = match store.begin("key", 0) {
+                    IdempotencyDecision::Execute(guard) => guard,
+                    _ => panic!("首个请求应该执行"),
+                };
+                entered.wait();
+                leader_may_panic.wait();
+                panic!("处理请求时 panic");
+            });
+            entered.wait();
+            let waiter = scope.spawn(|| match store.begin("key", 0) {
+                IdempotencyDecision::Execute(guard) => guard.complete(ok_response("重试")),
+                _ => panic!("首个请求没有完成，不应该重放"),
+            });
+            leader_may_panic.wait();
+            assert!(leader.join().is_err());
+            waiter.join().unwrap();
+        });
+
+        match store.begin("key", 0) {
+            IdempotencyDecision::Replay(response) => assert_eq!(response.body, "重试"),
+            _ => panic!("应该重放等待者的响应"),
+        };
+    }
+
+    #[test]
+    fn test_reused_key_is_scoped_by_route_and_checked_against_body() {
+        let gateway = order_gateway(Duration::from_secs(60), Duration::ZERO);
+        let first = gateway.handle_request(HttpRequest { body: "{\"item\":1}".to_string(), ..order_request("key-1") });
+        assert_eq!(first.status_code, 200);
+
+        // 同一个键换了请求体：拒绝而不是返回第一次的响应
+        let mismatched = gateway.handle_request(HttpRequest { body: "{\"item\":2}".to_string(), ..order_request("key-1") });
+        assert_eq!(mismatched.status_code, 422);
+        assert_eq!(backend_hits(&gateway), 1);
+
+        // 同一个键用在别的路径上是另一个请求
+        let other_path = gateway.handle_request(HttpRequest { path: "/api/orders/other".to_string(), ..order_request("key-1") });
+        assert!(!other_path.headers.contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        assert_eq!(backend_hits(&gateway), 2);
+    }
+
+    #[test]
+    fn test_server_error_is_not_cached_for_retries() {
+        let mut gateway = failing_primary_gateway();
+        assert_eq!(gateway.handle_request(order_request("key-1")).status_code, 503);
+
+        // 后端恢复后用同一个键重试，请求重新转发
+        gateway.add_service("order-service".to_string(),
+                           MockService::new("order-service".to_string(), Duration::ZERO, 1.0));
+        let retried = gateway.handle_request(order_request("key-1"));
+        assert_eq!(retried.status_code, 200);
+        assert!(!retried.headers.contains_key(IDEMPOTENT_REPLAYED_HEADER));
+    }
+
+    #[test]
+    fn test_begin_prunes_expired_responses() {
+        let store = IdempotencyStore::new(Duration::ZERO);
+        for key in ["a", "b", "c"] {
+            if let IdempotencyDecision::Execute(guard) = store.begin(key, 0) {
+                guard.complete(ok_response(key));
+            }
+        }
+        assert!(matches!(store.begin("d", 0), IdempotencyDecision::Execute(_)));
+        // 过期的 a、b、c 已被清理，d 的登记随 guard 被丢弃而移除
+        assert!(store.entries.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_duplicates_hit_backend_once() {
+        let gateway = order_gateway(Duration::from_secs(60), Duration::from_millis(50));
+
+        let responses: Vec<HttpResponse> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..5)
+                .map(|_| scope.spawn(|| gateway.handle_request(order_request("same-key"))))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        assert_eq!(backend_hits(&gateway), 1);
+        assert!(responses.iter().all(|response| response.status_code == 200 && response.body == responses[0].body));
+        let replayed = responses.iter().filter(|response| response.headers.contains_key(IDEMPOTENT_REPLAYED_HEADER)).count();
+        assert_eq!(replayed, 4);
+    }
+
+    #[test]
+    fn test_open_breaker_fast_fails_without_calling_backend() {
+        let gateway = failing_primary_gateway();
+
+        for _ in 0..3 {
+            assert_eq!(gateway.handle_request(plain_order_request()).status_code, 503);
+        }
+        assert_eq!(backend_hits(&gateway), 3);
+        assert_eq!(gateway.circuit_breaker_snapshot().open_breakers(), vec!["order-service"]);
+
+        // 熔断打开后直接返回503，不再调用后端
+        for _ in 0..5 {
+            assert_eq!(gateway.handle_request(plain_order_request()).status_code, 503);
+        }
+        assert_eq!(backend_hits(&gateway), 3);
+        assert_eq!(gateway.circuit_breaker_snapshot().breakers["order-service"].rejected_calls, 5);
+    }
+
+    #[test]
+    fn test_open_breaker_reroutes_to_fallback_service() {
+        let mut gateway = failing_primary_gateway();
+        gateway.add_service("order-service-backup".to_string(),
+                           MockService::new("order-service-backup".to_string(), Duration::ZERO, 1.0));
+        gateway.add_fallback_service("order-service", "order-service-backup");
+
+        // 熔断之前失败直接返回给调用方，不切换到备用服务
+        for _ in 0..3 {
+            assert_eq!(gateway.handle_request(plain_order_request()).status_code, 503);
+        }
+        assert_eq!(gateway.services["order-service-backup"].handled_count(), 0);
+
+        for _ in 0..4 {
+            let response = gateway.handle_request(plain_order_request());
+            assert_eq!(response.status_code, 200);
+            assert_eq!(response.headers.get("X-Service").map(String::as_str), Some("order-service-backup"));
+        }
+        assert_eq!(backend_hits(&gateway), 3);
+        assert_eq!(gateway.services["order-service-backup"].handled_count(), 4);
+    }
+}
@@ -0,0 +1,287 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/CommunicationPatterns/idempotent_consumer.rs
+ *
+ * Idempotent Consumer模式 (幂等消费者)
+ *
+ * 消息队列只保证至少一次投递：确认丢失或处理失败后，同一条消息会被再次投递。
+ * 幂等消费者在业务处理器外面包一层去重：处理成功后把消息编号写入去重存储，
+ * 再次收到相同编号的消息时直接确认而不调用处理器，把至少一次变成事实上的恰好一次。
+ *
+ * 主要特点：
+ * 1. 先记录后确认 - 处理成功后先记录编号再发送Ack，Ack丢失导致的重复投递会被跳过
+ * 2. 失败重试 - 处理器返回错误时不记录编号，发送Nack让代理重新投递
+ * 3. 有界存储 - 去重记录在TTL后过期，并按容量淘汰最早的记录，
+ *    TTL应大于代理的最长重新投递间隔，否则过期后的重复投递无法识别
+ */
+
+use std::collections::{HashMap, VecDeque};
+
+use super::message_queue::{MessageBroker, QueueMessage, QueueProducer, BROKER_ADDRESS};
+use super::sim_network::{SimNetwork, SimNetworkConfig, Transport};
+
+// =================
+// 去重存储
+// =================
+
+/// 记录已处理消息编号的去重存储，按TTL和容量限制大小
+pub struct DedupStore {
+    ttl: u64,
+    capacity: usize,
+    /// 消息编号 -> 记录时间
+    seen: HashMap<u64, u64>,
+    /// 按记录时间排列，用于过期和淘汰
+    order: VecDeque<(u64, u64)>,
+}
+
+impl DedupStore {
+    pub fn new(ttl: u64, capacity: usize) -> Self {
+        Self { ttl, capacity, seen: HashMap::new(), order: VecDeque::new() }
+    }
+
+    /// 编号是否已在TTL内记录过
+    pub fn contains(&mut self, message_id: u64, now: u64) -> bool {
+        self.expire(now);
+        self.seen.contains_key(&message_id)
+    }
+
+    pub fn record(&mut self, message_id: u64, now: u64) {
+        self.expire(now);
+        if self.seen.insert(message_id, now).is_none() {
+            self.order.push_back((message_id, now));
+        }
+        while self.seen.len() > self.capacity {
+            match self.order.pop_front() {
+                Some((oldest, _)) => {
+                    self.seen.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    fn expire(&mut self, now: u64) {
+        while let Some(&(message_id, recorded_at)) = self.order.front() {
+            if now.saturating_sub(recorded_at) < self.ttl {
+                break;
+            }
+            self.order.pop_front();
+            self.seen.remove(&message_id);
+        }
+    }
+}
+
+// =================
+// 幂等消费者
+// =================
+
+/// 业务处理器，参数为消息编号和内容
+type MessageHandler = Box<dyn FnMut(u64, &str) -> Result<(), String>>;
+
+/// 包装业务处理器的幂等消费者
+pub struct IdempotentConsumer {
+    name: String,
+    handler: MessageHandler,
+    store: DedupStore,
+    duplicates: u64,
+    failures: u64,
+}
+
+impl IdempotentConsumer {
+    pub fn new<F>(name: &str, store: DedupStore, handler: F) -> Self
+    where
+        F: FnMut(u64, &str) -> Result<(), String> + 'static,
+    {
+        Self { name: name.to_string(), handler: Box::new(handler), store, duplicates: 0, failures: 0 }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn poll(&mut self, transport: &dyn Transport<QueueMessage>) {
+        while let Some(envelope) = transport.receive(&self.name) {
+            let QueueMessage::Deliver { message_id, payload } = envelope.message else { continue };
+            let now = transport.now();
+
+            let reply = if self.store.contains(message_id, now) {
+                self.duplicates += 1;
+                QueueMessage::Ack { message_id }
+            } else {
+                match (self.handler)(message_id, &payload) {
+                    Ok(()) => {
+                        self.store.record(message_id, now);
+                        QueueMessage::Ack { message_id }
+                    }
+                    Err(_) => {
+                        self.failures += 1;
+                        QueueMessage::Nack { message_id }
+                    }
+                }
+            };
+            transport.send(&self.name, &envelope.from, reply);
+        }
+    }
+
+    /// 因已处理过而跳过的投递次数
+    pub fn duplicate_count(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// 处理器返回错误的次数
+    pub fn failure_count(&self) -> u64 {
+        self.failures
+    }
+
+    pub fn store(&self) -> &DedupStore {
+        &self.store
+    }
+}
+
+/// Idempotent Consumer模式演示
+pub fn demo_idempotent_consumer() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    println!("=== Idempotent Consumer模式演示 ===\n");
+
+    let network = SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 1, ..SimNetworkConfig::default() }, 3);
+    let mut broker = MessageBroker::new(5);
+    let charges = Rc::new(RefCell::new(Vec::new()));
+    let ledger = Rc::clone(&charges);
+    let mut consumer = IdempotentConsumer::new("扣款服务", DedupStore::new(100, 1000), move |message_id, payload| {
+        ledger.borrow_mut().push((message_id, payload.to_string()));
+        Ok(())
+    });
+    broker.subscribe(consumer.name());
+
+    let mut producer = QueueProducer::new("订单服务", 1);
+    for i in 1..=3 {
+        producer.publish(&network, &format!("订单{}扣款", i));
+    }
+
+    // 确认全部丢失，代理不断重新投递
+    network.block_link(consumer.name(), BROKER_ADDRESS);
+    for tick in 0..40 {
+        if tick == 20 {
+            network.unblock_link(consumer.name(), BROKER_ADDRESS);
+        }
+        network.tick();
+        broker.poll(&network);
+        consumer.poll(&network);
+    }
+
+    println!("重新投递次数: {}", broker.redelivery_count());
+    println!("跳过的重复投递: {}, 处理失败: {}", consumer.duplicate_count(), consumer.failure_count());
+    println!("实际扣款: {:?}", charges.borrow());
+    if !consumer.store().is_empty() {
+        println!("去重记录数: {}", consumer.store().len());
+    }
+
+    println!("\n【Idempotent Consumer模式特点】");
+    println!("✓ 按消息编号去重 - 重复投递只确认不处理");
+    println!("✓ 失败重试 - 处理失败发送Nack，成功后才记录编号");
+    println!("✓ 有界存储 - 去重记录按TTL过期、按容量淘汰");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn network() -> SimNetwork<QueueMessage> {
+        SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 1, ..SimNetworkConfig::default() }, 9)
+    }
+
+    fn run(ticks: usize, network: &SimNetwork<QueueMessage>, broker: &mut MessageBroker, consumer: &mut IdempotentConsumer) {
+        for _ in 0..ticks {
+            network.tick();
+            broker.poll(network);
+            consumer.poll(network);
+        }
+    }
+
+    #[test]
+    fn test_redelivery_after_lost_ack_runs_handler_once() {
+        let network = network();
+        let mut broker = MessageBroker::new(5);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&calls);
+        let mut consumer = IdempotentConsumer::new("worker", DedupStore::new(100, 10), move |message_id, payload| {
+            log.borrow_mut().push((message_id, payload.to_string()));
+            Ok(())
+        });
+        broker.subscribe("worker");
+        let id = QueueProducer::new("producer", 7).publish(&network, "charge");
+
+        network.block_link("worker", BROKER_ADDRESS);
+        run(15, &network, &mut broker, &mut consumer);
+        assert!(broker.redelivery_count() >= 2);
+        assert_eq!(consumer.duplicate_count(), broker.redelivery_count());
+
+        network.unblock_link("worker", BROKER_ADDRESS);
+        run(15, &network, &mut broker, &mut consumer);
+        assert_eq!(broker.unacked_count(), 0);
+        assert_eq!(*calls.borrow(), vec![(id, "charge".to_string())]);
+    }
+
+    #[test]
+    fn test_nack_redelivers_until_handler_succeeds_once() {
+        let network = network();
+        let mut broker = MessageBroker::new(100);
+        let attempts = Rc::new(RefCell::new(0));
+        let applied = Rc::new(RefCell::new(0));
+        let (attempt_counter, applied_counter) = (Rc::clone(&attempts), Rc::clone(&applied));
+        let mut consumer = IdempotentConsumer::new("worker", DedupStore::new(1000, 10), move |_, _| {
+            *attempt_counter.borrow_mut() += 1;
+            if *attempt_counter.borrow() == 1 {
+                return Err("数据库暂时不可用".to_string());
+            }
+            *applied_counter.borrow_mut() += 1;
+            Ok(())
+        });
+        broker.subscribe("worker");
+        let mut producer = QueueProducer::new("producer", 1);
+        producer.publish(&network, "order");
+
+        // 第一次处理失败发送Nack，代理立即重新投递，第二次成功
+        run(10, &network, &mut broker, &mut consumer);
+        assert_eq!((*attempts.borrow(), *applied.borrow()), (2, 1));
+        assert_eq!(consumer.failure_count(), 1);
+        assert_eq!(broker.redelivery_count(), 1);
+        assert_eq!(broker.unacked_count(), 0);
+
+        // 同一条消息再次投递（例如代理故障恢复后重放）时处理器不再执行
+        network.send(BROKER_ADDRESS, "worker", QueueMessage::Deliver { message_id: 1, payload: "order".to_string() });
+        run(5, &network, &mut broker, &mut consumer);
+        assert_eq!((*attempts.borrow(), *applied.borrow()), (2, 1));
+        assert_eq!(consumer.duplicate_count(), 1);
+    }
+
+    #[test]
+    fn test_dedup_store_is_bounded_by_ttl_and_capacity() {
+        let mut store = DedupStore::new(10, 3);
+        for id in 1..=4 {
+            store.record(id, id);
+        }
+        // 超过容量时淘汰最早的记录
+        assert_eq!(store.len(), 3);
+        assert!(!store.contains(1, 4));
+        assert!(store.contains(2, 4));
+
+        // 记录在TTL后过期
+        assert!(store.contains(3, 12));
+        assert!(!store.contains(2, 12));
+        assert_eq!(store.len(), 2);
+        assert!(!store.contains(4, 14));
+        assert!(store.is_empty());
+    }
+}
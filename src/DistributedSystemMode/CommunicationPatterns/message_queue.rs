@@ -0,0 +1,326 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/CommunicationPatterns/message_queue.rs
+ *
+ * Message Queue模式 (消息队列)
+ *
+ * 消息队列在生产者和消费者之间引入代理（Broker），实现异步消息传递：
+ * 生产者只管发布，代理负责缓存并把消息投递给消费者。
+ *
+ * 主要特点：
+ * 1. 解耦 - 生产者和消费者互不感知，可以独立扩缩容
+ * 2. 削峰 - 消费者按自己的节奏处理，积压的消息留在队列中
+ * 3. 至少一次投递 - 消费者确认前消息保持未确认状态，超时后重新投递
+ * 4. 负载分担 - 多个消费者订阅同一队列时轮询分发
+ *
+ * 实现说明：
+ * - 所有节点通过 Transport 通信，配合 SimNetwork 可以模拟丢包后的重新投递
+ * - 确认丢失时消息会被重复投递，消费者需要自行保证幂等（见 idempotent_consumer）
+ * - 消费者处理失败时发送 Nack，代理立即把消息放回队首重新投递
+ * - 代理按消息编号对发布去重，生产者重发同一编号的消息只会入队一次，
+ *   去重记录复用 DedupStore，按TTL过期并按容量淘汰，不会随发布量无限增长
+ */
+
+use std::collections::{BTreeMap, VecDeque};
+
+use super::idempotent_consumer::DedupStore;
+use super::sim_network::{SimNetwork, SimNetworkConfig, Transport};
+
+/// 代理在网络中的地址
+pub const BROKER_ADDRESS: &str = "broker";
+
+/// 发布去重记录的默认保留时长
+pub const DEFAULT_PUBLISH_DEDUP_TTL: u64 = 1_000;
+
+/// 发布去重记录的默认容量
+pub const DEFAULT_PUBLISH_DEDUP_CAPACITY: usize = 10_000;
+
+/// 队列消息
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueMessage {
+    /// 生产者发布消息
+    Publish { message_id: u64, payload: String },
+    /// 代理投递消息给消费者
+    Deliver { message_id: u64, payload: String },
+    /// 消费者确认已处理
+    Ack { message_id: u64 },
+    /// 消费者处理失败，请求立即重新投递
+    Nack { message_id: u64 },
+}
+
+struct Unacked {
+    payload: String,
+    delivered_at: u64,
+}
+
+/// 消息代理
+pub struct MessageBroker {
+    queue: VecDeque<(u64, String)>,
+    unacked: BTreeMap<u64, Unacked>,
+    consumers: Vec<String>,
+    next_consumer: usize,
+    redelivery_timeout: u64,
+    redeliveries: u64,
+    /// 已接受过的发布编号，用于丢弃生产者的重复发布
+    published_ids: DedupStore,
+    duplicate_publishes: u64,
+}
+
+impl MessageBroker {
+    /// `redelivery_timeout` 为投递后等待确认的时长
+    pub fn new(redelivery_timeout: u64) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            unacked: BTreeMap::new(),
+            consumers: Vec::new(),
+            next_consumer: 0,
+            redelivery_timeout,
+            redeliveries: 0,
+            published_ids: DedupStore::new(DEFAULT_PUBLISH_DEDUP_TTL, DEFAULT_PUBLISH_DEDUP_CAPACITY),
+            duplicate_publishes: 0,
+        }
+    }
+
+    /// 设置发布去重记录的保留时长和容量，
+    /// 超出TTL或被容量淘汰的编号再次发布时会重新入队
+    pub fn with_publish_dedup(mut self, ttl: u64, capacity: usize) -> Self {
+        self.published_ids = DedupStore::new(ttl, capacity);
+        self
+    }
+
+    pub fn subscribe(&mut self, consumer: &str) {
+        self.consumers.push(consumer.to_string());
+    }
+
+    /// 处理收件箱，重新投递超时未确认的消息，并把队列中的消息分发给消费者
+    pub fn poll(&mut self, transport: &dyn Transport<QueueMessage>) {
+        let now = transport.now();
+        while let Some(envelope) = transport.receive(BROKER_ADDRESS) {
+            match envelope.message {
+                QueueMessage::Publish { message_id, payload } => {
+                    if self.published_ids.contains(message_id, now) {
+                        self.duplicate_publishes += 1;
+                    } else {
+                        self.published_ids.record(message_id, now);
+                        self.queue.push_back((message_id, payload));
+                    }
+                }
+                QueueMessage::Ack { message_id } => {
+                    self.unacked.remove(&message_id);
+                }
+                QueueMessage::Nack { message_id } => {
+                    if let Some(unacked) = self.unacked.remove(&message_id) {
+                        self.redeliveries += 1;
+                        self.queue.push_front((message_id, unacked.payload));
+                    }
+                }
+                QueueMessage::Deliver { .. } => {}
+            }
+        }
+        if self.consumers.is_empty() {
+            return;
+        }
+
+        let expired: Vec<u64> = self.unacked.iter()
+            .filter(|(_, unacked)| now.saturating_sub(unacked.delivered_at) >= self.redelivery_timeout)
+            .map(|(message_id, _)| *message_id)
+            .collect();
+        for message_id in expired {
+            if let Some(unacked) = self.unacked.remove(&message_id) {
+                self.redeliveries += 1;
+                self.queue.push_front((message_id, unacked.payload));
+            }
+        }
+
+        while let Some((message_id, payload)) = self.queue.pop_front() {
+            let consumer = &self.consumers[self.next_consumer % self.consumers.len()];
+            self.next_consumer += 1;
+            transport.send(BROKER_ADDRESS, consumer, QueueMessage::Deliver { message_id, payload: payload.clone() });
+            self.unacked.insert(message_id, Unacked { payload, delivered_at: now });
+        }
+    }
+
+    /// 尚未投递的消息数
+    pub fn queued_count(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// 已投递但未确认的消息数
+    pub fn unacked_count(&self) -> usize {
+        self.unacked.len()
+    }
+
+    pub fn redelivery_count(&self) -> u64 {
+        self.redeliveries
+    }
+
+    /// 因编号重复而被丢弃的发布次数
+    pub fn duplicate_publish_count(&self) -> u64 {
+        self.duplicate_publishes
+    }
+
+    /// 当前保留的发布去重记录数
+    pub fn published_id_count(&self) -> usize {
+        self.published_ids.len()
+    }
+}
+
+/// 生产者
+pub struct QueueProducer {
+    name: String,
+    next_message_id: u64,
+}
+
+impl QueueProducer {
+    /// `id_base` 用于区分不同生产者的消息编号
+    pub fn new(name: &str, id_base: u64) -> Self {
+        Self { name: name.to_string(), next_message_id: id_base }
+    }
+
+    pub fn publish(&mut self, transport: &dyn Transport<QueueMessage>, payload: &str) -> u64 {
+        let message_id = self.next_message_id;
+        self.next_message_id += 1;
+        transport.send(&self.name, BROKER_ADDRESS, QueueMessage::Publish { message_id, payload: payload.to_string() });
+        message_id
+    }
+}
+
+/// 消费者 - 处理收到的消息并确认
+pub struct QueueConsumer {
+    name: String,
+    /// 按处理顺序记录的消息，重复投递的消息会出现多次
+    pub processed: Vec<(u64, String)>,
+}
+
+impl QueueConsumer {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), processed: Vec::new() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn poll(&mut self, transport: &dyn Transport<QueueMessage>) {
+        while let Some(envelope) = transport.receive(&self.name) {
+            if let QueueMessage::Deliver { message_id, payload } = envelope.message {
+                self.processed.push((message_id, payload));
+                transport.send(&self.name, &envelope.from, QueueMessage::Ack { message_id });
+            }
+        }
+    }
+}
+
+/// Message Queue模式演示
+pub fn demo_message_queue() {
+    println!("=== Message Queue模式演示 ===\n");
+
+    // 20%丢包的模拟网络，确认丢失的消息会被重新投递
+    let network = SimNetwork::new(SimNetworkConfig { drop_rate: 0.2, ..SimNetworkConfig::default() }, 2024);
+    let mut broker = MessageBroker::new(20).with_publish_dedup(500, 100);
+    let mut consumers = vec![QueueConsumer::new("消费者A"), QueueConsumer::new("消费者B")];
+    for consumer in &consumers {
+        broker.subscribe(consumer.name());
+    }
+
+    let mut producer = QueueProducer::new("订单服务", 1);
+    for i in 1..=6 {
+        producer.publish(&network, &format!("订单{}", i));
+    }
+
+    for _ in 0..200 {
+        network.tick();
+        broker.poll(&network);
+        for consumer in &mut consumers {
+            consumer.poll(&network);
+        }
+    }
+
+    for consumer in &consumers {
+        println!("{} 处理了 {} 条消息: {:?}", consumer.name(), consumer.processed.len(),
+                 consumer.processed.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+    }
+    println!("重新投递次数: {}, 未确认: {}", broker.redelivery_count(), broker.unacked_count());
+    println!("发布去重记录: {}", broker.published_id_count());
+    println!("网络统计: {:?}", network.stats());
+
+    println!("\n【Message Queue模式特点】");
+    println!("✓ 异步解耦 - 生产者和消费者通过代理通信");
+    println!("✓ 负载分担 - 多个消费者轮询分发");
+    println!("✓ 至少一次 - 未确认的消息超时后重新投递");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lost_ack_causes_redelivery() {
+        let network = SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 1, ..SimNetworkConfig::default() }, 5);
+        let mut broker = MessageBroker::new(10);
+        let mut consumer = QueueConsumer::new("worker");
+        broker.subscribe("worker");
+        let mut producer = QueueProducer::new("producer", 100);
+        let first = producer.publish(&network, "a");
+        let second = producer.publish(&network, "b");
+
+        let run = |ticks: usize, broker: &mut MessageBroker, consumer: &mut QueueConsumer| {
+            for _ in 0..ticks {
+                network.tick();
+                broker.poll(&network);
+                consumer.poll(&network);
+            }
+        };
+
+        // 确认全部丢失：超时后重新投递，消费者看到重复消息
+        network.block_link("worker", BROKER_ADDRESS);
+        run(15, &mut broker, &mut consumer);
+        assert_eq!(broker.unacked_count(), 2);
+        assert_eq!(broker.redelivery_count(), 2);
+        let times_processed = |consumer: &QueueConsumer, id: u64| consumer.processed.iter().filter(|(processed, _)| *processed == id).count();
+        assert_eq!((times_processed(&consumer, first), times_processed(&consumer, second)), (2, 2));
+
+        // 链路恢复后下一次重新投递的确认送达，消息不再重复
+        network.unblock_link("worker", BROKER_ADDRESS);
+        run(30, &mut broker, &mut consumer);
+        assert_eq!(broker.unacked_count(), 0);
+        assert_eq!(broker.queued_count(), 0);
+        assert_eq!((times_processed(&consumer, first), times_processed(&consumer, second)), (3, 3));
+    }
+
+    #[test]
+    fn test_publish_dedup_records_are_bounded() {
+        let network = SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 1, ..SimNetworkConfig::default() }, 5);
+        let mut broker = MessageBroker::new(10).with_publish_dedup(20, 3);
+        let publish = |message_id: u64| {
+            network.send("producer", BROKER_ADDRESS, QueueMessage::Publish { message_id, payload: format!("m{}", message_id) });
+        };
+        let settle = |broker: &mut MessageBroker| {
+            network.tick();
+            broker.poll(&network);
+        };
+
+        // 超出容量时淘汰最早的编号
+        for message_id in 1..=5 {
+            publish(message_id);
+        }
+        settle(&mut broker);
+        assert_eq!(broker.published_id_count(), 3);
+        assert_eq!(broker.queued_count(), 5);
+
+        // 仍在记录中的编号被识别为重复，被淘汰的编号重新入队
+        publish(5);
+        publish(1);
+        settle(&mut broker);
+        assert_eq!(broker.duplicate_publish_count(), 1);
+        assert_eq!(broker.queued_count(), 6);
+
+        // 超过TTL后记录全部过期
+        for _ in 0..25 {
+            settle(&mut broker);
+        }
+        publish(5);
+        settle(&mut broker);
+        assert_eq!(broker.duplicate_publish_count(), 1);
+        assert_eq!(broker.published_id_count(), 1);
+    }
+}
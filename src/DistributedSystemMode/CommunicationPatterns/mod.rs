@@ -5,25 +5,30 @@
  */
 
 pub mod api_gateway;
+pub mod sim_network;
+pub mod service_mesh;
+pub mod message_queue;
+pub mod idempotent_consumer;
 
 // 其他模式的存根实现
-pub mod service_mesh {
-    pub fn demo_service_mesh() {
-        println!("=== Service Mesh模式演示 ===");
-        println!("服务网格处理服务间通信的基础设施层");
-    }
-}
-
-pub mod message_queue {
-    pub fn demo_message_queue() {
-        println!("=== Message Queue模式演示 ===");
-        println!("异步消息传递，解耦生产者和消费者");
-    }
-}
-
 pub mod publish_subscribe {
+    use crate::ConcurrentMode::broadcast_channel::{BroadcastChannel, OverflowPolicy};
+
     pub fn demo_publish_subscribe() {
         println!("=== Publish-Subscribe模式演示 ===");
         println!("发布订阅模式支持一对多的消息传递");
+
+        // 每个订阅者独立缓冲：统计服务可以丢弃旧事件，账务服务要求背压
+        let topic = BroadcastChannel::new();
+        let analytics = topic.subscribe(2, OverflowPolicy::DropOldest);
+        let billing = topic.subscribe(8, OverflowPolicy::Block);
+        for order in ["订单1已创建", "订单2已创建", "订单1已支付"] {
+            let _ = topic.send(order.to_string());
+        }
+        topic.close();
+        println!("统计服务丢弃 {} 条旧事件", analytics.dropped_count());
+        while let Ok(event) = billing.recv() {
+            println!("账务服务收到: {}", event);
+        }
     }
 } 
\ No newline at end of file
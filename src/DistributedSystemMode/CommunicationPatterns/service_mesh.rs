@@ -0,0 +1,224 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/CommunicationPatterns/service_mesh.rs
+ *
+ * Service Mesh模式 (服务网格)
+ *
+ * 服务网格把服务间通信的横切关注点（超时、重试、指标）从业务代码中剥离，
+ * 交给与每个服务部署在一起的边车代理（Sidecar）处理。业务代码只发起调用，
+ * 边车负责把请求送到目标服务并在失败时重试。
+ *
+ * 主要特点：
+ * 1. 边车代理 - 通信策略集中在代理中，业务代码无需关心
+ * 2. 超时与重试 - 请求超时或返回5xx时自动重试
+ * 3. 迟到响应过滤 - 按请求编号匹配响应，丢弃之前尝试的迟到响应
+ * 4. 调用指标 - 边车统计请求、重试、超时和失败次数
+ *
+ * 实现说明：
+ * - 边车与服务实例通过 Transport 通信，配合 SimNetwork 可以复现网络故障
+ * - 单线程驱动：等待响应期间推进传输层时钟并让服务实例处理请求
+ */
+
+use std::fmt;
+
+use super::sim_network::{SimNetwork, SimNetworkConfig, Transport};
+
+/// 网格内的消息
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshMessage {
+    Request { request_id: u64, path: String },
+    Response { request_id: u64, status: u16, body: String },
+}
+
+/// 网格调用错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshError {
+    /// 所有尝试都没有在超时前收到响应
+    Timeout { attempts: u32 },
+    /// 服务返回了不可重试的错误状态，或重试后仍返回5xx
+    Status { status: u16, body: String },
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::Timeout { attempts } => write!(f, "请求在 {} 次尝试后仍然超时", attempts),
+            MeshError::Status { status, body } => write!(f, "服务返回错误 {}: {}", status, body),
+        }
+    }
+}
+
+/// 请求处理函数，返回 (状态码, 响应体)
+pub type RequestHandler = Box<dyn Fn(&str) -> (u16, String) + Send + Sync>;
+
+/// 服务实例 - 处理收到的请求并回复
+pub struct ServiceInstance {
+    name: String,
+    handler: RequestHandler,
+}
+
+impl ServiceInstance {
+    pub fn new<F>(name: &str, handler: F) -> Self
+    where
+        F: Fn(&str) -> (u16, String) + Send + Sync + 'static,
+    {
+        Self { name: name.to_string(), handler: Box::new(handler) }
+    }
+
+    pub fn poll(&self, transport: &dyn Transport<MeshMessage>) {
+        while let Some(envelope) = transport.receive(&self.name) {
+            if let MeshMessage::Request { request_id, path } = envelope.message {
+                let (status, body) = (self.handler)(&path);
+                transport.send(&self.name, &envelope.from, MeshMessage::Response { request_id, status, body });
+            }
+        }
+    }
+}
+
+/// 边车指标
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SidecarMetrics {
+    pub requests: u64,
+    pub retries: u64,
+    pub timeouts: u64,
+    pub failures: u64,
+}
+
+/// 边车代理
+pub struct Sidecar {
+    address: String,
+    timeout: u64,
+    max_attempts: u32,
+    next_request_id: u64,
+    metrics: SidecarMetrics,
+}
+
+impl Sidecar {
+    /// `timeout` 为每次尝试等待响应的时长，`max_attempts` 包含首次请求
+    pub fn new(service: &str, timeout: u64, max_attempts: u32) -> Self {
+        Self {
+            address: format!("{}-sidecar", service),
+            timeout,
+            max_attempts: max_attempts.max(1),
+            next_request_id: 1,
+            metrics: SidecarMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &SidecarMetrics {
+        &self.metrics
+    }
+
+    /// 调用目标服务，超时或5xx时重试
+    pub fn call(
+        &mut self,
+        transport: &dyn Transport<MeshMessage>,
+        target: &str,
+        path: &str,
+        instances: &[ServiceInstance],
+    ) -> Result<String, MeshError> {
+        self.metrics.requests += 1;
+        let mut last_error = MeshError::Timeout { attempts: 0 };
+
+        for attempt in 1..=self.max_attempts {
+            if attempt > 1 {
+                self.metrics.retries += 1;
+            }
+            let request_id = self.next_request_id;
+            self.next_request_id += 1;
+            transport.send(&self.address, target, MeshMessage::Request { request_id, path: path.to_string() });
+
+            match self.await_response(transport, request_id, instances) {
+                Some((status, body)) if status < 500 => {
+                    if status >= 400 {
+                        self.metrics.failures += 1;
+                        return Err(MeshError::Status { status, body });
+                    }
+                    return Ok(body);
+                }
+                Some((status, body)) => last_error = MeshError::Status { status, body },
+                None => {
+                    self.metrics.timeouts += 1;
+                    last_error = MeshError::Timeout { attempts: attempt };
+                }
+            }
+        }
+
+        self.metrics.failures += 1;
+        Err(last_error)
+    }
+
+    fn await_response(
+        &self,
+        transport: &dyn Transport<MeshMessage>,
+        request_id: u64,
+        instances: &[ServiceInstance],
+    ) -> Option<(u16, String)> {
+        let deadline = transport.now() + self.timeout;
+        while transport.now() < deadline {
+            transport.tick();
+            for instance in instances {
+                instance.poll(transport);
+            }
+            while let Some(envelope) = transport.receive(&self.address) {
+                match envelope.message {
+                    MeshMessage::Response { request_id: id, status, body } if id == request_id => return Some((status, body)),
+                    // 之前尝试的迟到响应
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Service Mesh模式演示
+pub fn demo_service_mesh() {
+    println!("=== Service Mesh模式演示 ===\n");
+
+    let network = SimNetwork::new(SimNetworkConfig { drop_rate: 0.3, ..SimNetworkConfig::default() }, 7);
+    let inventory = ServiceInstance::new("inventory", |path| (200, format!("{} 有货", path)));
+    let mut sidecar = Sidecar::new("orders", 15, 4);
+
+    for item in ["item-1", "item-2", "item-3", "item-4"] {
+        match sidecar.call(&network, "inventory", item, std::slice::from_ref(&inventory)) {
+            Ok(body) => println!("调用成功: {}", body),
+            Err(e) => println!("调用失败: {}", e),
+        }
+    }
+    println!("边车指标: {:?}", sidecar.metrics());
+
+    println!("\n【Service Mesh模式特点】");
+    println!("✓ 边车代理 - 通信策略与业务代码分离");
+    println!("✓ 自动重试 - 超时和5xx错误自动重试");
+    println!("✓ 调用指标 - 统一收集服务间调用的统计数据");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_sidecar_retries_server_errors_and_timeouts() {
+        let network = SimNetwork::new(SimNetworkConfig::default(), 1);
+        let calls = std::sync::Arc::new(AtomicU32::new(0));
+        let counter = std::sync::Arc::clone(&calls);
+        // 第一次调用返回503，之后恢复正常
+        let flaky = ServiceInstance::new("payments", move |path| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                (503, "暂时不可用".to_string())
+            } else {
+                (200, format!("paid {}", path))
+            }
+        });
+
+        let mut sidecar = Sidecar::new("orders", 20, 3);
+        assert_eq!(sidecar.call(&network, "payments", "order-1", std::slice::from_ref(&flaky)), Ok("paid order-1".to_string()));
+        assert_eq!(sidecar.metrics(), &SidecarMetrics { requests: 1, retries: 1, timeouts: 0, failures: 0 });
+
+        // 链路中断时所有尝试都超时
+        network.block_link("orders-sidecar", "payments");
+        assert_eq!(sidecar.call(&network, "payments", "order-2", std::slice::from_ref(&flaky)), Err(MeshError::Timeout { attempts: 3 }));
+        assert_eq!(sidecar.metrics(), &SidecarMetrics { requests: 2, retries: 3, timeouts: 3, failures: 1 });
+    }
+}
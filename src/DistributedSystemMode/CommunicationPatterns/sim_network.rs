@@ -0,0 +1,309 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/CommunicationPatterns/sim_network.rs
+ *
+ * 确定性网络模拟 (Deterministic Network Simulation)
+ *
+ * 分布式交互中的延迟、乱序和丢包很难在真实网络上复现。SimNetwork 用虚拟时钟
+ * 和固定种子的随机数生成器模拟节点之间的消息传递，同样的种子和同样的操作序列
+ * 总是得到同样的结果，便于编写可重复的测试。
+ *
+ * 主要特点：
+ * 1. 统一的Transport特质 - 消息队列、服务网格、两阶段提交的演示共用同一套接口
+ * 2. 虚拟时钟 - 时间只在调用 tick/advance 时前进，不依赖真实时间
+ * 3. 故障注入 - 可配置延迟范围、丢包率、乱序率，以及阻断指定链路
+ * 4. 可复现 - 随机决策全部来自带种子的RNG，trace记录每条消息的去向
+ *
+ * 实现说明：
+ * - 在途消息按 (送达时间, 发送序号) 排序，送达时间相同时保持发送顺序
+ * - 乱序通过给部分消息额外增加延迟实现
+ * - LocalTransport 是零延迟的进程内实现，用于不需要故障注入的场景
+ */
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// =================
+// 传输层接口
+// =================
+
+/// 网络中传递的消息信封
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope<M> {
+    pub from: String,
+    pub to: String,
+    pub message: M,
+    /// 发送时的时钟（毫秒）
+    pub sent_at: u64,
+}
+
+/// 节点间传输层
+pub trait Transport<M>: Send + Sync {
+    /// 发送消息，传输层可能延迟、乱序或丢弃它
+    fn send(&self, from: &str, to: &str, message: M);
+    /// 取出节点收件箱中的下一条消息
+    fn receive(&self, node: &str) -> Option<Envelope<M>>;
+    /// 传输层的当前时钟（毫秒）
+    fn now(&self) -> u64;
+    /// 让时间前进一步，使在途消息有机会送达
+    fn tick(&self);
+}
+
+// =================
+// 模拟网络
+// =================
+
+/// 模拟网络配置，延迟单位为虚拟毫秒
+#[derive(Debug, Clone)]
+pub struct SimNetworkConfig {
+    pub min_latency: u64,
+    pub max_latency: u64,
+    /// 丢包率 (0.0 - 1.0)
+    pub drop_rate: f64,
+    /// 乱序率 (0.0 - 1.0)，命中的消息额外延迟 `reorder_delay`
+    pub reorder_rate: f64,
+    pub reorder_delay: u64,
+}
+
+impl Default for SimNetworkConfig {
+    fn default() -> Self {
+        Self {
+            min_latency: 1,
+            max_latency: 5,
+            drop_rate: 0.0,
+            reorder_rate: 0.0,
+            reorder_delay: 10,
+        }
+    }
+}
+
+/// 模拟网络统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimNetworkStats {
+    pub sent: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+}
+
+struct SimState<M> {
+    config: SimNetworkConfig,
+    rng: StdRng,
+    now: u64,
+    next_sequence: u64,
+    in_flight: BTreeMap<(u64, u64), Envelope<M>>,
+    inboxes: HashMap<String, VecDeque<Envelope<M>>>,
+    blocked_links: HashSet<(String, String)>,
+    stats: SimNetworkStats,
+    trace: Vec<String>,
+}
+
+/// 确定性模拟网络
+pub struct SimNetwork<M> {
+    state: Mutex<SimState<M>>,
+}
+
+impl<M> SimNetwork<M> {
+    /// 创建模拟网络
+    ///
+    /// `drop_rate` 和 `reorder_rate` 必须在 [0, 1] 之间，否则直接 panic，
+    /// 而不是等到第一次发送消息时才失败。
+    pub fn new(config: SimNetworkConfig, seed: u64) -> Self {
+        for (name, rate) in [("drop_rate", config.drop_rate), ("reorder_rate", config.reorder_rate)] {
+            assert!((0.0..=1.0).contains(&rate), "SimNetworkConfig::{} 必须在 [0, 1] 之间，实际为 {}", name, rate);
+        }
+        Self {
+            state: Mutex::new(SimState {
+                config,
+                rng: StdRng::seed_from_u64(seed),
+                now: 0,
+                next_sequence: 0,
+                in_flight: BTreeMap::new(),
+                inboxes: HashMap::new(),
+                blocked_links: HashSet::new(),
+                stats: SimNetworkStats::default(),
+                trace: Vec::new(),
+            }),
+        }
+    }
+
+    /// 阻断从 `from` 到 `to` 的单向链路，之后的消息全部丢弃
+    pub fn block_link(&self, from: &str, to: &str) {
+        self.state.lock().unwrap().blocked_links.insert((from.to_string(), to.to_string()));
+    }
+
+    pub fn unblock_link(&self, from: &str, to: &str) {
+        self.state.lock().unwrap().blocked_links.remove(&(from.to_string(), to.to_string()));
+    }
+
+    /// 让虚拟时钟前进 `millis` 毫秒，送达到期的消息
+    pub fn advance(&self, millis: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.now += millis;
+        let now = state.now;
+
+        // BTreeMap按 (送达时间, 序号) 有序，取出所有已到期的消息
+        let pending = state.in_flight.split_off(&(now + 1, 0));
+        let due = std::mem::replace(&mut state.in_flight, pending);
+        for ((deliver_at, sequence), envelope) in due {
+            state.trace.push(format!("t={} 送达 #{} {} -> {}", deliver_at, sequence, envelope.from, envelope.to));
+            state.stats.delivered += 1;
+            state.inboxes.entry(envelope.to.clone()).or_default().push_back(envelope);
+        }
+    }
+
+    pub fn stats(&self) -> SimNetworkStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    pub fn in_flight_count(&self) -> usize {
+        self.state.lock().unwrap().in_flight.len()
+    }
+
+    /// 每条消息的去向记录，相同种子下两次运行的记录完全一致
+    pub fn trace(&self) -> Vec<String> {
+        self.state.lock().unwrap().trace.clone()
+    }
+}
+
+impl<M: Send> Transport<M> for SimNetwork<M> {
+    fn send(&self, from: &str, to: &str, message: M) {
+        let mut state = self.state.lock().unwrap();
+        let sequence = state.next_sequence;
+        state.next_sequence += 1;
+        state.stats.sent += 1;
+
+        // 每条消息总是消耗同样多的随机数，阻断链路不会改变其他消息的命运
+        let config = state.config.clone();
+        let dropped_randomly = state.rng.gen_bool(config.drop_rate);
+        let mut latency = state.rng.gen_range(config.min_latency..=config.max_latency.max(config.min_latency));
+        if state.rng.gen_bool(config.reorder_rate) {
+            latency += config.reorder_delay;
+        }
+
+        if dropped_randomly || state.blocked_links.contains(&(from.to_string(), to.to_string())) {
+            state.stats.dropped += 1;
+            let now = state.now;
+            state.trace.push(format!("t={} 丢弃 #{} {} -> {}", now, sequence, from, to));
+            return;
+        }
+
+        let envelope = Envelope { from: from.to_string(), to: to.to_string(), message, sent_at: state.now };
+        let deliver_at = state.now + latency;
+        state.in_flight.insert((deliver_at, sequence), envelope);
+    }
+
+    fn receive(&self, node: &str) -> Option<Envelope<M>> {
+        self.state.lock().unwrap().inboxes.get_mut(node)?.pop_front()
+    }
+
+    fn now(&self) -> u64 {
+        self.state.lock().unwrap().now
+    }
+
+    fn tick(&self) {
+        self.advance(1);
+    }
+}
+
+// =================
+// 进程内传输
+// =================
+
+/// 零延迟的进程内传输，消息立即进入收件箱
+pub struct LocalTransport<M> {
+    inboxes: Mutex<HashMap<String, VecDeque<Envelope<M>>>>,
+    started: Instant,
+}
+
+impl<M> LocalTransport<M> {
+    pub fn new() -> Self {
+        Self { inboxes: Mutex::new(HashMap::new()), started: Instant::now() }
+    }
+}
+
+impl<M> Default for LocalTransport<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Send> Transport<M> for LocalTransport<M> {
+    fn send(&self, from: &str, to: &str, message: M) {
+        let envelope = Envelope { from: from.to_string(), to: to.to_string(), message, sent_at: self.now() };
+        self.inboxes.lock().unwrap().entry(to.to_string()).or_default().push_back(envelope);
+    }
+
+    fn receive(&self, node: &str) -> Option<Envelope<M>> {
+        self.inboxes.lock().unwrap().get_mut(node)?.pop_front()
+    }
+
+    fn now(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn tick(&self) {
+        std::thread::yield_now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(network: &SimNetwork<u32>, node: &str) -> Vec<u32> {
+        std::iter::from_fn(|| network.receive(node)).map(|envelope| envelope.message).collect()
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_delivery_order() {
+        let run = |seed| {
+            let network = SimNetwork::new(SimNetworkConfig { reorder_rate: 0.3, drop_rate: 0.2, ..SimNetworkConfig::default() }, seed);
+            for i in 0..20 {
+                network.send("a", "b", i);
+            }
+            network.advance(100);
+            (drain(&network, "b"), network.stats(), network.trace())
+        };
+
+        let (first, stats, trace) = run(7);
+        assert_eq!(run(7), (first.clone(), stats.clone(), trace));
+        assert_eq!(stats.sent, 20);
+        assert_eq!(stats.delivered + stats.dropped, 20);
+        // 乱序：送达顺序与发送顺序不同
+        assert_ne!(first, {
+            let mut sorted = first.clone();
+            sorted.sort();
+            sorted
+        });
+    }
+
+    #[test]
+    fn test_latency_and_blocked_link() {
+        let network = SimNetwork::new(SimNetworkConfig { min_latency: 3, max_latency: 3, ..SimNetworkConfig::default() }, 1);
+        network.block_link("a", "c");
+        network.send("a", "b", 1);
+        network.send("a", "c", 2);
+
+        network.advance(2);
+        assert_eq!(network.receive("b"), None);
+        network.tick();
+        assert_eq!(network.receive("b").map(|envelope| (envelope.message, envelope.sent_at)), Some((1, 0)));
+        assert_eq!(network.receive("c"), None);
+        assert_eq!(network.stats(), SimNetworkStats { sent: 2, delivered: 1, dropped: 1 });
+    }
+
+    #[test]
+    #[should_panic(expected = "SimNetworkConfig::drop_rate 必须在 [0, 1] 之间")]
+    fn test_out_of_range_drop_rate_is_rejected_on_construction() {
+        SimNetwork::<u32>::new(SimNetworkConfig { drop_rate: 1.5, ..SimNetworkConfig::default() }, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "SimNetworkConfig::reorder_rate 必须在 [0, 1] 之间")]
+    fn test_nan_reorder_rate_is_rejected_on_construction() {
+        SimNetwork::<u32>::new(SimNetworkConfig { reorder_rate: f64::NAN, ..SimNetworkConfig::default() }, 1);
+    }
+}
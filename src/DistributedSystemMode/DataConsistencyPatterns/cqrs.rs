@@ -0,0 +1,450 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/DataConsistencyPatterns/cqrs.rs
+ *
+ * CQRS模式 (命令查询职责分离)
+ *
+ * 写端通过命令修改聚合并把事件追加到事件存储，读端由投影器异步订阅事件，
+ * 更新为查询优化的读模型。读写分离带来的代价是最终一致性：
+ * 读模型可能落后于写端。
+ *
+ * 主要特点：
+ * 1. 读写分离 - 写端校验命令并产生事件，读端只负责查询
+ * 2. 异步投影 - 投影器在后台把事件应用到各个读模型
+ * 3. 延迟可观测 - 记录每个读模型已应用的事件序号，计算与写端的差距
+ * 4. 读己之写 - 调用方可以等待读模型追上某个版本后再查询
+ *
+ * 实现说明：
+ * - 写端复用 event_sourcing 中的 EventStore 和 AccountAggregate
+ * - 版本使用事件存储的全局序号，命令处理返回其写入的最后一个序号
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::event_sourcing::{AccountAggregate, AccountEvent, EventStore, LoadedAggregate, StoredEvent};
+use crate::DistributedSystemMode::ResiliencePatterns::timeout::CancellationToken;
+
+// =================
+// 写端
+// =================
+
+/// 账户命令
+#[derive(Debug, Clone)]
+pub enum AccountCommand {
+    Open { account_id: String, owner: String },
+    Deposit { account_id: String, amount: u64 },
+    Withdraw { account_id: String, amount: u64 },
+}
+
+/// 命令处理错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    Rejected(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Rejected(reason) => write!(f, "命令被拒绝: {}", reason),
+        }
+    }
+}
+
+/// 命令处理器 - 写端入口
+pub struct AccountCommandHandler {
+    store: EventStore<AccountEvent>,
+}
+
+impl AccountCommandHandler {
+    pub fn new(store: EventStore<AccountEvent>) -> Self {
+        Self { store }
+    }
+
+    /// 处理命令，返回写入的最后一个事件的全局序号
+    ///
+    /// 聚合只加载一次：校验命令和乐观并发检查使用同一个版本，
+    /// 校验之后有并发写入时追加会因版本冲突被拒绝，而不是基于过期状态写入
+    pub fn handle(&self, command: AccountCommand) -> Result<u64, CommandError> {
+        let account_id = match &command {
+            AccountCommand::Open { account_id, .. }
+            | AccountCommand::Deposit { account_id, .. }
+            | AccountCommand::Withdraw { account_id, .. } => account_id.clone(),
+        };
+        let current: LoadedAggregate<AccountAggregate> = self.store.load(&account_id);
+
+        let events = match command {
+            AccountCommand::Open { owner, .. } => vec![AccountEvent::Opened { owner }],
+            _ if current.version == 0 => {
+                return Err(CommandError::Rejected(format!("账户 {} 不存在", account_id)));
+            }
+            AccountCommand::Deposit { amount, .. } => current.state.deposit(amount).map_err(CommandError::Rejected)?,
+            AccountCommand::Withdraw { amount, .. } => current.state.withdraw(amount).map_err(CommandError::Rejected)?,
+        };
+
+        let outcome = self.store
+            .append_with_sequence(&account_id, current.version, events)
+            .map_err(|e| CommandError::Rejected(e.to_string()))?;
+        Ok(outcome.sequence)
+    }
+}
+
+// =================
+// 读端
+// =================
+
+/// 投影 - 把事件应用到读模型
+pub trait Projection<E>: Send {
+    fn apply(&mut self, event: &StoredEvent<E>);
+}
+
+/// 账户余额读模型
+#[derive(Debug, Default)]
+pub struct AccountBalanceView {
+    balances: HashMap<String, u64>,
+}
+
+impl AccountBalanceView {
+    pub fn balance(&self, account_id: &str) -> Option<u64> {
+        self.balances.get(account_id).copied()
+    }
+}
+
+impl Projection<AccountEvent> for AccountBalanceView {
+    fn apply(&mut self, stored: &StoredEvent<AccountEvent>) {
+        let balance = self.balances.entry(stored.aggregate_id.clone()).or_insert(0);
+        match &stored.event {
+            AccountEvent::Opened { .. } => {}
+            AccountEvent::Deposited(amount) => *balance = balance.saturating_add(*amount),
+            AccountEvent::Withdrawn(amount) => *balance = balance.saturating_sub(*amount),
+        }
+    }
+}
+
+/// 交易流水读模型
+#[derive(Debug, Default)]
+pub struct TransactionLogView {
+    pub entries: Vec<String>,
+}
+
+impl Projection<AccountEvent> for TransactionLogView {
+    fn apply(&mut self, stored: &StoredEvent<AccountEvent>) {
+        self.entries.push(format!("#{} {}: {:?}", stored.sequence, stored.aggregate_id, stored.event));
+    }
+}
+
+struct ProjectionSlot<E> {
+    name: String,
+    projection: Arc<Mutex<dyn Projection<E>>>,
+    /// 已应用到该读模型的最后一个全局序号
+    applied: u64,
+}
+
+struct ProjectorShared<E> {
+    slots: Mutex<Vec<ProjectionSlot<E>>>,
+    progress: Condvar,
+    paused: AtomicBool,
+}
+
+/// 投影器 - 把事件存储中的新事件应用到已注册的读模型，并跟踪一致性延迟
+pub struct Projector<E> {
+    store: EventStore<E>,
+    shared: Arc<ProjectorShared<E>>,
+}
+
+impl<E> Clone for Projector<E> {
+    fn clone(&self) -> Self {
+        Self { store: self.store.clone(), shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<E: Clone + Send + 'static> Projector<E> {
+    pub fn new(store: EventStore<E>) -> Self {
+        Self {
+            store,
+            shared: Arc::new(ProjectorShared {
+                slots: Mutex::new(Vec::new()),
+                progress: Condvar::new(),
+                paused: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// 注册读模型，调用方保留自己的引用用于查询
+    pub fn register<P: Projection<E> + 'static>(&self, name: &str, projection: Arc<Mutex<P>>) {
+        self.shared.slots.lock().unwrap().push(ProjectionSlot {
+            name: name.to_string(),
+            projection,
+            applied: 0,
+        });
+    }
+
+    /// 把所有未应用的事件应用到各个读模型，暂停时不做任何事；返回应用的事件数
+    pub fn catch_up(&self) -> usize {
+        // 持有读模型锁时检查暂停标志，pause返回后不会再有事件被应用
+        let mut slots = self.shared.slots.lock().unwrap();
+        if self.is_paused() {
+            return 0;
+        }
+        let mut applied = 0;
+        for slot in slots.iter_mut() {
+            let pending = self.store.events_since(slot.applied);
+            if pending.is_empty() {
+                continue;
+            }
+            let mut projection = slot.projection.lock().unwrap();
+            for stored in pending {
+                projection.apply(&stored);
+                slot.applied = stored.sequence;
+                applied += 1;
+            }
+        }
+        drop(slots);
+
+        if applied > 0 {
+            self.shared.progress.notify_all();
+        }
+        applied
+    }
+
+    /// 在后台线程中定期追赶，直到令牌被取消
+    pub fn spawn(&self, poll_interval: Duration, token: CancellationToken) -> JoinHandle<()> {
+        let projector = self.clone();
+        thread::spawn(move || {
+            while !token.is_cancelled() {
+                projector.catch_up();
+                thread::sleep(poll_interval);
+            }
+        })
+    }
+
+    /// 暂停投影，等待正在进行的追赶结束后返回
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::SeqCst);
+        drop(self.shared.slots.lock().unwrap());
+    }
+
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::SeqCst)
+    }
+
+    /// 读模型已应用的最后一个事件序号，未注册时返回None
+    pub fn applied_version(&self, projection: &str) -> Option<u64> {
+        self.shared.slots.lock().unwrap()
+            .iter()
+            .find(|slot| slot.name == projection)
+            .map(|slot| slot.applied)
+    }
+
+    /// 读模型落后写端的事件数，未注册的读模型视为落后全部事件
+    pub fn lag(&self, projection: &str) -> u64 {
+        let applied = self.applied_version(projection).unwrap_or(0);
+        self.store.latest_sequence().saturating_sub(applied)
+    }
+
+    /// 等待读模型至少应用到 `target_version`，超时返回false
+    pub fn wait_for_consistency(&self, projection: &str, target_version: u64, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut slots = self.shared.slots.lock().unwrap();
+        loop {
+            let applied = slots.iter().find(|slot| slot.name == projection).map(|slot| slot.applied);
+            match applied {
+                Some(applied) if applied >= target_version => return true,
+                None => return false,
+                Some(_) => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            slots = self.shared.progress.wait_timeout(slots, deadline - now).unwrap().0;
+        }
+    }
+}
+
+/// CQRS模式演示
+pub fn demo_cqrs() {
+    println!("=== CQRS模式演示 ===\n");
+
+    let store = EventStore::new();
+    let handler = AccountCommandHandler::new(store.clone());
+    let projector = Projector::new(store.clone());
+    let balances = Arc::new(Mutex::new(AccountBalanceView::default()));
+    let transactions = Arc::new(Mutex::new(TransactionLogView::default()));
+    projector.register("balances", Arc::clone(&balances));
+    projector.register("transactions", Arc::clone(&transactions));
+
+    let token = CancellationToken::new();
+    let background = projector.spawn(Duration::from_millis(5), token.clone());
+
+    // 1. 写端处理命令
+    let commands = vec![
+        AccountCommand::Open { account_id: "acc-1".to_string(), owner: "张三".to_string() },
+        AccountCommand::Deposit { account_id: "acc-1".to_string(), amount: 300 },
+        AccountCommand::Withdraw { account_id: "acc-1".to_string(), amount: 500 },
+        AccountCommand::Withdraw { account_id: "acc-1".to_string(), amount: 100 },
+    ];
+    let mut last_version = 0;
+    for command in commands {
+        match handler.handle(command) {
+            Ok(version) => last_version = version,
+            Err(e) => println!("{}", e),
+        }
+    }
+    println!("写端最新版本: {}, 余额读模型延迟: {}", last_version, projector.lag("balances"));
+
+    // 2. 读己之写：等待读模型追上写端
+    if projector.wait_for_consistency("balances", last_version, Duration::from_secs(1)) {
+        println!("读模型余额: {:?}", balances.lock().unwrap().balance("acc-1"));
+    }
+
+    // 3. 暂停投影器，读模型开始落后
+    projector.pause();
+    let _ = handler.handle(AccountCommand::Deposit { account_id: "acc-1".to_string(), amount: 50 });
+    println!("暂停期间的延迟: {}", projector.lag("balances"));
+    projector.resume();
+    projector.wait_for_consistency("transactions", store.latest_sequence(), Duration::from_secs(1));
+    println!("恢复后的延迟: {}, 流水条数: {}", projector.lag("transactions"), transactions.lock().unwrap().entries.len());
+
+    token.cancel();
+    let _ = background.join();
+
+    println!("\n【CQRS模式特点】");
+    println!("✓ 读写分离 - 写端产生事件，读端维护查询模型");
+    println!("✓ 异步投影 - 读模型在后台追赶写端");
+    println!("✓ 延迟跟踪 - 每个读模型报告落后写端的事件数");
+    println!("✓ 读己之写 - 可等待读模型达到指定版本后再查询");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_and_deposit(handler: &AccountCommandHandler) -> u64 {
+        handler.handle(AccountCommand::Open { account_id: "acc-1".to_string(), owner: "李四".to_string() }).unwrap();
+        handler.handle(AccountCommand::Deposit { account_id: "acc-1".to_string(), amount: 100 }).unwrap()
+    }
+
+    #[test]
+    fn test_paused_projector_reports_lag_until_resumed() {
+        let store = EventStore::new();
+        let handler = AccountCommandHandler::new(store.clone());
+        let projector = Projector::new(store.clone());
+        let balances = Arc::new(Mutex::new(AccountBalanceView::default()));
+        projector.register("balances", Arc::clone(&balances));
+
+        let token = CancellationToken::new();
+        let background = projector.spawn(Duration::from_millis(1), token.clone());
+        let version = open_and_deposit(&handler);
+        assert!(projector.wait_for_consistency("balances", version, Duration::from_secs(5)));
+        assert_eq!(projector.lag("balances"), 0);
+
+        projector.pause();
+        handler.handle(AccountCommand::Deposit { account_id: "acc-1".to_string(), amount: 20 }).unwrap();
+        let target = handler.handle(AccountCommand::Withdraw { account_id: "acc-1".to_string(), amount: 50 }).unwrap();
+
+        assert_eq!(projector.lag("balances"), 2);
+        assert!(!projector.wait_for_consistency("balances", target, Duration::from_millis(30)));
+        assert_eq!(balances.lock().unwrap().balance("acc-1"), Some(100));
+
+        projector.resume();
+        assert!(projector.wait_for_consistency("balances", target, Duration::from_secs(5)));
+        assert_eq!(projector.lag("balances"), 0);
+        assert_eq!(balances.lock().unwrap().balance("acc-1"), Some(70));
+
+        token.cancel();
+        background.join().unwrap();
+    }
+
+    #[test]
+    fn test_lag_is_tracked_per_read_model() {
+        let store = EventStore::new();
+        let handler = AccountCommandHandler::new(store.clone());
+        let projector = Projector::new(store.clone());
+        projector.register("balances", Arc::new(Mutex::new(AccountBalanceView::default())));
+        open_and_deposit(&handler);
+        assert_eq!(projector.catch_up(), 2);
+
+        // 后注册的读模型需要从头追赶
+        let transactions = Arc::new(Mutex::new(TransactionLogView::default()));
+        projector.register("transactions", Arc::clone(&transactions));
+        assert_eq!(projector.lag("balances"), 0);
+        assert_eq!(projector.lag("transactions"), 2);
+        assert_eq!(projector.lag("unknown"), 2);
+
+        // 被拒绝的命令不产生事件
+        assert!(handler.handle(AccountCommand::Withdraw { account_id: "acc-1".to_string(), amount: 999 }).is_err());
+        assert_eq!(projector.catch_up(), 2);
+        assert_eq!(projector.applied_version("transactions"), Some(2));
+        assert_eq!(transactions.lock().unwrap().entries.len(), 2);
+    }
+
+    #[test]
+    fn test_handle_returns_sequence_of_its_own_event_under_concurrency() {
+        let store = EventStore::new();
+        let handler = Arc::new(AccountCommandHandler::new(store.clone()));
+        let accounts = ["acc-a", "acc-b", "acc-c", "acc-d"];
+        for account_id in accounts {
+            handler.handle(AccountCommand::Open { account_id: account_id.to_string(), owner: "并发".to_string() }).unwrap();
+        }
+
+        let workers: Vec<_> = accounts
+            .iter()
+            .map(|account_id| {
+                let handler = Arc::clone(&handler);
+                let account_id = account_id.to_string();
+                thread::spawn(move || {
+                    (0..50)
+                        .map(|_| {
+                            let command = AccountCommand::Deposit { account_id: account_id.clone(), amount: 1 };
+                            (account_id.clone(), handler.handle(command).unwrap())
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let results: Vec<(String, u64)> = workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect();
+
+        // 每个返回的序号都必须指向调用方自己写入的事件，而不是别人随后写入的
+        let all_events = store.events_since(0);
+        for (account_id, sequence) in results {
+            let stored = &all_events[(sequence - 1) as usize];
+            assert_eq!(stored.sequence, sequence);
+            assert_eq!(stored.aggregate_id, account_id, "序号 {} 不属于 {}", sequence, account_id);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_withdrawals_never_overdraw() {
+        let store = EventStore::new();
+        let handler = Arc::new(AccountCommandHandler::new(store.clone()));
+        handler.handle(AccountCommand::Open { account_id: "acc".to_string(), owner: "并发".to_string() }).unwrap();
+        handler.handle(AccountCommand::Deposit { account_id: "acc".to_string(), amount: 100 }).unwrap();
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let handler = Arc::clone(&handler);
+                thread::spawn(move || {
+                    (0..20)
+                        .filter(|_| handler.handle(AccountCommand::Withdraw { account_id: "acc".to_string(), amount: 30 }).is_ok())
+                        .count()
+                })
+            })
+            .collect();
+        let succeeded: usize = workers.into_iter().map(|worker| worker.join().unwrap()).sum();
+
+        // 余额100最多支持3次取款30，基于过期状态的取款必须被版本冲突拒绝
+        assert_eq!(succeeded, 3);
+        let current: LoadedAggregate<AccountAggregate> = store.load("acc");
+        assert_eq!(current.state.balance, 10);
+    }
+}
@@ -0,0 +1,377 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/DataConsistencyPatterns/event_sourcing.rs
+ *
+ * Event Sourcing模式 (事件溯源)
+ *
+ * 事件溯源不直接保存聚合的当前状态，而是保存导致状态变化的事件序列，
+ * 需要状态时从初始状态开始依次重放事件。
+ *
+ * 主要特点：
+ * 1. 完整历史 - 所有变化都以事件形式保留，天然支持审计
+ * 2. 历史查询 - 只重放到某个版本或时间点，即可得到当时的状态
+ * 3. 乐观并发 - 追加事件时校验期望版本，避免并发覆盖
+ * 4. 全局序号 - 每个事件带有全局序号，便于投影按顺序订阅
+ */
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+// =================
+// 事件与聚合
+// =================
+
+/// 已存储的事件
+#[derive(Debug, Clone)]
+pub struct StoredEvent<E> {
+    pub aggregate_id: String,
+    /// 聚合内的版本号，从1开始
+    pub version: u64,
+    /// 事件存储内的全局序号，从1开始
+    pub sequence: u64,
+    pub recorded_at: SystemTime,
+    pub event: E,
+}
+
+/// 聚合 - 通过重放事件来重建状态
+pub trait Aggregate: Default {
+    type Event;
+
+    fn apply(&mut self, event: &Self::Event);
+}
+
+/// 加载得到的聚合
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedAggregate<A> {
+    pub state: A,
+    /// 已重放到的版本
+    pub version: u64,
+    /// 请求的版本超过了最新版本，返回的是最新状态
+    pub clamped_to_latest: bool,
+}
+
+/// 一次追加的结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AppendOutcome {
+    /// 追加后聚合的版本
+    pub version: u64,
+    /// 本次追加的最后一个事件的全局序号，没有追加事件时为追加前的最新序号
+    pub sequence: u64,
+}
+
+/// 事件存储错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventStoreError {
+    /// 期望版本与实际版本不一致
+    ConcurrencyConflict { aggregate_id: String, expected: u64, actual: u64 },
+}
+
+impl fmt::Display for EventStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventStoreError::ConcurrencyConflict { aggregate_id, expected, actual } => {
+                write!(f, "聚合 {} 版本冲突: 期望 v{}，实际 v{}", aggregate_id, expected, actual)
+            }
+        }
+    }
+}
+
+// =================
+// 事件存储
+// =================
+
+/// 内存事件存储，克隆后共享同一份事件
+pub struct EventStore<E> {
+    events: Arc<Mutex<Vec<StoredEvent<E>>>>,
+}
+
+impl<E> Clone for EventStore<E> {
+    fn clone(&self) -> Self {
+        Self { events: Arc::clone(&self.events) }
+    }
+}
+
+impl<E> Default for EventStore<E> {
+    fn default() -> Self {
+        Self { events: Arc::new(Mutex::new(Vec::new())) }
+    }
+}
+
+impl<E: Clone> EventStore<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加事件，`expected_version` 为调用方读取聚合时看到的版本；返回追加后的版本
+    pub fn append(&self, aggregate_id: &str, expected_version: u64, new_events: Vec<E>) -> Result<u64, EventStoreError> {
+        self.append_with_sequence(aggregate_id, expected_version, new_events).map(|outcome| outcome.version)
+    }
+
+    /// 同 `append`，同时返回本次写入的全局序号；
+    /// 序号在持锁期间确定，不会混入其他并发写入
+    pub fn append_with_sequence(
+        &self,
+        aggregate_id: &str,
+        expected_version: u64,
+        new_events: Vec<E>,
+    ) -> Result<AppendOutcome, EventStoreError> {
+        let mut events = self.events.lock().unwrap();
+        let actual = events.iter().filter(|stored| stored.aggregate_id == aggregate_id).count() as u64;
+        if actual != expected_version {
+            return Err(EventStoreError::ConcurrencyConflict {
+                aggregate_id: aggregate_id.to_string(),
+                expected: expected_version,
+                actual,
+            });
+        }
+
+        let mut version = actual;
+        for event in new_events {
+            version += 1;
+            let sequence = events.len() as u64 + 1;
+            events.push(StoredEvent {
+                aggregate_id: aggregate_id.to_string(),
+                version,
+                sequence,
+                recorded_at: SystemTime::now(),
+                event,
+            });
+        }
+        Ok(AppendOutcome { version, sequence: events.len() as u64 })
+    }
+
+    /// 聚合的全部事件，按版本排序
+    pub fn events_for(&self, aggregate_id: &str) -> Vec<StoredEvent<E>> {
+        self.events.lock().unwrap()
+            .iter()
+            .filter(|stored| stored.aggregate_id == aggregate_id)
+            .cloned()
+            .collect()
+    }
+
+    /// 全局序号大于 `sequence` 的事件，供投影增量订阅
+    pub fn events_since(&self, sequence: u64) -> Vec<StoredEvent<E>> {
+        self.events.lock().unwrap()
+            .iter()
+            .filter(|stored| stored.sequence > sequence)
+            .cloned()
+            .collect()
+    }
+
+    /// 最新写入事件的全局序号，没有事件时为0
+    pub fn latest_sequence(&self) -> u64 {
+        self.events.lock().unwrap().len() as u64
+    }
+
+    /// 加载聚合的最新状态
+    pub fn load<A: Aggregate<Event = E>>(&self, aggregate_id: &str) -> LoadedAggregate<A> {
+        self.replay(aggregate_id, |_| true, false)
+    }
+
+    /// 加载聚合在指定版本时的状态
+    ///
+    /// 版本0返回初始状态；超过最新版本时返回最新状态并设置 `clamped_to_latest`。
+    pub fn load_at<A: Aggregate<Event = E>>(&self, aggregate_id: &str, version: u64) -> LoadedAggregate<A> {
+        let latest = self.events.lock().unwrap()
+            .iter()
+            .filter(|stored| stored.aggregate_id == aggregate_id)
+            .count() as u64;
+        self.replay(aggregate_id, |stored| stored.version <= version, version > latest)
+    }
+
+    /// 加载聚合在指定时间点时的状态，只重放该时间点及之前记录的事件
+    pub fn load_as_of<A: Aggregate<Event = E>>(&self, aggregate_id: &str, timestamp: SystemTime) -> LoadedAggregate<A> {
+        self.replay(aggregate_id, |stored| stored.recorded_at <= timestamp, false)
+    }
+
+    fn replay<A, P>(&self, aggregate_id: &str, include: P, clamped_to_latest: bool) -> LoadedAggregate<A>
+    where
+        A: Aggregate<Event = E>,
+        P: Fn(&StoredEvent<E>) -> bool,
+    {
+        let events = self.events.lock().unwrap();
+        let mut state = A::default();
+        let mut version = 0;
+        for stored in events.iter().filter(|stored| stored.aggregate_id == aggregate_id) {
+            // 事件按版本有序，遇到第一个不满足条件的事件即可停止
+            if !include(stored) {
+                break;
+            }
+            state.apply(&stored.event);
+            version = stored.version;
+        }
+        LoadedAggregate { state, version, clamped_to_latest }
+    }
+}
+
+// =================
+// 示例：银行账户聚合
+// =================
+
+/// 账户事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountEvent {
+    Opened { owner: String },
+    Deposited(u64),
+    Withdrawn(u64),
+}
+
+/// 账户聚合
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountAggregate {
+    pub owner: Option<String>,
+    pub balance: u64,
+    pub transaction_count: u32,
+}
+
+impl AccountAggregate {
+    /// 处理存款命令，余额会溢出时拒绝，不产生事件
+    pub fn deposit(&self, amount: u64) -> Result<Vec<AccountEvent>, String> {
+        if self.balance.checked_add(amount).is_none() {
+            return Err(format!("余额溢出: 当前 {}，存款 {}", self.balance, amount));
+        }
+        Ok(vec![AccountEvent::Deposited(amount)])
+    }
+
+    /// 处理取款命令，余额不足时拒绝，不产生事件
+    pub fn withdraw(&self, amount: u64) -> Result<Vec<AccountEvent>, String> {
+        if amount > self.balance {
+            return Err(format!("余额不足: 当前 {}，取款 {}", self.balance, amount));
+        }
+        Ok(vec![AccountEvent::Withdrawn(amount)])
+    }
+}
+
+impl Aggregate for AccountAggregate {
+    type Event = AccountEvent;
+
+    /// 事件是已发生的事实，重放时不能失败；校验在 `deposit`/`withdraw` 命令中完成，
+    /// 绕过命令直接追加的越界事件在这里按饱和运算处理，而不是溢出
+    fn apply(&mut self, event: &AccountEvent) {
+        match event {
+            AccountEvent::Opened { owner } => self.owner = Some(owner.clone()),
+            AccountEvent::Deposited(amount) => {
+                self.balance = self.balance.saturating_add(*amount);
+                self.transaction_count += 1;
+            }
+            AccountEvent::Withdrawn(amount) => {
+                self.balance = self.balance.saturating_sub(*amount);
+                self.transaction_count += 1;
+            }
+        }
+    }
+}
+
+/// Event Sourcing模式演示
+pub fn demo_event_sourcing() {
+    println!("=== Event Sourcing模式演示 ===\n");
+
+    let store = EventStore::new();
+    let version = store.append("acc-1", 0, vec![
+        AccountEvent::Opened { owner: "张三".to_string() },
+        AccountEvent::Deposited(500),
+    ]).unwrap();
+
+    // 命令基于当前状态做校验，产生新的事件
+    let current: LoadedAggregate<AccountAggregate> = store.load("acc-1");
+    match current.state.withdraw(200) {
+        Ok(events) => {
+            store.append("acc-1", version, events).unwrap();
+        }
+        Err(e) => println!("取款失败: {}", e),
+    }
+
+    // 使用过期的版本追加会发生冲突
+    if let Err(e) = store.append("acc-1", version, vec![AccountEvent::Deposited(1)]) {
+        println!("追加失败: {}", e);
+    }
+
+    let latest: LoadedAggregate<AccountAggregate> = store.load("acc-1");
+    println!("最新状态 (v{}): 余额 {}", latest.version, latest.state.balance);
+
+    // 历史查询
+    for version in 0..=4 {
+        let historical: LoadedAggregate<AccountAggregate> = store.load_at("acc-1", version);
+        println!("  v{} 时的余额: {}{}", version, historical.state.balance,
+                 if historical.clamped_to_latest { " (已是最新版本)" } else { "" });
+    }
+
+    for stored in store.events_for("acc-1") {
+        println!("  事件 #{} v{}: {:?}", stored.sequence, stored.version, stored.event);
+    }
+
+    println!("\n【Event Sourcing模式特点】");
+    println!("✓ 完整历史 - 所有状态变化都以事件形式保留");
+    println!("✓ 历史查询 - 重放到指定版本或时间点得到当时的状态");
+    println!("✓ 乐观并发 - 追加时校验期望版本");
+    println!("✓ 全局序号 - 投影可以按序号增量订阅事件");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_store() -> EventStore<AccountEvent> {
+        let store = EventStore::new();
+        store.append("acc-1", 0, vec![
+            AccountEvent::Opened { owner: "李四".to_string() },
+            AccountEvent::Deposited(100),
+            AccountEvent::Deposited(50),
+            AccountEvent::Withdrawn(30),
+        ]).unwrap();
+        store.append("acc-2", 0, vec![AccountEvent::Opened { owner: "王五".to_string() }]).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_load_at_intermediate_version() {
+        let store = account_store();
+
+        let historical: LoadedAggregate<AccountAggregate> = store.load_at("acc-1", 2);
+        assert_eq!(historical.version, 2);
+        assert!(!historical.clamped_to_latest);
+        assert_eq!(historical.state, AccountAggregate { owner: Some("李四".to_string()), balance: 100, transaction_count: 1 });
+
+        let latest: LoadedAggregate<AccountAggregate> = store.load("acc-1");
+        assert_eq!((latest.version, latest.state.balance), (4, 120));
+
+        // 按时间点查询：任何事件之前是初始状态，当前时间点是最新状态
+        let before_all: LoadedAggregate<AccountAggregate> = store.load_as_of("acc-1", std::time::UNIX_EPOCH);
+        assert_eq!(before_all.version, 0);
+        let now: LoadedAggregate<AccountAggregate> = store.load_as_of("acc-1", SystemTime::now());
+        assert_eq!(now, latest);
+    }
+
+    #[test]
+    fn test_load_at_version_zero_and_beyond_latest() {
+        let store = account_store();
+
+        let initial: LoadedAggregate<AccountAggregate> = store.load_at("acc-1", 0);
+        assert_eq!(initial, LoadedAggregate { state: AccountAggregate::default(), version: 0, clamped_to_latest: false });
+
+        let beyond: LoadedAggregate<AccountAggregate> = store.load_at("acc-1", 99);
+        assert!(beyond.clamped_to_latest);
+        assert_eq!((beyond.version, beyond.state.balance), (4, 120));
+
+        // 过期版本追加失败，不会写入事件
+        assert_eq!(
+            store.append("acc-1", 2, vec![AccountEvent::Deposited(1)]),
+            Err(EventStoreError::ConcurrencyConflict { aggregate_id: "acc-1".to_string(), expected: 2, actual: 4 })
+        );
+        assert_eq!(store.latest_sequence(), 5);
+    }
+
+    #[test]
+    fn test_commands_reject_out_of_range_amounts_and_replay_never_overflows() {
+        let store = account_store();
+        let latest: LoadedAggregate<AccountAggregate> = store.load("acc-1");
+        assert!(latest.state.withdraw(121).is_err());
+        assert!(latest.state.deposit(u64::MAX).is_err());
+        assert_eq!(latest.state.deposit(5), Ok(vec![AccountEvent::Deposited(5)]));
+
+        // 绕过命令直接追加的越界事件，重放时不会溢出
+        store.append("acc-2", 1, vec![AccountEvent::Withdrawn(10), AccountEvent::Deposited(u64::MAX), AccountEvent::Deposited(1)]).unwrap();
+        let corrupted: LoadedAggregate<AccountAggregate> = store.load("acc-2");
+        assert_eq!(corrupted.state.balance, u64::MAX);
+    }
+}
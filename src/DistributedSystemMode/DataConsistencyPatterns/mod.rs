@@ -7,23 +7,10 @@
 pub mod saga_pattern;
 
 // 其他模式的存根实现
-pub mod two_phase_commit {
-    pub fn demo_two_phase_commit() {
-        println!("=== Two Phase Commit模式演示 ===");
-        println!("两阶段提交协议确保分布式事务的ACID特性");
-    }
-}
+pub mod two_phase_commit;
 
-pub mod event_sourcing {
-    pub fn demo_event_sourcing() {
-        println!("=== Event Sourcing模式演示 ===");
-        println!("通过存储事件序列来重建应用程序状态");
-    }
-}
+pub mod event_sourcing;
 
-pub mod cqrs {
-    pub fn demo_cqrs() {
-        println!("=== CQRS模式演示 ===");
-        println!("命令查询职责分离，分别优化读写操作");
-    }
-} 
\ No newline at end of file
+pub mod cqrs;
+
+pub mod transactional_outbox;
@@ -9,6 +9,10 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::DistributedSystemMode::ResiliencePatterns::timeout::{CancellationToken, TimeoutPolicy};
 
 #[derive(Debug, Clone)]
 pub enum SagaStepResult {
@@ -20,6 +24,12 @@ pub trait SagaStep: Send + Sync {
     fn execute(&self) -> SagaStepResult;
     fn compensate(&self) -> SagaStepResult;
     fn get_name(&self) -> &str;
+
+    /// 可取消的执行入口，长时间运行的步骤应覆盖此方法并定期检查令牌；
+    /// 默认直接调用 `execute`
+    fn execute_cancellable(&self, _token: &CancellationToken) -> SagaStepResult {
+        self.execute()
+    }
 }
 
 pub struct OrderCreationStep {
@@ -48,9 +58,69 @@ impl SagaStep for OrderCreationStep {
     }
 }
 
+/// 步骤在一次Saga执行中的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SagaStepState {
+    Pending,
+    Running,
+    Completed,
+    Compensated,
+    /// 执行失败，或补偿本身失败
+    Failed,
+}
+
+impl fmt::Display for SagaStepState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SagaStepState::Pending => "Pending",
+            SagaStepState::Running => "Running",
+            SagaStepState::Completed => "Completed",
+            SagaStepState::Compensated => "Compensated",
+            SagaStepState::Failed => "Failed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 单个步骤的状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct SagaStepStatus {
+    pub name: String,
+    pub state: SagaStepState,
+}
+
+/// Saga执行状态报告
+#[derive(Debug, Clone, PartialEq)]
+pub struct SagaStatusReport {
+    pub steps: Vec<SagaStepStatus>,
+}
+
+impl SagaStatusReport {
+    pub fn state_of(&self, name: &str) -> Option<SagaStepState> {
+        self.steps.iter().find(|status| status.name == name).map(|status| status.state)
+    }
+}
+
+impl fmt::Display for SagaStatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Saga状态报告:")?;
+        for (index, status) in self.steps.iter().enumerate() {
+            writeln!(f, "  {}. {} - {}", index + 1, status.name, status.state)?;
+        }
+        Ok(())
+    }
+}
+
+/// 编排器中的步骤条目，可选地附带超时策略
+struct SagaStepEntry {
+    step: Arc<dyn SagaStep>,
+    timeout: Option<TimeoutPolicy>,
+}
+
 pub struct SagaOrchestrator {
-    steps: Vec<Box<dyn SagaStep>>,
+    steps: Vec<SagaStepEntry>,
     executed_steps: Vec<usize>,
+    states: Vec<SagaStepState>,
 }
 
 impl SagaOrchestrator {
@@ -58,23 +128,39 @@ impl SagaOrchestrator {
         Self {
             steps: Vec::new(),
             executed_steps: Vec::new(),
+            states: Vec::new(),
         }
     }
     
     pub fn add_step(&mut self, step: Box<dyn SagaStep>) {
-        self.steps.push(step);
+        self.steps.push(SagaStepEntry { step: Arc::from(step), timeout: None });
+        self.states.push(SagaStepState::Pending);
+    }
+    
+    /// 添加带截止时间的步骤，超时视为步骤失败并触发补偿
+    pub fn add_step_with_timeout(&mut self, step: Box<dyn SagaStep>, timeout: Duration) {
+        self.steps.push(SagaStepEntry {
+            step: Arc::from(step),
+            timeout: Some(TimeoutPolicy::new(timeout)),
+        });
+        self.states.push(SagaStepState::Pending);
     }
     
     pub fn execute(&mut self) -> Result<(), String> {
         self.executed_steps.clear();
+        self.states.iter_mut().for_each(|state| *state = SagaStepState::Pending);
         
-        for (index, step) in self.steps.iter().enumerate() {
-            match step.execute() {
+        for index in 0..self.steps.len() {
+            self.states[index] = SagaStepState::Running;
+            let entry = &self.steps[index];
+            match Self::run_step(entry) {
                 SagaStepResult::Success => {
+                    self.states[index] = SagaStepState::Completed;
                     self.executed_steps.push(index);
                 }
                 SagaStepResult::Failure(error) => {
-                    println!("步骤 {} 失败: {}, 开始回滚", step.get_name(), error);
+                    self.states[index] = SagaStepState::Failed;
+                    println!("步骤 {} 失败: {}, 开始回滚", entry.step.get_name(), error);
                     self.compensate();
                     return Err(error);
                 }
@@ -84,14 +170,68 @@ impl SagaOrchestrator {
         Ok(())
     }
     
-    fn compensate(&self) {
+    /// 执行单个步骤；配置了超时的步骤在工作线程中运行，
+    /// 超时后通过取消令牌通知步骤停止
+    fn run_step(entry: &SagaStepEntry) -> SagaStepResult {
+        match &entry.timeout {
+            None => entry.step.execute_cancellable(&CancellationToken::new()),
+            Some(policy) => {
+                let step = Arc::clone(&entry.step);
+                match policy.execute(move |token| step.execute_cancellable(token)) {
+                    Ok(result) => result,
+                    Err(error) => SagaStepResult::Failure(format!(
+                        "步骤 {} 执行失败: {}", entry.step.get_name(), error
+                    )),
+                }
+            }
+        }
+    }
+    
+    fn compensate(&mut self) {
         for &index in self.executed_steps.iter().rev() {
-            if let Some(step) = self.steps.get(index) {
-                println!("补偿步骤: {}", step.get_name());
-                let _ = step.compensate();
+            if let Some(entry) = self.steps.get(index) {
+                println!("补偿步骤: {}", entry.step.get_name());
+                self.states[index] = match entry.step.compensate() {
+                    SagaStepResult::Success => SagaStepState::Compensated,
+                    SagaStepResult::Failure(_) => SagaStepState::Failed,
+                };
             }
         }
     }
+    
+    /// 最近一次执行中各步骤的状态
+    pub fn status_report(&self) -> SagaStatusReport {
+        SagaStatusReport {
+            steps: self.steps.iter()
+                .zip(&self.states)
+                .map(|(entry, state)| SagaStepStatus { name: entry.step.get_name().to_string(), state: *state })
+                .collect(),
+        }
+    }
+    
+    /// 生成Graphviz DOT图：实线为正向执行流，虚线为补偿流，节点颜色表示步骤状态
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph saga {\n    rankdir=LR;\n");
+        for (index, status) in self.status_report().steps.iter().enumerate() {
+            let color = match status.state {
+                SagaStepState::Pending => "gray",
+                SagaStepState::Running => "blue",
+                SagaStepState::Completed => "green",
+                SagaStepState::Compensated => "orange",
+                SagaStepState::Failed => "red",
+            };
+            dot.push_str(&format!(
+                "    step{} [label=\"{}\\n[{}]\", color={}];\n",
+                index, status.name.replace('"', "\\\""), status.state, color
+            ));
+        }
+        for index in 1..self.steps.len() {
+            dot.push_str(&format!("    step{} -> step{} [label=\"执行\"];\n", index - 1, index));
+            dot.push_str(&format!("    step{} -> step{} [style=dashed, label=\"补偿\"];\n", index, index - 1));
+        }
+        dot.push_str("}\n");
+        dot
+    }
 }
 
 /// Saga Pattern模式演示
@@ -105,10 +245,152 @@ pub fn demo_saga_pattern() {
         Ok(_) => println!("Saga执行成功"),
         Err(e) => println!("Saga执行失败: {}", e),
     }
+    print!("{}", saga.status_report());
+    println!("{}", saga.to_dot());
+    
+    // 带截止时间的步骤：即使步骤本身很快，也受超时保护
+    let mut timed_saga = SagaOrchestrator::new();
+    timed_saga.add_step_with_timeout(
+        Box::new(OrderCreationStep::new("order-456".to_string())),
+        Duration::from_millis(500),
+    );
+    match timed_saga.execute() {
+        Ok(_) => println!("带超时的Saga执行成功"),
+        Err(e) => println!("带超时的Saga执行失败: {}", e),
+    }
     
     println!("\n【Saga Pattern模式特点】");
     println!("✓ 分布式事务 - 通过本地事务序列实现分布式事务");
     println!("✓ 补偿机制 - 失败时自动执行补偿操作");
     println!("✓ 最终一致性 - 保证系统最终达到一致状态");
     println!("✓ 容错处理 - 优雅处理部分失败场景");
-} 
\ No newline at end of file
+    println!("✓ 步骤超时 - 挂起的步骤超过截止时间后视为失败");
+    println!("✓ 状态报告 - 列出各步骤状态并导出DOT流程图，便于调试");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::thread;
+
+    /// 记录执行与补偿情况的测试步骤
+    struct RecordingStep {
+        name: String,
+        delay: Duration,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RecordingStep {
+        fn new(name: &str, delay: Duration, log: &Arc<Mutex<Vec<String>>>) -> Box<Self> {
+            Box::new(Self { name: name.to_string(), delay, log: Arc::clone(log) })
+        }
+    }
+
+    impl SagaStep for RecordingStep {
+        fn execute(&self) -> SagaStepResult {
+            self.execute_cancellable(&CancellationToken::new())
+        }
+
+        fn compensate(&self) -> SagaStepResult {
+            self.log.lock().unwrap().push(format!("compensate:{}", self.name));
+            SagaStepResult::Success
+        }
+
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        fn execute_cancellable(&self, token: &CancellationToken) -> SagaStepResult {
+            let start = std::time::Instant::now();
+            while start.elapsed() < self.delay {
+                if token.is_cancelled() {
+                    self.log.lock().unwrap().push(format!("cancelled:{}", self.name));
+                    return SagaStepResult::Failure("已取消".to_string());
+                }
+                thread::sleep(Duration::from_millis(5));
+            }
+            self.log.lock().unwrap().push(format!("execute:{}", self.name));
+            SagaStepResult::Success
+        }
+    }
+
+    #[test]
+    fn test_step_within_deadline_succeeds() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut saga = SagaOrchestrator::new();
+        saga.add_step_with_timeout(RecordingStep::new("reserve", Duration::ZERO, &log), Duration::from_millis(500));
+
+        assert!(saga.execute().is_ok());
+        assert_eq!(*log.lock().unwrap(), vec!["execute:reserve".to_string()]);
+    }
+
+    #[test]
+    fn test_step_exceeding_deadline_aborts_and_compensates() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut saga = SagaOrchestrator::new();
+        saga.add_step(RecordingStep::new("order", Duration::ZERO, &log));
+        saga.add_step(RecordingStep::new("payment", Duration::ZERO, &log));
+        saga.add_step_with_timeout(RecordingStep::new("shipping", Duration::from_secs(5), &log), Duration::from_millis(50));
+
+        let error = saga.execute().unwrap_err();
+        assert!(error.contains("超时"), "unexpected error: {}", error);
+
+        // 挂起的步骤在工作线程里收到取消信号后退出，等它写入日志；
+        // 它与补偿的先后顺序不确定，只检查补偿本身按逆序执行
+        let cancelled = "cancelled:shipping".to_string();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !log.lock().unwrap().contains(&cancelled) {
+            assert!(std::time::Instant::now() < deadline, "挂起的步骤没有响应取消");
+            thread::sleep(Duration::from_millis(5));
+        }
+        let log = log.lock().unwrap();
+        let main_flow: Vec<&String> = log.iter().filter(|entry| **entry != cancelled).collect();
+        assert_eq!(main_flow, ["execute:order", "execute:payment", "compensate:payment", "compensate:order"]);
+        assert_eq!(log.iter().filter(|entry| **entry == cancelled).count(), 1);
+    }
+
+    /// 总是失败的测试步骤
+    struct FailingStep;
+
+    impl SagaStep for FailingStep {
+        fn execute(&self) -> SagaStepResult {
+            SagaStepResult::Failure("库存不足".to_string())
+        }
+
+        fn compensate(&self) -> SagaStepResult {
+            SagaStepResult::Success
+        }
+
+        fn get_name(&self) -> &str {
+            "inventory"
+        }
+    }
+
+    #[test]
+    fn test_status_report_after_mid_way_failure() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut saga = SagaOrchestrator::new();
+        saga.add_step(RecordingStep::new("order", Duration::ZERO, &log));
+        saga.add_step(RecordingStep::new("payment", Duration::ZERO, &log));
+        saga.add_step(Box::new(FailingStep));
+        saga.add_step(RecordingStep::new("shipping", Duration::ZERO, &log));
+        assert!(saga.status_report().steps.iter().all(|status| status.state == SagaStepState::Pending));
+
+        assert_eq!(saga.execute(), Err("库存不足".to_string()));
+
+        let report = saga.status_report();
+        let states: Vec<SagaStepState> = report.steps.iter().map(|status| status.state).collect();
+        assert_eq!(
+            states,
+            vec![SagaStepState::Compensated, SagaStepState::Compensated, SagaStepState::Failed, SagaStepState::Pending]
+        );
+        assert_eq!(report.state_of("inventory"), Some(SagaStepState::Failed));
+
+        let dot = saga.to_dot();
+        assert!(dot.starts_with("digraph saga {"));
+        assert!(dot.contains("step2 [label=\"inventory\\n[Failed]\", color=red];"));
+        assert!(dot.contains("step0 -> step1 [label=\"执行\"];"));
+        assert!(dot.contains("step1 -> step0 [style=dashed, label=\"补偿\"];"));
+    }
+}
@@ -0,0 +1,364 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/DataConsistencyPatterns/transactional_outbox.rs
+ *
+ * Transactional Outbox模式 (事务性发件箱)
+ *
+ * Saga步骤既要修改本地状态，又要通知其他服务。如果先写状态再发消息，
+ * 两者之间崩溃会丢失事件；先发消息再写状态，又可能发出从未生效的事件。
+ * 发件箱模式把要发出的消息和状态修改写进同一个本地事务，
+ * 再由独立的中继（Relay）把发件箱中的消息投递到消息队列。
+ *
+ * 主要特点：
+ * 1. 原子性 - 状态和消息一起提交或一起丢弃，不会只有一半生效
+ * 2. 至少一次 - 中继先发送、后标记已发布，发送后崩溃会在重启后重发
+ * 3. 去重 - 消息编号在事务中分配，重发使用同一编号，代理按编号丢弃重复发布
+ * 4. 与Saga集成 - OutboxSagaStep 的执行和补偿都在发件箱事务中完成
+ */
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use super::saga_pattern::{SagaOrchestrator, SagaStep, SagaStepResult};
+use crate::DistributedSystemMode::CommunicationPatterns::message_queue::{
+    MessageBroker, QueueConsumer, QueueMessage, BROKER_ADDRESS,
+};
+use crate::DistributedSystemMode::CommunicationPatterns::sim_network::{SimNetwork, SimNetworkConfig, Transport};
+
+// =================
+// 发件箱存储
+// =================
+
+/// 发件箱中的一条消息
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxMessage {
+    pub message_id: u64,
+    pub payload: String,
+    pub published: bool,
+}
+
+#[derive(Debug, Default)]
+struct OutboxData {
+    state: BTreeMap<String, String>,
+    outbox: BTreeMap<u64, OutboxMessage>,
+    next_message_id: u64,
+}
+
+/// 事务中暂存的修改，提交时一次性写入存储
+pub struct OutboxTransaction {
+    writes: Vec<(String, String)>,
+    messages: Vec<String>,
+}
+
+impl OutboxTransaction {
+    /// 写入一条状态
+    pub fn put(&mut self, key: &str, value: &str) {
+        self.writes.push((key.to_string(), value.to_string()));
+    }
+
+    /// 记录一条待发出的消息
+    pub fn record_message(&mut self, payload: &str) {
+        self.messages.push(payload.to_string());
+    }
+}
+
+/// 带发件箱的本地存储，克隆后共享同一份数据
+#[derive(Debug, Clone)]
+pub struct OutboxStore {
+    data: Arc<Mutex<OutboxData>>,
+}
+
+impl OutboxStore {
+    /// `id_base` 用于区分不同服务发件箱的消息编号
+    pub fn new(id_base: u64) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(OutboxData { next_message_id: id_base, ..OutboxData::default() })),
+        }
+    }
+
+    /// 在事务中执行操作：返回Ok时状态和消息一起提交，返回Err时全部丢弃
+    pub fn transaction<T, F>(&self, operation: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut OutboxTransaction) -> Result<T, String>,
+    {
+        let mut tx = OutboxTransaction { writes: Vec::new(), messages: Vec::new() };
+        let result = operation(&mut tx)?;
+
+        let mut data = self.data.lock().unwrap();
+        for (key, value) in tx.writes {
+            data.state.insert(key, value);
+        }
+        for payload in tx.messages {
+            let message_id = data.next_message_id;
+            data.next_message_id += 1;
+            data.outbox.insert(message_id, OutboxMessage { message_id, payload, published: false });
+        }
+        Ok(result)
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.data.lock().unwrap().state.get(key).cloned()
+    }
+
+    /// 尚未发布的消息，按编号排序
+    pub fn pending(&self) -> Vec<OutboxMessage> {
+        self.data.lock().unwrap().outbox.values().filter(|message| !message.published).cloned().collect()
+    }
+
+    /// 发件箱中的全部消息
+    pub fn messages(&self) -> Vec<OutboxMessage> {
+        self.data.lock().unwrap().outbox.values().cloned().collect()
+    }
+
+    pub fn mark_published(&self, message_ids: &[u64]) {
+        let mut data = self.data.lock().unwrap();
+        for message_id in message_ids {
+            if let Some(message) = data.outbox.get_mut(message_id) {
+                message.published = true;
+            }
+        }
+    }
+}
+
+// =================
+// 中继
+// =================
+
+/// 把发件箱中的消息发布到消息队列的中继
+pub struct OutboxRelay {
+    name: String,
+}
+
+impl OutboxRelay {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+
+    /// 发送所有未发布的消息但不标记，返回发送的编号
+    ///
+    /// 单独调用它相当于中继在发送之后、标记之前崩溃，
+    /// 下一次 `publish_pending` 会用相同的编号重发这些消息。
+    pub fn send_pending(&self, store: &OutboxStore, transport: &dyn Transport<QueueMessage>) -> Vec<u64> {
+        store.pending()
+            .into_iter()
+            .map(|message| {
+                transport.send(&self.name, BROKER_ADDRESS, QueueMessage::Publish {
+                    message_id: message.message_id,
+                    payload: message.payload,
+                });
+                message.message_id
+            })
+            .collect()
+    }
+
+    /// 发送并标记所有未发布的消息，返回本次发送的数量
+    pub fn publish_pending(&self, store: &OutboxStore, transport: &dyn Transport<QueueMessage>) -> usize {
+        let sent = self.send_pending(store, transport);
+        store.mark_published(&sent);
+        sent.len()
+    }
+}
+
+// =================
+// Saga集成
+// =================
+
+type OutboxAction = Box<dyn Fn(&mut OutboxTransaction) -> Result<(), String> + Send + Sync>;
+
+/// 在发件箱事务中执行和补偿的Saga步骤
+pub struct OutboxSagaStep {
+    name: String,
+    store: OutboxStore,
+    action: OutboxAction,
+    compensation: OutboxAction,
+}
+
+impl OutboxSagaStep {
+    pub fn new<A, C>(name: &str, store: &OutboxStore, action: A, compensation: C) -> Self
+    where
+        A: Fn(&mut OutboxTransaction) -> Result<(), String> + Send + Sync + 'static,
+        C: Fn(&mut OutboxTransaction) -> Result<(), String> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            store: store.clone(),
+            action: Box::new(action),
+            compensation: Box::new(compensation),
+        }
+    }
+
+    fn run(&self, operation: &OutboxAction) -> SagaStepResult {
+        match self.store.transaction(|tx| operation(tx)) {
+            Ok(()) => SagaStepResult::Success,
+            Err(error) => SagaStepResult::Failure(error),
+        }
+    }
+}
+
+impl SagaStep for OutboxSagaStep {
+    fn execute(&self) -> SagaStepResult {
+        self.run(&self.action)
+    }
+
+    fn compensate(&self) -> SagaStepResult {
+        self.run(&self.compensation)
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Transactional Outbox模式演示
+pub fn demo_transactional_outbox() {
+    println!("=== Transactional Outbox模式演示 ===\n");
+
+    let store = OutboxStore::new(1000);
+    let mut saga = SagaOrchestrator::new();
+    saga.add_step(Box::new(OutboxSagaStep::new(
+        "创建订单",
+        &store,
+        |tx| {
+            tx.put("order:42", "created");
+            tx.record_message("OrderCreated:42");
+            Ok(())
+        },
+        |tx| {
+            tx.put("order:42", "cancelled");
+            tx.record_message("OrderCancelled:42");
+            Ok(())
+        },
+    )));
+    saga.add_step(Box::new(OutboxSagaStep::new(
+        "扣减库存",
+        &store,
+        |tx| {
+            tx.put("stock:sku-1", "reserved");
+            tx.record_message("StockReserved:sku-1");
+            Err("库存不足".to_string())
+        },
+        |_| Ok(()),
+    )));
+
+    match saga.execute() {
+        Ok(()) => println!("Saga执行成功"),
+        Err(e) => println!("Saga失败: {}", e),
+    }
+    println!("订单状态: {:?}, 库存状态: {:?}", store.get("order:42"), store.get("stock:sku-1"));
+    for message in store.messages() {
+        println!("发件箱消息 #{}: {}", message.message_id, message.payload);
+    }
+
+    let network = SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 1, ..SimNetworkConfig::default() }, 7);
+    let mut broker = MessageBroker::new(10);
+    let mut consumer = QueueConsumer::new("通知服务");
+    broker.subscribe(consumer.name());
+    let relay = OutboxRelay::new("订单中继");
+
+    // 中继发送后崩溃，重启后重发同一批消息
+    relay.send_pending(&store, &network);
+    relay.publish_pending(&store, &network);
+    for _ in 0..10 {
+        network.tick();
+        broker.poll(&network);
+        consumer.poll(&network);
+    }
+
+    println!("消费者收到: {:?}", consumer.processed);
+    println!("代理丢弃的重复发布: {}", broker.duplicate_publish_count());
+
+    println!("\n【Transactional Outbox模式特点】");
+    println!("✓ 原子写入 - 状态和事件在同一事务中提交");
+    println!("✓ 至少一次 - 中继崩溃后重发未标记的消息");
+    println!("✓ 按编号去重 - 重复发布不会产生重复事件");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network() -> SimNetwork<QueueMessage> {
+        SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 1, ..SimNetworkConfig::default() }, 11)
+    }
+
+    fn run(network: &SimNetwork<QueueMessage>, broker: &mut MessageBroker, consumer: &mut QueueConsumer) {
+        for _ in 0..10 {
+            network.tick();
+            broker.poll(network);
+            consumer.poll(network);
+        }
+    }
+
+    fn order_step(store: &OutboxStore) -> Box<OutboxSagaStep> {
+        Box::new(OutboxSagaStep::new(
+            "create_order",
+            store,
+            |tx| {
+                tx.put("order:1", "created");
+                tx.record_message("OrderCreated:1");
+                Ok(())
+            },
+            |tx| {
+                tx.put("order:1", "cancelled");
+                tx.record_message("OrderCancelled:1");
+                Ok(())
+            },
+        ))
+    }
+
+    #[test]
+    fn test_relay_retry_after_crash_delivers_exactly_once() {
+        let store = OutboxStore::new(500);
+        let mut saga = SagaOrchestrator::new();
+        saga.add_step(order_step(&store));
+        assert!(saga.execute().is_ok());
+
+        // 状态和消息在同一事务中提交
+        assert_eq!(store.get("order:1"), Some("created".to_string()));
+        assert_eq!(store.pending(), vec![OutboxMessage { message_id: 500, payload: "OrderCreated:1".to_string(), published: false }]);
+
+        let network = network();
+        let mut broker = MessageBroker::new(10);
+        let mut consumer = QueueConsumer::new("billing");
+        broker.subscribe("billing");
+        let relay = OutboxRelay::new("relay");
+
+        // 第一次发送后中继崩溃，消息仍然是未发布状态
+        assert_eq!(relay.send_pending(&store, &network), vec![500]);
+        assert_eq!(store.pending().len(), 1);
+        // 重启后的中继重发同一编号
+        assert_eq!(relay.publish_pending(&store, &network), 1);
+        assert!(store.pending().is_empty());
+        run(&network, &mut broker, &mut consumer);
+
+        assert_eq!(consumer.processed, vec![(500, "OrderCreated:1".to_string())]);
+        assert_eq!(broker.duplicate_publish_count(), 1);
+
+        // 已标记的消息不会再次发送
+        assert_eq!(relay.publish_pending(&store, &network), 0);
+    }
+
+    #[test]
+    fn test_failed_step_commits_neither_state_nor_message() {
+        let store = OutboxStore::new(1);
+        let mut saga = SagaOrchestrator::new();
+        saga.add_step(order_step(&store));
+        saga.add_step(Box::new(OutboxSagaStep::new(
+            "charge",
+            &store,
+            |tx| {
+                tx.put("payment:1", "charged");
+                tx.record_message("PaymentCharged:1");
+                Err("余额不足".to_string())
+            },
+            |_| Ok(()),
+        )));
+
+        assert_eq!(saga.execute(), Err("余额不足".to_string()));
+
+        // 失败步骤的写入和消息都被丢弃，补偿在自己的事务中记录了撤销事件
+        assert_eq!(store.get("payment:1"), None);
+        assert_eq!(store.get("order:1"), Some("cancelled".to_string()));
+        let payloads: Vec<String> = store.messages().into_iter().map(|message| message.payload).collect();
+        assert_eq!(payloads, vec!["OrderCreated:1", "OrderCancelled:1"]);
+    }
+}
@@ -0,0 +1,619 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/DataConsistencyPatterns/two_phase_commit.rs
+ *
+ * Two Phase Commit模式 (两阶段提交)
+ *
+ * 两阶段提交协议确保分布式事务的原子性：协调者先让所有参与者准备（投票），
+ * 全部同意后再通知提交，任一参与者拒绝则通知中止。
+ *
+ * 协调者在通知参与者之前先把决定写入预写日志（WAL）。协调者在通知过程中崩溃时，
+ * 恢复流程读取日志，对已有决定但未完成的事务重新发送提交或中止；
+ * 尚未做出决定的事务按"推定中止"处理。
+ *
+ * 协调者既可以直接调用本地参与者，也可以通过 Transport 与参与者节点收发消息；
+ * 配合 SimNetwork 可以确定性地模拟消息延迟、乱序和丢失。
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::DistributedSystemMode::CommunicationPatterns::sim_network::{SimNetwork, SimNetworkConfig, Transport};
+
+// =================
+// 参与者
+// =================
+
+/// 参与者对准备请求的投票
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vote {
+    Yes,
+    No(String),
+}
+
+/// 事务参与者，commit和abort必须是幂等的，恢复时可能被重复调用
+pub trait Participant: Send + Sync {
+    fn name(&self) -> &str;
+    fn prepare(&self, tx_id: &str) -> Vote;
+    fn commit(&self, tx_id: &str);
+    fn abort(&self, tx_id: &str);
+}
+
+/// 参与者本地的事务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticipantState {
+    Prepared,
+    Committed,
+    Aborted,
+}
+
+/// 内存参与者
+pub struct InMemoryParticipant {
+    name: String,
+    reject_reason: Option<String>,
+    transactions: Mutex<HashMap<String, ParticipantState>>,
+}
+
+impl InMemoryParticipant {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            reject_reason: None,
+            transactions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 创建在准备阶段总是投反对票的参与者
+    pub fn rejecting(name: &str, reason: &str) -> Self {
+        Self {
+            reject_reason: Some(reason.to_string()),
+            ..Self::new(name)
+        }
+    }
+
+    pub fn state_of(&self, tx_id: &str) -> Option<ParticipantState> {
+        self.transactions.lock().unwrap().get(tx_id).copied()
+    }
+}
+
+impl Participant for InMemoryParticipant {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn prepare(&self, tx_id: &str) -> Vote {
+        if let Some(reason) = &self.reject_reason {
+            return Vote::No(reason.clone());
+        }
+        self.transactions.lock().unwrap().insert(tx_id.to_string(), ParticipantState::Prepared);
+        Vote::Yes
+    }
+
+    fn commit(&self, tx_id: &str) {
+        self.transactions.lock().unwrap().insert(tx_id.to_string(), ParticipantState::Committed);
+    }
+
+    fn abort(&self, tx_id: &str) {
+        self.transactions.lock().unwrap().insert(tx_id.to_string(), ParticipantState::Aborted);
+    }
+}
+
+// =================
+// 预写日志
+// =================
+
+/// 协调者的决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Commit,
+    Abort,
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Commit => write!(f, "提交"),
+            Decision::Abort => write!(f, "中止"),
+        }
+    }
+}
+
+/// 预写日志记录
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalRecord {
+    /// 事务开始，记录参与者名单
+    Begin { tx_id: String, participants: Vec<String> },
+    /// 协调者的决定，写入后才能通知参与者
+    Decision { tx_id: String, decision: Decision },
+    /// 所有参与者都已收到决定
+    Completed { tx_id: String },
+}
+
+/// 预写日志 - 追加写入的持久化记录
+pub trait WriteAheadLog: Send + Sync {
+    fn append(&self, record: WalRecord);
+    fn records(&self) -> Vec<WalRecord>;
+}
+
+/// 内存预写日志，克隆后共享同一份记录，用于模拟协调者重启后读取日志
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryWal {
+    records: Arc<Mutex<Vec<WalRecord>>>,
+}
+
+impl InMemoryWal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl WriteAheadLog for InMemoryWal {
+    fn append(&self, record: WalRecord) {
+        self.records.lock().unwrap().push(record);
+    }
+
+    fn records(&self) -> Vec<WalRecord> {
+        self.records.lock().unwrap().clone()
+    }
+}
+
+// =================
+// 网络消息与参与者节点
+// =================
+
+/// 协调者在网络中的地址
+pub const COORDINATOR_ADDRESS: &str = "coordinator";
+
+/// 协调者与参与者之间的消息
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwoPcMessage {
+    Prepare { tx_id: String },
+    Vote { tx_id: String, vote: Vote },
+    Commit { tx_id: String },
+    Abort { tx_id: String },
+    /// 参与者已执行提交或中止
+    Ack { tx_id: String },
+}
+
+/// 参与者节点 - 从传输层接收协调者的消息并回复
+pub struct ParticipantNode {
+    participant: Arc<dyn Participant>,
+}
+
+impl ParticipantNode {
+    pub fn new(participant: Arc<dyn Participant>) -> Self {
+        Self { participant }
+    }
+
+    pub fn name(&self) -> &str {
+        self.participant.name()
+    }
+
+    /// 处理收件箱中的全部消息
+    pub fn poll(&self, transport: &dyn Transport<TwoPcMessage>) {
+        while let Some(envelope) = transport.receive(self.name()) {
+            let reply = match envelope.message {
+                TwoPcMessage::Prepare { tx_id } => {
+                    let vote = self.participant.prepare(&tx_id);
+                    TwoPcMessage::Vote { tx_id, vote }
+                }
+                TwoPcMessage::Commit { tx_id } => {
+                    self.participant.commit(&tx_id);
+                    TwoPcMessage::Ack { tx_id }
+                }
+                TwoPcMessage::Abort { tx_id } => {
+                    self.participant.abort(&tx_id);
+                    TwoPcMessage::Ack { tx_id }
+                }
+                TwoPcMessage::Vote { .. } | TwoPcMessage::Ack { .. } => continue,
+            };
+            transport.send(self.name(), &envelope.from, reply);
+        }
+    }
+}
+
+// =================
+// 协调者
+// =================
+
+/// 两阶段提交错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwoPhaseCommitError {
+    /// 有参与者投了反对票，事务已中止
+    Aborted { participant: String, reason: String },
+    /// 模拟的协调者崩溃：决定已写入日志但尚未通知参与者
+    CoordinatorCrashed { tx_id: String },
+    /// 参与者没有在超时前投票，事务已中止
+    VoteTimeout { participants: Vec<String> },
+}
+
+impl fmt::Display for TwoPhaseCommitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TwoPhaseCommitError::Aborted { participant, reason } => {
+                write!(f, "参与者 {} 拒绝提交: {}", participant, reason)
+            }
+            TwoPhaseCommitError::CoordinatorCrashed { tx_id } => {
+                write!(f, "协调者在通知参与者前崩溃，事务 {} 处于不确定状态", tx_id)
+            }
+            TwoPhaseCommitError::VoteTimeout { participants } => {
+                write!(f, "参与者 {} 未在超时前投票", participants.join(", "))
+            }
+        }
+    }
+}
+
+/// 两阶段提交协调者
+pub struct TwoPhaseCommitCoordinator {
+    participants: Vec<Arc<dyn Participant>>,
+    wal: Arc<dyn WriteAheadLog>,
+    crash_after_decision: bool,
+}
+
+impl TwoPhaseCommitCoordinator {
+    pub fn new(wal: Arc<dyn WriteAheadLog>) -> Self {
+        Self {
+            participants: Vec::new(),
+            wal,
+            crash_after_decision: false,
+        }
+    }
+
+    pub fn add_participant(&mut self, participant: Arc<dyn Participant>) {
+        self.participants.push(participant);
+    }
+
+    /// 故障注入：下一次执行在写入决定后、通知参与者前"崩溃"
+    pub fn simulate_crash_after_decision(&mut self) {
+        self.crash_after_decision = true;
+    }
+
+    /// 执行两阶段提交
+    pub fn execute(&mut self, tx_id: &str) -> Result<(), TwoPhaseCommitError> {
+        self.wal.append(WalRecord::Begin {
+            tx_id: tx_id.to_string(),
+            participants: self.participants.iter().map(|p| p.name().to_string()).collect(),
+        });
+
+        // 第一阶段：准备
+        let mut rejection = None;
+        for participant in &self.participants {
+            if let Vote::No(reason) = participant.prepare(tx_id) {
+                rejection = Some(TwoPhaseCommitError::Aborted {
+                    participant: participant.name().to_string(),
+                    reason,
+                });
+                break;
+            }
+        }
+        let decision = if rejection.is_none() { Decision::Commit } else { Decision::Abort };
+
+        // 决定先持久化，再通知参与者
+        self.wal.append(WalRecord::Decision { tx_id: tx_id.to_string(), decision });
+        if self.crash_after_decision {
+            self.crash_after_decision = false;
+            return Err(TwoPhaseCommitError::CoordinatorCrashed { tx_id: tx_id.to_string() });
+        }
+
+        // 第二阶段：提交或中止
+        self.deliver(tx_id, decision);
+        rejection.map_or(Ok(()), Err)
+    }
+
+    /// 读取日志，完成所有不确定状态的事务，返回被恢复的事务及其决定
+    ///
+    /// 决定只发给开始记录中登记的参与者（按名称匹配已注册的参与者）；
+    /// 有参与者未注册时不写完成记录，下次恢复会再次处理。
+    pub fn recover(&self) -> Vec<(String, Decision)> {
+        let mut begun = Vec::new();
+        let mut decisions = HashMap::new();
+        let mut completed = Vec::new();
+        for record in self.wal.records() {
+            match record {
+                WalRecord::Begin { tx_id, participants } => begun.push((tx_id, participants)),
+                WalRecord::Decision { tx_id, decision } => {
+                    decisions.insert(tx_id, decision);
+                }
+                WalRecord::Completed { tx_id } => completed.push(tx_id),
+            }
+        }
+
+        let mut recovered = Vec::new();
+        for (tx_id, names) in begun.into_iter().filter(|(tx_id, _)| !completed.contains(tx_id)) {
+            let decision = match decisions.get(&tx_id) {
+                Some(decision) => *decision,
+                None => {
+                    // 没有决定记录：推定中止
+                    self.wal.append(WalRecord::Decision { tx_id: tx_id.clone(), decision: Decision::Abort });
+                    Decision::Abort
+                }
+            };
+            let participants: Vec<&Arc<dyn Participant>> = names.iter()
+                .filter_map(|name| self.participants.iter().find(|p| p.name() == name))
+                .collect();
+            for participant in &participants {
+                Self::notify(participant.as_ref(), &tx_id, decision);
+            }
+            if participants.len() == names.len() {
+                self.wal.append(WalRecord::Completed { tx_id: tx_id.clone() });
+            }
+            recovered.push((tx_id, decision));
+        }
+        recovered
+    }
+
+    /// 通过传输层执行两阶段提交
+    ///
+    /// 单线程驱动：等待期间推进传输层时钟并让参与者节点处理消息，
+    /// 配合带种子的 SimNetwork 时整个交互可以完全复现。
+    /// `timeout` 为每一轮等待投票或确认的时长；决定最多重发3轮，
+    /// 仍有参与者未确认时不写完成记录，留给 `recover` 处理。
+    pub fn execute_over(
+        &mut self,
+        tx_id: &str,
+        transport: &dyn Transport<TwoPcMessage>,
+        nodes: &[ParticipantNode],
+        timeout: u64,
+    ) -> Result<(), TwoPhaseCommitError> {
+        let names: Vec<String> = nodes.iter().map(|node| node.name().to_string()).collect();
+        self.wal.append(WalRecord::Begin { tx_id: tx_id.to_string(), participants: names.clone() });
+
+        // 第一阶段：发送准备请求并收集投票
+        for name in &names {
+            transport.send(COORDINATOR_ADDRESS, name, TwoPcMessage::Prepare { tx_id: tx_id.to_string() });
+        }
+        let mut voted = Vec::new();
+        let mut rejection = None;
+        let deadline = transport.now() + timeout;
+        while voted.len() < names.len() && rejection.is_none() && transport.now() < deadline {
+            Self::drive(transport, nodes);
+            while let Some(envelope) = transport.receive(COORDINATOR_ADDRESS) {
+                match envelope.message {
+                    TwoPcMessage::Vote { tx_id: ref id, vote: Vote::Yes } if id == tx_id => voted.push(envelope.from),
+                    TwoPcMessage::Vote { tx_id: ref id, vote: Vote::No(reason) } if id == tx_id => {
+                        rejection = Some(TwoPhaseCommitError::Aborted { participant: envelope.from, reason });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if rejection.is_none() && voted.len() < names.len() {
+            let missing = names.iter().filter(|name| !voted.contains(name)).cloned().collect();
+            rejection = Some(TwoPhaseCommitError::VoteTimeout { participants: missing });
+        }
+        let decision = if rejection.is_none() { Decision::Commit } else { Decision::Abort };
+
+        self.wal.append(WalRecord::Decision { tx_id: tx_id.to_string(), decision });
+        if self.crash_after_decision {
+            self.crash_after_decision = false;
+            return Err(TwoPhaseCommitError::CoordinatorCrashed { tx_id: tx_id.to_string() });
+        }
+
+        // 第二阶段：发送决定，收集确认，未确认的参与者重发
+        let mut acked: Vec<String> = Vec::new();
+        for _ in 0..3 {
+            for name in names.iter().filter(|name| !acked.contains(name)) {
+                let message = match decision {
+                    Decision::Commit => TwoPcMessage::Commit { tx_id: tx_id.to_string() },
+                    Decision::Abort => TwoPcMessage::Abort { tx_id: tx_id.to_string() },
+                };
+                transport.send(COORDINATOR_ADDRESS, name, message);
+            }
+            let deadline = transport.now() + timeout;
+            while acked.len() < names.len() && transport.now() < deadline {
+                Self::drive(transport, nodes);
+                while let Some(envelope) = transport.receive(COORDINATOR_ADDRESS) {
+                    if matches!(envelope.message, TwoPcMessage::Ack { tx_id: ref id } if id == tx_id) && !acked.contains(&envelope.from) {
+                        acked.push(envelope.from);
+                    }
+                }
+            }
+            if acked.len() == names.len() {
+                self.wal.append(WalRecord::Completed { tx_id: tx_id.to_string() });
+                break;
+            }
+        }
+
+        rejection.map_or(Ok(()), Err)
+    }
+
+    fn drive(transport: &dyn Transport<TwoPcMessage>, nodes: &[ParticipantNode]) {
+        transport.tick();
+        for node in nodes {
+            node.poll(transport);
+        }
+    }
+
+    fn deliver(&self, tx_id: &str, decision: Decision) {
+        for participant in &self.participants {
+            Self::notify(participant.as_ref(), tx_id, decision);
+        }
+        self.wal.append(WalRecord::Completed { tx_id: tx_id.to_string() });
+    }
+
+    fn notify(participant: &dyn Participant, tx_id: &str, decision: Decision) {
+        match decision {
+            Decision::Commit => participant.commit(tx_id),
+            Decision::Abort => participant.abort(tx_id),
+        }
+    }
+}
+
+/// Two Phase Commit模式演示
+pub fn demo_two_phase_commit() {
+    println!("=== Two Phase Commit模式演示 ===\n");
+
+    let wal = InMemoryWal::new();
+    let inventory = Arc::new(InMemoryParticipant::new("库存服务"));
+    let payment = Arc::new(InMemoryParticipant::new("支付服务"));
+
+    // 1. 正常提交
+    println!("1. 所有参与者同意:");
+    let mut coordinator = TwoPhaseCommitCoordinator::new(Arc::new(wal.clone()));
+    coordinator.add_participant(inventory.clone());
+    coordinator.add_participant(payment.clone());
+    match coordinator.execute("tx-1") {
+        Ok(()) => println!("事务 tx-1 已提交"),
+        Err(e) => println!("事务 tx-1 失败: {}", e),
+    }
+    println!("参与者状态: 库存={:?}, 支付={:?}", inventory.state_of("tx-1"), payment.state_of("tx-1"));
+
+    // 2. 协调者崩溃后从日志恢复
+    println!("\n2. 协调者在通知参与者前崩溃:");
+    coordinator.simulate_crash_after_decision();
+    if let Err(e) = coordinator.execute("tx-2") {
+        println!("{}", e);
+    }
+    println!("崩溃后参与者状态: {:?}", payment.state_of("tx-2"));
+
+    let mut restarted = TwoPhaseCommitCoordinator::new(Arc::new(wal.clone()));
+    restarted.add_participant(inventory.clone());
+    restarted.add_participant(payment.clone());
+    for (tx_id, decision) in restarted.recover() {
+        println!("恢复事务 {}: 重新发送{}", tx_id, decision);
+    }
+    println!("恢复后参与者状态: {:?}", payment.state_of("tx-2"));
+    println!("日志记录数: {}", wal.records().len());
+
+    // 3. 通过模拟网络执行，准备请求在途中丢失
+    println!("\n3. 模拟网络中丢失准备请求:");
+    let network = SimNetwork::new(SimNetworkConfig::default(), 42);
+    network.block_link(COORDINATOR_ADDRESS, "支付服务");
+    let nodes = vec![ParticipantNode::new(inventory.clone()), ParticipantNode::new(payment.clone())];
+    let mut networked = TwoPhaseCommitCoordinator::new(Arc::new(InMemoryWal::new()));
+    match networked.execute_over("tx-3", &network, &nodes, 20) {
+        Ok(()) => println!("事务 tx-3 已提交"),
+        Err(e) => println!("事务 tx-3 已中止: {}", e),
+    }
+    println!("网络统计: {:?}", network.stats());
+
+    println!("\n【Two Phase Commit模式特点】");
+    println!("✓ 原子性 - 所有参与者要么全部提交，要么全部中止");
+    println!("✓ 预写日志 - 决定在通知参与者之前持久化");
+    println!("✓ 崩溃恢复 - 重启后根据日志完成不确定状态的事务");
+    println!("✓ 推定中止 - 没有决定记录的事务在恢复时中止");
+    println!("✓ 确定性模拟 - 通过SimNetwork重现消息丢失与乱序");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinator(wal: &InMemoryWal, participants: &[Arc<InMemoryParticipant>]) -> TwoPhaseCommitCoordinator {
+        let mut coordinator = TwoPhaseCommitCoordinator::new(Arc::new(wal.clone()));
+        for participant in participants {
+            coordinator.add_participant(participant.clone());
+        }
+        coordinator
+    }
+
+    #[test]
+    fn test_recovery_commits_after_crash_before_notification() {
+        let wal = InMemoryWal::new();
+        let participants = vec![Arc::new(InMemoryParticipant::new("orders")), Arc::new(InMemoryParticipant::new("billing"))];
+
+        let mut crashed = coordinator(&wal, &participants);
+        crashed.simulate_crash_after_decision();
+        assert_eq!(crashed.execute("tx-42"), Err(TwoPhaseCommitError::CoordinatorCrashed { tx_id: "tx-42".to_string() }));
+        drop(crashed);
+
+        // 决定已写入日志，但参与者仍停留在准备状态
+        assert!(wal.records().contains(&WalRecord::Decision { tx_id: "tx-42".to_string(), decision: Decision::Commit }));
+        assert!(participants.iter().all(|p| p.state_of("tx-42") == Some(ParticipantState::Prepared)));
+
+        let restarted = coordinator(&wal, &participants);
+        assert_eq!(restarted.recover(), vec![("tx-42".to_string(), Decision::Commit)]);
+        assert!(participants.iter().all(|p| p.state_of("tx-42") == Some(ParticipantState::Committed)));
+
+        // 已完成的事务不会被再次恢复
+        assert_eq!(wal.records().last(), Some(&WalRecord::Completed { tx_id: "tx-42".to_string() }));
+        assert!(restarted.recover().is_empty());
+    }
+
+    #[test]
+    fn test_rejection_aborts_and_undecided_transaction_is_presumed_aborted() {
+        let wal = InMemoryWal::new();
+        let participants = vec![Arc::new(InMemoryParticipant::new("orders")), Arc::new(InMemoryParticipant::rejecting("billing", "余额不足"))];
+
+        let mut coordinator = coordinator(&wal, &participants);
+        assert!(matches!(coordinator.execute("tx-1"), Err(TwoPhaseCommitError::Aborted { ref participant, .. }) if participant == "billing"));
+        assert_eq!(participants[0].state_of("tx-1"), Some(ParticipantState::Aborted));
+
+        // 只写了开始记录就崩溃的事务
+        wal.append(WalRecord::Begin { tx_id: "tx-2".to_string(), participants: vec!["orders".to_string()] });
+        participants[0].prepare("tx-2");
+        assert_eq!(coordinator.recover(), vec![("tx-2".to_string(), Decision::Abort)]);
+        assert_eq!(participants[0].state_of("tx-2"), Some(ParticipantState::Aborted));
+    }
+
+    /// 一次网络执行的结果：执行结果、日志、消息轨迹和参与者
+    type NetworkRun = (Result<(), TwoPhaseCommitError>, Vec<WalRecord>, Vec<String>, Vec<Arc<InMemoryParticipant>>);
+
+    fn run_over_network(seed: u64, block_prepare_to: Option<&str>) -> NetworkRun {
+        let participants = vec![Arc::new(InMemoryParticipant::new("orders")), Arc::new(InMemoryParticipant::new("billing"))];
+        let nodes: Vec<ParticipantNode> = participants.iter().map(|p| ParticipantNode::new(p.clone())).collect();
+        let network = SimNetwork::new(SimNetworkConfig { min_latency: 1, max_latency: 8, reorder_rate: 0.3, ..SimNetworkConfig::default() }, seed);
+        if let Some(target) = block_prepare_to {
+            network.block_link(COORDINATOR_ADDRESS, target);
+        }
+
+        let wal = InMemoryWal::new();
+        let result = TwoPhaseCommitCoordinator::new(Arc::new(wal.clone())).execute_over("tx-7", &network, &nodes, 30);
+        (result, wal.records(), network.trace(), participants)
+    }
+
+    #[test]
+    fn test_dropped_prepare_aborts_deterministically() {
+        let (result, records, trace, participants) = run_over_network(11, Some("billing"));
+
+        assert_eq!(result, Err(TwoPhaseCommitError::VoteTimeout { participants: vec!["billing".to_string()] }));
+        assert!(records.contains(&WalRecord::Decision { tx_id: "tx-7".to_string(), decision: Decision::Abort }));
+        assert_eq!(participants[0].state_of("tx-7"), Some(ParticipantState::Aborted));
+        assert_eq!(participants[1].state_of("tx-7"), None);
+        // billing 收不到中止消息，事务没有完成记录，需要恢复流程处理
+        assert!(!records.contains(&WalRecord::Completed { tx_id: "tx-7".to_string() }));
+
+        // 相同种子下再次运行，日志和消息轨迹完全一致
+        let (again, again_records, again_trace, _) = run_over_network(11, Some("billing"));
+        assert_eq!((again, again_records, again_trace), (result, records, trace));
+    }
+
+    #[test]
+    fn test_recover_transaction_coordinated_over_network() {
+        let participants = [Arc::new(InMemoryParticipant::new("orders")), Arc::new(InMemoryParticipant::new("billing"))];
+        let nodes: Vec<ParticipantNode> = participants.iter().map(|p| ParticipantNode::new(p.clone())).collect();
+        let network = SimNetwork::new(SimNetworkConfig::default(), 5);
+        let wal = InMemoryWal::new();
+
+        // 通过网络协调的协调者没有注册本地参与者，决定写入日志后崩溃
+        let mut networked = TwoPhaseCommitCoordinator::new(Arc::new(wal.clone()));
+        networked.simulate_crash_after_decision();
+        assert!(matches!(networked.execute_over("tx-9", &network, &nodes, 30), Err(TwoPhaseCommitError::CoordinatorCrashed { .. })));
+        assert!(participants.iter().all(|p| p.state_of("tx-9") == Some(ParticipantState::Prepared)));
+
+        // 重启后的协调者只注册了 billing：只通知 billing，不写完成记录
+        let audit = Arc::new(InMemoryParticipant::new("audit"));
+        let partial = coordinator(&wal, &[participants[1].clone(), audit.clone()]);
+        assert_eq!(partial.recover(), vec![("tx-9".to_string(), Decision::Commit)]);
+        assert_eq!(participants[1].state_of("tx-9"), Some(ParticipantState::Committed));
+        assert_eq!(participants[0].state_of("tx-9"), Some(ParticipantState::Prepared));
+        // 不在开始记录中的参与者不会收到决定
+        assert_eq!(audit.state_of("tx-9"), None);
+
+        // 所有参与者都注册后恢复完成
+        let restarted = coordinator(&wal, &[participants[1].clone(), participants[0].clone(), audit.clone()]);
+        assert_eq!(restarted.recover(), vec![("tx-9".to_string(), Decision::Commit)]);
+        assert!(participants.iter().all(|p| p.state_of("tx-9") == Some(ParticipantState::Committed)));
+        assert_eq!(audit.state_of("tx-9"), None);
+        assert!(restarted.recover().is_empty());
+    }
+
+    #[test]
+    fn test_commit_over_reordering_network() {
+        let (result, records, _, participants) = run_over_network(3, None);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(records.last(), Some(&WalRecord::Completed { tx_id: "tx-7".to_string() }));
+        assert!(participants.iter().all(|p| p.state_of("tx-7") == Some(ParticipantState::Committed)));
+    }
+}
@@ -12,9 +12,10 @@
  * 3. 并行处理 - 多个分片可以并行处理查询
  * 4. 容错性 - 单个分片故障不会影响其他分片
  * 5. 性能提升 - 减少单个数据库的负载
+ * 6. 一致性哈希 - 增删节点时只迁移相邻区间的键，并报告迁移的区间
  */
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, RwLock};
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -104,6 +105,162 @@ impl ShardingStrategy for DirectoryShardingStrategy {
     }
 }
 
+// =================
+// 一致性哈希环
+// =================
+
+/// 一段哈希区间 `(start, end]` 的归属从 `from` 节点变为 `to` 节点
+///
+/// 区间可能跨越环的起点（`start >= end`）；`start == end` 表示整个环。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMigration {
+    pub start: u64,
+    pub end: u64,
+    pub from: String,
+    pub to: String,
+}
+
+impl KeyMigration {
+    /// 哈希值是否落在迁移区间内
+    pub fn contains(&self, hash: u64) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => hash > self.start && hash <= self.end,
+            std::cmp::Ordering::Greater => hash > self.start || hash <= self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+/// 一致性哈希环 - 每个节点在环上放置多个虚拟节点，键归属于顺时针方向的第一个虚拟节点
+///
+/// 增删节点时只有相邻区间的键改变归属，`add_with_report` / `remove_with_report`
+/// 返回这些区间，有状态的系统（如分片缓存）据此只迁移受影响的数据。
+pub struct ConsistentHashRing {
+    virtual_nodes: usize,
+    ring: BTreeMap<u64, String>,
+}
+
+impl ConsistentHashRing {
+    pub fn new(virtual_nodes: usize) -> Self {
+        Self { virtual_nodes: virtual_nodes.max(1), ring: BTreeMap::new() }
+    }
+
+    /// 键在环上的位置
+    pub fn hash(key: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn add(&mut self, node: &str) {
+        self.add_with_report(node);
+    }
+
+    pub fn remove(&mut self, node: &str) {
+        self.remove_with_report(node);
+    }
+
+    /// 加入节点，返回改由新节点负责的区间；环原本为空时没有数据需要迁移
+    pub fn add_with_report(&mut self, node: &str) -> Vec<KeyMigration> {
+        let was_empty = self.ring.is_empty();
+        let mut added = Vec::new();
+        for point in self.virtual_points(node) {
+            // 与其他节点的虚拟节点冲突时保留原有归属
+            if let std::collections::btree_map::Entry::Vacant(entry) = self.ring.entry(point) {
+                entry.insert(node.to_string());
+                added.push(point);
+            }
+        }
+        if was_empty {
+            return Vec::new();
+        }
+        added.sort_unstable();
+
+        // 新环中前驱到新虚拟节点之间的哈希，在旧环中都归属于新虚拟节点的后继
+        let mut migrations: Vec<KeyMigration> = Vec::new();
+        for point in added {
+            let start = self.predecessor(point);
+            let from = self.successor_excluding(point, node);
+            Self::push_merged(&mut migrations, KeyMigration { start, end: point, from, to: node.to_string() });
+        }
+        migrations
+    }
+
+    /// 移除节点，返回原属于该节点的区间及其新归属；移除最后一个节点时没有迁移目标
+    pub fn remove_with_report(&mut self, node: &str) -> Vec<KeyMigration> {
+        let removed: Vec<(u64, u64)> = self.ring.iter()
+            .filter(|(_, owner)| owner.as_str() == node)
+            .map(|(point, _)| (self.predecessor(*point), *point))
+            .collect();
+        self.ring.retain(|_, owner| owner != node);
+        if self.ring.is_empty() {
+            return Vec::new();
+        }
+
+        let mut migrations: Vec<KeyMigration> = Vec::new();
+        for (start, end) in removed {
+            let to = self.owner_of(end).to_string();
+            Self::push_merged(&mut migrations, KeyMigration { start, end, from: node.to_string(), to });
+        }
+        migrations
+    }
+
+    /// 键所属的节点
+    pub fn node_for(&self, key: &str) -> Option<&str> {
+        if self.ring.is_empty() {
+            return None;
+        }
+        Some(self.owner_of(Self::hash(key)))
+    }
+
+    /// 环上的节点（去重后按名称排序）
+    pub fn nodes(&self) -> Vec<String> {
+        let mut nodes: Vec<String> = self.ring.values().cloned().collect();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    fn virtual_points(&self, node: &str) -> Vec<u64> {
+        (0..self.virtual_nodes).map(|i| Self::hash(&format!("{}#{}", node, i))).collect()
+    }
+
+    /// 顺时针方向第一个不小于 `hash` 的虚拟节点，超过最大值时回绕到起点
+    fn owner_of(&self, hash: u64) -> &str {
+        self.ring.range(hash..).next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, owner)| owner.as_str())
+            .expect("环不为空")
+    }
+
+    /// 环上位于 `point` 之前的虚拟节点，只有一个虚拟节点时返回它自己
+    fn predecessor(&self, point: u64) -> u64 {
+        self.ring.range(..point).next_back()
+            .or_else(|| self.ring.iter().next_back())
+            .map(|(p, _)| *p)
+            .expect("环不为空")
+    }
+
+    /// `point` 之后第一个不属于 `node` 的虚拟节点的归属
+    fn successor_excluding(&self, point: u64, node: &str) -> String {
+        self.ring.range(point..).chain(self.ring.range(..point))
+            .find(|(_, owner)| owner.as_str() != node)
+            .map(|(_, owner)| owner.clone())
+            .expect("环中存在其他节点")
+    }
+
+    /// 与上一段首尾相接且归属变化相同时合并为一段
+    fn push_merged(migrations: &mut Vec<KeyMigration>, migration: KeyMigration) {
+        if let Some(last) = migrations.last_mut() {
+            if last.end == migration.start && last.from == migration.from && last.to == migration.to {
+                last.end = migration.end;
+                return;
+            }
+        }
+        migrations.push(migration);
+    }
+}
+
 // =================
 // 分片错误处理
 // =================
@@ -562,8 +719,42 @@ pub fn demo_database_sharding() {
     println!("恢复分片健康状态...");
     user_cluster.set_shard_health(0, true);
     
-    // 5. 性能统计
-    println!("\n5. 性能统计:");
+    // 5. 一致性哈希扩容
+    println!("\n5. 一致性哈希扩容:");
+    let mut ring = ConsistentHashRing::new(16);
+    let mut cache: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for node in ["cache-a", "cache-b", "cache-c"] {
+        ring.add(node);
+        cache.insert(node.to_string(), HashMap::new());
+    }
+    for i in 0..200 {
+        let key = format!("session_{:03}", i);
+        let node = ring.node_for(&key).unwrap().to_string();
+        cache.get_mut(&node).unwrap().insert(key.clone(), format!("value_{}", i));
+    }
+    
+    // 只迁移落在报告区间内的键
+    let migrations = ring.add_with_report("cache-d");
+    let mut target = HashMap::new();
+    for migration in &migrations {
+        let source = cache.get_mut(&migration.from).unwrap();
+        let moved: Vec<String> = source.keys()
+            .filter(|key| migration.contains(ConsistentHashRing::hash(key)))
+            .cloned()
+            .collect();
+        for key in moved {
+            let value = source.remove(&key).unwrap();
+            target.insert(key, value);
+        }
+    }
+    println!("加入 cache-d: {} 段区间改变归属, 迁移 {} 个键", migrations.len(), target.len());
+    cache.insert("cache-d".to_string(), target);
+    for node in ring.nodes() {
+        println!("  {}: {} 个键", node, cache[&node].len());
+    }
+    
+    // 6. 性能统计
+    println!("\n6. 性能统计:");
     println!("用户集群:");
     println!("  总分片数: {}", user_cluster.shards.len());
     println!("  健康分片数: {}", user_cluster.get_healthy_shard_count());
@@ -580,4 +771,88 @@ pub fn demo_database_sharding() {
     println!("✓ 并行处理 - 多个分片可以并行处理查询");
     println!("✓ 容错性 - 单个分片故障不会影响其他分片");
     println!("✓ 性能提升 - 减少单个数据库的负载");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn populated_ring() -> (ConsistentHashRing, Vec<String>) {
+        let mut ring = ConsistentHashRing::new(32);
+        for node in ["node-a", "node-b", "node-c"] {
+            ring.add(node);
+        }
+        let keys = (0..2000).map(|i| format!("key-{}", i)).collect();
+        (ring, keys)
+    }
+
+    fn owners(ring: &ConsistentHashRing, keys: &[String]) -> Vec<String> {
+        keys.iter().map(|key| ring.node_for(key).unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn test_add_reports_exactly_the_keys_that_changed_owner() {
+        let (mut ring, keys) = populated_ring();
+        let before = owners(&ring, &keys);
+
+        let migrations = ring.add_with_report("node-d");
+        let after = owners(&ring, &keys);
+
+        assert!(!migrations.is_empty());
+        assert!(migrations.iter().all(|m| m.to == "node-d" && m.from != "node-d"));
+        for (key, (old, new)) in keys.iter().zip(before.iter().zip(&after)) {
+            let covering: Vec<&KeyMigration> = migrations.iter().filter(|m| m.contains(ConsistentHashRing::hash(key))).collect();
+            if old == new {
+                assert!(covering.is_empty(), "{} 没有改变归属却落在迁移区间内", key);
+            } else {
+                assert_eq!(covering.len(), 1, "{} 改变了归属但没有唯一的迁移区间", key);
+                assert_eq!((&covering[0].from, &covering[0].to), (old, new));
+            }
+        }
+
+        // 只有一部分键迁移
+        let moved = before.iter().zip(&after).filter(|(old, new)| old != new).count();
+        assert!(moved > 0 && moved < keys.len() / 2);
+    }
+
+    #[test]
+    fn test_remove_reports_new_owners_of_departed_ranges() {
+        let (mut ring, keys) = populated_ring();
+        let before = owners(&ring, &keys);
+
+        let migrations = ring.remove_with_report("node-b");
+        let after = owners(&ring, &keys);
+
+        assert_eq!(ring.nodes(), vec!["node-a", "node-c"]);
+        for (key, (old, new)) in keys.iter().zip(before.iter().zip(&after)) {
+            let covering: Vec<&KeyMigration> = migrations.iter().filter(|m| m.contains(ConsistentHashRing::hash(key))).collect();
+            if old == "node-b" {
+                assert_eq!(covering.len(), 1);
+                assert_eq!((covering[0].from.as_str(), &covering[0].to), ("node-b", new));
+            } else {
+                assert_eq!(old, new);
+                assert!(covering.is_empty());
+            }
+        }
+
+        ring.remove("node-c");
+        assert!(owners(&ring, &keys).iter().all(|owner| owner == "node-a"));
+    }
+
+    #[test]
+    fn test_first_and_last_node_report_no_migrations() {
+        let mut ring = ConsistentHashRing::new(4);
+        assert!(ring.node_for("key").is_none());
+        assert!(ring.add_with_report("only").is_empty());
+        assert_eq!(ring.node_for("key"), Some("only"));
+        // 重复加入同一节点不会改变归属
+        assert!(ring.add_with_report("only").is_empty());
+        assert!(ring.remove_with_report("only").is_empty());
+        assert!(ring.nodes().is_empty());
+
+        let whole_ring = KeyMigration { start: 7, end: 7, from: "a".to_string(), to: "b".to_string() };
+        let wrapping = KeyMigration { start: u64::MAX - 1, end: 3, ..whole_ring.clone() };
+        assert!(whole_ring.contains(0) && whole_ring.contains(u64::MAX));
+        assert!(wrapping.contains(u64::MAX) && wrapping.contains(3) && !wrapping.contains(4) && !wrapping.contains(u64::MAX - 1));
+    }
+}
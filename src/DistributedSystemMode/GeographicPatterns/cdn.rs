@@ -0,0 +1,312 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/GeographicPatterns/cdn.rs
+ *
+ * CDN模式 (内容分发网络)
+ *
+ * CDN 在各地部署边缘节点缓存内容，用户请求由最近的健康边缘节点响应；
+ * 边缘未命中时回源获取内容并缓存，之后同一地区的请求直接命中。
+ *
+ * 主要特点：
+ * 1. 就近接入 - 基于 GeoTopology 选择最近的健康边缘节点
+ * 2. 边缘缓存 - 命中(HIT)只需客户端到边缘的延迟，未命中(MISS)还要加上回源延迟
+ * 3. 节点故障转移 - 边缘节点不健康时由次近节点响应
+ * 4. 缓存预热 - 在用户请求之前把内容推送到边缘，或根据访问日志预取热门内容
+ * 5. 命中率指标 - 统计客户端请求的命中与未命中次数
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use super::geo_topology::GeoTopology;
+
+/// 缓存状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+impl fmt::Display for CacheStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheStatus::Hit => write!(f, "HIT"),
+            CacheStatus::Miss => write!(f, "MISS"),
+        }
+    }
+}
+
+/// CDN 错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum CdnError {
+    NotFound(String),
+    NoHealthyEdge,
+}
+
+impl fmt::Display for CdnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CdnError::NotFound(url) => write!(f, "源站不存在内容: {}", url),
+            CdnError::NoHealthyEdge => write!(f, "没有可用的边缘节点"),
+        }
+    }
+}
+
+/// CDN 响应
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdnResponse {
+    pub edge: String,
+    pub status: CacheStatus,
+    pub body: String,
+    pub latency_ms: u32,
+}
+
+/// 缓存命中指标，只统计客户端请求，预热不计入
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CdnMetrics {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CdnMetrics {
+    /// 命中率，没有请求时为0
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct EdgeNode {
+    healthy: bool,
+    cache: HashMap<String, String>,
+}
+
+/// 内容分发网络
+pub struct Cdn {
+    topology: GeoTopology,
+    origin_region: String,
+    origin: HashMap<String, String>,
+    edges: BTreeMap<String, EdgeNode>,
+    metrics: CdnMetrics,
+}
+
+impl Cdn {
+    pub fn new(topology: GeoTopology, origin_region: &str) -> Self {
+        Self {
+            topology,
+            origin_region: origin_region.to_string(),
+            origin: HashMap::new(),
+            edges: BTreeMap::new(),
+            metrics: CdnMetrics::default(),
+        }
+    }
+
+    /// 在源站发布内容
+    pub fn publish(&mut self, url: &str, body: &str) {
+        self.origin.insert(url.to_string(), body.to_string());
+    }
+
+    pub fn add_edge(&mut self, region: &str) {
+        self.edges.insert(region.to_string(), EdgeNode { healthy: true, cache: HashMap::new() });
+    }
+
+    pub fn set_edge_healthy(&mut self, region: &str, healthy: bool) {
+        if let Some(edge) = self.edges.get_mut(region) {
+            edge.healthy = healthy;
+        }
+    }
+
+    /// 客户端请求内容，由最近的健康边缘节点响应
+    pub fn request(&mut self, client_region: &str, url: &str) -> Result<CdnResponse, CdnError> {
+        let healthy: Vec<&str> = self.edges.iter()
+            .filter(|(_, edge)| edge.healthy)
+            .map(|(region, _)| region.as_str())
+            .collect();
+        let edge_region = self.topology.nearest(client_region, &healthy)
+            .ok_or(CdnError::NoHealthyEdge)?
+            .to_string();
+        let client_latency = self.topology.latency(client_region, &edge_region).unwrap_or_default();
+
+        let edge = self.edges.get_mut(&edge_region).expect("边缘节点来自 edges");
+        if let Some(body) = edge.cache.get(url) {
+            self.metrics.hits += 1;
+            return Ok(CdnResponse { edge: edge_region, status: CacheStatus::Hit, body: body.clone(), latency_ms: client_latency });
+        }
+
+        let body = self.origin.get(url).cloned().ok_or_else(|| CdnError::NotFound(url.to_string()))?;
+        edge.cache.insert(url.to_string(), body.clone());
+        self.metrics.misses += 1;
+        let origin_latency = self.topology.latency(&edge_region, &self.origin_region).unwrap_or_default();
+        Ok(CdnResponse { edge: edge_region, status: CacheStatus::Miss, body, latency_ms: client_latency + origin_latency })
+    }
+
+    /// 把源站内容推送到所有健康的边缘节点，源站没有的URL被跳过；返回实际预取的URL数
+    pub fn prefetch(&mut self, urls: &[&str]) -> usize {
+        let mut prefetched = 0;
+        for url in urls {
+            let Some(body) = self.origin.get(*url) else {
+                continue;
+            };
+            for edge in self.edges.values_mut().filter(|edge| edge.healthy) {
+                edge.cache.insert(url.to_string(), body.clone());
+            }
+            prefetched += 1;
+        }
+        prefetched
+    }
+
+    /// 预取访问日志中最常访问的 `top_n` 个URL，次数相同时按URL排序；返回预取的URL
+    pub fn warm_from_access_log(&mut self, log: &[&str], top_n: usize) -> Vec<String> {
+        let mut frequency: HashMap<&str, usize> = HashMap::new();
+        for url in log {
+            *frequency.entry(url).or_insert(0) += 1;
+        }
+        let mut ranked: Vec<(&str, usize)> = frequency.into_iter()
+            .filter(|(url, _)| self.origin.contains_key(*url))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let hottest: Vec<&str> = ranked.into_iter().take(top_n).map(|(url, _)| url).collect();
+        self.prefetch(&hottest);
+        hottest.into_iter().map(str::to_string).collect()
+    }
+
+    pub fn metrics(&self) -> CdnMetrics {
+        self.metrics
+    }
+
+    pub fn reset_metrics(&mut self) {
+        self.metrics = CdnMetrics::default();
+    }
+}
+
+/// CDN模式演示
+pub fn demo_cdn() {
+    println!("=== CDN模式演示 ===\n");
+
+    let mut cdn = Cdn::new(GeoTopology::global_sample(), "us-east");
+    cdn.publish("/index.html", "<h1>首页</h1>");
+    for region in ["us-west", "eu-west", "ap-northeast"] {
+        cdn.add_edge(region);
+    }
+
+    for client in ["ap-southeast", "ap-southeast", "eu-west"] {
+        match cdn.request(client, "/index.html") {
+            Ok(response) => println!("{} 请求 /index.html -> {} {} ({}ms)", client, response.edge, response.status, response.latency_ms),
+            Err(e) => println!("{} 请求失败: {}", client, e),
+        }
+    }
+
+    println!("\nap-northeast 边缘节点故障:");
+    cdn.set_edge_healthy("ap-northeast", false);
+    if let Ok(response) = cdn.request("ap-southeast", "/index.html") {
+        println!("ap-southeast 请求 /index.html -> {} {} ({}ms)", response.edge, response.status, response.latency_ms);
+    }
+
+    // 缓存预热：冷启动与按访问日志预热的命中率对比
+    let access_log = ["/index.html", "/app.js", "/index.html", "/style.css", "/app.js", "/index.html", "/about.html"];
+    let build = || {
+        let mut cdn = Cdn::new(GeoTopology::global_sample(), "us-east");
+        for url in ["/index.html", "/app.js", "/style.css", "/about.html"] {
+            cdn.publish(url, "内容");
+        }
+        cdn.add_edge("eu-west");
+        cdn
+    };
+    let replay = |cdn: &mut Cdn| {
+        for url in access_log {
+            let _ = cdn.request("eu-west", url);
+        }
+        cdn.metrics()
+    };
+    let mut cold = build();
+    let cold_metrics = replay(&mut cold);
+    let mut warmed = build();
+    let prefetched = warmed.warm_from_access_log(&access_log, 2);
+    let warm_metrics = replay(&mut warmed);
+    println!("\n冷启动命中率: {:.0}%", cold_metrics.hit_ratio() * 100.0);
+    println!("预热 {:?} 后命中率: {:.0}%", prefetched, warm_metrics.hit_ratio() * 100.0);
+
+    println!("\n【CDN模式特点】");
+    println!("✓ 就近接入 - 用户连接最近的边缘节点");
+    println!("✓ 边缘缓存 - 命中缓存时无需回源");
+    println!("✓ 故障转移 - 边缘节点故障时由次近节点响应");
+    println!("✓ 缓存预热 - 热门内容提前推送到边缘，提高命中率");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_edge_caches_after_first_miss() {
+        let mut cdn = Cdn::new(GeoTopology::global_sample(), "us-east");
+        cdn.publish("/logo.png", "png");
+        cdn.add_edge("us-west");
+        cdn.add_edge("ap-northeast");
+
+        let first = cdn.request("ap-southeast", "/logo.png").unwrap();
+        assert_eq!((first.edge.as_str(), first.status, first.latency_ms), ("ap-northeast", CacheStatus::Miss, 70 + 170));
+        let second = cdn.request("ap-southeast", "/logo.png").unwrap();
+        assert_eq!((second.status, second.latency_ms), (CacheStatus::Hit, 70));
+
+        // 最近节点故障后由次近节点响应，它的缓存是空的
+        cdn.set_edge_healthy("ap-northeast", false);
+        let failover = cdn.request("ap-southeast", "/logo.png").unwrap();
+        assert_eq!((failover.edge.as_str(), failover.status), ("us-west", CacheStatus::Miss));
+        assert_eq!(cdn.request("ap-southeast", "/missing"), Err(CdnError::NotFound("/missing".to_string())));
+    }
+
+    fn catalog_cdn() -> Cdn {
+        let mut cdn = Cdn::new(GeoTopology::global_sample(), "us-east");
+        for url in ["/a", "/b", "/c", "/d"] {
+            cdn.publish(url, url);
+        }
+        cdn.add_edge("eu-west");
+        cdn.add_edge("ap-northeast");
+        cdn
+    }
+
+    #[test]
+    fn test_prefetched_urls_hit_on_first_request() {
+        let mut cdn = catalog_cdn();
+        assert_eq!(cdn.prefetch(&["/a", "/missing"]), 1);
+        // 预热不计入指标
+        assert_eq!(cdn.metrics(), CdnMetrics::default());
+
+        for client in ["eu-west", "ap-southeast"] {
+            assert_eq!(cdn.request(client, "/a").unwrap().status, CacheStatus::Hit);
+        }
+        assert_eq!(cdn.request("eu-west", "/b").unwrap().status, CacheStatus::Miss);
+        assert_eq!(cdn.metrics(), CdnMetrics { hits: 2, misses: 1 });
+    }
+
+    #[test]
+    fn test_warming_from_access_log_improves_hit_ratio() {
+        let log = ["/a", "/b", "/a", "/c", "/a", "/b", "/d", "/e"];
+        let replay = |cdn: &mut Cdn| {
+            for url in log {
+                let _ = cdn.request("eu-west", url);
+            }
+            cdn.metrics()
+        };
+
+        let mut cold = catalog_cdn();
+        let before = replay(&mut cold);
+        let mut warmed = catalog_cdn();
+        // "/e" 不在源站，不参与排名
+        assert_eq!(warmed.warm_from_access_log(&log, 2), vec!["/a", "/b"]);
+        let after = replay(&mut warmed);
+
+        assert_eq!(before, CdnMetrics { hits: 3, misses: 4 });
+        assert_eq!(after, CdnMetrics { hits: 5, misses: 2 });
+        assert!(after.hit_ratio() > before.hit_ratio());
+
+        warmed.reset_metrics();
+        assert_eq!(warmed.metrics().hit_ratio(), 0.0);
+    }
+}
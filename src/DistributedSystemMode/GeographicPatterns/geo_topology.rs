@@ -0,0 +1,126 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/GeographicPatterns/geo_topology.rs
+ *
+ * 地理拓扑 (Geo Topology)
+ *
+ * 多区域部署和CDN都需要回答同一个问题："离这个地区最近的可用节点是哪个？"
+ * GeoTopology 保存区域之间的往返延迟表，提供按距离排序和最近节点选择。
+ *
+ * 主要特点：
+ * 1. 对称延迟表 - set_latency(a, b) 同时设置 a->b 和 b->a，区域到自身的延迟为0
+ * 2. 最近选择 - nearest 在给定候选中选延迟最低的一个，调用方先过滤掉不健康的区域
+ * 3. 容错 - 未知区域或没有延迟数据的候选会被跳过，而不是报错
+ * 4. 确定性 - 延迟相同时按区域名排序
+ */
+
+use std::collections::{BTreeSet, HashMap};
+
+/// 区域之间的延迟拓扑，延迟单位为毫秒
+#[derive(Debug, Clone, Default)]
+pub struct GeoTopology {
+    regions: BTreeSet<String>,
+    latencies: HashMap<(String, String), u32>,
+}
+
+impl GeoTopology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 演示和测试使用的五个区域
+    pub fn global_sample() -> Self {
+        let mut topology = Self::new();
+        for (a, b, latency) in [
+            ("us-east", "us-west", 70),
+            ("us-east", "eu-west", 80),
+            ("us-east", "ap-northeast", 170),
+            ("us-east", "ap-southeast", 220),
+            ("us-west", "eu-west", 140),
+            ("us-west", "ap-northeast", 110),
+            ("us-west", "ap-southeast", 170),
+            ("eu-west", "ap-northeast", 230),
+            ("eu-west", "ap-southeast", 160),
+            ("ap-northeast", "ap-southeast", 70),
+        ] {
+            topology.set_latency(a, b, latency);
+        }
+        topology
+    }
+
+    pub fn add_region(&mut self, region: &str) {
+        self.regions.insert(region.to_string());
+    }
+
+    /// 设置两个区域之间的往返延迟，未知的区域会自动加入
+    pub fn set_latency(&mut self, a: &str, b: &str, latency_ms: u32) {
+        self.add_region(a);
+        self.add_region(b);
+        self.latencies.insert((a.to_string(), b.to_string()), latency_ms);
+        self.latencies.insert((b.to_string(), a.to_string()), latency_ms);
+    }
+
+    pub fn contains(&self, region: &str) -> bool {
+        self.regions.contains(region)
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = &str> {
+        self.regions.iter().map(String::as_str)
+    }
+
+    /// 两个区域之间的延迟，区域未知或没有延迟数据时返回 None
+    pub fn latency(&self, from: &str, to: &str) -> Option<u32> {
+        if from == to {
+            return self.contains(from).then_some(0);
+        }
+        self.latencies.get(&(from.to_string(), to.to_string())).copied()
+    }
+
+    /// 在候选区域中选出离 `from` 最近的一个
+    pub fn nearest<'a>(&self, from: &str, candidates: &[&'a str]) -> Option<&'a str> {
+        candidates.iter()
+            .filter_map(|&candidate| self.latency(from, candidate).map(|latency| (latency, candidate)))
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+
+    /// 所有可达区域按离 `from` 的延迟排序（包括 `from` 自身）
+    pub fn sorted_by_proximity(&self, from: &str) -> Vec<(String, u32)> {
+        let mut sorted: Vec<(String, u32)> = self.regions()
+            .filter_map(|region| self.latency(from, region).map(|latency| (region.to_string(), latency)))
+            .collect();
+        sorted.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        sorted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_respects_latency_table() {
+        let topology = GeoTopology::global_sample();
+        let all: Vec<&str> = topology.regions().collect();
+
+        assert_eq!(topology.nearest("ap-southeast", &all), Some("ap-southeast"));
+        assert_eq!(topology.nearest("ap-southeast", &["us-east", "eu-west", "ap-northeast"]), Some("ap-northeast"));
+        // 排除不健康的最近区域后选择次优区域
+        assert_eq!(topology.nearest("ap-southeast", &["us-east", "eu-west"]), Some("eu-west"));
+
+        let proximity: Vec<String> = topology.sorted_by_proximity("us-east").into_iter().map(|(region, _)| region).collect();
+        assert_eq!(proximity, vec!["us-east", "us-west", "eu-west", "ap-northeast", "ap-southeast"]);
+    }
+
+    #[test]
+    fn test_unknown_regions_are_skipped() {
+        let mut topology = GeoTopology::global_sample();
+        assert_eq!(topology.nearest("mars", &["us-east"]), None);
+        assert!(topology.sorted_by_proximity("mars").is_empty());
+        assert_eq!(topology.nearest("us-east", &["mars", "eu-west"]), Some("eu-west"));
+
+        // 没有延迟数据的新区域只能到达自身
+        topology.add_region("sa-east");
+        assert_eq!(topology.sorted_by_proximity("sa-east"), vec![("sa-east".to_string(), 0)]);
+        assert_eq!(topology.nearest("us-east", &["sa-east"]), None);
+    }
+}
@@ -1 +1,9 @@
- 
\ No newline at end of file
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/GeographicPatterns/mod.rs
+ * 
+ * 地理分布模式模块 (Geographic Patterns)
+ */
+
+pub mod geo_topology;
+pub mod multi_region_deployment;
+pub mod cdn;
@@ -0,0 +1,153 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/GeographicPatterns/multi_region_deployment.rs
+ *
+ * Multi-Region Deployment模式 (多区域部署)
+ *
+ * 服务同时部署在多个地理区域，用户请求被路由到离自己最近的健康区域；
+ * 某个区域整体故障时，流量自动转移到次近的区域。
+ *
+ * 主要特点：
+ * 1. 就近路由 - 基于 GeoTopology 的延迟表选择最近的健康区域
+ * 2. 区域故障转移 - 区域不健康时自动选择次优区域
+ * 3. 流量统计 - 记录每个区域承接的请求数
+ */
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::geo_topology::GeoTopology;
+
+/// 路由错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegionRoutingError {
+    /// 客户端所在区域不在拓扑中
+    UnknownRegion(String),
+    NoHealthyRegion,
+}
+
+impl fmt::Display for RegionRoutingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionRoutingError::UnknownRegion(region) => write!(f, "未知区域: {}", region),
+            RegionRoutingError::NoHealthyRegion => write!(f, "没有可用的健康区域"),
+        }
+    }
+}
+
+/// 路由结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionRoute {
+    pub region: String,
+    pub latency_ms: u32,
+}
+
+#[derive(Debug, Clone)]
+struct RegionDeployment {
+    healthy: bool,
+    served: u64,
+}
+
+/// 多区域部署
+pub struct MultiRegionDeployment {
+    topology: GeoTopology,
+    deployments: BTreeMap<String, RegionDeployment>,
+}
+
+impl MultiRegionDeployment {
+    pub fn new(topology: GeoTopology) -> Self {
+        Self { topology, deployments: BTreeMap::new() }
+    }
+
+    /// 在区域中部署服务，新部署的区域默认健康
+    pub fn deploy(&mut self, region: &str) {
+        self.deployments.insert(region.to_string(), RegionDeployment { healthy: true, served: 0 });
+    }
+
+    pub fn set_healthy(&mut self, region: &str, healthy: bool) {
+        if let Some(deployment) = self.deployments.get_mut(region) {
+            deployment.healthy = healthy;
+        }
+    }
+
+    pub fn healthy_regions(&self) -> Vec<&str> {
+        self.deployments.iter()
+            .filter(|(_, deployment)| deployment.healthy)
+            .map(|(region, _)| region.as_str())
+            .collect()
+    }
+
+    /// 把客户端请求路由到最近的健康区域
+    pub fn route(&mut self, client_region: &str) -> Result<RegionRoute, RegionRoutingError> {
+        if !self.topology.contains(client_region) {
+            return Err(RegionRoutingError::UnknownRegion(client_region.to_string()));
+        }
+        let region = self.topology.nearest(client_region, &self.healthy_regions())
+            .ok_or(RegionRoutingError::NoHealthyRegion)?
+            .to_string();
+        let latency_ms = self.topology.latency(client_region, &region).unwrap_or_default();
+        if let Some(deployment) = self.deployments.get_mut(&region) {
+            deployment.served += 1;
+        }
+        Ok(RegionRoute { region, latency_ms })
+    }
+
+    /// 每个区域承接的请求数
+    pub fn traffic(&self) -> BTreeMap<&str, u64> {
+        self.deployments.iter().map(|(region, deployment)| (region.as_str(), deployment.served)).collect()
+    }
+}
+
+/// Multi-Region Deployment模式演示
+pub fn demo_multi_region_deployment() {
+    println!("=== Multi-Region Deployment模式演示 ===\n");
+
+    let mut deployment = MultiRegionDeployment::new(GeoTopology::global_sample());
+    for region in ["us-east", "eu-west", "ap-northeast"] {
+        deployment.deploy(region);
+    }
+
+    let clients = ["us-west", "eu-west", "ap-southeast", "mars"];
+    for client in clients {
+        match deployment.route(client) {
+            Ok(route) => println!("{} 的用户 -> {} ({}ms)", client, route.region, route.latency_ms),
+            Err(e) => println!("{} 的用户路由失败: {}", client, e),
+        }
+    }
+
+    println!("\nap-northeast 区域故障:");
+    deployment.set_healthy("ap-northeast", false);
+    for client in ["ap-southeast", "us-west"] {
+        if let Ok(route) = deployment.route(client) {
+            println!("{} 的用户 -> {} ({}ms)", client, route.region, route.latency_ms);
+        }
+    }
+    println!("各区域流量: {:?}", deployment.traffic());
+
+    println!("\n【Multi-Region Deployment模式特点】");
+    println!("✓ 就近路由 - 用户请求进入延迟最低的区域");
+    println!("✓ 故障转移 - 区域故障时流量转移到次近区域");
+    println!("✓ 全球可用 - 单个区域故障不影响整体服务");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failover_to_next_nearest_region() {
+        let mut deployment = MultiRegionDeployment::new(GeoTopology::global_sample());
+        for region in ["us-east", "eu-west", "ap-northeast"] {
+            deployment.deploy(region);
+        }
+
+        assert_eq!(deployment.route("ap-southeast"), Ok(RegionRoute { region: "ap-northeast".to_string(), latency_ms: 70 }));
+        deployment.set_healthy("ap-northeast", false);
+        assert_eq!(deployment.route("ap-southeast"), Ok(RegionRoute { region: "eu-west".to_string(), latency_ms: 160 }));
+
+        assert_eq!(deployment.route("mars"), Err(RegionRoutingError::UnknownRegion("mars".to_string())));
+        deployment.set_healthy("us-east", false);
+        deployment.set_healthy("eu-west", false);
+        assert_eq!(deployment.route("us-west"), Err(RegionRoutingError::NoHealthyRegion));
+        assert_eq!(deployment.traffic()["ap-northeast"], 1);
+    }
+}
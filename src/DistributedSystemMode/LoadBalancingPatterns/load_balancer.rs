@@ -5,10 +5,24 @@
  * 
  * 负载均衡器将传入的请求分发到多个后端服务实例，
  * 以提高系统的可用性、性能和可扩展性。
+ * 
+ * 策略：
+ * - 轮询 (RoundRobin)
+ * - 加权随机 (WeightedRandom) - 按权重比例随机选择，使用带种子的RNG便于复现
+ * - 二选一 (P2C) - 随机挑两台，选择进行中请求更少的那台
+ * 
+ * 被动异常检测：根据调用方上报的结果统计连续失败次数，超过阈值后暂时把服务器
+ * 移出候选池，冷却期过后自动重新加入；重复被驱逐时冷却时间指数增长。
  */
 
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::DistributedSystemMode::ResiliencePatterns::time_source::{system_time_source, FakeClock, TimeSource};
 
 #[derive(Debug, Clone)]
 pub struct Server {
@@ -54,9 +68,123 @@ impl LoadBalancingStrategy for RoundRobinStrategy {
     }
 }
 
+/// 加权随机策略 - 被选中的概率与权重成正比
+pub struct WeightedRandomStrategy {
+    rng: Mutex<StdRng>,
+}
+
+impl WeightedRandomStrategy {
+    /// 相同的种子得到相同的选择序列
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl LoadBalancingStrategy for WeightedRandomStrategy {
+    fn select_server(&self, servers: &[Server]) -> Option<usize> {
+        let total_weight: u64 = servers.iter()
+            .filter(|server| server.is_healthy)
+            .map(|server| server.weight as u64)
+            .sum();
+        if total_weight == 0 {
+            return None;
+        }
+
+        let mut remaining = self.rng.lock().unwrap().gen_range(0..total_weight);
+        for (index, server) in servers.iter().enumerate().filter(|(_, server)| server.is_healthy) {
+            if remaining < server.weight as u64 {
+                return Some(index);
+            }
+            remaining -= server.weight as u64;
+        }
+        None
+    }
+}
+
+/// 二选一策略 (Power of Two Choices) - 随机挑两台健康服务器，选择进行中请求更少的那台
+///
+/// 只比较两台就能避开最繁忙的实例，又不需要像最少连接那样扫描全部服务器。
+pub struct P2CStrategy {
+    rng: Mutex<StdRng>,
+}
+
+impl P2CStrategy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl LoadBalancingStrategy for P2CStrategy {
+    fn select_server(&self, servers: &[Server]) -> Option<usize> {
+        let healthy: Vec<usize> = servers.iter()
+            .enumerate()
+            .filter(|(_, server)| server.is_healthy)
+            .map(|(index, _)| index)
+            .collect();
+        if healthy.len() < 2 {
+            return healthy.first().copied();
+        }
+
+        let mut rng = self.rng.lock().unwrap();
+        let first = rng.gen_range(0..healthy.len());
+        // 第二个候选从剩余的服务器中选，保证两个候选不同
+        let mut second = rng.gen_range(0..healthy.len() - 1);
+        if second >= first {
+            second += 1;
+        }
+
+        let (a, b) = (healthy[first], healthy[second]);
+        if servers[b].active_connections < servers[a].active_connections {
+            Some(b)
+        } else {
+            Some(a)
+        }
+    }
+}
+
+/// 异常检测配置
+#[derive(Debug, Clone)]
+pub struct OutlierDetectionConfig {
+    /// 连续失败多少次后驱逐
+    pub consecutive_failures: u32,
+    /// 首次驱逐的时长，之后每次翻倍
+    pub base_ejection_time: Duration,
+    pub max_ejection_time: Duration,
+}
+
+impl Default for OutlierDetectionConfig {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: 5,
+            base_ejection_time: Duration::from_secs(30),
+            max_ejection_time: Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct OutlierState {
+    consecutive_failures: u32,
+    ejection_count: u32,
+    ejected_until: Option<Instant>,
+}
+
+impl OutlierState {
+    fn is_ejected(&self, now: Instant) -> bool {
+        self.ejected_until.is_some_and(|until| until > now)
+    }
+}
+
 pub struct LoadBalancer {
     servers: Vec<Server>,
     strategy: Box<dyn LoadBalancingStrategy>,
+    outlier_detection: Option<OutlierDetectionConfig>,
+    outliers: HashMap<String, OutlierState>,
+    time: Arc<dyn TimeSource>,
 }
 
 impl LoadBalancer {
@@ -64,18 +192,101 @@ impl LoadBalancer {
         Self {
             servers: Vec::new(),
             strategy,
+            outlier_detection: None,
+            outliers: HashMap::new(),
+            time: system_time_source(),
         }
     }
     
+    /// 使用指定的时间源计算驱逐的冷却时间
+    pub fn with_time_source(mut self, time: Arc<dyn TimeSource>) -> Self {
+        self.time = time;
+        self
+    }
+    
+    /// 启用被动异常检测
+    pub fn with_outlier_detection(mut self, config: OutlierDetectionConfig) -> Self {
+        self.outlier_detection = Some(config);
+        self
+    }
+    
     pub fn add_server(&mut self, server: Server) {
         self.servers.push(server);
     }
     
     pub fn get_server(&self) -> Option<&Server> {
-        let index = self.strategy.select_server(&self.servers)?;
+        let index = self.select_index()?;
         self.servers.get(index)
     }
     
+    /// 选择服务器并把它的进行中请求数加一，返回服务器ID
+    pub fn start_request(&mut self) -> Option<String> {
+        let index = self.select_index()?;
+        let server = self.servers.get_mut(index)?;
+        server.active_connections += 1;
+        Some(server.id.clone())
+    }
+    
+    /// 请求结束，进行中请求数减一
+    pub fn finish_request(&mut self, server_id: &str) {
+        if let Some(server) = self.servers.iter_mut().find(|server| server.id == server_id) {
+            server.active_connections = server.active_connections.saturating_sub(1);
+        }
+    }
+    
+    /// 上报一次调用结果，连续失败达到阈值时驱逐服务器
+    ///
+    /// 成功只清零连续失败计数，驱逐次数会保留，再次被驱逐时冷却时间更长。
+    pub fn record_result(&mut self, server_id: &str, ok: bool) {
+        let Some(config) = &self.outlier_detection else {
+            return;
+        };
+        let now = self.time.now();
+        let state = self.outliers.entry(server_id.to_string()).or_default();
+        // 驱逐期间完成的请求不再计入
+        if state.is_ejected(now) {
+            return;
+        }
+        if ok {
+            state.consecutive_failures = 0;
+            return;
+        }
+
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= config.consecutive_failures {
+            let factor = 2u32.saturating_pow(state.ejection_count);
+            let ejection_time = config.base_ejection_time.saturating_mul(factor).min(config.max_ejection_time);
+            state.ejection_count += 1;
+            state.consecutive_failures = 0;
+            state.ejected_until = Some(now + ejection_time);
+        }
+    }
+    
+    /// 服务器当前是否被驱逐
+    pub fn is_ejected(&self, server_id: &str) -> bool {
+        self.outliers.get(server_id).is_some_and(|state| state.is_ejected(self.time.now()))
+    }
+    
+    /// 服务器累计被驱逐的次数
+    pub fn ejection_count(&self, server_id: &str) -> u32 {
+        self.outliers.get(server_id).map_or(0, |state| state.ejection_count)
+    }
+    
+    /// 被驱逐的服务器对策略表现为不健康，索引与 `servers` 保持一致
+    fn select_index(&self) -> Option<usize> {
+        if self.outliers.is_empty() {
+            return self.strategy.select_server(&self.servers);
+        }
+        let now = self.time.now();
+        let candidates: Vec<Server> = self.servers.iter()
+            .map(|server| {
+                let ejected = self.outliers.get(&server.id).is_some_and(|state| state.is_ejected(now));
+                Server { is_healthy: server.is_healthy && !ejected, ..server.clone() }
+            })
+            .collect();
+        self.strategy.select_server(&candidates)
+    }
+    
     pub fn set_server_health(&mut self, server_id: &str, healthy: bool) {
         for server in &mut self.servers {
             if server.id == server_id {
@@ -118,9 +329,193 @@ pub fn demo_load_balancer() {
         }
     }
     
+    // 加权随机：server2 的权重是 server1 的3倍
+    let mut weighted = LoadBalancer::new(Box::new(WeightedRandomStrategy::new(42)));
+    for (id, weight) in [("server1", 1), ("server2", 3)] {
+        weighted.add_server(Server {
+            id: id.to_string(),
+            host: "192.168.1.20".to_string(),
+            port: 8080,
+            weight,
+            active_connections: 0,
+            is_healthy: true,
+        });
+    }
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for _ in 0..1000 {
+        if let Some(server) = weighted.get_server() {
+            *counts.entry(server.id.clone()).or_insert(0) += 1;
+        }
+    }
+    println!("\n加权随机 1000 次分布: server1={}, server2={}",
+             counts.get("server1").unwrap_or(&0), counts.get("server2").unwrap_or(&0));
+    
+    // P2C：进行中的请求越多，越不容易被选中
+    let mut p2c = LoadBalancer::new(Box::new(P2CStrategy::new(7)));
+    for id in ["server1", "server2", "server3"] {
+        p2c.add_server(Server {
+            id: id.to_string(),
+            host: "192.168.1.30".to_string(),
+            port: 8080,
+            weight: 1,
+            active_connections: 0,
+            is_healthy: true,
+        });
+    }
+    let in_flight: Vec<String> = (0..6).filter_map(|_| p2c.start_request()).collect();
+    println!("P2C 6个并发请求分配: {:?}", in_flight);
+    
+    // 异常检测：server3 连续失败3次后被驱逐；冷却时间用虚拟时钟计时，演示时不必真实等待
+    let clock = FakeClock::new();
+    let config = OutlierDetectionConfig { consecutive_failures: 3, ..OutlierDetectionConfig::default() };
+    let cooldown = config.base_ejection_time;
+    let mut p2c = p2c.with_outlier_detection(config).with_time_source(clock.as_time_source());
+    for _ in 0..3 {
+        p2c.record_result("server3", false);
+    }
+    println!("server3 已被驱逐: {}, 累计驱逐 {} 次", p2c.is_ejected("server3"), p2c.ejection_count("server3"));
+    let after_ejection: Vec<String> = (0..6).filter_map(|_| p2c.start_request()).collect();
+    println!("驱逐 server3 后的分配: {:?}", after_ejection);
+    clock.advance(cooldown);
+    println!("冷却 {:?} 后 server3 已被驱逐: {}", cooldown, p2c.is_ejected("server3"));
+    
     println!("\n【Load Balancer模式特点】");
     println!("✓ 请求分发 - 将请求分发到多个后端服务");
     println!("✓ 健康检查 - 只向健康的服务器发送请求");
     println!("✓ 多种策略 - 支持轮询、加权、最少连接等算法");
     println!("✓ 故障转移 - 自动处理服务器故障");
-} 
\ No newline at end of file
+    println!("✓ 异常检测 - 连续失败的服务器被暂时驱逐，冷却后重新加入");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, weight: u32, active_connections: u32) -> Server {
+        Server {
+            id: id.to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            weight,
+            active_connections,
+            is_healthy: true,
+        }
+    }
+
+    #[test]
+    fn test_weighted_random_follows_weights() {
+        let servers = vec![server("a", 1, 0), server("b", 3, 0), server("c", 6, 0), server("zero", 0, 0)];
+        let pick_counts = |seed| {
+            let strategy = WeightedRandomStrategy::new(seed);
+            let mut counts = [0u32; 4];
+            for _ in 0..10_000 {
+                counts[strategy.select_server(&servers).unwrap()] += 1;
+            }
+            counts
+        };
+
+        let counts = pick_counts(1);
+        // 期望比例 10% / 30% / 60%，允许 ±2% 的误差
+        for (index, expected) in [(0, 1_000), (1, 3_000), (2, 6_000)] {
+            assert!((counts[index] as i64 - expected).abs() < 200, "{:?}", counts);
+        }
+        assert_eq!(counts[3], 0);
+        // 相同种子结果可复现
+        assert_eq!(pick_counts(1), counts);
+    }
+
+    #[test]
+    fn test_p2c_avoids_busiest_backend() {
+        let mut balancer = LoadBalancer::new(Box::new(P2CStrategy::new(3)));
+        balancer.add_server(server("busy", 1, 50));
+        balancer.add_server(server("idle-1", 1, 0));
+        balancer.add_server(server("idle-2", 1, 0));
+
+        // 每个候选对中至少有一台空闲服务器，繁忙的服务器永远不会被选中
+        for _ in 0..40 {
+            assert_ne!(balancer.start_request().as_deref(), Some("busy"));
+        }
+        let connections = |balancer: &LoadBalancer, id: &str| {
+            balancer.servers.iter().find(|server| server.id == id).unwrap().active_connections
+        };
+        assert_eq!(connections(&balancer, "busy"), 50);
+        // 两台空闲服务器分摊了请求
+        assert_eq!(connections(&balancer, "idle-1") + connections(&balancer, "idle-2"), 40);
+        assert!(connections(&balancer, "idle-1").abs_diff(connections(&balancer, "idle-2")) <= 10);
+
+        balancer.finish_request("idle-1");
+        assert_eq!(connections(&balancer, "idle-1") + connections(&balancer, "idle-2"), 39);
+    }
+
+    fn outlier_balancer(clock: &FakeClock) -> LoadBalancer {
+        let mut balancer = LoadBalancer::new(Box::new(RoundRobinStrategy::new()))
+            .with_outlier_detection(OutlierDetectionConfig {
+                consecutive_failures: 3,
+                base_ejection_time: Duration::from_millis(50),
+                max_ejection_time: Duration::from_millis(150),
+            })
+            .with_time_source(clock.as_time_source());
+        balancer.add_server(server("good", 1, 0));
+        balancer.add_server(server("flaky", 1, 0));
+        balancer
+    }
+
+    fn picks(balancer: &mut LoadBalancer, count: usize) -> Vec<String> {
+        (0..count).filter_map(|_| balancer.start_request()).collect()
+    }
+
+    #[test]
+    fn test_repeated_failures_eject_backend_until_cooldown() {
+        let clock = FakeClock::new();
+        let mut balancer = outlier_balancer(&clock);
+
+        // 中间的成功清零连续失败计数，不会被驱逐
+        balancer.record_result("flaky", false);
+        balancer.record_result("flaky", false);
+        balancer.record_result("flaky", true);
+        balancer.record_result("flaky", false);
+        assert!(!balancer.is_ejected("flaky"));
+
+        balancer.record_result("flaky", false);
+        balancer.record_result("flaky", false);
+        assert!(balancer.is_ejected("flaky"));
+        assert!(picks(&mut balancer, 6).iter().all(|id| id == "good"));
+
+        // 冷却结束后重新加入候选池
+        clock.advance(Duration::from_millis(49));
+        assert!(balancer.is_ejected("flaky"));
+        clock.advance(Duration::from_millis(1));
+        assert!(!balancer.is_ejected("flaky"));
+        assert!(picks(&mut balancer, 6).iter().any(|id| id == "flaky"));
+    }
+
+    #[test]
+    fn test_repeat_offenses_double_ejection_time() {
+        let clock = FakeClock::new();
+        let mut balancer = outlier_balancer(&clock);
+        let eject = |balancer: &mut LoadBalancer| {
+            for _ in 0..3 {
+                balancer.record_result("flaky", false);
+            }
+        };
+
+        eject(&mut balancer);
+        clock.advance(Duration::from_millis(50));
+        assert!(!balancer.is_ejected("flaky"));
+
+        // 第二次驱逐持续 100ms，70ms 后仍处于驱逐状态
+        eject(&mut balancer);
+        assert_eq!(balancer.ejection_count("flaky"), 2);
+        clock.advance(Duration::from_millis(70));
+        assert!(balancer.is_ejected("flaky"));
+        clock.advance(Duration::from_millis(30));
+        assert!(!balancer.is_ejected("flaky"));
+
+        // 第三次驱逐本应持续 200ms，受上限限制只持续 150ms
+        eject(&mut balancer);
+        clock.advance(Duration::from_millis(149));
+        assert!(balancer.is_ejected("flaky"));
+        clock.advance(Duration::from_millis(1));
+        assert!(!balancer.is_ejected("flaky"));
+    }
+}
@@ -0,0 +1,69 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ObservabilityPatterns/instrument.rs
+ *
+ * 指标埋点装饰器 (Instrumented Decorator)
+ *
+ * 装饰器模式应用于可观测性：`instrumented!` 宏为一个trait生成装饰器结构体，
+ * 装饰器实现同一个trait，把每个方法调用转发给内部对象，
+ * 同时向 MetricsRegistry 上报调用次数和耗时。业务实现本身不需要任何改动。
+ *
+ * 对于前缀为 `payment` 的装饰器，调用 `process_payment` 会：
+ * - 计数器 `payment.process_payment` 加一
+ * - 向计时指标 `payment.process_payment` 追加一个耗时样本
+ *
+ * 用法：
+ *
+ *     crate::instrumented! {
+ *         pub struct InstrumentedGateway: PaymentGateway {
+ *             fn process_payment(&self, amount: f64) -> Result<PaymentResponse, GatewayError>;
+ *         }
+ *     }
+ *
+ * trait中的方法都需要列出，且只支持 `&self` 方法。
+ */
+
+pub use super::metrics_collection::MetricsRegistry;
+
+/// 为trait生成上报调用次数和耗时的装饰器
+///
+/// 生成的结构体通过 `new(inner, metrics, prefix)` 创建，
+/// `inner` 可以是 `Box<dyn Trait + Send + Sync>` 或 `Arc<dyn Trait + Send + Sync>`。
+#[macro_export]
+macro_rules! instrumented {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident : $trait:path {
+            $( fn $method:ident (&self $(, $arg:ident : $arg_ty:ty )* $(,)? ) -> $ret:ty ; )*
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            inner: std::sync::Arc<dyn $trait + Send + Sync>,
+            metrics: $crate::DistributedSystemMode::ObservabilityPatterns::instrument::MetricsRegistry,
+            prefix: String,
+        }
+
+        impl $name {
+            $vis fn new(
+                inner: impl Into<std::sync::Arc<dyn $trait + Send + Sync>>,
+                metrics: $crate::DistributedSystemMode::ObservabilityPatterns::instrument::MetricsRegistry,
+                prefix: &str,
+            ) -> Self {
+                Self { inner: inner.into(), metrics, prefix: prefix.to_string() }
+            }
+        }
+
+        impl $trait for $name {
+            $(
+                fn $method(&self $(, $arg: $arg_ty )*) -> $ret {
+                    let metric = format!("{}.{}", self.prefix, stringify!($method));
+                    let start = std::time::Instant::now();
+                    let result = self.inner.$method($( $arg ),*);
+                    self.metrics.increment(&metric);
+                    self.metrics.record_timing(&metric, start.elapsed());
+                    result
+                }
+            )*
+        }
+    };
+}
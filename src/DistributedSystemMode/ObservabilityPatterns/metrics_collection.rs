@@ -0,0 +1,123 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ObservabilityPatterns/metrics_collection.rs
+ *
+ * Metrics Collection模式 (指标收集)
+ *
+ * 指标收集模式在运行时记录系统和业务指标（调用次数、耗时等），
+ * 供监控系统聚合、告警和绘制看板。
+ * MetricsRegistry 是进程内的指标注册表，克隆后共享同一份数据，
+ * 可以交给多个组件分别上报。
+ */
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct MetricsData {
+    counters: BTreeMap<String, u64>,
+    timings: BTreeMap<String, Vec<Duration>>,
+}
+
+/// 计时指标的汇总
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingSummary {
+    pub count: usize,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl TimingSummary {
+    pub fn average(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.count as u32
+        }
+    }
+}
+
+/// 指标注册表
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRegistry {
+    data: Arc<Mutex<MetricsData>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 计数器加一
+    pub fn increment(&self, name: &str) {
+        *self.data.lock().unwrap().counters.entry(name.to_string()).or_insert(0) += 1;
+    }
+
+    /// 记录一次耗时样本
+    pub fn record_timing(&self, name: &str, duration: Duration) {
+        self.data.lock().unwrap().timings.entry(name.to_string()).or_default().push(duration);
+    }
+
+    /// 执行操作并记录其耗时
+    pub fn time<T, F>(&self, name: &str, operation: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let start = Instant::now();
+        let result = operation();
+        self.record_timing(name, start.elapsed());
+        result
+    }
+
+    /// 计数器当前值，未上报过的计数器为0
+    pub fn counter(&self, name: &str) -> u64 {
+        self.data.lock().unwrap().counters.get(name).copied().unwrap_or(0)
+    }
+
+    /// 按记录顺序返回耗时样本
+    pub fn timings(&self, name: &str) -> Vec<Duration> {
+        self.data.lock().unwrap().timings.get(name).cloned().unwrap_or_default()
+    }
+
+    /// 耗时样本汇总，没有样本时返回None
+    pub fn timing_summary(&self, name: &str) -> Option<TimingSummary> {
+        let data = self.data.lock().unwrap();
+        let samples = data.timings.get(name).filter(|samples| !samples.is_empty())?;
+        Some(TimingSummary {
+            count: samples.len(),
+            total: samples.iter().sum(),
+            max: samples.iter().copied().max().unwrap_or_default(),
+        })
+    }
+
+    /// 所有计数器的快照，按名称排序
+    pub fn counters(&self) -> BTreeMap<String, u64> {
+        self.data.lock().unwrap().counters.clone()
+    }
+}
+
+/// Metrics Collection模式演示
+pub fn demo_metrics_collection() {
+    println!("=== Metrics Collection模式演示 ===\n");
+
+    let metrics = MetricsRegistry::new();
+    for order in 1..=3 {
+        metrics.time("orders.create", || {
+            std::thread::sleep(Duration::from_millis(5 * order));
+        });
+        metrics.increment("orders.create");
+    }
+
+    for (name, value) in metrics.counters() {
+        println!("计数器 {} = {}", name, value);
+    }
+    if let Some(summary) = metrics.timing_summary("orders.create") {
+        println!("耗时 orders.create: {}次, 平均{}ms, 最大{}ms",
+                 summary.count, summary.average().as_millis(), summary.max.as_millis());
+    }
+
+    println!("\n【Metrics Collection模式特点】");
+    println!("✓ 运行时指标 - 记录调用次数和耗时");
+    println!("✓ 共享注册表 - 多个组件向同一注册表上报");
+    println!("✓ 与装饰器结合 - instrumented! 宏为任意trait自动埋点");
+}
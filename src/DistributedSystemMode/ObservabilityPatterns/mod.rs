@@ -13,9 +13,6 @@ pub mod centralized_logging {
     }
 }
 
-pub mod metrics_collection {
-    pub fn demo_metrics_collection() {
-        println!("=== Metrics Collection模式演示 ===");
-        println!("系统和业务指标收集与监控");
-    }
-} 
\ No newline at end of file
+pub mod metrics_collection;
+
+pub mod instrument; 
\ No newline at end of file
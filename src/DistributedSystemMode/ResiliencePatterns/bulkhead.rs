@@ -0,0 +1,179 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ResiliencePatterns/bulkhead.rs
+ *
+ * Bulkhead模式 (舱壁)
+ *
+ * 舱壁模式为每个下游资源分配独立的并发名额，就像船舱的隔板：
+ * 一个下游变慢只会占满它自己的名额，其他下游的调用不受影响。
+ *
+ * 主要特点：
+ * 1. 资源隔离 - 每个舱壁有独立的并发上限
+ * 2. 有界等待 - 名额用尽时最多等待 `max_wait`，超时后失败而不是无限排队
+ * 3. 许可守卫 - 许可在drop时自动归还名额
+ */
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::time_source::{system_time_source, FakeClock, TimeSource};
+
+// =================
+// 舱壁错误
+// =================
+
+/// 舱壁错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkheadError {
+    /// 名额已满且不等待
+    Full { max_concurrent: usize },
+    /// 等待名额超时
+    WaitTimeout(Duration),
+}
+
+impl fmt::Display for BulkheadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkheadError::Full { max_concurrent } => write!(f, "舱壁已满 (上限 {})", max_concurrent),
+            BulkheadError::WaitTimeout(wait) => write!(f, "等待舱壁名额超时 ({}ms)", wait.as_millis()),
+        }
+    }
+}
+
+// =================
+// 舱壁
+// =================
+
+/// 舱壁配置
+#[derive(Debug, Clone)]
+pub struct BulkheadConfig {
+    pub max_concurrent: usize,
+    /// 名额用尽时的最长等待时间
+    pub max_wait: Duration,
+    /// 等待期间检查名额的间隔
+    pub poll_interval: Duration,
+}
+
+impl Default for BulkheadConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 10,
+            max_wait: Duration::from_millis(100),
+            poll_interval: Duration::from_millis(10),
+        }
+    }
+}
+
+/// 舱壁 - 限制同一资源的并发调用数
+pub struct Bulkhead {
+    name: String,
+    config: BulkheadConfig,
+    in_flight: Mutex<usize>,
+    time: Arc<dyn TimeSource>,
+}
+
+impl Bulkhead {
+    pub fn new(name: &str, config: BulkheadConfig) -> Self {
+        Self { name: name.to_string(), config, in_flight: Mutex::new(0), time: system_time_source() }
+    }
+
+    /// 使用指定的时间源计算等待时间
+    pub fn with_time_source(mut self, time: Arc<dyn TimeSource>) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 当前占用的名额
+    pub fn in_flight(&self) -> usize {
+        *self.in_flight.lock().unwrap()
+    }
+
+    /// 尝试立即获取名额
+    pub fn try_enter(&self) -> Result<BulkheadPermit<'_>, BulkheadError> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if *in_flight >= self.config.max_concurrent {
+            return Err(BulkheadError::Full { max_concurrent: self.config.max_concurrent });
+        }
+        *in_flight += 1;
+        Ok(BulkheadPermit { bulkhead: self })
+    }
+
+    /// 获取名额，名额用尽时最多等待 `max_wait`
+    pub fn enter(&self) -> Result<BulkheadPermit<'_>, BulkheadError> {
+        let started = self.time.now();
+        loop {
+            if let Ok(permit) = self.try_enter() {
+                return Ok(permit);
+            }
+            let waited = self.time.elapsed_since(started);
+            if waited >= self.config.max_wait {
+                return Err(BulkheadError::WaitTimeout(self.config.max_wait));
+            }
+            self.time.sleep(self.config.poll_interval.min(self.config.max_wait - waited));
+        }
+    }
+
+    /// 在舱壁名额保护下执行操作
+    pub fn call<T, F>(&self, operation: F) -> Result<T, BulkheadError>
+    where
+        F: FnOnce() -> T,
+    {
+        let _permit = self.enter()?;
+        Ok(operation())
+    }
+
+    fn release(&self) {
+        *self.in_flight.lock().unwrap() -= 1;
+    }
+}
+
+/// 舱壁许可 - drop时归还名额
+pub struct BulkheadPermit<'a> {
+    bulkhead: &'a Bulkhead,
+}
+
+impl Drop for BulkheadPermit<'_> {
+    fn drop(&mut self) {
+        self.bulkhead.release();
+    }
+}
+
+/// Bulkhead模式演示
+pub fn demo_bulkhead() {
+    println!("=== Bulkhead模式演示 ===\n");
+
+    let config = BulkheadConfig { max_concurrent: 2, max_wait: Duration::from_millis(50), ..BulkheadConfig::default() };
+    // 支付服务的排队等待用虚拟时钟计时，演示时不必真实等待
+    let clock = FakeClock::new();
+    let payments = Bulkhead::new("支付服务", config.clone()).with_time_source(clock.as_time_source());
+    let inventory = Bulkhead::new("库存服务", config);
+
+    // 1. 支付服务变慢，占满了自己的名额
+    let stuck: Vec<_> = (0..2).filter_map(|_| payments.try_enter().ok()).collect();
+    println!("{} 占用名额: {}", payments.name(), payments.in_flight());
+
+    // 2. 新的支付调用等待后失败，库存调用不受影响
+    match payments.call(|| "支付完成") {
+        Ok(result) => println!("{}: {}", payments.name(), result),
+        Err(e) => println!("{}: {} (等待了 {} 次)", payments.name(), e, clock.sleeps().len()),
+    }
+    match inventory.call(|| "库存已扣减") {
+        Ok(result) => println!("{}: {}", inventory.name(), result),
+        Err(e) => println!("{}: {}", inventory.name(), e),
+    }
+
+    // 3. 名额归还后支付调用恢复
+    drop(stuck);
+    if let Ok(result) = payments.call(|| "支付完成") {
+        println!("名额归还后 {}: {}", payments.name(), result);
+    }
+
+    println!("\n【Bulkhead模式特点】");
+    println!("✓ 资源隔离 - 每个下游有独立的并发名额");
+    println!("✓ 有界等待 - 名额用尽时最多等待设定时间");
+    println!("✓ 防止级联故障 - 慢下游不会耗尽调用方的全部线程");
+}
@@ -12,12 +12,17 @@
  * 3. 自动恢复 - 定期尝试调用服务，检测服务是否已恢复
  * 4. 状态管理 - 管理关闭、打开、半开三种状态
  * 5. 指标收集 - 收集调用统计信息用于监控和决策
+ * 6. 按资源分组 - 注册表为每个下游资源惰性创建独立的熔断器
+ *
+ * 恢复超时、调用耗时和统计中的时间戳都从时间源读取，默认使用系统时钟。
  */
 
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 use std::fmt;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use super::time_source::{system_time_source, TimeSource};
 
 // =================
 // 熔断器状态
@@ -167,9 +172,9 @@ impl StatsCollector {
         }
     }
     
-    fn record_call(&mut self, success: bool, duration: Duration) {
+    fn record_call(&mut self, success: bool, duration: Duration, now: Instant) {
         let record = CallRecord {
-            timestamp: Instant::now(),
+            timestamp: now,
             success,
             duration,
         };
@@ -186,10 +191,10 @@ impl StatsCollector {
         self.total_calls += 1;
         if success {
             self.successful_calls += 1;
-            self.last_success_time = Some(Instant::now());
+            self.last_success_time = Some(now);
         } else {
             self.failed_calls += 1;
-            self.last_failure_time = Some(Instant::now());
+            self.last_failure_time = Some(now);
         }
     }
     
@@ -238,17 +243,24 @@ pub struct CircuitBreaker {
     stats: Arc<Mutex<StatsCollector>>,
     state_changed_time: Arc<Mutex<Instant>>,
     half_open_calls: Arc<Mutex<u32>>,
+    time: Arc<dyn TimeSource>,
 }
 
 impl CircuitBreaker {
     /// 创建新的熔断器
     pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self::with_time_source(config, system_time_source())
+    }
+    
+    /// 使用指定的时间源创建熔断器
+    pub fn with_time_source(config: CircuitBreakerConfig, time: Arc<dyn TimeSource>) -> Self {
         Self {
             config: config.clone(),
             state: Arc::new(RwLock::new(CircuitState::Closed)),
             stats: Arc::new(Mutex::new(StatsCollector::new(config.stats_window_size))),
-            state_changed_time: Arc::new(Mutex::new(Instant::now())),
+            state_changed_time: Arc::new(Mutex::new(time.now())),
             half_open_calls: Arc::new(Mutex::new(0)),
+            time,
         }
     }
     
@@ -269,11 +281,11 @@ impl CircuitBreaker {
             return Err(CircuitBreakerError::CircuitOpen);
         }
         
-        let start_time = Instant::now();
+        let start_time = self.time.now();
         
         // 执行调用
         let result = operation();
-        let duration = start_time.elapsed();
+        let duration = self.time.elapsed_since(start_time);
         
         // 处理结果
         match result {
@@ -299,11 +311,11 @@ impl CircuitBreaker {
             return Err(CircuitBreakerError::CircuitOpen);
         }
         
-        let start_time = Instant::now();
+        let start_time = self.time.now();
         
         // 简单的超时模拟 (实际实现中应该使用真正的异步超时机制)
         let result = operation();
-        let duration = start_time.elapsed();
+        let duration = self.time.elapsed_since(start_time);
         
         if duration > self.config.timeout {
             self.on_failure(duration);
@@ -331,7 +343,7 @@ impl CircuitBreaker {
             CircuitState::Open => {
                 // 检查是否到了尝试恢复的时间
                 let state_changed_time = *self.state_changed_time.lock().unwrap();
-                if state_changed_time.elapsed() >= self.config.recovery_timeout {
+                if self.time.elapsed_since(state_changed_time) >= self.config.recovery_timeout {
                     self.transition_to_half_open();
                     true
                 } else {
@@ -354,7 +366,7 @@ impl CircuitBreaker {
     /// 处理成功调用
     fn on_success(&self, duration: Duration) {
         let mut stats = self.stats.lock().unwrap();
-        stats.record_call(true, duration);
+        stats.record_call(true, duration, self.time.now());
         
         let state = *self.state.read().unwrap();
         if state == CircuitState::HalfOpen {
@@ -366,7 +378,7 @@ impl CircuitBreaker {
     /// 处理失败调用
     fn on_failure(&self, duration: Duration) {
         let mut stats = self.stats.lock().unwrap();
-        stats.record_call(false, duration);
+        stats.record_call(false, duration, self.time.now());
         
         let state = *self.state.read().unwrap();
         
@@ -408,7 +420,7 @@ impl CircuitBreaker {
         *state = CircuitState::Closed;
         
         let mut state_changed_time = self.state_changed_time.lock().unwrap();
-        *state_changed_time = Instant::now();
+        *state_changed_time = self.time.now();
         
         let mut half_open_calls = self.half_open_calls.lock().unwrap();
         *half_open_calls = 0;
@@ -422,7 +434,7 @@ impl CircuitBreaker {
         *state = CircuitState::Open;
         
         let mut state_changed_time = self.state_changed_time.lock().unwrap();
-        *state_changed_time = Instant::now();
+        *state_changed_time = self.time.now();
         
         let mut half_open_calls = self.half_open_calls.lock().unwrap();
         *half_open_calls = 0;
@@ -436,7 +448,7 @@ impl CircuitBreaker {
         *state = CircuitState::HalfOpen;
         
         let mut state_changed_time = self.state_changed_time.lock().unwrap();
-        *state_changed_time = Instant::now();
+        *state_changed_time = self.time.now();
         
         let mut half_open_calls = self.half_open_calls.lock().unwrap();
         *half_open_calls = 0;
@@ -444,6 +456,11 @@ impl CircuitBreaker {
         println!("熔断器状态变更: 半开");
     }
     
+    /// 获取熔断器配置
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+    
     /// 获取当前状态
     pub fn get_state(&self) -> CircuitState {
         *self.state.read().unwrap()
@@ -484,6 +501,100 @@ impl CircuitBreaker {
     }
 }
 
+// =================
+// 熔断器注册表
+// =================
+
+/// 注册表中所有熔断器的状态快照
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerRegistrySnapshot {
+    pub breakers: BTreeMap<String, CircuitBreakerStats>,
+}
+
+impl CircuitBreakerRegistrySnapshot {
+    pub fn state_of(&self, name: &str) -> Option<CircuitState> {
+        self.breakers.get(name).map(|stats| stats.state)
+    }
+    
+    /// 当前处于打开状态的资源
+    pub fn open_breakers(&self) -> Vec<&str> {
+        self.breakers.iter()
+            .filter(|(_, stats)| stats.state == CircuitState::Open)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// 熔断器注册表 - 按资源名称惰性创建并复用熔断器
+///
+/// 未单独配置的资源使用共享的默认配置；单独配置只影响之后创建的熔断器。
+pub struct CircuitBreakerRegistry {
+    default_config: CircuitBreakerConfig,
+    overrides: RwLock<HashMap<String, CircuitBreakerConfig>>,
+    breakers: RwLock<HashMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new(default_config: CircuitBreakerConfig) -> Self {
+        Self {
+            default_config,
+            overrides: RwLock::new(HashMap::new()),
+            breakers: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// 为指定资源设置单独的配置
+    pub fn with_override(self, name: &str, config: CircuitBreakerConfig) -> Self {
+        self.overrides.write().unwrap().insert(name.to_string(), config);
+        self
+    }
+    
+    /// 获取资源对应的熔断器，不存在时按配置创建
+    pub fn get(&self, name: &str) -> Arc<CircuitBreaker> {
+        if let Some(breaker) = self.breakers.read().unwrap().get(name) {
+            return Arc::clone(breaker);
+        }
+        
+        let mut breakers = self.breakers.write().unwrap();
+        // 获取写锁期间其他线程可能已经创建
+        let breaker = breakers.entry(name.to_string()).or_insert_with(|| {
+            let config = self.overrides.read().unwrap()
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| self.default_config.clone());
+            Arc::new(CircuitBreaker::new(config))
+        });
+        Arc::clone(breaker)
+    }
+    
+    /// 通过资源对应的熔断器执行调用
+    pub fn call<T, E, F>(&self, name: &str, operation: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        self.get(name).call(operation)
+    }
+    
+    /// 已创建的熔断器数量
+    pub fn len(&self) -> usize {
+        self.breakers.read().unwrap().len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// 所有已创建熔断器的状态快照
+    pub fn snapshot(&self) -> CircuitBreakerRegistrySnapshot {
+        CircuitBreakerRegistrySnapshot {
+            breakers: self.breakers.read().unwrap()
+                .iter()
+                .map(|(name, breaker)| (name.clone(), breaker.get_stats()))
+                .collect(),
+        }
+    }
+}
+
 // =================
 // 模拟服务
 // =================
@@ -661,10 +772,73 @@ pub fn demo_circuit_breaker() {
         println!("  最后成功时间: {}秒前", last_success.elapsed().as_secs());
     }
     
+    // 6. 按资源分组的熔断器
+    println!("\n6. 熔断器注册表:");
+    let registry = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+        failure_threshold: 2,
+        ..CircuitBreakerConfig::default()
+    });
+    for i in 1..=3 {
+        let _ = registry.call("支付服务", || Err::<String, String>(format!("支付请求{}失败", i)));
+        let _ = registry.call("库存服务", || Ok::<String, String>(format!("库存请求{}成功", i)));
+    }
+    for (name, stats) in &registry.snapshot().breakers {
+        println!("  {}: 状态 {}, 总调用 {}, 被拒绝 {}", name, stats.state, stats.total_calls, stats.rejected_calls);
+    }
+    
     println!("\n【Circuit Breaker模式特点】");
     println!("✓ 故障检测 - 监控服务调用的成功率和响应时间");
     println!("✓ 快速失败 - 在服务不可用时立即返回错误，避免等待");
     println!("✓ 自动恢复 - 定期尝试调用服务，检测服务是否已恢复");
     println!("✓ 状态管理 - 管理关闭、打开、半开三种状态");
     println!("✓ 指标收集 - 收集调用统计信息用于监控和决策");
+    println!("✓ 按资源分组 - 注册表为每个下游资源维护独立的熔断器");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_trip_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: 2,
+            recovery_timeout: Duration::from_secs(60),
+            ..CircuitBreakerConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_same_key_shares_breaker() {
+        let registry = CircuitBreakerRegistry::new(fast_trip_config());
+
+        let first = registry.get("orders");
+        let second = registry.get("orders");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(registry.len(), 1);
+
+        // 通过任一引用记录的失败都会累计到同一个熔断器
+        let _ = first.call(|| Err::<(), _>("超时"));
+        let _ = second.call(|| Err::<(), _>("超时"));
+        assert_eq!(registry.get("orders").get_state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_tripping_one_resource_does_not_affect_another() {
+        let registry = CircuitBreakerRegistry::new(fast_trip_config())
+            .with_override("search", CircuitBreakerConfig { failure_threshold: 10, ..fast_trip_config() });
+
+        for _ in 0..3 {
+            let _ = registry.call("payments", || Err::<(), _>("连接被拒绝"));
+            let _ = registry.call("search", || Err::<(), _>("连接被拒绝"));
+        }
+        assert!(registry.call("inventory", || Ok::<_, String>(42)).is_ok());
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.state_of("payments"), Some(CircuitState::Open));
+        assert_eq!(snapshot.breakers["payments"].rejected_calls, 1);
+        // 单独配置的资源阈值更高，尚未熔断
+        assert_eq!(snapshot.state_of("search"), Some(CircuitState::Closed));
+        assert_eq!(snapshot.state_of("inventory"), Some(CircuitState::Closed));
+        assert_eq!(snapshot.open_breakers(), vec!["payments"]);
+    }
 } 
\ No newline at end of file
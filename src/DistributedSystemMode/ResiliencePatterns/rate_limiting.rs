@@ -0,0 +1,364 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ResiliencePatterns/rate_limiting.rs
+ *
+ * Rate Limiting模式 (限流)
+ *
+ * 限流模式控制进入后端的请求量，保护系统稳定。固定的并发上限很难设定：
+ * 设得太低浪费容量，设得太高在后端变慢时又起不到保护作用。
+ * 自适应限流器根据观测到的延迟动态调整并发上限（AIMD）：
+ * 延迟正常时线性增加上限，延迟突增时按比例成倍缩小上限。
+ *
+ * 主要特点：
+ * 1. 无需固定上限 - 上限随后端的实际处理能力变化
+ * 2. 快速退让 - 延迟突增时乘性减小，迅速降低后端压力
+ * 3. 缓慢探测 - 延迟正常时加性增加，逐步试探可用容量
+ * 4. 许可守卫 - 许可在drop时自动归还，失败的请求不产生延迟样本
+ *
+ * 令牌桶则按固定速率限制请求频率：令牌按时间匀速补充，最多攒满桶容量，
+ * 每个请求消耗一个令牌，允许不超过容量的突发流量。
+ */
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::time_source::{system_time_source, FakeClock, TimeSource};
+
+// =================
+// 限流错误
+// =================
+
+/// 限流错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitError {
+    /// 在途请求已达到当前并发上限
+    LimitExceeded { limit: usize, in_flight: usize },
+    /// 令牌桶已空，`retry_after` 后会补充出下一个令牌
+    TokensExhausted { retry_after: Duration },
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitError::LimitExceeded { limit, in_flight } => {
+                write!(f, "并发已达上限 (在途 {} / 上限 {})", in_flight, limit)
+            }
+            RateLimitError::TokensExhausted { retry_after } => {
+                write!(f, "令牌已耗尽，{}ms后重试", retry_after.as_millis())
+            }
+        }
+    }
+}
+
+// =================
+// 自适应限流器
+// =================
+
+/// 自适应限流器配置
+#[derive(Debug, Clone)]
+pub struct AdaptiveLimiterConfig {
+    pub initial_limit: usize,
+    pub min_limit: usize,
+    pub max_limit: usize,
+    /// 延迟阈值 - 超过该值的样本视为延迟突增
+    pub latency_threshold: Duration,
+    /// 每个正常样本增加的上限
+    pub additive_increase: usize,
+    /// 延迟突增时上限乘以的系数 (0.0 - 1.0)
+    pub decrease_factor: f64,
+}
+
+impl Default for AdaptiveLimiterConfig {
+    fn default() -> Self {
+        Self {
+            initial_limit: 10,
+            min_limit: 1,
+            max_limit: 200,
+            latency_threshold: Duration::from_millis(100),
+            additive_increase: 1,
+            decrease_factor: 0.5,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    limit: usize,
+    in_flight: usize,
+}
+
+/// 自适应并发限流器（AIMD）
+pub struct AdaptiveLimiter {
+    config: AdaptiveLimiterConfig,
+    state: Mutex<LimiterState>,
+    time: Arc<dyn TimeSource>,
+}
+
+impl AdaptiveLimiter {
+    pub fn new(config: AdaptiveLimiterConfig) -> Self {
+        let limit = config.initial_limit.clamp(config.min_limit, config.max_limit);
+        Self {
+            config,
+            state: Mutex::new(LimiterState { limit, in_flight: 0 }),
+            time: system_time_source(),
+        }
+    }
+
+    /// 使用指定的时间源测量请求延迟
+    pub fn with_time_source(mut self, time: Arc<dyn TimeSource>) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// 当前允许的并发上限
+    pub fn current_limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    /// 当前在途请求数
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().in_flight
+    }
+
+    /// 尝试获取许可，在途请求已达上限时立即拒绝
+    pub fn try_acquire(&self) -> Result<LimiterPermit<'_>, RateLimitError> {
+        let mut state = self.state.lock().unwrap();
+        if state.in_flight >= state.limit {
+            return Err(RateLimitError::LimitExceeded { limit: state.limit, in_flight: state.in_flight });
+        }
+        state.in_flight += 1;
+        Ok(LimiterPermit { limiter: self, started: self.time.now() })
+    }
+
+    /// 在许可保护下执行操作，成功的调用以实际耗时作为延迟样本
+    pub fn call<T, E, F>(&self, operation: F) -> Result<Result<T, E>, RateLimitError>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        let permit = self.try_acquire()?;
+        let result = operation();
+        if result.is_ok() {
+            permit.complete();
+        }
+        Ok(result)
+    }
+
+    /// 记录一个延迟样本并按AIMD调整上限
+    pub fn on_sample(&self, latency: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = if latency > self.config.latency_threshold {
+            ((state.limit as f64 * self.config.decrease_factor) as usize).max(self.config.min_limit)
+        } else {
+            (state.limit + self.config.additive_increase).min(self.config.max_limit)
+        };
+    }
+
+    fn release(&self) {
+        self.state.lock().unwrap().in_flight -= 1;
+    }
+}
+
+/// 限流许可 - drop时归还并发名额
+pub struct LimiterPermit<'a> {
+    limiter: &'a AdaptiveLimiter,
+    started: Instant,
+}
+
+impl LimiterPermit<'_> {
+    /// 请求成功完成，以获取许可以来的耗时作为延迟样本
+    pub fn complete(self) {
+        let latency = self.limiter.time.elapsed_since(self.started);
+        self.complete_with(latency);
+    }
+
+    /// 以指定的延迟样本完成请求
+    pub fn complete_with(self, latency: Duration) {
+        self.limiter.on_sample(latency);
+    }
+}
+
+impl Drop for LimiterPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release();
+    }
+}
+
+// =================
+// 令牌桶
+// =================
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// 令牌桶限流器 - 按固定速率补充令牌，容量决定允许的突发量
+pub struct TokenBucket {
+    capacity: u32,
+    refill_per_second: f64,
+    state: Mutex<BucketState>,
+    time: Arc<dyn TimeSource>,
+}
+
+impl TokenBucket {
+    /// 创建装满令牌的桶
+    pub fn new(capacity: u32, refill_per_second: f64) -> Self {
+        Self::with_time_source(capacity, refill_per_second, system_time_source())
+    }
+
+    pub fn with_time_source(capacity: u32, refill_per_second: f64, time: Arc<dyn TimeSource>) -> Self {
+        let last_refill = time.now();
+        Self {
+            capacity,
+            refill_per_second,
+            state: Mutex::new(BucketState { tokens: capacity as f64, last_refill }),
+            time,
+        }
+    }
+
+    /// 尝试取走一个令牌，桶空时返回下一个令牌补充出来前的等待时间
+    pub fn try_acquire(&self) -> Result<(), RateLimitError> {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            return Ok(());
+        }
+        // 补充速率为0时桶空后永远不会再有令牌
+        let missing = 1.0 - state.tokens;
+        let retry_after = Duration::try_from_secs_f64(missing / self.refill_per_second).unwrap_or(Duration::MAX);
+        Err(RateLimitError::TokensExhausted { retry_after })
+    }
+
+    /// 当前可用的完整令牌数
+    pub fn available(&self) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        state.tokens as u32
+    }
+
+    fn refill(&self, state: &mut BucketState) {
+        let now = self.time.now();
+        let elapsed = now.saturating_duration_since(state.last_refill);
+        state.tokens = (state.tokens + elapsed.as_secs_f64() * self.refill_per_second).min(self.capacity as f64);
+        state.last_refill = now;
+    }
+}
+
+/// Rate Limiting模式演示
+pub fn demo_rate_limiting() {
+    println!("=== Rate Limiting模式演示 ===\n");
+
+    let limiter = AdaptiveLimiter::new(AdaptiveLimiterConfig {
+        initial_limit: 4,
+        latency_threshold: Duration::from_millis(50),
+        ..AdaptiveLimiterConfig::default()
+    });
+    println!("初始并发上限: {}", limiter.current_limit());
+
+    // 1. 后端响应正常，上限逐步增加
+    for _ in 0..6 {
+        if let Ok(permit) = limiter.try_acquire() {
+            permit.complete_with(Duration::from_millis(20));
+        }
+    }
+    println!("正常延迟后的并发上限: {}", limiter.current_limit());
+
+    // 2. 后端变慢，上限成倍缩小
+    if let Ok(permit) = limiter.try_acquire() {
+        permit.complete_with(Duration::from_millis(300));
+    }
+    println!("延迟突增后的并发上限: {}", limiter.current_limit());
+
+    // 3. 在途请求达到上限时快速拒绝
+    let permits: Vec<_> = (0..limiter.current_limit()).filter_map(|_| limiter.try_acquire().ok()).collect();
+    match limiter.try_acquire() {
+        Ok(_) => println!("请求被接受"),
+        Err(e) => println!("请求被拒绝: {}", e),
+    }
+    drop(permits);
+    println!("许可归还后在途请求: {}", limiter.in_flight());
+
+    // 4. 令牌桶允许有限的突发，之后按补充速率放行
+    let bucket = TokenBucket::new(3, 10.0);
+    for i in 1..=4 {
+        match bucket.try_acquire() {
+            Ok(()) => println!("突发请求{}通过，剩余令牌 {}", i, bucket.available()),
+            Err(e) => println!("突发请求{}被拒绝: {}", i, e),
+        }
+    }
+
+    // 5. 用虚拟时钟推进时间，观察令牌补充和延迟测量
+    let clock = FakeClock::new();
+    let bucket = TokenBucket::with_time_source(3, 10.0, clock.as_time_source());
+    while bucket.try_acquire().is_ok() {}
+    clock.advance(Duration::from_millis(200));
+    println!("虚拟时间经过200ms后可用令牌: {}", bucket.available());
+    let limiter = AdaptiveLimiter::new(AdaptiveLimiterConfig::default()).with_time_source(clock.as_time_source());
+    let permit = limiter.try_acquire().unwrap();
+    clock.advance(Duration::from_millis(500));
+    permit.complete();
+    println!("虚拟耗时500ms的请求完成后并发上限: {}", limiter.current_limit());
+
+    println!("\n【Rate Limiting模式特点】");
+    println!("✓ 自适应上限 - 根据观测延迟动态调整并发上限");
+    println!("✓ 加性增加 - 延迟正常时逐步试探可用容量");
+    println!("✓ 乘性减小 - 延迟突增时迅速降低后端压力");
+    println!("✓ 快速拒绝 - 超过上限的请求立即失败，不在后端排队");
+    println!("✓ 令牌桶 - 按固定速率补充令牌，容量限制突发流量");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(initial_limit: usize) -> AdaptiveLimiter {
+        AdaptiveLimiter::new(AdaptiveLimiterConfig {
+            initial_limit,
+            min_limit: 2,
+            max_limit: 20,
+            latency_threshold: Duration::from_millis(50),
+            additive_increase: 1,
+            decrease_factor: 0.5,
+        })
+    }
+
+    #[test]
+    fn test_low_latency_grows_limit_up_to_max() {
+        let limiter = limiter(5);
+
+        for _ in 0..5 {
+            limiter.try_acquire().unwrap().complete_with(Duration::from_millis(10));
+        }
+        assert_eq!(limiter.current_limit(), 10);
+
+        for _ in 0..50 {
+            limiter.on_sample(Duration::from_millis(10));
+        }
+        assert_eq!(limiter.current_limit(), 20);
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_high_latency_shrinks_limit_down_to_min() {
+        let limiter = limiter(16);
+
+        limiter.on_sample(Duration::from_millis(200));
+        assert_eq!(limiter.current_limit(), 8);
+        limiter.on_sample(Duration::from_millis(200));
+        assert_eq!(limiter.current_limit(), 4);
+        for _ in 0..5 {
+            limiter.on_sample(Duration::from_millis(200));
+        }
+        assert_eq!(limiter.current_limit(), 2);
+
+        // 缩小后的上限立即生效，失败的请求不产生样本
+        let first = limiter.try_acquire().unwrap();
+        let _second = limiter.try_acquire().unwrap();
+        assert_eq!(limiter.try_acquire().err(), Some(RateLimitError::LimitExceeded { limit: 2, in_flight: 2 }));
+        drop(first);
+        assert!(limiter.call(|| Err::<(), _>("后端错误")).unwrap().is_err());
+        assert_eq!(limiter.current_limit(), 2);
+    }
+}
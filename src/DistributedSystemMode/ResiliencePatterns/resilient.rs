@@ -0,0 +1,354 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ResiliencePatterns/resilient.rs
+ *
+ * 弹性组合 (Resilient Operation)
+ *
+ * 重试、熔断、超时各自解决一类问题，单独使用时需要调用方手工嵌套。
+ * 本模块把三者按固定顺序组合起来：
+ *
+ *   熔断器( 重试( 超时( 操作 ) ) )
+ *
+ * 1. 超时作用于每一次尝试 - 一次挂起的尝试不会吃掉整个重试预算
+ * 2. 重试位于中间 - 只对操作声明为可重试的错误（以及超时）退避重试
+ * 3. 熔断器包住整体 - 一次完整的重试序列只记一次成功或失败，
+ *    熔断器打开时直接拒绝，不会发起任何尝试
+ */
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::circuit_breaker::{CircuitBreaker, CircuitBreakerError};
+use super::retry::{RetryConfig, RetryExecutor};
+use super::timeout::{TimeoutError, TimeoutPolicy};
+
+// =================
+// 可重试操作
+// =================
+
+/// 需要弹性保护的操作
+///
+/// 操作可能在超时策略的工作线程中执行，因此要求 `Send + Sync + 'static`。
+pub trait RetryableOperation: Send + Sync + 'static {
+    type Output: Send + 'static;
+    type Error: Send + 'static;
+
+    /// 执行一次尝试
+    fn execute(&self) -> Result<Self::Output, Self::Error>;
+
+    /// 该错误是否值得重试，默认所有错误都重试
+    fn is_retryable(&self, _error: &Self::Error) -> bool {
+        true
+    }
+}
+
+// =================
+// 弹性错误
+// =================
+
+/// 组合调用的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResilienceError<E> {
+    /// 熔断器打开，没有发起任何尝试
+    CircuitOpen,
+    /// 最后一次尝试超时
+    Timeout(Duration),
+    /// 尝试过程中操作发生panic
+    Panicked,
+    /// 操作返回的错误
+    Operation(E),
+}
+
+impl<E> ResilienceError<E> {
+    fn is_retryable_with<O>(&self, operation: &O) -> bool
+    where
+        O: RetryableOperation<Error = E>,
+    {
+        match self {
+            ResilienceError::Timeout(_) => true,
+            ResilienceError::Operation(error) => operation.is_retryable(error),
+            ResilienceError::CircuitOpen | ResilienceError::Panicked => false,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ResilienceError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResilienceError::CircuitOpen => write!(f, "熔断器打开，调用被拒绝"),
+            ResilienceError::Timeout(timeout) => write!(f, "操作超时 ({}ms)", timeout.as_millis()),
+            ResilienceError::Panicked => write!(f, "操作执行时发生panic"),
+            ResilienceError::Operation(e) => write!(f, "操作失败: {}", e),
+        }
+    }
+}
+
+// =================
+// 组合器
+// =================
+
+/// 以 熔断器 → 重试 → 超时 的顺序执行操作
+///
+/// 熔断器与超时都是可选的；熔断器通过引用传入，以便多次调用共享状态。
+pub fn resilient<O>(
+    operation: O,
+    retry: RetryConfig,
+    breaker: Option<&CircuitBreaker>,
+    timeout: Option<TimeoutPolicy>,
+) -> Result<O::Output, ResilienceError<O::Error>>
+where
+    O: RetryableOperation,
+    O::Error: fmt::Debug,
+{
+    let operation = Arc::new(operation);
+    let executor = RetryExecutor::new(retry);
+
+    let retried = || {
+        executor.execute_when(
+            || attempt(&operation, timeout.as_ref()),
+            |error| error.is_retryable_with(operation.as_ref()),
+        )
+    };
+
+    match breaker {
+        Some(breaker) => breaker.call(retried).map_err(|error| match error {
+            CircuitBreakerError::CircuitOpen => ResilienceError::CircuitOpen,
+            CircuitBreakerError::ServiceError(error) => error,
+            // call() 本身不计时，超时通常已经由每次尝试处理；这里按熔断器配置的超时上报
+            CircuitBreakerError::CallTimeout => ResilienceError::Timeout(breaker.config().timeout),
+        }),
+        None => retried(),
+    }
+}
+
+/// 执行一次尝试，配置了超时策略时在截止时间内执行
+fn attempt<O>(operation: &Arc<O>, timeout: Option<&TimeoutPolicy>) -> Result<O::Output, ResilienceError<O::Error>>
+where
+    O: RetryableOperation,
+{
+    match timeout {
+        Some(policy) => {
+            let operation = Arc::clone(operation);
+            match policy.execute(move |_| operation.execute()) {
+                Ok(result) => result.map_err(ResilienceError::Operation),
+                Err(TimeoutError::Elapsed(timeout)) => Err(ResilienceError::Timeout(timeout)),
+                Err(TimeoutError::Panicked) => Err(ResilienceError::Panicked),
+            }
+        }
+        None => operation.execute().map_err(ResilienceError::Operation),
+    }
+}
+
+/// 弹性组合演示
+pub fn demo_resilient() {
+    println!("=== 弹性组合演示 ===\n");
+
+    struct FlakyLookup {
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl RetryableOperation for FlakyLookup {
+        type Output = &'static str;
+        type Error = String;
+
+        fn execute(&self) -> Result<Self::Output, Self::Error> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            if call == 1 {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            if call < 3 {
+                Err(format!("第{}次尝试失败", call))
+            } else {
+                Ok("查询成功")
+            }
+        }
+    }
+
+    let breaker = CircuitBreaker::with_default_config();
+    let result = resilient(
+        FlakyLookup { calls: Default::default() },
+        RetryConfig { base_delay: Duration::from_millis(10), jitter: false, ..RetryConfig::default() },
+        Some(&breaker),
+        Some(TimeoutPolicy::new(Duration::from_millis(50))),
+    );
+    match result {
+        Ok(result) => println!("最终结果: {}", result),
+        Err(e) => println!("调用失败: {}", e),
+    }
+    println!("熔断器记录的调用次数: {}", breaker.get_stats().total_calls);
+
+    println!("\n【弹性组合特点】");
+    println!("✓ 每次尝试独立超时 - 挂起的尝试不会耗尽重试预算");
+    println!("✓ 按错误分类重试 - 不可重试的错误立即返回");
+    println!("✓ 熔断器包住整体 - 一次重试序列只计一次结果");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::circuit_breaker::{CircuitBreakerConfig, CircuitState};
+    use super::super::time_source::{FakeClock, TimeSource};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// 慢尝试在虚拟时钟上消耗的时间，远超测试用的超时
+    const SLOW_ATTEMPT: Duration = Duration::from_secs(10);
+    /// 测试用的超时；真实等待同样以它为上限，快尝试有充足的余量
+    const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// 前 `slow_attempts` 次尝试在虚拟时钟上挂起，之后按 `fail_until` 决定失败还是成功
+    struct ScriptedOperation {
+        clock: FakeClock,
+        attempts: Arc<AtomicU32>,
+        slow_attempts: u32,
+        fail_until: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum ScriptedError {
+        Transient,
+        Fatal,
+    }
+
+    impl RetryableOperation for ScriptedOperation {
+        type Output = u32;
+        type Error = ScriptedError;
+
+        fn execute(&self) -> Result<u32, ScriptedError> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt <= self.slow_attempts {
+                self.clock.sleep(SLOW_ATTEMPT);
+            }
+            if attempt <= self.fail_until {
+                Err(ScriptedError::Transient)
+            } else {
+                Ok(attempt)
+            }
+        }
+
+        fn is_retryable(&self, error: &ScriptedError) -> bool {
+            *error == ScriptedError::Transient
+        }
+    }
+
+    struct AlwaysFatal {
+        attempts: Arc<AtomicU32>,
+    }
+
+    impl RetryableOperation for AlwaysFatal {
+        type Output = ();
+        type Error = ScriptedError;
+
+        fn execute(&self) -> Result<(), ScriptedError> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ScriptedError::Fatal)
+        }
+
+        fn is_retryable(&self, error: &ScriptedError) -> bool {
+            *error == ScriptedError::Transient
+        }
+    }
+
+    fn quick_retry(max_attempts: u32) -> RetryConfig {
+        RetryConfig { max_attempts, base_delay: Duration::from_millis(1), jitter: false, ..RetryConfig::default() }
+    }
+
+    fn breaker(failure_threshold: u32) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold,
+            recovery_timeout: Duration::from_secs(60),
+            ..CircuitBreakerConfig::default()
+        })
+    }
+
+    /// 以虚拟时钟判定是否超时的策略，结果不受机器负载影响
+    fn timeout_on(clock: &FakeClock) -> TimeoutPolicy {
+        TimeoutPolicy::new(ATTEMPT_TIMEOUT).with_time_source(clock.as_time_source())
+    }
+
+    #[test]
+    fn test_timeout_applies_per_attempt_and_retry_recovers() {
+        let clock = FakeClock::new();
+        let attempts = Arc::new(AtomicU32::new(0));
+        let breaker = breaker(5);
+        let operation =
+            ScriptedOperation { clock: clock.clone(), attempts: Arc::clone(&attempts), slow_attempts: 2, fail_until: 0 };
+
+        // 前两次尝试各自超时后被重试，第三次在截止时间内成功；
+        // 如果超时包住整个重试序列，这里会以超时告终
+        let result = resilient(operation, quick_retry(3), Some(&breaker), Some(timeout_on(&clock)));
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        // 熔断器只看到整体的一次成功
+        let stats = breaker.get_stats();
+        assert_eq!(stats.total_calls, 1);
+        assert_eq!(stats.successful_calls, 1);
+    }
+
+    #[test]
+    fn test_breaker_counts_whole_retry_sequence_once() {
+        let clock = FakeClock::new();
+        let attempts = Arc::new(AtomicU32::new(0));
+        let breaker = breaker(2);
+        let call = || {
+            resilient(
+                ScriptedOperation {
+                    clock: clock.clone(),
+                    attempts: Arc::clone(&attempts),
+                    slow_attempts: 0,
+                    fail_until: u32::MAX - 1,
+                },
+                quick_retry(3),
+                Some(&breaker),
+                Some(timeout_on(&clock)),
+            )
+        };
+
+        // 第一次调用内部重试3次，但熔断器只记1次失败
+        assert_eq!(call(), Err(ResilienceError::Operation(ScriptedError::Transient)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(breaker.get_state(), CircuitState::Closed);
+
+        assert_eq!(call(), Err(ResilienceError::Operation(ScriptedError::Transient)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 6);
+        assert_eq!(breaker.get_state(), CircuitState::Open);
+
+        // 熔断器打开后不再发起任何尝试
+        assert_eq!(call(), Err(ResilienceError::CircuitOpen));
+        assert_eq!(attempts.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_non_retryable_error_stops_retrying() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let result = resilient(
+            AlwaysFatal { attempts: Arc::clone(&attempts) },
+            quick_retry(5),
+            None,
+            Some(timeout_on(&FakeClock::new())),
+        );
+
+        assert_eq!(result, Err(ResilienceError::Operation(ScriptedError::Fatal)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_last_timeout_is_reported_when_attempts_exhausted() {
+        let clock = FakeClock::new();
+        let attempts = Arc::new(AtomicU32::new(0));
+        let result = resilient(
+            ScriptedOperation {
+                clock: clock.clone(),
+                attempts: Arc::clone(&attempts),
+                slow_attempts: u32::MAX - 1,
+                fail_until: 0,
+            },
+            quick_retry(2),
+            None,
+            Some(timeout_on(&clock)),
+        );
+
+        assert_eq!(result, Err(ResilienceError::Timeout(ATTEMPT_TIMEOUT)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
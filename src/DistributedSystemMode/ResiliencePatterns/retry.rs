@@ -5,10 +5,16 @@
  * 
  * 重试模式用于处理瞬时故障，通过重复执行失败的操作来提高系统的可靠性。
  * 包含指数退避、抖动等策略来优化重试行为。
+ * 
+ * 只有幂等操作才能安全地自动重试：GET/PUT/DELETE 重复执行结果相同，
+ * POST 重复执行可能产生重复的副作用，除非请求带有幂等键。
  */
 
 use std::time::{Duration, Instant};
 use std::fmt;
+use std::sync::Arc;
+
+use super::time_source::{system_time_source, FakeClock, TimeSource};
 
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
@@ -31,19 +37,56 @@ impl Default for RetryConfig {
     }
 }
 
+/// 操作的幂等性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idempotency {
+    Idempotent,
+    NonIdempotent,
+}
+
+/// 按HTTP方法判断幂等性，未知方法按非幂等处理
+pub fn classify_method(method: &str) -> Idempotency {
+    match method.to_ascii_uppercase().as_str() {
+        "GET" | "HEAD" | "OPTIONS" | "TRACE" | "PUT" | "DELETE" => Idempotency::Idempotent,
+        _ => Idempotency::NonIdempotent,
+    }
+}
+
+/// 操作失败后是否可以自动重试：幂等方法总是可以，非幂等方法需要幂等键
+pub fn is_safe_to_retry(method: &str, has_idempotency_key: bool) -> bool {
+    has_idempotency_key || classify_method(method) == Idempotency::Idempotent
+}
+
 pub struct RetryExecutor {
     config: RetryConfig,
+    time: Arc<dyn TimeSource>,
 }
 
 impl RetryExecutor {
     pub fn new(config: RetryConfig) -> Self {
-        Self { config }
+        Self { config, time: system_time_source() }
     }
     
-    pub fn execute<T, E, F>(&self, mut operation: F) -> Result<T, E>
+    /// 使用指定的时间源执行退避等待
+    pub fn with_time_source(mut self, time: Arc<dyn TimeSource>) -> Self {
+        self.time = time;
+        self
+    }
+    
+    pub fn execute<T, E, F>(&self, operation: F) -> Result<T, E>
     where
         F: FnMut() -> Result<T, E>,
         E: fmt::Debug,
+    {
+        self.execute_when(operation, |_| true)
+    }
+    
+    /// 只在 `should_retry` 认定错误可重试时才重试，其他错误立即返回
+    pub fn execute_when<T, E, F, P>(&self, mut operation: F, should_retry: P) -> Result<T, E>
+    where
+        F: FnMut() -> Result<T, E>,
+        P: Fn(&E) -> bool,
+        E: fmt::Debug,
     {
         let mut attempt = 1;
         let mut delay = self.config.base_delay;
@@ -52,12 +95,12 @@ impl RetryExecutor {
             match operation() {
                 Ok(result) => return Ok(result),
                 Err(error) => {
-                    if attempt >= self.config.max_attempts {
+                    if attempt >= self.config.max_attempts || !should_retry(&error) {
                         return Err(error);
                     }
                     
                     println!("重试第{}次失败，{}ms后重试", attempt, delay.as_millis());
-                    std::thread::sleep(delay);
+                    self.time.sleep(delay);
                     
                     // 计算下次延迟时间
                     delay = Duration::from_millis(
@@ -75,6 +118,19 @@ impl RetryExecutor {
             }
         }
     }
+    
+    /// 按请求方法决定是否重试，不能安全重试的操作只执行一次
+    pub fn execute_request<T, E, F>(&self, method: &str, has_idempotency_key: bool, mut operation: F) -> Result<T, E>
+    where
+        F: FnMut() -> Result<T, E>,
+        E: fmt::Debug,
+    {
+        if is_safe_to_retry(method, has_idempotency_key) {
+            self.execute(operation)
+        } else {
+            operation()
+        }
+    }
 }
 
 /// Retry模式演示
@@ -98,9 +154,60 @@ pub fn demo_retry() {
         Err(e) => println!("重试失败: {}", e),
     }
     
+    println!("\n使用虚拟时钟检查退避序列:");
+    let clock = FakeClock::new();
+    let executor = RetryExecutor::new(RetryConfig { max_attempts: 5, jitter: false, ..RetryConfig::default() })
+        .with_time_source(clock.as_time_source());
+    let _ = executor.execute(|| Err::<(), _>("服务暂时不可用"));
+    println!("  退避延迟: {:?}, 虚拟时间共经过 {}ms", clock.sleeps(), clock.elapsed().as_millis());
+    
+    println!("\n按请求方法判断是否可以重试:");
+    for (method, has_key) in [("GET", false), ("PUT", false), ("DELETE", false), ("POST", false), ("POST", true)] {
+        println!("  {} (幂等键: {}) -> {}", method, has_key,
+                 if is_safe_to_retry(method, has_key) { "可以重试" } else { "只执行一次" });
+    }
+    
     println!("\n【Retry模式特点】");
     println!("✓ 瞬时故障处理 - 自动重试失败的操作");
     println!("✓ 指数退避 - 逐渐增加重试间隔时间");
     println!("✓ 抖动支持 - 避免雷群效应");
     println!("✓ 可配置策略 - 支持自定义重试参数");
+    println!("✓ 幂等判断 - 非幂等操作不会被自动重试");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_safety_by_method() {
+        for method in ["GET", "HEAD", "OPTIONS", "PUT", "DELETE", "get", "Delete"] {
+            assert_eq!(classify_method(method), Idempotency::Idempotent, "{}", method);
+            assert!(is_safe_to_retry(method, false), "{}", method);
+        }
+        for method in ["POST", "PATCH", "CONNECT", "UNKNOWN"] {
+            assert_eq!(classify_method(method), Idempotency::NonIdempotent, "{}", method);
+            assert!(!is_safe_to_retry(method, false), "{}", method);
+        }
+        // 幂等键让POST也可以安全重试
+        assert!(is_safe_to_retry("POST", true));
+        assert!(is_safe_to_retry("PATCH", true));
+    }
+
+    #[test]
+    fn test_execute_request_does_not_retry_post_without_key() {
+        let executor = RetryExecutor::new(RetryConfig { base_delay: Duration::from_millis(1), jitter: false, ..RetryConfig::default() });
+        let attempts_for = |method: &str, has_key: bool| {
+            let mut attempts = 0;
+            let _ = executor.execute_request(method, has_key, || -> Result<(), &str> {
+                attempts += 1;
+                Err("失败")
+            });
+            attempts
+        };
+
+        assert_eq!(attempts_for("POST", false), 1);
+        assert_eq!(attempts_for("POST", true), 3);
+        assert_eq!(attempts_for("GET", false), 3);
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,275 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ResiliencePatterns/time_source.rs
+ *
+ * 时间源 (Time Source)
+ *
+ * 熔断器的恢复超时、重试的退避延迟、令牌桶的补充速率、超时的截止时间、
+ * 舱壁的排队等待都依赖时间。直接调用 `Instant::now()` 和 `thread::sleep`
+ * 会让这些行为只能靠真实等待来验证。各组件改为通过 `TimeSource` 读取时间和等待：
+ *
+ * 1. SystemTimeSource - 默认实现，使用系统单调时钟和真实的线程休眠
+ * 2. FakeClock - 手动推进的虚拟时钟，`sleep` 立即返回并把虚拟时间向前推进，
+ *    同时记录每次休眠的时长，便于断言退避序列
+ */
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 时间源 - 组件读取当前时间和等待的唯一入口
+pub trait TimeSource: fmt::Debug + Send + Sync {
+    /// 当前时间
+    fn now(&self) -> Instant;
+
+    /// 等待指定时长
+    fn sleep(&self, duration: Duration);
+
+    /// 从 `earlier` 到现在经过的时间
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// 系统时间源
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// 各组件默认使用的系统时间源
+pub fn system_time_source() -> Arc<dyn TimeSource> {
+    Arc::new(SystemTimeSource)
+}
+
+#[derive(Debug, Default)]
+struct FakeClockState {
+    offset: Duration,
+    sleeps: Vec<Duration>,
+}
+
+/// 手动推进的虚拟时钟
+///
+/// 克隆共享同一个虚拟时间，可以把一个克隆交给组件，另一个留在调用方推进时间。
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    origin: Instant,
+    state: Arc<Mutex<FakeClockState>>,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), state: Arc::new(Mutex::new(FakeClockState::default())) }
+    }
+
+    /// 把虚拟时间向前推进
+    pub fn advance(&self, duration: Duration) {
+        self.state.lock().unwrap().offset += duration;
+    }
+
+    /// 创建以来经过的虚拟时间
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().offset
+    }
+
+    /// 组件通过 `sleep` 请求过的所有等待时长，按调用顺序排列
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.state.lock().unwrap().sleeps.clone()
+    }
+
+    /// 作为组件可以持有的时间源
+    pub fn as_time_source(&self) -> Arc<dyn TimeSource> {
+        Arc::new(self.clone())
+    }
+}
+
+impl TimeSource for FakeClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.offset += duration;
+        state.sleeps.push(duration);
+    }
+}
+
+/// 各弹性组件在虚拟时钟下的时间行为一致性测试，全程不做真实等待
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DistributedSystemMode::ResiliencePatterns::bulkhead::{Bulkhead, BulkheadConfig, BulkheadError};
+    use crate::DistributedSystemMode::ResiliencePatterns::circuit_breaker::{
+        CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitState,
+    };
+    use crate::DistributedSystemMode::ResiliencePatterns::rate_limiting::{
+        AdaptiveLimiter, AdaptiveLimiterConfig, RateLimitError, TokenBucket,
+    };
+    use crate::DistributedSystemMode::ResiliencePatterns::retry::{RetryConfig, RetryExecutor};
+    use crate::DistributedSystemMode::ResiliencePatterns::timeout::{CancellationToken, TimeoutError, TimeoutPolicy};
+
+    /// 虚拟时间推进了很久，真实时间几乎没有流逝
+    fn assert_no_real_sleep(real_start: Instant, clock: &FakeClock, virtual_at_least: Duration) {
+        assert!(clock.elapsed() >= virtual_at_least, "虚拟时间只经过了 {:?}", clock.elapsed());
+        assert!(real_start.elapsed() < Duration::from_secs(1), "真实时间经过了 {:?}", real_start.elapsed());
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_opens_after_recovery_timeout() {
+        let real_start = Instant::now();
+        let clock = FakeClock::new();
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            recovery_timeout: Duration::from_secs(60),
+            half_open_max_calls: 1,
+            timeout: Duration::from_secs(1),
+            ..CircuitBreakerConfig::default()
+        };
+        let breaker = CircuitBreaker::with_time_source(config, clock.as_time_source());
+
+        // 调用耗时按虚拟时钟计算
+        let _ = breaker.call(|| {
+            clock.advance(Duration::from_millis(30));
+            Err::<(), _>("失败")
+        });
+        assert_eq!(breaker.get_stats().average_response_time, Duration::from_millis(30));
+        let slow = breaker.call_with_timeout(|| {
+            clock.advance(Duration::from_secs(2));
+            Ok::<_, String>("迟到的响应")
+        });
+        assert!(matches!(slow, Err(CircuitBreakerError::CallTimeout)));
+        assert_eq!(breaker.get_state(), CircuitState::Open);
+
+        // 恢复超时之前一直拒绝
+        clock.advance(Duration::from_secs(59));
+        assert!(matches!(breaker.call(|| Ok::<_, String>(())), Err(CircuitBreakerError::CircuitOpen)));
+        assert_eq!(breaker.get_state(), CircuitState::Open);
+
+        // 到达恢复超时后放行探测请求，成功则关闭
+        clock.advance(Duration::from_secs(1));
+        assert!(breaker.call(|| Ok::<_, String>(())).is_ok());
+        assert_eq!(breaker.get_state(), CircuitState::Closed);
+        assert_no_real_sleep(real_start, &clock, Duration::from_secs(62));
+    }
+
+    #[test]
+    fn test_retry_backoff_sleeps_on_time_source() {
+        let real_start = Instant::now();
+        let clock = FakeClock::new();
+        let executor = RetryExecutor::new(RetryConfig {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(3),
+            multiplier: 2.0,
+            jitter: false,
+        })
+        .with_time_source(clock.as_time_source());
+
+        let mut attempts = 0;
+        let result = executor.execute(|| {
+            attempts += 1;
+            Err::<(), _>("暂时不可用")
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 5);
+        let secs = |s: u64| Duration::from_secs(s);
+        assert_eq!(clock.sleeps(), vec![secs(1), secs(2), secs(3), secs(3)]);
+        assert_no_real_sleep(real_start, &clock, secs(9));
+    }
+
+    #[test]
+    fn test_rate_limiters_refill_and_measure_on_time_source() {
+        let real_start = Instant::now();
+        let clock = FakeClock::new();
+        let bucket = TokenBucket::with_time_source(2, 1.0, clock.as_time_source());
+
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+        assert_eq!(bucket.try_acquire(), Err(RateLimitError::TokensExhausted { retry_after: Duration::from_secs(1) }));
+
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(bucket.try_acquire(), Err(RateLimitError::TokensExhausted { retry_after: Duration::from_millis(500) }));
+        clock.advance(Duration::from_millis(500));
+        assert!(bucket.try_acquire().is_ok());
+
+        // 空闲很久也只补充到桶容量
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(bucket.available(), 2);
+
+        // 自适应限流器的延迟样本来自虚拟时钟
+        let limiter = AdaptiveLimiter::new(AdaptiveLimiterConfig {
+            initial_limit: 8,
+            latency_threshold: Duration::from_millis(100),
+            ..AdaptiveLimiterConfig::default()
+        })
+        .with_time_source(clock.as_time_source());
+        let permit = limiter.try_acquire().unwrap();
+        clock.advance(Duration::from_millis(300));
+        permit.complete();
+        assert_eq!(limiter.current_limit(), 4);
+        assert_no_real_sleep(real_start, &clock, Duration::from_secs(61));
+    }
+
+    #[test]
+    fn test_timeout_deadline_follows_time_source() {
+        let real_start = Instant::now();
+        let clock = FakeClock::new();
+        let policy = TimeoutPolicy::new(Duration::from_millis(100)).with_time_source(clock.as_time_source());
+
+        let fast_clock = clock.clone();
+        let fast = policy.execute(move |_| {
+            fast_clock.sleep(Duration::from_millis(50));
+            "按时完成"
+        });
+        assert_eq!(fast, Ok("按时完成"));
+
+        let slow_clock = clock.clone();
+        let token = CancellationToken::new();
+        let slow = policy.execute_with_token(token.clone(), move |_| {
+            slow_clock.sleep(Duration::from_millis(200));
+            "迟到的响应"
+        });
+        assert_eq!(slow, Err(TimeoutError::Elapsed(Duration::from_millis(100))));
+        assert!(token.is_cancelled());
+        assert_no_real_sleep(real_start, &clock, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_bulkhead_wait_times_out_on_time_source() {
+        let real_start = Instant::now();
+        let clock = FakeClock::new();
+        let bulkhead = Bulkhead::new("orders", BulkheadConfig {
+            max_concurrent: 1,
+            max_wait: Duration::from_secs(5),
+            poll_interval: Duration::from_secs(2),
+        })
+        .with_time_source(clock.as_time_source());
+
+        let held = bulkhead.enter().unwrap();
+        assert_eq!(bulkhead.call(|| ()), Err(BulkheadError::WaitTimeout(Duration::from_secs(5))));
+        // 最后一次等待被截断到剩余的等待时间
+        let secs = |s: u64| Duration::from_secs(s);
+        assert_eq!(clock.sleeps(), vec![secs(2), secs(2), secs(1)]);
+
+        drop(held);
+        assert_eq!(bulkhead.call(|| "下单"), Ok("下单"));
+        assert_eq!(bulkhead.in_flight(), 0);
+        assert_no_real_sleep(real_start, &clock, secs(5));
+    }
+}
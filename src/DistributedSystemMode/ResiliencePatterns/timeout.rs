@@ -0,0 +1,187 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/ResiliencePatterns/timeout.rs
+ *
+ * Timeout模式 (超时)
+ *
+ * 超时模式为操作设置最长等待时间，避免调用方被挂起的下游无限阻塞。
+ * 操作在独立线程中执行，超过截止时间后调用方立即得到超时错误，
+ * 同时通过取消令牌通知操作尽快协作式退出。
+ * 截止时间按时间源计时：操作完成时在时间源上记下完成时刻，
+ * 如果完成时刻已经超过截止时间，即使结果送达也视为超时。
+ */
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::time_source::{system_time_source, FakeClock, TimeSource};
+
+// =================
+// 取消令牌
+// =================
+
+/// 取消令牌 - 协作式取消长时间运行的操作
+///
+/// 令牌可以被克隆并在线程间共享，操作需要在合适的检查点调用
+/// `is_cancelled()` 自行退出。
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 发出取消信号
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否已被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+// =================
+// 超时错误
+// =================
+
+/// 超时错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeoutError {
+    /// 操作在截止时间内未完成
+    Elapsed(Duration),
+    /// 操作线程发生panic
+    Panicked,
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutError::Elapsed(timeout) => write!(f, "操作超时 ({}ms)", timeout.as_millis()),
+            TimeoutError::Panicked => write!(f, "操作执行时发生panic"),
+        }
+    }
+}
+
+// =================
+// 超时策略
+// =================
+
+/// 超时策略
+#[derive(Debug, Clone)]
+pub struct TimeoutPolicy {
+    pub timeout: Duration,
+    time: Arc<dyn TimeSource>,
+}
+
+impl TimeoutPolicy {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, time: system_time_source() }
+    }
+
+    /// 使用指定的时间源判断截止时间
+    ///
+    /// 时间源决定操作是否在截止时间内完成，调用方仍然最多真实等待 `timeout`：
+    /// 使用 FakeClock 时，一直不返回的操作同样会让调用方真实阻塞 `timeout`。
+    pub fn with_time_source(mut self, time: Arc<dyn TimeSource>) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// 在截止时间内执行操作
+    ///
+    /// 超时后令牌会被取消，但操作线程不会被强制终止；
+    /// 不检查令牌的操作会在后台继续运行直到自然结束。
+    pub fn execute<T, F>(&self, operation: F) -> Result<T, TimeoutError>
+    where
+        F: FnOnce(&CancellationToken) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.execute_with_token(CancellationToken::new(), operation)
+    }
+
+    /// 使用调用方提供的令牌执行操作，便于外部提前取消
+    ///
+    /// 完成时刻由操作线程在结果产生时从时间源读取，而不是在调用方收到结果后读取，
+    /// 因此刚好在截止时间前完成的结果不会因为线程唤醒延迟被误报为超时。
+    pub fn execute_with_token<T, F>(&self, token: CancellationToken, operation: F) -> Result<T, TimeoutError>
+    where
+        F: FnOnce(&CancellationToken) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let worker_token = token.clone();
+        let worker_time = Arc::clone(&self.time);
+        let started = self.time.now();
+
+        thread::spawn(move || {
+            let result = operation(&worker_token);
+            let finished = worker_time.now();
+            // 调用方可能已经超时返回，发送失败可以忽略
+            let _ = sender.send((result, finished));
+        });
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok((result, finished)) if finished.saturating_duration_since(started) <= self.timeout => Ok(result),
+            Ok(_) | Err(mpsc::RecvTimeoutError::Timeout) => {
+                token.cancel();
+                Err(TimeoutError::Elapsed(self.timeout))
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(TimeoutError::Panicked),
+        }
+    }
+}
+
+/// Timeout模式演示
+pub fn demo_timeout() {
+    println!("=== Timeout模式演示 ===\n");
+
+    let policy = TimeoutPolicy::new(Duration::from_millis(100));
+
+    // 1. 快速操作在截止时间内完成
+    match policy.execute(|_| "快速响应") {
+        Ok(result) => println!("快速操作结果: {}", result),
+        Err(e) => println!("快速操作失败: {}", e),
+    }
+
+    // 2. 慢操作超时，并通过令牌协作式退出
+    let result = policy.execute(|token| {
+        for _ in 0..50 {
+            if token.is_cancelled() {
+                return "已取消";
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        "慢速响应"
+    });
+    match result {
+        Ok(result) => println!("慢操作结果: {}", result),
+        Err(e) => println!("慢操作失败: {}", e),
+    }
+
+    // 3. 截止时间按时间源计时，虚拟时钟下无需真实等待
+    let clock = FakeClock::new();
+    let policy = TimeoutPolicy::new(Duration::from_millis(100)).with_time_source(clock.as_time_source());
+    let worker_clock = clock.clone();
+    let result = policy.execute(move |_| {
+        worker_clock.sleep(Duration::from_secs(1));
+        "虚拟慢速响应"
+    });
+    match result {
+        Ok(result) => println!("虚拟时钟操作结果: {}", result),
+        Err(e) => println!("虚拟时钟操作失败: {} (虚拟时间经过 {}ms)", e, clock.elapsed().as_millis()),
+    }
+
+    println!("\n【Timeout模式特点】");
+    println!("✓ 有界等待 - 调用方最多等待设定的截止时间");
+    println!("✓ 协作取消 - 通过取消令牌通知操作停止");
+    println!("✓ 故障隔离 - 挂起的下游不会拖垮调用方");
+}
+
@@ -0,0 +1,543 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/SecurityPatterns/api_keys_jwt.rs
+ *
+ * API Keys & JWT模式 (API密钥与JSON Web Token)
+ *
+ * API密钥适合服务之间的长期凭证：服务端只保存密钥的哈希，按哈希查找调用方。
+ * JWT 是自包含的令牌：声明（claims）和签名放在令牌里，验证方不需要查询签发方的存储。
+ *
+ * 主要特点：
+ * 1. 哈希存储的API密钥 - 泄露存储不会泄露密钥本身，可随时吊销
+ * 2. HS256 - 签发方和验证方共享密钥，适合单一服务内部
+ * 3. ES256（非对称模式）- 私钥签名、公钥验证，公钥以 JWKS 文档发布
+ * 4. 联邦验证 - 其他服务只拿到 JWKS 文档即可验证令牌
+ *
+ * 实现说明：
+ * - SHA-256、HMAC 和 Base64URL 使用 sha2、hmac、base64 库
+ * - ES256 使用 p256 库的 P-256 ECDSA 签名，位于 `jwt-asymmetric` 特性之后（默认启用）
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+#[cfg(feature = "jwt-asymmetric")]
+use p256::ecdsa::{self, signature::Signer, signature::Verifier};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// =================
+// 编码与哈希
+// =================
+
+/// Base64URL 编码，不带填充
+pub fn base64url_encode(data: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Base64URL 解码，输入不能带填充
+pub fn base64url_decode(input: &str) -> Option<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(input).ok()
+}
+
+/// SHA-256 摘要
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+fn hmac(key: &[u8]) -> Hmac<Sha256> {
+    Hmac::new_from_slice(key).expect("HMAC 接受任意长度的密钥")
+}
+
+/// HMAC-SHA256
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = hmac(key);
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+/// 按固定时间校验 HMAC，避免通过比较耗时猜测签名
+fn verify_hmac_sha256(key: &[u8], message: &[u8], tag: &[u8]) -> bool {
+    let mut mac = hmac(key);
+    mac.update(message);
+    mac.verify_slice(tag).is_ok()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+// =================
+// API密钥
+// =================
+
+/// API密钥记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyRecord {
+    pub owner: String,
+    pub scopes: Vec<String>,
+}
+
+/// API密钥存储 - 只保存密钥的SHA-256哈希
+pub struct ApiKeyStore {
+    keys: HashMap<[u8; 32], ApiKeyRecord>,
+    rng: StdRng,
+}
+
+impl ApiKeyStore {
+    pub fn new(seed: u64) -> Self {
+        Self { keys: HashMap::new(), rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// 签发新密钥，明文只在此时返回一次
+    pub fn issue(&mut self, owner: &str, scopes: &[&str]) -> String {
+        let secret: [u8; 24] = self.rng.gen();
+        let key = format!("ak_{}", base64url_encode(&secret));
+        self.keys.insert(sha256(key.as_bytes()), ApiKeyRecord {
+            owner: owner.to_string(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+        });
+        key
+    }
+
+    pub fn validate(&self, key: &str) -> Option<&ApiKeyRecord> {
+        self.keys.get(&sha256(key.as_bytes()))
+    }
+
+    pub fn revoke(&mut self, key: &str) -> bool {
+        self.keys.remove(&sha256(key.as_bytes())).is_some()
+    }
+}
+
+// =================
+// JWT
+// =================
+
+/// JWT 错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum JwtError {
+    /// 令牌不是三段式，或某一段无法解码
+    Malformed(String),
+    UnsupportedAlgorithm(String),
+    InvalidSignature,
+    Expired { expired_at: u64 },
+    /// 令牌不是由期望的签发方签发
+    InvalidIssuer { expected: String, actual: String },
+    /// 签发时间加有效期超出了时间戳的表示范围
+    InvalidTtl(u64),
+    /// 令牌的 kid 不在已知的密钥中
+    UnknownKey(String),
+    InvalidJwks(String),
+}
+
+impl fmt::Display for JwtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JwtError::Malformed(msg) => write!(f, "令牌格式错误: {}", msg),
+            JwtError::UnsupportedAlgorithm(alg) => write!(f, "不支持的签名算法: {}", alg),
+            JwtError::InvalidSignature => write!(f, "签名无效"),
+            JwtError::Expired { expired_at } => write!(f, "令牌已于 {} 过期", expired_at),
+            JwtError::InvalidIssuer { expected, actual } => write!(f, "签发方不匹配: 期望 {}, 实际 {}", expected, actual),
+            JwtError::InvalidTtl(ttl) => write!(f, "有效期 {} 秒超出范围", ttl),
+            JwtError::UnknownKey(kid) => write!(f, "未知的密钥: {}", kid),
+            JwtError::InvalidJwks(msg) => write!(f, "JWKS 文档无效: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for JwtError {}
+
+/// JWT 头部
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JwtHeader {
+    pub alg: String,
+    pub typ: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+/// JWT 声明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JwtClaims {
+    pub iss: String,
+    pub sub: String,
+    pub iat: u64,
+    pub exp: u64,
+    /// 空格分隔的权限范围
+    #[serde(default)]
+    pub scope: String,
+}
+
+impl JwtClaims {
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scope.split_whitespace()
+    }
+}
+
+/// 签名密钥
+enum SigningKey {
+    Hs256 { secret: Vec<u8> },
+    #[cfg(feature = "jwt-asymmetric")]
+    Es256 { kid: String, key: ecdsa::SigningKey },
+}
+
+/// JWT 签发与验证服务
+pub struct JwtService {
+    issuer: String,
+    key: SigningKey,
+}
+
+impl JwtService {
+    /// HS256 模式，签发方和验证方共享密钥
+    pub fn new_hmac(issuer: &str, secret: &[u8]) -> Self {
+        Self { issuer: issuer.to_string(), key: SigningKey::Hs256 { secret: secret.to_vec() } }
+    }
+
+    /// ES256 模式，从系统随机源生成 P-256 密钥对，公钥可以通过 `jwks()` 发布
+    #[cfg(feature = "jwt-asymmetric")]
+    pub fn new_ecdsa(issuer: &str, kid: &str) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            key: SigningKey::Es256 { kid: kid.to_string(), key: ecdsa::SigningKey::random(&mut rand::rngs::OsRng) },
+        }
+    }
+
+    /// 签发令牌，`ttl_secs` 秒后过期；过期时间溢出时拒绝签发
+    pub fn issue(&self, subject: &str, scopes: &[&str], ttl_secs: u64) -> Result<String, JwtError> {
+        let iat = now_secs();
+        let exp = iat.checked_add(ttl_secs).ok_or(JwtError::InvalidTtl(ttl_secs))?;
+        let claims = JwtClaims {
+            iss: self.issuer.clone(),
+            sub: subject.to_string(),
+            iat,
+            exp,
+            scope: scopes.join(" "),
+        };
+        Ok(self.sign(&claims))
+    }
+
+    /// 对任意声明签名
+    pub fn sign(&self, claims: &JwtClaims) -> String {
+        let header = match &self.key {
+            SigningKey::Hs256 { .. } => JwtHeader { alg: "HS256".to_string(), typ: "JWT".to_string(), kid: None },
+            #[cfg(feature = "jwt-asymmetric")]
+            SigningKey::Es256 { kid, .. } => JwtHeader { alg: "ES256".to_string(), typ: "JWT".to_string(), kid: Some(kid.clone()) },
+        };
+        let signing_input = format!(
+            "{}.{}",
+            base64url_encode(&serde_json::to_vec(&header).unwrap()),
+            base64url_encode(&serde_json::to_vec(claims).unwrap())
+        );
+        let signature = match &self.key {
+            SigningKey::Hs256 { secret } => hmac_sha256(secret, signing_input.as_bytes()).to_vec(),
+            #[cfg(feature = "jwt-asymmetric")]
+            SigningKey::Es256 { key, .. } => {
+                let signature: ecdsa::Signature = key.sign(signing_input.as_bytes());
+                signature.to_bytes().to_vec()
+            }
+        };
+        format!("{}.{}", signing_input, base64url_encode(&signature))
+    }
+
+    /// 验证签名、签发方和有效期
+    pub fn verify(&self, token: &str) -> Result<JwtClaims, JwtError> {
+        let parts = TokenParts::parse(token)?;
+        match &self.key {
+            SigningKey::Hs256 { secret } => {
+                parts.expect_algorithm("HS256")?;
+                if !verify_hmac_sha256(secret, parts.signing_input.as_bytes(), &parts.signature) {
+                    return Err(JwtError::InvalidSignature);
+                }
+            }
+            #[cfg(feature = "jwt-asymmetric")]
+            SigningKey::Es256 { key, .. } => {
+                parts.expect_algorithm("ES256")?;
+                parts.verify_es256(key.verifying_key())?;
+            }
+        }
+        parts.into_valid_claims(&self.issuer)
+    }
+
+    /// 以 JWKS 文档发布验证公钥；HS256 的密钥不能公开，返回空的密钥集
+    pub fn jwks(&self) -> String {
+        let keys: Vec<serde_json::Value> = match &self.key {
+            SigningKey::Hs256 { .. } => Vec::new(),
+            #[cfg(feature = "jwt-asymmetric")]
+            SigningKey::Es256 { kid, key } => vec![es256_jwk::to_jwk(key.verifying_key(), kid)],
+        };
+        serde_json::json!({ "keys": keys }).to_string()
+    }
+}
+
+/// 拆分后的令牌
+struct TokenParts {
+    header: JwtHeader,
+    claims: JwtClaims,
+    signing_input: String,
+    signature: Vec<u8>,
+}
+
+impl TokenParts {
+    fn parse(token: &str) -> Result<Self, JwtError> {
+        let mut segments = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (segments.next(), segments.next(), segments.next(), segments.next())
+        else {
+            return Err(JwtError::Malformed("令牌必须由三段组成".to_string()));
+        };
+
+        let decode = |segment: &str, name: &str| {
+            base64url_decode(segment).ok_or_else(|| JwtError::Malformed(format!("{}不是合法的Base64URL", name)))
+        };
+        let header_json = decode(header, "头部")?;
+        let claims_json = decode(claims, "声明")?;
+        Ok(Self {
+            header: serde_json::from_slice(&header_json).map_err(|e| JwtError::Malformed(format!("头部: {}", e)))?,
+            claims: serde_json::from_slice(&claims_json).map_err(|e| JwtError::Malformed(format!("声明: {}", e)))?,
+            signing_input: format!("{}.{}", header, claims),
+            signature: decode(signature, "签名")?,
+        })
+    }
+
+    /// 算法由验证方决定，不信任令牌头部声明的算法
+    fn expect_algorithm(&self, alg: &str) -> Result<(), JwtError> {
+        if self.header.alg == alg {
+            Ok(())
+        } else {
+            Err(JwtError::UnsupportedAlgorithm(self.header.alg.clone()))
+        }
+    }
+
+    #[cfg(feature = "jwt-asymmetric")]
+    fn verify_es256(&self, key: &ecdsa::VerifyingKey) -> Result<(), JwtError> {
+        let signature = ecdsa::Signature::from_slice(&self.signature).map_err(|_| JwtError::InvalidSignature)?;
+        key.verify(self.signing_input.as_bytes(), &signature).map_err(|_| JwtError::InvalidSignature)
+    }
+
+    /// 签名验证通过后再检查声明：签发方必须是期望的签发方，且令牌未过期
+    fn into_valid_claims(self, issuer: &str) -> Result<JwtClaims, JwtError> {
+        if self.claims.iss != issuer {
+            return Err(JwtError::InvalidIssuer { expected: issuer.to_string(), actual: self.claims.iss });
+        }
+        if self.claims.exp <= now_secs() {
+            return Err(JwtError::Expired { expired_at: self.claims.exp });
+        }
+        Ok(self.claims)
+    }
+}
+
+// =================
+// JWKS 联邦验证
+// =================
+
+/// 只持有 JWKS 文档中公钥的验证方
+#[cfg(feature = "jwt-asymmetric")]
+pub struct JwksVerifier {
+    issuer: String,
+    keys: HashMap<String, ecdsa::VerifyingKey>,
+}
+
+#[cfg(feature = "jwt-asymmetric")]
+impl JwksVerifier {
+    /// 解析签发方 `issuer` 发布的 JWKS 文档，忽略非 P-256 椭圆曲线密钥
+    pub fn from_jwks(issuer: &str, document: &str) -> Result<Self, JwtError> {
+        let value: serde_json::Value = serde_json::from_str(document).map_err(|e| JwtError::InvalidJwks(e.to_string()))?;
+        let entries = value["keys"].as_array().ok_or_else(|| JwtError::InvalidJwks("缺少 keys 数组".to_string()))?;
+
+        let mut keys = HashMap::new();
+        for entry in entries.iter().filter(|entry| entry["kty"] == "EC" && entry["crv"] == "P-256") {
+            let kid = entry["kid"].as_str().ok_or_else(|| JwtError::InvalidJwks("密钥缺少 kid".to_string()))?;
+            keys.insert(kid.to_string(), es256_jwk::from_jwk(entry)?);
+        }
+        Ok(Self { issuer: issuer.to_string(), keys })
+    }
+
+    pub fn key_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.keys.keys().map(String::as_str).collect();
+        ids.sort();
+        ids
+    }
+
+    /// 按令牌头部的 kid 选择公钥验证
+    pub fn verify(&self, token: &str) -> Result<JwtClaims, JwtError> {
+        let parts = TokenParts::parse(token)?;
+        parts.expect_algorithm("ES256")?;
+        let kid = parts.header.kid.clone().unwrap_or_default();
+        let key = self.keys.get(&kid).ok_or(JwtError::UnknownKey(kid))?;
+        parts.verify_es256(key)?;
+        parts.into_valid_claims(&self.issuer)
+    }
+}
+
+/// P-256 公钥与 JWK（RFC 7518 第6.2节）之间的转换，坐标为32字节大端整数
+#[cfg(feature = "jwt-asymmetric")]
+mod es256_jwk {
+    use super::{base64url_decode, base64url_encode, JwtError};
+    use p256::ecdsa::VerifyingKey;
+    use p256::{EncodedPoint, FieldBytes};
+
+    pub fn to_jwk(key: &VerifyingKey, kid: &str) -> serde_json::Value {
+        let point = key.to_encoded_point(false);
+        serde_json::json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "use": "sig",
+            "alg": "ES256",
+            "kid": kid,
+            "x": base64url_encode(point.x().expect("未压缩的点带有x坐标")),
+            "y": base64url_encode(point.y().expect("未压缩的点带有y坐标")),
+        })
+    }
+
+    fn decode_coordinate(value: &serde_json::Value, field: &str) -> Result<FieldBytes, JwtError> {
+        value.as_str()
+            .and_then(base64url_decode)
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .map(FieldBytes::from)
+            .ok_or_else(|| JwtError::InvalidJwks(format!("字段 {} 无效", field)))
+    }
+
+    pub fn from_jwk(jwk: &serde_json::Value) -> Result<VerifyingKey, JwtError> {
+        let x = decode_coordinate(&jwk["x"], "x")?;
+        let y = decode_coordinate(&jwk["y"], "y")?;
+        VerifyingKey::from_encoded_point(&EncodedPoint::from_affine_coordinates(&x, &y, false))
+            .map_err(|_| JwtError::InvalidJwks("坐标不在 P-256 曲线上".to_string()))
+    }
+}
+
+/// API Keys & JWT模式演示
+pub fn demo_api_keys_jwt() {
+    println!("=== API Keys & JWT模式演示 ===\n");
+
+    // 1. API密钥
+    let mut api_keys = ApiKeyStore::new(2024);
+    let key = api_keys.issue("报表服务", &["reports:read"]);
+    println!("1. 签发API密钥: {}", key);
+    println!("   验证: {:?}", api_keys.validate(&key).map(|record| &record.owner));
+    api_keys.revoke(&key);
+    println!("   吊销后验证: {:?}", api_keys.validate(&key).map(|record| &record.owner));
+
+    // 2. HS256
+    let hmac = JwtService::new_hmac("auth.example.com", b"shared-secret");
+    match hmac.issue("user-42", &["orders:read"], 3600) {
+        Ok(token) => {
+            println!("\n2. HS256 令牌: {}", token);
+            println!("   验证: {:?}", hmac.verify(&token).map(|claims| claims.sub));
+        }
+        Err(e) => println!("\n2. 签发失败: {}", e),
+    }
+    println!("   HS256 的 JWKS: {}", hmac.jwks());
+
+    // 3. ES256 + JWKS 联邦验证
+    #[cfg(feature = "jwt-asymmetric")]
+    {
+        let issuer = JwtService::new_ecdsa("auth.example.com", "key-2024");
+        let jwks = issuer.jwks();
+        println!("\n3. 签发方发布的 JWKS: {}", jwks);
+        let verified = issuer.issue("user-42", &["orders:read", "orders:write"], 3600)
+            .and_then(|token| JwksVerifier::from_jwks("auth.example.com", &jwks)?.verify(&token));
+        match verified {
+            Ok(claims) => println!("   下游服务只用公钥验证通过: sub={}, scope={}", claims.sub, claims.scope),
+            Err(e) => println!("   验证失败: {}", e),
+        }
+    }
+
+    println!("\n【API Keys & JWT模式特点】");
+    println!("✓ 哈希存储 - API密钥只保存哈希，可随时吊销");
+    println!("✓ 自包含令牌 - JWT 携带声明和签名，验证无需查询存储");
+    println!("✓ 非对称签名 - 私钥签名、公钥验证");
+    println!("✓ JWKS 发布 - 其他服务通过公钥文档完成联邦验证");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_hash_and_encoding_vectors() {
+        assert_eq!(hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        // RFC 4231 测试用例2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        for input in [&b""[..], b"f", b"fo", b"foo", b"\xff\xfe\xfd\xfc"] {
+            assert_eq!(base64url_decode(&base64url_encode(input)).as_deref(), Some(input));
+        }
+        assert_eq!(base64url_encode(b"\xfb\xff"), "-_8");
+    }
+
+    #[test]
+    fn test_hmac_token_round_trip_and_rejections() {
+        let service = JwtService::new_hmac("issuer", b"secret");
+        let token = service.issue("alice", &["orders:read"], 60).unwrap();
+        let claims = service.verify(&token).unwrap();
+        assert_eq!((claims.sub.as_str(), claims.scopes().collect::<Vec<_>>()), ("alice", vec!["orders:read"]));
+
+        // 密钥不同或声明被篡改
+        assert_eq!(JwtService::new_hmac("issuer", b"other").verify(&token), Err(JwtError::InvalidSignature));
+        let forged_claims = base64url_encode(br#"{"iss":"issuer","sub":"admin","iat":0,"exp":99999999999}"#);
+        let segments: Vec<&str> = token.split('.').collect();
+        let forged = format!("{}.{}.{}", segments[0], forged_claims, segments[2]);
+        assert_eq!(service.verify(&forged), Err(JwtError::InvalidSignature));
+
+        let expired = service.sign(&JwtClaims { exp: 1, ..claims.clone() });
+        assert_eq!(service.verify(&expired), Err(JwtError::Expired { expired_at: 1 }));
+        assert_eq!(service.jwks(), r#"{"keys":[]}"#);
+
+        // 共享同一密钥的其他签发方签发的令牌不被接受
+        let foreign = JwtService::new_hmac("other-issuer", b"secret").issue("alice", &[], 60).unwrap();
+        assert_eq!(service.verify(&foreign), Err(JwtError::InvalidIssuer { expected: "issuer".to_string(), actual: "other-issuer".to_string() }));
+        let relabeled = service.sign(&JwtClaims { iss: "other-issuer".to_string(), ..claims });
+        assert!(matches!(service.verify(&relabeled), Err(JwtError::InvalidIssuer { .. })));
+    }
+
+    #[test]
+    fn test_overflowing_ttl_is_rejected() {
+        let service = JwtService::new_hmac("issuer", b"secret");
+        assert_eq!(service.issue("alice", &[], u64::MAX), Err(JwtError::InvalidTtl(u64::MAX)));
+        assert!(service.issue("alice", &[], u64::MAX - now_secs() - 10).is_ok());
+    }
+
+    #[cfg(feature = "jwt-asymmetric")]
+    #[test]
+    fn test_verify_with_only_exported_public_key() {
+        let issuer = JwtService::new_ecdsa("auth", "k1");
+        let token = issuer.issue("bob", &["orders:*"], 60).unwrap();
+
+        let jwks = issuer.jwks();
+        let document: serde_json::Value = serde_json::from_str(&jwks).unwrap();
+        assert_eq!((&document["keys"][0]["kty"], &document["keys"][0]["alg"]), (&"EC".into(), &"ES256".into()));
+        // 导出的文档只包含公钥
+        assert!(document["keys"][0].get("d").is_none());
+
+        let verifier = JwksVerifier::from_jwks("auth", &jwks).unwrap();
+        assert_eq!(verifier.key_ids(), vec!["k1"]);
+        assert_eq!(verifier.verify(&token).unwrap().sub, "bob");
+
+        // 其他签发方的令牌和HS256令牌都不能通过
+        let other = JwtService::new_ecdsa("auth", "k1").issue("bob", &[], 60).unwrap();
+        assert_eq!(verifier.verify(&other), Err(JwtError::InvalidSignature));
+        let hmac_token = JwtService::new_hmac("auth", b"secret").issue("bob", &[], 60).unwrap();
+        assert_eq!(verifier.verify(&hmac_token), Err(JwtError::UnsupportedAlgorithm("HS256".to_string())));
+        let unknown_kid = JwtService::new_ecdsa("auth", "k2").issue("bob", &[], 60).unwrap();
+        assert_eq!(verifier.verify(&unknown_kid), Err(JwtError::UnknownKey("k2".to_string())));
+        // 同一把密钥签发、但签发方不同的令牌被拒绝
+        let wrong_issuer = JwksVerifier::from_jwks("other", &jwks).unwrap();
+        assert!(matches!(wrong_issuer.verify(&token), Err(JwtError::InvalidIssuer { .. })));
+
+        // 篡改坐标后不再是曲线上的点
+        let mut tampered = document.clone();
+        tampered["keys"][0]["y"] = base64url_encode(&[0u8; 32]).into();
+        assert!(matches!(JwksVerifier::from_jwks("auth", &tampered.to_string()), Err(JwtError::InvalidJwks(_))));
+    }
+}
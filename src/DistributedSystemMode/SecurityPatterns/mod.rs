@@ -1 +1,8 @@
- 
\ No newline at end of file
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/SecurityPatterns/mod.rs
+ * 
+ * 安全模式模块 (Security Patterns)
+ */
+
+pub mod oauth;
+pub mod api_keys_jwt;
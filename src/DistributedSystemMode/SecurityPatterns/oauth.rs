@@ -0,0 +1,313 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/DistributedSystemMode/SecurityPatterns/oauth.rs
+ *
+ * OAuth模式 (OAuth 2.0 授权)
+ *
+ * OAuth 2.0 把"谁可以访问什么"交给授权服务器决定：客户端用自己的凭证换取访问令牌，
+ * 资源服务器通过令牌内省（introspection）确认令牌有效并检查权限范围（scope）。
+ *
+ * 主要特点：
+ * 1. 客户端凭证授权 - 服务之间用 client_id/client_secret 换取访问令牌
+ * 2. 不透明令牌 - 令牌本身不含信息，资源服务器通过内省获取令牌详情
+ * 3. 层级权限范围 - `orders:*` 覆盖 `orders:read`、`orders:items:write` 等子范围
+ * 4. 令牌吊销 - 吊销后内省立即返回无效
+ *
+ * 实现说明：
+ * - 客户端密钥只保存SHA-256哈希
+ * - 签发和授权检查共用 scope_matches，客户端只能申请被允许范围覆盖的权限
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::api_keys_jwt::{base64url_encode, sha256};
+
+// =================
+// 权限范围
+// =================
+
+/// 判断已授予的范围是否满足要求的范围
+///
+/// - 完全相同时满足
+/// - `*` 满足任何范围
+/// - `a:*` 满足以 `a:` 开头的任意层级子范围，但不满足 `a` 本身
+pub fn scope_matches(granted: &str, required: &str) -> bool {
+    if granted == required || granted == "*" {
+        return true;
+    }
+    match granted.strip_suffix('*') {
+        Some(prefix) if prefix.ends_with(':') => required.starts_with(prefix) && required.len() > prefix.len(),
+        _ => false,
+    }
+}
+
+/// 任意一个已授予的范围满足要求即可
+pub fn scopes_satisfy<'a>(granted: impl IntoIterator<Item = &'a str>, required: &str) -> bool {
+    granted.into_iter().any(|scope| scope_matches(scope, required))
+}
+
+// =================
+// 授权服务器
+// =================
+
+/// OAuth 错误，名称对应 RFC 6749 / RFC 6750 的错误码
+#[derive(Debug, Clone, PartialEq)]
+pub enum OAuthError {
+    InvalidClient,
+    /// 申请的范围超出客户端被允许的范围
+    InvalidScope(String),
+    InvalidToken,
+    /// 令牌有效但权限不足
+    InsufficientScope { required: String },
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OAuthError::InvalidClient => write!(f, "invalid_client: 客户端认证失败"),
+            OAuthError::InvalidScope(scope) => write!(f, "invalid_scope: 不允许申请 {}", scope),
+            OAuthError::InvalidToken => write!(f, "invalid_token: 令牌无效或已过期"),
+            OAuthError::InsufficientScope { required } => write!(f, "insufficient_scope: 需要 {}", required),
+        }
+    }
+}
+
+impl std::error::Error for OAuthError {}
+
+/// 访问令牌响应
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessTokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    pub scope: String,
+}
+
+/// 令牌内省结果 (RFC 7662)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenIntrospection {
+    pub active: bool,
+    pub client_id: Option<String>,
+    pub scope: Option<String>,
+    pub exp: Option<u64>,
+}
+
+impl TokenIntrospection {
+    fn inactive() -> Self {
+        Self { active: false, client_id: None, scope: None, exp: None }
+    }
+
+    /// 令牌有效且权限范围满足要求
+    pub fn allows(&self, required: &str) -> bool {
+        self.active && self.scope.as_deref().is_some_and(|scope| scopes_satisfy(scope.split_whitespace(), required))
+    }
+}
+
+struct RegisteredClient {
+    secret_hash: [u8; 32],
+    allowed_scopes: Vec<String>,
+}
+
+struct IssuedToken {
+    client_id: String,
+    scopes: Vec<String>,
+    expires_at: u64,
+}
+
+/// 授权服务器
+pub struct AuthorizationServer {
+    clients: HashMap<String, RegisteredClient>,
+    tokens: HashMap<String, IssuedToken>,
+    token_ttl_secs: u64,
+    rng: StdRng,
+}
+
+impl AuthorizationServer {
+    pub fn new(token_ttl_secs: u64, seed: u64) -> Self {
+        Self {
+            clients: HashMap::new(),
+            tokens: HashMap::new(),
+            token_ttl_secs,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// 注册客户端，`allowed_scopes` 可以包含通配范围
+    pub fn register_client(&mut self, client_id: &str, client_secret: &str, allowed_scopes: &[&str]) {
+        self.clients.insert(client_id.to_string(), RegisteredClient {
+            secret_hash: sha256(client_secret.as_bytes()),
+            allowed_scopes: allowed_scopes.iter().map(|scope| scope.to_string()).collect(),
+        });
+    }
+
+    /// 客户端凭证授权，`requested_scope` 为空格分隔的范围，为空时授予全部允许的范围
+    pub fn client_credentials_grant(
+        &mut self,
+        client_id: &str,
+        client_secret: &str,
+        requested_scope: &str,
+    ) -> Result<AccessTokenResponse, OAuthError> {
+        let client = self.clients.get(client_id)
+            .filter(|client| client.secret_hash == sha256(client_secret.as_bytes()))
+            .ok_or(OAuthError::InvalidClient)?;
+
+        let scopes: Vec<String> = if requested_scope.trim().is_empty() {
+            client.allowed_scopes.clone()
+        } else {
+            requested_scope.split_whitespace().map(str::to_string).collect()
+        };
+        if let Some(denied) = scopes.iter().find(|scope| !scopes_satisfy(client.allowed_scopes.iter().map(String::as_str), scope)) {
+            return Err(OAuthError::InvalidScope(denied.clone()));
+        }
+
+        let token_bytes: [u8; 32] = self.rng.gen();
+        let access_token = base64url_encode(&token_bytes);
+        let scope = scopes.join(" ");
+        self.tokens.insert(access_token.clone(), IssuedToken {
+            client_id: client_id.to_string(),
+            scopes,
+            expires_at: now_secs() + self.token_ttl_secs,
+        });
+
+        Ok(AccessTokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.token_ttl_secs,
+            scope,
+        })
+    }
+
+    /// 令牌内省，未知、过期或已吊销的令牌返回 active=false
+    pub fn introspect(&self, access_token: &str) -> TokenIntrospection {
+        match self.tokens.get(access_token) {
+            Some(token) if token.expires_at > now_secs() => TokenIntrospection {
+                active: true,
+                client_id: Some(token.client_id.clone()),
+                scope: Some(token.scopes.join(" ")),
+                exp: Some(token.expires_at),
+            },
+            _ => TokenIntrospection::inactive(),
+        }
+    }
+
+    /// 资源服务器的授权检查
+    pub fn authorize(&self, access_token: &str, required_scope: &str) -> Result<(), OAuthError> {
+        let introspection = self.introspect(access_token);
+        if !introspection.active {
+            return Err(OAuthError::InvalidToken);
+        }
+        if !introspection.allows(required_scope) {
+            return Err(OAuthError::InsufficientScope { required: required_scope.to_string() });
+        }
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, access_token: &str) -> bool {
+        self.tokens.remove(access_token).is_some()
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+/// OAuth模式演示
+pub fn demo_oauth() {
+    println!("=== OAuth模式演示 ===\n");
+
+    let mut server = AuthorizationServer::new(3600, 2024);
+    server.register_client("order-admin", "admin-secret", &["orders:*"]);
+    server.register_client("report-job", "report-secret", &["orders:read", "reports:read"]);
+
+    let admin = server.client_credentials_grant("order-admin", "admin-secret", "").unwrap();
+    let report = server.client_credentials_grant("report-job", "report-secret", "orders:read").unwrap();
+    println!("order-admin 令牌范围: {}", admin.scope);
+    println!("report-job 令牌范围: {}", report.scope);
+
+    for (name, token) in [("order-admin", &admin.access_token), ("report-job", &report.access_token)] {
+        for required in ["orders:read", "orders:write"] {
+            match server.authorize(token, required) {
+                Ok(()) => println!("  {} 访问 {}: 允许", name, required),
+                Err(e) => println!("  {} 访问 {}: 拒绝 ({})", name, required, e),
+            }
+        }
+    }
+
+    match server.client_credentials_grant("report-job", "report-secret", "orders:write") {
+        Ok(_) => println!("意外签发了超出范围的令牌"),
+        Err(e) => println!("申请超出允许范围的权限: {}", e),
+    }
+
+    server.revoke(&admin.access_token);
+    println!("吊销后内省: active={}", server.introspect(&admin.access_token).active);
+
+    println!("\n【OAuth模式特点】");
+    println!("✓ 集中授权 - 授权服务器统一签发和管理令牌");
+    println!("✓ 令牌内省 - 资源服务器查询令牌状态和权限范围");
+    println!("✓ 层级范围 - 通配范围覆盖所有子范围");
+    println!("✓ 即时吊销 - 不透明令牌吊销后立即失效");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_matching_rules() {
+        assert!(scope_matches("orders:read", "orders:read"));
+        assert!(scope_matches("orders:*", "orders:read"));
+        assert!(scope_matches("orders:*", "orders:items:write"));
+        assert!(scope_matches("*", "anything"));
+        assert!(!scope_matches("orders:*", "orders"));
+        assert!(!scope_matches("orders:*", "ordersx:read"));
+        assert!(!scope_matches("orders:read", "orders:write"));
+        // 要求通配范围时，具体范围不能满足
+        assert!(!scope_matches("orders:read", "orders:*"));
+    }
+
+    #[test]
+    fn test_wildcard_token_passes_and_narrow_token_fails() {
+        let mut server = AuthorizationServer::new(60, 1);
+        server.register_client("admin", "s1", &["orders:*"]);
+        server.register_client("reader", "s2", &["orders:read"]);
+
+        let admin = server.client_credentials_grant("admin", "s1", "orders:*").unwrap();
+        assert_eq!(server.authorize(&admin.access_token, "orders:read"), Ok(()));
+        assert_eq!(server.authorize(&admin.access_token, "orders:write"), Ok(()));
+        assert_eq!(
+            server.authorize(&admin.access_token, "payments:read"),
+            Err(OAuthError::InsufficientScope { required: "payments:read".to_string() })
+        );
+
+        let reader = server.client_credentials_grant("reader", "s2", "").unwrap();
+        assert_eq!(server.authorize(&reader.access_token, "orders:read"), Ok(()));
+        assert_eq!(
+            server.authorize(&reader.access_token, "orders:write"),
+            Err(OAuthError::InsufficientScope { required: "orders:write".to_string() })
+        );
+        let introspection = server.introspect(&reader.access_token);
+        assert_eq!((introspection.client_id.as_deref(), introspection.scope.as_deref()), (Some("reader"), Some("orders:read")));
+
+        server.revoke(&reader.access_token);
+        assert_eq!(server.authorize(&reader.access_token, "orders:read"), Err(OAuthError::InvalidToken));
+    }
+
+    #[test]
+    fn test_grant_limited_to_allowed_scopes() {
+        let mut server = AuthorizationServer::new(60, 1);
+        server.register_client("admin", "secret", &["orders:*"]);
+
+        // 通配的允许范围可以申请具体的子范围
+        let narrowed = server.client_credentials_grant("admin", "secret", "orders:read").unwrap();
+        assert_eq!(narrowed.scope, "orders:read");
+        assert!(server.authorize(&narrowed.access_token, "orders:write").is_err());
+
+        assert_eq!(server.client_credentials_grant("admin", "secret", "orders:read users:read"),
+                   Err(OAuthError::InvalidScope("users:read".to_string())));
+        assert_eq!(server.client_credentials_grant("admin", "wrong", ""), Err(OAuthError::InvalidClient));
+    }
+}
@@ -32,24 +32,10 @@ pub mod DataManagementPatterns {
 // =================
 pub mod DataConsistencyPatterns {
     pub mod saga_pattern;
-    pub mod two_phase_commit {
-        pub fn demo_two_phase_commit() {
-            println!("=== Two Phase Commit模式演示 ===");
-            println!("两阶段提交协议确保分布式事务的ACID特性");
-        }
-    }
-    pub mod event_sourcing {
-        pub fn demo_event_sourcing() {
-            println!("=== Event Sourcing模式演示 ===");
-            println!("通过存储事件序列来重建应用程序状态");
-        }
-    }
-    pub mod cqrs {
-        pub fn demo_cqrs() {
-            println!("=== CQRS模式演示 ===");
-            println!("命令查询职责分离，分别优化读写操作");
-        }
-    }
+    pub mod two_phase_commit;
+    pub mod event_sourcing;
+    pub mod cqrs;
+    pub mod transactional_outbox;
 }
 
 // =================
@@ -57,22 +43,29 @@ pub mod DataConsistencyPatterns {
 // =================
 pub mod CommunicationPatterns {
     pub mod api_gateway;
-    pub mod service_mesh {
-        pub fn demo_service_mesh() {
-            println!("=== Service Mesh模式演示 ===");
-            println!("服务网格处理服务间通信的基础设施层");
-        }
-    }
-    pub mod message_queue {
-        pub fn demo_message_queue() {
-            println!("=== Message Queue模式演示 ===");
-            println!("异步消息传递，解耦生产者和消费者");
-        }
-    }
+    pub mod sim_network;
+    pub mod service_mesh;
+    pub mod message_queue;
+    pub mod idempotent_consumer;
     pub mod publish_subscribe {
+        use crate::ConcurrentMode::broadcast_channel::{BroadcastChannel, OverflowPolicy};
+
         pub fn demo_publish_subscribe() {
             println!("=== Publish-Subscribe模式演示 ===");
             println!("发布订阅模式支持一对多的消息传递");
+
+            // 每个订阅者独立缓冲：统计服务可以丢弃旧事件，账务服务要求背压
+            let topic = BroadcastChannel::new();
+            let analytics = topic.subscribe(2, OverflowPolicy::DropOldest);
+            let billing = topic.subscribe(8, OverflowPolicy::Block);
+            for order in ["订单1已创建", "订单2已创建", "订单1已支付"] {
+                let _ = topic.send(order.to_string());
+            }
+            topic.close();
+            println!("统计服务丢弃 {} 条旧事件", analytics.dropped_count());
+            while let Ok(event) = billing.recv() {
+                println!("账务服务收到: {}", event);
+            }
         }
     }
 }
@@ -102,24 +95,11 @@ pub mod ServiceDiscoveryPatterns {
 pub mod ResiliencePatterns {
     pub mod circuit_breaker;
     pub mod retry;
-    pub mod bulkhead {
-        pub fn demo_bulkhead() {
-            println!("=== Bulkhead模式演示 ===");
-            println!("舱壁模式隔离资源池，防止级联故障");
-        }
-    }
-    pub mod timeout {
-        pub fn demo_timeout() {
-            println!("=== Timeout模式演示 ===");
-            println!("设置操作超时时间，避免无限等待");
-        }
-    }
-    pub mod rate_limiting {
-        pub fn demo_rate_limiting() {
-            println!("=== Rate Limiting模式演示 ===");
-            println!("限流模式控制请求速率，保护系统稳定");
-        }
-    }
+    pub mod bulkhead;
+    pub mod timeout;
+    pub mod rate_limiting;
+    pub mod resilient;
+    pub mod time_source;
 }
 
 // =================
@@ -146,30 +126,18 @@ pub mod ObservabilityPatterns {
             println!("集中式日志收集和分析");
         }
     }
-    pub mod metrics_collection {
-        pub fn demo_metrics_collection() {
-            println!("=== Metrics Collection模式演示 ===");
-            println!("系统和业务指标收集与监控");
-        }
-    }
+    pub mod metrics_collection;
+    pub mod instrument;
 }
 
 // =================
 // 安全模式
 // =================
 pub mod SecurityPatterns {
-    pub mod oauth {
-        pub fn demo_oauth() {
-            println!("=== OAuth模式演示 ===");
-            println!("OAuth 2.0身份认证和授权协议");
-        }
-    }
-    pub mod api_keys_jwt {
-        pub fn demo_api_keys_jwt() {
-            println!("=== API Keys & JWT模式演示 ===");
-            println!("API密钥和JWT令牌认证");
-        }
-    }
+    #[cfg(feature = "serde")]
+    pub mod oauth;
+    #[cfg(feature = "serde")]
+    pub mod api_keys_jwt;
 }
 
 // =================
@@ -200,18 +168,9 @@ pub mod DeploymentPatterns {
 // 地理分布模式
 // =================
 pub mod GeographicPatterns {
-    pub mod multi_region_deployment {
-        pub fn demo_multi_region_deployment() {
-            println!("=== Multi-Region Deployment模式演示 ===");
-            println!("多区域部署提供全球高可用性");
-        }
-    }
-    pub mod cdn {
-        pub fn demo_cdn() {
-            println!("=== CDN模式演示 ===");
-            println!("内容分发网络加速全球访问");
-        }
-    }
+    pub mod geo_topology;
+    pub mod multi_region_deployment;
+    pub mod cdn;
 }
 
 /// 演示所有分布式系统模式
@@ -229,6 +188,7 @@ pub fn demo_all_distributed_patterns() {
     DataConsistencyPatterns::two_phase_commit::demo_two_phase_commit();
     DataConsistencyPatterns::event_sourcing::demo_event_sourcing();
     DataConsistencyPatterns::cqrs::demo_cqrs();
+    DataConsistencyPatterns::transactional_outbox::demo_transactional_outbox();
     println!();
     
     // 通信模式
@@ -236,6 +196,7 @@ pub fn demo_all_distributed_patterns() {
     CommunicationPatterns::api_gateway::demo_api_gateway();
     CommunicationPatterns::service_mesh::demo_service_mesh();
     CommunicationPatterns::message_queue::demo_message_queue();
+    CommunicationPatterns::idempotent_consumer::demo_idempotent_consumer();
     CommunicationPatterns::publish_subscribe::demo_publish_subscribe();
     println!();
     
@@ -253,6 +214,7 @@ pub fn demo_all_distributed_patterns() {
     ResiliencePatterns::bulkhead::demo_bulkhead();
     ResiliencePatterns::timeout::demo_timeout();
     ResiliencePatterns::rate_limiting::demo_rate_limiting();
+    ResiliencePatterns::resilient::demo_resilient();
     println!();
     
     // 负载均衡模式
@@ -270,7 +232,9 @@ pub fn demo_all_distributed_patterns() {
     
     // 安全模式
     println!("【安全模式】");
+    #[cfg(feature = "serde")]
     SecurityPatterns::oauth::demo_oauth();
+    #[cfg(feature = "serde")]
     SecurityPatterns::api_keys_jwt::demo_api_keys_jwt();
     println!();
     
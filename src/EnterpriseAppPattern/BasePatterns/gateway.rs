@@ -18,19 +18,46 @@
  * - 需要对外部系统进行抽象和封装时
  * - 需要提供测试替身时
  * - 需要统一多个外部系统的访问方式时
+ * 
+ * 录制回放：
+ * RecordReplayGateway 在录制模式下把真实Gateway的响应按请求保存到内存中的cassette，
+ * 回放模式下直接返回录制的响应而不访问真实Gateway，让支付流程的测试可以离线、确定地运行。
+ * 
+ * 重试与超时：
+ * PaymentService 可以配置 with_retry(max_attempts, backoff) 和 with_timeout(Duration)。
+ * 只有 GatewayError::Transient 会按指数退避重试，单次等待不超过 MAX_RETRY_DELAY，
+ * 金额非法等永久性错误立即返回。
+ * 超时返回 GatewayError::Timeout：同步的Gateway无法取消，超时的调用可能仍在后台完成，
+ * 所以只有查询这类幂等调用会在超时后重试，支付和退款超时后直接返回，避免重复扣款。
+ * 配置超时后每次调用都在新的线程上执行，超时的线程会一直运行到Gateway返回，
+ * 线程数只受并发调用数和Gateway响应时间限制，不适合高并发或可能永久挂起的Gateway。
+ * 
+ * 指标埋点：
+ * PaymentService::with_metrics 用 instrumented! 宏生成的 InstrumentedPaymentGateway
+ * 装饰内部Gateway，每次Gateway调用（包括每次重试）都会向 MetricsRegistry
+ * 上报 `payment.<方法名>` 的调用次数和耗时。
  */
 
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use crate::DistributedSystemMode::ObservabilityPatterns::metrics_collection::MetricsRegistry;
 
 /// Gateway错误类型
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum GatewayError {
     NetworkError(String),
     AuthenticationError(String),
     DataNotFound(String),
     InvalidRequest(String),
+    /// 瞬时错误（网络抖动等），可以重试
+    Transient(String),
+    /// 调用超时，结果未知：调用可能仍在后台完成
+    Timeout(String),
 }
 
 impl Display for GatewayError {
@@ -40,6 +67,8 @@ impl Display for GatewayError {
             GatewayError::AuthenticationError(msg) => write!(f, "认证错误: {}", msg),
             GatewayError::DataNotFound(msg) => write!(f, "数据未找到: {}", msg),
             GatewayError::InvalidRequest(msg) => write!(f, "无效请求: {}", msg),
+            GatewayError::Transient(msg) => write!(f, "瞬时错误: {}", msg),
+            GatewayError::Timeout(msg) => write!(f, "调用超时: {}", msg),
         }
     }
 }
@@ -47,7 +76,7 @@ impl Display for GatewayError {
 impl Error for GatewayError {}
 
 /// 第三方支付响应
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PaymentResponse {
     pub transaction_id: String,
     pub status: String,
@@ -103,6 +132,9 @@ impl AlipayGateway {
         if endpoint.contains("invalid") {
             return Err(GatewayError::InvalidRequest("无效的支付请求".to_string()));
         }
+        if endpoint.contains("unavailable") {
+            return Err(GatewayError::Transient("服务暂时不可用".to_string()));
+        }
         
         Ok("success".to_string())
     }
@@ -265,20 +297,117 @@ impl PaymentGateway for WechatPayGateway {
     }
 }
 
+crate::instrumented! {
+    /// 向 MetricsRegistry 上报每个Gateway方法调用次数和耗时的装饰器
+    pub struct InstrumentedPaymentGateway: PaymentGateway {
+        fn process_payment(&self, amount: f64, card_number: &str, description: &str) -> Result<PaymentResponse, GatewayError>;
+        fn query_payment_status(&self, transaction_id: &str) -> Result<PaymentResponse, GatewayError>;
+        fn refund_payment(&self, transaction_id: &str, amount: f64) -> Result<PaymentResponse, GatewayError>;
+    }
+}
+
+type SharedGateway = Arc<dyn PaymentGateway + Send + Sync>;
+
+/// 两次重试之间的最长等待时间
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
 /// 支付服务，使用Gateway模式
 pub struct PaymentService {
-    gateway: Box<dyn PaymentGateway + Send + Sync>,
+    gateway: SharedGateway,
+    max_attempts: u32,
+    backoff: Duration,
+    timeout: Option<Duration>,
 }
 
 impl PaymentService {
     pub fn new(gateway: Box<dyn PaymentGateway + Send + Sync>) -> Self {
-        Self { gateway }
+        Self {
+            gateway: Arc::from(gateway),
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+            timeout: None,
+        }
+    }
+    
+    /// 瞬时错误最多尝试 `max_attempts` 次，第n次重试前等待 backoff * 2^(n-1)，不超过 [`MAX_RETRY_DELAY`]
+    pub fn with_retry(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.backoff = backoff;
+        self
+    }
+    
+    /// 单次调用的超时时间，超时返回 `GatewayError::Timeout`，只有幂等调用会重试
+    ///
+    /// 每次调用都会新建一个线程，超时后线程不会被回收，直到Gateway返回为止。
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    
+    /// 用指标装饰器包装Gateway，指标名以 `payment.` 为前缀
+    pub fn with_metrics(mut self, metrics: MetricsRegistry) -> Self {
+        self.gateway = Arc::new(InstrumentedPaymentGateway::new(self.gateway, metrics, "payment"));
+        self
+    }
+    
+    /// 按重试配置调用Gateway，超过最大次数返回最后一次错误
+    ///
+    /// `idempotent` 为 false 的调用（支付、退款）超时后不重试：超时的调用仍在后台线程上执行，
+    /// 再次调用可能重复扣款或退款。
+    fn call<F>(&self, idempotent: bool, operation: F) -> Result<PaymentResponse, GatewayError>
+    where
+        F: Fn(&dyn PaymentGateway) -> Result<PaymentResponse, GatewayError> + Send + Sync + 'static,
+    {
+        let operation = Arc::new(operation);
+        let mut attempt = 1;
+        loop {
+            let result = self.call_once(&operation);
+            let retryable = match &result {
+                Err(GatewayError::Transient(_)) => true,
+                Err(GatewayError::Timeout(_)) => idempotent,
+                _ => false,
+            };
+            match result {
+                Err(_) if retryable && attempt < self.max_attempts => {
+                    thread::sleep(self.retry_delay(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+    
+    /// 第 `attempt` 次调用失败后的等待时间，溢出时取上限
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt - 1)
+            .and_then(|factor| self.backoff.checked_mul(factor))
+            .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+    }
+    
+    fn call_once<F>(&self, operation: &Arc<F>) -> Result<PaymentResponse, GatewayError>
+    where
+        F: Fn(&dyn PaymentGateway) -> Result<PaymentResponse, GatewayError> + Send + Sync + 'static,
+    {
+        let Some(timeout) = self.timeout else {
+            return operation(self.gateway.as_ref());
+        };
+        
+        // 同步的Gateway无法中途取消，超时后放弃等待，后台线程在Gateway返回后自行结束
+        let (sender, receiver) = mpsc::channel();
+        let gateway = Arc::clone(&self.gateway);
+        let operation = Arc::clone(operation);
+        thread::spawn(move || {
+            let _ = sender.send(operation(gateway.as_ref()));
+        });
+        receiver.recv_timeout(timeout)
+            .unwrap_or_else(|_| Err(GatewayError::Timeout(format!("调用超过{}ms未返回", timeout.as_millis()))))
     }
     
     /// 统一的支付接口
     pub fn make_payment(&self, amount: f64, card_number: &str, description: &str) -> Result<PaymentResponse, GatewayError> {
         println!("正在处理支付请求...");
-        let result = self.gateway.process_payment(amount, card_number, description);
+        let (card_number, description) = (card_number.to_string(), description.to_string());
+        let result = self.call(false, move |gateway| gateway.process_payment(amount, &card_number, &description));
         
         match &result {
             Ok(response) => println!("支付处理完成: {}", response),
@@ -290,13 +419,15 @@ impl PaymentService {
     
     /// 查询支付状态
     pub fn check_payment_status(&self, transaction_id: &str) -> Result<PaymentResponse, GatewayError> {
-        self.gateway.query_payment_status(transaction_id)
+        let transaction_id = transaction_id.to_string();
+        self.call(true, move |gateway| gateway.query_payment_status(&transaction_id))
     }
     
     /// 处理退款
     pub fn process_refund(&self, transaction_id: &str, amount: f64) -> Result<PaymentResponse, GatewayError> {
         println!("正在处理退款请求...");
-        let result = self.gateway.refund_payment(transaction_id, amount);
+        let transaction_id = transaction_id.to_string();
+        let result = self.call(false, move |gateway| gateway.refund_payment(&transaction_id, amount));
         
         match &result {
             Ok(response) => println!("退款处理完成: {}", response),
@@ -307,6 +438,128 @@ impl PaymentService {
     }
 }
 
+/// 录制回放模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// 录制的交互：请求键 -> 按调用顺序记录的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cassette {
+    interactions: HashMap<String, Vec<Result<PaymentResponse, GatewayError>>>,
+}
+
+impl Cassette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 录制的交互总数
+    pub fn len(&self) -> usize {
+        self.interactions.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn payment_key(amount: f64, card_number: &str, description: &str) -> String {
+        format!("process_payment|{:.2}|{}|{}", amount, card_number, description)
+    }
+
+    fn query_key(transaction_id: &str) -> String {
+        format!("query_payment_status|{}", transaction_id)
+    }
+
+    fn refund_key(transaction_id: &str, amount: f64) -> String {
+        format!("refund_payment|{}|{:.2}", transaction_id, amount)
+    }
+}
+
+struct CassetteState {
+    cassette: Cassette,
+    // 回放模式下每个请求键已经回放到的位置
+    replay_positions: HashMap<String, usize>,
+}
+
+/// 录制回放Gateway
+///
+/// 同一个请求被调用多次时按录制顺序依次回放，回放次数超过录制次数或请求未录制时返回错误
+pub struct RecordReplayGateway {
+    inner: Option<Box<dyn PaymentGateway + Send + Sync>>,
+    state: std::sync::Mutex<CassetteState>,
+}
+
+impl RecordReplayGateway {
+    /// 录制模式：调用真实Gateway并记录响应
+    pub fn record(inner: Box<dyn PaymentGateway + Send + Sync>) -> Self {
+        Self::with_state(Some(inner), Cassette::new())
+    }
+
+    /// 回放模式：只使用cassette中的响应，不需要真实Gateway
+    pub fn replay(cassette: Cassette) -> Self {
+        Self::with_state(None, cassette)
+    }
+
+    fn with_state(inner: Option<Box<dyn PaymentGateway + Send + Sync>>, cassette: Cassette) -> Self {
+        Self {
+            inner,
+            state: std::sync::Mutex::new(CassetteState { cassette, replay_positions: HashMap::new() }),
+        }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        if self.inner.is_some() { CassetteMode::Record } else { CassetteMode::Replay }
+    }
+
+    /// 当前录制的内容
+    pub fn cassette(&self) -> Cassette {
+        self.state.lock().unwrap().cassette.clone()
+    }
+
+    fn exchange<F>(&self, key: String, call: F) -> Result<PaymentResponse, GatewayError>
+    where
+        F: FnOnce(&dyn PaymentGateway) -> Result<PaymentResponse, GatewayError>,
+    {
+        match &self.inner {
+            Some(inner) => {
+                let result = call(inner.as_ref());
+                let mut state = self.state.lock().unwrap();
+                state.cassette.interactions.entry(key).or_default().push(result.clone());
+                result
+            }
+            None => {
+                let mut state = self.state.lock().unwrap();
+                let position = state.replay_positions.get(&key).copied().unwrap_or(0);
+                let recorded = state.cassette.interactions.get(&key)
+                    .and_then(|results| results.get(position))
+                    .cloned()
+                    .ok_or_else(|| GatewayError::DataNotFound(format!("cassette中没有录制请求: {}", key)))?;
+                state.replay_positions.insert(key, position + 1);
+                recorded
+            }
+        }
+    }
+}
+
+impl PaymentGateway for RecordReplayGateway {
+    fn process_payment(&self, amount: f64, card_number: &str, description: &str) -> Result<PaymentResponse, GatewayError> {
+        self.exchange(Cassette::payment_key(amount, card_number, description), |gateway| {
+            gateway.process_payment(amount, card_number, description)
+        })
+    }
+
+    fn query_payment_status(&self, transaction_id: &str) -> Result<PaymentResponse, GatewayError> {
+        self.exchange(Cassette::query_key(transaction_id), |gateway| gateway.query_payment_status(transaction_id))
+    }
+
+    fn refund_payment(&self, transaction_id: &str, amount: f64) -> Result<PaymentResponse, GatewayError> {
+        self.exchange(Cassette::refund_key(transaction_id, amount), |gateway| gateway.refund_payment(transaction_id, amount))
+    }
+}
+
 /// Gateway模式演示
 pub fn demo() {
     println!("=== Gateway（入口）模式演示 ===\n");
@@ -389,6 +642,22 @@ pub fn demo() {
         Err(e) => println!("预期错误: {}", e),
     }
     
+    println!("{}", "=".repeat(50));
+    
+    // 4. 录制回放
+    println!("4. 录制回放演示:");
+    let recorder = RecordReplayGateway::record(Box::new(AlipayGateway::new(
+        "record_key".to_string(),
+        "record_app".to_string(),
+    )));
+    let recorded = recorder.process_payment(88.0, "6222000011112222", "录制支付");
+    let cassette = recorder.cassette();
+    println!("录制了 {} 次交互", cassette.len());
+    
+    let replay_service = PaymentService::new(Box::new(RecordReplayGateway::replay(cassette)));
+    let replayed = replay_service.make_payment(88.0, "6222000011112222", "录制支付");
+    println!("回放结果与录制一致: {}", recorded == replayed);
+    
     println!("\n=== Gateway模式演示完成 ===");
     
     // 输出模式总结
@@ -396,7 +665,7 @@ pub fn demo() {
     println!("优点:");
     println!("1. 封装复杂性：隐藏外部API的复杂性");
     println!("2. 统一接口：为不同的外部系统提供统一的访问方式");
-    println!("3. 易于测试：可以轻松创建Mock实现进行测试");
+    println!("3. 易于测试：可以轻松创建Mock实现进行测试，或录制回放真实响应");
     println!("4. 错误处理：统一的错误处理机制");
     println!("5. 可替换性：可以轻松切换不同的外部服务提供商");
     
@@ -427,4 +696,198 @@ mod chrono {
                 .as_millis()
         }
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_serves_recorded_charge_without_real_gateway() {
+        let recorder = RecordReplayGateway::record(Box::new(AlipayGateway::new("key".to_string(), "app".to_string())));
+        assert_eq!(recorder.mode(), CassetteMode::Record);
+        let charge = recorder.process_payment(100.0, "1234567890", "购买商品").unwrap();
+        let refund = recorder.refund_payment(&charge.transaction_id, 30.0).unwrap();
+        let invalid = recorder.process_payment(-1.0, "1234567890", "无效金额");
+        assert!(invalid.is_err());
+
+        let cassette = recorder.cassette();
+        // 退款内部的状态查询发生在真实Gateway内部，不经过录制层
+        assert_eq!(cassette.len(), 3);
+        let replayer = RecordReplayGateway::replay(cassette);
+        assert_eq!(replayer.mode(), CassetteMode::Replay);
+
+        // 回放得到与录制时完全相同的输出，包括交易ID和错误
+        assert_eq!(replayer.process_payment(100.0, "1234567890", "购买商品"), Ok(charge.clone()));
+        assert_eq!(replayer.refund_payment(&charge.transaction_id, 30.0), Ok(refund));
+        assert_eq!(replayer.process_payment(-1.0, "1234567890", "无效金额"), invalid);
+    }
+
+    #[test]
+    fn test_replay_rejects_unrecorded_or_exhausted_requests() {
+        let recorder = RecordReplayGateway::record(Box::new(WechatPayGateway::new("merchant".to_string(), "secret".to_string())));
+        let charge = recorder.process_payment(20.0, "card", "充值").unwrap();
+
+        let service = PaymentService::new(Box::new(RecordReplayGateway::replay(recorder.cassette())));
+        assert_eq!(service.make_payment(20.0, "card", "充值"), Ok(charge));
+        // 同一请求只录制了一次
+        assert!(matches!(service.make_payment(20.0, "card", "充值"), Err(GatewayError::DataNotFound(_))));
+        // 参数不同的请求没有录制
+        assert!(matches!(service.make_payment(21.0, "card", "充值"), Err(GatewayError::DataNotFound(_))));
+    }
+
+    /// 前 `failures` 次调用返回瞬时错误，之后正常处理的mock网关
+    struct FlakyGateway {
+        failures: u32,
+        delay: Duration,
+        calls: Arc<std::sync::atomic::AtomicU32>,
+    }
+
+    impl FlakyGateway {
+        fn new(failures: u32) -> (Self, Arc<std::sync::atomic::AtomicU32>) {
+            let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+            (Self { failures, delay: Duration::ZERO, calls: Arc::clone(&calls) }, calls)
+        }
+
+        fn respond(&self, amount: f64) -> Result<PaymentResponse, GatewayError> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            thread::sleep(self.delay);
+            if amount <= 0.0 {
+                return Err(GatewayError::InvalidRequest("支付金额必须大于0".to_string()));
+            }
+            if call <= self.failures {
+                return Err(GatewayError::Transient(format!("第{}次调用网络抖动", call)));
+            }
+            Ok(PaymentResponse {
+                transaction_id: format!("flaky_{}", call),
+                status: "success".to_string(),
+                amount,
+                message: "支付成功".to_string(),
+            })
+        }
+    }
+
+    impl PaymentGateway for FlakyGateway {
+        fn process_payment(&self, amount: f64, _card_number: &str, _description: &str) -> Result<PaymentResponse, GatewayError> {
+            self.respond(amount)
+        }
+
+        fn query_payment_status(&self, _transaction_id: &str) -> Result<PaymentResponse, GatewayError> {
+            self.respond(1.0)
+        }
+
+        fn refund_payment(&self, _transaction_id: &str, amount: f64) -> Result<PaymentResponse, GatewayError> {
+            self.respond(amount)
+        }
+    }
+
+    fn calls(counter: &Arc<std::sync::atomic::AtomicU32>) -> u32 {
+        counter.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// 超时的调用在后台线程上执行，等它们开始后再读取调用次数
+    fn calls_after_timeouts(counter: &Arc<std::sync::atomic::AtomicU32>, expected: u32) -> u32 {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while calls(counter) < expected && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        calls(counter)
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped_instead_of_overflowing() {
+        let (gateway, _) = FlakyGateway::new(0);
+        let service = PaymentService::new(Box::new(gateway)).with_retry(u32::MAX, Duration::from_secs(1));
+
+        assert_eq!(service.retry_delay(1), Duration::from_secs(1));
+        assert_eq!(service.retry_delay(3), Duration::from_secs(4));
+        assert_eq!(service.retry_delay(6), MAX_RETRY_DELAY);
+        // 2^(n-1) 超出 u32 或乘积超出 Duration 时取上限而不是 panic
+        assert_eq!(service.retry_delay(40), MAX_RETRY_DELAY);
+        assert_eq!(service.retry_delay(u32::MAX), MAX_RETRY_DELAY);
+
+        let service = PaymentService::new(Box::new(FlakyGateway::new(0).0)).with_retry(40, Duration::MAX / 2);
+        assert_eq!(service.retry_delay(2), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_transient_errors_retried_with_exponential_backoff() {
+        let (gateway, counter) = FlakyGateway::new(2);
+        let service = PaymentService::new(Box::new(gateway)).with_retry(3, Duration::from_millis(10));
+
+        let started = std::time::Instant::now();
+        let response = service.make_payment(50.0, "card", "重试").unwrap();
+        assert_eq!(response.transaction_id, "flaky_3");
+        assert_eq!(calls(&counter), 3);
+        // 两次重试分别等待10ms和20ms
+        assert!(started.elapsed() >= Duration::from_millis(30));
+
+        // 默认不重试
+        let (gateway, counter) = FlakyGateway::new(1);
+        let service = PaymentService::new(Box::new(gateway));
+        assert!(matches!(service.check_payment_status("tx"), Err(GatewayError::Transient(_))));
+        assert_eq!(calls(&counter), 1);
+    }
+
+    #[test]
+    fn test_retry_gives_up_with_last_error_and_skips_permanent_errors() {
+        let (gateway, counter) = FlakyGateway::new(10);
+        let service = PaymentService::new(Box::new(gateway)).with_retry(3, Duration::from_millis(1));
+        assert_eq!(service.make_payment(50.0, "card", "失败"),
+                   Err(GatewayError::Transient("第3次调用网络抖动".to_string())));
+        assert_eq!(calls(&counter), 3);
+
+        // 金额非法是永久性错误，只调用一次
+        let (gateway, counter) = FlakyGateway::new(0);
+        let service = PaymentService::new(Box::new(gateway)).with_retry(3, Duration::from_millis(1));
+        assert!(matches!(service.process_refund("tx", -1.0), Err(GatewayError::InvalidRequest(_))));
+        assert_eq!(calls(&counter), 1);
+    }
+
+    #[test]
+    fn test_instrumented_gateway_records_calls_and_timings() {
+        let metrics = MetricsRegistry::new();
+        let (mut gateway, _) = FlakyGateway::new(1);
+        gateway.delay = Duration::from_millis(5);
+        let service = PaymentService::new(Box::new(gateway))
+            .with_retry(2, Duration::from_millis(1))
+            .with_metrics(metrics.clone());
+
+        assert!(service.make_payment(50.0, "card", "埋点").is_ok());
+        // 每次重试都是一次独立的Gateway调用
+        assert_eq!(metrics.counter("payment.process_payment"), 2);
+        let timings = metrics.timings("payment.process_payment");
+        assert_eq!(timings.len(), 2);
+        assert!(timings.iter().all(|timing| *timing >= Duration::from_millis(5)));
+
+        assert!(service.check_payment_status("tx").is_ok());
+        assert_eq!(metrics.counter("payment.query_payment_status"), 1);
+        assert_eq!(metrics.timing_summary("payment.query_payment_status").unwrap().count, 1);
+        assert_eq!(metrics.counter("payment.refund_payment"), 0);
+        assert!(metrics.timings("payment.refund_payment").is_empty());
+    }
+
+    #[test]
+    fn test_timeout_retries_only_idempotent_calls() {
+        let (mut gateway, counter) = FlakyGateway::new(0);
+        gateway.delay = Duration::from_millis(200);
+        let service = PaymentService::new(Box::new(gateway))
+            .with_timeout(Duration::from_millis(20))
+            .with_retry(2, Duration::from_millis(1));
+
+        // 支付和退款超时后可能仍在后台完成，不能重试
+        let result = service.make_payment(50.0, "card", "慢调用");
+        assert_eq!(result, Err(GatewayError::Timeout("调用超过20ms未返回".to_string())));
+        assert_eq!(calls_after_timeouts(&counter, 1), 1);
+        assert!(matches!(service.process_refund("tx", 50.0), Err(GatewayError::Timeout(_))));
+        assert_eq!(calls_after_timeouts(&counter, 2), 2);
+
+        // 查询是幂等的，超时后重试
+        assert!(matches!(service.check_payment_status("tx"), Err(GatewayError::Timeout(_))));
+        assert_eq!(calls_after_timeouts(&counter, 4), 4);
+
+        let (gateway, _) = FlakyGateway::new(0);
+        let service = PaymentService::new(Box::new(gateway)).with_timeout(Duration::from_secs(5));
+        assert!(service.make_payment(50.0, "card", "快调用").is_ok());
+    }
+}
@@ -6,6 +6,7 @@
 //! 
 //! 文件位置：/d%3A/workspace/RustLearn/RustDesignPattern/src/EnterpriseAppPattern/BasePatterns/mapper.rs
 
+use std::any::{type_name, Any, TypeId};
 use std::collections::HashMap;
 use std::fmt;
 
@@ -139,6 +140,20 @@ pub trait Mapper<T> {
     fn update_row(&self, object: &T, row: &mut dyn DataRow) -> Result<(), Self::Error>;
 }
 
+/// 类型之间的映射器接口 - 将源类型A转换为目标类型B
+pub trait TypeMapper<A, B> {
+    fn map(&self, source: &A) -> Result<B, MapperError>;
+}
+
+impl<A, B, F> TypeMapper<A, B> for F
+where
+    F: Fn(&A) -> Result<B, MapperError>,
+{
+    fn map(&self, source: &A) -> Result<B, MapperError> {
+        self(source)
+    }
+}
+
 // =================
 // 具体实体类
 // =================
@@ -256,6 +271,8 @@ pub enum MapperError {
     MissingField(String),
     InvalidValue(String),
     TypeConversion(String),
+    /// 没有为 (源类型, 目标类型) 注册映射器
+    MapperNotFound { source: &'static str, target: &'static str },
 }
 
 impl fmt::Display for MapperError {
@@ -264,6 +281,9 @@ impl fmt::Display for MapperError {
             MapperError::MissingField(field) => write!(f, "缺少字段: {}", field),
             MapperError::InvalidValue(msg) => write!(f, "无效值: {}", msg),
             MapperError::TypeConversion(msg) => write!(f, "类型转换错误: {}", msg),
+            MapperError::MapperNotFound { source, target } => {
+                write!(f, "未注册映射器: {} -> {}", source, target)
+            }
         }
     }
 }
@@ -469,17 +489,41 @@ pub struct MapperRegistry {
     user_mapper: UserMapper,
     product_mapper: ProductMapper,
     order_mapper: OrderMapper,
+    // (源类型, 目标类型) -> Box<dyn TypeMapper<A, B>>
+    type_mappers: HashMap<(TypeId, TypeId), Box<dyn Any + Send + Sync>>,
 }
 
+type BoxedTypeMapper<A, B> = Box<dyn TypeMapper<A, B> + Send + Sync>;
+
 impl MapperRegistry {
     pub fn new() -> Self {
         Self {
             user_mapper: UserMapper,
             product_mapper: ProductMapper,
             order_mapper: OrderMapper,
+            type_mappers: HashMap::new(),
         }
     }
     
+    /// 注册 A -> B 的映射器，同一类型对重复注册时替换旧的映射器
+    pub fn register<A: 'static, B: 'static>(&mut self, mapper: impl TypeMapper<A, B> + Send + Sync + 'static) {
+        let boxed: BoxedTypeMapper<A, B> = Box::new(mapper);
+        self.type_mappers.insert((TypeId::of::<A>(), TypeId::of::<B>()), Box::new(boxed));
+    }
+    
+    /// 按 (源类型, 目标类型) 查找映射器
+    pub fn resolve<A: 'static, B: 'static>(&self) -> Result<&(dyn TypeMapper<A, B> + Send + Sync), MapperError> {
+        self.type_mappers.get(&(TypeId::of::<A>(), TypeId::of::<B>()))
+            .and_then(|mapper| mapper.downcast_ref::<BoxedTypeMapper<A, B>>())
+            .map(|mapper| mapper.as_ref())
+            .ok_or(MapperError::MapperNotFound { source: type_name::<A>(), target: type_name::<B>() })
+    }
+    
+    /// 查找映射器并执行 A -> B 的映射
+    pub fn map<A: 'static, B: 'static>(&self, source: &A) -> Result<B, MapperError> {
+        self.resolve::<A, B>()?.map(source)
+    }
+    
     pub fn get_user_mapper(&self) -> &UserMapper {
         &self.user_mapper
     }
@@ -588,7 +632,9 @@ impl Mapper<UserStatistics> for UserStatisticsMapper {
 pub fn demo_mapper_pattern() {
     println!("=== 映射器（Mapper）模式演示 ===\n");
     
-    let registry = MapperRegistry::new();
+    let mut registry = MapperRegistry::new();
+    registry.register::<User, String>(|user: &User| Ok(format!("{} <{}>", user.username, user.email)));
+    registry.register::<Order, String>(|order: &Order| Ok(format!("订单#{} {:.2} ({})", order.id.unwrap_or(0), order.total_amount, order.status)));
     
     println!("1. 用户映射器演示:");
     
@@ -671,7 +717,15 @@ pub fn demo_mapper_pattern() {
     println!("  平均订单金额: {:.2}", user_stats.average_order_value);
     println!("  最后订单日期: {}\n", user_stats.last_order_date);
     
-    println!("5. 错误处理演示:");
+    println!("5. 按类型对查找映射器:");
+    println!("  User -> String: {}", registry.map::<User, String>(&user).unwrap());
+    println!("  Order -> String: {}", registry.map::<Order, String>(&order).unwrap());
+    if let Err(error) = registry.map::<Product, String>(&product) {
+        println!("  Product -> String: {}", error);
+    }
+    println!();
+    
+    println!("6. 错误处理演示:");
     
     // 创建缺少必需字段的数据行
     let mut invalid_data = HashMap::new();
@@ -692,4 +746,53 @@ pub fn demo_mapper_pattern() {
     println!("✓ 扩展性 - 易于添加新的映射规则和数据类型");
     println!("✓ 错误处理 - 完善的错误处理机制");
     println!("✓ 复杂映射 - 支持复杂对象和聚合映射");
-}
\ No newline at end of file
+    println!("✓ 类型对注册 - 按 (源类型, 目标类型) 通用地查找映射器");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry_with_mappers() -> MapperRegistry {
+        let mut registry = MapperRegistry::new();
+        registry.register::<User, String>(|user: &User| Ok(user.username.clone()));
+        registry.register::<Product, f64>(|product: &Product| {
+            if product.price < 0.0 {
+                return Err(MapperError::InvalidValue("价格不能为负".to_string()));
+            }
+            Ok(product.price * 1.25)
+        });
+        registry
+    }
+
+    #[test]
+    fn test_resolve_each_mapper_by_type_pair() {
+        let registry = registry_with_mappers();
+        let user = User::new("张三".to_string(), "zhangsan@example.com".to_string(), 30, 8000.0);
+        let product = Product::new("键盘".to_string(), "机械键盘".to_string(), 100.0, 1);
+
+        assert_eq!(registry.map::<User, String>(&user).unwrap(), "张三");
+        assert_eq!(registry.resolve::<Product, f64>().unwrap().map(&product).unwrap(), 125.0);
+
+        let mut negative = product.clone();
+        negative.price = -1.0;
+        assert!(matches!(registry.map::<Product, f64>(&negative), Err(MapperError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_unregistered_pair_returns_clear_error() {
+        let registry = registry_with_mappers();
+        let user = User::new("李四".to_string(), "lisi@example.com".to_string(), 25, 6000.0);
+
+        // 源类型已注册但目标类型不同，也算未注册
+        let error = registry.map::<User, f64>(&user).unwrap_err();
+        match &error {
+            MapperError::MapperNotFound { source, target } => {
+                assert!(source.ends_with("User"));
+                assert_eq!(*target, "f64");
+            }
+            other => panic!("应该找不到映射器: {:?}", other),
+        }
+        assert!(error.to_string().contains("未注册映射器"));
+    }
+}
@@ -12,6 +12,8 @@
  * 4. Mapper（映射器）- 对象与数据源之间的映射转换
  * 5. Money（金钱）- 货币值对象，支持多币种和精确计算
  * 
+ * 演示输出通过 reporter 模块的 Reporter 抽象发出，便于在测试中捕获。
+ * 
  * 这些模式是构建企业应用的基础构建块，为上层的业务逻辑模式和架构模式提供支持。
  */
 
@@ -79,6 +81,12 @@
 //! - **描述**: 内存中表格数据的面向对象表示
 //! - **优点**: 统一的数据访问接口，支持多种数据源，内存操作高效
 //! - **适用**: 数据表示，报表生成，批量数据处理
+//!
+//! ### 12. 分页结果 (Page)
+//! - **文件**: `page.rs`
+//! - **描述**: 仓储、查询对象和页面控制器共用的分页结果类型
+//! - **优点**: 统一页码和总页数的计算，避免各查询层重复实现
+//! - **适用**: 列表查询，分页浏览，API分页响应
 
 pub mod gateway;
 pub mod registry;
@@ -89,124 +97,161 @@ pub mod layer_supertype;
 pub mod separated_interface;
 pub mod special_case;
 pub mod plugin;
+pub mod reporter;
+pub mod page;
 
 // 重新导出主要的公共接口
-pub use gateway::{PaymentGateway, PaymentService, PaymentResponse, GatewayError};
-pub use registry::{ServiceRegistry, ConfigRegistry, ServiceFactory, ServiceLifetime, RegistryError, global_registry, global_config};
-pub use value_object::{DateRange, EmailAddress, ProductSpecification, ValueObjectError};
-pub use mapper::{Mapper, MapperError, DataRow, HashMapDataRow, UserMapper, ProductMapper, OrderMapper, MapperRegistry};
+pub use gateway::{PaymentGateway, PaymentService, InstrumentedPaymentGateway, PaymentResponse, GatewayError, RecordReplayGateway, Cassette, CassetteMode};
+pub use registry::{ServiceRegistry, ConfigRegistry, ConfigValue, ConfigSchema, ConfigConstraint, RegistryChain, ScopeHandle, ServiceFactory, ServiceLifetime, RegistryError, global_registry, global_config};
+pub use value_object::{Date, DateRange, DaysIter, EmailAddress, ProductSpecification, CatalogProduct, ValueObjectError, BatchBuild, build_batch};
+pub use mapper::{Mapper, TypeMapper, MapperError, DataRow, HashMapDataRow, UserMapper, ProductMapper, OrderMapper, MapperRegistry};
 pub use money::{Money, Currency, MoneyError, CurrencyConverter, MoneyBag};
 pub use layer_supertype::{DomainObject, DataAccessObject, BusinessService, BusinessContext, TransactionContext, BusinessError, Product, Order, ProductDAO, ProductService};
 pub use separated_interface::*;
+pub use reporter::{Reporter, ConsoleReporter, BufferReporter, ReportEntry};
+pub use page::Page;
 
 /// 演示所有基础模式
 pub fn demo_all() {
-    println!("=== 基础模式总览 ===\n");
-    
-    println!("📋 基础模式分类表:");
-    println!("┌──────────────────┬──────────────────┬──────────────────┬──────────────────┐");
-    println!("│ 模式类型         │ 主要职责         │ 核心优势         │ 典型应用         │");
-    println!("├──────────────────┼──────────────────┼──────────────────┼──────────────────┤");
-    println!("│ 网关 (Gateway)   │ 外部系统访问     │ 接口统一         │ API调用, 数据库  │");
-    println!("│ 映射器 (Mapper)  │ 对象转换映射     │ 对象独立         │ ORM, 数据转换    │");
-    println!("│ 注册表 (Registry)│ 全局对象访问     │ 服务定位         │ IoC容器, 配置    │");
-    println!("│ 值对象 (Value)   │ 值概念表示       │ 不可变性         │ 金额, 日期范围   │");
-    println!("│ 金钱 (Money)     │ 货币安全计算     │ 类型安全         │ 财务, 电商系统   │");
-    println!("└──────────────────┴──────────────────┴──────────────────┴──────────────────┘");
-    
-    println!("\n📋 新增模式分类表:");
-    println!("┌──────────────────┬──────────────────┬──────────────────┬──────────────────┐");
-    println!("│ 模式类型         │ 主要职责         │ 核心优势         │ 典型应用         │");
-    println!("├──────────────────┼──────────────────┼──────────────────┼──────────────────┤");
-    println!("│ 层超类型         │ 层内共同行为     │ 减少重复代码     │ 实体基类, DAO    │");
-    println!("│ 分离接口         │ 接口与实现分离   │ 降低依赖         │ 插件架构, 测试   │");
-    println!("│ 特殊情况         │ 特殊情况处理     │ 减少条件逻辑     │ 空对象, 默认值   │");
-    println!("│ 插件 (Plugin)    │ 动态功能扩展     │ 运行时配置       │ 插件系统, 扩展   │");
-    println!("│ 服务存根         │ 测试服务替身     │ 隔离外部依赖     │ 单元测试, Mock   │");
-    println!("│ 记录集           │ 内存数据表示     │ 统一数据接口     │ 报表, 批处理     │");
-    println!("└──────────────────┴──────────────────┴──────────────────┴──────────────────┘");
-    
-    println!("\n🎯 模式选择指南:");
-    
-    println!("\n• 外部系统集成:");
-    println!("  ✅ 网关模式 - 统一外部接口访问");
-    println!("  ✅ 映射器模式 - 处理数据格式转换");
-    println!("  ✅ 服务存根 - 开发测试时的服务替身");
-    
-    println!("\n• 对象管理:");
-    println!("  ✅ 注册表模式 - 全局对象访问和服务定位");
-    println!("  ✅ 层超类型 - 为同一层提供共同行为");
-    println!("  ✅ 分离接口 - 解耦接口定义和实现");
-    
-    println!("\n• 值和数据表示:");
-    println!("  ✅ 值对象模式 - 表示领域中的值概念");
-    println!("  ✅ 金钱模式 - 安全的货币计算");
-    println!("  ✅ 记录集模式 - 内存中的表格数据");
-    
-    println!("\n• 特殊情况处理:");
-    println!("  ✅ 特殊情况模式 - 避免大量条件判断");
-    println!("  ✅ 插件模式 - 支持动态功能扩展");
-    
-    println!("\n💡 设计原则:");
-    println!("  1. 单一职责原则 - 每个模式专注解决特定问题");
-    println!("  2. 开闭原则 - 对扩展开放，对修改关闭");
-    println!("  3. 依赖倒置原则 - 依赖抽象而非具体");
-    println!("  4. 接口隔离原则 - 客户端不应依赖不需要的接口");
-    
-    println!("\n🔗 模式组合使用:");
-    println!("  • 网关 + 映射器 - 外部系统集成的完整解决方案");
-    println!("  • 注册表 + 分离接口 - 依赖注入和解耦");
-    println!("  • 值对象 + 特殊情况 - 领域模型的完善表达");
-    println!("  • 层超类型 + 插件 - 可扩展的分层架构");
-    
-    println!("\n{}", "=".repeat(80));
-    
-    // 演示网关模式
+    demo_all_with(&mut ConsoleReporter::new());
+}
+
+/// 演示所有基础模式，总览部分通过 `reporter` 输出
+pub fn demo_all_with(reporter: &mut dyn Reporter) {
+    report_overview(reporter);
+
+    // 各模式的详细演示仍直接打印，这里只标记它们的位置
+    reporter.section("网关模式演示");
     gateway::demo();
-    
-    println!("\n{}", "=".repeat(80));
-    
-    // 演示注册表模式
+
+    reporter.section("注册表模式演示");
     registry::demo();
-    
-    println!("\n{}", "=".repeat(80));
-    
-    // 演示值对象模式
+
+    reporter.section("值对象模式演示");
     value_object::demo();
-    
-    println!("\n{}", "=".repeat(80));
-    
-    // 演示层超类型模式
+
+    reporter.section("层超类型模式演示");
     layer_supertype::demo();
-    
-    println!("\n=== 基础模式演示全部完成 ===");
-    
-    println!("\n🎓 学习建议:");
-    println!("  1. 先掌握基础模式:");
-    println!("     - 值对象：理解值语义和不可变性");
-    println!("     - 网关：学习外部系统集成");
-    println!("     - 映射器：掌握对象转换技术");
-    
-    println!("\n  2. 进阶到架构模式:");
-    println!("     - 注册表：服务定位和依赖注入");
-    println!("     - 层超类型：分层架构设计");
-    println!("     - 分离接口：模块化和解耦");
-    
-    println!("\n  3. 专业化应用:");
-    println!("     - 特殊情况：高质量代码设计");
-    println!("     - 插件：可扩展系统架构");
-    println!("     - 服务存根：测试驱动开发");
-    
-    println!("\n📚 相关资源:");
-    println!("  • Martin Fowler《企业应用架构模式》");
-    println!("  • Eric Evans《领域驱动设计》");
-    println!("  • Clean Architecture principles");
-    println!("  • SOLID design principles");
-    
-    println!("\n🚀 实践建议:");
-    println!("  • 从简单项目开始应用这些模式");
-    println!("  • 重点关注模式解决的问题");
-    println!("  • 避免过度设计和模式滥用");
-    println!("  • 结合具体场景选择合适的模式");
-    println!("  • 持续重构和改进代码质量");
-}
\ No newline at end of file
+
+    report_guidance(reporter);
+}
+
+/// 总览：分类表、选择指南、设计原则和模式组合
+fn report_overview(reporter: &mut dyn Reporter) {
+    const HEADERS: [&str; 4] = ["模式类型", "主要职责", "核心优势", "典型应用"];
+
+    reporter.section("基础模式总览");
+
+    reporter.section("📋 基础模式分类表");
+    reporter.table(&HEADERS, &[
+        &["网关 (Gateway)", "外部系统访问", "接口统一", "API调用, 数据库"],
+        &["映射器 (Mapper)", "对象转换映射", "对象独立", "ORM, 数据转换"],
+        &["注册表 (Registry)", "全局对象访问", "服务定位", "IoC容器, 配置"],
+        &["值对象 (Value)", "值概念表示", "不可变性", "金额, 日期范围"],
+        &["金钱 (Money)", "货币安全计算", "类型安全", "财务, 电商系统"],
+    ]);
+
+    reporter.section("📋 新增模式分类表");
+    reporter.table(&HEADERS, &[
+        &["层超类型", "层内共同行为", "减少重复代码", "实体基类, DAO"],
+        &["分离接口", "接口与实现分离", "降低依赖", "插件架构, 测试"],
+        &["特殊情况", "特殊情况处理", "减少条件逻辑", "空对象, 默认值"],
+        &["插件 (Plugin)", "动态功能扩展", "运行时配置", "插件系统, 扩展"],
+        &["服务存根", "测试服务替身", "隔离外部依赖", "单元测试, Mock"],
+        &["记录集", "内存数据表示", "统一数据接口", "报表, 批处理"],
+    ]);
+
+    reporter.section("🎯 模式选择指南");
+    reporter.line("• 外部系统集成:");
+    reporter.line("  ✅ 网关模式 - 统一外部接口访问");
+    reporter.line("  ✅ 映射器模式 - 处理数据格式转换");
+    reporter.line("  ✅ 服务存根 - 开发测试时的服务替身");
+    reporter.line("• 对象管理:");
+    reporter.line("  ✅ 注册表模式 - 全局对象访问和服务定位");
+    reporter.line("  ✅ 层超类型 - 为同一层提供共同行为");
+    reporter.line("  ✅ 分离接口 - 解耦接口定义和实现");
+    reporter.line("• 值和数据表示:");
+    reporter.line("  ✅ 值对象模式 - 表示领域中的值概念");
+    reporter.line("  ✅ 金钱模式 - 安全的货币计算");
+    reporter.line("  ✅ 记录集模式 - 内存中的表格数据");
+    reporter.line("• 特殊情况处理:");
+    reporter.line("  ✅ 特殊情况模式 - 避免大量条件判断");
+    reporter.line("  ✅ 插件模式 - 支持动态功能扩展");
+
+    reporter.section("💡 设计原则");
+    reporter.line("  1. 单一职责原则 - 每个模式专注解决特定问题");
+    reporter.line("  2. 开闭原则 - 对扩展开放，对修改关闭");
+    reporter.line("  3. 依赖倒置原则 - 依赖抽象而非具体");
+    reporter.line("  4. 接口隔离原则 - 客户端不应依赖不需要的接口");
+
+    reporter.section("🔗 模式组合使用");
+    reporter.line("  • 网关 + 映射器 - 外部系统集成的完整解决方案");
+    reporter.line("  • 注册表 + 分离接口 - 依赖注入和解耦");
+    reporter.line("  • 值对象 + 特殊情况 - 领域模型的完善表达");
+    reporter.line("  • 层超类型 + 插件 - 可扩展的分层架构");
+}
+
+/// 学习建议、相关资源和实践建议
+fn report_guidance(reporter: &mut dyn Reporter) {
+    reporter.section("🎓 学习建议");
+    reporter.line("  1. 先掌握基础模式:");
+    reporter.line("     - 值对象：理解值语义和不可变性");
+    reporter.line("     - 网关：学习外部系统集成");
+    reporter.line("     - 映射器：掌握对象转换技术");
+    reporter.line("  2. 进阶到架构模式:");
+    reporter.line("     - 注册表：服务定位和依赖注入");
+    reporter.line("     - 层超类型：分层架构设计");
+    reporter.line("     - 分离接口：模块化和解耦");
+    reporter.line("  3. 专业化应用:");
+    reporter.line("     - 特殊情况：高质量代码设计");
+    reporter.line("     - 插件：可扩展系统架构");
+    reporter.line("     - 服务存根：测试驱动开发");
+
+    reporter.section("📚 相关资源");
+    reporter.line("  • Martin Fowler《企业应用架构模式》");
+    reporter.line("  • Eric Evans《领域驱动设计》");
+    reporter.line("  • Clean Architecture principles");
+    reporter.line("  • SOLID design principles");
+
+    reporter.section("🚀 实践建议");
+    reporter.line("  • 从简单项目开始应用这些模式");
+    reporter.line("  • 重点关注模式解决的问题");
+    reporter.line("  • 避免过度设计和模式滥用");
+    reporter.line("  • 结合具体场景选择合适的模式");
+    reporter.line("  • 持续重构和改进代码质量");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 只检查总览和建议部分：完整演示会改动全局注册表和全局配置，与其他测试相互影响
+    #[test]
+    fn test_overview_reports_through_buffer() {
+        let mut reporter = BufferReporter::new();
+        report_overview(&mut reporter);
+        report_guidance(&mut reporter);
+
+        let sections = reporter.sections();
+        assert_eq!(sections.first(), Some(&"基础模式总览"));
+        assert!(sections.contains(&"📋 基础模式分类表"));
+        assert!(sections.contains(&"🎓 学习建议"));
+        assert_eq!(sections.last(), Some(&"🚀 实践建议"));
+
+        assert_eq!(reporter.lines_in("💡 设计原则").len(), 4);
+        assert!(reporter.lines_in("🔗 模式组合使用").iter().any(|line| line.contains("网关 + 映射器")));
+
+        let tables: Vec<&ReportEntry> = reporter.entries().iter()
+            .filter(|entry| matches!(entry, ReportEntry::Table { .. }))
+            .collect();
+        assert_eq!(tables.len(), 2);
+        match tables[0] {
+            ReportEntry::Table { headers, rows } => {
+                assert_eq!(headers[0], "模式类型");
+                assert_eq!(rows.len(), 5);
+                assert_eq!(rows[4][0], "金钱 (Money)");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -4,11 +4,19 @@
 //! 支持多币种，提供安全的货币运算，并确保不同币种之间不能直接运算。
 //! 
 //! 文件位置：/d%3A/workspace/RustLearn/RustDesignPattern/src/EnterpriseAppPattern/BasePatterns/money.rs
+//!
+//! `Money` 可以从 `"USD 12.50"` 形式的字符串解析；启用 `money-serde` 特性时
+//! 序列化为 `{"currency":"USD","amount_minor":1250}`，用整数最小单位避免浮点误差。
 
 use std::fmt;
+use std::str::FromStr;
 use std::ops::{Add, Sub, Mul, Div};
 use std::cmp::{PartialEq, Eq, PartialOrd, Ord, Ordering};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::DistributedSystemMode::ResiliencePatterns::time_source::{system_time_source, FakeClock, TimeSource};
 
 // =================
 // 货币类型
@@ -221,6 +229,24 @@ impl Money {
         }
     }
     
+    /// 检查币种的加法，与 `+` 运算符行为相同
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        Money::add(self, other)
+    }
+    
+    /// 检查币种的减法，与 `-` 运算符行为相同
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.subtract(other)
+    }
+    
+    /// 乘以整数数量，溢出时返回错误
+    pub fn checked_mul(&self, factor: i64) -> Result<Money, MoneyError> {
+        self.amount
+            .checked_mul(factor)
+            .map(|amount| Money::from_cents(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+    
     /// 乘法（乘以数量）
     pub fn multiply(&self, factor: f64) -> Result<Money, MoneyError> {
         let result = (self.amount as f64 * factor).round() as i64;
@@ -237,32 +263,50 @@ impl Money {
         Ok(Money::from_cents(result, self.currency))
     }
     
-    /// 分配金额（按比例分配，确保总和不变）
-    pub fn allocate(&self, ratios: &[f64]) -> Result<Vec<Money>, MoneyError> {
+    /// 分配金额（按整数比例分配，确保总和不变）
+    ///
+    /// 采用最大余数法：每份先取向下取整的份额，剩下的最小单位逐个分给
+    /// 余数最大的份额，余数相同时靠前的优先。负数金额按绝对值分配后取负。
+    pub fn allocate(&self, ratios: &[u32]) -> Result<Vec<Money>, MoneyError> {
         if ratios.is_empty() {
             return Err(MoneyError::InvalidRatio("比例数组不能为空".to_string()));
         }
         
-        let total_ratio: f64 = ratios.iter().sum();
-        if total_ratio == 0.0 {
+        let total_ratio: i128 = ratios.iter().map(|&ratio| ratio as i128).sum();
+        if total_ratio == 0 {
             return Err(MoneyError::InvalidRatio("比例总和不能为零".to_string()));
         }
         
-        let mut results = Vec::new();
-        let mut remaining = self.amount;
+        // 用 i128 计算，金额乘以比例不会溢出
+        let amount = (self.amount as i128).abs();
+        let mut shares: Vec<i128> = Vec::with_capacity(ratios.len());
+        let mut remainders: Vec<(i128, usize)> = Vec::with_capacity(ratios.len());
+        for (index, &ratio) in ratios.iter().enumerate() {
+            let scaled = amount * ratio as i128;
+            shares.push(scaled / total_ratio);
+            remainders.push((scaled % total_ratio, index));
+        }
         
-        for (i, &ratio) in ratios.iter().enumerate() {
-            if i == ratios.len() - 1 {
-                // 最后一个分配剩余金额，确保总和正确
-                results.push(Money::from_cents(remaining, self.currency));
-            } else {
-                let allocated = (self.amount as f64 * ratio / total_ratio).round() as i64;
-                results.push(Money::from_cents(allocated, self.currency));
-                remaining -= allocated;
-            }
+        let left_over = amount - shares.iter().sum::<i128>();
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for &(_, index) in remainders.iter().take(left_over as usize) {
+            shares[index] += 1;
         }
         
-        Ok(results)
+        // 先在 i128 中恢复符号再检查范围：i64::MIN 的绝对值超出 i64，但取负后的份额仍然可以表示
+        let sign = self.amount.signum() as i128;
+        shares
+            .into_iter()
+            .map(|share| {
+                let cents = share.checked_mul(sign).and_then(|cents| i64::try_from(cents).ok()).ok_or(MoneyError::Overflow)?;
+                Ok(Money::from_cents(cents, self.currency))
+            })
+            .collect()
+    }
+    
+    /// 求同币种金额之和，混用币种时返回错误
+    pub fn sum(currency: Currency, items: &[Money]) -> Result<Money, MoneyError> {
+        items.iter().try_fold(Money::zero(currency), |total, item| Money::add(&total, item))
     }
     
     /// 平均分配（尽可能均等，余数分配给前几个）
@@ -344,6 +388,8 @@ pub enum MoneyError {
     Overflow,
     DivisionByZero,
     InvalidRatio(String),
+    /// 字符串无法解析为金额
+    ParseError(String),
 }
 
 impl fmt::Display for MoneyError {
@@ -355,6 +401,7 @@ impl fmt::Display for MoneyError {
             MoneyError::Overflow => write!(f, "数值溢出"),
             MoneyError::DivisionByZero => write!(f, "除零错误"),
             MoneyError::InvalidRatio(msg) => write!(f, "无效的比例: {}", msg),
+            MoneyError::ParseError(msg) => write!(f, "金额解析失败: {}", msg),
         }
     }
 }
@@ -362,6 +409,10 @@ impl fmt::Display for MoneyError {
 // =================
 // 运算符重载
 // =================
+//
+// `+`、`-`、`*` 都返回 `Result<Money, MoneyError>`，不会 panic：
+// 不同币种相加减得到 `Err(MoneyError::CurrencyMismatch)`，溢出得到 `Err(MoneyError::Overflow)`。
+// 因为结果是 `Result`，连续运算需要写成 `(a + b)?` 后再参与下一次运算。
 
 impl PartialEq for Money {
     fn eq(&self, other: &Self) -> bool {
@@ -394,7 +445,7 @@ impl Add for Money {
     type Output = Result<Money, MoneyError>;
     
     fn add(self, other: Money) -> Self::Output {
-        self.add(other)
+        self.checked_add(&other)
     }
 }
 
@@ -402,7 +453,7 @@ impl Sub for Money {
     type Output = Result<Money, MoneyError>;
     
     fn sub(self, other: Money) -> Self::Output {
-        self.subtract(&other)
+        self.checked_sub(&other)
     }
 }
 
@@ -414,6 +465,14 @@ impl Mul<f64> for Money {
     }
 }
 
+impl Mul<i64> for Money {
+    type Output = Result<Money, MoneyError>;
+    
+    fn mul(self, factor: i64) -> Self::Output {
+        self.checked_mul(factor)
+    }
+}
+
 impl Div<f64> for Money {
     type Output = Result<Money, MoneyError>;
     
@@ -428,6 +487,77 @@ impl fmt::Display for Money {
     }
 }
 
+// =================
+// 解析与序列化
+// =================
+
+/// 解析 `"<币种代码> <金额>"`，如 `"USD 12.50"`、`"JPY -300"`
+///
+/// 金额按十进制字符串精确解析，不经过浮点数；小数位数不能超过币种允许的位数。
+impl FromStr for Money {
+    type Err = MoneyError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let (code, amount) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(code), Some(amount), None) => (code, amount),
+            _ => return Err(MoneyError::ParseError(format!("格式应为 \"<币种> <金额>\": {:?}", s))),
+        };
+        
+        let currency = Currency::from_code(code).ok_or_else(|| MoneyError::ParseError(format!("未知币种: {}", code)))?;
+        let (negative, digits) = match amount.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, amount.strip_prefix('+').unwrap_or(amount)),
+        };
+        let (integer_part, fraction_part) = match digits.split_once('.') {
+            Some((integer_part, fraction_part)) => (integer_part, fraction_part),
+            None => (digits, ""),
+        };
+        
+        let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(integer_part) || (digits.contains('.') && !is_digits(fraction_part)) {
+            return Err(MoneyError::ParseError(format!("无效的金额: {}", amount)));
+        }
+        let places = currency.decimal_places() as usize;
+        if fraction_part.len() > places {
+            return Err(MoneyError::ParseError(format!("{} 最多允许{}位小数: {}", currency, places, amount)));
+        }
+        
+        // 补齐小数位后整体作为最小单位的整数解析
+        let minor_digits = format!("{}{:0<width$}", integer_part, fraction_part, width = places);
+        let minor: i64 = minor_digits.parse().map_err(|_| MoneyError::Overflow)?;
+        Ok(Money::from_cents(if negative { -minor } else { minor }, currency))
+    }
+}
+
+#[cfg(feature = "money-serde")]
+mod money_serde {
+    use super::{Currency, Money};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    
+    /// 序列化时的结构：币种代码 + 最小单位整数
+    #[derive(Serialize, Deserialize)]
+    struct MoneyRepr {
+        currency: String,
+        amount_minor: i64,
+    }
+    
+    impl Serialize for Money {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            MoneyRepr { currency: self.currency.code().to_string(), amount_minor: self.amount }.serialize(serializer)
+        }
+    }
+    
+    impl<'de> Deserialize<'de> for Money {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = MoneyRepr::deserialize(deserializer)?;
+            let currency = Currency::from_code(&repr.currency)
+                .ok_or_else(|| de::Error::custom(format!("未知币种: {}", repr.currency)))?;
+            Ok(Money::from_cents(repr.amount_minor, currency))
+        }
+    }
+}
+
 // =================
 // 货币转换器
 // =================
@@ -456,15 +586,28 @@ impl ExchangeRate {
     }
 }
 
+/// 默认的汇率缓存有效期
+pub const DEFAULT_RATE_CACHE_TTL: Duration = Duration::from_secs(60);
+
 /// 货币转换器
+///
+/// `convert` 每次查询汇率表；`convert_cached` 先查带有效期的缓存，
+/// 缓存缺失或过期时回退到汇率表并按默认有效期重新缓存。缓存由 `RwLock` 保护，可以跨线程共享。
 pub struct CurrencyConverter {
     exchange_rates: HashMap<(Currency, Currency), ExchangeRate>,
+    /// (源币种, 目标币种) -> (汇率, 过期时刻)
+    rate_cache: RwLock<HashMap<(Currency, Currency), (f64, Instant)>>,
+    cache_ttl: Duration,
+    time: Arc<dyn TimeSource>,
 }
 
 impl CurrencyConverter {
     pub fn new() -> Self {
         let mut converter = Self {
             exchange_rates: HashMap::new(),
+            rate_cache: RwLock::new(HashMap::new()),
+            cache_ttl: DEFAULT_RATE_CACHE_TTL,
+            time: system_time_source(),
         };
         
         // 初始化一些示例汇率
@@ -509,6 +652,65 @@ impl CurrencyConverter {
     pub fn list_rates(&self) -> Vec<&ExchangeRate> {
         self.exchange_rates.values().collect()
     }
+    
+    /// 设置回源后重新缓存使用的有效期
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+    
+    /// 替换判断缓存过期所用的时间源，测试中可以注入 FakeClock
+    pub fn with_time_source(mut self, time: Arc<dyn TimeSource>) -> Self {
+        self.time = time;
+        self
+    }
+    
+    /// 直接写入缓存汇率，在 `ttl` 内优先于汇率表使用
+    pub fn set_rate_with_ttl(&self, from: Currency, to: Currency, rate: f64, ttl: Duration) {
+        self.rate_cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((from, to), (rate, self.time.now() + ttl));
+    }
+    
+    /// 取缓存中未过期的汇率
+    fn cached_rate(&self, from: Currency, to: Currency) -> Option<f64> {
+        let cache = self.rate_cache.read().unwrap_or_else(|e| e.into_inner());
+        cache
+            .get(&(from, to))
+            .filter(|(_, expires_at)| *expires_at > self.time.now())
+            .map(|(rate, _)| *rate)
+    }
+    
+    /// 使用缓存的汇率转换，缓存缺失或过期时回退到汇率表
+    pub fn convert_cached(&self, money: &Money, to_currency: Currency) -> Result<Money, MoneyError> {
+        if money.currency == to_currency {
+            return Ok(*money);
+        }
+        
+        let from = money.currency;
+        let rate = match self.cached_rate(from, to_currency) {
+            Some(rate) => rate,
+            None => {
+                let rate = self
+                    .get_rate(from, to_currency)
+                    .map(ExchangeRate::rate)
+                    .ok_or_else(|| MoneyError::InvalidRatio(format!("无法找到从 {} 到 {} 的汇率", from, to_currency)))?;
+                self.set_rate_with_ttl(from, to_currency, rate, self.cache_ttl);
+                rate
+            }
+        };
+        Ok(Money::new(money.amount() * rate, to_currency))
+    }
+    
+    /// 清理过期的缓存汇率，返回清理的条数
+    pub fn clear_expired(&self) -> usize {
+        let mut cache = self.rate_cache.write().unwrap_or_else(|e| e.into_inner());
+        let before = cache.len();
+        let now = self.time.now();
+        cache.retain(|_, (_, expires_at)| *expires_at > now);
+        before - cache.len()
+    }
 }
 
 // =================
@@ -660,7 +862,7 @@ pub fn demo_money_pattern() {
     let total = Money::new(100.0, Currency::USD);
     
     // 按比例分配
-    let ratios = [0.3, 0.5, 0.2];
+    let ratios = [3, 5, 2];
     if let Ok(allocated) = total.allocate(&ratios) {
         println!("$100 按比例 [30%, 50%, 20%] 分配:");
         for (i, amount) in allocated.iter().enumerate() {
@@ -687,7 +889,8 @@ pub fn demo_money_pattern() {
     
     println!("5. 货币转换:");
     
-    let converter = CurrencyConverter::new();
+    let clock = FakeClock::new();
+    let converter = CurrencyConverter::new().with_time_source(clock.as_time_source());
     
     let usd_amount = Money::new(100.0, Currency::USD);
     
@@ -699,6 +902,18 @@ pub fn demo_money_pattern() {
         println!("$100 转换为欧元: {}", eur_amount);
     }
     
+    // 临时汇率写入缓存，有效期内优先使用
+    converter.set_rate_with_ttl(Currency::USD, Currency::CNY, 7.1, Duration::from_secs(300));
+    if let Ok(cny_amount) = converter.convert_cached(&usd_amount, Currency::CNY) {
+        println!("$100 按缓存汇率转换为人民币: {}", cny_amount);
+    }
+    
+    // 缓存过期后回源到汇率表
+    clock.advance(Duration::from_secs(301));
+    if let Ok(cny_amount) = converter.convert_cached(&usd_amount, Currency::CNY) {
+        println!("缓存过期后按汇率表转换为人民币: {}", cny_amount);
+    }
+    
     println!();
     
     println!("6. 多币种钱袋:");
@@ -744,4 +959,222 @@ pub fn demo_money_pattern() {
     println!("✓ 多币种支持 - 完整的货币类型系统");
     println!("✓ 丰富操作 - 分配、转换、格式化等功能");
     println!("✓ 溢出保护 - 安全的数学运算");
-} 
\ No newline at end of file
+}
+
+/// 金钱的性质测试
+///
+/// 除示例测试外，用随机生成的金额验证运算不变量：
+/// 加法交换律、分配结果总和守恒、乘除往返误差不超过一个最小单位。
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// 生成任意币种
+    fn any_currency() -> impl Strategy<Value = Currency> {
+        prop_oneof![
+            Just(Currency::USD),
+            Just(Currency::EUR),
+            Just(Currency::CNY),
+            Just(Currency::JPY),
+            Just(Currency::GBP),
+            Just(Currency::KRW),
+            Just(Currency::HKD),
+            Just(Currency::SGD),
+        ]
+    }
+
+    /// 生成指定币种的金额（最小单位），范围足够小以避免加法溢出
+    fn money_of(currency: Currency) -> impl Strategy<Value = Money> {
+        (-1_000_000_000_i64..1_000_000_000_i64).prop_map(move |cents| Money::from_cents(cents, currency))
+    }
+
+    /// 生成同币种的一对金额
+    fn same_currency_pair() -> impl Strategy<Value = (Money, Money)> {
+        any_currency().prop_flat_map(|currency| (money_of(currency), money_of(currency)))
+    }
+
+    /// 生成任意币种的单个金额
+    fn any_money() -> impl Strategy<Value = Money> {
+        any_currency().prop_flat_map(money_of)
+    }
+
+    /// 生成分配比例，允许出现零比例但总和为正
+    fn ratios() -> impl Strategy<Value = Vec<u32>> {
+        prop::collection::vec(0_u32..100, 1..8).prop_filter("比例总和需要为正", |ratios| ratios.iter().any(|&ratio| ratio > 0))
+    }
+
+    proptest! {
+        #[test]
+        fn prop_add_is_commutative((a, b) in same_currency_pair()) {
+            prop_assert_eq!(Money::add(&a, &b).unwrap(), Money::add(&b, &a).unwrap());
+        }
+
+        #[test]
+        fn prop_allocate_preserves_total(money in any_money(), ratios in ratios()) {
+            let parts = money.allocate(&ratios).unwrap();
+
+            prop_assert_eq!(parts.len(), ratios.len());
+            let total: i64 = parts.iter().map(|part| part.amount_in_cents()).sum();
+            prop_assert_eq!(total, money.amount_in_cents());
+            prop_assert!(parts.iter().all(|part| part.currency() == money.currency()));
+        }
+
+        /// 乘法和除法各自四舍五入到最小单位：乘法误差至多 0.5，
+        /// 除以 factor 后缩小为 0.5 / factor，再加上除法自身的 0.5。
+        /// 因此只有 factor >= 1 时往返误差才能保证不超过一个最小单位，
+        /// factor < 1 时乘法丢失的精度会在除法中被放大。
+        #[test]
+        fn prop_multiply_then_divide_round_trips(money in any_money(), factor in 1.0_f64..100.0) {
+            let round_trip = money.multiply(factor).unwrap().divide(factor).unwrap();
+
+            prop_assert_eq!(round_trip.currency(), money.currency());
+            prop_assert!((round_trip.amount_in_cents() - money.amount_in_cents()).abs() <= 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_five_cents_evenly() {
+        let parts = Money::new(0.05, Currency::USD).allocate(&[1, 1]).unwrap();
+
+        assert_eq!(parts, vec![Money::new(0.03, Currency::USD), Money::new(0.02, Currency::USD)]);
+        assert_eq!(Money::sum(Currency::USD, &parts).unwrap(), Money::new(0.05, Currency::USD));
+    }
+
+    #[test]
+    fn test_allocate_uses_largest_remainder() {
+        // 100 × [1, 2, 4] / 7 = 14.28, 28.57, 57.14：余下的1分给余数最大的第二份
+        let parts = Money::from_cents(100, Currency::CNY).allocate(&[1, 2, 4]).unwrap();
+        let cents: Vec<i64> = parts.iter().map(Money::amount_in_cents).collect();
+        assert_eq!(cents, vec![14, 29, 57]);
+
+        // 零比例不分得金额，负数金额按绝对值分配后取负
+        let parts = Money::from_cents(-10, Currency::EUR).allocate(&[0, 1, 2]).unwrap();
+        let cents: Vec<i64> = parts.iter().map(Money::amount_in_cents).collect();
+        assert_eq!(cents, vec![0, -3, -7]);
+    }
+
+    #[test]
+    fn test_allocate_extreme_amounts_without_overflow() {
+        let cents = |money: Money, ratios: &[u32]| -> Vec<i64> {
+            money.allocate(ratios).unwrap().iter().map(Money::amount_in_cents).collect()
+        };
+
+        assert_eq!(cents(Money::from_cents(i64::MIN, Currency::USD), &[1]), vec![i64::MIN]);
+        assert_eq!(cents(Money::from_cents(i64::MIN, Currency::USD), &[1, 1]), vec![i64::MIN / 2, i64::MIN / 2]);
+        assert_eq!(cents(Money::from_cents(i64::MIN, Currency::USD), &[0, u32::MAX]), vec![0, i64::MIN]);
+        assert_eq!(cents(Money::from_cents(i64::MAX, Currency::USD), &[u32::MAX, 1]).iter().sum::<i64>(), i64::MAX);
+    }
+
+    #[test]
+    fn test_allocate_rejects_invalid_ratios_and_mixed_currencies() {
+        let money = Money::new(1.0, Currency::USD);
+        assert!(matches!(money.allocate(&[]), Err(MoneyError::InvalidRatio(_))));
+        assert!(matches!(money.allocate(&[0, 0]), Err(MoneyError::InvalidRatio(_))));
+
+        let mut parts = money.allocate(&[1, 1]).unwrap();
+        parts.push(Money::new(0.5, Currency::CNY));
+        assert!(matches!(
+            Money::sum(Currency::USD, &parts),
+            Err(MoneyError::CurrencyMismatch { left: Currency::USD, right: Currency::CNY })
+        ));
+    }
+
+    #[test]
+    fn test_operators_on_same_currency() {
+        let a = Money::new(10.25, Currency::USD);
+        let b = Money::new(0.75, Currency::USD);
+
+        assert_eq!((a + b).unwrap(), Money::new(11.0, Currency::USD));
+        assert_eq!((a - b).unwrap(), Money::new(9.5, Currency::USD));
+        assert_eq!((a * 3_i64).unwrap(), Money::new(30.75, Currency::USD));
+        assert_eq!((a * 0.5).unwrap(), Money::from_cents(513, Currency::USD));
+        assert_eq!(a.checked_add(&b).unwrap(), (a + b).unwrap());
+        assert_eq!(a.checked_sub(&b).unwrap(), a.subtract(&b).unwrap());
+    }
+
+    #[test]
+    fn test_mixed_currency_operators_return_error_instead_of_panicking() {
+        let usd = Money::new(1.0, Currency::USD);
+        let cny = Money::new(1.0, Currency::CNY);
+
+        assert!(matches!(usd + cny, Err(MoneyError::CurrencyMismatch { left: Currency::USD, right: Currency::CNY })));
+        assert!(matches!(cny - usd, Err(MoneyError::CurrencyMismatch { left: Currency::CNY, right: Currency::USD })));
+        assert!(matches!(usd.checked_add(&cny), Err(MoneyError::CurrencyMismatch { .. })));
+
+        let max = Money::from_cents(i64::MAX, Currency::USD);
+        assert!(matches!(max + usd, Err(MoneyError::Overflow)));
+        assert!(matches!(max * 2_i64, Err(MoneyError::Overflow)));
+    }
+
+    #[test]
+    fn test_convert_cached_uses_unexpired_cache_entry() {
+        let converter = CurrencyConverter::new();
+        converter.set_rate_with_ttl(Currency::USD, Currency::CNY, 7.0, Duration::from_secs(60));
+
+        let usd = Money::new(100.0, Currency::USD);
+        // 缓存命中：使用缓存汇率7.0，而不是汇率表中的7.2
+        assert_eq!(converter.convert_cached(&usd, Currency::CNY).unwrap(), Money::new(700.0, Currency::CNY));
+        assert_eq!(converter.convert(&usd, Currency::CNY).unwrap(), Money::new(720.0, Currency::CNY));
+        assert_eq!(converter.clear_expired(), 0);
+    }
+
+    #[test]
+    fn test_convert_cached_falls_back_after_expiry() {
+        let clock = FakeClock::new();
+        let converter = CurrencyConverter::new()
+            .with_cache_ttl(Duration::from_secs(60))
+            .with_time_source(clock.as_time_source());
+        converter.set_rate_with_ttl(Currency::USD, Currency::CNY, 7.0, Duration::from_secs(60));
+        clock.advance(Duration::from_secs(60));
+
+        // 过期后回源到汇率表，并以 with_cache_ttl 设置的 60 秒有效期重新缓存
+        let usd = Money::new(100.0, Currency::USD);
+        assert_eq!(converter.convert_cached(&usd, Currency::CNY).unwrap(), Money::new(720.0, Currency::CNY));
+        assert_eq!(converter.clear_expired(), 0);
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(converter.clear_expired(), 0);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(converter.clear_expired(), 1);
+        assert!(matches!(converter.convert_cached(&usd, Currency::JPY), Err(MoneyError::InvalidRatio(_))));
+    }
+
+    #[test]
+    fn test_parse_money_from_string() {
+        assert_eq!("USD 12.50".parse::<Money>().unwrap(), Money::from_cents(1250, Currency::USD));
+        assert_eq!("usd 12.5".parse::<Money>().unwrap(), Money::from_cents(1250, Currency::USD));
+        assert_eq!("CNY -0.05".parse::<Money>().unwrap(), Money::from_cents(-5, Currency::CNY));
+        assert_eq!("  EUR   +3  ".parse::<Money>().unwrap(), Money::from_cents(300, Currency::EUR));
+        assert_eq!("JPY -300".parse::<Money>().unwrap(), Money::from_cents(-300, Currency::JPY));
+    }
+
+    #[test]
+    fn test_parse_money_rejects_invalid_input() {
+        for input in ["", "USD", "12.50 USD", "USD 12.50 extra", "USD abc", "USD 1.2.3", "USD .5", "USD 5.", "USD --1", "JPY 1.5", "USD 1.005"] {
+            assert!(matches!(input.parse::<Money>(), Err(MoneyError::ParseError(_))), "{:?}", input);
+        }
+        match "XYZ 1.00".parse::<Money>() {
+            Err(MoneyError::ParseError(msg)) => assert!(msg.contains("未知币种")),
+            other => panic!("未知币种应解析失败: {:?}", other),
+        }
+        assert!(matches!("USD 99999999999999999999".parse::<Money>(), Err(MoneyError::Overflow)));
+    }
+
+    #[cfg(feature = "money-serde")]
+    #[test]
+    fn test_money_serde_uses_minor_units() {
+        let money = Money::from_cents(-1250, Currency::USD);
+        let json = serde_json::to_string(&money).unwrap();
+        assert_eq!(json, r#"{"currency":"USD","amount_minor":-1250}"#);
+        assert_eq!(serde_json::from_str::<Money>(&json).unwrap(), money);
+
+        assert!(serde_json::from_str::<Money>(r#"{"currency":"XYZ","amount_minor":1}"#).is_err());
+        assert!(serde_json::from_str::<Money>(r#"{"currency":"USD","amount_minor":12.5}"#).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! # 分页结果（Page）
+//!
+//! 仓储、查询对象和页面控制器都需要"第几页、每页几条、一共多少条"的分页结果。
+//! `Page<T>` 是这些查询层共用的分页类型，页码从1开始，
+//! 总页数按 `total / size` 向上取整。
+//!
+//! ## 用法
+//! - `Page::new(items, total, page, size)`: 数据源已经完成分页时，直接包装当前页的数据
+//! - `Page::slice(all, page, size)`: 从内存中的完整结果切出一页
+//! - `Page::after_cursor(items, total, size, next_cursor)`: 游标分页的一页，没有页码，
+//!   是否有下一页由 `next_cursor` 决定
+//! - `map(f)`: 转换当前页的元素，分页信息保持不变（例如把实体转换为视图模型）
+
+/// 一页查询结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// 当前页的元素
+    pub items: Vec<T>,
+    /// 所有页的元素总数
+    pub total: usize,
+    /// 当前页码，从1开始
+    pub page: usize,
+    /// 每页大小
+    pub size: usize,
+    /// 总页数，没有元素时为0
+    pub total_pages: usize,
+    /// 游标分页时下一页的不透明游标，没有更多数据或按页码分页时为 None
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: usize, page: usize, size: usize) -> Self {
+        let total_pages = if size == 0 { 0 } else { total.div_ceil(size) };
+        Self { items, total, page, size, total_pages, next_cursor: None }
+    }
+
+    /// 游标分页的一页：游标分页没有页码，`page` 为0
+    pub fn after_cursor(items: Vec<T>, total: usize, size: usize, next_cursor: Option<String>) -> Self {
+        Self { next_cursor, ..Self::new(items, total, 0, size) }
+    }
+
+    /// 是否为游标分页的结果
+    pub fn is_cursor_page(&self) -> bool {
+        self.page == 0
+    }
+
+    /// 从完整结果中取出第 `page` 页，页码超出范围时返回空页
+    pub fn slice(all: Vec<T>, page: usize, size: usize) -> Self {
+        let total = all.len();
+        let start = page.saturating_sub(1).saturating_mul(size);
+        let items = all.into_iter().skip(start).take(size).collect();
+        Self::new(items, total, page, size)
+    }
+
+    pub fn has_next(&self) -> bool {
+        if self.is_cursor_page() {
+            self.next_cursor.is_some()
+        } else {
+            self.page < self.total_pages
+        }
+    }
+
+    pub fn has_prev(&self) -> bool {
+        self.page > 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 转换当前页的元素，保留分页信息
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            size: self.size,
+            total_pages: self.total_pages,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_pages_rounds_up() {
+        let pages: Vec<Page<u32>> = (1..=3).map(|page| Page::slice((1..=11).collect(), page, 5)).collect();
+
+        assert!(pages.iter().all(|p| p.total == 11 && p.total_pages == 3));
+        assert_eq!(pages[0].items, vec![1, 2, 3, 4, 5]);
+        assert_eq!(pages[2].items, vec![11]);
+        assert!(!pages[0].has_prev() && pages[0].has_next());
+        assert!(pages[1].has_prev() && pages[1].has_next());
+        assert!(pages[2].has_prev() && !pages[2].has_next());
+
+        assert_eq!(Page::<u32>::new(vec![], 10, 1, 5).total_pages, 2);
+        assert_eq!(Page::<u32>::new(vec![], 0, 1, 5).total_pages, 0);
+        assert_eq!(Page::<u32>::new(vec![], 3, 1, 0).total_pages, 0);
+
+        let beyond = Page::slice((1..=11).collect::<Vec<u32>>(), 4, 5);
+        assert!(beyond.is_empty());
+        assert!(!beyond.has_next());
+    }
+
+    #[test]
+    fn test_map_transforms_items_and_keeps_paging() {
+        let page = Page::slice(vec!["alice", "bob", "carol"], 2, 2);
+        let mapped = page.map(|name| name.len());
+
+        assert_eq!(mapped, Page { items: vec![5], total: 3, page: 2, size: 2, total_pages: 2, next_cursor: None });
+    }
+
+    #[test]
+    fn test_cursor_page_has_next_follows_cursor() {
+        let middle = Page::after_cursor(vec![4, 5, 6], 7, 3, Some("after:6".to_string()));
+        assert!(middle.is_cursor_page() && middle.has_next() && !middle.has_prev());
+        assert_eq!(middle.map(|n| n * 10).next_cursor.as_deref(), Some("after:6"));
+
+        let last = Page::after_cursor(vec![7], 7, 3, None);
+        assert!(!last.has_next());
+        assert!(!Page::slice(vec![1, 2, 3], 1, 2).is_cursor_page());
+    }
+}
@@ -15,11 +15,27 @@
 //! - 可配置的业务流程
 //! - 多租户系统中的定制功能
 //! - 插件架构的应用程序
+//!
+//! ## 沙箱执行
+//! `execute_plugin` 在工作线程中运行插件并设置超时，插件panic会被捕获并转换为
+//! `PluginExecutionError`；插件连续失败达到上限后被自动禁用，避免拖垮宿主。
+//!
+//! ## 配置模式
+//! 插件通过 `config_schema` 声明需要的配置参数及类型，注册时校验配置，
+//! 缺失或类型错误的参数一次性全部报告，避免运行时才因配置错误失败。
+//!
+//! ## 生命周期钩子
+//! `PluginObserver` 接收注册、初始化、执行前后和卸载事件，用于监控和审计。
+//! 钩子不能中止核心生命周期，只有 `on_execute_before` 可以返回 false 否决一次执行。
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::error::Error;
 use std::any::{Any, TypeId};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
 
 /// 插件系统错误类型
 #[derive(Debug)]
@@ -79,6 +95,83 @@ impl PluginConfig {
     pub fn get_parameter(&self, key: &str) -> Option<&String> {
         self.parameters.get(key)
     }
+
+    /// 按插件声明的参数校验配置
+    pub fn validate(&self, schema: &[ConfigParameter]) -> Result<(), PluginConfigViolations> {
+        let mut violations = PluginConfigViolations::default();
+        for parameter in schema {
+            match self.parameters.get(&parameter.name) {
+                Some(value) if !parameter.param_type.accepts(value) => {
+                    violations.mistyped.push((parameter.name.clone(), parameter.param_type));
+                }
+                Some(_) => {}
+                None if parameter.required => violations.missing.push(parameter.name.clone()),
+                None => {}
+            }
+        }
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+}
+
+/// 配置参数类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+}
+
+impl ParameterType {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            ParameterType::String => true,
+            ParameterType::Integer => value.parse::<i64>().is_ok(),
+            ParameterType::Float => value.parse::<f64>().is_ok(),
+            ParameterType::Boolean => value.parse::<bool>().is_ok(),
+        }
+    }
+}
+
+/// 插件声明的配置参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigParameter {
+    pub name: String,
+    pub param_type: ParameterType,
+    pub required: bool,
+}
+
+impl ConfigParameter {
+    pub fn required(name: &str, param_type: ParameterType) -> Self {
+        Self { name: name.to_string(), param_type, required: true }
+    }
+
+    pub fn optional(name: &str, param_type: ParameterType) -> Self {
+        Self { name: name.to_string(), param_type, required: false }
+    }
+}
+
+/// 配置校验发现的问题
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PluginConfigViolations {
+    /// 缺少的必需参数
+    pub missing: Vec<String>,
+    /// 类型不匹配的参数及期望类型
+    pub mistyped: Vec<(String, ParameterType)>,
+}
+
+impl PluginConfigViolations {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.mistyped.is_empty()
+    }
+}
+
+impl Display for PluginConfigViolations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut problems: Vec<String> = self.missing.iter().map(|name| format!("缺少参数 {}", name)).collect();
+        problems.extend(self.mistyped.iter().map(|(name, expected)| format!("参数 {} 类型错误，应为 {:?}", name, expected)));
+        write!(f, "{}", problems.join(", "))
+    }
 }
 
 /// 插件上下文
@@ -156,6 +249,11 @@ pub trait Plugin: Send + Sync {
     fn cleanup(&mut self) -> Result<(), PluginError>;
     fn get_supported_operations(&self) -> Vec<String>;
     fn is_compatible_with(&self, version: &str) -> bool;
+
+    /// 插件需要的配置参数，默认不需要任何参数
+    fn config_schema(&self) -> Vec<ConfigParameter> {
+        Vec::new()
+    }
 }
 
 /// 数据处理插件接口
@@ -243,6 +341,10 @@ impl Plugin for JsonProcessorPlugin {
     fn is_compatible_with(&self, version: &str) -> bool {
         version >= "1.0" && version < "2.0"
     }
+
+    fn config_schema(&self) -> Vec<ConfigParameter> {
+        vec![ConfigParameter::optional("encoding", ParameterType::String)]
+    }
 }
 
 impl DataProcessorPlugin for JsonProcessorPlugin {
@@ -328,6 +430,10 @@ impl Plugin for XmlProcessorPlugin {
     fn is_compatible_with(&self, version: &str) -> bool {
         version >= "1.0" && version < "2.0"
     }
+
+    fn config_schema(&self) -> Vec<ConfigParameter> {
+        vec![ConfigParameter::optional("schema_validation", ParameterType::Boolean)]
+    }
 }
 
 impl DataProcessorPlugin for XmlProcessorPlugin {
@@ -346,6 +452,119 @@ impl DataProcessorPlugin for XmlProcessorPlugin {
     }
 }
 
+/// 流水线处理插件 - 组合多个数据处理插件
+///
+/// 阶段顺序来自配置参数 `stages`（逗号分隔）：处理器名表示执行其 `process_data`，
+/// `validate:处理器名` 表示用该处理器的 `validate_data` 校验当前数据。
+/// 任一阶段失败时立即停止，返回的错误标明失败的阶段。
+pub struct PipelineProcessorPlugin {
+    name: String,
+    processors: Vec<Box<dyn DataProcessorPlugin>>,
+}
+
+impl PipelineProcessorPlugin {
+    pub fn new(name: String, processors: Vec<Box<dyn DataProcessorPlugin>>) -> Self {
+        Self { name, processors }
+    }
+
+    fn find_processor(&self, name: &str) -> Result<&dyn DataProcessorPlugin, PluginError> {
+        self.processors.iter()
+            .find(|processor| processor.get_name() == name)
+            .map(|processor| processor.as_ref())
+            .ok_or_else(|| PluginError::PluginConfigError(format!("流水线中没有处理器: {}", name)))
+    }
+
+    /// 按配置的顺序执行所有阶段，返回每个阶段之后的数据
+    pub fn run_stages(&self, data: &str, context: &PluginContext) -> Result<Vec<(String, String)>, PluginError> {
+        let stages = context.config.get_parameter("stages")
+            .ok_or_else(|| PluginError::PluginConfigError("流水线缺少 stages 配置".to_string()))?;
+
+        let mut current = data.to_string();
+        let mut trace = Vec::new();
+        for stage in stages.split(',').map(str::trim).filter(|stage| !stage.is_empty()) {
+            let stage_error = |error: PluginError| {
+                PluginError::PluginExecutionError(format!("流水线阶段 {} 失败: {}", stage, error))
+            };
+            match stage.strip_prefix("validate:") {
+                Some(name) => {
+                    let valid = self.find_processor(name)?.validate_data(&current).map_err(stage_error)?;
+                    if !valid {
+                        return Err(stage_error(PluginError::InvalidInterface(format!("数据不符合 {} 的格式", name))));
+                    }
+                }
+                None => {
+                    current = self.find_processor(stage)?.process_data(&current, context).map_err(stage_error)?;
+                }
+            }
+            trace.push((stage.to_string(), current.clone()));
+        }
+        Ok(trace)
+    }
+}
+
+impl Plugin for PipelineProcessorPlugin {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn get_description(&self) -> &str {
+        "按配置顺序串联多个数据处理插件"
+    }
+
+    fn initialize(&mut self, context: &mut PluginContext) -> Result<(), PluginError> {
+        for processor in self.processors.iter_mut() {
+            processor.initialize(context)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&self, context: &PluginContext, input: &str) -> Result<PluginResult, PluginError> {
+        let output = self.process_data(input, context)?;
+        Ok(PluginResult::success("流水线处理完成".to_string()).with_data("output".to_string(), output))
+    }
+
+    fn cleanup(&mut self) -> Result<(), PluginError> {
+        for processor in self.processors.iter_mut() {
+            processor.cleanup()?;
+        }
+        Ok(())
+    }
+
+    fn get_supported_operations(&self) -> Vec<String> {
+        vec!["pipeline".to_string()]
+    }
+
+    fn is_compatible_with(&self, version: &str) -> bool {
+        self.processors.iter().all(|processor| processor.is_compatible_with(version))
+    }
+
+    fn config_schema(&self) -> Vec<ConfigParameter> {
+        vec![ConfigParameter::required("stages", ParameterType::String)]
+    }
+}
+
+impl DataProcessorPlugin for PipelineProcessorPlugin {
+    fn process_data(&self, data: &str, context: &PluginContext) -> Result<String, PluginError> {
+        let trace = self.run_stages(data, context)?;
+        Ok(trace.last().map(|(_, output)| output.clone()).unwrap_or_else(|| data.to_string()))
+    }
+
+    fn get_supported_formats(&self) -> Vec<String> {
+        self.processors.last().map(|processor| processor.get_supported_formats()).unwrap_or_default()
+    }
+
+    fn validate_data(&self, data: &str) -> Result<bool, PluginError> {
+        match self.processors.first() {
+            Some(processor) => processor.validate_data(data),
+            None => Ok(true),
+        }
+    }
+}
+
 /// 简单认证插件
 pub struct SimpleAuthPlugin {
     name: String,
@@ -430,6 +649,10 @@ impl Plugin for SimpleAuthPlugin {
     fn is_compatible_with(&self, version: &str) -> bool {
         version >= "1.0" && version < "2.0"
     }
+
+    fn config_schema(&self) -> Vec<ConfigParameter> {
+        vec![ConfigParameter::required("session_timeout", ParameterType::Integer)]
+    }
 }
 
 impl AuthenticationPlugin for SimpleAuthPlugin {
@@ -468,12 +691,54 @@ impl AuthenticationPlugin for SimpleAuthPlugin {
     }
 }
 
+/// 插件生命周期观察者，所有钩子默认什么也不做
+///
+/// 管理器在 `catch_unwind` 中调用每个钩子，观察者 panic 不会中断插件管理；
+/// `on_execute_before` 中的 panic 按否决处理。
+pub trait PluginObserver: Send + Sync {
+    fn on_register(&self, _plugin_name: &str) {}
+    fn on_initialize(&self, _plugin_name: &str) {}
+    /// 返回 false 否决本次执行
+    fn on_execute_before(&self, _plugin_name: &str, _input: &str) -> bool {
+        true
+    }
+    fn on_execute_after(&self, _plugin_name: &str, _succeeded: bool) {}
+    /// 插件连续失败 `failures` 次后被自动禁用
+    fn on_auto_disable(&self, _plugin_name: &str, _failures: u32) {}
+    fn on_unload(&self, _plugin_name: &str) {}
+}
+
+/// 插件沙箱策略
+#[derive(Debug, Clone)]
+pub struct SandboxPolicy {
+    /// 单次执行的超时时间
+    pub timeout: Duration,
+    /// 连续失败多少次后自动禁用插件
+    pub max_consecutive_failures: u32,
+}
+
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            max_consecutive_failures: 3,
+        }
+    }
+}
+
+type SharedPlugin = Arc<RwLock<Box<dyn Plugin>>>;
+
 /// 插件管理器
 pub struct PluginManager {
-    plugins: HashMap<String, Box<dyn Plugin>>,
+    // 通用插件在工作线程中执行，因此用Arc共享
+    plugins: HashMap<String, SharedPlugin>,
     data_processors: HashMap<String, Box<dyn DataProcessorPlugin>>,
     auth_providers: HashMap<String, Box<dyn AuthenticationPlugin>>,
     configurations: HashMap<String, PluginConfig>,
+    sandbox_policy: SandboxPolicy,
+    failure_counts: Mutex<HashMap<String, u32>>,
+    auto_disabled: Mutex<HashSet<String>>,
+    observers: Vec<Arc<dyn PluginObserver>>,
 }
 
 impl PluginManager {
@@ -483,9 +748,56 @@ impl PluginManager {
             data_processors: HashMap::new(),
             auth_providers: HashMap::new(),
             configurations: HashMap::new(),
+            sandbox_policy: SandboxPolicy::default(),
+            failure_counts: Mutex::new(HashMap::new()),
+            auto_disabled: Mutex::new(HashSet::new()),
+            observers: Vec::new(),
         }
     }
 
+    /// 添加生命周期观察者，按添加顺序通知
+    pub fn add_observer(&mut self, observer: Arc<dyn PluginObserver>) {
+        self.observers.push(observer);
+    }
+
+    fn notify(&self, hook: impl Fn(&dyn PluginObserver)) {
+        for observer in &self.observers {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(observer.as_ref())));
+        }
+    }
+
+    /// 执行前询问所有观察者，任何一个否决都不执行
+    ///
+    /// 每个观察者都会收到询问，不会因为前面的观察者否决而被跳过；panic 的观察者计为否决。
+    fn check_execution_allowed(&self, plugin_name: &str, input: &str) -> Result<(), PluginError> {
+        let vetoes = self.observers.iter()
+            .map(|observer| {
+                panic::catch_unwind(AssertUnwindSafe(|| observer.on_execute_before(plugin_name, input)))
+                    .unwrap_or(false)
+            })
+            .filter(|allowed| !allowed)
+            .count();
+
+        if vetoes == 0 {
+            Ok(())
+        } else {
+            Err(PluginError::PluginExecutionError(format!("插件 {} 的执行被 {} 个观察者否决", plugin_name, vetoes)))
+        }
+    }
+
+    /// 注册时校验配置，错误信息列出所有缺失和类型错误的参数
+    fn validate_config(name: &str, schema: &[ConfigParameter], config: &PluginConfig) -> Result<(), PluginError> {
+        config.validate(schema).map_err(|violations| {
+            PluginError::PluginConfigError(format!("插件 {} 配置无效: {}", name, violations))
+        })
+    }
+
+    /// 设置插件沙箱策略
+    pub fn with_sandbox_policy(mut self, policy: SandboxPolicy) -> Self {
+        self.sandbox_policy = policy;
+        self
+    }
+
     /// 注册插件
     pub fn register_plugin(&mut self, plugin: Box<dyn Plugin>, config: PluginConfig) -> Result<(), PluginError> {
         let name = plugin.get_name().to_string();
@@ -495,10 +807,13 @@ impl PluginManager {
             return Ok(());
         }
 
+        Self::validate_config(&name, &plugin.config_schema(), &config)?;
+
         println!("📦 注册插件: {} v{}", name, plugin.get_version());
         
         self.configurations.insert(name.clone(), config);
-        self.plugins.insert(name, plugin);
+        self.notify(|observer| observer.on_register(&name));
+        self.plugins.insert(name, Arc::new(RwLock::new(plugin)));
         
         Ok(())
     }
@@ -512,9 +827,12 @@ impl PluginManager {
             return Ok(());
         }
 
+        Self::validate_config(&name, &plugin.config_schema(), &config)?;
+
         println!("📦 注册数据处理插件: {} v{}", name, plugin.get_version());
         
         self.configurations.insert(name.clone(), config);
+        self.notify(|observer| observer.on_register(&name));
         self.data_processors.insert(name, plugin);
         
         Ok(())
@@ -529,9 +847,12 @@ impl PluginManager {
             return Ok(());
         }
 
+        Self::validate_config(&name, &plugin.config_schema(), &config)?;
+
         println!("📦 注册认证插件: {} v{}", name, plugin.get_version());
         
         self.configurations.insert(name.clone(), config);
+        self.notify(|observer| observer.on_register(&name));
         self.auth_providers.insert(name, plugin);
         
         Ok(())
@@ -549,8 +870,9 @@ impl PluginManager {
             if let Some(config) = self.configurations.get(&name).cloned() {
                 let mut context = PluginContext::new(name.clone(), config);
                 
-                if let Some(plugin) = self.plugins.get_mut(&name) {
-                    plugin.initialize(&mut context)?;
+                if let Some(plugin) = self.plugins.get(&name) {
+                    plugin.write().unwrap_or_else(|e| e.into_inner()).initialize(&mut context)?;
+                    self.notify(|observer| observer.on_initialize(&name));
                 }
             }
         }
@@ -559,7 +881,7 @@ impl PluginManager {
         Ok(())
     }
 
-    /// 执行插件
+    /// 在沙箱中执行插件：工作线程运行、超时返回、捕获panic
     pub fn execute_plugin(&self, plugin_name: &str, input: &str) -> Result<PluginResult, PluginError> {
         let plugin = self.plugins.get(plugin_name)
             .ok_or_else(|| PluginError::PluginNotFound(plugin_name.to_string()))?;
@@ -567,8 +889,77 @@ impl PluginManager {
         let config = self.configurations.get(plugin_name)
             .ok_or_else(|| PluginError::PluginConfigError(format!("配置未找到: {}", plugin_name)))?;
         
+        if self.is_auto_disabled(plugin_name) {
+            return Err(PluginError::PluginExecutionError(
+                format!("插件 {} 连续失败次数过多，已被自动禁用", plugin_name)
+            ));
+        }
+        
+        self.check_execution_allowed(plugin_name, input)?;
         let context = PluginContext::new(plugin_name.to_string(), config.clone());
-        plugin.execute(&context, input)
+        let result = Self::run_sandboxed(Arc::clone(plugin), context, input.to_string(), self.sandbox_policy.timeout);
+        let disabled_after = self.record_outcome(plugin_name, result.is_ok());
+        self.notify(|observer| observer.on_execute_after(plugin_name, result.is_ok()));
+        if let Some(failures) = disabled_after {
+            self.notify(|observer| observer.on_auto_disable(plugin_name, failures));
+        }
+        result
+    }
+
+    /// 工作线程中执行插件；超时后不再等待，线程结束时结果被丢弃
+    fn run_sandboxed(plugin: SharedPlugin, context: PluginContext, input: String, timeout: Duration) -> Result<PluginResult, PluginError> {
+        let (sender, receiver) = mpsc::channel();
+        let plugin_name = context.plugin_name.clone();
+        thread::spawn(move || {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                plugin.read().unwrap_or_else(|e| e.into_inner()).execute(&context, &input)
+            }));
+            let _ = sender.send(outcome);
+        });
+        
+        match receiver.recv_timeout(timeout) {
+            Ok(Ok(result)) => result,
+            Ok(Err(payload)) => {
+                let reason = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "未知原因".to_string());
+                Err(PluginError::PluginExecutionError(format!("插件 {} 发生panic: {}", plugin_name, reason)))
+            }
+            Err(_) => Err(PluginError::PluginExecutionError(
+                format!("插件 {} 执行超时 ({}ms)", plugin_name, timeout.as_millis())
+            )),
+        }
+    }
+
+    /// 记录执行结果，本次失败导致插件被自动禁用时返回连续失败次数
+    fn record_outcome(&self, plugin_name: &str, succeeded: bool) -> Option<u32> {
+        let mut failure_counts = self.failure_counts.lock().unwrap();
+        if succeeded {
+            failure_counts.remove(plugin_name);
+            return None;
+        }
+        let failures = failure_counts.entry(plugin_name.to_string()).or_insert(0);
+        *failures += 1;
+        if *failures < self.sandbox_policy.max_consecutive_failures {
+            return None;
+        }
+        self.auto_disabled.lock().unwrap().insert(plugin_name.to_string());
+        Some(*failures)
+    }
+
+    /// 插件当前的连续失败次数
+    pub fn failure_count(&self, plugin_name: &str) -> u32 {
+        self.failure_counts.lock().unwrap().get(plugin_name).copied().unwrap_or(0)
+    }
+
+    pub fn is_auto_disabled(&self, plugin_name: &str) -> bool {
+        self.auto_disabled.lock().unwrap().contains(plugin_name)
+    }
+
+    /// 重新启用被自动禁用的插件并清零失败计数
+    pub fn reenable_plugin(&self, plugin_name: &str) {
+        self.auto_disabled.lock().unwrap().remove(plugin_name);
+        self.failure_counts.lock().unwrap().remove(plugin_name);
     }
 
     /// 处理数据
@@ -579,8 +970,11 @@ impl PluginManager {
         let config = self.configurations.get(processor_name)
             .ok_or_else(|| PluginError::PluginConfigError(format!("配置未找到: {}", processor_name)))?;
         
+        self.check_execution_allowed(processor_name, data)?;
         let context = PluginContext::new(processor_name.to_string(), config.clone());
-        processor.process_data(data, &context)
+        let result = processor.process_data(data, &context);
+        self.notify(|observer| observer.on_execute_after(processor_name, result.is_ok()));
+        result
     }
 
     /// 认证用户
@@ -601,11 +995,12 @@ impl PluginManager {
         
         for (name, plugin) in &self.plugins {
             if let Some(config) = self.configurations.get(name) {
+                let plugin = plugin.read().unwrap_or_else(|e| e.into_inner());
                 infos.push(PluginInfo {
                     name: name.clone(),
                     version: plugin.get_version().to_string(),
                     description: plugin.get_description().to_string(),
-                    enabled: config.enabled,
+                    enabled: config.enabled && !self.is_auto_disabled(name),
                     priority: config.priority,
                     plugin_type: "通用插件".to_string(),
                 });
@@ -647,8 +1042,13 @@ impl PluginManager {
     pub fn cleanup_all(&mut self) -> Result<(), PluginError> {
         println!("🧹 清理所有插件...");
         
-        for (_, plugin) in self.plugins.iter_mut() {
+        for (name, plugin) in &self.plugins {
+            // 超时的执行可能仍然持有插件，此时不能清理
+            let mut plugin = plugin.try_write().map_err(|_| PluginError::PluginExecutionError(
+                format!("插件 {} 仍在执行，无法清理", name)
+            ))?;
             plugin.cleanup()?;
+            self.notify(|observer| observer.on_unload(name));
         }
         
         println!("✅ 所有插件清理完成");
@@ -675,12 +1075,30 @@ impl Display for PluginInfo {
     }
 }
 
+/// 审计观察者 - 打印插件生命周期事件
+struct AuditObserver;
+
+impl PluginObserver for AuditObserver {
+    fn on_register(&self, plugin_name: &str) {
+        println!("   📝 [审计] 注册 {}", plugin_name);
+    }
+
+    fn on_execute_after(&self, plugin_name: &str, succeeded: bool) {
+        println!("   📝 [审计] 执行 {} {}", plugin_name, if succeeded { "成功" } else { "失败" });
+    }
+
+    fn on_auto_disable(&self, plugin_name: &str, failures: u32) {
+        println!("   📝 [审计] ⚠️  插件 {} 连续失败 {} 次，自动禁用", plugin_name, failures);
+    }
+}
+
 /// 演示插件模式
 pub fn demo() {
     println!("=== 插件模式演示 ===\n");
 
     // 创建插件管理器
     let mut manager = PluginManager::new();
+    manager.add_observer(Arc::new(AuditObserver));
 
     println!("1. 注册各种插件");
     
@@ -712,6 +1130,13 @@ pub fn demo() {
     
     manager.register_data_processor(Box::new(JsonProcessorPlugin::new()), disabled_config).unwrap();
 
+    // 参数类型不匹配的配置在注册时被拒绝
+    let bad_auth_config = PluginConfig::new("简单认证".to_string(), "1.0.0".to_string())
+        .with_parameter("session_timeout".to_string(), "一小时".to_string());
+    if let Err(e) = PluginManager::new().register_auth_provider(Box::new(SimpleAuthPlugin::new()), bad_auth_config) {
+        println!("   ✅ 配置校验: {}", e);
+    }
+
     println!("\n2. 初始化所有插件");
     manager.initialize_all().unwrap();
 
@@ -739,6 +1164,20 @@ pub fn demo() {
         Err(e) => println!("     ❌ 处理失败: {}", e),
     }
 
+    // 流水线插件：JSON处理 -> JSON校验 -> XML转换
+    println!("\n   📄 流水线处理:");
+    let pipeline = PipelineProcessorPlugin::new(
+        "数据流水线".to_string(),
+        vec![Box::new(JsonProcessorPlugin::new()), Box::new(XmlProcessorPlugin::new())],
+    );
+    let pipeline_config = PluginConfig::new("数据流水线".to_string(), "1.0.0".to_string())
+        .with_parameter("stages".to_string(), "JSON处理器, validate:JSON处理器, XML处理器".to_string());
+    manager.register_data_processor(Box::new(pipeline), pipeline_config).unwrap();
+    match manager.process_data("数据流水线", "订单数据") {
+        Ok(result) => println!("     ✅ 处理结果:\n{}", result),
+        Err(e) => println!("     ❌ 处理失败: {}", e),
+    }
+
     println!("\n5. 演示认证插件");
     
     let test_credentials = vec![
@@ -796,6 +1235,7 @@ pub fn demo() {
     println!("2. 松耦合 - 插件与核心系统解耦");
     println!("3. 可扩展性 - 支持第三方开发插件");
     println!("4. 热插拔 - 支持动态加载和卸载");
+    println!("5. 沙箱执行 - 超时和panic不会拖垮宿主，反复失败的插件自动禁用");
 
     println!("\n⚠️ 设计考虑:");
     println!("1. 接口设计 - 需要设计稳定的插件接口");
@@ -897,4 +1337,362 @@ mod tests {
         let cleanup_result = manager.cleanup_all();
         assert!(cleanup_result.is_ok());
     }
-} 
\ No newline at end of file
+
+    /// 行为可控的测试插件
+    struct MisbehavingPlugin {
+        name: String,
+        behavior: &'static str,
+    }
+
+    impl Plugin for MisbehavingPlugin {
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        fn get_version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn get_description(&self) -> &str {
+            "测试沙箱的插件"
+        }
+
+        fn initialize(&mut self, _context: &mut PluginContext) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn execute(&self, _context: &PluginContext, input: &str) -> Result<PluginResult, PluginError> {
+            match self.behavior {
+                "slow" => {
+                    thread::sleep(Duration::from_secs(2));
+                    Ok(PluginResult::success("终于完成".to_string()))
+                }
+                "panic" => panic!("插件内部错误: {}", input),
+                _ => Ok(PluginResult::success(format!("处理 {}", input))),
+            }
+        }
+
+        fn cleanup(&mut self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn get_supported_operations(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn is_compatible_with(&self, _version: &str) -> bool {
+            true
+        }
+    }
+
+    /// 超时路径用较短的 `timeout`；其他用例给足时间，避免负载高时把 panic 误判为超时
+    fn sandboxed_manager(timeout: Duration, behaviors: &[(&str, &'static str)]) -> PluginManager {
+        let mut manager = PluginManager::new().with_sandbox_policy(SandboxPolicy {
+            timeout,
+            max_consecutive_failures: 2,
+        });
+        for (name, behavior) in behaviors {
+            let plugin = MisbehavingPlugin { name: name.to_string(), behavior };
+            manager.register_plugin(Box::new(plugin), PluginConfig::new(name.to_string(), "1.0.0".to_string())).unwrap();
+        }
+        manager
+    }
+
+    #[test]
+    fn test_slow_plugin_times_out() {
+        let manager = sandboxed_manager(Duration::from_millis(50), &[("慢插件", "slow"), ("正常插件", "ok")]);
+
+        let started = std::time::Instant::now();
+        match manager.execute_plugin("慢插件", "数据") {
+            Err(PluginError::PluginExecutionError(msg)) => assert!(msg.contains("超时")),
+            other => panic!("应该超时: {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(manager.failure_count("慢插件"), 1);
+
+        // 其他插件不受影响
+        assert_eq!(manager.execute_plugin("正常插件", "数据").unwrap().message, "处理 数据");
+    }
+
+    #[test]
+    fn test_panicking_plugin_is_isolated_counted_and_disabled() {
+        let manager = sandboxed_manager(Duration::from_secs(10), &[("崩溃插件", "panic")]);
+
+        for expected_failures in 1..=2 {
+            match manager.execute_plugin("崩溃插件", "输入") {
+                Err(PluginError::PluginExecutionError(msg)) => assert!(msg.contains("panic") && msg.contains("插件内部错误: 输入")),
+                other => panic!("应该捕获panic: {:?}", other),
+            }
+            assert_eq!(manager.failure_count("崩溃插件"), expected_failures);
+        }
+
+        // 连续失败达到上限后自动禁用，不再执行
+        assert!(manager.is_auto_disabled("崩溃插件"));
+        match manager.execute_plugin("崩溃插件", "输入") {
+            Err(PluginError::PluginExecutionError(msg)) => assert!(msg.contains("自动禁用")),
+            other => panic!("应该被禁用: {:?}", other),
+        }
+        assert!(!manager.list_plugins()[0].enabled);
+
+        manager.reenable_plugin("崩溃插件");
+        assert_eq!(manager.failure_count("崩溃插件"), 0);
+        assert!(!manager.is_auto_disabled("崩溃插件"));
+    }
+
+    #[test]
+    fn test_register_with_valid_config() {
+        let mut manager = PluginManager::new();
+        let config = PluginConfig::new("简单认证".to_string(), "1.0.0".to_string())
+            .with_parameter("session_timeout".to_string(), "3600".to_string());
+        assert!(manager.register_auth_provider(Box::new(SimpleAuthPlugin::new()), config).is_ok());
+
+        // 可选参数可以省略
+        let config = PluginConfig::new("XML处理器".to_string(), "1.0.0".to_string());
+        assert!(manager.register_data_processor(Box::new(XmlProcessorPlugin::new()), config).is_ok());
+        assert_eq!(manager.list_plugins().len(), 2);
+    }
+
+    #[test]
+    fn test_register_rejects_missing_or_mistyped_parameters() {
+        let mut manager = PluginManager::new();
+        let config = PluginConfig::new("简单认证".to_string(), "1.0.0".to_string());
+        match manager.register_auth_provider(Box::new(SimpleAuthPlugin::new()), config) {
+            Err(PluginError::PluginConfigError(msg)) => assert!(msg.contains("缺少参数 session_timeout")),
+            other => panic!("应该拒绝缺少参数的配置: {:?}", other),
+        }
+        assert!(manager.list_plugins().is_empty());
+
+        let config = PluginConfig::new("XML处理器".to_string(), "1.0.0".to_string())
+            .with_parameter("schema_validation".to_string(), "maybe".to_string());
+        assert!(matches!(manager.register_data_processor(Box::new(XmlProcessorPlugin::new()), config),
+                         Err(PluginError::PluginConfigError(_))));
+
+        let config = PluginConfig::new("简单认证".to_string(), "1.0.0".to_string())
+            .with_parameter("session_timeout".to_string(), "一小时".to_string());
+        match manager.register_auth_provider(Box::new(SimpleAuthPlugin::new()), config) {
+            Err(PluginError::PluginConfigError(msg)) => {
+                assert!(msg.contains("参数 session_timeout 类型错误") && !msg.contains("缺少"), "{}", msg)
+            }
+            other => panic!("应该拒绝类型错误的配置: {:?}", other),
+        }
+
+        // 所有问题一次性报告
+        let schema = vec![
+            ConfigParameter::required("host", ParameterType::String),
+            ConfigParameter::required("port", ParameterType::Integer),
+            ConfigParameter::optional("ratio", ParameterType::Float),
+        ];
+        let config = PluginConfig::new("服务".to_string(), "1.0.0".to_string())
+            .with_parameter("port".to_string(), "http".to_string())
+            .with_parameter("ratio".to_string(), "0.5".to_string());
+        assert_eq!(config.validate(&schema), Err(PluginConfigViolations {
+            missing: vec!["host".to_string()],
+            mistyped: vec![("port".to_string(), ParameterType::Integer)],
+        }));
+    }
+
+    fn pipeline_context(stages: &str) -> PluginContext {
+        let config = PluginConfig::new("流水线".to_string(), "1.0.0".to_string())
+            .with_parameter("stages".to_string(), stages.to_string());
+        PluginContext::new("流水线".to_string(), config)
+    }
+
+    fn json_xml_pipeline() -> PipelineProcessorPlugin {
+        PipelineProcessorPlugin::new(
+            "流水线".to_string(),
+            vec![Box::new(JsonProcessorPlugin::new()), Box::new(XmlProcessorPlugin::new())],
+        )
+    }
+
+    #[test]
+    fn test_pipeline_passes_intermediate_data_between_stages() {
+        let pipeline = json_xml_pipeline();
+        let context = pipeline_context("JSON处理器, validate:JSON处理器, XML处理器, validate:XML处理器");
+
+        let trace = pipeline.run_stages("订单", &context).unwrap();
+        let stages: Vec<&str> = trace.iter().map(|(stage, _)| stage.as_str()).collect();
+        assert_eq!(stages, vec!["JSON处理器", "validate:JSON处理器", "XML处理器", "validate:XML处理器"]);
+
+        let json = JsonProcessorPlugin::new().process_data("订单", &context).unwrap();
+        assert_eq!(trace[0].1, json);
+        // 校验阶段不改变数据
+        assert_eq!(trace[1].1, json);
+        // XML阶段接收的是JSON阶段的输出
+        assert_eq!(trace[2].1, XmlProcessorPlugin::new().process_data(&json, &context).unwrap());
+        assert_eq!(pipeline.process_data("订单", &context).unwrap(), trace[3].1);
+
+        // 通过管理器注册后，阶段顺序来自注册时的配置
+        let mut manager = PluginManager::new();
+        let config = PluginConfig::new("流水线".to_string(), "1.0.0".to_string())
+            .with_parameter("stages".to_string(), "XML处理器".to_string());
+        manager.register_data_processor(Box::new(json_xml_pipeline()), config).unwrap();
+        assert!(manager.process_data("流水线", "订单").unwrap().starts_with("<data>"));
+    }
+
+    #[test]
+    fn test_pipeline_short_circuits_on_failing_stage() {
+        let pipeline = json_xml_pipeline();
+
+        // JSON阶段的输出不是XML，第二阶段校验失败，XML阶段不会执行
+        let context = pipeline_context("JSON处理器, validate:XML处理器, XML处理器");
+        match pipeline.run_stages("订单", &context) {
+            Err(PluginError::PluginExecutionError(msg)) => {
+                assert!(msg.contains("validate:XML处理器"), "{}", msg);
+                assert!(msg.contains("数据不符合 XML处理器 的格式"), "{}", msg);
+            }
+            other => panic!("应该在校验阶段失败: {:?}", other),
+        }
+
+        // 配置了不存在的处理器
+        let context = pipeline_context("JSON处理器, CSV处理器");
+        assert!(matches!(pipeline.run_stages("订单", &context), Err(PluginError::PluginConfigError(msg)) if msg.contains("CSV处理器")));
+
+        // 缺少 stages 配置时注册就会失败
+        let mut manager = PluginManager::new();
+        let config = PluginConfig::new("流水线".to_string(), "1.0.0".to_string());
+        assert!(matches!(manager.register_data_processor(Box::new(json_xml_pipeline()), config), Err(PluginError::PluginConfigError(_))));
+    }
+
+    /// 记录钩子调用顺序的观察者，可以否决指定输入的执行
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+        veto_input: Option<&'static str>,
+    }
+
+    impl RecordingObserver {
+        fn new(veto_input: Option<&'static str>) -> Arc<Self> {
+            Arc::new(Self { events: Mutex::new(Vec::new()), veto_input })
+        }
+
+        fn record(&self, event: String) {
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn events(&self) -> Vec<String> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl PluginObserver for RecordingObserver {
+        fn on_register(&self, plugin_name: &str) {
+            self.record(format!("register:{}", plugin_name));
+        }
+
+        fn on_initialize(&self, plugin_name: &str) {
+            self.record(format!("initialize:{}", plugin_name));
+        }
+
+        fn on_execute_before(&self, plugin_name: &str, input: &str) -> bool {
+            self.record(format!("before:{}", plugin_name));
+            self.veto_input != Some(input)
+        }
+
+        fn on_execute_after(&self, plugin_name: &str, succeeded: bool) {
+            self.record(format!("after:{}:{}", plugin_name, succeeded));
+        }
+
+        fn on_auto_disable(&self, plugin_name: &str, failures: u32) {
+            self.record(format!("auto_disable:{}:{}", plugin_name, failures));
+        }
+
+        fn on_unload(&self, plugin_name: &str) {
+            self.record(format!("unload:{}", plugin_name));
+        }
+    }
+
+    #[test]
+    fn test_observer_receives_lifecycle_hook_sequence() {
+        let observer = RecordingObserver::new(None);
+        let mut manager = PluginManager::new();
+        manager.add_observer(observer.clone());
+
+        let plugin = MisbehavingPlugin { name: "审计插件".to_string(), behavior: "ok" };
+        manager.register_plugin(Box::new(plugin), PluginConfig::new("审计插件".to_string(), "1.0.0".to_string())).unwrap();
+        manager.initialize_all().unwrap();
+        manager.execute_plugin("审计插件", "数据").unwrap();
+        manager.cleanup_all().unwrap();
+
+        assert_eq!(observer.events(), vec![
+            "register:审计插件",
+            "initialize:审计插件",
+            "before:审计插件",
+            "after:审计插件:true",
+            "unload:审计插件",
+        ]);
+    }
+
+    #[test]
+    fn test_auto_disable_is_reported_to_observers() {
+        let observer = RecordingObserver::new(None);
+        let mut manager = sandboxed_manager(Duration::from_secs(10), &[("崩溃插件", "panic")]);
+        manager.add_observer(observer.clone());
+
+        let _ = manager.execute_plugin("崩溃插件", "输入");
+        let _ = manager.execute_plugin("崩溃插件", "输入");
+
+        assert_eq!(observer.events(), vec![
+            "before:崩溃插件",
+            "after:崩溃插件:false",
+            "before:崩溃插件",
+            "after:崩溃插件:false",
+            "auto_disable:崩溃插件:2",
+        ]);
+    }
+
+    #[test]
+    fn test_observer_veto_prevents_execution() {
+        let observer = RecordingObserver::new(Some("禁止的输入"));
+        let mut manager = PluginManager::new();
+        manager.add_observer(observer.clone());
+        manager.register_data_processor(Box::new(JsonProcessorPlugin::new()), PluginConfig::new("JSON处理器".to_string(), "1.0.0".to_string())).unwrap();
+
+        match manager.process_data("JSON处理器", "禁止的输入") {
+            Err(PluginError::PluginExecutionError(msg)) => assert!(msg.contains("否决")),
+            other => panic!("执行应该被否决: {:?}", other),
+        }
+        assert!(manager.process_data("JSON处理器", "允许的输入").is_ok());
+
+        // 被否决的执行没有 after 事件
+        assert_eq!(observer.events(), vec![
+            "register:JSON处理器",
+            "before:JSON处理器",
+            "before:JSON处理器",
+            "after:JSON处理器:true",
+        ]);
+    }
+
+    /// 所有钩子都 panic 的观察者
+    struct PanickingObserver;
+
+    impl PluginObserver for PanickingObserver {
+        fn on_register(&self, _plugin_name: &str) {
+            panic!("观察者注册钩子故障");
+        }
+
+        fn on_execute_before(&self, _plugin_name: &str, _input: &str) -> bool {
+            panic!("观察者执行钩子故障");
+        }
+    }
+
+    #[test]
+    fn test_every_observer_is_asked_even_after_a_veto_or_panic() {
+        let vetoing = RecordingObserver::new(Some("禁止的输入"));
+        let later = RecordingObserver::new(None);
+        let mut manager = PluginManager::new();
+        manager.add_observer(vetoing.clone());
+        manager.add_observer(Arc::new(PanickingObserver));
+        manager.add_observer(later.clone());
+        manager.register_data_processor(Box::new(JsonProcessorPlugin::new()), PluginConfig::new("JSON处理器".to_string(), "1.0.0".to_string())).unwrap();
+
+        match manager.process_data("JSON处理器", "禁止的输入") {
+            Err(PluginError::PluginExecutionError(msg)) => assert!(msg.contains("2 个观察者否决"), "{}", msg),
+            other => panic!("执行应该被否决: {:?}", other),
+        }
+
+        // 否决和 panic 都不会让后面的观察者错过事件
+        assert_eq!(later.events(), vec![
+            "register:JSON处理器",
+            "before:JSON处理器",
+        ]);
+    }
+}
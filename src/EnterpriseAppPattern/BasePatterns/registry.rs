@@ -13,6 +13,8 @@
  * 3. 单例保证：确保某些对象只有一个实例
  * 4. 解耦：避免硬编码的依赖关系
  * 5. 配置中心：集中管理系统配置
+ * 6. 类型化配置：按目标类型解析配置值，支持默认值和启动时的模式校验
+ * 7. 回退链：按 请求 -> 会话 -> 全局 的顺序逐层查找，返回最近作用域中的值
  * 
  * 适用场景：
  * - 需要全局访问某些服务或对象时
@@ -23,6 +25,7 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::any::{Any, TypeId};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
@@ -34,6 +37,12 @@ pub enum RegistryError {
     ServiceAlreadyRegistered(String),
     TypeMismatch(String),
     InitializationError(String),
+    /// 必需的配置项不存在
+    Missing(String),
+    /// 配置值不满足约束
+    InvalidValue(String),
+    /// 服务之间循环依赖，按解析顺序列出依赖链，首尾是同一个类型
+    CircularDependency(Vec<String>),
 }
 
 impl Display for RegistryError {
@@ -43,6 +52,9 @@ impl Display for RegistryError {
             RegistryError::ServiceAlreadyRegistered(msg) => write!(f, "服务已注册: {}", msg),
             RegistryError::TypeMismatch(msg) => write!(f, "类型不匹配: {}", msg),
             RegistryError::InitializationError(msg) => write!(f, "初始化错误: {}", msg),
+            RegistryError::Missing(key) => write!(f, "缺少必需的配置: {}", key),
+            RegistryError::InvalidValue(msg) => write!(f, "配置值无效: {}", msg),
+            RegistryError::CircularDependency(chain) => write!(f, "循环依赖: {}", chain.join(" -> ")),
         }
     }
 }
@@ -63,11 +75,49 @@ pub trait ServiceFactory: Send + Sync {
     fn lifetime(&self) -> ServiceLifetime;
 }
 
+thread_local! {
+    /// 当前线程正在解析的服务：(注册表地址, 类型, 类型名)
+    static RESOLVING: std::cell::RefCell<Vec<(usize, TypeId, &'static str)>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// 解析期间把类型留在解析栈上，离开作用域（包括出错返回）时出栈
+struct ResolvingGuard;
+
+impl ResolvingGuard {
+    fn enter<T: 'static>(registry: &ServiceRegistry) -> Result<Self, RegistryError> {
+        let key = (registry as *const ServiceRegistry as usize, TypeId::of::<T>());
+        RESOLVING.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(start) = stack.iter().position(|&(address, type_id, _)| (address, type_id) == key) {
+                let mut chain: Vec<String> = stack[start..].iter().map(|&(_, _, name)| name.to_string()).collect();
+                chain.push(std::any::type_name::<T>().to_string());
+                return Err(RegistryError::CircularDependency(chain));
+            }
+            stack.push((key.0, key.1, std::any::type_name::<T>()));
+            Ok(ResolvingGuard)
+        })
+    }
+}
+
+impl Drop for ResolvingGuard {
+    fn drop(&mut self) {
+        RESOLVING.with(|stack| {
+            stack.borrow_mut().pop();
+        });
+    }
+}
+
 /// 服务注册表
+///
+/// 服务定位是读多写少的场景：查找工厂和已创建的单例只取读锁，
+/// 只有注册服务和首次创建单例时才取写锁。工厂运行期间不持有任何锁，
+/// 创建实例时可以继续解析其他服务，同一线程上的循环依赖会返回 `RegistryError::CircularDependency`。
+/// 多个线程同时首次解析同一单例时工厂可能运行多次，但只有最先发布的实例被保留并返回给所有调用方。
 pub struct ServiceRegistry {
     services: RwLock<HashMap<TypeId, Arc<dyn ServiceFactory>>>,
-    singletons: Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    singletons: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
     scoped_instances: RwLock<HashMap<String, HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+    next_scope_id: AtomicU64,
 }
 
 impl ServiceRegistry {
@@ -75,8 +125,9 @@ impl ServiceRegistry {
     pub fn new() -> Self {
         Self {
             services: RwLock::new(HashMap::new()),
-            singletons: Mutex::new(HashMap::new()),
+            singletons: RwLock::new(HashMap::new()),
             scoped_instances: RwLock::new(HashMap::new()),
+            next_scope_id: AtomicU64::new(1),
         }
     }
     
@@ -99,46 +150,48 @@ impl ServiceRegistry {
     pub fn get<T: 'static + Send + Sync>(&self, scope: Option<&str>) -> Result<Arc<T>, RegistryError> {
         let type_id = TypeId::of::<T>();
         
-        // 获取服务工厂
-        let services = self.services.read().unwrap();
-        let factory = services.get(&type_id)
+        // 获取服务工厂，克隆后立即释放读锁，工厂创建实例时可以再访问注册表
+        let factory = self.services.read().unwrap().get(&type_id).cloned()
             .ok_or_else(|| RegistryError::ServiceNotFound(
                 format!("未找到类型 {:?} 的服务", type_id)
             ))?;
         
+        let _guard = ResolvingGuard::enter::<T>(self)?;
         match factory.lifetime() {
-            ServiceLifetime::Singleton => self.get_singleton::<T>(factory.clone()),
-            ServiceLifetime::Transient => self.create_transient::<T>(factory.clone()),
-            ServiceLifetime::Scoped => self.get_scoped::<T>(factory.clone(), scope.unwrap_or("default")),
+            ServiceLifetime::Singleton => self.get_singleton::<T>(factory),
+            ServiceLifetime::Transient => self.create_transient::<T>(factory),
+            ServiceLifetime::Scoped => self.get_scoped::<T>(factory, scope.unwrap_or("default")),
         }
     }
     
+    /// 解析服务实例，等同于不带作用域的 `get`
+    pub fn resolve<T: 'static + Send + Sync>(&self) -> Result<Arc<T>, RegistryError> {
+        self.get::<T>(None)
+    }
+    
     /// 获取单例实例
     fn get_singleton<T: 'static + Send + Sync>(&self, factory: Arc<dyn ServiceFactory>) -> Result<Arc<T>, RegistryError> {
         let type_id = TypeId::of::<T>();
         
-        // 先检查是否已经存在
-        {
-            let singletons = self.singletons.lock().unwrap();
-            if let Some(instance) = singletons.get(&type_id) {
-                return instance.clone().downcast::<T>()
-                    .map_err(|_| RegistryError::TypeMismatch("类型转换失败".to_string()));
-            }
+        // 快速路径：已创建的单例只取读锁
+        let existing = self.singletons.read().unwrap().get(&type_id).cloned();
+        if let Some(instance) = existing {
+            return instance.downcast::<T>()
+                .map_err(|_| RegistryError::TypeMismatch("类型转换失败".to_string()));
         }
         
-        // 创建新实例
+        // 不持有任何锁创建实例，工厂跨线程互相解析也不会死锁
         let instance = factory.create()?;
-        let typed_instance = instance.downcast::<T>()
-            .map_err(|_| RegistryError::TypeMismatch("类型转换失败".to_string()))?;
+        let typed_instance: Arc<T> = Arc::from(instance.downcast::<T>()
+            .map_err(|_| RegistryError::TypeMismatch("类型转换失败".to_string()))?);
         
-        // 转换为 Arc 以共享所有权
-        let arc_instance: Arc<T> = Arc::from(typed_instance);
-        
-        // 存储单例
-        let mut singletons = self.singletons.lock().unwrap();
-        singletons.insert(type_id, arc_instance.clone());
-        
-        Ok(arc_instance)
+        // 并发创建时保留最先发布的实例，其余的丢弃
+        let published = self.singletons.write().unwrap()
+            .entry(type_id)
+            .or_insert(typed_instance)
+            .clone();
+        published.downcast::<T>()
+            .map_err(|_| RegistryError::TypeMismatch("类型转换失败".to_string()))
     }
     
     /// 创建瞬态实例
@@ -182,6 +235,15 @@ impl ServiceRegistry {
         Ok(arc_instance)
     }
     
+    /// 创建匿名作用域，作用域内 Scoped 服务只创建一次，句柄释放时清理缓存的实例
+    pub fn create_scope(&self) -> ScopeHandle<'_> {
+        let id = self.next_scope_id.fetch_add(1, Ordering::Relaxed);
+        ScopeHandle {
+            registry: self,
+            name: format!("scope-{}", id),
+        }
+    }
+    
     /// 清理作用域
     pub fn clear_scope(&self, scope: &str) {
         let mut scoped_instances = self.scoped_instances.write().unwrap();
@@ -200,6 +262,33 @@ impl Default for ServiceRegistry {
     }
 }
 
+/// 作用域句柄，例如一次请求
+///
+/// 通过句柄解析的 Scoped 服务在同一作用域内是同一实例，不同作用域互相隔离；
+/// Singleton 和 Transient 服务的行为与直接解析相同。
+pub struct ScopeHandle<'a> {
+    registry: &'a ServiceRegistry,
+    name: String,
+}
+
+impl ScopeHandle<'_> {
+    /// 作用域名称，可以传给 `ServiceRegistry::get`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// 在本作用域内解析服务
+    pub fn resolve<T: 'static + Send + Sync>(&self) -> Result<Arc<T>, RegistryError> {
+        self.registry.get::<T>(Some(&self.name))
+    }
+}
+
+impl Drop for ScopeHandle<'_> {
+    fn drop(&mut self) {
+        self.registry.clear_scope(&self.name);
+    }
+}
+
 /// 全局服务注册表
 static GLOBAL_REGISTRY: std::sync::OnceLock<ServiceRegistry> = std::sync::OnceLock::new();
 
@@ -208,6 +297,96 @@ pub fn global_registry() -> &'static ServiceRegistry {
     GLOBAL_REGISTRY.get_or_init(ServiceRegistry::new)
 }
 
+/// 可以从配置字符串解析的类型
+///
+/// 解析前去掉首尾空白，布尔值额外接受 yes/no、on/off、1/0
+pub trait ConfigValue: Sized {
+    fn parse_config(raw: &str) -> Option<Self>;
+}
+
+macro_rules! impl_config_value_from_str {
+    ($($ty:ty),*) => {
+        $(
+            impl ConfigValue for $ty {
+                fn parse_config(raw: &str) -> Option<Self> {
+                    raw.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_config_value_from_str!(i32, i64, u16, u32, u64, usize, f64);
+
+impl ConfigValue for bool {
+    fn parse_config(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl ConfigValue for String {
+    fn parse_config(raw: &str) -> Option<Self> {
+        Some(raw.to_string())
+    }
+}
+
+/// 配置值约束
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigConstraint {
+    /// 任意值
+    Any,
+    /// 闭区间内的整数
+    IntRange { min: i64, max: i64 },
+    Bool,
+    /// 必须是列出的值之一
+    OneOf(Vec<String>),
+}
+
+impl ConfigConstraint {
+    fn check(&self, key: &str, raw: &str) -> Result<(), RegistryError> {
+        let valid = match self {
+            ConfigConstraint::Any => true,
+            ConfigConstraint::IntRange { min, max } => {
+                i64::parse_config(raw).is_some_and(|value| (*min..=*max).contains(&value))
+            }
+            ConfigConstraint::Bool => bool::parse_config(raw).is_some(),
+            ConfigConstraint::OneOf(allowed) => allowed.iter().any(|value| value == raw.trim()),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(RegistryError::InvalidValue(format!("{} = {} 不满足约束 {:?}", key, raw, self)))
+        }
+    }
+}
+
+/// 配置模式：声明必需/可选的配置项及其约束
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSchema {
+    entries: Vec<(String, bool, ConfigConstraint)>,
+}
+
+impl ConfigSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn required(mut self, key: &str, constraint: ConfigConstraint) -> Self {
+        self.entries.push((key.to_string(), true, constraint));
+        self
+    }
+    
+    /// 可选配置项只在存在时检查约束
+    pub fn optional(mut self, key: &str, constraint: ConfigConstraint) -> Self {
+        self.entries.push((key.to_string(), false, constraint));
+        self
+    }
+}
+
 /// 配置注册表 - 专门用于管理配置
 pub struct ConfigRegistry {
     configs: RwLock<HashMap<String, String>>,
@@ -232,9 +411,30 @@ impl ConfigRegistry {
         configs.get(key).cloned()
     }
     
-    /// 获取配置值或默认值
-    pub fn get_or_default(&self, key: &str, default: &str) -> String {
-        self.get(key).unwrap_or_else(|| default.to_string())
+    /// 获取类型化配置值，不存在或无法解析时返回默认值
+    pub fn get_or_default<T: ConfigValue>(&self, key: &str, default: T) -> T {
+        self.get(key).and_then(|raw| T::parse_config(&raw)).unwrap_or(default)
+    }
+    
+    /// 获取必需的类型化配置值
+    pub fn require<T: ConfigValue>(&self, key: &str) -> Result<T, RegistryError> {
+        let raw = self.get(key).ok_or_else(|| RegistryError::Missing(key.to_string()))?;
+        T::parse_config(&raw).ok_or_else(|| RegistryError::TypeMismatch(
+            format!("配置 {} = {} 无法转换为 {}", key, raw, std::any::type_name::<T>())
+        ))
+    }
+    
+    /// 按模式一次性校验所有配置，返回全部问题
+    pub fn validate(&self, schema: &ConfigSchema) -> Result<(), Vec<RegistryError>> {
+        let configs = self.configs.read().unwrap();
+        let errors: Vec<RegistryError> = schema.entries.iter()
+            .filter_map(|(key, required, constraint)| match configs.get(key) {
+                Some(raw) => constraint.check(key, raw).err(),
+                None if *required => Some(RegistryError::Missing(key.clone())),
+                None => None,
+            })
+            .collect();
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
     }
     
     /// 获取整数配置
@@ -287,6 +487,51 @@ pub fn global_config() -> &'static ConfigRegistry {
     GLOBAL_CONFIG.get_or_init(ConfigRegistry::new)
 }
 
+/// 注册表回退链
+///
+/// 按添加顺序查找，最先添加的层级优先级最高，返回第一个命中的值。
+/// 近层的同名键会遮蔽远层的值，例如请求级配置覆盖全局配置。
+pub struct RegistryChain<'a> {
+    layers: Vec<(String, &'a ConfigRegistry)>,
+}
+
+impl<'a> RegistryChain<'a> {
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+    
+    /// 请求 -> 会话 -> 全局 的标准回退链
+    pub fn request_session_global(request: &'a ConfigRegistry, session: &'a ConfigRegistry) -> Self {
+        Self::new()
+            .with_layer("request", request)
+            .with_layer("session", session)
+            .with_layer("global", global_config())
+    }
+    
+    /// 追加一层，优先级低于已有的层
+    pub fn with_layer(mut self, name: &str, registry: &'a ConfigRegistry) -> Self {
+        self.layers.push((name.to_string(), registry));
+        self
+    }
+    
+    /// 沿回退链查找键，返回第一个命中的值
+    pub fn resolve_with_fallback(&self, key: &str) -> Option<String> {
+        self.resolve_with_source(key).map(|(_, value)| value)
+    }
+    
+    /// 查找键并返回命中的层名，便于排查配置来源
+    pub fn resolve_with_source(&self, key: &str) -> Option<(&str, String)> {
+        self.layers.iter()
+            .find_map(|(name, registry)| registry.get(key).map(|value| (name.as_str(), value)))
+    }
+}
+
+impl Default for RegistryChain<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// 示例服务接口
 pub trait DatabaseService: Send + Sync {
     fn connect(&self) -> Result<String, String>;
@@ -362,9 +607,8 @@ impl ServiceFactory for DatabaseServiceFactory {
                 Box::new(MySqlDatabaseService::new(connection_string))
             }
             "postgresql" => {
-                let host = global_config().get_or_default("postgres.host", "localhost");
-                let port: u16 = global_config().get_int("postgres.port")
-                    .unwrap_or(5432) as u16;
+                let host = global_config().get_or_default("postgres.host", "localhost".to_string());
+                let port = global_config().get_or_default("postgres.port", 5432u16);
                 Box::new(PostgreSqlDatabaseService::new(host, port))
             }
             _ => return Err(RegistryError::InitializationError(
@@ -436,9 +680,8 @@ impl CacheServiceFactory {
 
 impl ServiceFactory for CacheServiceFactory {
     fn create(&self) -> Result<Box<dyn Any + Send + Sync>, RegistryError> {
-        let host = global_config().get_or_default("redis.host", "localhost");
-        let port: u16 = global_config().get_int("redis.port")
-            .unwrap_or(6379) as u16;
+        let host = global_config().get_or_default("redis.host", "localhost".to_string());
+        let port = global_config().get_or_default("redis.port", 6379u16);
         
         let service: Box<dyn Any + Send + Sync> = Box::new(RedisCacheService::new(host, port));
         Ok(service)
@@ -470,7 +713,29 @@ pub fn demo() {
     println!("PostgreSQL主机: {}", config.get("postgres.host").unwrap());
     println!("Redis端口: {}", config.get_int("redis.port").unwrap());
     println!("调试模式: {}", config.get_bool("app.debug").unwrap());
-    println!("不存在的配置（使用默认值）: {}", config.get_or_default("app.timeout", "30"));
+    println!("不存在的配置（使用默认值）: {}", config.get_or_default("app.timeout", 30));
+    
+    // 启动时按模式校验配置
+    let schema = ConfigSchema::new()
+        .required("app.max_connections", ConfigConstraint::IntRange { min: 1, max: 1000 })
+        .required("app.debug", ConfigConstraint::Bool)
+        .optional("app.log_level", ConfigConstraint::OneOf(vec!["info".to_string(), "debug".to_string()]));
+    match config.validate(&schema) {
+        Ok(()) => println!("配置校验通过"),
+        Err(errors) => errors.iter().for_each(|e| println!("配置校验失败: {}", e)),
+    }
+    
+    // 回退链：请求级配置覆盖会话级和全局配置
+    let session_config = ConfigRegistry::new();
+    session_config.set("app.max_connections", "20");
+    let request_config = ConfigRegistry::new();
+    request_config.set("app.debug", "false");
+    let chain = RegistryChain::request_session_global(&request_config, &session_config);
+    for key in ["app.debug", "app.max_connections", "redis.host"] {
+        if let Some((layer, value)) = chain.resolve_with_source(key) {
+            println!("回退链查找 {}: {} (来自 {})", key, value, layer);
+        }
+    }
     
     println!("{}", "=".repeat(50));
     
@@ -553,6 +818,15 @@ pub fn demo() {
     registry.clear_scope("request-1");
     println!("已清理请求1的作用域");
     
+    // 作用域句柄：离开代码块时自动清理
+    {
+        let request_scope = registry.create_scope();
+        let a: Arc<PostgreSqlDatabaseService> = request_scope.resolve().unwrap();
+        let b: Arc<PostgreSqlDatabaseService> = request_scope.resolve().unwrap();
+        println!("作用域 {} 内的两个实例是否相同: {}", request_scope.name(), Arc::ptr_eq(&a, &b));
+    }
+    println!("作用域句柄已释放，其缓存的实例随之清理");
+    
     println!("{}", "=".repeat(50));
     
     // 4. 错误处理演示
@@ -602,4 +876,312 @@ pub fn demo() {
     println!("2. 实现依赖注入容器时");
     println!("3. 需要管理对象生命周期时");
     println!("4. 需要延迟初始化昂贵对象时");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_retrieval_with_coercion() {
+        let config = ConfigRegistry::new();
+        config.set("port", " 8080 ");
+        config.set("verbose", "yes");
+        config.set("ratio", "0.75");
+        config.set("name", "orders");
+
+        assert_eq!(config.require::<u16>("port").unwrap(), 8080);
+        assert!(config.require::<bool>("verbose").unwrap());
+        assert_eq!(config.require::<f64>("ratio").unwrap(), 0.75);
+        assert_eq!(config.get_or_default("name", String::new()), "orders");
+        // 不存在或无法解析时使用默认值
+        assert_eq!(config.get_or_default("timeout", 30u32), 30);
+        assert_eq!(config.get_or_default("name", 7i32), 7);
+        assert!(matches!(config.require::<u16>("name"), Err(RegistryError::TypeMismatch(_))));
+    }
+
+    #[test]
+    fn test_missing_required_key_errors() {
+        let config = ConfigRegistry::new();
+        match config.require::<String>("db.url") {
+            Err(RegistryError::Missing(key)) => assert_eq!(key, "db.url"),
+            other => panic!("应该缺少配置: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_schema_validation_catches_out_of_range_value() {
+        let config = ConfigRegistry::new();
+        config.set("pool.size", "5000");
+        config.set("feature.enabled", "on");
+        config.set("log.level", "trace");
+
+        let schema = ConfigSchema::new()
+            .required("pool.size", ConfigConstraint::IntRange { min: 1, max: 100 })
+            .required("feature.enabled", ConfigConstraint::Bool)
+            .required("db.url", ConfigConstraint::Any)
+            .optional("log.level", ConfigConstraint::OneOf(vec!["info".to_string(), "warn".to_string()]))
+            .optional("cache.ttl", ConfigConstraint::IntRange { min: 0, max: 3600 });
+
+        let errors = config.validate(&schema).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(&errors[0], RegistryError::InvalidValue(msg) if msg.contains("pool.size")));
+        assert!(matches!(&errors[1], RegistryError::Missing(key) if key == "db.url"));
+        assert!(matches!(&errors[2], RegistryError::InvalidValue(msg) if msg.contains("log.level")));
+
+        config.set("pool.size", "50");
+        config.set("db.url", "mysql://localhost");
+        config.set("log.level", "warn");
+        assert!(config.validate(&schema).is_ok());
+    }
+
+    #[test]
+    fn test_global_only_key_resolves_via_fallback() {
+        global_config().set("test.chain.global_only", "global-value");
+        let request = ConfigRegistry::new();
+        let session = ConfigRegistry::new();
+        let chain = RegistryChain::request_session_global(&request, &session);
+
+        assert_eq!(chain.resolve_with_fallback("test.chain.global_only"), Some("global-value".to_string()));
+        assert_eq!(chain.resolve_with_source("test.chain.global_only"), Some(("global", "global-value".to_string())));
+        assert_eq!(chain.resolve_with_fallback("test.chain.missing"), None);
+    }
+
+    #[test]
+    fn test_shadowed_key_resolves_from_nearest_scope() {
+        let global = ConfigRegistry::new();
+        global.set("timeout", "30");
+        global.set("region", "cn-north");
+        let session = ConfigRegistry::new();
+        session.set("timeout", "20");
+        session.set("locale", "zh-CN");
+        let request = ConfigRegistry::new();
+        request.set("timeout", "5");
+
+        let chain = RegistryChain::new()
+            .with_layer("request", &request)
+            .with_layer("session", &session)
+            .with_layer("global", &global);
+        assert_eq!(chain.resolve_with_source("timeout"), Some(("request", "5".to_string())));
+        assert_eq!(chain.resolve_with_source("locale"), Some(("session", "zh-CN".to_string())));
+        assert_eq!(chain.resolve_with_source("region"), Some(("global", "cn-north".to_string())));
+
+        // 去掉请求级覆盖后，回退到会话级的值
+        request.clear();
+        assert_eq!(chain.resolve_with_fallback("timeout"), Some("20".to_string()));
+    }
+
+    /// 计数创建次数的服务，用于并发测试
+    struct CountedService {
+        id: usize,
+    }
+
+    struct CountedServiceFactory {
+        created: Arc<std::sync::atomic::AtomicUsize>,
+        lifetime: ServiceLifetime,
+    }
+
+    impl ServiceFactory for CountedServiceFactory {
+        fn create(&self) -> Result<Box<dyn Any + Send + Sync>, RegistryError> {
+            // 放大首次创建的并发窗口
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            let id = self.created.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Box::new(CountedService { id }))
+        }
+
+        fn lifetime(&self) -> ServiceLifetime {
+            self.lifetime
+        }
+    }
+
+    #[test]
+    fn test_concurrent_resolve_under_stress() {
+        let registry = Arc::new(ServiceRegistry::new());
+        let created = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        registry.register::<CountedService>(Arc::new(CountedServiceFactory {
+            created: Arc::clone(&created),
+            lifetime: ServiceLifetime::Singleton,
+        })).unwrap();
+
+        let barrier = Arc::new(std::sync::Barrier::new(8));
+        let handles: Vec<_> = (0..8).map(|worker| {
+            let registry = Arc::clone(&registry);
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                let mut resolved = Vec::new();
+                for round in 0..500 {
+                    resolved.push(registry.resolve::<CountedService>().unwrap());
+                    // 解析的同时有写操作，不应死锁
+                    if worker == 0 && round % 100 == 0 {
+                        registry.clear_scope("default");
+                        let _ = registry.register::<String>(Arc::new(CacheServiceFactory::new(ServiceLifetime::Transient)));
+                    }
+                }
+                resolved
+            })
+        }).collect();
+
+        let all: Vec<Arc<CountedService>> = handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect();
+        assert_eq!(all.len(), 8 * 500);
+        // 并发首次解析时工厂可能运行多次，但所有调用方拿到同一个实例
+        assert!((1..=8).contains(&created.load(std::sync::atomic::Ordering::SeqCst)));
+        assert!(all.iter().all(|service| Arc::ptr_eq(service, &all[0])));
+        assert_eq!(registry.service_count(), 2);
+    }
+
+    #[test]
+    fn test_global_registry_is_shared_across_threads() {
+        struct GlobalProbe;
+
+        let handles: Vec<_> = (0..4).map(|_| std::thread::spawn(|| global_registry() as *const ServiceRegistry as usize)).collect();
+        let addresses: Vec<usize> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert!(addresses.iter().all(|&address| address == addresses[0]));
+        assert!(matches!(global_registry().resolve::<GlobalProbe>(), Err(RegistryError::ServiceNotFound(_))));
+    }
+
+    #[test]
+    fn test_scope_per_request_isolation_and_release() {
+        let registry = ServiceRegistry::new();
+        let created = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        registry.register::<CountedService>(Arc::new(CountedServiceFactory {
+            created: Arc::clone(&created),
+            lifetime: ServiceLifetime::Scoped,
+        })).unwrap();
+
+        // 模拟两个请求，每个请求多次解析同一服务
+        let handle_request = |registry: &ServiceRegistry| {
+            let scope = registry.create_scope();
+            let first = scope.resolve::<CountedService>().unwrap();
+            let second = scope.resolve::<CountedService>().unwrap();
+            assert!(Arc::ptr_eq(&first, &second));
+            // 通过作用域名称直接解析得到同一实例
+            assert!(Arc::ptr_eq(&first, &registry.get::<CountedService>(Some(scope.name())).unwrap()));
+            first
+        };
+
+        let request1 = handle_request(&registry);
+        let request2 = handle_request(&registry);
+        assert!(!Arc::ptr_eq(&request1, &request2));
+        assert_eq!((request1.id, request2.id), (0, 1));
+        assert_eq!(created.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // 作用域释放后注册表不再持有实例
+        assert!(registry.scoped_instances.read().unwrap().is_empty());
+        assert_eq!(Arc::strong_count(&request1), 1);
+
+        let scope = registry.create_scope();
+        let other = registry.create_scope();
+        assert_ne!(scope.name(), other.name());
+        drop(other);
+        scope.resolve::<CountedService>().unwrap();
+        assert_eq!(registry.scoped_instances.read().unwrap().len(), 1);
+    }
+
+    struct ServiceA {
+        _b: Arc<ServiceB>,
+    }
+
+    struct ServiceB {
+        _a: Arc<ServiceA>,
+    }
+
+    /// 创建时先解析依赖的工厂
+    struct DependentFactory {
+        registry: std::sync::Weak<ServiceRegistry>,
+        build: fn(&ServiceRegistry) -> Result<Box<dyn Any + Send + Sync>, RegistryError>,
+    }
+
+    impl ServiceFactory for DependentFactory {
+        fn create(&self) -> Result<Box<dyn Any + Send + Sync>, RegistryError> {
+            let registry = self.registry.upgrade().ok_or_else(|| RegistryError::InitializationError("注册表已释放".to_string()))?;
+            (self.build)(&registry)
+        }
+
+        fn lifetime(&self) -> ServiceLifetime {
+            ServiceLifetime::Singleton
+        }
+    }
+
+    fn short_names(chain: &[String]) -> Vec<&str> {
+        chain.iter().map(|name| name.rsplit("::").next().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_circular_dependency_is_reported_with_chain() {
+        let registry = Arc::new(ServiceRegistry::new());
+        registry.register::<ServiceA>(Arc::new(DependentFactory {
+            registry: Arc::downgrade(&registry),
+            build: |registry| Ok(Box::new(ServiceA { _b: registry.resolve::<ServiceB>()? })),
+        })).unwrap();
+        registry.register::<ServiceB>(Arc::new(DependentFactory {
+            registry: Arc::downgrade(&registry),
+            build: |registry| Ok(Box::new(ServiceB { _a: registry.resolve::<ServiceA>()? })),
+        })).unwrap();
+        registry.register::<CountedService>(Arc::new(CountedServiceFactory {
+            created: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            lifetime: ServiceLifetime::Singleton,
+        })).unwrap();
+
+        match registry.resolve::<ServiceA>() {
+            Err(RegistryError::CircularDependency(chain)) => assert_eq!(short_names(&chain), vec!["ServiceA", "ServiceB", "ServiceA"]),
+            Err(other) => panic!("应该检测到循环依赖: {}", other),
+            Ok(_) => panic!("应该检测到循环依赖"),
+        }
+
+        // 出错后解析栈已清空：其他服务正常解析，再次解析得到同样的依赖链
+        assert!(RESOLVING.with(|stack| stack.borrow().is_empty()));
+        assert!(registry.resolve::<CountedService>().is_ok());
+        match registry.resolve::<ServiceB>() {
+            Err(RegistryError::CircularDependency(chain)) => assert_eq!(short_names(&chain), vec!["ServiceB", "ServiceA", "ServiceB"]),
+            _ => panic!("应该检测到循环依赖"),
+        }
+    }
+
+    /// 两个线程同时进入工厂后再解析依赖的工厂
+    struct RendezvousFactory {
+        registry: std::sync::Weak<ServiceRegistry>,
+        rendezvous: Arc<std::sync::Barrier>,
+        build: fn(&ServiceRegistry) -> Result<Box<dyn Any + Send + Sync>, RegistryError>,
+    }
+
+    impl ServiceFactory for RendezvousFactory {
+        fn create(&self) -> Result<Box<dyn Any + Send + Sync>, RegistryError> {
+            let registry = self.registry.upgrade().ok_or_else(|| RegistryError::InitializationError("注册表已释放".to_string()))?;
+            self.rendezvous.wait();
+            (self.build)(&registry)
+        }
+
+        fn lifetime(&self) -> ServiceLifetime {
+            ServiceLifetime::Singleton
+        }
+    }
+
+    #[test]
+    fn test_cross_thread_circular_dependency_does_not_deadlock() {
+        let registry = Arc::new(ServiceRegistry::new());
+        let rendezvous = Arc::new(std::sync::Barrier::new(2));
+        registry.register::<ServiceA>(Arc::new(RendezvousFactory {
+            registry: Arc::downgrade(&registry),
+            rendezvous: Arc::clone(&rendezvous),
+            build: |registry| Ok(Box::new(ServiceA { _b: registry.resolve::<ServiceB>()? })),
+        })).unwrap();
+        registry.register::<ServiceB>(Arc::new(RendezvousFactory {
+            registry: Arc::downgrade(&registry),
+            rendezvous,
+            build: |registry| Ok(Box::new(ServiceB { _a: registry.resolve::<ServiceA>()? })),
+        })).unwrap();
+
+        // 两个线程分别在A和B的工厂中时解析对方，各自在本线程检测到循环依赖
+        let a = std::thread::spawn({
+            let registry = Arc::clone(&registry);
+            move || registry.resolve::<ServiceA>().map(|_| ())
+        });
+        let b = std::thread::spawn({
+            let registry = Arc::clone(&registry);
+            move || registry.resolve::<ServiceB>().map(|_| ())
+        });
+        assert!(matches!(a.join().unwrap(), Err(RegistryError::CircularDependency(_))));
+        assert!(matches!(b.join().unwrap(), Err(RegistryError::CircularDependency(_))));
+    }
+}
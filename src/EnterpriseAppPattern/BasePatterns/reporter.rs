@@ -0,0 +1,208 @@
+//! # 报告输出（Reporter）
+//!
+//! 演示函数原本直接调用 `println!`，输出无法被捕获，也就无法在测试中断言。
+//! `Reporter` 把演示输出抽象为三种结构：章节、文本行和表格，
+//! 由具体实现决定如何呈现。
+//!
+//! ## 实现
+//! - **ConsoleReporter**: 默认实现，打印到标准输出，表格绘制为边框表
+//! - **BufferReporter**: 把输出记录在内存中，供测试按章节检查
+
+/// 演示输出的接收者
+pub trait Reporter {
+    /// 开始一个新章节
+    fn section(&mut self, title: &str);
+    /// 输出一行文本
+    fn line(&mut self, text: &str);
+    /// 输出一张表格
+    fn table(&mut self, headers: &[&str], rows: &[&[&str]]);
+}
+
+// =================
+// 控制台输出
+// =================
+
+/// 打印到标准输出的报告器
+#[derive(Debug, Default)]
+pub struct ConsoleReporter;
+
+impl ConsoleReporter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Reporter for ConsoleReporter {
+    fn section(&mut self, title: &str) {
+        println!("\n{}:", title);
+    }
+
+    fn line(&mut self, text: &str) {
+        println!("{}", text);
+    }
+
+    fn table(&mut self, headers: &[&str], rows: &[&[&str]]) {
+        for line in render_table(headers, rows) {
+            println!("{}", line);
+        }
+    }
+}
+
+/// 终端中的显示宽度，中日韩字符和全角符号占两列
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|ch| match ch as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// 按列宽绘制边框表格
+fn render_table(headers: &[&str], rows: &[&[&str]]) -> Vec<String> {
+    let columns = headers.len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .chain(headers.get(column))
+                .map(|cell| display_width(cell))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let border = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+        format!("{}{}{}", left, segments.join(middle), right)
+    };
+    let row_line = |cells: &[&str]| {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let cell = cells.get(column).copied().unwrap_or("");
+                format!(" {}{} ", cell, " ".repeat(width - display_width(cell)))
+            })
+            .collect();
+        format!("│{}│", padded.join("│"))
+    };
+
+    let mut lines = vec![border("┌", "┬", "┐"), row_line(headers), border("├", "┼", "┤")];
+    lines.extend(rows.iter().map(|row| row_line(row)));
+    lines.push(border("└", "┴", "┘"));
+    lines
+}
+
+// =================
+// 内存缓冲输出
+// =================
+
+/// 记录下来的一条输出
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportEntry {
+    Section(String),
+    Line(String),
+    Table { headers: Vec<String>, rows: Vec<Vec<String>> },
+}
+
+/// 把输出记录在内存中的报告器，用于测试
+#[derive(Debug, Default)]
+pub struct BufferReporter {
+    entries: Vec<ReportEntry>,
+}
+
+impl BufferReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按输出顺序返回全部记录
+    pub fn entries(&self) -> &[ReportEntry] {
+        &self.entries
+    }
+
+    /// 所有章节标题
+    pub fn sections(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                ReportEntry::Section(title) => Some(title.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 指定章节下的文本行，章节不存在时返回空
+    pub fn lines_in(&self, section: &str) -> Vec<&str> {
+        self.entries
+            .iter()
+            .skip_while(|entry| !matches!(entry, ReportEntry::Section(title) if title == section))
+            .skip(1)
+            .take_while(|entry| !matches!(entry, ReportEntry::Section(_)))
+            .filter_map(|entry| match entry {
+                ReportEntry::Line(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Reporter for BufferReporter {
+    fn section(&mut self, title: &str) {
+        self.entries.push(ReportEntry::Section(title.to_string()));
+    }
+
+    fn line(&mut self, text: &str) {
+        self.entries.push(ReportEntry::Line(text.to_string()));
+    }
+
+    fn table(&mut self, headers: &[&str], rows: &[&[&str]]) {
+        self.entries.push(ReportEntry::Table {
+            headers: headers.iter().map(|cell| cell.to_string()).collect(),
+            rows: rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_reporter_groups_lines_by_section() {
+        let mut reporter = BufferReporter::new();
+        reporter.section("第一章");
+        reporter.line("a");
+        reporter.table(&["列"], &[&["值"]]);
+        reporter.line("b");
+        reporter.section("第二章");
+        reporter.line("c");
+
+        assert_eq!(reporter.sections(), vec!["第一章", "第二章"]);
+        assert_eq!(reporter.lines_in("第一章"), vec!["a", "b"]);
+        assert_eq!(reporter.lines_in("第二章"), vec!["c"]);
+        assert!(reporter.lines_in("不存在").is_empty());
+        assert_eq!(
+            reporter.entries()[2],
+            ReportEntry::Table { headers: vec!["列".to_string()], rows: vec![vec!["值".to_string()]] }
+        );
+    }
+
+    #[test]
+    fn test_table_columns_align_with_wide_characters() {
+        let lines = render_table(&["模式", "file"], &[&["网关 (Gateway)", "gateway.rs"]]);
+
+        assert_eq!(lines[0], "┌────────────────┬────────────┐");
+        assert_eq!(lines[1], "│ 模式           │ file       │");
+        assert_eq!(lines[3], "│ 网关 (Gateway) │ gateway.rs │");
+        let widths: Vec<usize> = lines.iter().map(|line| display_width(line)).collect();
+        assert!(widths.iter().all(|&width| width == widths[0]));
+    }
+}
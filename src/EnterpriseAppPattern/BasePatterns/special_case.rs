@@ -15,6 +15,10 @@
 //! - 默认行为实现
 //! - 异常情况的优雅处理
 //! - 减少防御性编程代码
+//!
+//! ## 特殊情况工厂
+//! `CustomerFactory::from_record` 根据查询结果和账户状态集中决定返回真实对象
+//! 还是哪一种特殊情况对象（未知客户、暂停客户、封禁客户、测试客户）
 
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
@@ -62,6 +66,26 @@ pub trait Customer: Send + Sync {
     fn apply_discount(&self, original_price: f64) -> f64;
     fn get_welcome_message(&self) -> String;
     fn is_special_case(&self) -> bool;
+    /// 客户对象的具体类别；默认按 `is_special_case` 区分常规客户与未知客户，
+    /// 其他特殊情况对象应覆盖此方法
+    fn kind(&self) -> CustomerKind {
+        if self.is_special_case() {
+            CustomerKind::Unknown
+        } else {
+            CustomerKind::Regular
+        }
+    }
+}
+
+/// 客户对象类别，区分真实对象和各种特殊情况
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CustomerKind {
+    Regular,
+    Guest,
+    Unknown,
+    Suspended,
+    Banned,
+    Test,
 }
 
 /// 常规客户实现
@@ -187,6 +211,10 @@ impl Customer for NullCustomer {
         "欢迎访问！请登录以享受更多服务。".to_string()
     }
 
+    fn kind(&self) -> CustomerKind {
+        CustomerKind::Guest
+    }
+
     fn is_special_case(&self) -> bool {
         true
     }
@@ -243,6 +271,10 @@ impl Customer for BannedCustomer {
         format!("抱歉，{}，您的账户已被暂停。原因: {}", self.name, self.ban_reason)
     }
 
+    fn kind(&self) -> CustomerKind {
+        CustomerKind::Banned
+    }
+
     fn is_special_case(&self) -> bool {
         true
     }
@@ -297,11 +329,150 @@ impl Customer for TestCustomer {
         "欢迎，测试用户！这是测试环境。".to_string()
     }
 
+    fn kind(&self) -> CustomerKind {
+        CustomerKind::Test
+    }
+
+    fn is_special_case(&self) -> bool {
+        true
+    }
+}
+
+/// 未知客户（特殊情况）- 提供了客户ID但查无此人
+#[derive(Debug)]
+pub struct UnknownCustomer {
+    pub id: String,
+}
+
+impl UnknownCustomer {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl Customer for UnknownCustomer {
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn get_name(&self) -> &str {
+        "未知客户"
+    }
+
+    fn get_email(&self) -> &str {
+        ""
+    }
+
+    fn get_tier(&self) -> CustomerTier {
+        CustomerTier::Unknown
+    }
+
+    fn get_discount_rate(&self) -> f64 {
+        0.0
+    }
+
+    fn get_credit_limit(&self) -> f64 {
+        0.0
+    }
+
+    fn can_purchase(&self, _amount: f64) -> bool {
+        false
+    }
+
+    fn apply_discount(&self, original_price: f64) -> f64 {
+        original_price
+    }
+
+    fn get_welcome_message(&self) -> String {
+        format!("未找到客户 {}，请确认账户信息或重新注册。", self.id)
+    }
+
+    fn is_special_case(&self) -> bool {
+        true
+    }
+}
+
+/// 暂停客户（特殊情况）- 账户暂时冻结，保留等级但不能下单
+#[derive(Debug)]
+pub struct SuspendedCustomer {
+    pub id: String,
+    pub name: String,
+    pub tier: CustomerTier,
+    pub reason: String,
+}
+
+impl SuspendedCustomer {
+    pub fn new(id: String, name: String, tier: CustomerTier, reason: String) -> Self {
+        Self { id, name, tier, reason }
+    }
+}
+
+impl Customer for SuspendedCustomer {
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_email(&self) -> &str {
+        ""
+    }
+
+    fn get_tier(&self) -> CustomerTier {
+        self.tier.clone()
+    }
+
+    fn get_discount_rate(&self) -> f64 {
+        0.0
+    }
+
+    fn get_credit_limit(&self) -> f64 {
+        0.0
+    }
+
+    fn can_purchase(&self, _amount: f64) -> bool {
+        false // 暂停期间不能下单
+    }
+
+    fn apply_discount(&self, original_price: f64) -> f64 {
+        original_price
+    }
+
+    fn get_welcome_message(&self) -> String {
+        format!("{}，您的账户已暂时冻结（{}），恢复后即可继续购物。", self.name, self.reason)
+    }
+
+    fn kind(&self) -> CustomerKind {
+        CustomerKind::Suspended
+    }
+
     fn is_special_case(&self) -> bool {
         true
     }
 }
 
+/// 账户状态
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountStatus {
+    Active,
+    Suspended { reason: String },
+    Banned { reason: String },
+    Test,
+}
+
+/// 从数据源读取的客户记录
+#[derive(Debug, Clone)]
+pub struct CustomerRecord {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub tier: CustomerTier,
+    pub credit_limit: f64,
+    pub status: AccountStatus,
+}
+
 /// 客户工厂 - 创建客户对象，包括特殊情况
 pub struct CustomerFactory;
 
@@ -335,6 +506,24 @@ impl CustomerFactory {
         }
     }
 
+    /// 根据查询结果和账户状态决定返回真实对象还是哪种特殊情况对象
+    ///
+    /// "用哪个特殊情况"的判断集中在这里，调用方只面对 `dyn Customer`
+    pub fn from_record(requested_id: &str, record: Option<CustomerRecord>) -> Box<dyn Customer> {
+        let record = match record {
+            Some(record) => record,
+            None => return Box::new(UnknownCustomer::new(requested_id.to_string())),
+        };
+        match record.status {
+            AccountStatus::Active => Box::new(RegularCustomer::new(
+                record.id, record.name, record.email, record.tier, record.credit_limit,
+            )),
+            AccountStatus::Suspended { reason } => Box::new(SuspendedCustomer::new(record.id, record.name, record.tier, reason)),
+            AccountStatus::Banned { reason } => Box::new(BannedCustomer::new(record.id, record.name, reason)),
+            AccountStatus::Test => Box::new(TestCustomer::new(record.id)),
+        }
+    }
+
     /// 从数据库加载客户（模拟）
     pub fn load_from_database(customer_id: &str) -> Box<dyn Customer> {
         // 模拟数据库查询失败或用户不存在的情况
@@ -656,6 +845,27 @@ pub fn demo() {
                  customer.get_welcome_message());
     }
 
+    // 演示按账户状态集中选择特殊情况
+    println!("\n5. 特殊情况工厂：按账户状态选择对象");
+    let record = |id: &str, status: AccountStatus| CustomerRecord {
+        id: id.to_string(),
+        name: format!("客户{}", id),
+        email: format!("{}@example.com", id),
+        tier: CustomerTier::Gold,
+        credit_limit: 8000.0,
+        status,
+    };
+    let lookups = vec![
+        ("C100", Some(record("C100", AccountStatus::Active))),
+        ("C200", Some(record("C200", AccountStatus::Suspended { reason: "逾期未付款".to_string() }))),
+        ("C300", Some(record("C300", AccountStatus::Banned { reason: "欺诈交易".to_string() }))),
+        ("C404", None),
+    ];
+    for (id, found) in lookups {
+        let customer = CustomerFactory::from_record(id, found);
+        println!("   {:?} {}: 可以购买 ¥100: {}, {}", customer.kind(), id, customer.can_purchase(100.0), customer.get_welcome_message());
+    }
+
     println!("\n=== 特殊情况模式演示完成 ===");
 
     println!("\n💡 特殊情况模式的优势:");
@@ -768,4 +978,61 @@ mod tests {
         assert_eq!(report.summary.regular_customers, 1);
         assert_eq!(report.summary.special_cases, 2);
     }
-} 
\ No newline at end of file
+
+    fn record(status: AccountStatus) -> CustomerRecord {
+        CustomerRecord {
+            id: "C001".to_string(),
+            name: "王五".to_string(),
+            email: "wangwu@example.com".to_string(),
+            tier: CustomerTier::Gold,
+            credit_limit: 5000.0,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_factory_picks_special_case_by_status() {
+        let cases = vec![
+            (Some(record(AccountStatus::Active)), CustomerKind::Regular),
+            (Some(record(AccountStatus::Suspended { reason: "逾期".to_string() })), CustomerKind::Suspended),
+            (Some(record(AccountStatus::Banned { reason: "欺诈".to_string() })), CustomerKind::Banned),
+            (Some(record(AccountStatus::Test)), CustomerKind::Test),
+            (None, CustomerKind::Unknown),
+        ];
+        for (found, expected) in cases {
+            let customer = CustomerFactory::from_record("C001", found);
+            assert_eq!(customer.kind(), expected);
+            assert_eq!(customer.is_special_case(), expected != CustomerKind::Regular);
+            assert_eq!(customer.get_id(), "C001");
+        }
+
+        let suspended = CustomerFactory::from_record("C001", Some(record(AccountStatus::Suspended { reason: "逾期".to_string() })));
+        assert_eq!(suspended.get_tier(), CustomerTier::Gold);
+        assert!(suspended.get_welcome_message().contains("逾期"));
+    }
+
+    #[test]
+    fn test_callers_treat_factory_results_uniformly() {
+        let mut cart = ShoppingCartService::new();
+        cart.add_item(CartItem::new("P001".to_string(), "产品".to_string(), 100.0, 1));
+
+        // 同一段结账代码处理所有对象，不需要判断具体类别
+        let outcomes: Vec<(CustomerKind, bool)> = [
+            Some(record(AccountStatus::Active)),
+            Some(record(AccountStatus::Suspended { reason: "逾期".to_string() })),
+            None,
+        ]
+        .into_iter()
+        .map(|found| {
+            let customer = CustomerFactory::from_record("C001", found);
+            (customer.kind(), cart.checkout(customer.as_ref()).unwrap().success)
+        })
+        .collect();
+
+        assert_eq!(outcomes, vec![
+            (CustomerKind::Regular, true),
+            (CustomerKind::Suspended, false),
+            (CustomerKind::Unknown, false),
+        ]);
+    }
+}
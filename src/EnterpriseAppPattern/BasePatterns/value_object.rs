@@ -13,6 +13,7 @@
  * 3. 没有身份：两个具有相同值的值对象被认为是相等的
  * 4. 替换性：可以用具有相同值的另一个实例来替换
  * 5. 副作用自由：操作不会产生副作用
+ * 6. 批量构造：导入数据时收集所有无效输入及原因，而不是在第一个错误处失败
  * 
  * 适用场景：
  * - 表示度量、数量或描述性的值时
@@ -45,6 +46,26 @@ impl Display for ValueObjectError {
 
 impl Error for ValueObjectError {}
 
+/// 批量构造结果：(有效的值对象, 无效的输入及原因)
+pub type BatchBuild<T, I> = (Vec<T>, Vec<(I, ValueObjectError)>);
+
+/// 批量构造值对象，有效和无效的输入分别收集，保持输入顺序
+pub fn build_batch<I, T, F>(inputs: impl IntoIterator<Item = I>, constructor: F) -> BatchBuild<T, I>
+where
+    I: Clone,
+    F: Fn(I) -> Result<T, ValueObjectError>,
+{
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for input in inputs {
+        match constructor(input.clone()) {
+            Ok(value) => valid.push(value),
+            Err(error) => invalid.push((input, error)),
+        }
+    }
+    (valid, invalid)
+}
+
 /// 货币枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Currency {
@@ -258,7 +279,116 @@ impl Mul<f64> for Money {
     }
 }
 
+/// 日历日期，与DateRange使用的天数互相转换
+///
+/// 天数表示从 1970-01-01（第0天）开始经过的天数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// 按年月日创建日期，只支持1970年及以后
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Result<Self, ValueObjectError> {
+        if year < 1970 || !(1..=12).contains(&month) || day == 0 || day > Self::days_in_month(year, month) {
+            return Err(ValueObjectError::InvalidValue(
+                format!("无效的日期: {}-{}-{}", year, month, day)
+            ));
+        }
+        
+        Ok(Self { year, month, day })
+    }
+    
+    /// 由天数得到日期
+    pub fn from_day_number(day_number: u32) -> Self {
+        // 公历日期换算，以3月为一年的开始使闰日落在年末
+        let days = day_number as i64 + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        
+        Self { year: year as i32, month: month as u32, day: day as u32 }
+    }
+    
+    /// 转换为天数
+    pub fn day_number(&self) -> u32 {
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year / 400;
+        let year_of_era = year - era * 400;
+        let shifted_month = if self.month > 2 { self.month - 3 } else { self.month + 9 } as i64;
+        let day_of_year = (153 * shifted_month + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        (era * 146_097 + day_of_era - 719_468) as u32
+    }
+    
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+    
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+    
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+    
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// 按天遍历DateRange的迭代器，每次只计算下一天，不预先分配存储
+#[derive(Debug, Clone)]
+pub struct DaysIter {
+    next: u32,
+    // 剩余天数，为0时迭代结束
+    remaining: u64,
+}
+
+impl Iterator for DaysIter {
+    type Item = Date;
+    
+    fn next(&mut self) -> Option<Date> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let date = Date::from_day_number(self.next);
+        self.remaining -= 1;
+        // 最后一天可能是u32::MAX，之后不再前进
+        self.next = self.next.saturating_add(1);
+        Some(date)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.remaining).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
 /// DateRange值对象 - 表示日期范围
+///
+/// 区间是闭区间 `[start_date, end_date]`，开始和结束当天都包含在内，
+/// 因此 `[1, 5]` 和 `[6, 9]` 不重叠但相邻，可以合并为 `[1, 9]`。
+/// 日期用天数表示，可以通过 `Date` 与年月日互相转换。
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DateRange {
     start_date: u32,  // 简化表示，使用天数（从某个基准日期开始）
@@ -287,24 +417,51 @@ impl DateRange {
         self.end_date
     }
     
+    /// 由两个日历日期创建日期范围
+    pub fn from_dates(start: Date, end: Date) -> Result<Self, ValueObjectError> {
+        Self::new(start.day_number(), end.day_number())
+    }
+    
     /// 获取天数
     pub fn days(&self) -> u32 {
         self.end_date - self.start_date + 1
     }
     
-    /// 检查是否包含指定日期
+    /// 按天遍历区间内的每一天，包含开始和结束当天
+    ///
+    /// 闭区间至少包含一天；不相交区间的交集为None，
+    /// 对 `a.intersection(&b).into_iter().flat_map(|range| range.iter_days())` 迭代得到空序列。
+    pub fn iter_days(&self) -> DaysIter {
+        DaysIter {
+            next: self.start_date,
+            remaining: u64::from(self.end_date - self.start_date) + 1,
+        }
+    }
+    
+    /// 检查是否包含指定日期，包含开始和结束当天
     pub fn contains(&self, date: u32) -> bool {
         date >= self.start_date && date <= self.end_date
     }
     
-    /// 检查是否与另一个日期范围重叠
-    pub fn overlaps_with(&self, other: &DateRange) -> bool {
+    /// 检查是否与另一个日期范围重叠，至少共有一天才算重叠
+    pub fn overlaps(&self, other: &DateRange) -> bool {
         self.start_date <= other.end_date && self.end_date >= other.start_date
     }
     
-    /// 获取与另一个日期范围的交集
+    /// 检查是否与另一个日期范围重叠，等同于 overlaps
+    pub fn overlaps_with(&self, other: &DateRange) -> bool {
+        self.overlaps(other)
+    }
+    
+    /// 检查两个日期范围是否首尾相接（一个结束的第二天另一个开始）
+    pub fn is_adjacent(&self, other: &DateRange) -> bool {
+        self.end_date.checked_add(1) == Some(other.start_date)
+            || other.end_date.checked_add(1) == Some(self.start_date)
+    }
+    
+    /// 获取与另一个日期范围的交集，不重叠时返回None
     pub fn intersection(&self, other: &DateRange) -> Option<DateRange> {
-        if !self.overlaps_with(other) {
+        if !self.overlaps(other) {
             return None;
         }
         
@@ -314,6 +471,18 @@ impl DateRange {
         DateRange::new(start, end).ok()
     }
     
+    /// 合并两个重叠或相邻的日期范围，中间有空档时返回None
+    pub fn union(&self, other: &DateRange) -> Option<DateRange> {
+        if !self.overlaps(other) && !self.is_adjacent(other) {
+            return None;
+        }
+        
+        Some(DateRange {
+            start_date: self.start_date.min(other.start_date),
+            end_date: self.end_date.max(other.end_date),
+        })
+    }
+    
     /// 扩展日期范围
     pub fn extend(&self, days: u32) -> DateRange {
         DateRange {
@@ -331,6 +500,13 @@ impl Display for DateRange {
 }
 
 /// EmailAddress值对象 - 表示电子邮件地址
+///
+/// 构造时校验：
+/// - 恰好一个 `@`，本地部分和域名都不能为空
+/// - 本地部分最长64个字符，不能以点开头或结尾，不能有连续的点
+/// - 域名至少包含一个点，每个标签由字母、数字和连字符组成，不能以连字符开头或结尾
+///
+/// 国际化域名（如 `例子.com`）暂不支持，只接受ASCII地址；需要时应先转换为Punycode（`xn--` 形式）。
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EmailAddress {
     email: String,
@@ -339,9 +515,9 @@ pub struct EmailAddress {
 impl EmailAddress {
     /// 创建新的邮箱地址
     pub fn new(email: String) -> Result<Self, ValueObjectError> {
-        if !Self::is_valid_email(&email) {
+        if let Err(reason) = Self::validate(&email) {
             return Err(ValueObjectError::InvalidValue(
-                format!("无效的邮箱地址: {}", email)
+                format!("无效的邮箱地址: {} ({})", email, reason)
             ));
         }
         
@@ -350,14 +526,62 @@ impl EmailAddress {
         })
     }
     
-    /// 验证邮箱格式
-    fn is_valid_email(email: &str) -> bool {
-        // 简化的邮箱验证
-        email.contains('@') && 
-        email.len() > 3 && 
-        !email.starts_with('@') && 
-        !email.ends_with('@') &&
-        email.chars().all(|c| c.is_ascii())
+    /// 批量创建邮箱地址，例如导入用户数据
+    pub fn parse_many(inputs: &[&str]) -> BatchBuild<EmailAddress, String> {
+        build_batch(inputs.iter().map(|input| input.to_string()), EmailAddress::new)
+    }
+    
+    /// 验证邮箱格式，返回不合法的原因
+    fn validate(email: &str) -> Result<(), &'static str> {
+        if !email.is_ascii() {
+            return Err("只支持ASCII地址");
+        }
+        let (local, domain) = email.split_once('@').ok_or("缺少@")?;
+        if domain.contains('@') {
+            return Err("包含多个@");
+        }
+        
+        if local.is_empty() {
+            return Err("缺少用户名");
+        }
+        if local.len() > 64 {
+            return Err("用户名超过64个字符");
+        }
+        if local.starts_with('.') || local.ends_with('.') {
+            return Err("用户名不能以点开头或结尾");
+        }
+        if local.contains("..") {
+            return Err("用户名包含连续的点");
+        }
+        if !local.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || "!#$%&'*+/=?^_`{|}~-".contains(c)) {
+            return Err("用户名包含非法字符");
+        }
+        
+        if domain.is_empty() {
+            return Err("缺少域名");
+        }
+        if domain.len() > 253 {
+            return Err("域名超过253个字符");
+        }
+        if !domain.contains('.') {
+            return Err("域名缺少点");
+        }
+        // 标签为空说明域名以点开头、结尾或包含连续的点
+        for label in domain.split('.') {
+            if label.is_empty() {
+                return Err("域名包含空标签");
+            }
+            if label.len() > 63 {
+                return Err("域名标签超过63个字符");
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err("域名标签不能以连字符开头或结尾");
+            }
+            if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err("域名包含非法字符");
+            }
+        }
+        Ok(())
     }
     
     /// 获取邮箱地址
@@ -365,20 +589,30 @@ impl EmailAddress {
         &self.email
     }
     
+    /// 获取本地部分（@之前）
+    pub fn local_part(&self) -> &str {
+        self.email.split_once('@').map_or("", |(local, _)| local)
+    }
+    
     /// 获取域名部分
     pub fn domain(&self) -> &str {
-        self.email.split('@').nth(1).unwrap_or("")
+        self.email.split_once('@').map_or("", |(_, domain)| domain)
     }
     
-    /// 获取用户名部分
+    /// 获取用户名部分，等同于 local_part
     pub fn username(&self) -> &str {
-        self.email.split('@').next().unwrap_or("")
+        self.local_part()
     }
     
     /// 检查是否为指定域名
     pub fn is_domain(&self, domain: &str) -> bool {
         self.domain().eq_ignore_ascii_case(domain)
     }
+    
+    /// 两个地址是否属于同一域名，域名已在构造时标准化为小写
+    pub fn is_same_domain(&self, other: &EmailAddress) -> bool {
+        self.domain() == other.domain()
+    }
 }
 
 impl Display for EmailAddress {
@@ -387,12 +621,49 @@ impl Display for EmailAddress {
     }
 }
 
+/// 目录中的具体产品，用于检验是否满足产品规格
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogProduct {
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+}
+
+impl CatalogProduct {
+    pub fn new(name: &str, version: &str, features: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            features: features.iter().map(|feature| feature.to_string()).collect(),
+        }
+    }
+    
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f.eq_ignore_ascii_case(feature))
+    }
+}
+
 /// 产品规格值对象
+///
+/// 单一规格由名称、最低版本和必需特性组成：产品名称相同（忽略大小写）、
+/// 版本不低于规格版本、且具备全部特性时满足规格。
+/// 规格可以用 `and`、`or`、`not` 组合成新的规格，例如 `a.and(b).or(c.not())`；
+/// 组合规格本身没有名称、版本和特性，对应的访问方法返回空值。
 #[derive(Debug, Clone, PartialEq)]
 pub struct ProductSpecification {
-    name: String,
-    version: String,
-    features: Vec<String>,
+    rule: SpecRule,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SpecRule {
+    Single {
+        name: String,
+        version: String,
+        features: Vec<String>,
+    },
+    And(Box<ProductSpecification>, Box<ProductSpecification>),
+    Or(Box<ProductSpecification>, Box<ProductSpecification>),
+    Not(Box<ProductSpecification>),
 }
 
 impl ProductSpecification {
@@ -405,38 +676,109 @@ impl ProductSpecification {
             return Err(ValueObjectError::InvalidValue("版本号不能为空".to_string()));
         }
         
-        Ok(Self { name, version, features })
+        Ok(Self { rule: SpecRule::Single { name, version, features } })
     }
     
     pub fn name(&self) -> &str {
-        &self.name
+        match &self.rule {
+            SpecRule::Single { name, .. } => name,
+            _ => "",
+        }
     }
     
     pub fn version(&self) -> &str {
-        &self.version
+        match &self.rule {
+            SpecRule::Single { version, .. } => version,
+            _ => "",
+        }
     }
     
     pub fn features(&self) -> &[String] {
-        &self.features
+        match &self.rule {
+            SpecRule::Single { features, .. } => features,
+            _ => &[],
+        }
     }
     
     pub fn has_feature(&self, feature: &str) -> bool {
-        self.features.iter().any(|f| f.eq_ignore_ascii_case(feature))
+        self.features().iter().any(|f| f.eq_ignore_ascii_case(feature))
     }
     
-    /// 创建升级版本
+    /// 是否为组合规格
+    pub fn is_composite(&self) -> bool {
+        !matches!(self.rule, SpecRule::Single { .. })
+    }
+    
+    /// 创建升级版本，组合规格不能升级
     pub fn upgrade(&self, new_version: String, additional_features: Vec<String>) -> Result<Self, ValueObjectError> {
-        let mut new_features = self.features.clone();
+        if self.is_composite() {
+            return Err(ValueObjectError::InvalidValue("组合规格不能升级".to_string()));
+        }
+        
+        let mut new_features = self.features().to_vec();
         new_features.extend(additional_features);
         
-        Self::new(self.name.clone(), new_version, new_features)
+        Self::new(self.name().to_string(), new_version, new_features)
+    }
+    
+    /// 两个规格都满足
+    pub fn and(self, other: ProductSpecification) -> ProductSpecification {
+        Self { rule: SpecRule::And(Box::new(self), Box::new(other)) }
+    }
+    
+    /// 任意一个规格满足
+    pub fn or(self, other: ProductSpecification) -> ProductSpecification {
+        Self { rule: SpecRule::Or(Box::new(self), Box::new(other)) }
+    }
+    
+    /// 不满足该规格
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> ProductSpecification {
+        Self { rule: SpecRule::Not(Box::new(self)) }
+    }
+    
+    /// 检验产品是否满足规格
+    pub fn is_satisfied_by(&self, product: &CatalogProduct) -> bool {
+        match &self.rule {
+            SpecRule::Single { name, version, features } => {
+                product.name.eq_ignore_ascii_case(name)
+                    && compare_versions(&product.version, version) != std::cmp::Ordering::Less
+                    && features.iter().all(|feature| product.has_feature(feature))
+            }
+            SpecRule::And(left, right) => left.is_satisfied_by(product) && right.is_satisfied_by(product),
+            SpecRule::Or(left, right) => left.is_satisfied_by(product) || right.is_satisfied_by(product),
+            SpecRule::Not(inner) => !inner.is_satisfied_by(product),
+        }
     }
 }
 
+/// 按点分隔的数字逐段比较版本号，缺少的段视为0，非数字段按字符串比较
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let a: Vec<&str> = a.trim().split('.').collect();
+    let b: Vec<&str> = b.trim().split('.').collect();
+    for i in 0..a.len().max(b.len()) {
+        let (x, y) = (a.get(i).copied().unwrap_or("0"), b.get(i).copied().unwrap_or("0"));
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != std::cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
 impl Display for ProductSpecification {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} v{} [特性: {}]", 
-               self.name, self.version, self.features.join(", "))
+        match &self.rule {
+            SpecRule::Single { name, version, features } => {
+                write!(f, "{} v{} [特性: {}]", name, version, features.join(", "))
+            }
+            SpecRule::And(left, right) => write!(f, "({} 且 {})", left, right),
+            SpecRule::Or(left, right) => write!(f, "({} 或 {})", left, right),
+            SpecRule::Not(inner) => write!(f, "非 {}", inner),
+        }
     }
 }
 
@@ -514,6 +856,15 @@ pub fn demo() {
         println!("范围1与范围2的交集: {}", intersection);
     }
     
+    if let Some(union) = range1.union(&range2) {
+        println!("范围1与范围2的并集: {}", union);
+    }
+    println!("范围1与范围3能否合并: {}", range1.union(&range3).is_some());
+    
+    let month_end = DateRange::from_dates(Date::from_ymd(2024, 2, 27).unwrap(), Date::from_ymd(2024, 3, 2).unwrap()).unwrap();
+    let days: Vec<String> = month_end.iter_days().map(|date| date.to_string()).collect();
+    println!("跨月按天遍历: {}", days.join(", "));
+    
     let extended_range = range1.extend(5);
     println!("范围1扩展5天后: {}", extended_range);
     
@@ -537,6 +888,13 @@ pub fn demo() {
         Err(e) => println!("预期错误: {}", e),
     }
     
+    // 批量导入：收集所有无效输入而不是遇到第一个就失败
+    let (valid, invalid) = EmailAddress::parse_many(&["a@example.com", "bad-email", "B@Example.com", "@nobody"]);
+    println!("批量导入: {} 个有效, {} 个无效", valid.len(), invalid.len());
+    for (input, error) in &invalid {
+        println!("  跳过 {}: {}", input, error);
+    }
+    
     println!("{}", "=".repeat(50));
     
     // 4. ProductSpecification值对象演示
@@ -561,6 +919,15 @@ pub fn demo() {
         Err(e) => println!("升级失败: {}", e),
     }
     
+    // 组合规格
+    let phone_with_nfc = spec1.clone()
+        .and(ProductSpecification::new("智能手机".to_string(), "1.0".to_string(), vec!["NFC".to_string()]).unwrap());
+    let legacy_tablet = ProductSpecification::new("平板".to_string(), "3.0".to_string(), vec![]).unwrap().not();
+    let wanted = phone_with_nfc.or(legacy_tablet);
+    println!("组合规格: {}", wanted);
+    let phone = CatalogProduct::new("智能手机", "2.1", &["GPS", "蓝牙", "WiFi", "NFC"]);
+    println!("{} v{} 是否满足: {}", phone.name, phone.version, wanted.is_satisfied_by(&phone));
+    
     println!("{}", "=".repeat(50));
     
     // 5. 值对象相等性演示
@@ -594,4 +961,217 @@ pub fn demo() {
     println!("3. 需要确保数据完整性时");
     println!("4. 作为实体对象的属性时");
     println!("5. 需要进行值比较而不是引用比较时");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_many_partitions_mixed_emails() {
+        let (valid, invalid) = EmailAddress::parse_many(&[
+            "alice@example.com",
+            "not-an-email",
+            "Bob@Company.COM",
+            "@missing-user",
+            "carol@例子.com",
+        ]);
+
+        assert_eq!(valid.iter().map(EmailAddress::as_str).collect::<Vec<_>>(), vec!["alice@example.com", "bob@company.com"]);
+        let inputs: Vec<&str> = invalid.iter().map(|(input, _)| input.as_str()).collect();
+        assert_eq!(inputs, vec!["not-an-email", "@missing-user", "carol@例子.com"]);
+        // 每个无效输入都带有原因
+        for (input, error) in &invalid {
+            assert!(matches!(error, ValueObjectError::InvalidValue(msg) if msg.contains(input.as_str())));
+        }
+    }
+
+    #[test]
+    fn test_build_batch_with_other_value_objects() {
+        let (valid, invalid) = build_batch(vec![(1, 5), (9, 3), (2, 2)], |(start, end)| DateRange::new(start, end));
+        assert_eq!(valid, vec![DateRange::new(1, 5).unwrap(), DateRange::new(2, 2).unwrap()]);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0, (9, 3));
+
+        let (valid, invalid) = build_batch(Vec::<String>::new(), EmailAddress::new);
+        assert!(valid.is_empty() && invalid.is_empty());
+    }
+
+    #[test]
+    fn test_email_accepts_typical_addresses() {
+        let email = EmailAddress::new("John.Doe+news@Mail.Example.COM".to_string()).unwrap();
+        assert_eq!(email.local_part(), "john.doe+news");
+        assert_eq!(email.domain(), "mail.example.com");
+        assert_eq!(email.username(), email.local_part());
+
+        for valid in ["a@b.co", "user_name-1@sub-domain.example.org", "o'brien@example.ie", "x@xn--fsqu00a.com"] {
+            assert!(EmailAddress::new(valid.to_string()).is_ok(), "{}", valid);
+        }
+
+        let other = EmailAddress::new("admin@MAIL.example.com".to_string()).unwrap();
+        assert!(email.is_same_domain(&other));
+        assert!(!email.is_same_domain(&EmailAddress::new("admin@example.com".to_string()).unwrap()));
+    }
+
+    #[test]
+    fn test_email_rejects_malformed_addresses() {
+        let invalid = [
+            ("plainaddress", "缺少@"),
+            ("a@b@example.com", "包含多个@"),
+            ("@example.com", "缺少用户名"),
+            ("user@", "缺少域名"),
+            (".user@example.com", "用户名不能以点开头或结尾"),
+            ("user.@example.com", "用户名不能以点开头或结尾"),
+            ("us..er@example.com", "用户名包含连续的点"),
+            ("us er@example.com", "用户名包含非法字符"),
+            ("user@localhost", "域名缺少点"),
+            ("user@.example.com", "域名包含空标签"),
+            ("user@example.com.", "域名包含空标签"),
+            ("user@example..com", "域名包含空标签"),
+            ("user@-example.com", "域名标签不能以连字符开头或结尾"),
+            ("user@exa_mple.com", "域名包含非法字符"),
+            ("用户@example.com", "只支持ASCII地址"),
+        ];
+        for (input, reason) in invalid {
+            match EmailAddress::new(input.to_string()) {
+                Err(ValueObjectError::InvalidValue(msg)) => assert!(msg.contains(reason), "{}: {}", input, msg),
+                other => panic!("{} 应该无效: {:?}", input, other),
+            }
+        }
+        let long_local = format!("{}@example.com", "a".repeat(65));
+        assert!(EmailAddress::new(long_local).is_err());
+    }
+
+    #[test]
+    fn test_date_range_adjacent_ranges() {
+        let first = DateRange::new(1, 5).unwrap();
+        let second = DateRange::new(6, 9).unwrap();
+
+        // 闭区间：首尾相接但没有共同的一天
+        assert!(!first.overlaps(&second));
+        assert!(first.is_adjacent(&second) && second.is_adjacent(&first));
+        assert_eq!(first.intersection(&second), None);
+        assert_eq!(first.union(&second), Some(DateRange::new(1, 9).unwrap()));
+        assert_eq!(second.union(&first), first.union(&second));
+
+        // 共享端点时重叠一天
+        let touching = DateRange::new(5, 7).unwrap();
+        assert!(first.overlaps(&touching));
+        assert_eq!(first.intersection(&touching), Some(DateRange::new(5, 5).unwrap()));
+        assert!(first.contains(1) && first.contains(5) && !first.contains(6));
+    }
+
+    #[test]
+    fn test_date_range_contained_and_disjoint_ranges() {
+        let outer = DateRange::new(1, 30).unwrap();
+        let inner = DateRange::new(10, 12).unwrap();
+        assert!(outer.overlaps(&inner) && inner.overlaps(&outer));
+        assert_eq!(outer.intersection(&inner), Some(inner));
+        assert_eq!(inner.union(&outer), Some(outer));
+
+        let left = DateRange::new(1, 5).unwrap();
+        let right = DateRange::new(8, 9).unwrap();
+        assert!(!left.overlaps(&right) && !left.is_adjacent(&right));
+        assert_eq!(left.intersection(&right), None);
+        assert_eq!(left.union(&right), None);
+        assert!(!left.contains(7));
+
+        // 边界值不会溢出
+        let max = DateRange::new(u32::MAX, u32::MAX).unwrap();
+        assert!(!max.is_adjacent(&DateRange::new(0, 0).unwrap()));
+    }
+
+    fn ymd(year: i32, month: u32, day: u32) -> Date {
+        Date::from_ymd(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_date_day_number_round_trip() {
+        assert_eq!(Date::from_day_number(0), ymd(1970, 1, 1));
+        assert_eq!(ymd(2000, 3, 1).day_number(), 11_017);
+        for day_number in [0, 58, 59, 365, 10_956, 11_016, 19_782, 47_540] {
+            assert_eq!(Date::from_day_number(day_number).day_number(), day_number);
+        }
+        assert!(Date::from_ymd(2023, 2, 29).is_err());
+        assert!(Date::from_ymd(2024, 13, 1).is_err());
+        assert_eq!(ymd(2024, 2, 29).to_string(), "2024-02-29");
+    }
+
+    #[test]
+    fn test_iter_days_crosses_month_and_year_boundaries() {
+        let dates = |start: Date, end: Date| -> Vec<String> {
+            DateRange::from_dates(start, end).unwrap().iter_days().map(|date| date.to_string()).collect()
+        };
+
+        assert_eq!(dates(ymd(2024, 1, 30), ymd(2024, 2, 2)), vec!["2024-01-30", "2024-01-31", "2024-02-01", "2024-02-02"]);
+        // 闰年二月有29天，平年28天
+        assert_eq!(dates(ymd(2024, 2, 27), ymd(2024, 3, 1)), vec!["2024-02-27", "2024-02-28", "2024-02-29", "2024-03-01"]);
+        assert_eq!(dates(ymd(2023, 2, 27), ymd(2023, 3, 1)), vec!["2023-02-27", "2023-02-28", "2023-03-01"]);
+        assert_eq!(dates(ymd(2023, 12, 31), ymd(2024, 1, 1)), vec!["2023-12-31", "2024-01-01"]);
+
+        let year = DateRange::from_dates(ymd(2024, 1, 1), ymd(2024, 12, 31)).unwrap();
+        assert_eq!(year.iter_days().size_hint(), (366, Some(366)));
+        assert_eq!(year.iter_days().count() as u32, year.days());
+        assert_eq!(year.iter_days().last(), Some(ymd(2024, 12, 31)));
+
+        // 不相交区间的交集为空，按天遍历得到空序列
+        let march = DateRange::from_dates(ymd(2024, 3, 1), ymd(2024, 3, 31)).unwrap();
+        let april = DateRange::from_dates(ymd(2024, 4, 1), ymd(2024, 4, 30)).unwrap();
+        assert_eq!(march.intersection(&april).into_iter().flat_map(|range| range.iter_days()).count(), 0);
+
+        // 最大范围不会溢出，迭代是惰性的
+        let huge = DateRange::new(u32::MAX - 1, u32::MAX).unwrap();
+        assert_eq!(huge.iter_days().count(), 2);
+    }
+
+    fn spec(name: &str, version: &str, features: &[&str]) -> ProductSpecification {
+        ProductSpecification::new(name.to_string(), version.to_string(), features.iter().map(|f| f.to_string()).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_single_specification_matches_name_version_and_features() {
+        let gps_phone = spec("智能手机", "1.2", &["GPS"]);
+        assert!(!gps_phone.is_composite());
+        assert_eq!((gps_phone.name(), gps_phone.version()), ("智能手机", "1.2"));
+
+        assert!(gps_phone.is_satisfied_by(&CatalogProduct::new("智能手机", "1.10", &["gps", "WiFi"])));
+        assert!(gps_phone.is_satisfied_by(&CatalogProduct::new("智能手机", "1.2.0", &["GPS"])));
+        // 版本过低、缺少特性、名称不同
+        assert!(!gps_phone.is_satisfied_by(&CatalogProduct::new("智能手机", "1.1.9", &["GPS"])));
+        assert!(!gps_phone.is_satisfied_by(&CatalogProduct::new("智能手机", "2.0", &["WiFi"])));
+        assert!(!gps_phone.is_satisfied_by(&CatalogProduct::new("平板", "2.0", &["GPS"])));
+    }
+
+    #[test]
+    fn test_combined_specifications() {
+        let phone = spec("智能手机", "1.0", &[]);
+        let nfc = spec("智能手机", "1.0", &["NFC"]);
+        let old_tablet = spec("平板", "1.0", &[]).and(spec("平板", "3.0", &[]).not());
+
+        // (手机 且 NFC) 或 非(平板v3及以上)
+        let wanted = phone.clone().and(nfc.clone()).or(spec("平板", "3.0", &[]).not());
+        assert!(wanted.is_composite());
+        assert_eq!((wanted.name(), wanted.version(), wanted.features().len()), ("", "", 0));
+
+        let nfc_phone = CatalogProduct::new("智能手机", "2.0", &["NFC"]);
+        let plain_phone = CatalogProduct::new("智能手机", "2.0", &["GPS"]);
+        let new_tablet = CatalogProduct::new("平板", "3.1", &[]);
+        let legacy_tablet = CatalogProduct::new("平板", "2.5", &[]);
+
+        assert!(wanted.is_satisfied_by(&nfc_phone));
+        // 普通手机不满足左侧，但它不是v3平板，满足右侧的not
+        assert!(wanted.is_satisfied_by(&plain_phone));
+        assert!(!wanted.is_satisfied_by(&new_tablet));
+        assert!(wanted.is_satisfied_by(&legacy_tablet));
+
+        assert!(old_tablet.is_satisfied_by(&legacy_tablet));
+        assert!(!old_tablet.is_satisfied_by(&new_tablet));
+        assert!(!phone.clone().and(nfc.clone()).is_satisfied_by(&plain_phone));
+        assert!(phone.clone().or(nfc).is_satisfied_by(&plain_phone));
+        assert!(!phone.clone().not().not().is_satisfied_by(&new_tablet));
+
+        assert_eq!(phone.clone().and(spec("平板", "1.0", &[]).not()).to_string(),
+                   "(智能手机 v1.0 [特性: ] 且 非 平板 v1.0 [特性: ])");
+        assert!(matches!(phone.not().upgrade("2.0".to_string(), vec![]), Err(ValueObjectError::InvalidValue(_))));
+    }
+}
@@ -1,6 +1,7 @@
 //! 活动记录模式 (Active Record)
 //! 
 //! 将数据访问逻辑嵌入到领域对象中
+//! 通过 setter 修改属性时记录字段变更事件，保存后可查询本次保存的变更，用于审计和历史记录
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/EnterpriseAppPattern/DataSourceArchitecturalPatterns/active_record.rs
 
 use std::collections::HashMap;
@@ -22,6 +23,14 @@ impl fmt::Display for ActiveRecordError {
     }
 }
 
+/// 字段变更事件，新旧值以字符串形式记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChanged {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
 // 使用线程安全的全局存储
 static USER_STORAGE: OnceLock<Mutex<HashMap<u32, User>>> = OnceLock::new();
 static NEXT_ID: OnceLock<Mutex<u32>> = OnceLock::new();
@@ -42,9 +51,14 @@ fn get_next_id() -> u32 {
 #[derive(Debug, Clone)]
 pub struct User {
     pub id: Option<u32>,
-    pub username: String,
-    pub email: String,
-    pub balance: f64,
+    // 字段只能通过 setter 修改，保证每次变更都被记录
+    username: String,
+    email: String,
+    balance: f64,
+    // 尚未保存的字段变更
+    pending_changes: Vec<FieldChanged>,
+    // 最近一次保存提交的字段变更
+    saved_changes: Vec<FieldChanged>,
 }
 
 impl User {
@@ -54,9 +68,53 @@ impl User {
             username,
             email,
             balance: 0.0,
+            pending_changes: Vec::new(),
+            saved_changes: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    fn balance(&self) -> f64 {
+        self.balance
+    }
+
+    // 属性修改：只有值真正改变时才记录变更事件
+    pub fn set_username(&mut self, username: String) {
+        if self.username != username {
+            let old = std::mem::replace(&mut self.username, username);
+            self.record_change("username", old, self.username.clone());
+        }
+    }
+
+    pub fn set_email(&mut self, email: String) {
+        if self.email != email {
+            let old = std::mem::replace(&mut self.email, email);
+            self.record_change("email", old, self.email.clone());
+        }
+    }
+
+    pub fn set_balance(&mut self, balance: f64) {
+        if self.balance != balance {
+            let old = std::mem::replace(&mut self.balance, balance);
+            self.record_change("balance", format!("{:.2}", old), format!("{:.2}", balance));
         }
     }
 
+    fn record_change(&mut self, field: &'static str, old: String, new: String) {
+        self.pending_changes.push(FieldChanged { field, old, new });
+    }
+
+    /// 是否有尚未保存的变更
+    #[cfg(test)]
+    fn is_dirty(&self) -> bool {
+        !self.pending_changes.is_empty()
+    }
+
+    /// 最近一次保存提交的变更
+    pub fn saved_changes(&self) -> &[FieldChanged] {
+        &self.saved_changes
+    }
+
     // 保存到数据库
     pub fn save(&mut self) -> Result<(), ActiveRecordError> {
         if self.username.is_empty() {
@@ -66,6 +124,7 @@ impl User {
         let storage = get_storage();
         let mut storage_guard = storage.lock().unwrap();
 
+        self.saved_changes = std::mem::take(&mut self.pending_changes);
         match self.id {
             Some(id) => {
                 storage_guard.insert(id, self.clone());
@@ -102,7 +161,7 @@ impl User {
         if amount <= 0.0 {
             return Err(ActiveRecordError::ValidationError("金额必须大于0".to_string()));
         }
-        self.balance += amount;
+        self.set_balance(self.balance + amount);
         self.save()?;
         println!("用户 {} 存款 {:.2}，余额: {:.2}", self.username, amount, self.balance);
         Ok(())
@@ -115,7 +174,7 @@ impl User {
         if self.balance < amount {
             return Err(ActiveRecordError::ValidationError("余额不足".to_string()));
         }
-        self.balance -= amount;
+        self.set_balance(self.balance - amount);
         self.save()?;
         println!("用户 {} 取款 {:.2}，余额: {:.2}", self.username, amount, self.balance);
         Ok(())
@@ -151,6 +210,15 @@ pub fn demo() {
         println!("找到用户: {}", found_user);
     }
 
+    println!("\n4. 字段变更事件:");
+    user2.set_username("李四".to_string()); // 值未改变，不产生事件
+    user2.set_email("lisi@example.com".to_string());
+    user2.set_balance(50.0);
+    user2.save().ok();
+    for change in user2.saved_changes() {
+        println!("  {}: {} -> {}", change.field, change.old, change.new);
+    }
+
     let all_users = User::find_all();
     println!("所有用户:");
     for user in &all_users {
@@ -162,4 +230,47 @@ pub fn demo() {
     println!("2. 简单直观，易于理解");
     println!("3. 包含业务逻辑和验证");
     println!("4. 需要快速开发原型");
+    println!("5. 字段变更事件便于构建审计和历史记录");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changing_two_fields_emits_two_events() {
+        let mut user = User::new("王五".to_string(), "wangwu@example.com".to_string());
+        user.save().unwrap();
+        assert!(user.saved_changes().is_empty());
+
+        user.set_email("wangwu@company.com".to_string());
+        user.set_balance(10.0);
+        // 设置为当前值不产生事件
+        user.set_username("王五".to_string());
+        user.set_balance(10.0);
+        assert!(user.is_dirty());
+        user.save().unwrap();
+
+        assert!(!user.is_dirty());
+        assert_eq!(user.saved_changes(), &[
+            FieldChanged { field: "email", old: "wangwu@example.com".to_string(), new: "wangwu@company.com".to_string() },
+            FieldChanged { field: "balance", old: "0.00".to_string(), new: "10.00".to_string() },
+        ]);
+
+        // 没有变更的保存会清空上一次的变更记录
+        user.save().unwrap();
+        assert!(user.saved_changes().is_empty());
+    }
+
+    #[test]
+    fn test_business_methods_record_balance_changes() {
+        let mut user = User::new("赵六".to_string(), "zhaoliu@example.com".to_string());
+        user.deposit(100.0).unwrap();
+        assert_eq!(user.saved_changes(), &[
+            FieldChanged { field: "balance", old: "0.00".to_string(), new: "100.00".to_string() },
+        ]);
+        assert!(user.withdraw(500.0).is_err());
+        assert!(!user.is_dirty());
+        assert_eq!(user.balance(), 100.0);
+    }
 }
\ No newline at end of file
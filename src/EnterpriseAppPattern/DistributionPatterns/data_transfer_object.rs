@@ -26,11 +26,21 @@
  * - 跨网络的数据传输
  * - API设计
  * - 微服务架构
+ * 
+ * 可插拔传输层：
+ * DtoTransport 把请求DTO送到服务端处理函数并带回响应DTO。进程内传输直接调用，
+ * 模拟网络传输把两个方向的DTO都序列化为JSON并加入延迟，调用方代码无需改动。
  */
 
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
 /// DTO错误类型
 #[derive(Debug)]
@@ -197,7 +207,7 @@ impl AddressDto {
 }
 
 /// 用户摘要DTO（用于列表显示，减少数据传输）
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserSummaryDto {
     pub id: Option<u32>,
     pub username: String,
@@ -556,6 +566,101 @@ impl DtoSerializer {
     }
 }
 
+// =================
+// 可插拔传输层
+// =================
+
+/// DTO传输层 - 把请求DTO送到服务端并带回响应DTO
+///
+/// 与 `sim_network::Transport` 的单向消息投递不同，这里是一次同步的请求/响应往返。
+pub trait DtoTransport<Req, Resp> {
+    fn send(&self, request: Req) -> Result<Resp, DtoError>;
+}
+
+/// 进程内传输 - 直接调用服务端处理函数，不做序列化
+pub struct InProcessTransport<H> {
+    handler: Mutex<H>,
+}
+
+impl<H> InProcessTransport<H> {
+    pub fn new(handler: H) -> Self {
+        Self { handler: Mutex::new(handler) }
+    }
+}
+
+impl<Req, Resp, H> DtoTransport<Req, Resp> for InProcessTransport<H>
+where
+    H: FnMut(Req) -> Resp,
+{
+    fn send(&self, request: Req) -> Result<Resp, DtoError> {
+        let mut handler = self.handler.lock().unwrap();
+        Ok((*handler)(request))
+    }
+}
+
+/// 模拟网络传输的统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkTransportStats {
+    pub round_trips: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+/// 模拟网络传输 - 请求和响应都序列化为JSON，单程加入固定延迟
+///
+/// 服务端只能看到反序列化后的请求，客户端只能拿到反序列化后的响应，
+/// 无法序列化的DTO会以 `DtoError::SerializationError` 暴露出来。
+pub struct SimulatedNetworkTransport<H> {
+    handler: Mutex<H>,
+    latency: Duration,
+    stats: Mutex<NetworkTransportStats>,
+}
+
+impl<H> SimulatedNetworkTransport<H> {
+    pub fn new(handler: H, latency: Duration) -> Self {
+        Self {
+            handler: Mutex::new(handler),
+            latency,
+            stats: Mutex::new(NetworkTransportStats::default()),
+        }
+    }
+
+    pub fn stats(&self) -> NetworkTransportStats {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+impl<Req, Resp, H> DtoTransport<Req, Resp> for SimulatedNetworkTransport<H>
+where
+    Req: Serialize + DeserializeOwned,
+    Resp: Serialize + DeserializeOwned,
+    H: FnMut(Req) -> Resp,
+{
+    fn send(&self, request: Req) -> Result<Resp, DtoError> {
+        let serialization_error = |e: serde_json::Error| DtoError::SerializationError(e.to_string());
+
+        // 客户端 -> 服务端
+        let request_bytes = serde_json::to_vec(&request).map_err(serialization_error)?;
+        thread::sleep(self.latency);
+        let server_request: Req = serde_json::from_slice(&request_bytes).map_err(serialization_error)?;
+
+        let server_response = {
+            let mut handler = self.handler.lock().unwrap();
+            (*handler)(server_request)
+        };
+
+        // 服务端 -> 客户端
+        let response_bytes = serde_json::to_vec(&server_response).map_err(serialization_error)?;
+        thread::sleep(self.latency);
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.round_trips += 1;
+        stats.bytes_sent += request_bytes.len() as u64;
+        stats.bytes_received += response_bytes.len() as u64;
+        serde_json::from_slice(&response_bytes).map_err(serialization_error)
+    }
+}
+
 /// 性能监控DTO
 #[derive(Debug, Clone)]
 pub struct PerformanceMetricsDto {
@@ -857,6 +962,23 @@ pub fn demo() {
     println!("  网络传输时间: {} ms", metrics.network_transfer_time_ms);
     println!("  传输效率分数: {:.1}", metrics.calculate_efficiency());
     
+    println!("{}", "=".repeat(50));
+    
+    // 9. 可插拔传输层
+    println!("9. 可插拔传输层:");
+    
+    let directory = vec![user_dto.to_summary()];
+    let lookup = |directory: Vec<UserSummaryDto>| {
+        move |username: String| directory.iter().find(|user| user.username == username).cloned()
+    };
+    let local = InProcessTransport::new(lookup(directory.clone()));
+    let remote = SimulatedNetworkTransport::new(lookup(directory), Duration::from_millis(5));
+    let local_result = local.send("alice123".to_string());
+    let remote_result = remote.send("alice123".to_string());
+    
+    println!("  进程内传输与模拟网络传输结果一致: {}", local_result.ok() == remote_result.ok());
+    println!("  网络统计: {:?}", remote.stats());
+    
     println!("\n=== Data Transfer Object模式演示完成 ===");
     
     // 输出模式总结
@@ -888,4 +1010,46 @@ pub fn demo() {
     println!("3. 最小化：只包含必要的数据，避免冗余");
     println!("4. 稳定性：接口稳定，支持版本演化");
     println!("5. 可测试性：易于创建测试数据和验证");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(username: &str) -> UserSummaryDto {
+        UserDto::new(username.to_string(), format!("{}@example.com", username), username.to_uppercase()).to_summary()
+    }
+
+    fn directory_handler() -> impl FnMut(String) -> Option<UserSummaryDto> {
+        let directory = [summary("alice"), summary("bob")];
+        move |username| directory.iter().find(|user| user.username == username).cloned()
+    }
+
+    #[test]
+    fn test_same_results_over_in_process_and_network_transport() {
+        let local = InProcessTransport::new(directory_handler());
+        let remote = SimulatedNetworkTransport::new(directory_handler(), Duration::from_millis(1));
+
+        for username in ["alice", "bob", "carol"] {
+            let local_result = local.send(username.to_string()).unwrap();
+            let remote_result = remote.send(username.to_string()).unwrap();
+            assert_eq!(local_result, remote_result);
+        }
+        assert_eq!(remote.send("bob".to_string()).unwrap(), Some(summary("bob")));
+
+        let stats = remote.stats();
+        assert_eq!(stats.round_trips, 4);
+        assert!(stats.bytes_sent > 0 && stats.bytes_received > 0);
+    }
+
+    #[test]
+    fn test_network_transport_reports_unserializable_response() {
+        // JSON对象的键只能是字符串，元组键的映射无法跨越网络
+        let handler = |_: u32| HashMap::from([((1u32, 2u32), 3u32)]);
+        assert!(InProcessTransport::new(handler).send(0).is_ok());
+
+        let remote = SimulatedNetworkTransport::new(handler, Duration::ZERO);
+        assert!(matches!(remote.send(0), Err(DtoError::SerializationError(_))));
+        assert_eq!(remote.stats().round_trips, 0);
+    }
+}
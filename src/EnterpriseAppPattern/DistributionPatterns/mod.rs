@@ -11,7 +11,9 @@
  * 这些模式主要解决分布式系统中的性能和通信问题。
  */
 
+#[cfg(feature = "serde")]
 pub mod data_transfer_object;
+#[cfg(feature = "serde")]
 pub mod remote_facade;
 
 /// 演示所有分布式模式
@@ -27,6 +29,7 @@ pub fn demo_all() {
     // 1. Data Transfer Object 演示
     println!("\n🚀 1. Data Transfer Object（数据传输对象）模式演示");
     println!("适合：分布式系统的数据传输，减少网络往返次数");
+    #[cfg(feature = "serde")]
     data_transfer_object::demo();
     
     println!("\n{}", "=".repeat(80));
@@ -34,6 +37,7 @@ pub fn demo_all() {
     // 2. Remote Facade 演示
     println!("\n🚀 2. Remote Facade（远程外观）模式演示");
     println!("适合：分布式系统的粗粒度接口设计");
+    #[cfg(feature = "serde")]
     remote_facade::demo();
     
     println!("\n{}", "=".repeat(80));
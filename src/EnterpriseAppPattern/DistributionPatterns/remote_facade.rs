@@ -19,13 +19,24 @@
 //! - 需要远程调用的系统
 //! - 网络延迟敏感的应用
 //! - 需要批量处理的场景
+//!
+//! ## 可插拔传输层
+//! 外观操作被封装为可序列化的请求/响应消息，通过 DTO 模块的 `DtoTransport` 发送：
+//! 进程内传输直接调用外观，模拟网络传输会把DTO序列化为JSON并加入延迟，
+//! 同一份客户端代码既可以在本地运行，也可以"远程"运行。
 
 use std::collections::HashMap;
 use std::fmt;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::data_transfer_object::{DtoTransport, InProcessTransport, SimulatedNetworkTransport};
+
+use crate::DistributedSystemMode::ResiliencePatterns::retry::is_safe_to_retry;
 
 /// 远程外观错误
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RemoteFacadeError {
     NetworkError(String),
     ServiceUnavailable(String),
@@ -49,7 +60,7 @@ impl fmt::Display for RemoteFacadeError {
 impl std::error::Error for RemoteFacadeError {}
 
 /// 客户端信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Customer {
     pub id: u32,
     pub name: String,
@@ -73,7 +84,7 @@ impl Customer {
 }
 
 /// VIP等级
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VipLevel {
     Regular,
     Silver,
@@ -131,7 +142,7 @@ impl OrderItem {
 }
 
 /// 订单状态
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderStatus {
     Created,
     Paid,
@@ -165,7 +176,7 @@ impl Payment {
 }
 
 /// 支付方式
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PaymentMethod {
     CreditCard(String), // 卡号后4位
     Alipay,
@@ -418,7 +429,7 @@ impl InventoryService {
 }
 
 /// 客户订单汇总
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CustomerOrdersSummary {
     pub customer_id: u32,
     pub total_orders: u32,
@@ -427,7 +438,7 @@ pub struct CustomerOrdersSummary {
 }
 
 /// 订单创建请求
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateOrderRequest {
     pub customer_id: u32,
     pub items: Vec<OrderItemRequest>,
@@ -435,7 +446,7 @@ pub struct CreateOrderRequest {
 }
 
 /// 订单项请求
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateOrderItemRequest {
     pub product_id: u32,
     pub product_name: String,
@@ -447,7 +458,7 @@ pub struct CreateOrderItemRequest {
 pub type OrderItemRequest = CreateOrderItemRequest;
 
 /// 订单创建响应
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateOrderResponse {
     pub order_id: u32,
     pub total_amount: f64,
@@ -457,7 +468,7 @@ pub struct CreateOrderResponse {
 }
 
 /// 客户详情响应
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CustomerDetailResponse {
     pub customer: Customer,
     pub orders_summary: CustomerOrdersSummary,
@@ -629,6 +640,120 @@ impl ECommerceRemoteFacade {
     }
 }
 
+// =================
+// 可插拔传输层
+// =================
+
+/// 外观请求 - 每个粗粒度操作对应一种请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FacadeRequest {
+    CreateCompleteOrder(CreateOrderRequest),
+    GetCustomerDetail { customer_id: u32 },
+    CheckBulkInventory { items: Vec<(u32, u32)> },
+    ProcessOrderStatusChange { order_id: u32, new_status: OrderStatus },
+}
+
+impl FacadeRequest {
+    /// 请求对应的HTTP方法，只读操作为GET
+    pub fn http_method(&self) -> &'static str {
+        match self {
+            FacadeRequest::CreateCompleteOrder(_) => "POST",
+            FacadeRequest::GetCustomerDetail { .. } | FacadeRequest::CheckBulkInventory { .. } => "GET",
+            FacadeRequest::ProcessOrderStatusChange { .. } => "PUT",
+        }
+    }
+
+    /// 传输失败后是否可以安全地重发，创建订单不带幂等键，重发会产生重复订单
+    pub fn is_safe_to_retry(&self) -> bool {
+        is_safe_to_retry(self.http_method(), false)
+    }
+}
+
+/// 外观响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FacadeResponse {
+    OrderCreated(CreateOrderResponse),
+    CustomerDetail(CustomerDetailResponse),
+    InventoryStatus(HashMap<u32, bool>),
+    Notification(String),
+    Failed(RemoteFacadeError),
+}
+
+/// 把请求分派给外观的对应操作，服务端入口
+pub fn dispatch(facade: &mut ECommerceRemoteFacade, request: FacadeRequest) -> FacadeResponse {
+    let result = match request {
+        FacadeRequest::CreateCompleteOrder(request) => facade.create_complete_order(request).map(FacadeResponse::OrderCreated),
+        FacadeRequest::GetCustomerDetail { customer_id } => facade.get_customer_detail(customer_id).map(FacadeResponse::CustomerDetail),
+        FacadeRequest::CheckBulkInventory { items } => Ok(FacadeResponse::InventoryStatus(facade.check_bulk_inventory(items))),
+        FacadeRequest::ProcessOrderStatusChange { order_id, new_status } => {
+            facade.process_order_status_change(order_id, new_status).map(FacadeResponse::Notification)
+        }
+    };
+    result.unwrap_or_else(FacadeResponse::Failed)
+}
+
+/// 服务端处理函数 - 拥有外观，把每个请求分派给它，可交给任意 `DtoTransport`
+pub fn facade_handler(mut facade: ECommerceRemoteFacade) -> impl FnMut(FacadeRequest) -> FacadeResponse {
+    move |request| dispatch(&mut facade, request)
+}
+
+/// 外观客户端 - 提供与外观相同的类型化接口，与具体传输无关
+pub struct RemoteFacadeClient<T: DtoTransport<FacadeRequest, FacadeResponse>> {
+    transport: T,
+}
+
+impl<T: DtoTransport<FacadeRequest, FacadeResponse>> RemoteFacadeClient<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn create_complete_order(&self, request: CreateOrderRequest) -> Result<CreateOrderResponse, RemoteFacadeError> {
+        match self.send(FacadeRequest::CreateCompleteOrder(request)) {
+            FacadeResponse::OrderCreated(response) => Ok(response),
+            other => Err(Self::unexpected(other)),
+        }
+    }
+
+    pub fn get_customer_detail(&self, customer_id: u32) -> Result<CustomerDetailResponse, RemoteFacadeError> {
+        match self.send(FacadeRequest::GetCustomerDetail { customer_id }) {
+            FacadeResponse::CustomerDetail(response) => Ok(response),
+            other => Err(Self::unexpected(other)),
+        }
+    }
+
+    pub fn check_bulk_inventory(&self, items: Vec<(u32, u32)>) -> Result<HashMap<u32, bool>, RemoteFacadeError> {
+        match self.send(FacadeRequest::CheckBulkInventory { items }) {
+            FacadeResponse::InventoryStatus(results) => Ok(results),
+            other => Err(Self::unexpected(other)),
+        }
+    }
+
+    pub fn process_order_status_change(&self, order_id: u32, new_status: OrderStatus) -> Result<String, RemoteFacadeError> {
+        match self.send(FacadeRequest::ProcessOrderStatusChange { order_id, new_status }) {
+            FacadeResponse::Notification(notification) => Ok(notification),
+            other => Err(Self::unexpected(other)),
+        }
+    }
+
+    /// 传输层的错误（如序列化失败）作为网络错误返回
+    fn send(&self, request: FacadeRequest) -> FacadeResponse {
+        self.transport
+            .send(request)
+            .unwrap_or_else(|e| FacadeResponse::Failed(RemoteFacadeError::NetworkError(e.to_string())))
+    }
+
+    fn unexpected(response: FacadeResponse) -> RemoteFacadeError {
+        match response {
+            FacadeResponse::Failed(error) => error,
+            other => RemoteFacadeError::NetworkError(format!("意外的响应类型: {:?}", other)),
+        }
+    }
+}
+
 /// 演示远程外观模式
 pub fn demo() {
     println!("=== 远程外观模式演示 ===\n");
@@ -755,6 +880,17 @@ pub fn demo() {
         Err(e) => println!("     ✅ 正确处理错误: {}", e),
     }
 
+    println!("\n7. 可插拔传输层");
+    let local = RemoteFacadeClient::new(InProcessTransport::new(facade_handler(ECommerceRemoteFacade::new())));
+    let remote = RemoteFacadeClient::new(SimulatedNetworkTransport::new(
+        facade_handler(ECommerceRemoteFacade::new()),
+        Duration::from_millis(5),
+    ));
+    let local_detail = local.get_customer_detail(2);
+    let remote_detail = remote.get_customer_detail(2);
+    println!("   进程内传输与模拟网络传输结果一致: {}", local_detail == remote_detail);
+    println!("   网络统计: {:?}", remote.transport().stats());
+
     println!("\n=== 远程外观模式演示完成 ===");
 
     println!("\n💡 远程外观模式的优势:");
@@ -853,4 +989,75 @@ mod tests {
         let result = service.confirm_reservation(1, 10);
         assert!(result.is_ok());
     }
+
+    fn sample_order() -> CreateOrderRequest {
+        CreateOrderRequest {
+            customer_id: 1,
+            items: vec![
+                OrderItemRequest { product_id: 1, product_name: "MacBook Pro".to_string(), price: 12999.0, quantity: 1 },
+                OrderItemRequest { product_id: 2, product_name: "Magic Mouse".to_string(), price: 699.0, quantity: 2 },
+            ],
+            payment_method: PaymentMethod::CreditCard("****1234".to_string()),
+        }
+    }
+
+    /// 一组外观操作各自的结果
+    type OperationResults = (
+        Result<CreateOrderResponse, RemoteFacadeError>,
+        Result<CustomerDetailResponse, RemoteFacadeError>,
+        Result<HashMap<u32, bool>, RemoteFacadeError>,
+        Result<String, RemoteFacadeError>,
+        Result<CustomerDetailResponse, RemoteFacadeError>,
+    );
+
+    /// 在给定传输上执行同一组外观操作
+    fn run_operations<T: DtoTransport<FacadeRequest, FacadeResponse>>(client: &RemoteFacadeClient<T>) -> OperationResults {
+        (
+            client.create_complete_order(sample_order()),
+            client.get_customer_detail(1),
+            client.check_bulk_inventory(vec![(1, 5), (2, 300)]),
+            client.process_order_status_change(1, OrderStatus::Shipped),
+            client.get_customer_detail(999),
+        )
+    }
+
+    #[test]
+    fn test_same_results_over_in_process_and_network_transport() {
+        let local = RemoteFacadeClient::new(InProcessTransport::new(facade_handler(ECommerceRemoteFacade::new())));
+        let remote = RemoteFacadeClient::new(SimulatedNetworkTransport::new(
+            facade_handler(ECommerceRemoteFacade::new()),
+            Duration::from_millis(1),
+        ));
+
+        let local_results = run_operations(&local);
+        let remote_results = run_operations(&remote);
+        assert_eq!(local_results, remote_results);
+        assert_eq!(local_results.0.unwrap().confirmation_number, "ORD00000001");
+        // 错误同样经过序列化传回客户端
+        assert_eq!(remote_results.4, Err(RemoteFacadeError::ValidationError("客户不存在".to_string())));
+
+        // 每个操作都是一次序列化往返
+        let stats = remote.transport().stats();
+        assert_eq!(stats.round_trips, 5);
+        assert!(stats.bytes_sent > 0 && stats.bytes_received > 0);
+    }
+
+    #[test]
+    fn test_only_create_order_is_unsafe_to_retry() {
+        assert!(!FacadeRequest::CreateCompleteOrder(sample_order()).is_safe_to_retry());
+        assert!(FacadeRequest::GetCustomerDetail { customer_id: 1 }.is_safe_to_retry());
+        assert!(FacadeRequest::ProcessOrderStatusChange { order_id: 1, new_status: OrderStatus::Shipped }.is_safe_to_retry());
+    }
+
+    #[test]
+    fn test_facade_messages_survive_json_round_trip() {
+        let request = FacadeRequest::CreateCompleteOrder(sample_order());
+        let decoded: FacadeRequest = serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        assert_eq!(decoded, request);
+
+        let mut facade = ECommerceRemoteFacade::new();
+        let response = dispatch(&mut facade, FacadeRequest::CheckBulkInventory { items: vec![(1, 5), (3, 1)] });
+        let decoded: FacadeResponse = serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+        assert_eq!(decoded, response);
+    }
 } 
\ No newline at end of file
@@ -28,7 +28,7 @@
  * - 需要对外提供Web服务
  */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::rc::Rc;
@@ -43,6 +43,8 @@ pub enum ServiceError {
     AuthorizationError(String),
     TransactionError(String),
     ExternalServiceError(String),
+    /// 乐观锁冲突：保存时实体已被其他会话修改
+    ConcurrencyError(String),
 }
 
 impl Display for ServiceError {
@@ -54,6 +56,7 @@ impl Display for ServiceError {
             ServiceError::AuthorizationError(msg) => write!(f, "授权错误: {}", msg),
             ServiceError::TransactionError(msg) => write!(f, "事务错误: {}", msg),
             ServiceError::ExternalServiceError(msg) => write!(f, "外部服务错误: {}", msg),
+            ServiceError::ConcurrencyError(msg) => write!(f, "并发冲突: {}", msg),
         }
     }
 }
@@ -69,6 +72,8 @@ pub struct User {
     pub balance: f64,
     pub status: UserStatus,
     pub level: UserLevel,
+    /// 乐观锁版本号，未保存时为0，每次保存加1
+    pub version: u64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -97,6 +102,7 @@ impl User {
             balance: 0.0,
             status: UserStatus::Active,
             level: UserLevel::Bronze,
+            version: 0,
         }
     }
     
@@ -132,6 +138,8 @@ pub struct Order {
     pub status: OrderStatus,
     pub items: Vec<OrderItem>,
     pub created_at: String, // 简化为字符串
+    /// 乐观锁版本号，未保存时为0，每次保存加1
+    pub version: u64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -163,6 +171,7 @@ impl Order {
             status: OrderStatus::Pending,
             items,
             created_at: "2024-01-01".to_string(), // 简化
+            version: 0,
         }
     }
     
@@ -283,9 +292,51 @@ impl<T> ServiceResponse<T> {
             errors,
         }
     }
+    
+    /// 转换成功响应中的数据，失败响应原样传播
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ServiceResponse<U> {
+        ServiceResponse {
+            success: self.success,
+            data: self.data.map(f),
+            message: self.message,
+            errors: self.errors,
+        }
+    }
+    
+    /// 成功时继续执行返回响应的下一步操作，失败响应原样传播
+    ///
+    /// 没有数据的响应视为失败。
+    pub fn and_then<U>(self, f: impl FnOnce(T) -> ServiceResponse<U>) -> ServiceResponse<U> {
+        match self.data {
+            Some(data) if self.success => f(data),
+            _ => ServiceResponse::error(self.message, self.errors),
+        }
+    }
+    
+    /// 转换为 `Result`，便于配合 `?` 使用；没有错误明细的失败以消息作为错误
+    pub fn into_result(self) -> Result<T, Vec<String>> {
+        match self.data {
+            Some(data) if self.success => Ok(data),
+            _ if self.errors.is_empty() => Err(vec![self.message]),
+            _ => Err(self.errors),
+        }
+    }
+}
+
+/// 事务开始时的数据快照，回滚时恢复
+struct RepositorySnapshot {
+    users: HashMap<u32, User>,
+    orders: HashMap<u32, Order>,
+    payments: HashMap<u32, Payment>,
 }
 
 /// 仓储接口的简单实现（模拟数据库）
+///
+/// `begin` 保存数据快照，`rollback` 恢复快照，`commit` 丢弃快照；
+/// 与数据库序列一样，回滚不会收回已分配的编号。
+///
+/// 用户和订单使用乐观锁：保存时实体的版本号必须与存储中的一致，
+/// 否则说明读取之后已被其他会话修改，返回 `ConcurrencyError`。
 #[derive(Clone)]
 pub struct MockRepository {
     users: Rc<RefCell<HashMap<u32, User>>>,
@@ -294,6 +345,9 @@ pub struct MockRepository {
     next_user_id: Rc<RefCell<u32>>,
     next_order_id: Rc<RefCell<u32>>,
     next_payment_id: Rc<RefCell<u32>>,
+    transaction: Rc<RefCell<Option<RepositorySnapshot>>>,
+    /// 模拟写入故障的用户编号
+    failing_user_ids: Rc<RefCell<HashSet<u32>>>,
 }
 
 impl MockRepository {
@@ -305,10 +359,53 @@ impl MockRepository {
             next_user_id: Rc::new(RefCell::new(1)),
             next_order_id: Rc::new(RefCell::new(1)),
             next_payment_id: Rc::new(RefCell::new(1)),
+            transaction: Rc::new(RefCell::new(None)),
+            failing_user_ids: Rc::new(RefCell::new(HashSet::new())),
+        }
+    }
+    
+    /// 开始事务，不支持嵌套事务
+    pub fn begin(&self) -> Result<(), ServiceError> {
+        let mut transaction = self.transaction.borrow_mut();
+        if transaction.is_some() {
+            return Err(ServiceError::TransactionError("事务已开始".to_string()));
         }
+        *transaction = Some(RepositorySnapshot {
+            users: self.users.borrow().clone(),
+            orders: self.orders.borrow().clone(),
+            payments: self.payments.borrow().clone(),
+        });
+        Ok(())
+    }
+    
+    /// 提交事务，保留事务中的所有修改
+    pub fn commit(&self) -> Result<(), ServiceError> {
+        self.transaction.borrow_mut().take()
+            .map(|_| ())
+            .ok_or_else(|| ServiceError::TransactionError("没有进行中的事务".to_string()))
+    }
+    
+    /// 回滚事务，撤销事务开始后的所有修改
+    pub fn rollback(&self) -> Result<(), ServiceError> {
+        let snapshot = self.transaction.borrow_mut().take()
+            .ok_or_else(|| ServiceError::TransactionError("没有进行中的事务".to_string()))?;
+        *self.users.borrow_mut() = snapshot.users;
+        *self.orders.borrow_mut() = snapshot.orders;
+        *self.payments.borrow_mut() = snapshot.payments;
+        Ok(())
     }
     
-    pub fn save_user(&self, mut user: User) -> User {
+    /// 模拟数据库故障：之后保存该用户都会失败
+    pub fn simulate_write_failure(&self, user_id: u32) {
+        self.failing_user_ids.borrow_mut().insert(user_id);
+    }
+    
+    pub fn save_user(&self, mut user: User) -> Result<User, ServiceError> {
+        if let Some(id) = user.id {
+            if self.failing_user_ids.borrow().contains(&id) {
+                return Err(ServiceError::TransactionError(format!("保存用户 {} 失败", id)));
+            }
+        }
         if user.id.is_none() {
             let id = *self.next_user_id.borrow();
             user.id = Some(id);
@@ -316,8 +413,16 @@ impl MockRepository {
         }
         
         let user_id = user.id.unwrap();
-        self.users.borrow_mut().insert(user_id, user.clone());
-        user
+        let mut users = self.users.borrow_mut();
+        let stored_version = users.get(&user_id).map_or(0, |stored| stored.version);
+        if stored_version != user.version {
+            return Err(ServiceError::ConcurrencyError(format!(
+                "用户 {} 的期望版本为 {}, 但实际版本为 {}", user_id, user.version, stored_version
+            )));
+        }
+        user.version += 1;
+        users.insert(user_id, user.clone());
+        Ok(user)
     }
     
     pub fn find_user(&self, id: u32) -> Option<User> {
@@ -330,7 +435,7 @@ impl MockRepository {
             .cloned()
     }
     
-    pub fn save_order(&self, mut order: Order) -> Order {
+    pub fn save_order(&self, mut order: Order) -> Result<Order, ServiceError> {
         if order.id.is_none() {
             let id = *self.next_order_id.borrow();
             order.id = Some(id);
@@ -338,8 +443,16 @@ impl MockRepository {
         }
         
         let order_id = order.id.unwrap();
-        self.orders.borrow_mut().insert(order_id, order.clone());
-        order
+        let mut orders = self.orders.borrow_mut();
+        let stored_version = orders.get(&order_id).map_or(0, |stored| stored.version);
+        if stored_version != order.version {
+            return Err(ServiceError::ConcurrencyError(format!(
+                "订单 {} 的期望版本为 {}, 但实际版本为 {}", order_id, order.version, stored_version
+            )));
+        }
+        order.version += 1;
+        orders.insert(order_id, order.clone());
+        Ok(order)
     }
     
     pub fn find_order(&self, id: u32) -> Option<Order> {
@@ -372,6 +485,72 @@ impl MockRepository {
     }
 }
 
+/// 库存服务（内存实现），按商品编号记录可售数量
+///
+/// 创建订单时扣减库存，取消订单时回补；克隆共享同一份库存。
+#[derive(Clone, Default)]
+pub struct InventoryService {
+    stock: Rc<RefCell<HashMap<u32, u32>>>,
+}
+
+impl InventoryService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 设置商品的可售数量
+    pub fn set_stock(&self, product_id: u32, quantity: u32) {
+        self.stock.borrow_mut().insert(product_id, quantity);
+    }
+    
+    pub fn stock_of(&self, product_id: u32) -> u32 {
+        self.stock.borrow().get(&product_id).copied().unwrap_or(0)
+    }
+    
+    /// 校验并扣减订单项的库存，任何商品不足时不扣减任何库存
+    ///
+    /// 同一商品出现在多个订单项中时按总数量校验。
+    pub fn reserve(&self, items: &[OrderItem]) -> Result<(), Vec<String>> {
+        let requested = Self::quantities(items);
+        let mut stock = self.stock.borrow_mut();
+        let mut reported = HashSet::new();
+        let errors: Vec<String> = items.iter()
+            .filter(|item| reported.insert(item.product_id))
+            .filter_map(|item| {
+                let wanted = requested[&item.product_id];
+                let available = stock.get(&item.product_id).copied().unwrap_or(0);
+                (wanted > available).then(|| format!("{} 库存不足：需要 {}，剩余 {}", item.product_name, wanted, available))
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        for (product_id, quantity) in requested {
+            *stock.entry(product_id).or_insert(0) -= quantity;
+        }
+        Ok(())
+    }
+    
+    /// 回补订单项占用的库存
+    pub fn release(&self, items: &[OrderItem]) {
+        let mut stock = self.stock.borrow_mut();
+        for (product_id, quantity) in Self::quantities(items) {
+            let available = stock.entry(product_id).or_insert(0);
+            *available = available.saturating_add(quantity);
+        }
+    }
+    
+    /// 按商品汇总数量；总数超过 u32 时取饱和值，校验时必然超出库存而被拒绝
+    fn quantities(items: &[OrderItem]) -> HashMap<u32, u32> {
+        let mut quantities = HashMap::new();
+        for item in items {
+            let quantity = quantities.entry(item.product_id).or_insert(0u32);
+            *quantity = quantity.saturating_add(item.quantity);
+        }
+        quantities
+    }
+}
+
 /// 通知服务接口
 pub trait NotificationService {
     fn send_notification(&self, notification: Notification) -> Result<(), ServiceError>;
@@ -454,7 +633,7 @@ impl UserService {
         }
         
         // 保存用户
-        let saved_user = self.repository.save_user(user);
+        let saved_user = self.repository.save_user(user)?;
         
         // 发送欢迎通知
         let notification = Notification {
@@ -526,9 +705,16 @@ impl UserService {
         from_user.update_level_by_balance();
         to_user.update_level_by_balance();
         
-        // 保存更改
-        self.repository.save_user(from_user.clone());
-        self.repository.save_user(to_user.clone());
+        // 扣款和到账在同一事务中保存，任何一步失败都回滚
+        self.repository.begin()?;
+        let saved = self.repository.save_user(from_user.clone())
+            .and_then(|_| self.repository.save_user(to_user.clone()));
+        if let Err(e) = saved {
+            self.repository.rollback()?;
+            println!("转账事务回滚: {}", e);
+            return Err(e);
+        }
+        self.repository.commit()?;
         
         // 发送通知
         let from_notification = Notification {
@@ -592,7 +778,7 @@ impl UserService {
         user.balance += amount;
         user.update_level_by_balance();
         
-        let updated_user = self.repository.save_user(user);
+        let updated_user = self.repository.save_user(user)?;
         
         Ok(ServiceResponse::success(
             updated_user,
@@ -602,9 +788,12 @@ impl UserService {
 }
 
 /// 订单服务层
+///
+/// 库存在创建订单时扣减，订单取消时回补；支付不改变库存。
 pub struct OrderService {
     repository: MockRepository,
     user_service: UserService,
+    inventory: InventoryService,
     notification_service: Box<dyn NotificationService>,
 }
 
@@ -612,11 +801,13 @@ impl OrderService {
     pub fn new(
         repository: MockRepository, 
         user_service: UserService,
+        inventory: InventoryService,
         notification_service: Box<dyn NotificationService>
     ) -> Self {
         Self {
             repository,
             user_service,
+            inventory,
             notification_service,
         }
     }
@@ -657,9 +848,20 @@ impl OrderService {
             })
             .collect();
         
-        // 创建订单
+        // 校验并扣减库存
+        if let Err(errors) = self.inventory.reserve(&order_items) {
+            return Ok(ServiceResponse::error("创建订单失败".to_string(), errors));
+        }
+        
+        // 创建订单，保存失败时回补库存
         let order = Order::new(request.user_id, order_items);
-        let saved_order = self.repository.save_order(order);
+        let saved_order = match self.repository.save_order(order.clone()) {
+            Ok(saved_order) => saved_order,
+            Err(e) => {
+                self.inventory.release(&order.items);
+                return Err(e);
+            }
+        };
         
         // 发送订单确认通知
         let notification = Notification {
@@ -697,20 +899,18 @@ impl OrderService {
             ));
         }
         
+        // 库存已在创建订单时扣减，支付失败的订单仍为待支付，库存保留到订单取消
         // 根据支付方式处理
         let payment_result = match request.payment_method {
             PaymentMethod::Balance => {
                 // 余额支付
-                match self.user_service.update_balance(order.user_id, -order.amount) {
-                    Ok(response) => {
-                        if response.success {
-                            PaymentStatus::Completed
-                        } else {
-                            return Ok(ServiceResponse::error(
-                                "支付处理失败".to_string(),
-                                response.errors
-                            ));
-                        }
+                match self.user_service.update_balance(order.user_id, -order.amount).map(ServiceResponse::into_result) {
+                    Ok(Ok(_)) => PaymentStatus::Completed,
+                    Ok(Err(errors)) => {
+                        return Ok(ServiceResponse::error(
+                            "支付处理失败".to_string(),
+                            errors
+                        ));
                     }
                     Err(_) => PaymentStatus::Failed,
                 }
@@ -737,7 +937,7 @@ impl OrderService {
         if saved_payment.status == PaymentStatus::Completed {
             order.status = OrderStatus::Confirmed;
             let user_id = order.user_id; // 保存user_id，因为order将被移动
-            self.repository.save_order(order);
+            self.repository.save_order(order)?;
             
             // 发送支付成功通知
             let notification = Notification {
@@ -798,9 +998,10 @@ impl OrderService {
             }
         }
         
-        // 更新订单状态
+        // 更新订单状态，保存成功后回补库存
         order.status = OrderStatus::Cancelled;
-        self.repository.save_order(order);
+        let cancelled = self.repository.save_order(order)?;
+        self.inventory.release(&cancelled.items);
         
         Ok(ServiceResponse::success(
             format!("订单 #{} 已成功取消", order_id),
@@ -831,7 +1032,10 @@ pub fn demo() {
     
     let notification_service2 = Box::new(MockNotificationService);
     let user_service_for_order = UserService::new(repository.clone(), Box::new(MockNotificationService));
-    let order_service = OrderService::new(repository.clone(), user_service_for_order, notification_service2);
+    let inventory = InventoryService::new();
+    inventory.set_stock(1, 10);
+    inventory.set_stock(2, 50);
+    let order_service = OrderService::new(repository.clone(), user_service_for_order, inventory.clone(), notification_service2);
     
     println!("服务层初始化完成");
     
@@ -1047,6 +1251,62 @@ pub fn demo() {
         Err(e) => println!("转账错误: {}", e),
     }
     
+    // 组合子：失败响应经过 map/and_then 原样传播
+    if let Ok(response) = user_service.get_user_info(999) {
+        let level = response
+            .and_then(|user| if user.is_active() {
+                ServiceResponse::success(user, "用户可用".to_string())
+            } else {
+                ServiceResponse::error("用户不可用".to_string(), vec!["用户状态异常".to_string()])
+            })
+            .map(|user| user.level);
+        println!("✅ 查询不存在用户的等级: {:?}", level.into_result());
+    }
+    
+    // 超出库存的订单被拒绝，库存不变
+    let oversell_order = CreateOrderRequest {
+        user_id: 1,
+        items: vec![CreateOrderItem {
+            product_id: 1,
+            product_name: "智能手机".to_string(),
+            quantity: 100,
+            unit_price: 2999.0,
+        }],
+    };
+    match order_service.create_order(oversell_order) {
+        Ok(response) => {
+            if !response.success {
+                println!("✅ 正确拒绝超卖订单: {:?}, 剩余库存: {}", response.errors, inventory.stock_of(1));
+            }
+        }
+        Err(e) => println!("创建订单错误: {}", e),
+    }
+    
+    // 到账保存失败时整笔转账回滚
+    repository.simulate_write_failure(1);
+    let failing_transfer = TransferRequest {
+        from_user_id: 2,
+        to_user_id: 1,
+        amount: 10.0,
+        description: None,
+    };
+    let balance_before = repository.find_user(2).map(|user| user.balance);
+    match user_service.transfer_money(failing_transfer) {
+        Ok(response) => println!("意外结果: {}", response.message),
+        Err(e) => println!("✅ 转账失败并回滚: {}, 转出方余额未变: {}", e,
+                           repository.find_user(2).map(|user| user.balance) == balance_before),
+    }
+    
+    // 两个会话基于同一版本修改用户，后提交的被乐观锁拒绝
+    if let (Some(mut first), Some(mut second)) = (repository.find_user(2), repository.find_user(2)) {
+        first.email = "first@example.com".to_string();
+        second.email = "second@example.com".to_string();
+        let _ = repository.save_user(first);
+        if let Err(e) = repository.save_user(second) {
+            println!("✅ 正确拒绝过期修改: {}", e);
+        }
+    }
+    
     println!("\n=== Service Layer模式演示完成 ===");
     
     // 输出模式总结
@@ -1071,4 +1331,206 @@ pub fn demo() {
     println!("3. 多种客户端（Web、API、移动端）");
     println!("4. 需要对外提供服务接口");
     println!("5. 企业级应用开发");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service_with_users() -> (MockRepository, UserService, u32, u32) {
+        let repository = MockRepository::new();
+        let service = UserService::new(repository.clone(), Box::new(MockNotificationService));
+        let create = |username: &str, balance: f64| {
+            service.create_user(CreateUserRequest {
+                username: username.to_string(),
+                email: format!("{}@example.com", username),
+                initial_balance: Some(balance),
+            }).unwrap().data.unwrap().id.unwrap()
+        };
+        let alice = create("alice", 500.0);
+        let bob = create("bob", 100.0);
+        (repository, service, alice, bob)
+    }
+
+    fn transfer(from_user_id: u32, to_user_id: u32, amount: f64) -> TransferRequest {
+        TransferRequest { from_user_id, to_user_id, amount, description: None }
+    }
+
+    fn balance(repository: &MockRepository, user_id: u32) -> f64 {
+        repository.find_user(user_id).unwrap().balance
+    }
+
+    #[test]
+    fn test_transfer_rolls_back_when_second_save_fails() {
+        let (repository, service, alice, bob) = service_with_users();
+        // 扣款保存成功，到账保存失败
+        repository.simulate_write_failure(bob);
+
+        let result = service.transfer_money(transfer(alice, bob, 200.0));
+
+        assert!(matches!(result, Err(ServiceError::TransactionError(_))));
+        assert_eq!(balance(&repository, alice), 500.0);
+        assert_eq!(balance(&repository, bob), 100.0);
+        // 事务已结束，可以开始新的事务
+        assert!(repository.begin().is_ok());
+        assert!(repository.rollback().is_ok());
+    }
+
+    #[test]
+    fn test_transfer_commits_both_saves() {
+        let (repository, service, alice, bob) = service_with_users();
+
+        let response = service.transfer_money(transfer(alice, bob, 200.0)).unwrap();
+
+        assert!(response.success);
+        assert_eq!(balance(&repository, alice), 300.0);
+        assert_eq!(balance(&repository, bob), 300.0);
+        assert!(matches!(repository.commit(), Err(ServiceError::TransactionError(_))));
+    }
+
+    #[test]
+    fn test_stale_save_is_rejected_by_optimistic_lock() {
+        let (repository, _service, alice, _bob) = service_with_users();
+
+        // 两个会话读取到同一版本
+        let mut first = repository.find_user(alice).unwrap();
+        let mut second = repository.find_user(alice).unwrap();
+        assert_eq!((first.version, second.version), (1, 1));
+
+        first.email = "alice@first.example.com".to_string();
+        let saved = repository.save_user(first).unwrap();
+        assert_eq!(saved.version, 2);
+
+        // 后提交的会话基于旧版本修改，保存失败且不覆盖前者的修改
+        second.balance = 0.0;
+        assert!(matches!(repository.save_user(second), Err(ServiceError::ConcurrencyError(_))));
+        let stored = repository.find_user(alice).unwrap();
+        assert_eq!((stored.version, stored.balance, stored.email.as_str()), (2, 500.0, "alice@first.example.com"));
+
+        // 订单同样受版本号保护
+        let order = repository.save_order(Order::new(alice, Vec::new())).unwrap();
+        let stale = order.clone();
+        repository.save_order(order).unwrap();
+        assert!(matches!(repository.save_order(stale), Err(ServiceError::ConcurrencyError(_))));
+    }
+
+    fn user_name_and_balance(service: &UserService, user_id: u32) -> Result<(String, f64), Vec<String>> {
+        let user = service.get_user_info(user_id).unwrap().into_result()?;
+        let balance = service.get_user_info(user_id).unwrap().map(|user| user.balance).into_result()?;
+        Ok((user.username, balance))
+    }
+
+    #[test]
+    fn test_service_response_combinators_propagate_success() {
+        let (_repository, service, alice, _bob) = service_with_users();
+
+        let response = service.get_user_info(alice).unwrap()
+            .map(|user| user.balance)
+            .and_then(|balance| ServiceResponse::success(balance * 2.0, "翻倍".to_string()));
+        assert!(response.success);
+        assert_eq!(response.message, "翻倍");
+        assert_eq!(response.into_result(), Ok(1000.0));
+
+        assert_eq!(user_name_and_balance(&service, alice), Ok(("alice".to_string(), 500.0)));
+    }
+
+    #[test]
+    fn test_service_response_combinators_propagate_failure() {
+        let (_repository, service, alice, _bob) = service_with_users();
+        let mut called = false;
+
+        let response = service.get_user_info(999).unwrap()
+            .map(|user| user.balance)
+            .and_then(|balance| {
+                called = true;
+                ServiceResponse::success(balance, "不会执行".to_string())
+            });
+        assert!(!called);
+        assert!(!response.success && response.data.is_none());
+        assert_eq!(response.message, "获取用户信息失败");
+        assert_eq!(response.into_result(), Err(vec!["用户不存在".to_string()]));
+        assert_eq!(user_name_and_balance(&service, 999), Err(vec!["用户不存在".to_string()]));
+
+        // 成功响应经 and_then 变为失败后，后续的 map 不再执行
+        let response = service.get_user_info(alice).unwrap()
+            .and_then(|_| ServiceResponse::<f64>::error("余额冻结".to_string(), Vec::new()))
+            .map(|balance| balance + 1.0);
+        assert_eq!(response.into_result(), Err(vec!["余额冻结".to_string()]));
+    }
+
+    fn order_service(repository: &MockRepository, inventory: &InventoryService) -> OrderService {
+        let user_service = UserService::new(repository.clone(), Box::new(MockNotificationService));
+        OrderService::new(repository.clone(), user_service, inventory.clone(), Box::new(MockNotificationService))
+    }
+
+    fn order_request(user_id: u32, items: &[(u32, u32)]) -> CreateOrderRequest {
+        CreateOrderRequest {
+            user_id,
+            items: items.iter()
+                .map(|&(product_id, quantity)| CreateOrderItem {
+                    product_id,
+                    product_name: format!("商品{}", product_id),
+                    quantity,
+                    unit_price: 10.0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_create_order_rejects_oversell_without_deducting() {
+        let (repository, _service, alice, bob) = service_with_users();
+        let inventory = InventoryService::new();
+        inventory.set_stock(1, 5);
+        inventory.set_stock(2, 1);
+        let orders = order_service(&repository, &inventory);
+
+        assert!(orders.create_order(order_request(alice, &[(1, 3)])).unwrap().success);
+        assert_eq!(inventory.stock_of(1), 2);
+
+        // 第二个订单超出剩余库存，整单拒绝，其他商品也不扣减
+        let oversell = orders.create_order(order_request(bob, &[(2, 1), (1, 3)])).unwrap();
+        assert!(!oversell.success);
+        assert_eq!(oversell.errors, vec!["商品1 库存不足：需要 3，剩余 2".to_string()]);
+        assert_eq!((inventory.stock_of(1), inventory.stock_of(2)), (2, 1));
+
+        // 同一商品拆成多个订单项时按总数量校验
+        assert!(!orders.create_order(order_request(bob, &[(1, 1), (1, 2)])).unwrap().success);
+        assert!(!orders.create_order(order_request(bob, &[(3, 1)])).unwrap().success);
+        assert_eq!(inventory.stock_of(1), 2);
+
+        // 数量之和超出 u32 范围时不会溢出，同样按库存不足拒绝
+        let overflow = orders.create_order(order_request(bob, &[(1, u32::MAX), (1, 1)])).unwrap();
+        assert!(!overflow.success);
+        assert_eq!(overflow.errors, vec![format!("商品1 库存不足：需要 {}，剩余 2", u32::MAX)]);
+        assert_eq!(inventory.stock_of(1), 2);
+        assert_eq!(repository.find_orders_by_user(bob).len(), 0);
+    }
+
+    #[test]
+    fn test_cancel_order_restores_stock_after_payment() {
+        let (repository, _service, alice, _bob) = service_with_users();
+        let inventory = InventoryService::new();
+        inventory.set_stock(1, 4);
+        let orders = order_service(&repository, &inventory);
+
+        let unpaid = orders.create_order(order_request(alice, &[(1, 1)])).unwrap().data.unwrap().id.unwrap();
+        let paid = orders.create_order(order_request(alice, &[(1, 3)])).unwrap().data.unwrap().id.unwrap();
+        assert_eq!(inventory.stock_of(1), 0);
+
+        // 支付不改变库存
+        let payment = orders.process_payment(ProcessPaymentRequest { order_id: paid, payment_method: PaymentMethod::Balance }).unwrap();
+        assert!(payment.success);
+        assert_eq!(inventory.stock_of(1), 0);
+
+        assert!(orders.cancel_order(paid, alice).unwrap().success);
+        assert_eq!(inventory.stock_of(1), 3);
+        assert_eq!(balance(&repository, alice), 500.0);
+        assert!(orders.cancel_order(unpaid, alice).unwrap().success);
+        assert_eq!(inventory.stock_of(1), 4);
+
+        // 已取消的订单不能再次取消，库存不会重复回补
+        assert!(!orders.cancel_order(unpaid, alice).unwrap().success);
+        assert_eq!(inventory.stock_of(1), 4);
+    }
+}
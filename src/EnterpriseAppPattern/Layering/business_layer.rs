@@ -282,6 +282,312 @@ impl BusinessRuleEngine {
     }
 }
 
+// =================
+// 声明式规则DSL
+// =================
+//
+// 规则以数据的形式表达，可以在运行时加载：
+//
+//     when amount > 10000 and role == "guest" then violation "访客单笔订单不能超过10000"
+//
+// - 条件为真时规则被违反，返回违规信息
+// - 比较运算符：== != > >= < <=，数字和字符串都支持
+// - and 的优先级高于 or，可以用括号分组
+
+/// 规则上下文中的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleValue {
+    Number(f64),
+    Text(String),
+}
+
+impl From<f64> for RuleValue {
+    fn from(value: f64) -> Self {
+        RuleValue::Number(value)
+    }
+}
+
+impl From<&str> for RuleValue {
+    fn from(value: &str) -> Self {
+        RuleValue::Text(value.to_string())
+    }
+}
+
+/// 规则求值的上下文：字段名到值的映射
+pub type RuleContext = HashMap<String, RuleValue>;
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    fn apply<T: PartialOrd>(self, left: &T, right: &T) -> bool {
+        match self {
+            CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+        }
+    }
+}
+
+/// 规则条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleCondition {
+    Compare { field: String, op: CompareOp, value: RuleValue },
+    And(Box<RuleCondition>, Box<RuleCondition>),
+    Or(Box<RuleCondition>, Box<RuleCondition>),
+}
+
+impl RuleCondition {
+    /// 对上下文求值，字段缺失或类型不匹配时返回验证错误
+    pub fn evaluate(&self, context: &RuleContext) -> Result<bool, BusinessError> {
+        match self {
+            RuleCondition::Compare { field, op, value } => {
+                let actual = context.get(field).ok_or_else(|| {
+                    BusinessError::ValidationError(format!("规则上下文缺少字段: {}", field))
+                })?;
+                match (actual, value) {
+                    (RuleValue::Number(left), RuleValue::Number(right)) => Ok(op.apply(left, right)),
+                    (RuleValue::Text(left), RuleValue::Text(right)) => Ok(op.apply(left, right)),
+                    _ => Err(BusinessError::ValidationError(
+                        format!("字段 {} 的类型与规则中的值不匹配", field)
+                    )),
+                }
+            }
+            RuleCondition::And(left, right) => Ok(left.evaluate(context)? && right.evaluate(context)?),
+            RuleCondition::Or(left, right) => Ok(left.evaluate(context)? || right.evaluate(context)?),
+        }
+    }
+}
+
+/// 声明式业务规则：条件成立时报告违规信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclarativeRule {
+    pub condition: RuleCondition,
+    pub message: String,
+}
+
+impl DeclarativeRule {
+    /// 解析 `when <条件> then violation "<信息>"` 形式的规则
+    pub fn parse(source: &str) -> Result<Self, BusinessError> {
+        let tokens = tokenize_rule(source)?;
+        let mut parser = RuleParser { tokens, position: 0, depth: 0 };
+        parser.expect_keyword("when")?;
+        let condition = parser.parse_or()?;
+        parser.expect_keyword("then")?;
+        parser.expect_keyword("violation")?;
+        let message = match parser.next() {
+            Some(RuleToken::Text(message)) => message,
+            _ => return Err(rule_parse_error("violation 后面需要带引号的违规信息")),
+        };
+        if parser.peek().is_some() {
+            return Err(rule_parse_error("违规信息后面有多余的内容"));
+        }
+        Ok(Self { condition, message })
+    }
+
+    /// 条件成立时返回业务规则违反错误
+    pub fn evaluate(&self, context: &RuleContext) -> Result<(), BusinessError> {
+        if self.condition.evaluate(context)? {
+            return Err(BusinessError::BusinessRuleViolation(self.message.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RuleToken {
+    Ident(String),
+    Number(f64),
+    Text(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+}
+
+fn rule_parse_error(detail: &str) -> BusinessError {
+    BusinessError::ValidationError(format!("规则解析失败: {}", detail))
+}
+
+fn tokenize_rule(source: &str) -> Result<Vec<RuleToken>, BusinessError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(RuleToken::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(RuleToken::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => text.push(escaped),
+                            None => return Err(rule_parse_error("字符串未结束")),
+                        },
+                        Some(other) => text.push(other),
+                        None => return Err(rule_parse_error("字符串未结束")),
+                    }
+                }
+                tokens.push(RuleToken::Text(text));
+            }
+            '=' | '!' | '>' | '<' => {
+                chars.next();
+                let followed_by_eq = chars.next_if_eq(&'=').is_some();
+                let op = match (c, followed_by_eq) {
+                    ('=', true) => CompareOp::Eq,
+                    ('!', true) => CompareOp::Ne,
+                    ('>', true) => CompareOp::Ge,
+                    ('>', false) => CompareOp::Gt,
+                    ('<', true) => CompareOp::Le,
+                    ('<', false) => CompareOp::Lt,
+                    _ => return Err(rule_parse_error(&format!("无法识别的运算符: {}", c))),
+                };
+                tokens.push(RuleToken::Op(op));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut literal = String::new();
+                while let Some(digit) = chars.next_if(|d| d.is_ascii_digit() || *d == '.' || *d == '-') {
+                    literal.push(digit);
+                }
+                let number = literal.parse()
+                    .map_err(|_| rule_parse_error(&format!("无效的数字: {}", literal)))?;
+                tokens.push(RuleToken::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(part) = chars.next_if(|d| d.is_alphanumeric() || *d == '_' || *d == '.') {
+                    ident.push(part);
+                }
+                tokens.push(RuleToken::Ident(ident));
+            }
+            other => return Err(rule_parse_error(&format!("无法识别的字符: {}", other))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// 括号的最大嵌套层数，超过时报错，避免恶意规则耗尽解析器的调用栈
+const MAX_RULE_NESTING_DEPTH: usize = 32;
+
+/// 递归下降解析器：or_expr := and_expr (or and_expr)*，and_expr := primary (and primary)*
+struct RuleParser {
+    tokens: Vec<RuleToken>,
+    position: usize,
+    // 当前所在的括号层数
+    depth: usize,
+}
+
+impl RuleParser {
+    fn peek(&self) -> Option<&RuleToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<RuleToken> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(RuleToken::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), BusinessError> {
+        if !self.is_keyword(keyword) {
+            return Err(rule_parse_error(&format!("缺少关键字 {}", keyword)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<RuleCondition, BusinessError> {
+        let mut condition = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.position += 1;
+            condition = RuleCondition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<RuleCondition, BusinessError> {
+        let mut condition = self.parse_primary()?;
+        while self.is_keyword("and") {
+            self.position += 1;
+            condition = RuleCondition::And(Box::new(condition), Box::new(self.parse_primary()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_primary(&mut self) -> Result<RuleCondition, BusinessError> {
+        match self.next() {
+            Some(RuleToken::LParen) => {
+                if self.depth >= MAX_RULE_NESTING_DEPTH {
+                    return Err(rule_parse_error(&format!("括号嵌套超过 {} 层", MAX_RULE_NESTING_DEPTH)));
+                }
+                self.depth += 1;
+                let condition = self.parse_or()?;
+                self.depth -= 1;
+                match self.next() {
+                    Some(RuleToken::RParen) => Ok(condition),
+                    _ => Err(rule_parse_error("缺少右括号")),
+                }
+            }
+            Some(RuleToken::Ident(field)) => {
+                let op = match self.next() {
+                    Some(RuleToken::Op(op)) => op,
+                    _ => return Err(rule_parse_error(&format!("字段 {} 后面需要比较运算符", field))),
+                };
+                let value = match self.next() {
+                    Some(RuleToken::Number(number)) => RuleValue::Number(number),
+                    Some(RuleToken::Text(text)) => RuleValue::Text(text),
+                    _ => return Err(rule_parse_error(&format!("字段 {} 缺少比较值", field))),
+                };
+                Ok(RuleCondition::Compare { field, op, value })
+            }
+            _ => Err(rule_parse_error("需要字段比较或括号分组")),
+        }
+    }
+}
+
+impl BusinessRuleEngine {
+    /// 从文本加载规则，每行一条，空行和以 # 开头的注释行被忽略
+    pub fn load_rules(source: &str) -> Result<Vec<DeclarativeRule>, BusinessError> {
+        source.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(DeclarativeRule::parse)
+            .collect()
+    }
+
+    /// 依次求值，返回第一条被违反的规则
+    pub fn evaluate_rules(rules: &[DeclarativeRule], context: &RuleContext) -> Result<(), BusinessError> {
+        rules.iter().try_for_each(|rule| rule.evaluate(context))
+    }
+}
+
 /// 用户业务服务
 pub struct UserBusinessService {
     // 在实际应用中，这里会注入数据访问层
@@ -879,6 +1185,22 @@ pub fn demo() {
     let discount = BusinessRuleEngine::calculate_discount(&test_user, 1200.0);
     println!("计算折扣: {:.1}%", discount * 100.0);
     
+    // 声明式规则：规则以文本形式在运行时加载
+    let rules = BusinessRuleEngine::load_rules(r#"
+        # 订单风控规则
+        when role == "guest" and amount > 500 then violation "访客单笔订单不能超过500"
+        when (role == "user" or role == "guest") and amount >= 10000 then violation "大额订单需要人工审核"
+    "#).unwrap();
+    for (role, amount) in [("guest", 300.0), ("guest", 800.0), ("user", 12000.0)] {
+        let context: RuleContext = [("role".to_string(), RuleValue::from(role)), ("amount".to_string(), RuleValue::from(amount))]
+            .into_iter()
+            .collect();
+        match BusinessRuleEngine::evaluate_rules(&rules, &context) {
+            Ok(_) => println!("声明式规则 {} / {}: 通过", role, amount),
+            Err(e) => println!("声明式规则 {} / {}: {}", role, amount, e),
+        }
+    }
+    
     println!("{}", "=".repeat(50));
     
     // 5. 错误处理演示
@@ -923,4 +1245,83 @@ pub fn demo() {
     println!("3. 跨多个实体的业务操作");
     println!("4. 需要事务控制的业务场景");
     println!("5. 权限和安全控制");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(fields: &[(&str, RuleValue)]) -> RuleContext {
+        fields.iter().map(|(name, value)| (name.to_string(), value.clone())).collect()
+    }
+
+    #[test]
+    fn test_parse_and_evaluate_declarative_rule() {
+        let rule = DeclarativeRule::parse(
+            r#"when amount > 10000 and (role == "guest" or role == "user") then violation "订单金额超过限额""#
+        ).unwrap();
+        assert_eq!(rule.message, "订单金额超过限额");
+
+        let passing = context(&[("amount", 20000.0.into()), ("role", "admin".into())]);
+        assert!(rule.evaluate(&passing).is_ok());
+        let small = context(&[("amount", 500.0.into()), ("role", "guest".into())]);
+        assert!(rule.evaluate(&small).is_ok());
+
+        let failing = context(&[("amount", 20000.0.into()), ("role", "user".into())]);
+        match rule.evaluate(&failing) {
+            Err(BusinessError::BusinessRuleViolation(msg)) => assert_eq!(msg, "订单金额超过限额"),
+            other => panic!("应该违反规则: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        // 等价于 a == 1 or (b == 1 and c == 1)
+        let rule = DeclarativeRule::parse(r#"when a == 1 or b == 1 and c == 1 then violation "命中""#).unwrap();
+        let hit = context(&[("a", 1.0.into()), ("b", 0.0.into()), ("c", 0.0.into())]);
+        let miss = context(&[("a", 0.0.into()), ("b", 1.0.into()), ("c", 0.0.into())]);
+        assert!(rule.evaluate(&hit).is_err());
+        assert!(rule.evaluate(&miss).is_ok());
+    }
+
+    #[test]
+    fn test_load_rules_reports_first_violation_and_errors() {
+        let rules = BusinessRuleEngine::load_rules(r#"
+            # 注释行会被忽略
+            when status != "active" then violation "只有活跃用户才能下单"
+            when balance < 0 then violation "余额不能为负"
+        "#).unwrap();
+        assert_eq!(rules.len(), 2);
+
+        let ctx = context(&[("status", "active".into()), ("balance", (-5.0).into())]);
+        match BusinessRuleEngine::evaluate_rules(&rules, &ctx) {
+            Err(BusinessError::BusinessRuleViolation(msg)) => assert_eq!(msg, "余额不能为负"),
+            other => panic!("应该违反规则: {:?}", other),
+        }
+
+        // 字段缺失、类型不匹配和语法错误都是验证错误
+        let missing = context(&[("status", "active".into())]);
+        assert!(matches!(BusinessRuleEngine::evaluate_rules(&rules, &missing), Err(BusinessError::ValidationError(_))));
+        let mismatched = context(&[("status", 1.0.into()), ("balance", 1.0.into())]);
+        assert!(matches!(BusinessRuleEngine::evaluate_rules(&rules, &mismatched), Err(BusinessError::ValidationError(_))));
+        assert!(matches!(DeclarativeRule::parse("when amount > then violation \"x\""), Err(BusinessError::ValidationError(_))));
+        assert!(matches!(DeclarativeRule::parse("when amount > 1 then violation"), Err(BusinessError::ValidationError(_))));
+    }
+
+    fn nested_rule(depth: usize) -> String {
+        format!(r#"when {}a == 1{} then violation "嵌套""#, "(".repeat(depth), ")".repeat(depth))
+    }
+
+    #[test]
+    fn test_nesting_beyond_limit_is_rejected() {
+        let deepest = DeclarativeRule::parse(&nested_rule(MAX_RULE_NESTING_DEPTH)).unwrap();
+        assert!(deepest.evaluate(&context(&[("a", 1.0.into())])).is_err());
+
+        match DeclarativeRule::parse(&nested_rule(MAX_RULE_NESTING_DEPTH + 1)) {
+            Err(BusinessError::ValidationError(msg)) => assert!(msg.contains("嵌套"), "{}", msg),
+            other => panic!("嵌套过深应该解析失败: {:?}", other),
+        }
+        // 极深的嵌套同样报错而不是栈溢出
+        assert!(DeclarativeRule::parse(&nested_rule(100_000)).is_err());
+    }
+}
@@ -17,10 +17,12 @@
  * - 层间通过数据传输对象（DTO）进行通信
  */
 
+#[cfg(feature = "serde")]
 pub mod presentation_layer;
 pub mod business_layer;
 
 // 重新导出主要的公共接口
+#[cfg(feature = "serde")]
 pub use presentation_layer::{
     HttpRequest, HttpResponse,
     UserController, PresentationError
@@ -51,6 +53,7 @@ pub fn demo_all() {
     
     // 演示表现层
     println!("=== 表现层演示 ===");
+    #[cfg(feature = "serde")]
     presentation_layer::demo();
     
     println!("\n{}", "=".repeat(80));
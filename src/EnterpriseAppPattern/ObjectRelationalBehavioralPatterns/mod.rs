@@ -8,6 +8,7 @@
  * 1. Unit of Work（工作单元）
  * 2. Identity Map（身份映射）
  * 3. Lazy Load（延迟加载）
+ * 4. Session（会话）- 组合身份映射、工作单元和仓储
  * 
  * 这些模式主要解决对象与关系数据库交互中的行为问题。
  */
@@ -15,6 +16,7 @@
 pub mod unit_of_work;
 pub mod identity_map;
 pub mod lazy_load;
+pub mod session;
 
 /// 演示所有对象-关系行为模式
 pub fn demo_all() {
@@ -24,6 +26,7 @@ pub fn demo_all() {
     println!("1. Unit of Work（工作单元）- 维护事务中的对象变更");
     println!("2. Identity Map（身份映射）- 确保对象唯一性");
     println!("3. Lazy Load（延迟加载）- 按需加载数据");
+    println!("4. Session（会话）- 组合身份映射、工作单元和仓储");
     
     println!("\n{}", "=".repeat(80));
     
@@ -48,6 +51,13 @@ pub fn demo_all() {
     
     println!("\n{}", "=".repeat(80));
     
+    // 4. Session 演示
+    println!("\n🚀 4. Session（会话）演示");
+    println!("适合：统一管理对象加载、变更追踪和持久化");
+    session::demo();
+    
+    println!("\n{}", "=".repeat(80));
+    
     // 模式总结
     println!("\n📊 【对象-关系行为模式总结】");
     println!("\n核心目标：优化对象与数据库的交互行为");
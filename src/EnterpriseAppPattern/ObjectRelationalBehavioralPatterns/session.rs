@@ -0,0 +1,564 @@
+// 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/EnterpriseAppPattern/ObjectRelationalBehavioralPatterns/session.rs
+
+//! # 会话 (Session)
+//!
+//! ## 概述
+//! 会话把身份映射、工作单元和仓储组合成一个完整的持久化上下文：
+//! - 加载经过身份映射：同一会话中同一标识的实体只加载一次，始终是同一个对象
+//! - 变更由工作单元追踪：加载时保存快照，`flush()` 时与快照比较找出脏实体
+//! - `flush()` 通过仓储按依赖顺序写入：先注册的实体类型先保存、后删除
+//! - `rollback()` 丢弃尚未刷新的变更：新实体被丢弃，已加载的实体恢复到最近一次刷新后的状态
+//!
+//! ## 依赖顺序
+//! 实体类型按注册顺序排列，被引用的类型（如作者）应先于引用它的类型（如书籍）注册。
+//! 插入和更新按注册顺序执行，删除按相反顺序执行，保证外键始终指向存在的记录。
+//!
+//! ## 部分失败
+//! 仓储接口没有事务，`flush()` 不是原子的：某次写入失败时，之前的写入已经生效且不会撤销。
+//! 会话对每条记录在写入成功后立即更新快照，因此失败后会话与仓储保持一致——
+//! 已写入的实体变为干净状态，失败及之后的变更仍然待刷新，修正问题后再次 `flush()`
+//! 只会写入剩余的变更。需要全有或全无语义时，应由支持事务的仓储在外层包裹整个刷新。
+
+use std::any::Any;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use super::identity_map::{Identifiable, IdentityMap, IdentityMapStatistics};
+use super::unit_of_work::ObjectState;
+use crate::EnterpriseAppPattern::ObjectRelationalMetadataMappingPatterns::repository::{
+    InMemoryUserRepository, Repository, RepositoryError, User,
+};
+
+/// 可以由会话管理的实体
+pub trait SessionEntity: Clone + PartialEq + Send + Sync + 'static {
+    type Id: Clone + Eq + Hash + fmt::Debug + Send + Sync + 'static;
+
+    /// 仓储分配的标识，尚未保存的新实体为 None
+    fn entity_id(&self) -> Option<Self::Id>;
+}
+
+impl SessionEntity for User {
+    type Id = u64;
+
+    fn entity_id(&self) -> Option<u64> {
+        self.id
+    }
+}
+
+/// 会话管理的实体，同一会话中同一标识只有一个实例
+pub struct Managed<T: SessionEntity> {
+    entity: Mutex<T>,
+}
+
+impl<T: SessionEntity> Managed<T> {
+    fn new(entity: T) -> Arc<Self> {
+        Arc::new(Self { entity: Mutex::new(entity) })
+    }
+
+    pub fn id(&self) -> Option<T::Id> {
+        self.entity.lock().unwrap().entity_id()
+    }
+
+    /// 当前状态的副本
+    pub fn get(&self) -> T {
+        self.entity.lock().unwrap().clone()
+    }
+
+    /// 修改实体，修改在下次 `flush()` 时写入
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.entity.lock().unwrap())
+    }
+
+    fn replace(&self, entity: T) {
+        *self.entity.lock().unwrap() = entity;
+    }
+}
+
+impl<T: SessionEntity> Identifiable for Managed<T> {
+    type Id = T::Id;
+
+    fn get_id(&self) -> T::Id {
+        self.id().expect("只有已持久化的实体会放入身份映射")
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+// =================
+// 按实体类型的变更追踪
+// =================
+
+/// 工作单元中的一条追踪记录
+struct Tracked<T: SessionEntity> {
+    managed: Arc<Managed<T>>,
+    /// 加载或最近一次刷新后的状态，用于脏检查和回滚；新实体为 None
+    original: Option<T>,
+    state: ObjectState,
+}
+
+impl<T: SessionEntity> Tracked<T> {
+    fn is_dirty(&self) -> bool {
+        match self.state {
+            ObjectState::New | ObjectState::Removed => true,
+            ObjectState::Clean | ObjectState::Dirty => self.original.as_ref() != Some(&self.managed.get()),
+        }
+    }
+}
+
+/// 一种实体类型的仓储及其追踪记录
+struct EntitySet<T: SessionEntity> {
+    repository: Box<dyn Repository<T, T::Id>>,
+    tracked: Vec<Tracked<T>>,
+}
+
+impl<T: SessionEntity> EntitySet<T> {
+    fn position(&self, managed: &Arc<Managed<T>>) -> Option<usize> {
+        self.tracked.iter().position(|tracked| Arc::ptr_eq(&tracked.managed, managed))
+    }
+}
+
+/// 擦除实体类型后的实体集合，会话按注册顺序保存它们
+trait EntitySetOps {
+    /// 插入新实体并更新脏实体，返回写入次数
+    fn save_changes(&mut self, identity_map: &mut IdentityMap) -> Result<usize, RepositoryError>;
+    /// 删除标记为删除的实体，返回写入次数
+    fn delete_removed(&mut self, identity_map: &mut IdentityMap) -> Result<usize, RepositoryError>;
+    fn rollback(&mut self);
+    fn pending_changes(&self) -> usize;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: SessionEntity> EntitySetOps for EntitySet<T> {
+    fn save_changes(&mut self, identity_map: &mut IdentityMap) -> Result<usize, RepositoryError> {
+        let mut writes = 0;
+        for tracked in &mut self.tracked {
+            if tracked.state == ObjectState::Removed || !tracked.is_dirty() {
+                continue;
+            }
+            // 每条记录写入后立即更新快照，刷新中途失败时已写入的记录不会被重复写入
+            let saved = self.repository.save(&tracked.managed.get())?;
+            tracked.managed.replace(saved.clone());
+            if tracked.state == ObjectState::New {
+                identity_map.put(Arc::clone(&tracked.managed));
+            }
+            tracked.original = Some(saved);
+            tracked.state = ObjectState::Clean;
+            writes += 1;
+        }
+        Ok(writes)
+    }
+
+    fn delete_removed(&mut self, identity_map: &mut IdentityMap) -> Result<usize, RepositoryError> {
+        let mut writes = 0;
+        let mut index = 0;
+        while index < self.tracked.len() {
+            if self.tracked[index].state != ObjectState::Removed {
+                index += 1;
+                continue;
+            }
+            if let Some(id) = self.tracked[index].managed.id() {
+                self.repository.delete(&id)?;
+                identity_map.remove::<Managed<T>>(&id);
+                writes += 1;
+            }
+            self.tracked.remove(index);
+        }
+        Ok(writes)
+    }
+
+    fn rollback(&mut self) {
+        self.tracked.retain(|tracked| tracked.state != ObjectState::New);
+        for tracked in &mut self.tracked {
+            if let Some(original) = &tracked.original {
+                tracked.managed.replace(original.clone());
+            }
+            tracked.state = ObjectState::Clean;
+        }
+    }
+
+    fn pending_changes(&self) -> usize {
+        self.tracked.iter().filter(|tracked| tracked.is_dirty()).count()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// =================
+// 会话
+// =================
+
+/// 组合身份映射、工作单元和仓储的持久化会话
+pub struct Session {
+    identity_map: IdentityMap,
+    /// 按依赖顺序排列的实体集合
+    entity_sets: Vec<Box<dyn EntitySetOps>>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self { identity_map: IdentityMap::new(), entity_sets: Vec::new() }
+    }
+
+    /// 注册实体类型的仓储，注册顺序即依赖顺序
+    pub fn register<T, R>(mut self, repository: R) -> Self
+    where
+        T: SessionEntity,
+        R: Repository<T, T::Id> + 'static,
+    {
+        self.entity_sets.push(Box::new(EntitySet::<T> { repository: Box::new(repository), tracked: Vec::new() }));
+        self
+    }
+
+    fn entity_set<T: SessionEntity>(entity_sets: &mut [Box<dyn EntitySetOps>]) -> Result<&mut EntitySet<T>, RepositoryError> {
+        entity_sets.iter_mut()
+            .find_map(|set| set.as_any_mut().downcast_mut::<EntitySet<T>>())
+            .ok_or_else(|| RepositoryError::DatabaseError(format!("实体类型 {} 未注册仓储", std::any::type_name::<T>())))
+    }
+
+    /// 按标识加载实体，同一会话中重复加载返回同一个实例
+    ///
+    /// 已标记删除的实体返回 None。
+    pub fn find<T: SessionEntity>(&mut self, id: &T::Id) -> Result<Option<Arc<Managed<T>>>, RepositoryError> {
+        let set = Self::entity_set::<T>(&mut self.entity_sets)?;
+        if let Some(managed) = self.identity_map.get::<Managed<T>>(id) {
+            let removed = set.position(&managed)
+                .is_some_and(|index| set.tracked[index].state == ObjectState::Removed);
+            return Ok((!removed).then_some(managed));
+        }
+
+        let Some(entity) = set.repository.find_by_id(id)? else {
+            return Ok(None);
+        };
+        let managed = Managed::new(entity.clone());
+        self.identity_map.put(Arc::clone(&managed));
+        set.tracked.push(Tracked { managed: Arc::clone(&managed), original: Some(entity), state: ObjectState::Clean });
+        Ok(Some(managed))
+    }
+
+    /// 登记新实体，下次 `flush()` 时插入
+    pub fn add<T: SessionEntity>(&mut self, entity: T) -> Result<Arc<Managed<T>>, RepositoryError> {
+        if entity.entity_id().is_some() {
+            return Err(RepositoryError::ValidationError("新实体不应该有ID".to_string()));
+        }
+        let set = Self::entity_set::<T>(&mut self.entity_sets)?;
+        let managed = Managed::new(entity);
+        set.tracked.push(Tracked { managed: Arc::clone(&managed), original: None, state: ObjectState::New });
+        Ok(managed)
+    }
+
+    /// 标记实体删除，下次 `flush()` 时删除；尚未插入的新实体直接丢弃
+    pub fn remove<T: SessionEntity>(&mut self, managed: &Arc<Managed<T>>) -> Result<(), RepositoryError> {
+        let set = Self::entity_set::<T>(&mut self.entity_sets)?;
+        let index = set.position(managed)
+            .ok_or_else(|| RepositoryError::NotFound("实体不属于当前会话".to_string()))?;
+        if set.tracked[index].state == ObjectState::New {
+            set.tracked.remove(index);
+        } else {
+            set.tracked[index].state = ObjectState::Removed;
+        }
+        Ok(())
+    }
+
+    /// 把所有变更写入仓储，返回写入次数
+    ///
+    /// 插入和更新按注册顺序执行，删除按相反顺序执行；没有变化的实体不会被写入。
+    /// 写入失败时立即返回错误，已完成的写入不会回滚，未完成的变更保留到下次刷新（见模块文档）。
+    pub fn flush(&mut self) -> Result<usize, RepositoryError> {
+        let mut writes = 0;
+        for set in self.entity_sets.iter_mut() {
+            writes += set.save_changes(&mut self.identity_map)?;
+        }
+        for set in self.entity_sets.iter_mut().rev() {
+            writes += set.delete_removed(&mut self.identity_map)?;
+        }
+        Ok(writes)
+    }
+
+    /// 丢弃尚未刷新的变更
+    pub fn rollback(&mut self) {
+        for set in self.entity_sets.iter_mut() {
+            set.rollback();
+        }
+    }
+
+    /// 尚未刷新的新增、修改和删除数量
+    pub fn pending_changes(&self) -> usize {
+        self.entity_sets.iter().map(|set| set.pending_changes()).sum()
+    }
+
+    pub fn identity_map_statistics(&self) -> IdentityMapStatistics {
+        self.identity_map.get_statistics()
+    }
+}
+
+/// 演示会话模式
+pub fn demo() {
+    println!("=== 会话模式演示 ===\n");
+
+    let repository = InMemoryUserRepository::new();
+    let alice = repository
+        .save(&User::new("alice".to_string(), "alice@example.com".to_string(), "Alice Johnson".to_string(), 28))
+        .unwrap();
+    let alice_id = alice.id.unwrap();
+    let mut session = Session::new().register::<User, _>(repository);
+
+    println!("1. 通过身份映射加载");
+    let first = session.find::<User>(&alice_id).unwrap().unwrap();
+    let second = session.find::<User>(&alice_id).unwrap().unwrap();
+    println!("   两次加载是同一个实例: {}", Arc::ptr_eq(&first, &second));
+    println!("   {}", session.identity_map_statistics());
+
+    println!("\n2. 工作单元追踪变更");
+    first.update(|user| user.full_name = "Alice Smith".to_string());
+    second.update(|user| user.age = 29);
+    let bob = session
+        .add(User::new("bob".to_string(), "bob@example.com".to_string(), "Bob Brown".to_string(), 35))
+        .unwrap();
+    println!("   待刷新的变更: {}", session.pending_changes());
+    match session.flush() {
+        Ok(writes) => println!("   刷新写入 {} 次，bob 分配到ID: {:?}", writes, bob.id()),
+        Err(e) => println!("   刷新失败: {}", e),
+    }
+    println!("   再次刷新写入 {} 次", session.flush().unwrap_or(0));
+
+    println!("\n3. 回滚未刷新的变更");
+    first.update(|user| user.deactivate());
+    session.remove(&bob).unwrap();
+    println!("   回滚前待刷新的变更: {}", session.pending_changes());
+    session.rollback();
+    println!("   回滚后: {}, bob 仍可加载: {}", first.get(), session.find::<User>(&bob.id().unwrap()).unwrap().is_some());
+
+    println!("\n=== 会话模式演示完成 ===");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    /// 记录写操作的仓储
+    struct RecordingRepository<T> {
+        name: &'static str,
+        rows: RefCell<HashMap<u64, T>>,
+        assign_id: fn(&mut T, u64),
+        log: Rc<RefCell<Vec<String>>>,
+        /// 返回 true 的实体保存失败，用于模拟写入错误
+        reject: fn(&T) -> bool,
+    }
+
+    impl<T: SessionEntity<Id = u64>> RecordingRepository<T> {
+        fn new(name: &'static str, assign_id: fn(&mut T, u64), log: &Rc<RefCell<Vec<String>>>) -> Self {
+            Self { name, rows: RefCell::new(HashMap::new()), assign_id, log: Rc::clone(log), reject: |_| false }
+        }
+
+        fn rejecting(mut self, reject: fn(&T) -> bool) -> Self {
+            self.reject = reject;
+            self
+        }
+    }
+
+    impl<T: SessionEntity<Id = u64>> Repository<T, u64> for RecordingRepository<T> {
+        fn find_by_id(&self, id: &u64) -> Result<Option<T>, RepositoryError> {
+            Ok(self.rows.borrow().get(id).cloned())
+        }
+
+        fn find_all(&self) -> Result<Vec<T>, RepositoryError> {
+            Ok(self.rows.borrow().values().cloned().collect())
+        }
+
+        fn save(&self, entity: &T) -> Result<T, RepositoryError> {
+            if (self.reject)(entity) {
+                return Err(RepositoryError::ValidationError(format!("{} 保存被拒绝", self.name)));
+            }
+            let mut rows = self.rows.borrow_mut();
+            let mut saved = entity.clone();
+            let id = saved.entity_id().unwrap_or(rows.len() as u64 + 1);
+            (self.assign_id)(&mut saved, id);
+            rows.insert(id, saved.clone());
+            self.log.borrow_mut().push(format!("save {} {}", self.name, id));
+            Ok(saved)
+        }
+
+        fn delete(&self, id: &u64) -> Result<bool, RepositoryError> {
+            self.log.borrow_mut().push(format!("delete {} {}", self.name, id));
+            Ok(self.rows.borrow_mut().remove(id).is_some())
+        }
+
+        fn exists(&self, id: &u64) -> Result<bool, RepositoryError> {
+            Ok(self.rows.borrow().contains_key(id))
+        }
+
+        fn count(&self) -> Result<usize, RepositoryError> {
+            Ok(self.rows.borrow().len())
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Author {
+        id: Option<u64>,
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Book {
+        id: Option<u64>,
+        author_id: u64,
+        title: String,
+    }
+
+    impl SessionEntity for Author {
+        type Id = u64;
+
+        fn entity_id(&self) -> Option<u64> {
+            self.id
+        }
+    }
+
+    impl SessionEntity for Book {
+        type Id = u64;
+
+        fn entity_id(&self) -> Option<u64> {
+            self.id
+        }
+    }
+
+    fn user_session(log: &Rc<RefCell<Vec<String>>>) -> (Session, u64) {
+        let repository = RecordingRepository::new("user", |user: &mut User, id| user.id = Some(id), log);
+        let alice = repository
+            .save(&User::new("alice".to_string(), "alice@example.com".to_string(), "Alice".to_string(), 30))
+            .unwrap();
+        log.borrow_mut().clear();
+        (Session::new().register::<User, _>(repository), alice.id.unwrap())
+    }
+
+    #[test]
+    fn test_loading_twice_returns_same_instance() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let (mut session, alice) = user_session(&log);
+
+        let first = session.find::<User>(&alice).unwrap().unwrap();
+        let second = session.find::<User>(&alice).unwrap().unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        first.update(|user| user.age = 31);
+        assert_eq!(second.get().age, 31);
+        let stats = session.identity_map_statistics();
+        assert_eq!((stats.hit_count, stats.total_objects), (1, 1));
+        assert!(session.find::<User>(&99).unwrap().is_none());
+        // 未注册仓储的实体类型
+        assert!(matches!(session.find::<Book>(&1), Err(RepositoryError::DatabaseError(_))));
+    }
+
+    #[test]
+    fn test_flush_writes_each_modified_entity_once() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let (mut session, alice) = user_session(&log);
+
+        let first = session.find::<User>(&alice).unwrap().unwrap();
+        let second = session.find::<User>(&alice).unwrap().unwrap();
+        first.update(|user| user.full_name = "Alice Smith".to_string());
+        second.update(|user| user.age = 31);
+        assert_eq!(session.pending_changes(), 1);
+
+        assert_eq!(session.flush().unwrap(), 1);
+        assert_eq!(*log.borrow(), vec![format!("save user {}", alice)]);
+        assert_eq!(session.pending_changes(), 0);
+
+        // 没有新变更时不再写入
+        assert_eq!(session.flush().unwrap(), 0);
+        assert_eq!(log.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_rollback_discards_unflushed_changes() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let (mut session, alice) = user_session(&log);
+
+        let user = session.find::<User>(&alice).unwrap().unwrap();
+        user.update(|user| user.age = 31);
+        session.flush().unwrap();
+
+        user.update(|user| user.deactivate());
+        let bob = session
+            .add(User::new("bob".to_string(), "bob@example.com".to_string(), "Bob".to_string(), 40))
+            .unwrap();
+        session.remove(&user).unwrap();
+        assert!(session.find::<User>(&alice).unwrap().is_none());
+        assert_eq!(session.pending_changes(), 2);
+
+        session.rollback();
+
+        // 恢复到最近一次刷新后的状态，新实体被丢弃
+        assert_eq!(session.pending_changes(), 0);
+        assert_eq!(user.get().age, 31);
+        assert!(user.get().is_active);
+        assert!(Arc::ptr_eq(&session.find::<User>(&alice).unwrap().unwrap(), &user));
+        assert_eq!(session.flush().unwrap(), 0);
+        assert!(bob.id().is_none());
+        assert_eq!(*log.borrow(), vec![format!("save user {}", alice)]);
+    }
+
+    #[test]
+    fn test_flush_follows_dependency_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut session = Session::new()
+            .register::<Author, _>(RecordingRepository::new("author", |author: &mut Author, id| author.id = Some(id), &log))
+            .register::<Book, _>(RecordingRepository::new("book", |book: &mut Book, id| book.id = Some(id), &log));
+
+        // 先登记书籍再登记作者，插入仍然先写作者
+        let book = session.add(Book { id: None, author_id: 1, title: "Rust设计模式".to_string() }).unwrap();
+        let author = session.add(Author { id: None, name: "张三".to_string() }).unwrap();
+        assert_eq!(session.flush().unwrap(), 2);
+        assert_eq!(*log.borrow(), vec!["save author 1", "save book 1"]);
+        assert!(Arc::ptr_eq(&session.find::<Author>(&1).unwrap().unwrap(), &author));
+
+        // 先标记作者再标记书籍，删除仍然先删书籍
+        log.borrow_mut().clear();
+        session.remove(&author).unwrap();
+        session.remove(&book).unwrap();
+        assert_eq!(session.flush().unwrap(), 2);
+        assert_eq!(*log.borrow(), vec!["delete book 1", "delete author 1"]);
+        assert!(session.find::<Author>(&1).unwrap().is_none());
+        assert_eq!(session.pending_changes(), 0);
+    }
+
+    #[test]
+    fn test_failed_flush_keeps_completed_writes_and_retries_the_rest() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut session = Session::new()
+            .register::<Author, _>(RecordingRepository::new("author", |author: &mut Author, id| author.id = Some(id), &log))
+            .register::<Book, _>(
+                RecordingRepository::new("book", |book: &mut Book, id| book.id = Some(id), &log)
+                    .rejecting(|book: &Book| book.title.is_empty()),
+            );
+
+        let author = session.add(Author { id: None, name: "张三".to_string() }).unwrap();
+        let book = session.add(Book { id: None, author_id: 1, title: String::new() }).unwrap();
+
+        // 作者已经写入且不会撤销，书籍仍然待刷新
+        assert!(matches!(session.flush(), Err(RepositoryError::ValidationError(_))));
+        assert_eq!(*log.borrow(), vec!["save author 1"]);
+        assert_eq!(author.id(), Some(1));
+        assert!(book.id().is_none());
+        assert_eq!(session.pending_changes(), 1);
+
+        // 修正后再次刷新只写入剩余的变更
+        book.update(|book| book.title = "Rust设计模式".to_string());
+        assert_eq!(session.flush().unwrap(), 1);
+        assert_eq!(*log.borrow(), vec!["save author 1", "save book 1"]);
+        assert_eq!(session.pending_changes(), 0);
+    }
+}
@@ -23,10 +23,12 @@
 //! - **优点**: 集中数据访问，可测试性好，支持多数据源
 //! - **适用**: 领域驱动设计，复杂查询，需要抽象数据层
 
+#[cfg(feature = "serde")]
 pub mod metadata_mapping;
 pub mod query_object;
 pub mod repository;
 
+#[cfg(feature = "serde")]
 pub use metadata_mapping::*;
 pub use query_object::*;
 pub use repository::*;
@@ -76,6 +78,7 @@ pub fn demo_all() {
     println!("\n{}", "=".repeat(60));
     
     // 演示元数据映射
+    #[cfg(feature = "serde")]
     metadata_mapping::demo();
     
     println!("\n{}", "=".repeat(60));
@@ -24,6 +24,8 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, Mutex};
 
+use crate::EnterpriseAppPattern::BasePatterns::Page;
+
 /// 通用错误类型
 #[derive(Debug)]
 pub enum RepositoryError {
@@ -114,6 +116,52 @@ impl fmt::Display for User {
     }
 }
 
+/// 分页游标
+///
+/// 对调用方不透明，内部编码上一页最后一条记录的排序键（用户ID）。
+/// 与偏移量分页不同，翻页过程中插入新记录不会导致记录被跳过或重复。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor(String);
+
+impl Cursor {
+    const PREFIX: &'static str = "after:";
+
+    fn encode(last_id: u64) -> Self {
+        let raw = format!("{}{}", Self::PREFIX, last_id);
+        Cursor(raw.bytes().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    fn decode(&self) -> Result<u64, RepositoryError> {
+        let invalid = || RepositoryError::ValidationError(format!("无效的分页游标: {}", self.0));
+
+        // 先校验字符集，非ASCII输入按字节切片会落在字符中间
+        if !self.0.len().is_multiple_of(2) || !self.0.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let bytes = (0..self.0.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&self.0[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+        raw.strip_prefix(Self::PREFIX)
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(invalid)
+    }
+
+    /// 游标的字符串形式，可直接返回给客户端
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Cursor {
+    fn from(value: String) -> Self {
+        Cursor(value)
+    }
+}
+
 /// 用户仓储接口
 pub trait UserRepository: Repository<User, u64> {
     /// 根据用户名查找用户
@@ -130,6 +178,24 @@ pub trait UserRepository: Repository<User, u64> {
     
     /// 搜索用户（模糊匹配姓名或用户名）
     fn search_users(&self, keyword: &str) -> Result<Vec<User>, RepositoryError>;
+    
+    /// 按ID升序分页，返回游标之后的至多 `limit` 个用户；cursor 为 None 时从头开始
+    ///
+    /// 结果页的 `next_cursor` 可以用 `Cursor::from` 还原后请求下一页。
+    fn find_after(&self, cursor: Option<&Cursor>, limit: usize) -> Result<Page<User>, RepositoryError>;
+    
+    /// 按ID升序分页，返回第 `page` 页（从1开始），每页 `size` 个用户
+    ///
+    /// 默认实现取出全部用户后在内存中切片，能在存储层分页的实现应当覆盖它。
+    fn find_page(&self, page: usize, size: usize) -> Result<Page<User>, RepositoryError> {
+        if page == 0 || size == 0 {
+            return Err(RepositoryError::ValidationError("页码和分页大小必须大于0".to_string()));
+        }
+        
+        let mut users = self.find_all()?;
+        users.sort_by_key(|user| user.id);
+        Ok(Page::slice(users, page, size))
+    }
 }
 
 /// 内存用户仓储实现
@@ -281,6 +347,32 @@ impl UserRepository for InMemoryUserRepository {
             .cloned()
             .collect())
     }
+
+    fn find_after(&self, cursor: Option<&Cursor>, limit: usize) -> Result<Page<User>, RepositoryError> {
+        if limit == 0 {
+            return Err(RepositoryError::ValidationError("分页大小必须大于0".to_string()));
+        }
+        let after_id = match cursor {
+            Some(cursor) => Some(cursor.decode()?),
+            None => None,
+        };
+        
+        let users = self.users.lock().unwrap();
+        let mut ids: Vec<u64> = users.keys()
+            .copied()
+            .filter(|id| after_id.is_none_or(|after| *id > after))
+            .collect();
+        ids.sort_unstable();
+        
+        // 多取一条用于判断是否还有下一页
+        let has_more = ids.len() > limit;
+        ids.truncate(limit);
+        
+        let next_cursor = if has_more { ids.last().map(|id| Cursor::encode(*id).0) } else { None };
+        let items = ids.iter().map(|id| users[id].clone()).collect();
+        
+        Ok(Page::after_cursor(items, users.len(), limit, next_cursor))
+    }
 }
 
 /// 用户服务（使用仓储模式）
@@ -373,6 +465,16 @@ impl UserService {
     pub fn get_total_users(&self) -> Result<usize, RepositoryError> {
         self.repository.count()
     }
+
+    /// 游标分页浏览用户
+    pub fn list_users_after(&self, cursor: Option<&Cursor>, limit: usize) -> Result<Page<User>, RepositoryError> {
+        self.repository.find_after(cursor, limit)
+    }
+
+    /// 按页码浏览用户
+    pub fn list_users(&self, page: usize, size: usize) -> Result<Page<User>, RepositoryError> {
+        self.repository.find_page(page, size)
+    }
 }
 
 /// 演示仓储模式
@@ -494,7 +596,36 @@ pub fn demo() {
         Err(e) => println!("   获取统计失败: {}", e),
     }
     
-    println!("\n7. 删除操作");
+    println!("\n7. 游标分页");
+    let mut cursor: Option<Cursor> = None;
+    let mut page_no = 1;
+    loop {
+        match user_service.list_users_after(cursor.as_ref(), 2) {
+            Ok(page) => {
+                let names: Vec<&str> = page.items.iter().map(|u| u.username.as_str()).collect();
+                println!("   第{}页: {:?}", page_no, names);
+                match page.next_cursor {
+                    Some(next) => cursor = Some(Cursor::from(next)),
+                    None => break,
+                }
+                page_no += 1;
+            }
+            Err(e) => {
+                println!("   分页失败: {}", e);
+                break;
+            }
+        }
+    }
+    
+    match user_service.list_users(2, 2) {
+        Ok(page) => {
+            let names: Vec<String> = page.map(|u| u.username).items;
+            println!("   按页码分页 第2页(每页2个): {:?}", names);
+        }
+        Err(e) => println!("   分页失败: {}", e),
+    }
+    
+    println!("\n8. 删除操作");
     if let Some(last_user) = created_users.last() {
         if let Some(user_id) = last_user.id {
             match user_service.delete_user(user_id) {
@@ -548,4 +679,81 @@ mod tests {
         assert!(repo.save(&user1).is_ok());
         assert!(repo.save(&user2).is_err()); // 应该失败，因为用户名重复
     }
-} 
\ No newline at end of file
+
+    fn seeded_repository(count: usize) -> InMemoryUserRepository {
+        let repo = InMemoryUserRepository::new();
+        for i in 0..count {
+            let user = User::new(format!("user{}", i), format!("user{}@example.com", i), format!("User {}", i), 20);
+            repo.save(&user).unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_cursor_pagination_visits_every_user_once() {
+        let repo = seeded_repository(7);
+        let mut seen = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        let mut pages = 0;
+
+        loop {
+            let page = repo.find_after(cursor.as_ref(), 3).unwrap();
+            seen.extend(page.items.iter().map(|u| u.id.unwrap()));
+            pages += 1;
+            assert_eq!((page.total, page.has_next()), (7, page.next_cursor.is_some()));
+            match page.next_cursor {
+                Some(next) => cursor = Some(Cursor::from(next)),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(seen, (1..=7).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_cursor_pagination_is_stable_under_inserts() {
+        let repo = seeded_repository(7);
+        repo.delete(&2).unwrap();
+
+        let first = repo.find_after(None, 3).unwrap();
+        // 翻页途中在游标之前补回ID为2的用户，偏移量分页会因此重复返回用户4
+        let restored = User::new("restored".to_string(), "restored@example.com".to_string(), "Restored User".to_string(), 30).with_id(2);
+        repo.users.lock().unwrap().insert(2, restored);
+        // 在游标之后插入新用户
+        let inserted = repo.save(&User::new("late".to_string(), "late@example.com".to_string(), "Late User".to_string(), 30)).unwrap();
+
+        let mut seen: Vec<u64> = first.items.iter().map(|u| u.id.unwrap()).collect();
+        let mut cursor = first.next_cursor.map(Cursor::from);
+        while let Some(current) = cursor {
+            let page = repo.find_after(Some(&current), 3).unwrap();
+            seen.extend(page.items.iter().map(|u| u.id.unwrap()));
+            cursor = page.next_cursor.map(Cursor::from);
+        }
+
+        assert_eq!(seen, vec![1, 3, 4, 5, 6, 7, inserted.id.unwrap()]);
+    }
+
+    #[test]
+    fn test_invalid_cursor_is_rejected() {
+        let repo = seeded_repository(2);
+        for bogus in ["not-a-cursor", "aé1", "+1", ""] {
+            let bogus = Cursor::from(bogus.to_string());
+            assert!(matches!(repo.find_after(Some(&bogus), 10), Err(RepositoryError::ValidationError(_))));
+        }
+    }
+
+    #[test]
+    fn test_page_number_pagination() {
+        let repo = seeded_repository(7);
+
+        let last = repo.find_page(3, 3).unwrap();
+        assert_eq!((last.total, last.total_pages), (7, 3));
+        assert_eq!(last.map(|u| u.id.unwrap()).items, vec![7]);
+
+        let first = repo.find_page(1, 3).unwrap();
+        assert!(first.has_next() && !first.has_prev());
+        assert!(repo.find_page(4, 3).unwrap().is_empty());
+        assert!(matches!(repo.find_page(0, 3), Err(RepositoryError::ValidationError(_))));
+    }
+}
@@ -58,23 +58,33 @@
 //! - **描述**: 为每个具体类创建一个表，包含所有继承的字段
 //! - **优点**: 无需表连接，查询性能好，模式简单
 //! - **适用**: 查询性能优先，很少多态查询，继承层次稳定
+//!
+//! ### 辅助工具: 模式迁移 (Schema Migration)
+//! - **文件**: `schema_migration.rs`
+//! - **描述**: 把旧版本的序列化载荷逐版本升级到最新结构
+//! - **适用**: 序列化LOB、会话状态等整体存储的载荷
 
 pub mod identity_field;
 pub mod foreign_key_mapping;
 pub mod association_table_mapping;
 pub mod dependent_mapping;
 pub mod embedded_value;
+#[cfg(feature = "serde")]
 pub mod serialized_lob;
+#[cfg(feature = "serde")]
 pub mod single_table_inheritance;
 pub mod class_table_inheritance;
 pub mod concrete_table_inheritance;
+pub mod schema_migration;
 
 pub use identity_field::*;
 pub use foreign_key_mapping::*;
 pub use association_table_mapping::*;
 pub use dependent_mapping::*;
 pub use embedded_value::*;
+#[cfg(feature = "serde")]
 pub use serialized_lob::*;
+#[cfg(feature = "serde")]
 pub use single_table_inheritance::*;
 pub use class_table_inheritance::*;
 pub use concrete_table_inheritance::*;
@@ -151,9 +161,11 @@ pub fn demo_all() {
     embedded_value::demo();
     println!("\n{}", "-".repeat(60));
     
+    #[cfg(feature = "serde")]
     serialized_lob::demo();
     println!("\n{}", "-".repeat(60));
     
+    #[cfg(feature = "serde")]
     single_table_inheritance::demo();
     println!("\n{}", "-".repeat(60));
     
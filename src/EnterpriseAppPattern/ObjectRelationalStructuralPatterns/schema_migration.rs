@@ -0,0 +1,209 @@
+//! # 模式迁移（Schema Migration）
+//!
+//! 序列化LOB、会话状态等持久化方式把整个对象图存成一份载荷，
+//! 载荷结构随业务演进而变化时，旧数据需要逐版本升级到最新结构。
+//! `MigrationRunner` 持有一组有序的版本迁移，按 v1 → v2 → v3 的顺序
+//! 逐步变换载荷，缺少中间版本时报错而不是跳过。
+//!
+//! 载荷统一用 `serde_json::Value` 表示，迁移逻辑因此与具体的
+//! Rust 类型解耦，旧版本的结构体不必保留在代码中。
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// 迁移错误类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    /// 缺少从某个版本出发的迁移
+    MissingMigration { from_version: u32, target_version: u32 },
+    /// 同一起始版本注册了多个迁移
+    DuplicateMigration(u32),
+    /// 迁移的目标版本不大于起始版本，或超过运行器的最新版本
+    InvalidMigration { from_version: u32, to_version: u32 },
+    /// 载荷版本比运行器已知的最新版本还新
+    UnknownVersion { version: u32, latest_version: u32 },
+    /// 单步变换失败
+    TransformFailed { from_version: u32, to_version: u32, reason: String },
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::MissingMigration { from_version, target_version } => {
+                write!(f, "缺少从 v{} 开始的迁移，无法升级到 v{}", from_version, target_version)
+            }
+            MigrationError::DuplicateMigration(version) => write!(f, "v{} 的迁移重复注册", version),
+            MigrationError::InvalidMigration { from_version, to_version } => {
+                write!(f, "无效的迁移: v{} -> v{}", from_version, to_version)
+            }
+            MigrationError::UnknownVersion { version, latest_version } => {
+                write!(f, "未知的载荷版本 v{}，最新版本为 v{}", version, latest_version)
+            }
+            MigrationError::TransformFailed { from_version, to_version, reason } => {
+                write!(f, "迁移 v{} -> v{} 失败: {}", from_version, to_version, reason)
+            }
+        }
+    }
+}
+
+impl Error for MigrationError {}
+
+/// 单步变换函数
+pub type MigrationTransform = Box<dyn Fn(Value) -> Result<Value, String> + Send + Sync>;
+
+/// 单个版本迁移
+pub struct Migration {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub description: String,
+    transform: MigrationTransform,
+}
+
+impl Migration {
+    pub fn new<F>(from_version: u32, to_version: u32, description: &str, transform: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        Self {
+            from_version,
+            to_version,
+            description: description.to_string(),
+            transform: Box::new(transform),
+        }
+    }
+}
+
+/// 迁移运行器
+pub struct MigrationRunner {
+    migrations: BTreeMap<u32, Migration>,
+    latest_version: u32,
+}
+
+impl MigrationRunner {
+    /// 创建运行器，`latest_version` 为当前代码期望的载荷版本
+    pub fn new(latest_version: u32) -> Self {
+        Self {
+            migrations: BTreeMap::new(),
+            latest_version,
+        }
+    }
+
+    /// 注册迁移，目标版本超过最新版本的迁移会被拒绝，升级结果因此不会越过最新版本
+    pub fn register(&mut self, migration: Migration) -> Result<(), MigrationError> {
+        if migration.to_version <= migration.from_version || migration.to_version > self.latest_version {
+            return Err(MigrationError::InvalidMigration {
+                from_version: migration.from_version,
+                to_version: migration.to_version,
+            });
+        }
+        if self.migrations.contains_key(&migration.from_version) {
+            return Err(MigrationError::DuplicateMigration(migration.from_version));
+        }
+        self.migrations.insert(migration.from_version, migration);
+        Ok(())
+    }
+
+    /// 链式注册，便于初始化
+    pub fn with_migration(mut self, migration: Migration) -> Result<Self, MigrationError> {
+        self.register(migration)?;
+        Ok(self)
+    }
+
+    pub fn latest_version(&self) -> u32 {
+        self.latest_version
+    }
+
+    /// 把载荷从 `version` 逐步升级到最新版本，返回升级后的载荷和版本号
+    pub fn migrate(&self, payload: Value, version: u32) -> Result<(Value, u32), MigrationError> {
+        if version > self.latest_version {
+            return Err(MigrationError::UnknownVersion { version, latest_version: self.latest_version });
+        }
+
+        let mut current = payload;
+        let mut current_version = version;
+        while current_version < self.latest_version {
+            let migration = self.migrations.get(&current_version).ok_or(MigrationError::MissingMigration {
+                from_version: current_version,
+                target_version: self.latest_version,
+            })?;
+
+            current = (migration.transform)(current).map_err(|reason| MigrationError::TransformFailed {
+                from_version: migration.from_version,
+                to_version: migration.to_version,
+                reason: format!("{}: {}", migration.description, reason),
+            })?;
+            current_version = migration.to_version;
+        }
+
+        Ok((current, current_version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_name_to_full_name() -> Migration {
+        Migration::new(1, 2, "name 拆分为 full_name", |mut payload| {
+            let name = payload["name"].take();
+            let object = payload.as_object_mut().ok_or("载荷不是对象")?;
+            object.remove("name");
+            object.insert("full_name".to_string(), name);
+            Ok(payload)
+        })
+    }
+
+    fn add_locale_default() -> Migration {
+        Migration::new(2, 3, "新增 locale 字段", |mut payload| {
+            payload["locale"] = json!("zh-CN");
+            Ok(payload)
+        })
+    }
+
+    #[test]
+    fn test_upgrade_v1_through_v2_to_v3() {
+        let runner = MigrationRunner::new(3)
+            .with_migration(add_locale_default()).unwrap()
+            .with_migration(rename_name_to_full_name()).unwrap();
+
+        let (payload, version) = runner.migrate(json!({"id": 7, "name": "张三"}), 1).unwrap();
+
+        assert_eq!(version, 3);
+        assert_eq!(payload, json!({"id": 7, "full_name": "张三", "locale": "zh-CN"}));
+
+        // 已是最新版本的载荷原样返回
+        let (unchanged, version) = runner.migrate(payload.clone(), 3).unwrap();
+        assert_eq!((unchanged, version), (payload, 3));
+    }
+
+    #[test]
+    fn test_missing_intermediate_migration_is_reported() {
+        let runner = MigrationRunner::new(3).with_migration(rename_name_to_full_name()).unwrap();
+
+        let error = runner.migrate(json!({"name": "李四"}), 1).unwrap_err();
+
+        assert_eq!(error, MigrationError::MissingMigration { from_version: 2, target_version: 3 });
+        assert!(matches!(
+            runner.migrate(json!({}), 4),
+            Err(MigrationError::UnknownVersion { version: 4, latest_version: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_migration_beyond_latest_version_is_rejected() {
+        let mut runner = MigrationRunner::new(3);
+        let overshoot = Migration::new(2, 4, "跳到未知版本", Ok);
+
+        assert_eq!(runner.register(overshoot), Err(MigrationError::InvalidMigration { from_version: 2, to_version: 4 }));
+        assert!(runner.register(Migration::new(2, 3, "补齐字段", |_| Err("字段类型不符".to_string()))).is_ok());
+
+        // 变换失败时错误中带上迁移的描述
+        assert_eq!(
+            runner.migrate(json!({}), 2),
+            Err(MigrationError::TransformFailed { from_version: 2, to_version: 3, reason: "补齐字段: 字段类型不符".to_string() })
+        );
+    }
+}
@@ -22,6 +22,8 @@ use std::fmt::{self, Display, Formatter};
 use std::error::Error;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use super::schema_migration::MigrationRunner;
+
 /// 序列化LOB错误类型
 #[derive(Debug)]
 pub enum SerializedLobError {
@@ -80,6 +82,13 @@ pub struct LobMetadata {
     pub size: usize,
     pub checksum: String,
     pub compression: String,
+    /// 载荷结构版本，与每次写入递增的 `version` 无关
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+}
+
+fn default_schema_version() -> u32 {
+    1
 }
 
 impl LobMetadata {
@@ -94,6 +103,7 @@ impl LobMetadata {
             size: 0,
             checksum: String::new(),
             compression: "none".to_string(),
+            schema_version: default_schema_version(),
         }
     }
 
@@ -418,6 +428,40 @@ impl SerializedLobRepository {
         Ok(())
     }
 
+    /// 以指定的载荷结构版本保存对象
+    pub fn save_versioned<T>(&mut self, id: String, object: &T, format: SerializationFormat, schema_version: u32) -> Result<(), SerializedLobError>
+    where 
+        T: Serialize,
+    {
+        self.save(id.clone(), object, format)?;
+        if let Some(lob) = self.storage.get_mut(&id) {
+            lob.metadata.schema_version = schema_version;
+        }
+        Ok(())
+    }
+
+    /// 加载对象，必要时先通过迁移运行器把载荷升级到最新版本并写回
+    pub fn load_migrated<T>(&mut self, id: &str, runner: &MigrationRunner) -> Result<T, SerializedLobError>
+    where 
+        T: for<'de> Deserialize<'de>,
+    {
+        let payload: serde_json::Value = self.load(id)?;
+        let schema_version = self.get_metadata(id)?.schema_version;
+
+        let (upgraded, new_version) = runner.migrate(payload, schema_version)
+            .map_err(|e| SerializedLobError::VersionMismatch(e.to_string()))?;
+
+        if new_version != schema_version {
+            self.update(id, &upgraded)?;
+            if let Some(lob) = self.storage.get_mut(id) {
+                lob.metadata.schema_version = new_version;
+            }
+        }
+
+        serde_json::from_value(upgraded)
+            .map_err(|e| SerializedLobError::DeserializationError(e.to_string()))
+    }
+
     /// 从LOB加载对象
     pub fn load<T>(&mut self, id: &str) -> Result<T, SerializedLobError>
     where 
@@ -693,6 +737,7 @@ pub fn demo() {
         println!("   当前版本: {}", metadata.version);
         println!("   创建时间: {}", metadata.created_at);
         println!("   更新时间: {}", metadata.updated_at);
+        println!("   载荷结构版本: v{}", metadata.schema_version);
     }
 
     println!("\n=== 序列化LOB模式演示完成 ===");
@@ -767,4 +812,46 @@ mod tests {
         lob.data.push(b'!');
         assert!(lob.validate().is_err());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_load_migrated_upgrades_and_writes_back() {
+        use super::super::schema_migration::Migration;
+
+        #[derive(Serialize)]
+        struct ProfileV1 {
+            id: String,
+            name: String,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct ProfileV3 {
+            id: String,
+            full_name: String,
+            tier: String,
+        }
+
+        let runner = MigrationRunner::new(3)
+            .with_migration(Migration::new(1, 2, "name 改名为 full_name", |mut payload| {
+                let name = payload["name"].take();
+                payload.as_object_mut().ok_or("载荷不是对象")?.remove("name");
+                payload["full_name"] = name;
+                Ok(payload)
+            })).unwrap()
+            .with_migration(Migration::new(2, 3, "新增会员等级", |mut payload| {
+                payload["tier"] = serde_json::json!("standard");
+                Ok(payload)
+            })).unwrap();
+
+        let mut repository = SerializedLobRepository::new();
+        let old = ProfileV1 { id: "c1".to_string(), name: "王五".to_string() };
+        repository.save_versioned("c1".to_string(), &old, SerializationFormat::Json, 1).unwrap();
+
+        let loaded: ProfileV3 = repository.load_migrated("c1", &runner).unwrap();
+        assert_eq!(loaded, ProfileV3 { id: "c1".to_string(), full_name: "王五".to_string(), tier: "standard".to_string() });
+        assert_eq!(repository.get_metadata("c1").unwrap().schema_version, 3);
+
+        // 写回后再次加载无需迁移
+        let reloaded: ProfileV3 = repository.load_migrated("c1", &runner).unwrap();
+        assert_eq!(reloaded, loaded);
+    }
+}
@@ -16,8 +16,9 @@
 //! - 减少开发者犯错的可能性时
 //! - 统一锁定策略时
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::fmt::{self, Display, Formatter};
 use std::error::Error;
 use std::thread;
@@ -413,6 +414,184 @@ impl ImplicitLockDecorator {
     }
 }
 
+/// 公平读写锁中排队者的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WaiterKind {
+    Reader,
+    Writer,
+}
+
+/// 公平读写锁的准入状态
+#[derive(Debug, Default)]
+struct FairLockState {
+    next_ticket: u64,
+    queue: VecDeque<(u64, WaiterKind)>,
+    active_readers: usize,
+    writer_active: bool,
+}
+
+impl FairLockState {
+    fn enqueue(&mut self, kind: WaiterKind) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        self.queue.push_back((ticket, kind));
+        ticket
+    }
+
+    fn dequeue(&mut self, ticket: u64) {
+        self.queue.retain(|(queued, _)| *queued != ticket);
+    }
+
+    // 读者只有在前面没有排队的写者时才能进入，避免插队饿死写者
+    fn reader_may_enter(&self, ticket: u64) -> bool {
+        !self.writer_active
+            && !self.queue.iter().any(|(queued, kind)| *queued < ticket && *kind == WaiterKind::Writer)
+    }
+
+    fn writer_may_enter(&self, ticket: u64) -> bool {
+        !self.writer_active
+            && self.active_readers == 0
+            && self.queue.front().map(|(queued, _)| *queued) == Some(ticket)
+    }
+}
+
+/// 公平读写锁指标
+#[derive(Debug, Clone, PartialEq)]
+pub struct FairRwLockMetrics {
+    pub waiting_readers: usize,
+    pub waiting_writers: usize,
+    pub active_readers: usize,
+    pub writer_active: bool,
+}
+
+impl Display for FairRwLockMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "公平读写锁 - 等待读者: {}, 等待写者: {}, 活跃读者: {}, 写者持有: {}",
+               self.waiting_readers, self.waiting_writers, self.active_readers, self.writer_active)
+    }
+}
+
+/// 公平读写锁 - 读者和写者按到达顺序排队
+///
+/// 标准库 `RwLock` 的公平性未作规定，持续的读负载可能让写者一直等待。
+/// 这里用排队号决定准入顺序：写者到达后，之后到达的读者必须等它完成。
+/// 数据本身仍放在 `RwLock` 中，准入控制保证内部锁不会发生竞争。
+pub struct FairRwLock<T> {
+    data: RwLock<T>,
+    state: Mutex<FairLockState>,
+    changed: Condvar,
+}
+
+impl<T> FairRwLock<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            data: RwLock::new(value),
+            state: Mutex::new(FairLockState::default()),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// 获取读锁，排在已等待的写者之后
+    pub fn read(&self) -> FairReadGuard<'_, T> {
+        let mut state = self.state.lock().unwrap();
+        let ticket = state.enqueue(WaiterKind::Reader);
+        while !state.reader_may_enter(ticket) {
+            state = self.changed.wait(state).unwrap();
+        }
+        state.dequeue(ticket);
+        state.active_readers += 1;
+        drop(state);
+        // 可能有排在后面的读者在等待同一批准入
+        self.changed.notify_all();
+
+        FairReadGuard { guard: Some(self.data.read().unwrap()), lock: self }
+    }
+
+    /// 获取写锁，等待排在前面的所有读者和写者完成
+    pub fn write(&self) -> FairWriteGuard<'_, T> {
+        let mut state = self.state.lock().unwrap();
+        let ticket = state.enqueue(WaiterKind::Writer);
+        while !state.writer_may_enter(ticket) {
+            state = self.changed.wait(state).unwrap();
+        }
+        state.dequeue(ticket);
+        state.writer_active = true;
+        drop(state);
+
+        FairWriteGuard { guard: Some(self.data.write().unwrap()), lock: self }
+    }
+
+    pub fn metrics(&self) -> FairRwLockMetrics {
+        let state = self.state.lock().unwrap();
+        let waiting_writers = state.queue.iter().filter(|(_, kind)| *kind == WaiterKind::Writer).count();
+        FairRwLockMetrics {
+            waiting_readers: state.queue.len() - waiting_writers,
+            waiting_writers,
+            active_readers: state.active_readers,
+            writer_active: state.writer_active,
+        }
+    }
+
+    fn release_read(&self) {
+        self.state.lock().unwrap().active_readers -= 1;
+        self.changed.notify_all();
+    }
+
+    fn release_write(&self) {
+        self.state.lock().unwrap().writer_active = false;
+        self.changed.notify_all();
+    }
+}
+
+/// 公平读锁守卫
+pub struct FairReadGuard<'a, T> {
+    guard: Option<RwLockReadGuard<'a, T>>,
+    lock: &'a FairRwLock<T>,
+}
+
+impl<T> Deref for FairReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<T> Drop for FairReadGuard<'_, T> {
+    fn drop(&mut self) {
+        // 先释放内部锁，再让出准入名额
+        self.guard.take();
+        self.lock.release_read();
+    }
+}
+
+/// 公平写锁守卫
+pub struct FairWriteGuard<'a, T> {
+    guard: Option<RwLockWriteGuard<'a, T>>,
+    lock: &'a FairRwLock<T>,
+}
+
+impl<T> Deref for FairWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for FairWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for FairWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.guard.take();
+        self.lock.release_write();
+    }
+}
+
 /// 银行服务 - 使用隐式锁
 pub struct BankService {
     accounts: Arc<Mutex<HashMap<String, Account>>>,
@@ -668,6 +847,22 @@ pub fn demo() {
         Err(e) => println!("   获取摘要失败: {}", e),
     }
 
+    // 公平读写锁
+    println!("\n9. 公平读写锁演示");
+    let config = Arc::new(FairRwLock::new(0u32));
+    let reader_guard = config.read();
+    let writer_lock = Arc::clone(&config);
+    let writer = thread::spawn(move || {
+        *writer_lock.write() += 1;
+    });
+    while config.metrics().waiting_writers == 0 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    println!("   {}", config.metrics());
+    drop(reader_guard);
+    let _ = writer.join();
+    println!("   写者完成后的值: {}", *config.read());
+
     println!("\n=== 隐式锁模式演示完成 ===");
 
     println!("\n💡 隐式锁模式的优势:");
@@ -754,4 +949,65 @@ mod tests {
         context.remove_lock("resource1");
         assert!(!context.has_lock("resource1"));
     }
-} 
\ No newline at end of file
+
+    fn wait_until<F: Fn() -> bool>(condition: F) {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(std::time::Instant::now() < deadline, "等待条件超时");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_waiting_writer_blocks_new_readers() {
+        let lock = Arc::new(FairRwLock::new(Vec::new()));
+        let first_reader = lock.read();
+
+        let writer_lock = Arc::clone(&lock);
+        let writer = thread::spawn(move || writer_lock.write().push("writer"));
+        wait_until(|| lock.metrics().waiting_writers == 1);
+
+        // 写者等待期间到达的读者不能插队
+        let reader_lock = Arc::clone(&lock);
+        let reader = thread::spawn(move || reader_lock.read().len());
+        wait_until(|| lock.metrics().waiting_readers == 1);
+        assert_eq!(lock.metrics(), FairRwLockMetrics { waiting_readers: 1, waiting_writers: 1, active_readers: 1, writer_active: false });
+
+        drop(first_reader);
+        writer.join().unwrap();
+        // 读者在写者之后进入，看到了写入的数据
+        assert_eq!(reader.join().unwrap(), 1);
+        assert_eq!(lock.metrics(), FairRwLockMetrics { waiting_readers: 0, waiting_writers: 0, active_readers: 0, writer_active: false });
+    }
+
+    #[test]
+    fn test_writer_acquires_under_continuous_read_load() {
+        let lock = Arc::new(FairRwLock::new(0u64));
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        // 多个读者交替持有读锁，任意时刻几乎总有读者在持有
+        let readers: Vec<_> = (0..4).map(|_| {
+            let lock = Arc::clone(&lock);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                let mut reads = 0;
+                while !stop.load(std::sync::atomic::Ordering::SeqCst) {
+                    let _value = lock.read();
+                    thread::sleep(Duration::from_millis(2));
+                    reads += 1;
+                }
+                reads
+            })
+        }).collect();
+        wait_until(|| lock.metrics().active_readers > 0);
+
+        for _ in 0..5 {
+            *lock.write() += 1;
+        }
+        stop.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let total_reads: u32 = readers.into_iter().map(|reader| reader.join().unwrap()).sum();
+        assert_eq!(*lock.read(), 5);
+        assert!(total_reads > 0);
+    }
+}
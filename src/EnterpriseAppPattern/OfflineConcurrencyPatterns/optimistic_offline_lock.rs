@@ -64,6 +64,11 @@ pub enum ConcurrencyError {
     },
     EntityNotFound(String),
     DatabaseError(String),
+    /// 重试多次后仍然发生版本冲突
+    ConflictExhausted {
+        entity_id: String,
+        attempts: u32,
+    },
 }
 
 impl fmt::Display for ConcurrencyError {
@@ -79,12 +84,45 @@ impl fmt::Display for ConcurrencyError {
             ConcurrencyError::DatabaseError(msg) => {
                 write!(f, "数据库错误: {}", msg)
             }
+            ConcurrencyError::ConflictExhausted { entity_id, attempts } => {
+                write!(f, "实体 {} 重试 {} 次后仍然版本冲突", entity_id, attempts)
+            }
         }
     }
 }
 
 impl std::error::Error for ConcurrencyError {}
 
+/// 冲突时自动重试的乐观更新
+///
+/// 每次尝试都重新加载实体、重新执行修改再保存；保存返回版本冲突时重试，
+/// 最多尝试 `max_attempts` 次（至少一次），仍然冲突则返回 `ConflictExhausted`。
+/// 加载、修改或保存返回的其他错误立即返回。
+pub fn with_optimistic_retry<T, L, M, S>(
+    max_attempts: u32,
+    mut load_fn: L,
+    mut mutate_fn: M,
+    mut save_fn: S,
+) -> Result<T, ConcurrencyError>
+where
+    L: FnMut() -> Result<T, ConcurrencyError>,
+    M: FnMut(&mut T) -> Result<(), ConcurrencyError>,
+    S: FnMut(&mut T) -> Result<(), ConcurrencyError>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut conflicted_entity = String::new();
+    for _ in 0..max_attempts {
+        let mut entity = load_fn()?;
+        mutate_fn(&mut entity)?;
+        match save_fn(&mut entity) {
+            Ok(()) => return Ok(entity),
+            Err(ConcurrencyError::OptimisticLockFailure { entity_id, .. }) => conflicted_entity = entity_id,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(ConcurrencyError::ConflictExhausted { entity_id: conflicted_entity, attempts: max_attempts })
+}
+
 /// 可版本控制的实体接口
 pub trait Versionable {
     fn get_id(&self) -> String;
@@ -254,6 +292,19 @@ impl<T: Versionable + Clone> OptimisticOfflineLockManager<T> {
         }
     }
 
+    /// 加载、修改并保存实体，版本冲突时自动重试
+    pub fn update_with_retry<M>(&self, id: &str, user_id: &str, max_attempts: u32, mutate_fn: M) -> Result<T, ConcurrencyError>
+    where
+        M: FnMut(&mut T) -> Result<(), ConcurrencyError>,
+    {
+        with_optimistic_retry(
+            max_attempts,
+            || self.load(id),
+            mutate_fn,
+            |entity| self.save(entity, user_id.to_string()),
+        )
+    }
+
     /// 获取统计信息
     pub fn get_statistics(&self) -> HashMap<String, usize> {
         let storage = self.storage.lock().unwrap();
@@ -398,8 +449,28 @@ pub fn demo() {
         Err(e) => println!("   转账失败: {}", e),
     }
     
+    // 冲突自动重试：第一次保存前有人抢先修改了账户
+    println!("\n6. 冲突自动重试");
+    let mut concurrent_changed = false;
+    let result = with_optimistic_retry(
+        3,
+        || tx_manager.get_account("acc2"),
+        |account| account.deposit(50.0).map_err(ConcurrencyError::DatabaseError),
+        |account| {
+            if !concurrent_changed {
+                concurrent_changed = true;
+                let _ = tx_manager.update_account("acc2", Some("李四(并发更新)".to_string()), None, "user3".to_string());
+            }
+            tx_manager.account_manager.save(account, "user2".to_string())
+        },
+    );
+    match result {
+        Ok(account) => println!("   重试后保存成功: {}", account),
+        Err(e) => println!("   保存失败: {}", e),
+    }
+
     // 显示最终状态
-    println!("\n6. 最终账户状态");
+    println!("\n7. 最终账户状态");
     for account in tx_manager.list_accounts() {
         println!("   {}", account);
     }
@@ -447,4 +518,98 @@ mod tests {
         account2.name = "User2 Update".to_string();
         assert!(manager.save(&mut account2, "user2".to_string()).is_err());
     }
-} 
\ No newline at end of file
+
+    fn manager_with_account(id: &str) -> OptimisticOfflineLockManager<Account> {
+        let manager = OptimisticOfflineLockManager::new(300);
+        manager.create(Account::new(id.to_string(), "Test".to_string(), "test@example.com".to_string(), "user1".to_string())).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_retry_succeeds_after_concurrent_change() {
+        let manager = manager_with_account("retry1");
+        let mut saves = 0;
+        let mut mutations = 0;
+
+        let saved = with_optimistic_retry(
+            3,
+            || manager.load("retry1"),
+            |account| {
+                mutations += 1;
+                account.deposit(10.0).map_err(ConcurrencyError::DatabaseError)
+            },
+            |account| {
+                saves += 1;
+                if saves == 1 {
+                    // 模拟另一个用户在本次保存之前提交了修改
+                    let mut other = manager.load("retry1").unwrap();
+                    other.deposit(5.0).unwrap();
+                    manager.save(&mut other, "user2".to_string()).unwrap();
+                }
+                manager.save(account, "user1".to_string())
+            },
+        ).unwrap();
+
+        // 第二次尝试基于最新数据重新执行了修改，并发修改没有丢失
+        assert_eq!((saves, mutations), (2, 2));
+        assert_eq!(saved.balance, 15.0);
+        assert_eq!(manager.load("retry1").unwrap().balance, 15.0);
+        assert_eq!(saved.version.number, 3);
+    }
+
+    #[test]
+    fn test_persistent_conflicts_exhaust_attempts() {
+        let manager = manager_with_account("retry2");
+        let mut attempts = 0;
+
+        let result = with_optimistic_retry(
+            3,
+            || manager.load("retry2"),
+            |account| {
+                attempts += 1;
+                account.deposit(1.0).map_err(ConcurrencyError::DatabaseError)
+            },
+            |account| {
+                let mut other = manager.load("retry2").unwrap();
+                manager.save(&mut other, "user2".to_string()).unwrap();
+                manager.save(account, "user1".to_string())
+            },
+        );
+
+        assert_eq!(attempts, 3);
+        assert!(matches!(result, Err(ConcurrencyError::ConflictExhausted { ref entity_id, attempts: 3 }) if entity_id == "retry2"));
+        assert_eq!(manager.load("retry2").unwrap().balance, 0.0);
+
+        // 修改本身失败时不会重试
+        let insufficient = manager.update_with_retry("retry2", "user1", 3, |account| {
+            account.withdraw(100.0).map_err(ConcurrencyError::DatabaseError)
+        });
+        assert!(matches!(insufficient, Err(ConcurrencyError::DatabaseError(_))));
+    }
+
+    #[test]
+    fn test_zero_attempts_still_tries_once() {
+        let manager = manager_with_account("retry3");
+
+        let saved = with_optimistic_retry(
+            0,
+            || manager.load("retry3"),
+            |account| account.deposit(10.0).map_err(ConcurrencyError::DatabaseError),
+            |account| manager.save(account, "user1".to_string()),
+        ).unwrap();
+        assert_eq!(saved.balance, 10.0);
+
+        // 冲突时报告实际尝试的次数和冲突的实体
+        let result = with_optimistic_retry(
+            0,
+            || manager.load("retry3"),
+            |_| Ok(()),
+            |account| {
+                let mut other = manager.load("retry3").unwrap();
+                manager.save(&mut other, "user2".to_string()).unwrap();
+                manager.save(account, "user1".to_string())
+            },
+        );
+        assert!(matches!(result, Err(ConcurrencyError::ConflictExhausted { ref entity_id, attempts: 1 }) if entity_id == "retry3"));
+    }
+}
@@ -18,9 +18,9 @@
 //! - 用户编辑时间相对较短的场景
 //! - 需要防止丢失更新的情况
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
 
 /// 锁信息
@@ -429,6 +429,92 @@ impl DocumentEditingService {
     }
 }
 
+/// 资源标识
+///
+/// 按字典序比较，`ResourceLockTable::acquire_all` 据此确定全局加锁顺序。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceId(pub String);
+
+impl ResourceId {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// 阻塞式资源锁表
+///
+/// 同时锁定多个资源时，若各线程按不同顺序加锁就可能形成循环等待。
+/// `acquire_all` 先对资源排序去重，所有调用方都按同一全局顺序加锁，
+/// 从而消除死锁的必要条件之一。
+pub struct ResourceLockTable {
+    held: Mutex<HashSet<ResourceId>>,
+    released: Condvar,
+}
+
+impl ResourceLockTable {
+    pub fn new() -> Self {
+        Self {
+            held: Mutex::new(HashSet::new()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// 按资源id顺序阻塞获取全部资源，返回的守卫在drop时释放所有锁
+    pub fn acquire_all(&self, resources: &[ResourceId]) -> MultiLockGuard<'_> {
+        let ordered: BTreeSet<ResourceId> = resources.iter().cloned().collect();
+        let mut acquired = Vec::with_capacity(ordered.len());
+
+        for resource in ordered {
+            let mut held = self.held.lock().unwrap();
+            while held.contains(&resource) {
+                held = self.released.wait(held).unwrap();
+            }
+            held.insert(resource.clone());
+            acquired.push(resource);
+        }
+
+        MultiLockGuard { table: self, resources: acquired }
+    }
+
+    /// 资源当前是否被锁定
+    pub fn is_locked(&self, resource: &ResourceId) -> bool {
+        self.held.lock().unwrap().contains(resource)
+    }
+
+    fn release(&self, resources: &[ResourceId]) {
+        let mut held = self.held.lock().unwrap();
+        for resource in resources.iter().rev() {
+            held.remove(resource);
+        }
+        self.released.notify_all();
+    }
+}
+
+/// 多资源锁守卫（RAII）
+pub struct MultiLockGuard<'a> {
+    table: &'a ResourceLockTable,
+    resources: Vec<ResourceId>,
+}
+
+impl MultiLockGuard<'_> {
+    /// 实际加锁的顺序
+    pub fn resources(&self) -> &[ResourceId] {
+        &self.resources
+    }
+}
+
+impl Drop for MultiLockGuard<'_> {
+    fn drop(&mut self) {
+        self.table.release(&self.resources);
+    }
+}
+
 /// 演示悲观离线锁模式
 pub fn demo() {
     println!("=== 悲观离线锁模式演示 ===\n");
@@ -502,6 +588,16 @@ pub fn demo() {
     let cleaned = edit_service.cleanup_expired_locks();
     println!("   清理了 {} 个过期锁", cleaned);
     
+    // 有序获取多个资源锁
+    println!("\n9. 按全局顺序同时锁定多个资源");
+    let lock_table = ResourceLockTable::new();
+    {
+        let guard = lock_table.acquire_all(&[ResourceId::new("doc2"), ResourceId::new("doc1")]);
+        let order: Vec<String> = guard.resources().iter().map(|r| r.to_string()).collect();
+        println!("   加锁顺序: {}", order.join(" -> "));
+    }
+    println!("   守卫释放后 doc1 是否锁定: {}", lock_table.is_locked(&ResourceId::new("doc1")));
+    
     println!("\n=== 悲观离线锁模式演示完成 ===");
 }
 
@@ -536,4 +632,49 @@ mod tests {
         // 其他用户现在可以获取锁
         assert!(manager.acquire_lock("doc1", "user2", LockType::Write, None).is_ok());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_acquire_all_orders_and_releases_on_drop() {
+        let table = ResourceLockTable::new();
+        let a = ResourceId::new("a");
+        let b = ResourceId::new("b");
+
+        {
+            let guard = table.acquire_all(&[b.clone(), a.clone(), b.clone()]);
+            assert_eq!(guard.resources(), &[a.clone(), b.clone()]);
+            assert!(table.is_locked(&a));
+            assert!(table.is_locked(&b));
+        }
+
+        assert!(!table.is_locked(&a));
+        assert!(!table.is_locked(&b));
+    }
+
+    #[test]
+    fn test_acquire_all_overlapping_sets_in_opposite_order_do_not_deadlock() {
+        use std::sync::mpsc;
+        use std::thread;
+
+        let table = Arc::new(ResourceLockTable::new());
+        let forward: Vec<ResourceId> = ["r1", "r2", "r3"].iter().map(|id| ResourceId::new(id)).collect();
+        let backward: Vec<ResourceId> = forward.iter().rev().cloned().collect();
+
+        let (done_tx, done_rx) = mpsc::channel();
+        for resources in [forward, backward] {
+            let table = Arc::clone(&table);
+            let done_tx = done_tx.clone();
+            thread::spawn(move || {
+                for _ in 0..200 {
+                    let _guard = table.acquire_all(&resources);
+                    thread::yield_now();
+                }
+                done_tx.send(()).unwrap();
+            });
+        }
+
+        for _ in 0..2 {
+            done_rx.recv_timeout(Duration::from_secs(10)).expect("疑似死锁: 线程未在限定时间内完成");
+        }
+        assert!(!table.is_locked(&ResourceId::new("r1")));
+    }
+}
@@ -19,12 +19,20 @@
  * - 购物车状态管理
  * - 用户偏好设置
  * - 临时的交互状态
+ *
+ * 会话结构演进：
+ * 状态保存在客户端，服务端升级后仍会收到旧结构的令牌。编码时写入结构版本，
+ * 解码时通过 `MigrationRunner` 把旧版本的会话数据逐步升级到当前结构。
  */
 
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+use serde_json::{Map, Value};
+
+use crate::EnterpriseAppPattern::ObjectRelationalStructuralPatterns::schema_migration::{Migration, MigrationRunner};
+
 /// 客户端会话状态错误
 #[derive(Debug)]
 pub enum ClientSessionError {
@@ -140,11 +148,21 @@ impl Default for SessionData {
 /// 客户端状态编码器
 pub struct ClientStateEncoder {
     secret_key: String,
+    migrations: MigrationRunner,
 }
 
 impl ClientStateEncoder {
     pub fn new(secret_key: String) -> Self {
-        Self { secret_key }
+        Self {
+            secret_key,
+            migrations: MigrationRunner::new(1),
+        }
+    }
+    
+    /// 设置会话结构迁移，编码时写入运行器的最新版本，解码时把旧版本数据升级到该版本
+    pub fn with_migrations(mut self, migrations: MigrationRunner) -> Self {
+        self.migrations = migrations;
+        self
     }
     
     /// 编码会话数据为客户端字符串
@@ -156,6 +174,7 @@ impl ClientStateEncoder {
         
         // 序列化数据（简化实现）
         let mut encoded = String::new();
+        encoded.push_str(&format!("sv:{};", self.migrations.latest_version()));
         encoded.push_str(&format!("ts:{};", session.timestamp));
         encoded.push_str(&format!("cs:{};", session.checksum));
         
@@ -181,6 +200,8 @@ impl ClientStateEncoder {
         // 解析数据
         let mut session = SessionData::new();
         session.data.clear(); // 清空默认数据
+        // 没有结构版本的旧令牌视为 v1
+        let mut schema_version = 1;
         
         for part in decrypted.split(';') {
             if part.is_empty() {
@@ -196,6 +217,10 @@ impl ClientStateEncoder {
             let value = kv[1];
             
             match key {
+                "sv" => {
+                    schema_version = value.parse()
+                        .map_err(|_| ClientSessionError::DecodingError("结构版本解析失败".to_string()))?;
+                }
                 "ts" => {
                     session.timestamp = value.parse()
                         .map_err(|_| ClientSessionError::DecodingError("时间戳解析失败".to_string()))?;
@@ -214,9 +239,35 @@ impl ClientStateEncoder {
             return Err(ClientSessionError::SecurityError("数据完整性验证失败".to_string()));
         }
         
+        if schema_version != self.migrations.latest_version() {
+            self.migrate(&mut session, schema_version)?;
+        }
+        
         Ok(session)
     }
     
+    /// 把旧结构的会话数据升级到最新版本，升级后重新计算校验和
+    fn migrate(&self, session: &mut SessionData, schema_version: u32) -> Result<(), ClientSessionError> {
+        let payload: Map<String, Value> = session.data.drain()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect();
+        
+        let (upgraded, _) = self.migrations.migrate(Value::Object(payload), schema_version)
+            .map_err(|e| ClientSessionError::DecodingError(format!("会话结构迁移失败: {}", e)))?;
+        let Value::Object(fields) = upgraded else {
+            return Err(ClientSessionError::DecodingError("迁移后的会话数据不是对象".to_string()));
+        };
+        
+        session.data = fields.into_iter()
+            .map(|(key, value)| match value {
+                Value::String(text) => (key, text),
+                other => (key, other.to_string()),
+            })
+            .collect();
+        session.update_checksum();
+        Ok(())
+    }
+    
     /// 简单加密（实际应用中应使用AES等算法）
     fn simple_encrypt(&self, data: &str) -> Result<String, ClientSessionError> {
         let key_bytes = self.secret_key.as_bytes();
@@ -617,6 +668,27 @@ pub fn demo() {
     println!("\n移除智能手机后的购物车总价: ¥{:.2}", 
              shopping_cart.calculate_total(&cart_session));
     
+    println!("{}", "=".repeat(50));
+    
+    // 6. 会话结构迁移
+    println!("6. 会话结构迁移:");
+    let legacy_encoder = ClientStateEncoder::new("schema_secret".to_string());
+    let mut legacy_session = SessionData::new();
+    legacy_session.set("name", "李四");
+    
+    match legacy_encoder.encode(&legacy_session) {
+        Ok(legacy_token) => {
+            let current_encoder = ClientStateEncoder::new("schema_secret".to_string())
+                .with_migrations(session_schema_v3());
+            match current_encoder.decode(&legacy_token) {
+                Ok(upgraded) => println!("v1 会话升级到 v3: full_name={}, locale={}",
+                                         upgraded.get("full_name").unwrap(), upgraded.get("locale").unwrap()),
+                Err(e) => println!("会话升级失败: {}", e),
+            }
+        }
+        Err(e) => println!("编码失败: {}", e),
+    }
+    
     println!("\n=== Client Session State模式演示完成 ===");
     
     // 输出模式总结
@@ -641,7 +713,72 @@ pub fn demo() {
     println!("5. 无状态服务架构");
 }
 
+/// 演示用的会话结构：v1 → v2 把 name 改名为 full_name，v2 → v3 新增 locale
+fn session_schema_v3() -> MigrationRunner {
+    MigrationRunner::new(3)
+        .with_migration(Migration::new(1, 2, "name 改名为 full_name", |mut payload| {
+            let fields = payload.as_object_mut().ok_or("会话数据不是对象")?;
+            let name = fields.remove("name").ok_or("缺少 name 字段")?;
+            fields.insert("full_name".to_string(), name);
+            Ok(payload)
+        }))
+        .and_then(|runner| runner.with_migration(Migration::new(2, 3, "新增 locale", |mut payload| {
+            payload["locale"] = Value::String("zh-CN".to_string());
+            Ok(payload)
+        })))
+        .expect("演示迁移的版本连续且不重复")
+}
+
 /// 客户端会话状态模式演示（包装函数）
 pub fn demo_client_session_state() {
     demo();
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_session_is_upgraded_through_v2_to_v3_on_decode() {
+        let mut session = SessionData::new();
+        session.set("name", "张三");
+        session.set("step", "2");
+        let legacy_token = ClientStateEncoder::new("secret".to_string()).encode(&session).unwrap();
+
+        let encoder = ClientStateEncoder::new("secret".to_string()).with_migrations(session_schema_v3());
+        let upgraded = encoder.decode(&legacy_token).unwrap();
+
+        assert_eq!(upgraded.get("full_name").map(String::as_str), Some("张三"));
+        assert_eq!(upgraded.get("locale").map(String::as_str), Some("zh-CN"));
+        assert_eq!(upgraded.get("step").map(String::as_str), Some("2"));
+        assert!(upgraded.get("name").is_none());
+        assert!(upgraded.validate_checksum());
+
+        // 重新编码后写入最新版本，再次解码不会重复迁移
+        let current_token = encoder.encode(&upgraded).unwrap();
+        assert_eq!(encoder.decode(&current_token).unwrap(), upgraded);
+    }
+
+    #[test]
+    fn test_missing_session_migration_is_rejected() {
+        let mut session = SessionData::new();
+        session.set("name", "李四");
+        let legacy_token = ClientStateEncoder::new("secret".to_string()).encode(&session).unwrap();
+
+        let gapped = MigrationRunner::new(3)
+            .with_migration(Migration::new(2, 3, "新增 locale", Ok))
+            .unwrap();
+        let encoder = ClientStateEncoder::new("secret".to_string()).with_migrations(gapped);
+        match encoder.decode(&legacy_token) {
+            Err(ClientSessionError::DecodingError(msg)) => assert!(msg.contains("缺少从 v1 开始的迁移"), "{}", msg),
+            other => panic!("缺少迁移时应该解码失败: {:?}", other),
+        }
+
+        // 比当前结构更新的令牌同样拒绝
+        let newer_token = ClientStateEncoder::new("secret".to_string())
+            .with_migrations(session_schema_v3())
+            .encode(&session)
+            .unwrap();
+        assert!(matches!(ClientStateEncoder::new("secret".to_string()).decode(&newer_token), Err(ClientSessionError::DecodingError(_))));
+    }
+}
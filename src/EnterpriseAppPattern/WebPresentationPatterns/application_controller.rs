@@ -6,6 +6,8 @@
 use std::collections::HashMap;
 use std::fmt;
 
+use crate::GoFDesignPattern::BehavioralPatterns::fsm::{always, Fsm};
+
 /// 应用控制器错误类型
 #[derive(Debug)]
 pub enum ApplicationControllerError {
@@ -136,75 +138,60 @@ pub enum Command {
     ManageProducts,
 }
 
-/// 应用流程状态机
+/// 应用流程状态机，转换表由通用的 `Fsm` 维护
 pub struct ApplicationStateMachine {
-    current_state: ApplicationState,
-    transitions: HashMap<(ApplicationState, String), ApplicationState>,
+    fsm: Fsm<ApplicationState, String>,
 }
 
 impl ApplicationStateMachine {
     pub fn new() -> Self {
-        let mut transitions = HashMap::new();
-        
-        // 定义状态转换规则
-        // 未登录状态
-        transitions.insert((ApplicationState::NotLoggedIn, "login".to_string()), ApplicationState::LoggedIn);
-        
-        // 已登录状态
-        transitions.insert((ApplicationState::LoggedIn, "logout".to_string()), ApplicationState::NotLoggedIn);
-        transitions.insert((ApplicationState::LoggedIn, "admin".to_string()), ApplicationState::AdminMode);
-        transitions.insert((ApplicationState::LoggedIn, "checkout".to_string()), ApplicationState::CheckingOut);
-        
-        // 管理员模式
-        transitions.insert((ApplicationState::AdminMode, "logout".to_string()), ApplicationState::NotLoggedIn);
-        transitions.insert((ApplicationState::AdminMode, "user_mode".to_string()), ApplicationState::LoggedIn);
-        
-        // 结账流程
-        transitions.insert((ApplicationState::CheckingOut, "payment".to_string()), ApplicationState::PaymentPending);
-        transitions.insert((ApplicationState::CheckingOut, "cancel".to_string()), ApplicationState::LoggedIn);
-        
-        // 支付流程
-        transitions.insert((ApplicationState::PaymentPending, "confirm".to_string()), ApplicationState::OrderCompleted);
-        transitions.insert((ApplicationState::PaymentPending, "cancel".to_string()), ApplicationState::LoggedIn);
-        
-        // 订单完成
-        transitions.insert((ApplicationState::OrderCompleted, "continue".to_string()), ApplicationState::LoggedIn);
-        
-        Self {
-            current_state: ApplicationState::NotLoggedIn,
-            transitions,
-        }
+        use ApplicationState::*;
+
+        let rules = [
+            // 未登录状态
+            (NotLoggedIn, "login", LoggedIn),
+            // 已登录状态
+            (LoggedIn, "logout", NotLoggedIn),
+            (LoggedIn, "admin", AdminMode),
+            (LoggedIn, "checkout", CheckingOut),
+            // 管理员模式
+            (AdminMode, "logout", NotLoggedIn),
+            (AdminMode, "user_mode", LoggedIn),
+            // 结账流程
+            (CheckingOut, "payment", PaymentPending),
+            (CheckingOut, "cancel", LoggedIn),
+            // 支付流程
+            (PaymentPending, "confirm", OrderCompleted),
+            (PaymentPending, "cancel", LoggedIn),
+            // 订单完成
+            (OrderCompleted, "continue", LoggedIn),
+        ];
+
+        let fsm = rules.into_iter().fold(Fsm::new(NotLoggedIn), |fsm, (from, event, to)| {
+            fsm.transition(from, event.to_string(), to, always)
+        });
+
+        Self { fsm }
     }
 
     pub fn get_current_state(&self) -> &ApplicationState {
-        &self.current_state
+        self.fsm.current()
     }
 
     pub fn transition(&mut self, event: &str) -> Result<&ApplicationState, ApplicationControllerError> {
-        let key = (self.current_state.clone(), event.to_string());
-        
-        match self.transitions.get(&key) {
-            Some(new_state) => {
-                println!("状态转换: {:?} --[{}]--> {:?}", self.current_state, event, new_state);
-                self.current_state = new_state.clone();
-                Ok(&self.current_state)
-            }
-            None => Err(ApplicationControllerError::StateTransitionError(
-                format!("无法从状态 {:?} 通过事件 '{}' 进行转换", self.current_state, event)
-            ))
-        }
+        let from = self.fsm.current().clone();
+        let to = self.fsm.handle(event.to_string())
+            .map_err(|e| ApplicationControllerError::StateTransitionError(e.to_string()))?;
+        println!("状态转换: {:?} --[{}]--> {:?}", from, event, to);
+        Ok(self.fsm.current())
     }
 
     pub fn can_transition(&self, event: &str) -> bool {
-        let key = (self.current_state.clone(), event.to_string());
-        self.transitions.contains_key(&key)
+        self.fsm.can_handle(&event.to_string())
     }
 
     pub fn get_available_events(&self) -> Vec<String> {
-        self.transitions.keys()
-            .filter(|(state, _)| *state == self.current_state)
-            .map(|(_, event)| event.clone())
-            .collect()
+        self.fsm.available_events()
     }
 }
 
@@ -52,6 +52,7 @@
 //! - **适用**: 需要支持多种客户端，同一内容多种显示格式的应用
 
 pub mod model_view_controller;
+#[cfg(feature = "serde")]
 pub mod page_controller;
 pub mod front_controller;
 pub mod template_view;
@@ -60,6 +61,7 @@ pub mod application_controller;
 pub mod two_step_view;
 
 pub use model_view_controller::*;
+#[cfg(feature = "serde")]
 pub use page_controller::*;
 pub use front_controller::*;
 pub use template_view::*;
@@ -161,6 +163,7 @@ pub fn demo_all() {
     model_view_controller::demo();
     println!("\n{}", "-".repeat(80));
     
+    #[cfg(feature = "serde")]
     page_controller::demo();
     println!("\n{}", "-".repeat(80));
     
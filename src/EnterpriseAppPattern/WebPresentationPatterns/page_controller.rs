@@ -19,10 +19,14 @@
 //! - 需要页面级定制的系统
 //! - 团队成员技能水平参差不齐的项目
 
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, Mutex};
 
+use crate::EnterpriseAppPattern::BasePatterns::Page;
+
 /// 页面控制器错误
 #[derive(Debug)]
 pub enum PageControllerError {
@@ -54,6 +58,10 @@ pub struct HttpRequest {
     pub query_params: HashMap<String, String>,
     pub form_data: HashMap<String, String>,
     pub session: HashMap<String, String>,
+    /// 路由匹配时从 `/users/:id` 这类模式中提取的路径参数
+    pub path_params: HashMap<String, String>,
+    /// 原始请求体，例如JSON
+    pub body: Option<String>,
 }
 
 impl HttpRequest {
@@ -65,9 +73,16 @@ impl HttpRequest {
             query_params: HashMap::new(),
             form_data: HashMap::new(),
             session: HashMap::new(),
+            path_params: HashMap::new(),
+            body: None,
         }
     }
 
+    /// 获取路径参数
+    pub fn path_param(&self, name: &str) -> Option<&str> {
+        self.path_params.get(name).map(|value| value.as_str())
+    }
+
     pub fn with_query_param(mut self, key: String, value: String) -> Self {
         self.query_params.insert(key, value);
         self
@@ -82,6 +97,29 @@ impl HttpRequest {
         self.session = session;
         self
     }
+
+    /// 设置JSON请求体，同时标记 `Content-Type: application/json`
+    pub fn with_json_body(mut self, body: String) -> Self {
+        self.headers.insert("Content-Type".to_string(), "application/json".to_string());
+        self.body = Some(body);
+        self
+    }
+
+    /// 请求体是否声明为JSON
+    pub fn is_json(&self) -> bool {
+        self.headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("Content-Type") && value.starts_with("application/json")
+        })
+    }
+
+    /// 把请求体反序列化为 `T`，请求体为空或不是合法JSON时返回验证错误
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, PageControllerError> {
+        let body = self.body.as_deref()
+            .filter(|body| !body.trim().is_empty())
+            .ok_or_else(|| PageControllerError::ValidationError("请求体为空".to_string()))?;
+        serde_json::from_str(body)
+            .map_err(|e| PageControllerError::ValidationError(format!("无效的JSON请求体: {}", e)))
+    }
 }
 
 /// HTTP响应
@@ -230,6 +268,13 @@ impl DataService {
         self.articles.lock().unwrap().values().cloned().collect()
     }
 
+    /// 按ID升序分页获取文章，页码从1开始
+    pub fn get_articles_page(&self, page: usize, size: usize) -> Page<Article> {
+        let mut articles = self.get_all_articles();
+        articles.sort_by_key(|article| article.id);
+        Page::slice(articles, page, size)
+    }
+
     pub fn get_articles_by_author(&self, author_id: u32) -> Vec<Article> {
         self.articles.lock().unwrap()
             .values()
@@ -484,7 +529,10 @@ impl PageController for UserDetailPageController {
     fn handle_get(&self, request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
         println!("   👤 处理用户详情GET请求: {}", request.path);
         
-        let user_id = self.extract_user_id(&request.path)
+        // 经路由器分发时使用路径参数，直接调用时从路径中解析
+        let user_id = request.path_param("id")
+            .and_then(|id| id.parse().ok())
+            .or_else(|| self.extract_user_id(&request.path))
             .ok_or_else(|| PageControllerError::ValidationError("无效的用户ID".to_string()))?;
 
         let user = self.data_service.get_user(user_id)
@@ -500,6 +548,26 @@ impl PageController for UserDetailPageController {
     }
 }
 
+/// 文章预览的最大字节数（不含省略号）
+const PREVIEW_MAX_BYTES: usize = 100;
+
+/// 文章列表每页显示的文章数
+const ARTICLES_PER_PAGE: usize = 10;
+
+/// 截取不超过 `max_bytes` 字节的预览，截断点落在字符边界上，被截断时补 `...`
+fn preview(content: &str, max_bytes: usize) -> String {
+    if content.len() <= max_bytes {
+        return content.to_string();
+    }
+    let end = content
+        .char_indices()
+        .map(|(index, ch)| index + ch.len_utf8())
+        .take_while(|&end| end <= max_bytes)
+        .last()
+        .unwrap_or(0);
+    format!("{}...", &content[..end])
+}
+
 /// 文章列表页面控制器
 pub struct ArticleListPageController {
     data_service: Arc<DataService>,
@@ -510,9 +578,12 @@ impl ArticleListPageController {
         Self { data_service }
     }
 
-    fn render_article_list(&self, articles: &[Article]) -> String {
+    fn render_article_list(&self, articles: &Page<Article>) -> String {
         let mut article_cards = String::new();
-        for article in articles {
+        if articles.is_empty() {
+            article_cards.push_str(r#"<p class="meta">暂无文章</p>"#);
+        }
+        for article in &articles.items {
             let author = self.data_service.get_user(article.author_id)
                 .map(|u| u.username)
                 .unwrap_or_else(|| "未知".to_string());
@@ -530,11 +601,7 @@ impl ArticleListPageController {
                 article.author_id,
                 author,
                 article.created_at,
-                if article.content.len() > 100 {
-                    format!("{}...", &article.content[..100])
-                } else {
-                    article.content.clone()
-                }
+                preview(&article.content, PREVIEW_MAX_BYTES)
             ));
         }
 
@@ -556,6 +623,8 @@ impl ArticleListPageController {
                     .preview {{ margin: 15px 0 0 0; line-height: 1.6; }}
                     .back-btn {{ display: inline-block; margin-bottom: 20px; padding: 8px 16px; background: #666; color: white; text-decoration: none; border-radius: 4px; }}
                     .new-btn {{ display: inline-block; margin-left: 10px; padding: 8px 16px; background: #4CAF50; color: white; text-decoration: none; border-radius: 4px; }}
+                    .pagination {{ margin-top: 20px; color: #666; }}
+                    .pagination a {{ margin-left: 10px; color: #2196f3; text-decoration: none; }}
                 </style>
             </head>
             <body>
@@ -564,19 +633,37 @@ impl ArticleListPageController {
                     <a href="/articles/new" class="new-btn">✏️ 写文章</a>
                     <h1>📰 文章列表</h1>
                     {}
+                    {}
                 </div>
             </body>
             </html>
             "#,
-            article_cards
+            article_cards,
+            self.render_pagination(articles)
         )
     }
+
+    fn render_pagination(&self, articles: &Page<Article>) -> String {
+        let mut links = vec![format!("第 {}/{} 页", articles.page, articles.total_pages.max(1))];
+        if articles.has_prev() {
+            links.push(format!(r#"<a href="/articles?page={}">上一页</a>"#, articles.page - 1));
+        }
+        if articles.has_next() {
+            links.push(format!(r#"<a href="/articles?page={}">下一页</a>"#, articles.page + 1));
+        }
+        format!(r#"<div class="pagination">{}</div>"#, links.join(" "))
+    }
 }
 
 impl PageController for ArticleListPageController {
-    fn handle_get(&self, _request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
+    fn handle_get(&self, request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
         println!("   📰 处理文章列表GET请求");
-        let articles = self.data_service.get_all_articles();
+        let page = match request.query_params.get("page") {
+            Some(value) => value.parse::<usize>().ok().filter(|page| *page > 0)
+                .ok_or_else(|| PageControllerError::ValidationError(format!("无效的页码: {}", value)))?,
+            None => 1,
+        };
+        let articles = self.data_service.get_articles_page(page, ARTICLES_PER_PAGE);
         let content = self.render_article_list(&articles);
         Ok(HttpResponse::ok(content))
     }
@@ -655,6 +742,32 @@ impl NewArticlePageController {
     }
 }
 
+/// 新建文章的输入，来自表单字段或JSON请求体
+#[derive(Debug, Deserialize)]
+struct NewArticleInput {
+    title: String,
+    content: String,
+    author_id: u32,
+}
+
+impl NewArticleInput {
+    fn from_form(form_data: &HashMap<String, String>) -> Result<Self, PageControllerError> {
+        let title = form_data.get("title")
+            .ok_or_else(|| PageControllerError::ValidationError("缺少文章标题".to_string()))?;
+        
+        let content = form_data.get("content")
+            .ok_or_else(|| PageControllerError::ValidationError("缺少文章内容".to_string()))?;
+        
+        let author_id_str = form_data.get("author_id")
+            .ok_or_else(|| PageControllerError::ValidationError("缺少作者ID".to_string()))?;
+        
+        let author_id: u32 = author_id_str.parse()
+            .map_err(|_| PageControllerError::ValidationError("无效的作者ID".to_string()))?;
+
+        Ok(Self { title: title.clone(), content: content.clone(), author_id })
+    }
+}
+
 impl PageController for NewArticlePageController {
     fn handle_get(&self, _request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
         println!("   ✏️ 处理新建文章GET请求");
@@ -665,17 +778,11 @@ impl PageController for NewArticlePageController {
     fn handle_post(&self, request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
         println!("   ✏️ 处理新建文章POST请求");
         
-        let title = request.form_data.get("title")
-            .ok_or_else(|| PageControllerError::ValidationError("缺少文章标题".to_string()))?;
-        
-        let content = request.form_data.get("content")
-            .ok_or_else(|| PageControllerError::ValidationError("缺少文章内容".to_string()))?;
-        
-        let author_id_str = request.form_data.get("author_id")
-            .ok_or_else(|| PageControllerError::ValidationError("缺少作者ID".to_string()))?;
-        
-        let author_id: u32 = author_id_str.parse()
-            .map_err(|_| PageControllerError::ValidationError("无效的作者ID".to_string()))?;
+        let NewArticleInput { title, content, author_id } = if request.is_json() {
+            request.json()?
+        } else {
+            NewArticleInput::from_form(&request.form_data)?
+        };
 
         // 验证输入
         if title.trim().is_empty() {
@@ -689,11 +796,7 @@ impl PageController for NewArticlePageController {
         }
 
         // 创建文章
-        let article = self.data_service.create_article(
-            title.clone(),
-            content.clone(),
-            author_id
-        );
+        let article = self.data_service.create_article(title, content, author_id);
 
         println!("     ✅ 文章创建成功: {} (ID: {})", article.title, article.id);
         
@@ -779,52 +882,119 @@ impl PageController for AboutPageController {
     }
 }
 
+/// 路由模式中的一段
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    /// 必须逐字匹配的路径段
+    Literal(String),
+    /// `:name` 形式的路径参数，匹配任意非空路径段
+    Param(String),
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// 路由表中的一条路由
+struct Route {
+    method: String,
+    segments: Vec<PathSegment>,
+    controller: Box<dyn PageController>,
+}
+
+impl Route {
+    /// 匹配成功时返回提取到的路径参数
+    fn matches(&self, method: &str, path: &str) -> Option<HashMap<String, String>> {
+        if !self.method.eq_ignore_ascii_case(method) {
+            return None;
+        }
+
+        let parts: Vec<&str> = split_path(path).collect();
+        if parts.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut params = HashMap::new();
+        for (segment, part) in self.segments.iter().zip(parts) {
+            match segment {
+                PathSegment::Literal(literal) if literal == part => {}
+                PathSegment::Literal(_) => return None,
+                PathSegment::Param(name) => {
+                    params.insert(name.clone(), part.to_string());
+                }
+            }
+        }
+        Some(params)
+    }
+}
+
 /// 页面控制器路由器
+///
+/// 路由按注册顺序匹配，第一条匹配的路由生效；
+/// 因此 `/articles/new` 这样的固定路径应先于 `/articles/:id` 注册。
 pub struct PageControllerRouter {
     data_service: Arc<DataService>,
+    routes: Vec<Route>,
 }
 
 impl PageControllerRouter {
+    /// 创建注册了内置页面的路由器
     pub fn new() -> Self {
-        Self {
-            data_service: Arc::new(DataService::new()),
-        }
+        let data_service = Arc::new(DataService::new());
+        let mut router = Self {
+            data_service: data_service.clone(),
+            routes: Vec::new(),
+        };
+
+        router.register("GET", "/", Box::new(HomePageController::new(data_service.clone())));
+        router.register("GET", "/users", Box::new(UserListPageController::new(data_service.clone())));
+        router.register("GET", "/users/:id", Box::new(UserDetailPageController::new(data_service.clone())));
+        router.register("GET", "/articles", Box::new(ArticleListPageController::new(data_service.clone())));
+        router.register("GET", "/articles/new", Box::new(NewArticlePageController::new(data_service.clone())));
+        router.register("POST", "/articles/new", Box::new(NewArticlePageController::new(data_service)));
+        router.register("GET", "/about", Box::new(AboutPageController::new()));
+        router
+    }
+
+    /// 内置页面共享的数据服务，便于自定义控制器复用
+    pub fn data_service(&self) -> Arc<DataService> {
+        self.data_service.clone()
+    }
+
+    /// 注册路由，`pattern` 中以 `:` 开头的路径段是路径参数
+    pub fn register(&mut self, method: &str, pattern: &str, controller: Box<dyn PageController>) {
+        let segments = split_path(pattern)
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) => PathSegment::Param(name.to_string()),
+                None => PathSegment::Literal(segment.to_string()),
+            })
+            .collect();
+
+        self.routes.push(Route {
+            method: method.to_ascii_uppercase(),
+            segments,
+            controller,
+        });
     }
 
     /// 路由请求到相应的页面控制器
-    pub fn route(&self, request: HttpRequest) -> HttpResponse {
+    pub fn route(&self, mut request: HttpRequest) -> HttpResponse {
         println!("🌐 路由请求: {} {}", request.method, request.path);
 
-        let result = match (request.method.as_str(), request.path.as_str()) {
-            ("GET", "/") => {
-                let controller = HomePageController::new(self.data_service.clone());
-                controller.handle_get(&request)
-            },
-            ("GET", "/users") => {
-                let controller = UserListPageController::new(self.data_service.clone());
-                controller.handle_get(&request)
-            },
-            ("GET", path) if path.starts_with("/users/") => {
-                let controller = UserDetailPageController::new(self.data_service.clone());
-                controller.handle_get(&request)
-            },
-            ("GET", "/articles") => {
-                let controller = ArticleListPageController::new(self.data_service.clone());
-                controller.handle_get(&request)
-            },
-            ("GET", "/articles/new") => {
-                let controller = NewArticlePageController::new(self.data_service.clone());
-                controller.handle_get(&request)
-            },
-            ("POST", "/articles/new") => {
-                let controller = NewArticlePageController::new(self.data_service.clone());
-                controller.handle_post(&request)
-            },
-            ("GET", "/about") => {
-                let controller = AboutPageController::new();
-                controller.handle_get(&request)
-            },
-            _ => Err(PageControllerError::NotFound("页面不存在".to_string())),
+        let matched = self.routes.iter().find_map(|route| {
+            route.matches(&request.method, &request.path).map(|params| (route, params))
+        });
+
+        let result = match matched {
+            Some((route, params)) => {
+                request.path_params = params;
+                match route.method.as_str() {
+                    "GET" => route.controller.handle_get(&request),
+                    "POST" => route.controller.handle_post(&request),
+                    method => Err(PageControllerError::ValidationError(format!("不支持的请求方法: {}", method))),
+                }
+            }
+            None => Err(PageControllerError::NotFound("页面不存在".to_string())),
         };
 
         match result {
@@ -978,6 +1148,135 @@ mod tests {
         assert_eq!(response.status, 404);
     }
 
+    #[test]
+    fn test_article_preview_truncates_on_char_boundary() {
+        // 每个汉字3字节，第100字节落在第34个汉字中间
+        let content = "设计模式".repeat(10);
+        assert_eq!(content.len(), 120);
+
+        let truncated = preview(&content, PREVIEW_MAX_BYTES);
+        let expected: String = content.chars().take(33).collect();
+        assert_eq!(truncated, format!("{}...", expected));
+
+        // 渲染文章列表不再panic，预览内容可读
+        let data_service = Arc::new(DataService::new());
+        data_service.create_article("中文长文".to_string(), content, 1);
+        let controller = ArticleListPageController::new(data_service);
+        let request = HttpRequest::new("GET".to_string(), "/articles".to_string());
+        let response = controller.handle_get(&request).unwrap();
+        assert!(response.body.contains(&format!("{}...", expected)));
+
+        assert_eq!(preview("短内容", PREVIEW_MAX_BYTES), "短内容");
+    }
+
+    #[test]
+    fn test_article_list_is_paginated() {
+        let data_service = Arc::new(DataService::new());
+        for i in 0..9 {
+            data_service.create_article(format!("文章{}", i), "内容".to_string(), 1);
+        }
+        // 初始3篇加新建9篇，每页10篇共2页
+        let page = data_service.get_articles_page(2, ARTICLES_PER_PAGE);
+        assert_eq!((page.total, page.total_pages), (12, 2));
+        assert_eq!(page.map(|article| article.id).items, vec![11, 12]);
+
+        let controller = ArticleListPageController::new(data_service);
+        let first = controller.handle_get(&HttpRequest::new("GET".to_string(), "/articles".to_string())).unwrap();
+        assert!(first.body.contains("第 1/2 页"));
+        assert!(first.body.contains(r#"<a href="/articles?page=2">下一页</a>"#));
+        assert!(!first.body.contains("上一页"));
+
+        let second = controller.handle_get(&HttpRequest::new("GET".to_string(), "/articles".to_string())
+            .with_query_param("page".to_string(), "2".to_string())).unwrap();
+        assert!(second.body.contains(r#"<a href="/articles?page=1">上一页</a>"#));
+        assert!(second.body.contains("文章8"));
+
+        let invalid = HttpRequest::new("GET".to_string(), "/articles".to_string())
+            .with_query_param("page".to_string(), "0".to_string());
+        assert!(matches!(controller.handle_get(&invalid), Err(PageControllerError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_router_registers_custom_controller_with_path_params() {
+        struct CommentPageController;
+
+        impl PageController for CommentPageController {
+            fn handle_get(&self, request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
+                let article_id = request.path_param("article_id").unwrap_or("?");
+                let comment_id = request.path_param("comment_id").unwrap_or("?");
+                Ok(HttpResponse::ok(format!("文章{}的评论{}", article_id, comment_id)))
+            }
+
+            fn handle_post(&self, _request: &HttpRequest) -> Result<HttpResponse, PageControllerError> {
+                Err(PageControllerError::ValidationError("不支持POST".to_string()))
+            }
+        }
+
+        let mut router = PageControllerRouter::new();
+        router.register("GET", "/articles/:article_id/comments/:comment_id", Box::new(CommentPageController));
+
+        let response = router.route(HttpRequest::new("GET".to_string(), "/articles/7/comments/42".to_string()));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "文章7的评论42");
+
+        // 段数不同或方法不匹配都不会命中
+        let response = router.route(HttpRequest::new("GET".to_string(), "/articles/7/comments".to_string()));
+        assert_eq!(response.status, 404);
+        let response = router.route(HttpRequest::new("POST".to_string(), "/articles/7/comments/42".to_string()));
+        assert_eq!(response.status, 404);
+
+        // 内置的用户详情页同样通过路径参数取得用户ID
+        let response = router.route(HttpRequest::new("GET".to_string(), "/users/1".to_string()));
+        assert_eq!(response.status, 200);
+        let response = router.route(HttpRequest::new("GET".to_string(), "/users/abc".to_string()));
+        assert_eq!(response.status, 400);
+    }
+
+    #[test]
+    fn test_new_article_accepts_json_body() {
+        let data_service = Arc::new(DataService::new());
+        let controller = NewArticlePageController::new(data_service.clone());
+        let before = data_service.get_all_articles().len();
+
+        let request = HttpRequest::new("POST".to_string(), "/articles/new".to_string())
+            .with_json_body(r#"{"title": "JSON文章", "content": "来自JSON的内容", "author_id": 2}"#.to_string());
+        let response = controller.handle_post(&request).unwrap();
+        assert_eq!(response.status, 302);
+
+        let articles = data_service.get_all_articles();
+        assert_eq!(articles.len(), before + 1);
+        assert!(articles.iter().any(|a| a.title == "JSON文章" && a.author_id == 2));
+
+        // 没有声明JSON时仍然从表单读取
+        let mut form = HashMap::new();
+        form.insert("title".to_string(), "表单文章".to_string());
+        form.insert("content".to_string(), "表单内容".to_string());
+        form.insert("author_id".to_string(), "1".to_string());
+        let request = HttpRequest::new("POST".to_string(), "/articles/new".to_string()).with_form_data(form);
+        assert_eq!(controller.handle_post(&request).unwrap().status, 302);
+    }
+
+    #[test]
+    fn test_json_body_empty_or_invalid_is_validation_error() {
+        let controller = NewArticlePageController::new(Arc::new(DataService::new()));
+
+        let empty = HttpRequest::new("POST".to_string(), "/articles/new".to_string())
+            .with_json_body("  ".to_string());
+        match controller.handle_post(&empty) {
+            Err(PageControllerError::ValidationError(msg)) => assert_eq!(msg, "请求体为空"),
+            other => panic!("空请求体应返回验证错误: {:?}", other.map(|r| r.status)),
+        }
+        let missing = HttpRequest::new("POST".to_string(), "/articles/new".to_string());
+        assert!(matches!(missing.json::<HashMap<String, String>>(), Err(PageControllerError::ValidationError(_))));
+
+        let invalid = HttpRequest::new("POST".to_string(), "/articles/new".to_string())
+            .with_json_body(r#"{"title": "缺少右括号""#.to_string());
+        match controller.handle_post(&invalid) {
+            Err(PageControllerError::ValidationError(msg)) => assert!(msg.starts_with("无效的JSON请求体")),
+            other => panic!("非法JSON应返回验证错误: {:?}", other.map(|r| r.status)),
+        }
+    }
+
     #[test]
     fn test_user_detail_controller() {
         let data_service = Arc::new(DataService::new());
@@ -72,6 +72,38 @@ pub enum LogicalElement {
     },
 }
 
+impl LogicalElement {
+    /// 深度优先（先序）遍历当前元素及其子元素，递归进入列表项、表格单元格和容器子元素
+    pub fn visit<F: FnMut(&LogicalElement)>(&self, mut f: F) {
+        self.walk(&mut f);
+    }
+    
+    /// `visit` 的可变版本，父元素先于子元素访问，回调中替换的元素会继续遍历其新的子元素
+    pub fn visit_mut<F: FnMut(&mut LogicalElement)>(&mut self, mut f: F) {
+        self.walk_mut(&mut f);
+    }
+    
+    fn walk<F: FnMut(&LogicalElement)>(&self, f: &mut F) {
+        f(self);
+        match self {
+            LogicalElement::List { items, .. } => items.iter().for_each(|item| item.walk(f)),
+            LogicalElement::Table { rows, .. } => rows.iter().flatten().for_each(|cell| cell.walk(f)),
+            LogicalElement::Container { children, .. } => children.iter().for_each(|child| child.walk(f)),
+            _ => {}
+        }
+    }
+    
+    fn walk_mut<F: FnMut(&mut LogicalElement)>(&mut self, f: &mut F) {
+        f(self);
+        match self {
+            LogicalElement::List { items, .. } => items.iter_mut().for_each(|item| item.walk_mut(f)),
+            LogicalElement::Table { rows, .. } => rows.iter_mut().flatten().for_each(|cell| cell.walk_mut(f)),
+            LogicalElement::Container { children, .. } => children.iter_mut().for_each(|child| child.walk_mut(f)),
+            _ => {}
+        }
+    }
+}
+
 /// 容器布局类型
 #[derive(Debug, Clone)]
 pub enum ContainerLayout {
@@ -164,6 +196,20 @@ impl LogicalPage {
         self.metadata.insert(key, value);
         self
     }
+    
+    /// 按文档顺序深度优先遍历页面中的所有元素
+    pub fn visit<F: FnMut(&LogicalElement)>(&self, mut f: F) {
+        for element in &self.elements {
+            element.visit(&mut f);
+        }
+    }
+    
+    /// `visit` 的可变版本，用于二次处理（注入id、替换链接等）
+    pub fn visit_mut<F: FnMut(&mut LogicalElement)>(&mut self, mut f: F) {
+        for element in &mut self.elements {
+            element.visit_mut(&mut f);
+        }
+    }
 }
 
 // =================
@@ -231,10 +277,17 @@ impl std::error::Error for RenderError {}
 // HTML 渲染器
 // =================
 
+/// 净化时拒绝的URL协议
+const DANGEROUS_URL_SCHEMES: [&str; 3] = ["javascript:", "vbscript:", "data:"];
+
 /// HTML格式渲染器
+///
+/// 所有属性值都会做HTML转义；开启URL净化（默认开启）时，
+/// `href`/`src`/`action` 中使用危险协议的URL会被替换为 `#`。
 pub struct HtmlRenderer {
     pretty_print: bool,
     include_meta: bool,
+    sanitize_urls: bool,
 }
 
 impl HtmlRenderer {
@@ -242,6 +295,7 @@ impl HtmlRenderer {
         Self {
             pretty_print: true,
             include_meta: true,
+            sanitize_urls: true,
         }
     }
     
@@ -255,6 +309,12 @@ impl HtmlRenderer {
         self
     }
     
+    /// 是否拒绝 `javascript:`、`vbscript:`、`data:` 等危险协议的URL
+    pub fn with_url_sanitizer(mut self, sanitize: bool) -> Self {
+        self.sanitize_urls = sanitize;
+        self
+    }
+    
     fn render_element(&self, element: &LogicalElement, depth: usize) -> Result<String, RenderError> {
         let indent = if self.pretty_print { "  ".repeat(depth) } else { String::new() };
         let newline = if self.pretty_print { "\n" } else { "" };
@@ -265,7 +325,7 @@ impl HtmlRenderer {
                     let style_str: Vec<String> = style.iter()
                         .map(|(k, v)| format!("{}:{}", k, v))
                         .collect();
-                    format!(" style=\"{}\"", style_str.join(";"))
+                    format!(" style=\"{}\"", self.escape_html(&style_str.join(";")))
                 } else {
                     String::new()
                 };
@@ -274,7 +334,7 @@ impl HtmlRenderer {
             
             LogicalElement::Heading { level, content, id } => {
                 let id_attr = id.as_ref()
-                    .map(|i| format!(" id=\"{}\"", i))
+                    .map(|i| format!(" id=\"{}\"", self.escape_html(i)))
                     .unwrap_or_default();
                 Ok(format!("{}<h{}{}>{}</h{}>", indent, level, id_attr, self.escape_html(content), level))
             },
@@ -332,11 +392,11 @@ impl HtmlRenderer {
             
             LogicalElement::Link { url, text, external } => {
                 let target = if *external { " target=\"_blank\"" } else { "" };
-                Ok(format!("{}<a href=\"{}\"{}>{}</a>", indent, url, target, self.escape_html(text)))
+                Ok(format!("{}<a href=\"{}\"{}>{}</a>", indent, self.url_attr(url), target, self.escape_html(text)))
             },
             
             LogicalElement::Image { src, alt, width, height } => {
-                let mut attrs = format!(" src=\"{}\" alt=\"{}\"", src, alt);
+                let mut attrs = format!(" src=\"{}\" alt=\"{}\"", self.url_attr(src), self.escape_html(alt));
                 if let Some(w) = width {
                     attrs.push_str(&format!(" width=\"{}\"", w));
                 }
@@ -348,7 +408,7 @@ impl HtmlRenderer {
             
             LogicalElement::Container { children, layout: _, css_class } => {
                 let class_attr = css_class.as_ref()
-                    .map(|c| format!(" class=\"{}\"", c))
+                    .map(|c| format!(" class=\"{}\"", self.escape_html(c)))
                     .unwrap_or_default();
                 
                 let mut result = format!("{}<div{}>{}", indent, class_attr, newline);
@@ -368,7 +428,7 @@ impl HtmlRenderer {
                     HttpMethod::DELETE => "post",
                 };
                 
-                let mut result = format!("{}<form action=\"{}\" method=\"{}\">{}", indent, action, method_str, newline);
+                let mut result = format!("{}<form action=\"{}\" method=\"{}\">{}", indent, self.url_attr(action), method_str, newline);
                 
                 for field in fields {
                     result.push_str(&self.render_form_field(field, depth + 1)?);
@@ -391,7 +451,7 @@ impl HtmlRenderer {
                     };
                     
                     result.push_str(&format!("{}    <li{}><a href=\"{}\">{}</a></li>{}", 
-                        indent, active_class, item.path, self.escape_html(&item.title), newline));
+                        indent, active_class, self.url_attr(&item.path), self.escape_html(&item.title), newline));
                 }
                 
                 result.push_str(&format!("{}  </ul>{}", indent, newline));
@@ -405,6 +465,7 @@ impl HtmlRenderer {
         let indent = if self.pretty_print { "  ".repeat(depth) } else { String::new() };
         let newline = if self.pretty_print { "\n" } else { "" };
         
+        let name = self.escape_html(&field.name);
         let required_attr = if field.required { " required" } else { "" };
         let value_attr = field.value.as_ref()
             .map(|v| format!(" value=\"{}\"", self.escape_html(v)))
@@ -415,35 +476,36 @@ impl HtmlRenderer {
         
         let mut result = format!("{}<div class=\"field\">{}", indent, newline);
         result.push_str(&format!("{}  <label for=\"{}\">{}</label>{}", 
-            indent, field.name, self.escape_html(&field.label), newline));
+            indent, name, self.escape_html(&field.label), newline));
         
         match &field.field_type {
             FieldType::Text => {
                 result.push_str(&format!("{}  <input type=\"text\" id=\"{}\" name=\"{}\"{}{}{} />", 
-                    indent, field.name, field.name, value_attr, placeholder_attr, required_attr));
+                    indent, name, name, value_attr, placeholder_attr, required_attr));
             },
             FieldType::Email => {
                 result.push_str(&format!("{}  <input type=\"email\" id=\"{}\" name=\"{}\"{}{}{} />", 
-                    indent, field.name, field.name, value_attr, placeholder_attr, required_attr));
+                    indent, name, name, value_attr, placeholder_attr, required_attr));
             },
             FieldType::Password => {
                 result.push_str(&format!("{}  <input type=\"password\" id=\"{}\" name=\"{}\"{}{} />", 
-                    indent, field.name, field.name, placeholder_attr, required_attr));
+                    indent, name, name, placeholder_attr, required_attr));
             },
             FieldType::Number => {
                 result.push_str(&format!("{}  <input type=\"number\" id=\"{}\" name=\"{}\"{}{}{} />", 
-                    indent, field.name, field.name, value_attr, placeholder_attr, required_attr));
+                    indent, name, name, value_attr, placeholder_attr, required_attr));
             },
             FieldType::TextArea => {
                 let content = field.value.as_deref().unwrap_or("");
                 result.push_str(&format!("{}  <textarea id=\"{}\" name=\"{}\"{}{}>{}</textarea>", 
-                    indent, field.name, field.name, placeholder_attr, required_attr, self.escape_html(content)));
+                    indent, name, name, placeholder_attr, required_attr, self.escape_html(content)));
             },
             FieldType::Select { options } => {
                 result.push_str(&format!("{}  <select id=\"{}\" name=\"{}\"{}>{}", 
-                    indent, field.name, field.name, required_attr, newline));
-                for (value, text) in options {
-                    let selected = if field.value.as_ref() == Some(value) { " selected" } else { "" };
+                    indent, name, name, required_attr, newline));
+                for (raw_value, text) in options {
+                    let selected = if field.value.as_ref() == Some(raw_value) { " selected" } else { "" };
+                    let value = self.escape_html(raw_value);
                     result.push_str(&format!("{}    <option value=\"{}\"{}>{}</option>{}", 
                         indent, value, selected, self.escape_html(text), newline));
                 }
@@ -453,21 +515,22 @@ impl HtmlRenderer {
                 let checked = field.value.as_deref() == Some("true");
                 let checked_attr = if checked { " checked" } else { "" };
                 result.push_str(&format!("{}  <input type=\"checkbox\" id=\"{}\" name=\"{}\" value=\"true\"{}{} />", 
-                    indent, field.name, field.name, checked_attr, required_attr));
+                    indent, name, name, checked_attr, required_attr));
             },
             FieldType::Radio { options } => {
-                for (value, text) in options {
-                    let checked = field.value.as_ref() == Some(value);
+                for (raw_value, text) in options {
+                    let checked = field.value.as_ref() == Some(raw_value);
+                    let value = self.escape_html(raw_value);
                     let checked_attr = if checked { " checked" } else { "" };
                     result.push_str(&format!("{}  <input type=\"radio\" id=\"{}_{}_{}\" name=\"{}\" value=\"{}\"{}{} />", 
-                        indent, field.name, value, field.name, field.name, value, checked_attr, required_attr));
+                        indent, name, value, name, name, value, checked_attr, required_attr));
                     result.push_str(&format!("  <label for=\"{}_{}_{}\">{}</label>{}", 
-                        field.name, value, field.name, self.escape_html(text), newline));
+                        name, value, name, self.escape_html(text), newline));
                 }
             },
             FieldType::Hidden => {
                 result.push_str(&format!("{}  <input type=\"hidden\" name=\"{}\" value=\"{}\" />", 
-                    indent, field.name, field.value.as_deref().unwrap_or("")));
+                    indent, name, self.escape_html(field.value.as_deref().unwrap_or(""))));
             },
         }
         
@@ -482,6 +545,24 @@ impl HtmlRenderer {
             .replace('"', "&quot;")
             .replace('\'', "&#39;")
     }
+    
+    /// 生成可以放进URL属性的值：先按需净化协议，再做属性级转义
+    fn url_attr(&self, url: &str) -> String {
+        if self.sanitize_urls && self.is_dangerous_url(url) {
+            return "#".to_string();
+        }
+        self.escape_html(url)
+    }
+    
+    /// 浏览器解析协议前会去掉首尾空白并忽略其中的制表符和换行，这里按同样的方式规范化后再比较
+    fn is_dangerous_url(&self, url: &str) -> bool {
+        let normalized: String = url.trim_matches(|c: char| c.is_ascii_whitespace() || c.is_ascii_control())
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        DANGEROUS_URL_SCHEMES.iter().any(|scheme| normalized.starts_with(scheme))
+    }
 }
 
 impl FormatRenderer for HtmlRenderer {
@@ -670,6 +751,496 @@ impl FormatRenderer for JsonRenderer {
     }
 }
 
+// =================
+// XML 渲染器
+// =================
+
+/// XML格式渲染器
+pub struct XmlRenderer {
+    pretty_print: bool,
+}
+
+impl XmlRenderer {
+    pub fn new() -> Self {
+        Self {
+            pretty_print: true,
+        }
+    }
+    
+    pub fn with_pretty_print(mut self, pretty: bool) -> Self {
+        self.pretty_print = pretty;
+        self
+    }
+    
+    /// 按缩进层级追加一行
+    fn push_line(&self, out: &mut String, depth: usize, line: &str) {
+        if self.pretty_print {
+            out.push_str(&"  ".repeat(depth));
+        }
+        out.push_str(line);
+        if self.pretty_print {
+            out.push('\n');
+        }
+    }
+    
+    /// 拼接属性，值为None的属性被省略
+    fn attributes(&self, attrs: &[(&str, Option<String>)]) -> String {
+        attrs.iter()
+            .filter_map(|(name, value)| value.as_ref().map(|v| format!(" {}=\"{}\"", name, self.escape_xml(v))))
+            .collect()
+    }
+    
+    /// 输出只包含文本的元素
+    fn push_text_element(&self, out: &mut String, depth: usize, tag: &str, attrs: &[(&str, Option<String>)], text: &str) {
+        self.push_line(out, depth, &format!("<{}{}>{}</{}>", tag, self.attributes(attrs), self.escape_xml(text), tag));
+    }
+    
+    fn render_element(&self, out: &mut String, element: &LogicalElement, depth: usize) {
+        match element {
+            LogicalElement::Text { content, style } => {
+                let mut declarations: Vec<String> = style.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+                declarations.sort();
+                let style = (!declarations.is_empty()).then(|| declarations.join("; "));
+                self.push_text_element(out, depth, "text", &[("style", style)], content);
+            },
+            LogicalElement::Heading { level, content, id } => {
+                self.push_text_element(out, depth, "heading",
+                    &[("level", Some(level.to_string())), ("id", id.clone())], content);
+            },
+            LogicalElement::List { items, ordered } => {
+                self.push_line(out, depth, &format!("<list{}>", self.attributes(&[("ordered", Some(ordered.to_string()))])));
+                for item in items {
+                    self.push_line(out, depth + 1, "<item>");
+                    self.render_element(out, item, depth + 2);
+                    self.push_line(out, depth + 1, "</item>");
+                }
+                self.push_line(out, depth, "</list>");
+            },
+            LogicalElement::Table { headers, rows, caption } => {
+                self.push_line(out, depth, "<table>");
+                if let Some(caption) = caption {
+                    self.push_text_element(out, depth + 1, "caption", &[], caption);
+                }
+                self.push_line(out, depth + 1, "<headers>");
+                for header in headers {
+                    self.push_text_element(out, depth + 2, "header", &[], header);
+                }
+                self.push_line(out, depth + 1, "</headers>");
+                for row in rows {
+                    self.push_line(out, depth + 1, "<row>");
+                    for cell in row {
+                        self.push_line(out, depth + 2, "<cell>");
+                        self.render_element(out, cell, depth + 3);
+                        self.push_line(out, depth + 2, "</cell>");
+                    }
+                    self.push_line(out, depth + 1, "</row>");
+                }
+                self.push_line(out, depth, "</table>");
+            },
+            LogicalElement::Link { url, text, external } => {
+                self.push_text_element(out, depth, "link",
+                    &[("url", Some(url.clone())), ("external", Some(external.to_string()))], text);
+            },
+            LogicalElement::Image { src, alt, width, height } => {
+                let attrs = self.attributes(&[
+                    ("src", Some(src.clone())),
+                    ("alt", Some(alt.clone())),
+                    ("width", width.map(|w| w.to_string())),
+                    ("height", height.map(|h| h.to_string())),
+                ]);
+                self.push_line(out, depth, &format!("<image{}/>", attrs));
+            },
+            LogicalElement::Container { children, layout, css_class } => {
+                let (layout_name, columns) = match layout {
+                    ContainerLayout::Vertical => ("vertical", None),
+                    ContainerLayout::Horizontal => ("horizontal", None),
+                    ContainerLayout::Grid { columns } => ("grid", Some(columns.to_string())),
+                    ContainerLayout::Flex => ("flex", None),
+                };
+                let attrs = self.attributes(&[
+                    ("layout", Some(layout_name.to_string())),
+                    ("columns", columns),
+                    ("class", css_class.clone()),
+                ]);
+                self.push_line(out, depth, &format!("<container{}>", attrs));
+                for child in children {
+                    self.render_element(out, child, depth + 1);
+                }
+                self.push_line(out, depth, "</container>");
+            },
+            LogicalElement::Form { fields, action, method } => {
+                let attrs = self.attributes(&[
+                    ("action", Some(action.clone())),
+                    ("method", Some(format!("{:?}", method))),
+                ]);
+                self.push_line(out, depth, &format!("<form{}>", attrs));
+                for field in fields {
+                    self.render_form_field(out, field, depth + 1);
+                }
+                self.push_line(out, depth, "</form>");
+            },
+            LogicalElement::Navigation { items, current_path } => {
+                self.push_line(out, depth, &format!("<navigation{}>", self.attributes(&[("current-path", current_path.clone())])));
+                for item in items {
+                    self.render_navigation_item(out, item, depth + 1);
+                }
+                self.push_line(out, depth, "</navigation>");
+            },
+        }
+    }
+    
+    fn render_form_field(&self, out: &mut String, field: &FormField, depth: usize) {
+        let (field_type, options) = match &field.field_type {
+            FieldType::Text => ("text", None),
+            FieldType::Email => ("email", None),
+            FieldType::Password => ("password", None),
+            FieldType::Number => ("number", None),
+            FieldType::Select { options } => ("select", Some(options)),
+            FieldType::TextArea => ("textarea", None),
+            FieldType::Checkbox => ("checkbox", None),
+            FieldType::Radio { options } => ("radio", Some(options)),
+            FieldType::Hidden => ("hidden", None),
+        };
+        let attrs = self.attributes(&[
+            ("name", Some(field.name.clone())),
+            ("type", Some(field_type.to_string())),
+            ("required", Some(field.required.to_string())),
+            ("value", field.value.clone()),
+            ("placeholder", field.placeholder.clone()),
+        ]);
+        
+        self.push_line(out, depth, &format!("<field{}>", attrs));
+        self.push_text_element(out, depth + 1, "label", &[], &field.label);
+        for (value, label) in options.into_iter().flatten() {
+            self.push_text_element(out, depth + 1, "option", &[("value", Some(value.clone()))], label);
+        }
+        self.push_line(out, depth, "</field>");
+    }
+    
+    fn render_navigation_item(&self, out: &mut String, item: &NavigationItem, depth: usize) {
+        let attrs = self.attributes(&[
+            ("path", Some(item.path.clone())),
+            ("title", Some(item.title.clone())),
+            ("active", Some(item.active.to_string())),
+        ]);
+        if item.children.is_empty() {
+            self.push_line(out, depth, &format!("<item{}/>", attrs));
+        } else {
+            self.push_line(out, depth, &format!("<item{}>", attrs));
+            for child in &item.children {
+                self.render_navigation_item(out, child, depth + 1);
+            }
+            self.push_line(out, depth, "</item>");
+        }
+    }
+    
+    /// 转义XML文本和属性值中的特殊字符
+    fn escape_xml(&self, text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+}
+
+impl FormatRenderer for XmlRenderer {
+    fn render(&self, page: &LogicalPage) -> Result<String, RenderError> {
+        let mut out = String::new();
+        self.push_line(&mut out, 0, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        self.push_line(&mut out, 0, "<page>");
+        
+        self.push_text_element(&mut out, 1, "title", &[], &page.title);
+        if let Some(desc) = &page.description {
+            self.push_text_element(&mut out, 1, "description", &[], desc);
+        }
+        
+        if !page.keywords.is_empty() {
+            self.push_line(&mut out, 1, "<keywords>");
+            for keyword in &page.keywords {
+                self.push_text_element(&mut out, 2, "keyword", &[], keyword);
+            }
+            self.push_line(&mut out, 1, "</keywords>");
+        }
+        
+        if !page.metadata.is_empty() {
+            let mut entries: Vec<_> = page.metadata.iter().collect();
+            entries.sort();
+            self.push_line(&mut out, 1, "<metadata>");
+            for (key, value) in entries {
+                self.push_text_element(&mut out, 2, "entry", &[("key", Some(key.clone()))], value);
+            }
+            self.push_line(&mut out, 1, "</metadata>");
+        }
+        
+        self.push_line(&mut out, 1, "<elements>");
+        for element in &page.elements {
+            self.render_element(&mut out, element, 2);
+        }
+        self.push_line(&mut out, 1, "</elements>");
+        self.push_line(&mut out, 0, "</page>");
+        
+        Ok(out)
+    }
+    
+    fn content_type(&self) -> &str {
+        "application/xml"
+    }
+}
+
+// =================
+// Markdown 渲染器
+// =================
+
+/// Markdown格式渲染器
+///
+/// 输出GitHub风格的Markdown：表格使用GFM语法，表单没有对应的语法，
+/// 降级为字段清单。页面标题等元信息写在开头的front matter中。
+/// 文本内容中的HTML字符和Markdown元字符都会转义，按字面显示。
+pub struct MarkdownRenderer {
+    front_matter: bool,
+}
+
+impl MarkdownRenderer {
+    pub fn new() -> Self {
+        Self {
+            front_matter: true,
+        }
+    }
+    
+    pub fn with_front_matter(mut self, front_matter: bool) -> Self {
+        self.front_matter = front_matter;
+        self
+    }
+    
+    /// 渲染一个块级元素，返回其中的各行
+    fn render_lines(&self, element: &LogicalElement) -> Vec<String> {
+        match element {
+            LogicalElement::Text { content, style } => {
+                let mut text = self.escape_text(content);
+                if style.get("font-style").map(String::as_str) == Some("italic") {
+                    text = format!("*{}*", text);
+                }
+                if style.get("font-weight").map(String::as_str) == Some("bold") {
+                    text = format!("**{}**", text);
+                }
+                vec![text]
+            },
+            LogicalElement::Heading { level, content, .. } => {
+                vec![format!("{} {}", "#".repeat((*level).clamp(1, 6) as usize), self.escape_text(content))]
+            },
+            LogicalElement::List { items, ordered } => {
+                let mut lines = Vec::new();
+                for (index, item) in items.iter().enumerate() {
+                    let marker = if *ordered { format!("{}. ", index + 1) } else { "- ".to_string() };
+                    lines.extend(self.list_item_lines(&marker, self.item_lines(item)));
+                }
+                lines
+            },
+            LogicalElement::Table { headers, rows, caption } => {
+                let columns = rows.iter().map(Vec::len).chain([headers.len()]).max().unwrap_or(0);
+                let row_line = |cells: Vec<String>| {
+                    let mut cells = cells;
+                    cells.resize(columns, String::new());
+                    format!("| {} |", cells.join(" | "))
+                };
+                
+                let mut lines = Vec::new();
+                if let Some(caption) = caption {
+                    lines.push(format!("**{}**", self.escape_text(caption)));
+                    lines.push(String::new());
+                }
+                lines.push(row_line(headers.iter().map(|h| self.escape_cell(&self.escape_text(h))).collect()));
+                lines.push(row_line(vec!["---".to_string(); columns]));
+                for row in rows {
+                    lines.push(row_line(row.iter().map(|cell| self.inline(cell)).collect()));
+                }
+                lines
+            },
+            LogicalElement::Link { url, text, .. } => vec![self.link(text, url)],
+            LogicalElement::Image { src, alt, .. } => {
+                vec![format!("![{}]({})", self.escape_text(alt), self.link_destination(src))]
+            },
+            LogicalElement::Container { children, .. } => {
+                let mut lines = Vec::new();
+                for child in children {
+                    if !lines.is_empty() {
+                        lines.push(String::new());
+                    }
+                    lines.extend(self.render_lines(child));
+                }
+                lines
+            },
+            LogicalElement::Form { fields, action, method } => {
+                let mut lines = vec![format!("**表单** `{:?} {}`", method, action), String::new()];
+                for field in fields.iter().filter(|f| !matches!(f.field_type, FieldType::Hidden)) {
+                    lines.extend(self.form_field_lines(field));
+                }
+                lines
+            },
+            LogicalElement::Navigation { items, .. } => {
+                items.iter().flat_map(|item| self.navigation_lines(item)).collect()
+            },
+        }
+    }
+    
+    /// 列表项中的容器紧凑排列，子元素之间不插入空行
+    fn item_lines(&self, item: &LogicalElement) -> Vec<String> {
+        match item {
+            LogicalElement::Container { children, .. } => {
+                children.iter().flat_map(|child| self.item_lines(child)).collect()
+            },
+            other => self.render_lines(other),
+        }
+    }
+    
+    /// 首行加上列表标记，其余行按标记宽度缩进，使嵌套内容归属于该项
+    fn list_item_lines(&self, marker: &str, lines: Vec<String>) -> Vec<String> {
+        let indent = " ".repeat(marker.len());
+        lines.into_iter()
+            .enumerate()
+            .map(|(index, line)| match (index, line.is_empty()) {
+                (0, _) => format!("{}{}", marker, line),
+                (_, true) => line,
+                (_, false) => format!("{}{}", indent, line),
+            })
+            .collect()
+    }
+    
+    fn form_field_lines(&self, field: &FormField) -> Vec<String> {
+        let (field_type, options) = match &field.field_type {
+            FieldType::Text => ("文本", None),
+            FieldType::Email => ("邮箱", None),
+            FieldType::Password => ("密码", None),
+            FieldType::Number => ("数字", None),
+            FieldType::Select { options } => ("单选下拉", Some(options)),
+            FieldType::TextArea => ("多行文本", None),
+            FieldType::Checkbox => ("复选框", None),
+            FieldType::Radio { options } => ("单选", Some(options)),
+            FieldType::Hidden => ("隐藏", None),
+        };
+        
+        let mut line = format!("{} (`{}`, {}{})", self.escape_text(&field.label), field.name, field_type,
+            if field.required { ", 必填" } else { "" });
+        if let Some(value) = &field.value {
+            line.push_str(&format!(": {}", self.escape_text(value)));
+        } else if let Some(placeholder) = &field.placeholder {
+            line.push_str(&format!(": _{}_", self.escape_text(placeholder)));
+        }
+        
+        let mut lines = vec![line];
+        for (value, label) in options.into_iter().flatten() {
+            lines.push(format!("- {} (`{}`)", self.escape_text(label), value));
+        }
+        self.list_item_lines("- ", lines)
+    }
+    
+    fn navigation_lines(&self, item: &NavigationItem) -> Vec<String> {
+        let link = self.link(&item.title, &item.path);
+        let mut lines = vec![if item.active { format!("**{}**", link) } else { link }];
+        for child in &item.children {
+            lines.extend(self.navigation_lines(child));
+        }
+        self.list_item_lines("- ", lines)
+    }
+    
+    /// 表格单元格只能容纳一行，多行内容用<br>连接
+    fn inline(&self, element: &LogicalElement) -> String {
+        let lines: Vec<String> = self.item_lines(element).into_iter().filter(|l| !l.is_empty()).collect();
+        self.escape_cell(&lines.join("<br>"))
+    }
+    
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("[{}]({})", self.escape_text(text), self.link_destination(url))
+    }
+    
+    /// 含空白或括号的地址用尖括号包裹
+    fn link_destination(&self, url: &str) -> String {
+        if url.chars().any(|c| c.is_whitespace() || c == '(' || c == ')') {
+            format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+        } else {
+            url.to_string()
+        }
+    }
+    
+    /// 转义文本中的HTML字符与Markdown元字符，使其按字面显示
+    ///
+    /// 行内元字符处处转义；`#`、`-`、`+`、`=` 与有序列表编号只在行首有特殊含义，只在行首转义
+    fn escape_text(&self, text: &str) -> String {
+        text.split('\n').map(|line| self.escape_line(line)).collect::<Vec<_>>().join("\n")
+    }
+    
+    fn escape_line(&self, line: &str) -> String {
+        let mut escaped = String::with_capacity(line.len());
+        for c in line.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '\\' | '`' | '*' | '_' | '[' | ']' | '~' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                },
+                _ => escaped.push(c),
+            }
+        }
+        
+        let body = escaped.trim_start();
+        let indent = escaped.len() - body.len();
+        let digits = body.bytes().take_while(u8::is_ascii_digit).count();
+        let marker = match body.as_bytes().first() {
+            Some(b'#' | b'-' | b'+' | b'=') => Some(indent),
+            _ if digits > 0 && matches!(body.as_bytes().get(digits), Some(b'.' | b')')) => Some(indent + digits),
+            _ => None,
+        };
+        if let Some(position) = marker {
+            escaped.insert(position, '\\');
+        }
+        escaped
+    }
+    
+    fn escape_cell(&self, text: &str) -> String {
+        text.replace('|', "\\|")
+    }
+}
+
+impl FormatRenderer for MarkdownRenderer {
+    fn render(&self, page: &LogicalPage) -> Result<String, RenderError> {
+        let quote = |value: &str| serde_json::to_string(value)
+            .map_err(|e| RenderError::RenderingFailed(e.to_string()));
+        let mut blocks = Vec::new();
+        
+        if self.front_matter {
+            // JSON字符串同时也是合法的YAML标量，可以安全地容纳冒号和引号
+            let mut lines = vec!["---".to_string(), format!("title: {}", quote(&page.title)?)];
+            if let Some(desc) = &page.description {
+                lines.push(format!("description: {}", quote(desc)?));
+            }
+            if !page.keywords.is_empty() {
+                let keywords: Result<Vec<String>, RenderError> = page.keywords.iter().map(|k| quote(k)).collect();
+                lines.push(format!("keywords: [{}]", keywords?.join(", ")));
+            }
+            let mut entries: Vec<_> = page.metadata.iter().collect();
+            entries.sort();
+            for (key, value) in entries {
+                lines.push(format!("{}: {}", quote(key)?, quote(value)?));
+            }
+            lines.push("---".to_string());
+            blocks.push(lines.join("\n"));
+        }
+        
+        for element in &page.elements {
+            blocks.push(self.render_lines(element).join("\n"));
+        }
+        
+        Ok(blocks.join("\n\n") + "\n")
+    }
+    
+    fn content_type(&self) -> &str {
+        "text/markdown"
+    }
+}
+
 // =================
 // 两步视图处理器
 // =================
@@ -678,6 +1249,7 @@ impl FormatRenderer for JsonRenderer {
 pub struct TwoStepViewProcessor<T> {
     page_builder: Box<dyn PageBuilder<Data = T>>,
     renderers: HashMap<String, Box<dyn FormatRenderer>>,
+    default_format: Option<String>,
 }
 
 impl<T> TwoStepViewProcessor<T> {
@@ -685,6 +1257,7 @@ impl<T> TwoStepViewProcessor<T> {
         Self {
             page_builder,
             renderers: HashMap::new(),
+            default_format: None,
         }
     }
     
@@ -693,6 +1266,69 @@ impl<T> TwoStepViewProcessor<T> {
         self
     }
     
+    /// 内容协商没有匹配的格式时使用的格式
+    pub fn with_default_format(mut self, format: String) -> Self {
+        self.default_format = Some(format);
+        self
+    }
+    
+    /// 根据Accept头选择格式并处理
+    pub fn process_negotiated(&self, data: &T, accept_header: &str) -> Result<(String, String), ProcessError> {
+        let format = self.negotiate(accept_header)?;
+        self.process(data, &format)
+    }
+    
+    /// 根据Accept头在已注册的渲染器中选出最合适的格式
+    ///
+    /// 每个渲染器按 `content_type()` 取最具体的匹配范围（`type/subtype` 优先于 `type/*`，
+    /// 再优先于 `*/*`）的q值，选q值最高的渲染器；q值相同时依次比较匹配的具体程度、
+    /// 匹配范围在Accept头中的位置，最后优先默认格式。空的Accept头视为 `*/*`。
+    /// 没有q值大于0的匹配时回退到默认格式，未设置默认格式则返回 `UnsupportedFormat`。
+    pub fn negotiate(&self, accept_header: &str) -> Result<String, ProcessError> {
+        let mut ranges = parse_accept(accept_header);
+        if accept_header.trim().is_empty() {
+            ranges.push(MediaRange { media_type: "*".to_string(), subtype: "*".to_string(), quality: 1.0 });
+        }
+        
+        let mut best: Option<(f32, u8, usize, bool, &String)> = None;
+        for (format, renderer) in &self.renderers {
+            let content_type = renderer.content_type().split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+            let (media_type, subtype) = content_type.split_once('/').unwrap_or((content_type.as_str(), ""));
+            
+            let matched = ranges.iter()
+                .enumerate()
+                .filter_map(|(position, range)| range.specificity(media_type, subtype).map(|s| (s, position, range.quality)))
+                .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+            let Some((specificity, position, quality)) = matched else { continue };
+            if quality <= 0.0 {
+                continue;
+            }
+            
+            let is_default = self.default_format.as_ref() == Some(format);
+            let candidate = (quality, specificity, position, is_default, format);
+            let better = match &best {
+                None => true,
+                Some(current) => {
+                    candidate.0.partial_cmp(&current.0).unwrap_or(std::cmp::Ordering::Equal)
+                        .then(candidate.1.cmp(&current.1))
+                        .then(current.2.cmp(&candidate.2))
+                        .then(candidate.3.cmp(&current.3))
+                        .then(current.4.cmp(candidate.4))
+                        .is_gt()
+                }
+            };
+            if better {
+                best = Some(candidate);
+            }
+        }
+        
+        match (best, &self.default_format) {
+            (Some((.., format)), _) => Ok(format.clone()),
+            (None, Some(default)) if self.renderers.contains_key(default) => Ok(default.clone()),
+            (None, _) => Err(ProcessError::UnsupportedFormat(accept_header.to_string())),
+        }
+    }
+    
     pub fn process(&self, data: &T, format: &str) -> Result<(String, String), ProcessError> {
         // 第一步：构建逻辑页面
         let logical_page = self.page_builder.build_page(data)
@@ -715,6 +1351,50 @@ impl<T> TwoStepViewProcessor<T> {
     }
 }
 
+/// Accept头中的一个媒体范围
+#[derive(Debug, Clone, PartialEq)]
+struct MediaRange {
+    media_type: String,
+    subtype: String,
+    quality: f32,
+}
+
+impl MediaRange {
+    /// 与给定类型匹配时返回具体程度：2为完全匹配，1为 `type/*`，0为 `*/*`
+    fn specificity(&self, media_type: &str, subtype: &str) -> Option<u8> {
+        match (self.media_type.as_str(), self.subtype.as_str()) {
+            ("*", "*") => Some(0),
+            (t, "*") if t == media_type => Some(1),
+            (t, s) if t == media_type && s == subtype => Some(2),
+            _ => None,
+        }
+    }
+}
+
+/// 解析Accept头，忽略格式错误的条目；q值缺省为1，超出 [0, 1] 的q值视为格式错误
+fn parse_accept(header: &str) -> Vec<MediaRange> {
+    header.split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let (media_type, subtype) = parts.next()?.trim().split_once('/')?;
+            let (media_type, subtype) = (media_type.trim().to_ascii_lowercase(), subtype.trim().to_ascii_lowercase());
+            if media_type.is_empty() || subtype.is_empty() || (media_type == "*" && subtype != "*") {
+                return None;
+            }
+            
+            let mut quality = 1.0;
+            for param in parts {
+                if let Some((name, value)) = param.split_once('=') {
+                    if name.trim().eq_ignore_ascii_case("q") {
+                        quality = value.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?;
+                    }
+                }
+            }
+            Some(MediaRange { media_type, subtype, quality })
+        })
+        .collect()
+}
+
 /// 处理错误
 #[derive(Debug)]
 pub enum ProcessError {
@@ -914,8 +1594,11 @@ pub fn demo() {
     
     // 创建两步视图处理器
     let processor = TwoStepViewProcessor::new(Box::new(BlogPostPageBuilder))
-        .add_renderer("html".to_string(), Box::new(HtmlRenderer::new().with_pretty_print(true)))
-        .add_renderer("json".to_string(), Box::new(JsonRenderer::new().with_pretty_print(true)));
+        .add_renderer("html".to_string(), Box::new(HtmlRenderer::new().with_pretty_print(true).with_url_sanitizer(true)))
+        .add_renderer("json".to_string(), Box::new(JsonRenderer::new().with_pretty_print(true)))
+        .add_renderer("xml".to_string(), Box::new(XmlRenderer::new().with_pretty_print(true)))
+        .add_renderer("markdown".to_string(), Box::new(MarkdownRenderer::new().with_front_matter(true)))
+        .with_default_format("html".to_string());
     
     println!("支持的输出格式: {:?}\n", processor.supported_formats());
     
@@ -965,9 +1648,83 @@ pub fn demo() {
     
     println!("\n{}", "=".repeat(50));
     
-    // 测试不支持的格式
-    println!("3. 测试不支持的格式:");
+    // 生成XML格式
+    println!("3. 生成XML格式:");
     match processor.process(&blog_post, "xml") {
+        Ok((content, content_type)) => {
+            println!("Content-Type: {}", content_type);
+            println!("XML长度: {} 字符", content.len());
+            
+            let lines: Vec<&str> = content.lines().collect();
+            println!("XML预览:");
+            for line in lines.iter().take(15) {
+                println!("  {}", line);
+            }
+            if lines.len() > 15 {
+                println!("  ... ({} 行总计)", lines.len());
+            }
+        },
+        Err(e) => println!("生成XML失败: {}", e),
+    }
+    
+    println!("\n{}", "=".repeat(50));
+    
+    // 生成Markdown格式
+    println!("4. 生成Markdown格式:");
+    match processor.process(&blog_post, "markdown") {
+        Ok((content, content_type)) => {
+            println!("Content-Type: {}", content_type);
+            for line in content.lines() {
+                println!("  {}", line);
+            }
+        },
+        Err(e) => println!("生成Markdown失败: {}", e),
+    }
+    
+    println!("\n{}", "=".repeat(50));
+    
+    // 根据Accept头协商格式
+    println!("5. 内容协商:");
+    for accept in ["text/html, application/json;q=0.9", "application/json;q=0.9, application/xml;q=0.5", "text/markdown", "image/png"] {
+        match processor.process_negotiated(&blog_post, accept) {
+            Ok((content, content_type)) => println!("Accept: {} -> {} ({} 字符)", accept, content_type, content.len()),
+            Err(e) => println!("Accept: {} -> 错误: {}", accept, e),
+        }
+    }
+    
+    println!("\n{}", "=".repeat(50));
+    
+    // 遍历逻辑页面做二次处理
+    println!("6. 逻辑页面二次处理:");
+    if let Ok(mut page) = BlogPostPageBuilder.build_page(&blog_post) {
+        let mut element_count = 0;
+        page.visit(|_| element_count += 1);
+        
+        let mut next_id = 0;
+        page.visit_mut(|element| {
+            if let LogicalElement::Heading { id: id @ None, .. } = element {
+                next_id += 1;
+                *id = Some(format!("section-{}", next_id));
+            }
+        });
+        
+        let mut headings = Vec::new();
+        page.visit(|element| {
+            if let LogicalElement::Heading { content, id: Some(id), .. } = element {
+                headings.push(format!("#{} {}", id, content));
+            }
+        });
+        println!("元素总数: {}, 补充id的标题: {}", element_count, next_id);
+        for heading in headings {
+            println!("  {}", heading);
+        }
+    }
+    
+    println!("\n{}", "=".repeat(50));
+    
+    // 测试不支持的格式
+    println!("7. 测试不支持的格式:");
+    match processor.process(&blog_post, "pdf") {
         Ok(_) => println!("意外成功"),
         Err(e) => println!("预期错误: {}", e),
     }
@@ -993,4 +1750,545 @@ pub fn demo() {
     println!("• 使用依赖注入管理渲染器");
     println!("• 考虑缓存逻辑页面结构以提高性能");
     println!("• 为不同格式提供合适的错误处理");
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 检查XML是否良构：标签正确嵌套、只有一个根元素、属性值带引号、实体引用合法
+    fn check_well_formed(xml: &str) -> Result<(), String> {
+        fn check_entities(text: &str) -> Result<(), String> {
+            for (index, _) in text.match_indices('&') {
+                let rest = &text[index..];
+                if !["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"].iter().any(|e| rest.starts_with(e)) {
+                    return Err(format!("未转义的&: {}", rest));
+                }
+            }
+            if text.contains('>') {
+                return Err(format!("未转义的>: {}", text));
+            }
+            Ok(())
+        }
+
+        let mut stack: Vec<String> = Vec::new();
+        let mut roots = 0;
+        let mut rest = xml.trim();
+        if let Some(after) = rest.strip_prefix("<?xml") {
+            rest = &after[after.find("?>").ok_or("XML声明未闭合")? + 2..];
+        }
+
+        while let Some(start) = rest.find('<') {
+            check_entities(&rest[..start])?;
+            if stack.is_empty() && !rest[..start].trim().is_empty() {
+                return Err("根元素之外存在文本".to_string());
+            }
+            let end = rest[start..].find('>').ok_or("标签未闭合")? + start;
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                match stack.pop() {
+                    Some(open) if open == name => {}
+                    other => return Err(format!("结束标签</{}>与{:?}不匹配", name, other)),
+                }
+                continue;
+            }
+
+            let self_closing = tag.ends_with('/');
+            let body = tag.trim_end_matches('/');
+            let name_end = body.find(' ').unwrap_or(body.len());
+            let name = &body[..name_end];
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(format!("非法标签名: {}", tag));
+            }
+            let mut attrs = body[name_end..].trim();
+            while !attrs.is_empty() {
+                let eq = attrs.find("=\"").ok_or(format!("属性值缺少引号: {}", attrs))?;
+                let value_end = attrs[eq + 2..].find('"').ok_or("属性值未闭合")? + eq + 2;
+                let value = &attrs[eq + 2..value_end];
+                if value.contains('<') {
+                    return Err(format!("属性值包含<: {}", value));
+                }
+                check_entities(value)?;
+                attrs = attrs[value_end + 1..].trim_start();
+            }
+
+            if stack.is_empty() {
+                roots += 1;
+            }
+            if !self_closing {
+                stack.push(name.to_string());
+            }
+        }
+
+        if !rest.trim().is_empty() {
+            return Err("根元素之后存在文本".to_string());
+        }
+        match (stack.is_empty(), roots) {
+            (true, 1) => Ok(()),
+            (false, _) => Err(format!("未闭合的标签: {:?}", stack)),
+            (true, n) => Err(format!("根元素数量为{}", n)),
+        }
+    }
+
+    fn sample_post() -> BlogPost {
+        BlogPost {
+            title: "<Rust> & \"两步视图\"".to_string(),
+            content: "a < b && c > d".to_string(),
+            author: "O'Neil".to_string(),
+            tags: vec!["Rust".to_string(), "XML&HTML".to_string()],
+            created_at: "2024-01-15 10:30:00".to_string(),
+            comments: vec![Comment {
+                author: "张三".to_string(),
+                content: "<script>alert('x')</script>".to_string(),
+                created_at: "2024-01-15 11:00:00".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_xml_renderer_outputs_well_formed_blog_post() {
+        let processor = TwoStepViewProcessor::new(Box::new(BlogPostPageBuilder))
+            .add_renderer("xml".to_string(), Box::new(XmlRenderer::new()));
+
+        let (xml, content_type) = processor.process(&sample_post(), "xml").unwrap();
+        assert_eq!(content_type, "application/xml");
+        check_well_formed(&xml).unwrap();
+
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains("<title>&lt;Rust&gt; &amp; &quot;两步视图&quot;</title>"));
+        assert!(xml.contains("<text>a &lt; b &amp;&amp; c &gt; d</text>"));
+        assert!(xml.contains("&lt;script&gt;alert(&apos;x&apos;)&lt;/script&gt;"));
+        assert!(xml.contains("<keyword>XML&amp;HTML</keyword>"));
+        assert!(xml.contains(r#"<heading level="1" id="article-title">"#));
+        assert!(xml.contains(r#"<container layout="vertical" class="comment">"#));
+        assert!(xml.contains(r#"<form action="/comments/create" method="POST">"#));
+        assert!(xml.contains(r#"<item path="/blog" title="博客" active="true"/>"#));
+
+        // 不换行输出同样良构
+        let compact = XmlRenderer::new().with_pretty_print(false)
+            .render(&BlogPostPageBuilder.build_page(&sample_post()).unwrap())
+            .unwrap();
+        assert!(!compact.contains('\n'));
+        check_well_formed(&compact).unwrap();
+    }
+
+    #[test]
+    fn test_xml_renderer_covers_every_element_and_escapes_attributes() {
+        let page = LogicalPage::new("全部元素".to_string())
+            .add_metadata("author".to_string(), "a\"b".to_string())
+            .add_element(LogicalElement::List {
+                items: vec![LogicalElement::Link {
+                    url: "/search?q=rust&page=2\"><x".to_string(),
+                    text: "搜索".to_string(),
+                    external: true,
+                }],
+                ordered: true,
+            })
+            .add_element(LogicalElement::Table {
+                headers: vec!["名称".to_string()],
+                rows: vec![vec![LogicalElement::Image {
+                    src: "/a.png".to_string(),
+                    alt: "<图>".to_string(),
+                    width: Some(10),
+                    height: None,
+                }]],
+                caption: Some("表 & 图".to_string()),
+            })
+            .add_element(LogicalElement::Container {
+                children: vec![],
+                layout: ContainerLayout::Grid { columns: 3 },
+                css_class: None,
+            })
+            .add_element(LogicalElement::Form {
+                fields: vec![FormField {
+                    name: "lang".to_string(),
+                    label: "语言".to_string(),
+                    field_type: FieldType::Select {
+                        options: vec![("rs".to_string(), "Rust".to_string()), ("c&c".to_string(), "C".to_string())],
+                    },
+                    required: false,
+                    value: Some("rs".to_string()),
+                    placeholder: None,
+                }],
+                action: "/save".to_string(),
+                method: HttpMethod::PUT,
+            })
+            .add_element(LogicalElement::Navigation {
+                items: vec![NavigationItem {
+                    path: "/docs".to_string(),
+                    title: "文档".to_string(),
+                    active: false,
+                    children: vec![NavigationItem {
+                        path: "/docs/xml".to_string(),
+                        title: "XML".to_string(),
+                        active: true,
+                        children: vec![],
+                    }],
+                }],
+                current_path: None,
+            });
+
+        let xml = XmlRenderer::new().render(&page).unwrap();
+        check_well_formed(&xml).unwrap();
+
+        assert!(xml.contains(r#"<entry key="author">a&quot;b</entry>"#));
+        assert!(xml.contains(r#"<link url="/search?q=rust&amp;page=2&quot;&gt;&lt;x" external="true">搜索</link>"#));
+        assert!(xml.contains(r#"<image src="/a.png" alt="&lt;图&gt;" width="10"/>"#));
+        assert!(xml.contains("<caption>表 &amp; 图</caption>"));
+        assert!(xml.contains(r#"<container layout="grid" columns="3">"#));
+        assert!(xml.contains(r#"<field name="lang" type="select" required="false" value="rs">"#));
+        assert!(xml.contains(r#"<option value="c&amp;c">C</option>"#));
+        assert!(xml.contains(r#"<item path="/docs/xml" title="XML" active="true"/>"#));
+
+        // 检查器本身能识别不良构的输入
+        assert!(check_well_formed("<a><b></a></b>").is_err());
+        assert!(check_well_formed("<a>x & y</a>").is_err());
+        assert!(check_well_formed("<a/><b/>").is_err());
+    }
+
+    fn text(content: &str) -> LogicalElement {
+        LogicalElement::Text { content: content.to_string(), style: HashMap::new() }
+    }
+
+    #[test]
+    fn test_markdown_renderer_nested_lists() {
+        let page = LogicalPage::new("列表".to_string())
+            .add_element(LogicalElement::List {
+                items: vec![
+                    text("准备"),
+                    LogicalElement::Container {
+                        children: vec![
+                            text("实现"),
+                            LogicalElement::List {
+                                items: vec![
+                                    text("第一步"),
+                                    LogicalElement::List { items: vec![text("细节")], ordered: false },
+                                    LogicalElement::Link { url: "/docs/step two".to_string(), text: "第[二]步".to_string(), external: false },
+                                ],
+                                ordered: true,
+                            },
+                        ],
+                        layout: ContainerLayout::Vertical,
+                        css_class: None,
+                    },
+                    LogicalElement::Image { src: "/done.png".to_string(), alt: "完成".to_string(), width: None, height: None },
+                ],
+                ordered: false,
+            });
+
+        let renderer = MarkdownRenderer::new().with_front_matter(false);
+        assert_eq!(renderer.content_type(), "text/markdown");
+        assert_eq!(renderer.render(&page).unwrap(), "\
+- 准备
+- 实现
+  1. 第一步
+  2. - 细节
+  3. [第\\[二\\]步](</docs/step two>)
+- ![完成](/done.png)
+");
+    }
+
+    #[test]
+    fn test_markdown_renderer_gfm_table() {
+        let page = LogicalPage::new("模式: \"表格\"".to_string())
+            .with_keywords(vec!["gfm".to_string()])
+            .add_element(LogicalElement::Heading { level: 2, content: "对比".to_string(), id: None })
+            .add_element(LogicalElement::Table {
+                headers: vec!["格式".to_string(), "说明".to_string()],
+                rows: vec![
+                    vec![text("HTML"), LogicalElement::Link { url: "https://html.spec.whatwg.org".to_string(), text: "规范".to_string(), external: true }],
+                    vec![text("a|b"), LogicalElement::Container {
+                        children: vec![text("第一行"), text("第二行")],
+                        layout: ContainerLayout::Vertical,
+                        css_class: None,
+                    }],
+                    vec![text("缺列")],
+                ],
+                caption: Some("输出格式".to_string()),
+            });
+
+        assert_eq!(MarkdownRenderer::new().render(&page).unwrap(), "\
+---
+title: \"模式: \\\"表格\\\"\"
+keywords: [\"gfm\"]
+---
+
+## 对比
+
+**输出格式**
+
+| 格式 | 说明 |
+| --- | --- |
+| HTML | [规范](https://html.spec.whatwg.org) |
+| a\\|b | 第一行<br>第二行 |
+| 缺列 |  |
+");
+    }
+
+    #[test]
+    fn test_markdown_renderer_degrades_form_to_field_list() {
+        let markdown = MarkdownRenderer::new().render(&BlogPostPageBuilder.build_page(&sample_post()).unwrap()).unwrap();
+
+        assert!(markdown.contains("- [首页](/)\n- **[博客](/blog)**\n- [关于](/about)"));
+        assert!(markdown.contains("# &lt;Rust&gt; &amp; \"两步视图\"\n"));
+        assert!(markdown.contains("**张三 - 2024-01-15 11:00:00**"));
+        assert!(markdown.contains("**表单** `POST /comments/create`\n\n- 姓名 (`author`, 文本, 必填): _请输入您的姓名_\n"));
+    }
+
+    #[test]
+    fn test_markdown_renderer_escapes_text_and_front_matter_keys() {
+        let page = LogicalPage::new("转义".to_string())
+            .add_metadata("a: b".to_string(), "c".to_string())
+            .add_element(LogicalElement::Heading { level: 1, content: "# *不是* 强调".to_string(), id: None })
+            .add_element(text("1. 不是列表 <b>x</b> & [链接](/x) `代码`\n  - 也不是"))
+            .add_element(LogicalElement::Table {
+                headers: vec!["a*b".to_string()],
+                rows: vec![vec![text("+ x|y")]],
+                caption: Some("_说明_".to_string()),
+            });
+
+        assert_eq!(MarkdownRenderer::new().render(&page).unwrap(), "\
+---
+title: \"转义\"
+\"a: b\": \"c\"
+---
+
+# \\# \\*不是\\* 强调
+
+1\\. 不是列表 &lt;b&gt;x&lt;/b&gt; &amp; \\[链接\\](/x) \\`代码\\`
+  \\- 也不是
+
+**\\_说明\\_**
+
+| a\\*b |
+| --- |
+| \\+ x\\|y |
+");
+    }
+
+    fn negotiating_processor() -> TwoStepViewProcessor<BlogPost> {
+        TwoStepViewProcessor::new(Box::new(BlogPostPageBuilder))
+            .add_renderer("html".to_string(), Box::new(HtmlRenderer::new()))
+            .add_renderer("json".to_string(), Box::new(JsonRenderer::new()))
+            .add_renderer("xml".to_string(), Box::new(XmlRenderer::new()))
+            .add_renderer("markdown".to_string(), Box::new(MarkdownRenderer::new()))
+    }
+
+    #[test]
+    fn test_parse_accept_header() {
+        let range = |media_type: &str, subtype: &str, quality: f32| MediaRange {
+            media_type: media_type.to_string(),
+            subtype: subtype.to_string(),
+            quality,
+        };
+
+        assert_eq!(parse_accept("text/html, application/json;q=0.9"),
+            vec![range("text", "html", 1.0), range("application", "json", 0.9)]);
+        assert_eq!(parse_accept(" Text/HTML ; level=1 ; Q=0.5 ,*/*;q=0.1"),
+            vec![range("text", "html", 0.5), range("*", "*", 0.1)]);
+        // 格式错误的条目被忽略
+        assert_eq!(parse_accept("html, */json, text/plain;q=2, text/csv;q=abc, image/*"),
+            vec![range("image", "*", 1.0)]);
+        assert!(parse_accept("").is_empty());
+    }
+
+    #[test]
+    fn test_negotiate_picks_highest_quality_registered_format() {
+        let processor = negotiating_processor();
+
+        assert_eq!(processor.negotiate("text/html, application/json;q=0.9").unwrap(), "html");
+        assert_eq!(processor.negotiate("application/json;q=0.9, text/html;q=0.5").unwrap(), "json");
+        // q值相同时先出现的优先
+        assert_eq!(processor.negotiate("application/xml, application/json").unwrap(), "xml");
+        // 具体的范围覆盖通配范围，q=0表示不可接受
+        assert_eq!(processor.negotiate("text/*;q=0.8, text/html;q=0, application/json;q=0.5").unwrap(), "markdown");
+        assert_eq!(processor.negotiate("image/png, */*;q=0.1").unwrap(), "html");
+
+        let (body, content_type) = processor.process_negotiated(&sample_post(), "application/json").unwrap();
+        assert_eq!(content_type, "application/json");
+        assert!(serde_json::from_str::<serde_json::Value>(&body).is_ok());
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_default_format() {
+        let processor = negotiating_processor();
+        assert!(matches!(processor.negotiate("image/png"), Err(ProcessError::UnsupportedFormat(_))));
+        assert!(matches!(processor.negotiate("application/json;q=0"), Err(ProcessError::UnsupportedFormat(_))));
+
+        let processor = negotiating_processor().with_default_format("markdown".to_string());
+        assert_eq!(processor.negotiate("image/png").unwrap(), "markdown");
+        // 空Accept头接受任意格式，默认格式优先
+        assert_eq!(processor.negotiate("").unwrap(), "markdown");
+        assert_eq!(processor.negotiate("application/*").unwrap(), "json");
+    }
+
+    fn link(url: &str) -> LogicalElement {
+        LogicalElement::Link { url: url.to_string(), text: "点击".to_string(), external: false }
+    }
+
+    #[test]
+    fn test_html_renderer_escapes_url_attributes() {
+        let page = LogicalPage::new("链接".to_string())
+            .add_element(link("/search?q=a&b=\" onmouseover=\"alert(1)"))
+            .add_element(LogicalElement::Image {
+                src: "/img.png'><script>x</script>".to_string(),
+                alt: "\"><b>".to_string(),
+                width: None,
+                height: None,
+            })
+            .add_element(link("https://example.com/a b"));
+
+        let html = HtmlRenderer::new().render(&page).unwrap();
+        assert!(html.contains(r#"<a href="/search?q=a&amp;b=&quot; onmouseover=&quot;alert(1)">点击</a>"#));
+        assert!(html.contains(r#"<img src="/img.png&#39;&gt;&lt;script&gt;x&lt;/script&gt;" alt="&quot;&gt;&lt;b&gt;" />"#));
+        assert!(html.contains(r#"<a href="https://example.com/a b">"#));
+        assert!(!html.contains("onmouseover=\"alert"));
+    }
+
+    #[test]
+    fn test_html_renderer_rejects_dangerous_url_schemes() {
+        let malicious = ["javascript:alert(1)", "  JaVaScRiPt:alert(1)", "java\tscript:alert(1)", "\u{1}vbscript:msgbox(1)", "data:text/html,<script>alert(1)</script>"];
+        let mut page = LogicalPage::new("恶意链接".to_string())
+            .add_element(LogicalElement::Form { fields: vec![], action: "javascript:steal()".to_string(), method: HttpMethod::POST })
+            .add_element(LogicalElement::Navigation {
+                items: vec![NavigationItem { path: "javascript:void(0)".to_string(), title: "首页".to_string(), active: false, children: vec![] }],
+                current_path: None,
+            });
+        for url in malicious {
+            page = page.add_element(link(url));
+        }
+        page = page.add_element(link("/javascript:in-path")).add_element(link("https://example.com/?next=javascript:x"));
+
+        let html = HtmlRenderer::new().render(&page).unwrap();
+        assert_eq!(html.matches(r##"<a href="#">点击</a>"##).count(), malicious.len());
+        assert!(html.contains(r##"<form action="#" method="post">"##));
+        assert!(html.contains(r##"<li><a href="#">首页</a></li>"##));
+        // 只检查协议，路径和查询参数中的同名字符串不受影响
+        assert!(html.contains(r#"<a href="/javascript:in-path">"#));
+        assert!(html.contains(r#"<a href="https://example.com/?next=javascript:x">"#));
+
+        // 关闭净化后保留原URL，但仍然转义
+        let raw = HtmlRenderer::new().with_url_sanitizer(false).render(&page).unwrap();
+        assert!(raw.contains(r#"<a href="data:text/html,&lt;script&gt;alert(1)&lt;/script&gt;">"#));
+        assert!(raw.contains(r#"<form action="javascript:steal()" method="post">"#));
+    }
+
+    #[test]
+    fn test_html_renderer_escapes_id_style_and_field_attributes() {
+        let injection = "x\" onclick=\"alert(1)";
+        let mut style = HashMap::new();
+        style.insert("color".to_string(), injection.to_string());
+        let page = LogicalPage::new("属性".to_string())
+            .add_element(LogicalElement::Heading { level: 2, content: "标题".to_string(), id: Some(injection.to_string()) })
+            .add_element(LogicalElement::Text { content: "文本".to_string(), style })
+            .add_element(LogicalElement::Form {
+                fields: vec![
+                    FormField {
+                        name: injection.to_string(),
+                        label: "名称".to_string(),
+                        field_type: FieldType::Select { options: vec![(injection.to_string(), "选项".to_string())] },
+                        required: false,
+                        value: Some(injection.to_string()),
+                        placeholder: None,
+                    },
+                    FormField {
+                        name: "token".to_string(),
+                        label: "令牌".to_string(),
+                        field_type: FieldType::Hidden,
+                        required: false,
+                        value: Some(injection.to_string()),
+                        placeholder: None,
+                    },
+                ],
+                action: "/submit".to_string(),
+                method: HttpMethod::POST,
+            });
+
+        let html = HtmlRenderer::new().render(&page).unwrap();
+        assert!(!html.contains("onclick=\""));
+        assert!(html.contains(r#"<h2 id="x&quot; onclick=&quot;alert(1)">"#));
+        assert!(html.contains(r#"style="color:x&quot; onclick=&quot;alert(1)""#));
+        assert!(html.contains(r#"<select id="x&quot; onclick=&quot;alert(1)" name="x&quot; onclick=&quot;alert(1)">"#));
+        // 转义不影响选中状态的比较
+        assert!(html.contains(r#"<option value="x&quot; onclick=&quot;alert(1)" selected>"#));
+        assert!(html.contains(r#"<input type="hidden" name="token" value="x&quot; onclick=&quot;alert(1)" />"#));
+    }
+
+    fn heading(level: u8, content: &str) -> LogicalElement {
+        LogicalElement::Heading { level, content: content.to_string(), id: None }
+    }
+
+    fn nested_page() -> LogicalPage {
+        LogicalPage::new("嵌套".to_string())
+            .add_element(heading(1, "标题"))
+            .add_element(LogicalElement::Container {
+                children: vec![
+                    heading(2, "容器中的标题"),
+                    LogicalElement::List {
+                        items: vec![text("条目"), LogicalElement::List { items: vec![heading(3, "嵌套列表中的标题")], ordered: true }],
+                        ordered: false,
+                    },
+                    LogicalElement::Table {
+                        headers: vec!["列".to_string()],
+                        rows: vec![vec![text("单元格")], vec![LogicalElement::Container {
+                            children: vec![heading(4, "单元格中的标题")],
+                            layout: ContainerLayout::Flex,
+                            css_class: None,
+                        }]],
+                        caption: None,
+                    },
+                ],
+                layout: ContainerLayout::Vertical,
+                css_class: None,
+            })
+    }
+
+    #[test]
+    fn test_visit_counts_headings_in_nested_elements() {
+        let page = nested_page();
+
+        let mut headings = 0;
+        page.visit(|element| {
+            if let LogicalElement::Heading { .. } = element {
+                headings += 1;
+            }
+        });
+        assert_eq!(headings, 4);
+
+        // 深度优先先序：父元素先于子元素，兄弟元素按文档顺序
+        let mut order = Vec::new();
+        page.visit(|element| match element {
+            LogicalElement::Heading { content, .. } | LogicalElement::Text { content, .. } => order.push(content.clone()),
+            LogicalElement::Container { .. } => order.push("容器".to_string()),
+            LogicalElement::List { .. } => order.push("列表".to_string()),
+            LogicalElement::Table { .. } => order.push("表格".to_string()),
+            _ => {}
+        });
+        assert_eq!(order, vec![
+            "标题", "容器", "容器中的标题", "列表", "条目", "列表", "嵌套列表中的标题",
+            "表格", "单元格", "容器", "单元格中的标题",
+        ]);
+    }
+
+    #[test]
+    fn test_visit_mut_injects_heading_ids() {
+        let mut page = nested_page();
+
+        let mut next_id = 0;
+        page.visit_mut(|element| {
+            if let LogicalElement::Heading { id, .. } = element {
+                next_id += 1;
+                *id = Some(format!("h{}", next_id));
+            }
+        });
+
+        let mut ids = Vec::new();
+        page.elements[1].visit(|element| {
+            if let LogicalElement::Heading { id: Some(id), .. } = element {
+                ids.push(id.clone());
+            }
+        });
+        assert_eq!(ids, vec!["h2", "h3", "h4"]);
+    }
+}
@@ -24,11 +24,13 @@
  * - 实现Pipe trait支持管道操作符
  * - 提供Combinator结构体支持链式组合
  * - 包含数学函数组合器的实用工具
+ * - 提供Trampoline蹦床，把深度递归转换为循环执行，避免栈溢出
  * 
  * 注意事项：
  * - 函数组合的顺序很重要，需要确保类型匹配
  * - 过度组合可能导致代码难以调试
  * - 在性能敏感的场景中要注意组合的开销
+ * - Rust不保证尾调用优化，深度递归应改用Trampoline
  */
 
 /// 基础函数组合 - 组合两个函数
@@ -95,6 +97,81 @@ impl MathComposer {
     }
 }
 
+/// 蹦床 - 把递归调用表示为数据，由run循环逐步求值
+///
+/// 递归函数不再直接调用自身，而是返回 `More(下一步)`，
+/// `run` 在循环中不断展开，调用栈深度始终为常数。
+pub enum Trampoline<T> {
+    Done(T),
+    More(Box<dyn FnOnce() -> Trampoline<T>>),
+}
+
+impl<T> Trampoline<T> {
+    pub fn done(value: T) -> Self {
+        Trampoline::Done(value)
+    }
+
+    /// 推迟下一步计算
+    pub fn more<F>(thunk: F) -> Self
+    where
+        F: FnOnce() -> Trampoline<T> + 'static,
+    {
+        Trampoline::More(Box::new(thunk))
+    }
+
+    /// 迭代求值直到得到最终结果
+    pub fn run(self) -> T {
+        let mut current = self;
+        loop {
+            match current {
+                Trampoline::Done(value) => return value,
+                Trampoline::More(thunk) => current = thunk(),
+            }
+        }
+    }
+}
+
+/// 蹦床化的递归示例
+pub struct TrampolineExamples;
+
+impl TrampolineExamples {
+    /// 互递归判断偶数
+    pub fn is_even(n: u64) -> Trampoline<bool> {
+        if n == 0 {
+            Trampoline::done(true)
+        } else {
+            Trampoline::more(move || Self::is_odd(n - 1))
+        }
+    }
+
+    /// 互递归判断奇数
+    pub fn is_odd(n: u64) -> Trampoline<bool> {
+        if n == 0 {
+            Trampoline::done(false)
+        } else {
+            Trampoline::more(move || Self::is_even(n - 1))
+        }
+    }
+
+    /// 累加 1..=n，带累加器的尾递归
+    pub fn sum_to(n: u64, acc: u64) -> Trampoline<u64> {
+        if n == 0 {
+            Trampoline::done(acc)
+        } else {
+            Trampoline::more(move || Self::sum_to(n - 1, acc + n))
+        }
+    }
+
+    /// 对比用的普通递归版本，深度过大时会栈溢出
+    pub fn sum_to_recursive(n: u64) -> u64 {
+        if n == 0 {
+            0
+        } else {
+            n + Self::sum_to_recursive(n - 1)
+        }
+    }
+}
+
 /// 函数组合模式演示
 pub fn demo_function_composition() {
     println!("=== 函数组合模式演示 ===");
@@ -129,8 +206,33 @@ pub fn demo_function_composition() {
     let math_result = compose(compose(add_10, multiply_2), square)(5);
     println!("数学组合 (5): {}", math_result);
     
+    // 蹦床 - 深度递归
+    println!("普通递归 sum(1..=1000) = {}", TrampolineExamples::sum_to_recursive(1_000));
+    println!("蹦床递归 sum(1..=1000000) = {}", TrampolineExamples::sum_to(1_000_000, 0).run());
+    println!("1000001 是偶数吗? {}", TrampolineExamples::is_even(1_000_001).run());
+    
     println!("\n【函数组合模式特点】");
     println!("✓ 模块化 - 将复杂操作分解为简单函数的组合");
     println!("✓ 可重用性 - 小函数可以在多个组合中复用");
     println!("✓ 可读性 - 函数组合清晰表达了数据流");
-} 
\ No newline at end of file
+    println!("✓ 蹦床 - 深度递归以循环执行，不会栈溢出");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutual_recursion_million_deep() {
+        assert!(TrampolineExamples::is_even(1_000_000).run());
+        assert!(TrampolineExamples::is_odd(1_000_001).run());
+        assert!(!TrampolineExamples::is_even(999_999).run());
+    }
+
+    #[test]
+    fn test_deep_sum_completes_via_trampoline() {
+        // 普通递归在测试线程的栈上无法达到这个深度
+        assert_eq!(TrampolineExamples::sum_to(1_000_000, 0).run(), 500_000_500_000);
+        assert_eq!(TrampolineExamples::sum_to(100, 0).run(), TrampolineExamples::sum_to_recursive(100));
+    }
+}
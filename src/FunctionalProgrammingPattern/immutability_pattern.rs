@@ -5,6 +5,9 @@
  * 
  * 不变性是函数式编程的核心概念，指数据一旦创建就不能被修改。
  * 这种模式提供了线程安全、可预测性和无副作用的保证。
+ * 
+ * Lens（透镜）用于深层嵌套结构的不可变更新：每个Lens聚焦结构中的一个字段，
+ * 多个Lens组合后可以直接"穿透"多层结构读取或替换字段，无需手动逐层重建。
  */
 
 use std::collections::HashMap;
@@ -319,6 +322,110 @@ impl DataTransformer {
     }
 }
 
+/// 透镜的读取函数
+type LensGetter<S, A> = Rc<dyn Fn(&S) -> A>;
+/// 透镜的写入函数，返回替换了字段的新结构
+type LensSetter<S, A> = Rc<dyn Fn(&S, A) -> S>;
+
+/// 透镜 - 聚焦结构S中类型为A的一个字段
+///
+/// `get` 读取字段，`set` 返回替换了该字段的新结构，原结构保持不变。
+pub struct Lens<S, A> {
+    getter: LensGetter<S, A>,
+    setter: LensSetter<S, A>,
+}
+
+impl<S, A> Clone for Lens<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            getter: Rc::clone(&self.getter),
+            setter: Rc::clone(&self.setter),
+        }
+    }
+}
+
+impl<S: 'static, A: 'static> Lens<S, A> {
+    pub fn new<G, St>(getter: G, setter: St) -> Self
+    where
+        G: Fn(&S) -> A + 'static,
+        St: Fn(&S, A) -> S + 'static,
+    {
+        Self {
+            getter: Rc::new(getter),
+            setter: Rc::new(setter),
+        }
+    }
+    
+    /// 读取聚焦的字段
+    pub fn get(&self, source: &S) -> A {
+        (self.getter)(source)
+    }
+    
+    /// 替换聚焦的字段（返回新结构）
+    pub fn set(&self, source: &S, value: A) -> S {
+        (self.setter)(source, value)
+    }
+    
+    /// 基于旧值计算新值（返回新结构）
+    pub fn modify<F>(&self, source: &S, f: F) -> S
+    where
+        F: FnOnce(A) -> A,
+    {
+        self.set(source, f(self.get(source)))
+    }
+    
+    /// 组合透镜：先聚焦到A，再聚焦到A中的B
+    pub fn compose<B: 'static>(&self, inner: Lens<A, B>) -> Lens<S, B> {
+        let outer_get = self.clone();
+        let outer_set = self.clone();
+        let inner_get = inner.clone();
+        Lens::new(
+            move |source: &S| inner_get.get(&outer_get.get(source)),
+            move |source: &S, value: B| {
+                let child = outer_set.get(source);
+                outer_set.set(source, inner.set(&child, value))
+            },
+        )
+    }
+}
+
+/// 嵌套记录树示例：公司 -> 总部地址 -> 城市
+#[derive(Debug, Clone, PartialEq)]
+pub struct Address {
+    pub city: String,
+    pub street: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Company {
+    pub name: String,
+    pub headquarters: Address,
+}
+
+/// 预定义的字段透镜
+pub struct CompanyLenses;
+
+impl CompanyLenses {
+    pub fn headquarters() -> Lens<Company, Address> {
+        Lens::new(
+            |company: &Company| company.headquarters.clone(),
+            |company: &Company, headquarters| Company { headquarters, ..company.clone() },
+        )
+    }
+    
+    pub fn city() -> Lens<Address, String> {
+        Lens::new(
+            |address: &Address| address.city.clone(),
+            |address: &Address, city| Address { city, ..address.clone() },
+        )
+    }
+    
+    /// 公司总部所在城市
+    pub fn headquarters_city() -> Lens<Company, String> {
+        Self::headquarters().compose(Self::city())
+    }
+}
+
 /// 不变性模式演示
 pub fn demo_immutability_pattern() {
     println!("=== 不变性模式演示 ===");
@@ -383,10 +490,59 @@ pub fn demo_immutability_pattern() {
     println!("原始数据: {:?}", numbers);
     println!("转换结果: {:?}", transformed);
     
+    // 7. 透镜 - 嵌套结构的不可变更新
+    println!("\n7. 透镜:");
+    let company = Company {
+        name: "示例科技".to_string(),
+        headquarters: Address { city: "北京".to_string(), street: "中关村大街1号".to_string() },
+    };
+    let city_lens = CompanyLenses::headquarters_city();
+    let relocated = city_lens.set(&company, "上海".to_string());
+    println!("原公司总部: {}", city_lens.get(&company));
+    println!("迁址后总部: {} ({})", city_lens.get(&relocated), relocated.headquarters.street);
+    
     println!("\n【不变性模式特点】");
     println!("✓ 线程安全 - 不可变数据天然线程安全");
     println!("✓ 可预测性 - 数据不会意外改变");
     println!("✓ 无副作用 - 函数不会修改输入数据");
     println!("✓ 历史追踪 - 可以保留所有历史版本");
     println!("✓ 函数式编程 - 支持纯函数式操作");
-} 
\ No newline at end of file
+    println!("✓ 透镜 - 组合式访问器简化嵌套结构的更新");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_company() -> Company {
+        Company {
+            name: "示例科技".to_string(),
+            headquarters: Address { city: "北京".to_string(), street: "中关村大街1号".to_string() },
+        }
+    }
+
+    #[test]
+    fn test_composed_lens_updates_grandchild_only() {
+        let company = sample_company();
+        let city = CompanyLenses::headquarters_city();
+
+        let updated = city.set(&company, "深圳".to_string());
+
+        assert_eq!(updated.headquarters.city, "深圳");
+        // 兄弟字段和上层字段保持不变
+        assert_eq!(updated.headquarters.street, company.headquarters.street);
+        assert_eq!(updated.name, company.name);
+        // 原结构不受影响
+        assert_eq!(company, sample_company());
+    }
+
+    #[test]
+    fn test_lens_get_and_modify() {
+        let company = sample_company();
+        let city = CompanyLenses::headquarters_city();
+
+        assert_eq!(city.get(&company), "北京");
+        let modified = city.modify(&company, |name| format!("{}市", name));
+        assert_eq!(city.get(&modified), "北京市");
+    }
+}
@@ -16,6 +16,8 @@ pub mod monad_pattern;
 pub mod functor_pattern;
 pub mod lazy_evaluation;
 pub mod immutability_pattern;
+pub mod validation_pattern;
+pub mod transducer_pattern;
 
 // 重新导出演示函数
 pub use higher_order_functions::demo_higher_order_functions;
@@ -26,6 +28,8 @@ pub use monad_pattern::demo_monad_pattern;
 pub use functor_pattern::demo_functor_pattern;
 pub use lazy_evaluation::demo_lazy_evaluation;
 pub use immutability_pattern::demo_immutability_pattern;
+pub use validation_pattern::demo_validation_pattern;
+pub use transducer_pattern::demo_transducer_pattern;
 
 /// 演示所有函数式编程模式
 pub fn demo_all_functional_patterns() {
@@ -61,6 +65,14 @@ pub fn demo_all_functional_patterns() {
     
     // 8. 不变性模式
     demo_immutability_pattern();
+    println!();
+    
+    // 9. 验证应用函子模式
+    demo_validation_pattern();
+    println!();
+    
+    // 10. 转换器模式
+    demo_transducer_pattern();
     
     println!("\n=== 函数式编程模式演示完成 ===");
     println!("\n【函数式编程模式总结】");
@@ -72,4 +84,6 @@ pub fn demo_all_functional_patterns() {
     println!("✓ 函子 - 可映射的容器抽象");
     println!("✓ 惰性求值 - 按需计算，提高性能");
     println!("✓ 不变性 - 数据不可变，保证线程安全和可预测性");
+    println!("✓ 验证应用函子 - 组合独立校验并累积所有错误");
+    println!("✓ 转换器 - 与集合无关、可组合的单次遍历转换");
 } 
\ No newline at end of file
@@ -29,6 +29,8 @@
  * - 实现Maybe单子用于处理可能不存在的值
  * - 提供map和flat_map操作支持函子和单子接口
  * - 包含实际的计算器示例展示链式操作
+ * - 实现State单子，在纯函数链中隐式传递状态
+ * - 实现Reader单子，以函数式的方式注入只读环境（依赖注入）
  * - 遵循Rust的类型系统和所有权规则
  * 
  * 注意事项：
@@ -110,6 +112,259 @@ impl Calculator {
     }
 }
 
+/// State单子 - 把状态在纯计算链中隐式传递
+///
+/// 本质上是一个 `S -> (A, S)` 的函数，每次bind都会把上一步产生的新状态
+/// 交给下一步，调用者无需手动传递。
+///
+/// 栈深度限制：`run` 时每一层 `bind` 都会在调用栈上占用若干帧，
+/// 左嵌套的长链深度与bind次数成正比。在默认2MB的线程栈上数千层是安全的，
+/// 更深的循环应使用 [`State::repeat`]，它在单个闭包内迭代执行，栈深度恒定。
+pub struct State<S, A> {
+    run_state: Box<dyn FnOnce(S) -> (A, S)>,
+}
+
+impl<S: 'static, A: 'static> State<S, A> {
+    /// 从状态转换函数创建
+    pub fn new<F>(f: F) -> Self
+    where
+        F: FnOnce(S) -> (A, S) + 'static,
+    {
+        Self { run_state: Box::new(f) }
+    }
+
+    /// return操作 - 包装一个值，不改变状态
+    pub fn pure(value: A) -> Self {
+        Self::new(move |state| (value, state))
+    }
+
+    /// 以初始状态运行，返回结果和最终状态
+    pub fn run(self, initial: S) -> (A, S) {
+        (self.run_state)(initial)
+    }
+
+    /// 只关心结果
+    pub fn eval(self, initial: S) -> A {
+        self.run(initial).0
+    }
+
+    /// 只关心最终状态
+    pub fn exec(self, initial: S) -> S {
+        self.run(initial).1
+    }
+
+    /// map操作 - 函子功能
+    pub fn map<B: 'static, F>(self, f: F) -> State<S, B>
+    where
+        F: FnOnce(A) -> B + 'static,
+    {
+        State::new(move |state| {
+            let (value, next) = self.run(state);
+            (f(value), next)
+        })
+    }
+
+    /// bind操作 - 单子功能，下一步计算可依赖上一步的结果
+    pub fn bind<B: 'static, F>(self, f: F) -> State<S, B>
+    where
+        F: FnOnce(A) -> State<S, B> + 'static,
+    {
+        State::new(move |state| {
+            let (value, next) = self.run(state);
+            f(value).run(next)
+        })
+    }
+
+    /// 忽略上一步结果，顺序执行下一步
+    pub fn then<B: 'static>(self, next: State<S, B>) -> State<S, B> {
+        self.bind(move |_| next)
+    }
+}
+
+impl<S: Clone + 'static> State<S, S> {
+    /// 读取当前状态
+    pub fn get() -> Self {
+        State::new(|state: S| (state.clone(), state))
+    }
+}
+
+impl<S: 'static> State<S, ()> {
+    /// 替换当前状态
+    pub fn put(new_state: S) -> Self {
+        State::new(move |_| ((), new_state))
+    }
+
+    /// 用函数修改当前状态
+    pub fn modify<F>(f: F) -> Self
+    where
+        F: FnOnce(S) -> S + 'static,
+    {
+        State::new(move |state| ((), f(state)))
+    }
+
+    /// 重复执行同一个状态步骤 `times` 次，栈深度不随次数增长
+    pub fn repeat<F>(times: usize, step: F) -> Self
+    where
+        F: Fn(S) -> S + 'static,
+    {
+        State::new(move |mut state| {
+            for _ in 0..times {
+                state = step(state);
+            }
+            ((), state)
+        })
+    }
+}
+
+/// 栈式计算器 - State单子示例，状态为操作数栈
+pub struct StackMachine;
+
+impl StackMachine {
+    pub fn push(value: i64) -> State<Vec<i64>, ()> {
+        State::modify(move |mut stack: Vec<i64>| {
+            stack.push(value);
+            stack
+        })
+    }
+
+    pub fn pop() -> State<Vec<i64>, Option<i64>> {
+        State::new(|mut stack: Vec<i64>| {
+            let top = stack.pop();
+            (top, stack)
+        })
+    }
+
+    /// 弹出两个操作数，把运算结果压回栈顶
+    pub fn binary_op<F>(op: F) -> State<Vec<i64>, Option<i64>>
+    where
+        F: FnOnce(i64, i64) -> i64 + 'static,
+    {
+        Self::pop().bind(move |right| {
+            Self::pop().bind(move |left| match (left, right) {
+                (Some(left), Some(right)) => {
+                    let result = op(left, right);
+                    Self::push(result).map(move |_| Some(result))
+                }
+                _ => State::pure(None),
+            })
+        })
+    }
+
+    /// 计算 (a + b) * c
+    pub fn add_then_multiply(a: i64, b: i64, c: i64) -> State<Vec<i64>, Option<i64>> {
+        Self::push(a)
+            .then(Self::push(b))
+            .then(Self::binary_op(|x, y| x + y))
+            .then(Self::push(c))
+            .then(Self::binary_op(|x, y| x * y))
+    }
+}
+
+/// Reader单子 - 在计算链中传递共享的只读环境
+///
+/// 本质上是一个 `&Env -> A` 的函数。计算链中的每一步都能读取同一份环境，
+/// 但环境只在 `run` 时才提供一次，适合以函数式的方式做依赖注入。
+pub struct Reader<Env, A> {
+    run_reader: Box<dyn FnOnce(&Env) -> A>,
+}
+
+impl<Env: 'static, A: 'static> Reader<Env, A> {
+    /// 从读取环境的函数创建
+    pub fn new<F>(f: F) -> Self
+    where
+        F: FnOnce(&Env) -> A + 'static,
+    {
+        Self { run_reader: Box::new(f) }
+    }
+
+    /// return操作 - 包装一个与环境无关的值
+    pub fn pure(value: A) -> Self {
+        Self::new(move |_| value)
+    }
+
+    /// 读取环境中的某一部分
+    pub fn asks<F>(f: F) -> Self
+    where
+        F: FnOnce(&Env) -> A + 'static,
+    {
+        Self::new(f)
+    }
+
+    /// 在给定环境中运行
+    pub fn run(self, env: &Env) -> A {
+        (self.run_reader)(env)
+    }
+
+    /// map操作 - 函子功能
+    pub fn map<B: 'static, F>(self, f: F) -> Reader<Env, B>
+    where
+        F: FnOnce(A) -> B + 'static,
+    {
+        Reader::new(move |env| f(self.run(env)))
+    }
+
+    /// bind操作 - 单子功能，下一步计算共享同一份环境
+    pub fn bind<B: 'static, F>(self, f: F) -> Reader<Env, B>
+    where
+        F: FnOnce(A) -> Reader<Env, B> + 'static,
+    {
+        Reader::new(move |env| {
+            let value = self.run(env);
+            f(value).run(env)
+        })
+    }
+
+    /// 用修改后的环境运行子计算，修改只在该子计算内可见
+    pub fn local<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&Env) -> Env + 'static,
+    {
+        Reader::new(move |env| {
+            let modified = f(env);
+            self.run(&modified)
+        })
+    }
+}
+
+impl<Env: Clone + 'static> Reader<Env, Env> {
+    /// 读取整个环境
+    pub fn ask() -> Self {
+        Reader::new(|env: &Env| env.clone())
+    }
+}
+
+/// 定价配置 - Reader单子的环境
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricingConfig {
+    pub tax_rate: f64,
+    pub discount_rate: f64,
+    pub currency: String,
+}
+
+/// 依赖配置的定价计算
+pub struct PriceCalculator;
+
+impl PriceCalculator {
+    pub fn apply_discount(price: f64) -> Reader<PricingConfig, f64> {
+        Reader::asks(move |config: &PricingConfig| price * (1.0 - config.discount_rate))
+    }
+
+    pub fn apply_tax(price: f64) -> Reader<PricingConfig, f64> {
+        Reader::asks(move |config: &PricingConfig| price * (1.0 + config.tax_rate))
+    }
+
+    pub fn format_price(price: f64) -> Reader<PricingConfig, String> {
+        Reader::asks(move |config: &PricingConfig| format!("{:.2} {}", price, config.currency))
+    }
+
+    /// 先打折再计税，最后按配置的币种格式化
+    pub fn final_price(price: f64) -> Reader<PricingConfig, String> {
+        Self::apply_discount(price)
+            .bind(Self::apply_tax)
+            .bind(Self::format_price)
+    }
+}
+
 /// 单子模式演示
 pub fn demo_monad_pattern() {
     println!("=== 单子模式演示 ===");
@@ -135,8 +390,102 @@ pub fn demo_monad_pattern() {
     println!("sqrt(16/4) = {:?}", calc1);
     println!("sqrt(16/0) = {:?}", calc2);
     
+    // State单子 - 栈式计算器
+    let (result, stack) = StackMachine::add_then_multiply(2, 3, 4).run(Vec::new());
+    println!("(2 + 3) * 4 = {:?}, 剩余栈: {:?}", result, stack);
+    
+    // State单子 - 计数器
+    let counter = State::modify(|n: u32| n + 1)
+        .then(State::modify(|n: u32| n * 10))
+        .then(State::get())
+        .map(|n| format!("计数器: {}", n));
+    println!("{}", counter.eval(0));
+    
+    // Reader单子 - 配置驱动的定价
+    let config = PricingConfig { tax_rate: 0.1, discount_rate: 0.2, currency: "CNY".to_string() };
+    println!("最终价格: {}", PriceCalculator::final_price(100.0).run(&config));
+    
+    let vip_price = PriceCalculator::final_price(100.0)
+        .local(|config: &PricingConfig| PricingConfig { discount_rate: 0.5, ..config.clone() });
+    println!("VIP价格(局部覆盖折扣): {}", vip_price.run(&config));
+    
     println!("\n【单子模式特点】");
     println!("✓ 链式操作 - 通过bind/flat_map实现操作链");
     println!("✓ 错误处理 - 优雅地处理可能失败的操作");
     println!("✓ 组合性 - 单子可以轻松组合和嵌套");
-} 
\ No newline at end of file
+    println!("✓ 状态传递 - State单子在纯函数中隐式传递状态");
+    println!("✓ 依赖注入 - Reader单子在计算链中共享只读环境");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_threads_through_stack_machine() {
+        let (result, stack) = StackMachine::add_then_multiply(2, 3, 4).run(vec![100]);
+
+        assert_eq!(result, Some(20));
+        // 初始栈中已有的元素不受影响，结果留在栈顶
+        assert_eq!(stack, vec![100, 20]);
+
+        // 操作数不足时返回None
+        let (underflow, stack) = StackMachine::binary_op(|x, y| x + y).run(vec![1]);
+        assert_eq!(underflow, None);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_get_put_modify_sequence() {
+        let program = State::get()
+            .bind(|start: i32| State::put(start * 2).map(move |_| start))
+            .bind(|start| State::modify(|n: i32| n + 1).then(State::get()).map(move |now| (start, now)));
+
+        let ((start, now), final_state) = program.run(5);
+
+        assert_eq!((start, now), (5, 11));
+        assert_eq!(final_state, 11);
+    }
+
+    #[test]
+    fn test_long_chains_do_not_overflow() {
+        // 在测试线程的默认栈上运行一千层bind链
+        let chain = (0..1_000).fold(State::pure(()), |acc, _| acc.then(State::modify(|n: u64| n + 1)));
+        assert_eq!(chain.exec(0), 1_000);
+
+        // repeat的栈深度恒定，可以处理任意次数
+        assert_eq!(State::repeat(1_000_000, |n: u64| n + 1).exec(0), 1_000_000);
+    }
+
+    fn pricing_config() -> PricingConfig {
+        PricingConfig { tax_rate: 0.1, discount_rate: 0.2, currency: "CNY".to_string() }
+    }
+
+    #[test]
+    fn test_reader_chain_reads_shared_environment() {
+        let config = pricing_config();
+
+        assert_eq!(PriceCalculator::final_price(100.0).run(&config), "88.00 CNY");
+
+        let currency = Reader::ask().map(|config: PricingConfig| config.currency);
+        assert_eq!(currency.run(&config), "CNY");
+    }
+
+    #[test]
+    fn test_local_only_affects_sub_computation() {
+        let config = pricing_config();
+
+        // 只有打折这一步使用修改后的环境，计税和格式化仍使用原环境
+        let program = PriceCalculator::apply_discount(100.0)
+            .local(|config: &PricingConfig| PricingConfig { discount_rate: 0.5, ..config.clone() })
+            .bind(PriceCalculator::apply_tax)
+            .bind(PriceCalculator::format_price)
+            .bind(|price| Reader::asks(move |config: &PricingConfig| (price, config.discount_rate)));
+
+        let (price, discount_after) = program.run(&config);
+
+        assert_eq!(price, "55.00 CNY");
+        assert_eq!(discount_after, 0.2);
+        assert_eq!(config, pricing_config());
+    }
+}
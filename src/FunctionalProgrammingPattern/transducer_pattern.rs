@@ -0,0 +1,246 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/FunctionalProgrammingPattern/transducer_pattern.rs
+ *
+ * 转换器模式 (Transducer Pattern)
+ *
+ * 转换器是"对归约函数的变换"：它接收一个归约函数，返回一个新的归约函数。
+ * mapping、filtering、taking等操作因此与输入输出的集合类型完全解耦，
+ * 组合后的转换只需一次遍历，中间不会产生临时集合。
+ *
+ * 主要特点：
+ * 1. 集合无关 - 同一个转换可以用在Vec、迭代器或任意fold上
+ * 2. 可组合 - 转换器通过compose组合，顺序与数据流方向一致
+ * 3. 单次遍历 - 组合后的操作在一次遍历中完成，没有中间分配
+ * 4. 提前终止 - 归约函数可以返回Stop，taking借此停止消费输入
+ *
+ * 使用场景：
+ * - 数据管道：同一套清洗逻辑复用于批处理和流式数据
+ * - 聚合统计：在一次遍历中完成过滤、转换和求和
+ * - 事件处理：对通道或队列中的消息应用相同的转换
+ *
+ * 实现说明：
+ * - Step<Acc>：归约的单步结果，区分继续和终止
+ * - Reducer<Acc, T>：装箱的归约函数
+ * - Transducer<A, B, Acc>：把消费B的归约函数变换为消费A的归约函数
+ * - transduce：用转换器和基础归约函数对数据源做一次归约
+ *
+ * 注意事项：
+ * - taking等有状态的转换器在每次apply时创建独立的状态，可重复使用
+ * - 为了便于组合，这里使用了装箱闭包，性能敏感的场景可改为泛型实现
+ */
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// 归约的单步结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step<Acc> {
+    /// 继续消费后续元素
+    Continue(Acc),
+    /// 停止消费，以当前累加值作为结果
+    Stop(Acc),
+}
+
+impl<Acc> Step<Acc> {
+    pub fn into_inner(self) -> Acc {
+        match self {
+            Step::Continue(acc) | Step::Stop(acc) => acc,
+        }
+    }
+}
+
+/// 归约函数
+pub type Reducer<Acc, T> = Box<dyn FnMut(Acc, T) -> Step<Acc>>;
+
+/// 归约函数的变换
+type Xform<A, B, Acc> = Box<dyn Fn(Reducer<Acc, B>) -> Reducer<Acc, A>>;
+
+/// 转换器 - 把消费B的归约函数变换为消费A的归约函数
+pub struct Transducer<A, B, Acc> {
+    xform: Xform<A, B, Acc>,
+}
+
+impl<A: 'static, B: 'static, Acc: 'static> Transducer<A, B, Acc> {
+    pub fn new<F>(xform: F) -> Self
+    where
+        F: Fn(Reducer<Acc, B>) -> Reducer<Acc, A> + 'static,
+    {
+        Self { xform: Box::new(xform) }
+    }
+
+    /// 把转换应用到归约函数上
+    pub fn apply(&self, reducer: Reducer<Acc, B>) -> Reducer<Acc, A> {
+        (self.xform)(reducer)
+    }
+
+    /// 组合转换器，数据先经过self再经过next
+    pub fn compose<C: 'static>(self, next: Transducer<B, C, Acc>) -> Transducer<A, C, Acc> {
+        Transducer::new(move |reducer| self.apply(next.apply(reducer)))
+    }
+}
+
+/// 映射转换器
+pub fn mapping<A, B, Acc, F>(f: F) -> Transducer<A, B, Acc>
+where
+    A: 'static,
+    B: 'static,
+    Acc: 'static,
+    F: Fn(A) -> B + 'static,
+{
+    let f = Rc::new(f);
+    Transducer::new(move |mut reducer: Reducer<Acc, B>| {
+        let f = Rc::clone(&f);
+        Box::new(move |acc, item| reducer(acc, f(item)))
+    })
+}
+
+/// 过滤转换器
+pub fn filtering<A, Acc, P>(predicate: P) -> Transducer<A, A, Acc>
+where
+    A: 'static,
+    Acc: 'static,
+    P: Fn(&A) -> bool + 'static,
+{
+    let predicate = Rc::new(predicate);
+    Transducer::new(move |mut reducer: Reducer<Acc, A>| {
+        let predicate = Rc::clone(&predicate);
+        Box::new(move |acc, item| {
+            if predicate(&item) {
+                reducer(acc, item)
+            } else {
+                Step::Continue(acc)
+            }
+        })
+    })
+}
+
+/// 截取转换器 - 只放行前n个元素，之后通知数据源停止
+pub fn taking<A, Acc>(n: usize) -> Transducer<A, A, Acc>
+where
+    A: 'static,
+    Acc: 'static,
+{
+    Transducer::new(move |mut reducer: Reducer<Acc, A>| {
+        let taken = Cell::new(0usize);
+        Box::new(move |acc, item| {
+            if taken.get() >= n {
+                return Step::Stop(acc);
+            }
+            taken.set(taken.get() + 1);
+            match reducer(acc, item) {
+                Step::Continue(acc) if taken.get() >= n => Step::Stop(acc),
+                step => step,
+            }
+        })
+    })
+}
+
+/// 用转换器和基础归约函数对数据源做一次归约
+pub fn transduce<A, B, Acc, R, I>(xform: &Transducer<A, B, Acc>, mut reducer: R, init: Acc, source: I) -> Acc
+where
+    A: 'static,
+    B: 'static,
+    Acc: 'static,
+    R: FnMut(Acc, B) -> Acc + 'static,
+    I: IntoIterator<Item = A>,
+{
+    let mut reduce = xform.apply(Box::new(move |acc, item| Step::Continue(reducer(acc, item))));
+    let mut acc = init;
+    for item in source {
+        match reduce(acc, item) {
+            Step::Continue(next) => acc = next,
+            Step::Stop(last) => return last,
+        }
+    }
+    acc
+}
+
+/// 收集到Vec的便捷函数
+pub fn into_vec<A, B, I>(xform: &Transducer<A, B, Vec<B>>, source: I) -> Vec<B>
+where
+    A: 'static,
+    B: 'static,
+    I: IntoIterator<Item = A>,
+{
+    transduce(xform, |mut acc: Vec<B>, item| {
+        acc.push(item);
+        acc
+    }, Vec::new(), source)
+}
+
+/// 转换器模式演示
+pub fn demo_transducer_pattern() {
+    println!("=== 转换器模式演示 ===");
+
+    let numbers = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    // 同一个组合转换器用于不同的归约
+    fn squares_of_even<Acc: 'static>() -> Transducer<i32, i32, Acc> {
+        filtering(|x: &i32| x % 2 == 0).compose(mapping(|x: i32| x * x))
+    }
+
+    let collected = into_vec(&squares_of_even(), numbers.clone());
+    println!("偶数的平方 (Vec): {:?}", collected);
+
+    let sum = transduce(&squares_of_even(), |acc, x| acc + x, 0, numbers.iter().copied());
+    println!("偶数的平方和 (fold): {}", sum);
+
+    let joined = transduce(
+        &squares_of_even(),
+        |acc: String, x| if acc.is_empty() { x.to_string() } else { format!("{},{}", acc, x) },
+        String::new(),
+        1..=10,
+    );
+    println!("偶数的平方 (字符串): {}", joined);
+
+    // 提前终止：无限序列上只取前3个
+    let first_three = filtering(|x: &u64| x.is_multiple_of(3)).compose(taking(3));
+    println!("无限序列中前3个3的倍数: {:?}", into_vec(&first_three, 1u64..));
+
+    println!("\n【转换器模式特点】");
+    println!("✓ 集合无关 - 转换逻辑与输入输出类型解耦");
+    println!("✓ 可组合 - mapping/filtering/taking自由组合");
+    println!("✓ 单次遍历 - 没有中间集合的分配");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double_then_keep_large() -> Transducer<i32, i32, Vec<i32>> {
+        mapping(|x: i32| x * 2).compose(filtering(|x: &i32| *x > 5))
+    }
+
+    #[test]
+    fn test_same_transducer_on_vec_and_iterator() {
+        let source = vec![1, 2, 3, 4, 5];
+        let xform = double_then_keep_large();
+
+        let pulls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&pulls);
+        let from_iter = into_vec(&xform, (1..=5).inspect(move |_| counter.set(counter.get() + 1)));
+        let from_vec = into_vec(&xform, source.clone());
+
+        assert_eq!(from_vec, vec![6, 8, 10]);
+        assert_eq!(from_iter, from_vec);
+        // 组合后的转换只遍历输入一次
+        assert_eq!(pulls.get(), source.len());
+    }
+
+    #[test]
+    fn test_transduce_into_fold_and_early_stop() {
+        let sum = transduce(&mapping(|x: i32| x * 2).compose(filtering(|x: &i32| *x > 5)), |acc, x| acc + x, 0, vec![1, 2, 3, 4, 5]);
+        assert_eq!(sum, 24);
+
+        // taking在取够元素后停止消费，无限序列也能结束
+        let pulls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&pulls);
+        let xform = filtering(|x: &u32| x % 2 == 1).compose(taking(2));
+        let odds = into_vec(&xform, (0u32..).inspect(move |_| counter.set(counter.get() + 1)));
+        assert_eq!(odds, vec![1, 3]);
+        assert_eq!(pulls.get(), 4);
+
+        // 有状态的转换器可重复使用
+        assert_eq!(into_vec(&xform, 10u32..20), vec![11, 13]);
+    }
+}
@@ -0,0 +1,400 @@
+/*
+ * 文件路径: /d%3A/workspace/RustLearn/RustDesignPattern/src/FunctionalProgrammingPattern/validation_pattern.rs
+ *
+ * 验证应用函子模式 (Validation Applicative Pattern)
+ *
+ * Result在遇到第一个错误时就会短路，适合前后依赖的计算链；而表单校验等场景中，
+ * 各个字段彼此独立，我们希望一次性收集所有错误再反馈给用户。Validation是一个
+ * 应用函子（Applicative），组合多个校验结果时会通过Monoid把所有错误累加起来。
+ *
+ * 主要特点：
+ * 1. 错误累积 - 组合多个Invalid时合并全部错误，而不是停在第一个
+ * 2. 应用函子 - 通过ap把包装在Validation中的函数应用到包装的值上
+ * 3. Monoid约束 - 错误类型只需提供空值和结合操作即可累加
+ * 4. 与Result互转 - 在需要短路的地方可以随时转回Result
+ *
+ * 应用函子定律：
+ * 1. 身份律：pure id <*> v ≡ v
+ * 2. 同态律：pure f <*> pure x ≡ pure (f x)
+ *
+ * 使用场景：
+ * - 表单校验：一次返回所有字段的错误信息
+ * - 配置解析：汇总所有缺失或非法的配置项
+ * - 批量导入：收集每条记录的问题后统一报告
+ *
+ * 实现说明：
+ * - Monoid trait：为Vec<T>和String提供实现
+ * - Validation<E, A>：提供map、ap、zip以及sequence
+ * - Either<L, R>：通用的二选一类型，右值为"正常"分支
+ *
+ * 注意事项：
+ * - Validation不是单子，后一个校验依赖前一个结果时应改用Result或Either
+ */
+
+/// 幺半群 - 具有单位元和结合操作的类型
+pub trait Monoid {
+    /// 单位元
+    fn empty() -> Self;
+    /// 结合操作，需满足结合律
+    fn combine(self, other: Self) -> Self;
+}
+
+impl<T> Monoid for Vec<T> {
+    fn empty() -> Self {
+        Vec::new()
+    }
+
+    fn combine(mut self, other: Self) -> Self {
+        self.extend(other);
+        self
+    }
+}
+
+impl Monoid for String {
+    fn empty() -> Self {
+        String::new()
+    }
+
+    fn combine(mut self, other: Self) -> Self {
+        self.push_str(&other);
+        self
+    }
+}
+
+/// 二选一类型 - Left通常表示异常分支，Right表示正常分支
+#[derive(Debug, Clone, PartialEq)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    pub fn is_left(&self) -> bool {
+        matches!(self, Either::Left(_))
+    }
+
+    pub fn is_right(&self) -> bool {
+        matches!(self, Either::Right(_))
+    }
+
+    /// 映射右值
+    pub fn map<U, F>(self, f: F) -> Either<L, U>
+    where
+        F: FnOnce(R) -> U,
+    {
+        match self {
+            Either::Left(left) => Either::Left(left),
+            Either::Right(right) => Either::Right(f(right)),
+        }
+    }
+
+    /// 映射左值
+    pub fn map_left<U, F>(self, f: F) -> Either<U, R>
+    where
+        F: FnOnce(L) -> U,
+    {
+        match self {
+            Either::Left(left) => Either::Left(f(left)),
+            Either::Right(right) => Either::Right(right),
+        }
+    }
+
+    /// 右偏的单子bind
+    pub fn flat_map<U, F>(self, f: F) -> Either<L, U>
+    where
+        F: FnOnce(R) -> Either<L, U>,
+    {
+        match self {
+            Either::Left(left) => Either::Left(left),
+            Either::Right(right) => f(right),
+        }
+    }
+
+    /// 分别处理两个分支，得到统一类型的结果
+    pub fn fold<T, FL, FR>(self, on_left: FL, on_right: FR) -> T
+    where
+        FL: FnOnce(L) -> T,
+        FR: FnOnce(R) -> T,
+    {
+        match self {
+            Either::Left(left) => on_left(left),
+            Either::Right(right) => on_right(right),
+        }
+    }
+
+    /// 转为累积错误的Validation
+    pub fn into_validation(self) -> Validation<L, R> {
+        match self {
+            Either::Left(left) => Validation::Invalid(left),
+            Either::Right(right) => Validation::Valid(right),
+        }
+    }
+}
+
+impl<L, R> From<Result<R, L>> for Either<L, R> {
+    fn from(result: Result<R, L>) -> Self {
+        match result {
+            Ok(value) => Either::Right(value),
+            Err(error) => Either::Left(error),
+        }
+    }
+}
+
+/// 验证应用函子 - 组合时累积所有错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validation<E, A> {
+    Valid(A),
+    Invalid(E),
+}
+
+impl<E, A> Validation<E, A> {
+    /// pure操作 - 包装一个合法值
+    pub fn valid(value: A) -> Self {
+        Validation::Valid(value)
+    }
+
+    pub fn invalid(error: E) -> Self {
+        Validation::Invalid(error)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Validation::Valid(_))
+    }
+
+    /// map操作 - 函子功能
+    pub fn map<B, F>(self, f: F) -> Validation<E, B>
+    where
+        F: FnOnce(A) -> B,
+    {
+        match self {
+            Validation::Valid(value) => Validation::Valid(f(value)),
+            Validation::Invalid(error) => Validation::Invalid(error),
+        }
+    }
+
+    /// 映射错误
+    pub fn map_err<G, F>(self, f: F) -> Validation<G, A>
+    where
+        F: FnOnce(E) -> G,
+    {
+        match self {
+            Validation::Valid(value) => Validation::Valid(value),
+            Validation::Invalid(error) => Validation::Invalid(f(error)),
+        }
+    }
+
+    /// ap操作 - 应用函子功能，把包装的函数应用到包装的值上
+    ///
+    /// 两边都无效时错误按"值在前、函数在后"的顺序合并。
+    pub fn ap<B, F>(self, f: Validation<E, F>) -> Validation<E, B>
+    where
+        F: FnOnce(A) -> B,
+        E: Monoid,
+    {
+        match (self, f) {
+            (Validation::Valid(value), Validation::Valid(f)) => Validation::Valid(f(value)),
+            (Validation::Valid(_), Validation::Invalid(error)) => Validation::Invalid(error),
+            (Validation::Invalid(error), Validation::Valid(_)) => Validation::Invalid(error),
+            (Validation::Invalid(left), Validation::Invalid(right)) => Validation::Invalid(left.combine(right)),
+        }
+    }
+
+    /// 组合两个校验结果，两边的错误都会保留
+    pub fn zip<B>(self, other: Validation<E, B>) -> Validation<E, (A, B)>
+    where
+        E: Monoid,
+    {
+        match (self, other) {
+            (Validation::Valid(a), Validation::Valid(b)) => Validation::Valid((a, b)),
+            (Validation::Valid(_), Validation::Invalid(error)) => Validation::Invalid(error),
+            (Validation::Invalid(error), Validation::Valid(_)) => Validation::Invalid(error),
+            (Validation::Invalid(left), Validation::Invalid(right)) => Validation::Invalid(left.combine(right)),
+        }
+    }
+
+    /// 组合两个校验结果并用函数合成最终值
+    pub fn combine<B, C, F>(self, other: Validation<E, B>, f: F) -> Validation<E, C>
+    where
+        F: FnOnce(A, B) -> C,
+        E: Monoid,
+    {
+        self.zip(other).map(|(a, b)| f(a, b))
+    }
+
+    /// 把一组校验结果合并为一个，全部有效时得到值列表，否则得到全部错误
+    pub fn sequence<I>(validations: I) -> Validation<E, Vec<A>>
+    where
+        I: IntoIterator<Item = Validation<E, A>>,
+        E: Monoid,
+    {
+        validations.into_iter().fold(Validation::Valid(Vec::new()), |acc, next| {
+            acc.combine(next, |mut values, value| {
+                values.push(value);
+                values
+            })
+        })
+    }
+
+    /// 转回短路语义的Result
+    pub fn into_result(self) -> Result<A, E> {
+        match self {
+            Validation::Valid(value) => Ok(value),
+            Validation::Invalid(error) => Err(error),
+        }
+    }
+
+    pub fn into_either(self) -> Either<E, A> {
+        match self {
+            Validation::Valid(value) => Either::Right(value),
+            Validation::Invalid(error) => Either::Left(error),
+        }
+    }
+}
+
+impl<E, A> From<Result<A, E>> for Validation<E, A> {
+    fn from(result: Result<A, E>) -> Self {
+        match result {
+            Ok(value) => Validation::Valid(value),
+            Err(error) => Validation::Invalid(error),
+        }
+    }
+}
+
+/// 注册表单示例
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registration {
+    pub username: String,
+    pub age: u32,
+    pub email: String,
+}
+
+/// 表单校验器，每个字段独立校验
+pub struct RegistrationValidator;
+
+impl RegistrationValidator {
+    pub fn validate_username(username: &str) -> Validation<Vec<String>, String> {
+        if username.chars().count() >= 3 {
+            Validation::valid(username.to_string())
+        } else {
+            Validation::invalid(vec![format!("用户名至少3个字符: '{}'", username)])
+        }
+    }
+
+    pub fn validate_age(age: i32) -> Validation<Vec<String>, u32> {
+        if (18..=150).contains(&age) {
+            Validation::valid(age as u32)
+        } else {
+            Validation::invalid(vec![format!("年龄必须在18到150之间: {}", age)])
+        }
+    }
+
+    pub fn validate_email(email: &str) -> Validation<Vec<String>, String> {
+        if email.contains('@') {
+            Validation::valid(email.to_string())
+        } else {
+            Validation::invalid(vec![format!("邮箱格式不正确: '{}'", email)])
+        }
+    }
+
+    /// 组合三个字段的校验，收集全部错误
+    pub fn validate(username: &str, age: i32, email: &str) -> Validation<Vec<String>, Registration> {
+        Self::validate_username(username)
+            .zip(Self::validate_age(age))
+            .zip(Self::validate_email(email))
+            .map(|((username, age), email)| Registration { username, age, email })
+    }
+}
+
+/// 验证应用函子模式演示
+pub fn demo_validation_pattern() {
+    println!("=== 验证应用函子模式演示 ===");
+
+    // 全部字段合法
+    let ok = RegistrationValidator::validate("alice", 30, "alice@example.com");
+    println!("合法表单: {:?}", ok);
+
+    // 多个字段非法，错误全部收集
+    match RegistrationValidator::validate("al", 12, "alice.example.com") {
+        Validation::Valid(registration) => println!("注册成功: {:?}", registration),
+        Validation::Invalid(errors) => {
+            println!("校验失败，共 {} 个错误:", errors.len());
+            for error in errors {
+                println!("  - {}", error);
+            }
+        }
+    }
+
+    // 与Result的对比：Result在第一个错误处短路
+    let short_circuit: Result<Vec<u32>, String> = ["1", "x", "y"]
+        .iter()
+        .map(|s| s.parse::<u32>().map_err(|_| format!("无法解析'{}'", s)))
+        .collect();
+    println!("Result短路: {:?}", short_circuit);
+
+    let accumulated = Validation::sequence(
+        ["1", "x", "y"]
+            .iter()
+            .map(|s| Validation::from(s.parse::<u32>().map_err(|_| vec![format!("无法解析'{}'", s)]))),
+    );
+    println!("Validation累积: {:?}", accumulated);
+
+    // Either示例
+    let either: Either<String, i32> = Either::from("42".parse::<i32>().map_err(|e| e.to_string()));
+    let described = either.map(|n| n * 2).fold(|e| format!("错误: {}", e), |n| format!("结果: {}", n));
+    println!("Either: {}", described);
+
+    println!("\n【验证应用函子模式特点】");
+    println!("✓ 错误累积 - 组合时收集所有错误而不是短路");
+    println!("✓ 应用函子 - 通过ap组合彼此独立的校验");
+    println!("✓ Monoid约束 - 任何可合并的错误类型都可使用");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_of_three_failures_are_both_collected() {
+        let result = RegistrationValidator::validate("al", 30, "not-an-email");
+
+        match result {
+            Validation::Invalid(errors) => {
+                assert_eq!(errors.len(), 2);
+                assert!(errors[0].contains("用户名"));
+                assert!(errors[1].contains("邮箱"));
+            }
+            Validation::Valid(registration) => panic!("不应通过校验: {:?}", registration),
+        }
+
+        // 同样的输入改用Result会在第一个错误处停下
+        let short_circuit = RegistrationValidator::validate_username("al").into_result()
+            .and_then(|_| RegistrationValidator::validate_email("not-an-email").into_result());
+        assert_eq!(short_circuit.unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn test_ap_applies_function_and_accumulates_errors() {
+        let add = |a: i32| move |b: i32| a + b;
+
+        let sum: Validation<Vec<&str>, i32> = Validation::valid(2).ap(Validation::valid(1).map(add));
+        assert_eq!(sum, Validation::Valid(3));
+
+        let both_bad: Validation<Vec<&str>, i32> =
+            Validation::<Vec<&str>, i32>::invalid(vec!["b"]).ap(Validation::<Vec<&str>, i32>::invalid(vec!["a"]).map(add));
+        assert_eq!(both_bad, Validation::Invalid(vec!["b", "a"]));
+
+        let joined: Validation<String, (i32, i32)> =
+            Validation::invalid("x;".to_string()).zip(Validation::invalid("y;".to_string()));
+        assert_eq!(joined, Validation::Invalid("x;y;".to_string()));
+    }
+
+    #[test]
+    fn test_either_map_and_conversion() {
+        let right: Either<String, i32> = Either::Right(20);
+        assert_eq!(right.map(|n| n + 1), Either::Right(21));
+
+        let left: Either<String, i32> = Either::from(Err::<i32, String>("boom".to_string()));
+        assert!(left.is_left());
+        assert_eq!(left.into_validation(), Validation::Invalid("boom".to_string()));
+    }
+}
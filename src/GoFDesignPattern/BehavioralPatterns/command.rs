@@ -2,12 +2,87 @@
 //! 
 //! 将一个请求封装为一个对象，从而使你可用不同的请求对客户进行参数化，对请求排队或记录请求日志，以及支持可撤销的操作。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/command.rs
+//!
+//! 命令可以带着追踪上下文执行：关联ID取自分布式追踪的 trace_id，
+//! 宏命令把同一个关联ID传给所有子命令，审计日志据此把相关操作归为一组。
+
+use crate::DistributedSystemMode::ObservabilityPatterns::distributed_tracing::{Span, Tracer};
 
 // 命令接口
 trait Command {
     fn execute(&mut self);
     fn undo(&mut self);
     fn get_description(&self) -> String;
+
+    // 带追踪上下文执行，默认直接执行并记录一条审计日志
+    fn execute_traced(&mut self, context: &CommandContext, audit: &mut AuditLog) {
+        self.execute();
+        audit.record(context, self.get_description());
+    }
+}
+
+// 命令执行上下文 - 可选的追踪Span，关联ID即Span的trace_id
+struct CommandContext<'a> {
+    tracer: Option<&'a Tracer>,
+    span: Option<Span>,
+}
+
+impl<'a> CommandContext<'a> {
+    // 不追踪的上下文，审计日志中没有关联ID
+    fn untraced() -> Self {
+        Self { tracer: None, span: None }
+    }
+
+    // 开始一次新的追踪，生成新的关联ID
+    fn traced(tracer: &'a Tracer, operation: &str) -> Self {
+        Self { tracer: Some(tracer), span: Some(tracer.start_span(operation.to_string())) }
+    }
+
+    fn correlation_id(&self) -> Option<&str> {
+        self.span.as_ref().map(|span| span.trace_id.as_str())
+    }
+
+    // 子命令的上下文，沿用同一个关联ID，Span挂在当前Span之下
+    fn child(&self, operation: &str) -> CommandContext<'a> {
+        let span = match (self.tracer, &self.span) {
+            (Some(tracer), Some(parent)) => Some(tracer.start_child_span(parent, operation.to_string())),
+            _ => None,
+        };
+        CommandContext { tracer: self.tracer, span }
+    }
+}
+
+// 审计日志条目
+#[derive(Debug, Clone, PartialEq)]
+struct AuditEntry {
+    correlation_id: Option<String>,
+    span_id: Option<String>,
+    parent_span_id: Option<String>,
+    description: String,
+}
+
+// 审计日志
+#[derive(Default)]
+struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    fn record(&mut self, context: &CommandContext, description: String) {
+        self.entries.push(AuditEntry {
+            correlation_id: context.correlation_id().map(str::to_string),
+            span_id: context.span.as_ref().map(|span| span.span_id.clone()),
+            parent_span_id: context.span.as_ref().and_then(|span| span.parent_span_id.clone()),
+            description,
+        });
+    }
+
+    // 同一关联ID下的所有操作
+    fn entries_for(&self, correlation_id: &str) -> Vec<&AuditEntry> {
+        self.entries.iter()
+            .filter(|entry| entry.correlation_id.as_deref() == Some(correlation_id))
+            .collect()
+    }
 }
 
 // 接收者 - 文本编辑器
@@ -112,6 +187,44 @@ impl Command for DeleteTextCommand {
     }
 }
 
+// 宏命令 - 按顺序执行一组子命令，撤销时逆序撤销
+struct MacroCommand {
+    name: String,
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl MacroCommand {
+    fn new(name: &str, commands: Vec<Box<dyn Command>>) -> Self {
+        Self { name: name.to_string(), commands }
+    }
+}
+
+impl Command for MacroCommand {
+    fn execute(&mut self) {
+        for command in &mut self.commands {
+            command.execute();
+        }
+    }
+
+    fn undo(&mut self) {
+        for command in self.commands.iter_mut().rev() {
+            command.undo();
+        }
+    }
+
+    fn get_description(&self) -> String {
+        format!("宏命令 '{}' ({} 个子命令)", self.name, self.commands.len())
+    }
+
+    fn execute_traced(&mut self, context: &CommandContext, audit: &mut AuditLog) {
+        audit.record(context, self.get_description());
+        for command in &mut self.commands {
+            let child = context.child(&command.get_description());
+            command.execute_traced(&child, audit);
+        }
+    }
+}
+
 // 调用者 - 编辑器控制器
 struct EditorController {
     history: Vec<Box<dyn Command>>,
@@ -137,6 +250,17 @@ impl EditorController {
         self.current_position = self.history.len();
     }
 
+    // 带追踪上下文执行命令，操作写入审计日志
+    fn execute_command_traced(&mut self, mut command: Box<dyn Command>, context: &CommandContext, audit: &mut AuditLog) {
+        if self.current_position < self.history.len() {
+            self.history.truncate(self.current_position);
+        }
+
+        command.execute_traced(context, audit);
+        self.history.push(command);
+        self.current_position = self.history.len();
+    }
+
     fn undo(&mut self) -> bool {
         if self.current_position > 0 {
             self.current_position -= 1;
@@ -208,4 +332,75 @@ pub fn demo() {
 
     // 显示最终历史
     controller.show_history();
-} 
\ No newline at end of file
+
+    // 带追踪的宏命令
+    println!("\n带关联ID执行宏命令:");
+    let tracer = Tracer::new("editor-service".to_string());
+    let mut audit = AuditLog::default();
+    let signature = MacroCommand::new("添加签名", vec![
+        Box::new(AddTextCommand::new(&mut editor, "\n--\n".to_string())),
+        Box::new(AddTextCommand::new(&mut editor, "张三".to_string())),
+    ]);
+    let context = CommandContext::traced(&tracer, "sign_document");
+    controller.execute_command_traced(Box::new(signature), &context, &mut audit);
+    for entry in &audit.entries {
+        println!("  [{}] {}", entry.correlation_id.as_deref().unwrap_or("-"), entry.description);
+    }
+    println!("当前内容: '{}'", editor.get_content());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macro_sub_commands_share_correlation_id() {
+        let mut editor = TextEditor::new();
+        let tracer = Tracer::new("editor".to_string());
+        let mut audit = AuditLog::default();
+
+        let mut greeting = MacroCommand::new("问候", vec![
+            Box::new(AddTextCommand::new(&mut editor, "Hello ".to_string())),
+            Box::new(AddTextCommand::new(&mut editor, "World".to_string())),
+            Box::new(DeleteTextCommand::new(&mut editor, 0)),
+        ]);
+        let context = CommandContext::traced(&tracer, "greet");
+        greeting.execute_traced(&context, &mut audit);
+        assert_eq!(editor.get_content(), "Hello World");
+
+        let correlation_id = context.correlation_id().unwrap();
+        assert_eq!(audit.entries.len(), 4);
+        assert_eq!(audit.entries_for(correlation_id).len(), 4);
+
+        // 子命令的Span都挂在宏命令的Span之下
+        let macro_span = audit.entries[0].span_id.clone();
+        assert!(macro_span.is_some());
+        for entry in &audit.entries[1..] {
+            assert_eq!(entry.parent_span_id, macro_span);
+        }
+        assert_eq!(audit.entries[1].description, "添加文本: 'Hello '");
+
+        greeting.undo();
+        assert_eq!(editor.get_content(), "");
+    }
+
+    #[test]
+    fn test_untraced_commands_have_no_correlation_id() {
+        let mut editor = TextEditor::new();
+        let mut controller = EditorController::new();
+        let mut audit = AuditLog::default();
+
+        let nested = MacroCommand::new("外层", vec![
+            Box::new(AddTextCommand::new(&mut editor, "a".to_string())),
+            Box::new(MacroCommand::new("内层", vec![Box::new(AddTextCommand::new(&mut editor, "b".to_string()))])),
+        ]);
+        controller.execute_command_traced(Box::new(nested), &CommandContext::untraced(), &mut audit);
+
+        assert_eq!(editor.get_content(), "ab");
+        assert_eq!(audit.entries.len(), 4);
+        assert!(audit.entries.iter().all(|entry| entry.correlation_id.is_none() && entry.span_id.is_none()));
+
+        assert!(controller.undo());
+        assert_eq!(editor.get_content(), "");
+    }
+}
@@ -0,0 +1,261 @@
+//! 有限状态机 (Finite State Machine)
+//!
+//! 状态模式和应用控制器都在描述"当前状态 + 事件 -> 新状态"的转换表。
+//! `Fsm<S, E>` 把转换表、守卫条件和进入/退出回调抽取成可复用的组件：
+//! - `transition(from, event, to, guard)` 声明转换，同一状态和事件可以有多条带不同守卫的转换，
+//!   按声明顺序取第一条守卫通过的转换
+//! - `on_enter` / `on_exit` 在进入或离开某个状态时触发回调，先退出旧状态再进入新状态
+//! - `handle(event)` 执行转换并返回新状态，没有匹配的转换时返回错误且状态不变
+//!
+//! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/fsm.rs
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// 守卫条件：参数为当前状态和触发的事件，要求 `Send + Sync` 以便状态机跨线程共享
+type Guard<S, E> = Box<dyn Fn(&S, &E) -> bool + Send + Sync>;
+/// 状态回调：参数为转换的另一端状态（进入时是旧状态，退出时是新状态）和事件
+type Callback<S, E> = Box<dyn FnMut(&S, &E) + Send + Sync>;
+
+/// 总是允许转换的守卫
+pub fn always<S, E>(_state: &S, _event: &E) -> bool {
+    true
+}
+
+/// 状态机错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum FsmError<S, E> {
+    /// 当前状态下没有声明该事件的转换
+    InvalidTransition { from: S, event: E },
+    /// 声明了转换，但所有守卫都拒绝了
+    GuardRejected { from: S, event: E },
+}
+
+impl<S: fmt::Debug, E: fmt::Debug> fmt::Display for FsmError<S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsmError::InvalidTransition { from, event } => {
+                write!(f, "无法从状态 {:?} 通过事件 {:?} 进行转换", from, event)
+            }
+            FsmError::GuardRejected { from, event } => {
+                write!(f, "状态 {:?} 上事件 {:?} 的转换被守卫拒绝", from, event)
+            }
+        }
+    }
+}
+
+struct Transition<S, E> {
+    to: S,
+    guard: Guard<S, E>,
+}
+
+/// 有限状态机
+pub struct Fsm<S, E> {
+    current: S,
+    transitions: HashMap<(S, E), Vec<Transition<S, E>>>,
+    on_enter: HashMap<S, Vec<Callback<S, E>>>,
+    on_exit: HashMap<S, Vec<Callback<S, E>>>,
+}
+
+impl<S, E> Fsm<S, E>
+where
+    S: Clone + Eq + Hash,
+    E: Clone + Eq + Hash,
+{
+    pub fn new(initial: S) -> Self {
+        Self {
+            current: initial,
+            transitions: HashMap::new(),
+            on_enter: HashMap::new(),
+            on_exit: HashMap::new(),
+        }
+    }
+
+    /// 声明一条转换，不需要条件时 `guard` 传入 [`always`]
+    pub fn transition<G>(mut self, from: S, event: E, to: S, guard: G) -> Self
+    where
+        G: Fn(&S, &E) -> bool + Send + Sync + 'static,
+    {
+        self.transitions
+            .entry((from, event))
+            .or_default()
+            .push(Transition { to, guard: Box::new(guard) });
+        self
+    }
+
+    /// 进入 `state` 时触发的回调
+    pub fn on_enter<F>(mut self, state: S, callback: F) -> Self
+    where
+        F: FnMut(&S, &E) + Send + Sync + 'static,
+    {
+        self.on_enter.entry(state).or_default().push(Box::new(callback));
+        self
+    }
+
+    /// 离开 `state` 时触发的回调
+    pub fn on_exit<F>(mut self, state: S, callback: F) -> Self
+    where
+        F: FnMut(&S, &E) + Send + Sync + 'static,
+    {
+        self.on_exit.entry(state).or_default().push(Box::new(callback));
+        self
+    }
+
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// 处理事件，成功时返回新状态
+    pub fn handle(&mut self, event: E) -> Result<S, FsmError<S, E>> {
+        let key = (self.current.clone(), event);
+        let candidates = match self.transitions.get(&key) {
+            Some(candidates) => candidates,
+            None => return Err(FsmError::InvalidTransition { from: key.0, event: key.1 }),
+        };
+        let to = match candidates.iter().find(|t| (t.guard)(&key.0, &key.1)) {
+            Some(transition) => transition.to.clone(),
+            None => return Err(FsmError::GuardRejected { from: key.0, event: key.1 }),
+        };
+
+        let (from, event) = key;
+        for callback in self.on_exit.get_mut(&from).into_iter().flatten() {
+            callback(&to, &event);
+        }
+        self.current = to.clone();
+        for callback in self.on_enter.get_mut(&to).into_iter().flatten() {
+            callback(&from, &event);
+        }
+        Ok(to)
+    }
+
+    /// 当前状态下事件是否有守卫通过的转换
+    pub fn can_handle(&self, event: &E) -> bool {
+        self.transitions
+            .get(&(self.current.clone(), event.clone()))
+            .is_some_and(|candidates| candidates.iter().any(|t| (t.guard)(&self.current, event)))
+    }
+
+    /// 当前状态下声明了转换的事件
+    pub fn available_events(&self) -> Vec<E> {
+        self.transitions
+            .keys()
+            .filter(|(state, _)| *state == self.current)
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+}
+
+pub fn demo() {
+    println!("=== 有限状态机演示 ===");
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Door {
+        Opened,
+        Closed,
+        Locked,
+    }
+
+    let mut door = Fsm::new(Door::Closed)
+        .transition(Door::Closed, "open", Door::Opened, always)
+        .transition(Door::Opened, "close", Door::Closed, always)
+        .transition(Door::Closed, "lock", Door::Locked, always)
+        .transition(Door::Locked, "unlock", Door::Closed, always)
+        .on_enter(Door::Locked, |_, _| println!("  🔒 门已上锁"))
+        .on_exit(Door::Locked, |_, _| println!("  🔓 门已解锁"));
+
+    for event in ["open", "lock", "close", "lock", "open", "unlock", "open"] {
+        match door.handle(event) {
+            Ok(state) => println!("事件 {} -> {:?}", event, state),
+            Err(e) => println!("事件 {} 被拒绝: {}", event, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Light {
+        Off,
+        On,
+        Broken,
+    }
+
+    #[test]
+    fn test_valid_and_invalid_transitions() {
+        let mut fsm = Fsm::new(Light::Off)
+            .transition(Light::Off, "switch", Light::On, always)
+            .transition(Light::On, "switch", Light::Off, always)
+            .transition(Light::On, "surge", Light::Broken, always);
+
+        assert_eq!(fsm.handle("switch"), Ok(Light::On));
+        assert_eq!(fsm.handle("switch"), Ok(Light::Off));
+        assert_eq!(fsm.handle("surge"), Err(FsmError::InvalidTransition { from: Light::Off, event: "surge" }));
+        assert_eq!(*fsm.current(), Light::Off);
+        assert!(!fsm.can_handle(&"surge"));
+        assert_eq!(fsm.available_events(), vec!["switch"]);
+    }
+
+    #[test]
+    fn test_guards_pick_first_passing_transition() {
+        let surges = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&surges);
+        let mut fsm = Fsm::new(Light::On)
+            .transition(Light::On, "surge", Light::Broken, move |_, _| counter.load(Ordering::SeqCst) >= 2)
+            .transition(Light::On, "surge", Light::On, always)
+            .transition(Light::Off, "repair", Light::On, |_, _| false);
+
+        // 前两次浪涌被第二条转换吸收，第三次灯坏了
+        for _ in 0..2 {
+            assert_eq!(fsm.handle("surge"), Ok(Light::On));
+            surges.fetch_add(1, Ordering::SeqCst);
+        }
+        assert_eq!(fsm.handle("surge"), Ok(Light::Broken));
+
+        let mut off = Fsm::new(Light::Off).transition(Light::Off, "repair", Light::On, |_, _| false);
+        assert!(!off.can_handle(&"repair"));
+        assert_eq!(off.handle("repair"), Err(FsmError::GuardRejected { from: Light::Off, event: "repair" }));
+    }
+
+    #[test]
+    fn test_exit_then_enter_callbacks_fire() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (enter_log, exit_log, other_log) = (Arc::clone(&log), Arc::clone(&log), Arc::clone(&log));
+        let mut fsm = Fsm::new(Light::Off)
+            .transition(Light::Off, "switch", Light::On, always)
+            .transition(Light::On, "switch", Light::Off, always)
+            .on_exit(Light::Off, move |to, event| exit_log.lock().unwrap().push(format!("exit Off -> {:?} ({})", to, event)))
+            .on_enter(Light::On, move |from, event| enter_log.lock().unwrap().push(format!("enter On <- {:?} ({})", from, event)))
+            .on_enter(Light::Off, move |_, _| other_log.lock().unwrap().push("enter Off".to_string()));
+
+        fsm.handle("switch").unwrap();
+        assert_eq!(*log.lock().unwrap(), vec!["exit Off -> On (switch)", "enter On <- Off (switch)"]);
+
+        // 非法转换不触发任何回调
+        assert!(fsm.handle("surge").is_err());
+        assert_eq!(log.lock().unwrap().len(), 2);
+
+        fsm.handle("switch").unwrap();
+        assert_eq!(log.lock().unwrap().last().unwrap(), "enter Off");
+    }
+
+    #[test]
+    fn test_fsm_can_move_across_threads() {
+        let entered = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&entered);
+        let mut fsm = Fsm::new(Light::Off)
+            .transition(Light::Off, "switch", Light::On, always)
+            .on_enter(Light::On, move |_, _| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+
+        let state = thread::spawn(move || fsm.handle("switch")).join().unwrap();
+        assert_eq!(state, Ok(Light::On));
+        assert_eq!(entered.load(Ordering::SeqCst), 1);
+    }
+}
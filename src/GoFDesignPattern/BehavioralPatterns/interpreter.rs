@@ -2,12 +2,25 @@
 //! 
 //! 给定一个语言，定义它的文法的一种表示，并定义一个解释器，这个解释器使用该表示来解释语言中的句子。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/interpreter.rs
+//!
+//! 表达式树同时支持访问者模式：格式化输出和类型检查作为访问者实现，不需要修改节点类型。
 
 use std::collections::HashMap;
 
 // 表达式接口
 trait Expression {
     fn interpret(&self, context: &Context) -> i32;
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor);
+}
+
+// 表达式访问者
+trait ExpressionVisitor {
+    fn visit_number(&mut self, number: &NumberExpression);
+    fn visit_variable(&mut self, variable: &VariableExpression);
+    fn visit_text(&mut self, text: &TextExpression);
+    fn visit_add(&mut self, add: &AddExpression);
+    fn visit_subtract(&mut self, subtract: &SubtractExpression);
+    fn visit_multiply(&mut self, multiply: &MultiplyExpression);
 }
 
 // 上下文类
@@ -46,6 +59,10 @@ impl Expression for NumberExpression {
     fn interpret(&self, _context: &Context) -> i32 {
         self.number
     }
+
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        visitor.visit_number(self);
+    }
 }
 
 // 终结符表达式 - 变量
@@ -63,6 +80,31 @@ impl Expression for VariableExpression {
     fn interpret(&self, context: &Context) -> i32 {
         context.get_variable(&self.name).unwrap_or(0)
     }
+
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        visitor.visit_variable(self);
+    }
+}
+
+// 终结符表达式 - 文本，不能参与算术运算，求值时按0处理，由类型检查报告错误
+struct TextExpression {
+    text: String,
+}
+
+impl TextExpression {
+    fn new(text: String) -> Self {
+        Self { text }
+    }
+}
+
+impl Expression for TextExpression {
+    fn interpret(&self, _context: &Context) -> i32 {
+        0
+    }
+
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        visitor.visit_text(self);
+    }
 }
 
 // 非终结符表达式 - 加法
@@ -81,6 +123,10 @@ impl Expression for AddExpression {
     fn interpret(&self, context: &Context) -> i32 {
         self.left.interpret(context) + self.right.interpret(context)
     }
+
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        visitor.visit_add(self);
+    }
 }
 
 // 非终结符表达式 - 减法
@@ -99,6 +145,153 @@ impl Expression for SubtractExpression {
     fn interpret(&self, context: &Context) -> i32 {
         self.left.interpret(context) - self.right.interpret(context)
     }
+
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        visitor.visit_subtract(self);
+    }
+}
+
+// 非终结符表达式 - 乘法
+struct MultiplyExpression {
+    left: Box<dyn Expression>,
+    right: Box<dyn Expression>,
+}
+
+impl MultiplyExpression {
+    fn new(left: Box<dyn Expression>, right: Box<dyn Expression>) -> Self {
+        Self { left, right }
+    }
+}
+
+impl Expression for MultiplyExpression {
+    fn interpret(&self, context: &Context) -> i32 {
+        self.left.interpret(context) * self.right.interpret(context)
+    }
+
+    fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        visitor.visit_multiply(self);
+    }
+}
+
+// 具体访问者 - 格式化输出，只在优先级需要时加括号
+struct PrettyPrinter {
+    output: String,
+    // 当前节点的优先级：加减为1，乘为2，终结符为3
+    precedence: u8,
+}
+
+impl PrettyPrinter {
+    fn print(expression: &dyn Expression) -> String {
+        Self::render(expression).0
+    }
+
+    fn render(expression: &dyn Expression) -> (String, u8) {
+        let mut printer = Self { output: String::new(), precedence: 3 };
+        expression.accept(&mut printer);
+        (printer.output, printer.precedence)
+    }
+
+    // 左结合：左操作数优先级低于当前运算符时加括号；
+    // 右操作数优先级相同时只有减法需要括号，a - (b - c) 不能写成 a - b - c
+    fn binary(&mut self, operator: &str, precedence: u8, left: &dyn Expression, right: &dyn Expression) {
+        let (left_text, left_precedence) = Self::render(left);
+        let (right_text, right_precedence) = Self::render(right);
+        let wrap = |text: String, needs_parens: bool| if needs_parens { format!("({})", text) } else { text };
+
+        let left_text = wrap(left_text, left_precedence < precedence);
+        let right_text = wrap(right_text, right_precedence < precedence || (right_precedence == precedence && operator == "-"));
+        self.output = format!("{} {} {}", left_text, operator, right_text);
+        self.precedence = precedence;
+    }
+}
+
+impl ExpressionVisitor for PrettyPrinter {
+    fn visit_number(&mut self, number: &NumberExpression) {
+        self.output = number.number.to_string();
+    }
+
+    fn visit_variable(&mut self, variable: &VariableExpression) {
+        self.output = variable.name.clone();
+    }
+
+    fn visit_text(&mut self, text: &TextExpression) {
+        self.output = format!("{:?}", text.text);
+    }
+
+    fn visit_add(&mut self, add: &AddExpression) {
+        self.binary("+", 1, add.left.as_ref(), add.right.as_ref());
+    }
+
+    fn visit_subtract(&mut self, subtract: &SubtractExpression) {
+        self.binary("-", 1, subtract.left.as_ref(), subtract.right.as_ref());
+    }
+
+    fn visit_multiply(&mut self, multiply: &MultiplyExpression) {
+        self.binary("*", 2, multiply.left.as_ref(), multiply.right.as_ref());
+    }
+}
+
+// 类型错误，path 是从根节点到出错节点的路径，如 root.left.right
+#[derive(Debug, Clone, PartialEq)]
+struct TypeError {
+    path: String,
+    message: String,
+}
+
+// 具体访问者 - 类型检查，要求所有运算数都是数值，报告遇到的第一个错误
+struct TypeChecker {
+    path: Vec<&'static str>,
+    error: Option<TypeError>,
+}
+
+impl TypeChecker {
+    fn check(expression: &dyn Expression) -> Result<(), TypeError> {
+        let mut checker = Self { path: Vec::new(), error: None };
+        expression.accept(&mut checker);
+        checker.error.map_or(Ok(()), Err)
+    }
+
+    fn current_path(&self) -> String {
+        std::iter::once("root").chain(self.path.iter().copied()).collect::<Vec<_>>().join(".")
+    }
+
+    fn binary(&mut self, left: &dyn Expression, right: &dyn Expression) {
+        for (side, operand) in [("left", left), ("right", right)] {
+            if self.error.is_some() {
+                return;
+            }
+            self.path.push(side);
+            operand.accept(self);
+            self.path.pop();
+        }
+    }
+}
+
+impl ExpressionVisitor for TypeChecker {
+    fn visit_number(&mut self, _number: &NumberExpression) {}
+
+    fn visit_variable(&mut self, _variable: &VariableExpression) {}
+
+    fn visit_text(&mut self, text: &TextExpression) {
+        if self.error.is_none() {
+            self.error = Some(TypeError {
+                path: self.current_path(),
+                message: format!("文本 {:?} 不能参与算术运算", text.text),
+            });
+        }
+    }
+
+    fn visit_add(&mut self, add: &AddExpression) {
+        self.binary(add.left.as_ref(), add.right.as_ref());
+    }
+
+    fn visit_subtract(&mut self, subtract: &SubtractExpression) {
+        self.binary(subtract.left.as_ref(), subtract.right.as_ref());
+    }
+
+    fn visit_multiply(&mut self, multiply: &MultiplyExpression) {
+        self.binary(multiply.left.as_ref(), multiply.right.as_ref());
+    }
 }
 
 // 简单的表达式解析器
@@ -115,6 +308,7 @@ impl ExpressionParser {
             match operator {
                 "+" => Ok(Box::new(AddExpression::new(left, right))),
                 "-" => Ok(Box::new(SubtractExpression::new(left, right))),
+                "*" => Ok(Box::new(MultiplyExpression::new(left, right))),
                 _ => Err(format!("不支持的操作符: {}", operator)),
             }
         } else if tokens.len() == 1 {
@@ -150,6 +344,7 @@ pub fn demo() {
         "x - y",
         "y + z",
         "x - z",
+        "y * z",
     ];
 
     for expr_str in expressions {
@@ -169,4 +364,74 @@ pub fn demo() {
     for (var, value) in &context.variables {
         println!("  {} = {}", var, value);
     }
+
+    // 访问者：格式化输出和类型检查
+    let tree = MultiplyExpression::new(
+        Box::new(AddExpression::new(
+            Box::new(VariableExpression::new("x".to_string())),
+            Box::new(VariableExpression::new("y".to_string())),
+        )),
+        Box::new(TextExpression::new("z".to_string())),
+    );
+    println!("\n格式化输出: {}", PrettyPrinter::print(&tree));
+    match TypeChecker::check(&tree) {
+        Ok(()) => println!("类型检查通过"),
+        Err(error) => println!("类型错误 ({}): {}", error.path, error.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str) -> Box<dyn Expression> {
+        Box::new(VariableExpression::new(name.to_string()))
+    }
+
+    fn num(number: i32) -> Box<dyn Expression> {
+        Box::new(NumberExpression::new(number))
+    }
+
+    #[test]
+    fn test_pretty_printer_uses_minimal_parentheses() {
+        let product = MultiplyExpression::new(Box::new(AddExpression::new(var("a"), var("b"))), var("c"));
+        assert_eq!(PrettyPrinter::print(&product), "(a + b) * c");
+
+        let sum = AddExpression::new(var("a"), Box::new(MultiplyExpression::new(var("b"), var("c"))));
+        assert_eq!(PrettyPrinter::print(&sum), "a + b * c");
+
+        // 左结合的减法：左边不加括号，右边必须加
+        let left_nested = SubtractExpression::new(Box::new(SubtractExpression::new(var("a"), var("b"))), num(1));
+        assert_eq!(PrettyPrinter::print(&left_nested), "a - b - 1");
+        let right_nested = SubtractExpression::new(var("a"), Box::new(AddExpression::new(var("b"), num(1))));
+        assert_eq!(PrettyPrinter::print(&right_nested), "a - (b + 1)");
+
+        let mut context = Context::new();
+        context.set_variable("a".to_string(), 2);
+        context.set_variable("b".to_string(), 3);
+        context.set_variable("c".to_string(), 4);
+        assert_eq!(product.interpret(&context), 20);
+    }
+
+    #[test]
+    fn test_type_checker_reports_first_error_with_path() {
+        let valid = MultiplyExpression::new(Box::new(AddExpression::new(var("a"), num(1))), var("c"));
+        assert_eq!(TypeChecker::check(&valid), Ok(()));
+
+        let malformed = AddExpression::new(
+            num(1),
+            Box::new(MultiplyExpression::new(
+                Box::new(TextExpression::new("hello".to_string())),
+                Box::new(TextExpression::new("world".to_string())),
+            )),
+        );
+        assert_eq!(
+            TypeChecker::check(&malformed),
+            Err(TypeError {
+                path: "root.right.left".to_string(),
+                message: "文本 \"hello\" 不能参与算术运算".to_string(),
+            })
+        );
+        assert_eq!(TypeChecker::check(&TextExpression::new("x".to_string())).unwrap_err().path, "root");
+    }
 } 
\ No newline at end of file
@@ -51,6 +51,10 @@ impl BookShelf {
     fn author_filter_iter(&self, author: &str) -> AuthorFilterIterator {
         AuthorFilterIterator::new(self, author.to_string())
     }
+
+    fn cursor(&self) -> BookCursor<'_> {
+        BookCursor::new(self)
+    }
 }
 
 // 基本迭代器
@@ -143,6 +147,59 @@ impl<'a> Iterator for AuthorFilterIterator<'a> {
     }
 }
 
+// 双向游标 - 支持预读和回退，适合需要向前看和回溯的解析器、编辑器
+// 游标位于两个元素之间：position 是下一次 next 返回的元素下标，取值 0..=length
+struct BookCursor<'a> {
+    bookshelf: &'a BookShelf,
+    position: usize,
+}
+
+impl<'a> BookCursor<'a> {
+    fn new(bookshelf: &'a BookShelf) -> Self {
+        Self { bookshelf, position: 0 }
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    // 查看下一个元素但不移动游标，已到末尾时返回 None
+    fn peek(&self) -> Option<&'a Book> {
+        self.bookshelf.get_book_at(self.position)
+    }
+
+    // 查看上一个元素但不移动游标，已在开头时返回 None
+    fn peek_prev(&self) -> Option<&'a Book> {
+        self.position.checked_sub(1).and_then(|index| self.bookshelf.get_book_at(index))
+    }
+
+    // 向后退一步并返回越过的元素，已在开头时不移动
+    fn prev(&mut self) -> Option<&'a Book> {
+        let book = self.peek_prev()?;
+        self.position -= 1;
+        Some(book)
+    }
+
+    // 把游标移动到 index 之前，下一次 next 返回该元素；越界时不移动并返回 false
+    fn seek(&mut self, index: usize) -> bool {
+        if index > self.bookshelf.length() {
+            return false;
+        }
+        self.position = index;
+        true
+    }
+}
+
+impl<'a> Iterator for BookCursor<'a> {
+    type Item = &'a Book;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let book = self.peek()?;
+        self.position += 1;
+        Some(book)
+    }
+}
+
 // 另一个例子 - 树节点迭代器
 #[derive(Debug)]
 struct TreeNode {
@@ -247,4 +304,98 @@ pub fn demo() {
         .collect();
     
     println!("  包含'算法'的书籍: {:?}", book_titles);
+
+    println!("\n6. 双向游标:");
+    let mut cursor = bookshelf.cursor();
+    if let Some(book) = cursor.peek() {
+        println!("  预读: 《{}》", book.title);
+    }
+    cursor.next();
+    cursor.next();
+    if let Some(book) = cursor.prev() {
+        println!("  回退到: 《{}》", book.title);
+    }
+    cursor.seek(3);
+    if let Some(book) = cursor.next() {
+        println!("  跳转到下标3: 《{}》", book.title);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_shelf() -> BookShelf {
+        let mut bookshelf = BookShelf::new();
+        for (index, title) in ["A", "B", "C"].iter().enumerate() {
+            bookshelf.add_book(Book::new(title.to_string(), "作者".to_string(), format!("isbn-{}", index)));
+        }
+        bookshelf
+    }
+
+    fn title(book: Option<&Book>) -> Option<&str> {
+        book.map(|book| book.title.as_str())
+    }
+
+    #[test]
+    fn test_cursor_forward_and_backward_traversal() {
+        let bookshelf = sample_shelf();
+        let mut cursor = bookshelf.cursor();
+
+        assert_eq!(title(cursor.prev()), None);
+        assert_eq!(title(cursor.next()), Some("A"));
+        assert_eq!(title(cursor.next()), Some("B"));
+        assert_eq!(title(cursor.next()), Some("C"));
+        assert_eq!(title(cursor.next()), None);
+        assert_eq!(cursor.position(), 3);
+
+        assert_eq!(title(cursor.prev()), Some("C"));
+        assert_eq!(title(cursor.prev()), Some("B"));
+        assert_eq!(title(cursor.prev()), Some("A"));
+        assert_eq!(title(cursor.prev()), None);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn test_cursor_peek_does_not_consume() {
+        let bookshelf = sample_shelf();
+        let mut cursor = bookshelf.cursor();
+
+        assert_eq!(title(cursor.peek_prev()), None);
+        assert_eq!(title(cursor.peek()), Some("A"));
+        assert_eq!(title(cursor.peek()), Some("A"));
+        assert_eq!(title(cursor.next()), Some("A"));
+        assert_eq!(title(cursor.peek_prev()), Some("A"));
+        assert_eq!(title(cursor.peek()), Some("B"));
+
+        // 越过末尾后预读返回 None
+        cursor.by_ref().count();
+        assert_eq!(title(cursor.peek()), None);
+        assert_eq!(title(cursor.peek_prev()), Some("C"));
+
+        let empty = BookShelf::new();
+        let cursor = empty.cursor();
+        assert_eq!(title(cursor.peek()), None);
+        assert_eq!(title(cursor.peek_prev()), None);
+    }
+
+    #[test]
+    fn test_cursor_seek_to_valid_index() {
+        let bookshelf = sample_shelf();
+        let mut cursor = bookshelf.cursor();
+
+        assert!(cursor.seek(2));
+        assert_eq!(title(cursor.peek()), Some("C"));
+        assert_eq!(title(cursor.prev()), Some("B"));
+
+        // 可以定位到末尾，但不能越过末尾
+        assert!(cursor.seek(3));
+        assert_eq!(title(cursor.peek()), None);
+        assert!(!cursor.seek(4));
+        assert_eq!(cursor.position(), 3);
+
+        assert!(cursor.seek(0));
+        let titles: Vec<&str> = cursor.map(|book| book.title.as_str()).collect();
+        assert_eq!(titles, vec!["A", "B", "C"]);
+    }
 } 
\ No newline at end of file
@@ -3,7 +3,7 @@
 //! 用一个中介对象来封装一系列的对象交互。中介者使各对象不需要显式地相互引用，从而使其耦合松散，而且可以独立地改变它们之间的交互。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/mediator.rs
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // 中介者接口
 trait Mediator {
@@ -16,23 +16,116 @@ trait Component {
     fn get_name(&self) -> &str;
 }
 
+// 审核结果
+#[derive(Debug, Clone, PartialEq)]
+enum ModerationDecision {
+    Allow,
+    /// 用替换后的内容继续投递
+    Redact(String),
+    /// 拦截消息，不投递给任何人
+    Block(String),
+}
+
+// 审核钩子 - 聊天室在广播前依次调用
+trait ModerationHook {
+    fn moderate(&self, sender: &str, message: &str) -> ModerationDecision;
+}
+
+// 违禁词过滤 - 把违禁词替换为等长的星号
+struct BannedWordFilter {
+    banned_words: Vec<String>,
+}
+
+impl BannedWordFilter {
+    fn new(banned_words: &[&str]) -> Self {
+        Self {
+            banned_words: banned_words.iter().map(|word| word.to_string()).collect(),
+        }
+    }
+}
+
+impl ModerationHook for BannedWordFilter {
+    fn moderate(&self, _sender: &str, message: &str) -> ModerationDecision {
+        let mut redacted = message.to_string();
+        for word in &self.banned_words {
+            redacted = redacted.replace(word.as_str(), &"*".repeat(word.chars().count()));
+        }
+        if redacted == message {
+            ModerationDecision::Allow
+        } else {
+            ModerationDecision::Redact(redacted)
+        }
+    }
+}
+
 // 具体中介者 - 聊天室
 struct ChatRoom {
     users: Vec<String>,
+    inboxes: HashMap<String, Vec<String>>,
+    muted: HashSet<String>,
+    moderators: Vec<Box<dyn ModerationHook>>,
 }
 
 impl ChatRoom {
     fn new() -> Self {
         Self {
             users: Vec::new(),
+            inboxes: HashMap::new(),
+            muted: HashSet::new(),
+            moderators: Vec::new(),
         }
     }
 
     fn add_user(&mut self, username: String) {
         self.users.push(username.clone());
+        self.inboxes.entry(username.clone()).or_default();
         println!("聊天室: {} 加入了聊天室", username);
     }
 
+    fn add_moderator(&mut self, hook: Box<dyn ModerationHook>) {
+        self.moderators.push(hook);
+    }
+
+    fn mute_user(&mut self, username: &str) {
+        self.muted.insert(username.to_string());
+        println!("聊天室: {} 已被禁言", username);
+    }
+
+    fn unmute_user(&mut self, username: &str) {
+        self.muted.remove(username);
+    }
+
+    // 用户收到的消息，格式为 "发送者: 内容"
+    fn messages_for(&self, username: &str) -> &[String] {
+        self.inboxes.get(username).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // 禁言和审核钩子，返回 None 表示消息不投递
+    fn moderate(&self, sender: &str, message: String) -> Option<String> {
+        if self.muted.contains(sender) {
+            println!("聊天室: {} 处于禁言状态，消息未投递", sender);
+            return None;
+        }
+        let mut message = message;
+        for hook in &self.moderators {
+            match hook.moderate(sender, &message) {
+                ModerationDecision::Allow => {}
+                ModerationDecision::Redact(redacted) => message = redacted,
+                ModerationDecision::Block(reason) => {
+                    println!("聊天室: 拦截 {} 的消息 ({})", sender, reason);
+                    return None;
+                }
+            }
+        }
+        Some(message)
+    }
+
+    fn deliver(&mut self, sender: &str, target: &str, message: &str) {
+        if let Some(inbox) = self.inboxes.get_mut(target) {
+            inbox.push(format!("{}: {}", sender, message));
+        }
+    }
+
     fn remove_user(&mut self, username: &str) {
         self.users.retain(|user| user != username);
         println!("聊天室: {} 离开了聊天室", username);
@@ -43,12 +136,12 @@ impl Mediator for ChatRoom {
     fn notify(&mut self, sender: &str, event: &str, data: Option<String>) {
         match event {
             "send_message" => {
-                if let Some(message) = data {
+                if let Some(message) = data.and_then(|message| self.moderate(sender, message)) {
                     println!("聊天室广播: {} 说: {}", sender, message);
-                    for user in &self.users {
-                        if user != sender {
-                            println!("  -> {} 收到消息", user);
-                        }
+                    let recipients: Vec<String> = self.users.iter().filter(|user| *user != sender).cloned().collect();
+                    for user in recipients {
+                        println!("  -> {} 收到消息", user);
+                        self.deliver(sender, &user, &message);
                     }
                 }
             }
@@ -57,9 +150,11 @@ impl Mediator for ChatRoom {
                     let parts: Vec<&str> = content.split('|').collect();
                     if parts.len() == 2 {
                         let target = parts[0];
-                        let message = parts[1];
                         if self.users.contains(&target.to_string()) {
-                            println!("私聊: {} -> {}: {}", sender, target, message);
+                            if let Some(message) = self.moderate(sender, parts[1].to_string()) {
+                                println!("私聊: {} -> {}: {}", sender, target, message);
+                                self.deliver(sender, target, &message);
+                            }
                         } else {
                             println!("错误: 用户 {} 不在聊天室", target);
                         }
@@ -233,6 +328,7 @@ pub fn demo() {
     chat_room.add_user("Alice".to_string());
     chat_room.add_user("Bob".to_string());
     chat_room.add_user("Charlie".to_string());
+    chat_room.add_moderator(Box::new(BannedWordFilter::new(&["笨蛋"])));
     
     // 用户交互
     println!();
@@ -240,6 +336,11 @@ pub fn demo() {
     bob.start_typing();
     bob.send_message("你好Alice！".to_string());
     charlie.send_private_message("Alice".to_string(), "私聊消息".to_string());
+    charlie.send_message("Bob是个笨蛋".to_string());
+    chat_room.mute_user("Charlie");
+    charlie.send_message("我被禁言了吗？".to_string());
+    chat_room.unmute_user("Charlie");
+    println!("Alice 的收件箱: {:?}", chat_room.messages_for("Alice"));
 
     // 2. 智能家居示例
     println!("\n\n2. 智能家居中介者:");
@@ -272,4 +373,74 @@ pub fn demo() {
     println!("2. 提高了系统的灵活性，使得系统易于维护和扩展");
     println!("3. 简化了对象之间的交互");
     println!("4. 将控制逻辑集中化");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 先完成聊天室配置再交给用户，避免配置时与用户持有的指针交错使用
+    fn setup_room(room: &mut ChatRoom) {
+        for name in ["Alice", "Bob", "Charlie"] {
+            room.add_user(name.to_string());
+        }
+        room.add_moderator(Box::new(BannedWordFilter::new(&["笨蛋", "spam"])));
+    }
+
+    #[test]
+    fn test_muted_user_message_is_not_delivered() {
+        let mut room = ChatRoom::new();
+        setup_room(&mut room);
+        room.mute_user("Charlie");
+
+        let mut alice = User::new("Alice".to_string());
+        let mut charlie = User::new("Charlie".to_string());
+        alice.set_mediator(&mut room as *mut dyn Mediator);
+        charlie.set_mediator(&mut room as *mut dyn Mediator);
+
+        charlie.send_message("有人吗？".to_string());
+        charlie.send_private_message("Bob".to_string(), "悄悄话".to_string());
+        alice.send_message("你好".to_string());
+
+        assert_eq!(room.messages_for("Bob"), ["Alice: 你好"]);
+        assert_eq!(room.messages_for("Charlie"), ["Alice: 你好"]);
+        assert!(room.messages_for("Alice").is_empty());
+    }
+
+    #[test]
+    fn test_banned_word_is_redacted_before_broadcast() {
+        let mut room = ChatRoom::new();
+        setup_room(&mut room);
+
+        let mut bob = User::new("Bob".to_string());
+        bob.set_mediator(&mut room as *mut dyn Mediator);
+        bob.send_message("别当笨蛋，也别发spam".to_string());
+        bob.send_private_message("Alice".to_string(), "你才是笨蛋".to_string());
+
+        assert_eq!(room.messages_for("Alice"), ["Bob: 别当**，也别发****", "Bob: 你才是**"]);
+        assert_eq!(room.messages_for("Charlie"), ["Bob: 别当**，也别发****"]);
+        // 发送者不会收到自己的广播
+        assert!(room.messages_for("Bob").is_empty());
+    }
+
+    #[test]
+    fn test_blocking_hook_stops_message() {
+        struct BlockLinks;
+
+        impl ModerationHook for BlockLinks {
+            fn moderate(&self, _sender: &str, message: &str) -> ModerationDecision {
+                if message.contains("http://") {
+                    ModerationDecision::Block("禁止发送链接".to_string())
+                } else {
+                    ModerationDecision::Allow
+                }
+            }
+        }
+
+        let mut room = ChatRoom::new();
+        setup_room(&mut room);
+        room.add_moderator(Box::new(BlockLinks));
+        assert_eq!(room.moderate("Alice", "看看 http://spam.example".to_string()), None);
+        assert_eq!(room.moderate("Alice", "没有链接".to_string()), Some("没有链接".to_string()));
+    }
+}
@@ -8,7 +8,7 @@
 //! - 中介者模式 (Mediator)
 //! - 备忘录模式 (Memento)
 //! - 观察者模式 (Observer)
-//! - 状态模式 (State)
+//! - 状态模式 (State)，以及可复用的有限状态机 (fsm)
 //! - 策略模式 (Strategy)
 //! - 模板方法模式 (Template Method)
 //! - 访问者模式 (Visitor)
@@ -21,6 +21,7 @@ pub mod mediator;
 pub mod memento;
 pub mod observer;
 pub mod state;
+pub mod fsm;
 pub mod strategy;
 pub mod template_method;
 pub mod visitor;
@@ -38,6 +39,7 @@ pub fn run_all_demos() {
     memento::demo();
     observer::demo();
     state::demo();
+    fsm::demo();
     strategy::demo();
     template_method::demo();
     visitor::demo();
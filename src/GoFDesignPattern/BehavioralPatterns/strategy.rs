@@ -3,6 +3,8 @@
 //! 定义一系列的算法，把它们一个个封装起来，并且使它们可相互替换。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/strategy.rs
 
+use std::cmp::Ordering;
+
 // 策略接口
 trait PaymentStrategy {
     fn pay(&self, amount: f64) -> Result<String, String>;
@@ -235,6 +237,74 @@ impl SortContext {
     }
 }
 
+// 第三个例子 - 可插拔比较器的通用排序器
+// 比较器本身就是策略，用闭包表示，运行时可以替换
+type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+// 自然顺序
+fn natural_order<T: Ord>() -> Comparator<T> {
+    Box::new(|a: &T, b: &T| a.cmp(b))
+}
+
+// 按字段排序
+fn by_field<T, K: Ord>(key: impl Fn(&T) -> K + 'static) -> Comparator<T> {
+    Box::new(move |a: &T, b: &T| key(a).cmp(&key(b)))
+}
+
+// 反转任意比较器
+fn reversed<T: 'static>(comparator: Comparator<T>) -> Comparator<T> {
+    Box::new(move |a: &T, b: &T| comparator(b, a))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortMode {
+    // 相等元素保持原有顺序
+    Stable,
+    // 不保证相等元素的顺序，不需要额外内存
+    Unstable,
+}
+
+struct Sorter<T> {
+    name: String,
+    comparator: Comparator<T>,
+    mode: SortMode,
+}
+
+impl<T> Sorter<T> {
+    fn new(name: &str, comparator: Comparator<T>) -> Self {
+        Self { name: name.to_string(), comparator, mode: SortMode::Stable }
+    }
+
+    fn with_mode(mut self, mode: SortMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn set_comparator(&mut self, name: &str, comparator: Comparator<T>) {
+        println!("切换比较策略: {} -> {}", self.name, name);
+        self.name = name.to_string();
+        self.comparator = comparator;
+    }
+
+    fn set_mode(&mut self, mode: SortMode) {
+        self.mode = mode;
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        match self.mode {
+            SortMode::Stable => data.sort_by(|a, b| (self.comparator)(a, b)),
+            SortMode::Unstable => data.sort_unstable_by(|a, b| (self.comparator)(a, b)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Employee {
+    name: &'static str,
+    department: &'static str,
+    age: u32,
+}
+
 pub fn demo() {
     println!("=== 策略模式演示 ===");
 
@@ -280,9 +350,81 @@ pub fn demo() {
     sort_context.set_strategy(Box::new(QuickSort));
     sort_context.sort(&mut data2);
 
+    // 3. 可插拔比较器
+    println!("\n\n3. 可插拔比较器:");
+    let mut employees = vec![
+        Employee { name: "张三", department: "研发", age: 30 },
+        Employee { name: "李四", department: "市场", age: 25 },
+        Employee { name: "王五", department: "研发", age: 28 },
+        Employee { name: "赵六", department: "市场", age: 35 },
+    ];
+    let mut sorter = Sorter::new("按部门", by_field(|employee: &Employee| employee.department));
+    sorter.sort(&mut employees);
+    println!("按部门(稳定): {:?}", employees.iter().map(|employee| employee.name).collect::<Vec<_>>());
+
+    sorter.set_comparator("按年龄倒序", reversed(by_field(|employee: &Employee| employee.age)));
+    sorter.set_mode(SortMode::Unstable);
+    sorter.sort(&mut employees);
+    println!("按年龄倒序: {:?}", employees.iter().map(|employee| employee.name).collect::<Vec<_>>());
+
     println!("\n策略模式的优点:");
     println!("1. 算法可以自由切换");
     println!("2. 避免使用多重条件判断");
     println!("3. 扩展性良好，易于增加新的策略");
     println!("4. 符合开闭原则");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn employees() -> Vec<Employee> {
+        vec![
+            Employee { name: "张三", department: "研发", age: 30 },
+            Employee { name: "李四", department: "市场", age: 25 },
+            Employee { name: "王五", department: "研发", age: 28 },
+            Employee { name: "赵六", department: "市场", age: 35 },
+            Employee { name: "孙七", department: "研发", age: 25 },
+        ]
+    }
+
+    fn names(employees: &[Employee]) -> Vec<&'static str> {
+        employees.iter().map(|employee| employee.name).collect()
+    }
+
+    #[test]
+    fn test_same_dataset_with_swapped_comparators() {
+        let mut data = employees();
+        let mut sorter = Sorter::new("按年龄", by_field(|employee: &Employee| employee.age));
+        sorter.sort(&mut data);
+        assert_eq!(names(&data), vec!["李四", "孙七", "王五", "张三", "赵六"]);
+
+        let mut data = employees();
+        sorter.set_comparator("按年龄倒序", reversed(by_field(|employee: &Employee| employee.age)));
+        sorter.sort(&mut data);
+        assert_eq!(names(&data), vec!["赵六", "张三", "王五", "李四", "孙七"]);
+
+        let mut numbers = vec![5, 3, 9, 1];
+        Sorter::new("自然顺序", natural_order()).with_mode(SortMode::Unstable).sort(&mut numbers);
+        assert_eq!(numbers, vec![1, 3, 5, 9]);
+    }
+
+    #[test]
+    fn test_stable_mode_keeps_order_of_equal_elements() {
+        // 先按年龄排好，再按部门稳定排序，同部门内仍按年龄有序
+        let mut data = employees();
+        let mut sorter = Sorter::new("按年龄", by_field(|employee: &Employee| employee.age));
+        sorter.sort(&mut data);
+        sorter.set_comparator("按部门", by_field(|employee: &Employee| employee.department));
+        sorter.sort(&mut data);
+
+        assert_eq!(names(&data), vec!["李四", "赵六", "孙七", "王五", "张三"]);
+
+        // 不稳定模式只保证按部门分组
+        let mut data = employees();
+        sorter.set_mode(SortMode::Unstable);
+        sorter.sort(&mut data);
+        let departments: Vec<&str> = data.iter().map(|employee| employee.department).collect();
+        assert_eq!(departments, vec!["市场", "市场", "研发", "研发", "研发"]);
+    }
+}
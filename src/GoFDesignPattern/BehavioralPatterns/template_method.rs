@@ -3,6 +3,8 @@
 //! 定义一个操作中的算法的骨架，而将一些步骤延迟到子类中。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/template_method.rs
 
+use std::num::NonZeroUsize;
+
 // 模板方法trait
 trait DataProcessor {
     // 模板方法 - 定义算法骨架
@@ -233,6 +235,147 @@ impl BeverageMaker for PlainTeaMaker {
     }
 }
 
+// 第三个例子 - 报表生成模板
+// 报表数据
+struct ReportData {
+    title: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl ReportData {
+    fn new(title: &str, columns: &[&str]) -> Self {
+        Self {
+            title: title.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn add_row(&mut self, cells: &[&str]) {
+        self.rows.push(cells.iter().map(|cell| cell.to_string()).collect());
+    }
+}
+
+trait ReportGenerator {
+    // 模板方法 - 页眉 → 逐行正文（按需分页）→ 页脚
+    fn generate(&self, data: &ReportData) -> String {
+        let mut output = self.header(&data.title, &data.columns);
+        for (index, row) in data.rows.iter().enumerate() {
+            if let Some(page_size) = self.rows_per_page() {
+                if index > 0 && index % page_size.get() == 0 {
+                    output.push_str(&self.page_break());
+                }
+            }
+            output.push_str(&self.row(row));
+        }
+        output.push_str(&self.footer(data.rows.len()));
+        output
+    }
+
+    // 抽象方法 - 各格式自己渲染
+    fn header(&self, title: &str, columns: &[String]) -> String;
+    fn row(&self, cells: &[String]) -> String;
+    fn footer(&self, row_count: usize) -> String;
+
+    // 钩子方法 - 默认不分页；每页行数用 NonZeroUsize 排除 0
+    fn rows_per_page(&self) -> Option<NonZeroUsize> {
+        None
+    }
+
+    fn page_break(&self) -> String {
+        String::new()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+struct HtmlReport;
+
+impl ReportGenerator for HtmlReport {
+    fn header(&self, title: &str, columns: &[String]) -> String {
+        let cells: String = columns.iter().map(|column| format!("<th>{}</th>", escape_html(column))).collect();
+        format!("<h1>{}</h1>\n<table>\n<tr>{}</tr>\n", escape_html(title), cells)
+    }
+
+    fn row(&self, cells: &[String]) -> String {
+        let cells: String = cells.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+        format!("<tr>{}</tr>\n", cells)
+    }
+
+    fn footer(&self, row_count: usize) -> String {
+        format!("</table>\n<p>共 {} 行</p>\n", row_count)
+    }
+}
+
+struct CsvReport;
+
+impl CsvReport {
+    // 含逗号、引号或换行的字段用双引号包裹，内部引号加倍
+    fn field(value: &str) -> String {
+        if value.contains([',', '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn line(values: &[String]) -> String {
+        let fields: Vec<String> = values.iter().map(|value| Self::field(value)).collect();
+        format!("{}\n", fields.join(","))
+    }
+}
+
+impl ReportGenerator for CsvReport {
+    // CSV只有列名行，标题不属于数据
+    fn header(&self, _title: &str, columns: &[String]) -> String {
+        Self::line(columns)
+    }
+
+    fn row(&self, cells: &[String]) -> String {
+        Self::line(cells)
+    }
+
+    fn footer(&self, _row_count: usize) -> String {
+        String::new()
+    }
+}
+
+struct TextReport {
+    rows_per_page: NonZeroUsize,
+}
+
+impl TextReport {
+    // 每页行数为 0 没有意义，直接拒绝
+    fn new(rows_per_page: usize) -> Option<Self> {
+        NonZeroUsize::new(rows_per_page).map(|rows_per_page| Self { rows_per_page })
+    }
+}
+
+impl ReportGenerator for TextReport {
+    fn header(&self, title: &str, columns: &[String]) -> String {
+        format!("{}\n{}\n{}\n", title, "=".repeat(title.chars().count() * 2), columns.join(" | "))
+    }
+
+    fn row(&self, cells: &[String]) -> String {
+        format!("{}\n", cells.join(" | "))
+    }
+
+    fn footer(&self, row_count: usize) -> String {
+        format!("-- 共 {} 行 --\n", row_count)
+    }
+
+    fn rows_per_page(&self) -> Option<NonZeroUsize> {
+        Some(self.rows_per_page)
+    }
+
+    fn page_break(&self) -> String {
+        "-- 分页 --\n".to_string()
+    }
+}
+
 pub fn demo() {
     println!("=== 模板方法模式演示 ===");
 
@@ -260,9 +403,88 @@ pub fn demo() {
     let plain_tea_maker = PlainTeaMaker;
     plain_tea_maker.prepare_beverage();
 
+    // 3. 报表生成示例
+    println!("3. 报表生成模板:");
+    let mut data = ReportData::new("季度销售", &["地区", "销售额"]);
+    data.add_row(&["华东", "1200"]);
+    data.add_row(&["华南", "980"]);
+    data.add_row(&["华北", "860"]);
+
+    let generators: Vec<(&str, Box<dyn ReportGenerator>)> = vec![
+        ("HTML", Box::new(HtmlReport)),
+        ("CSV", Box::new(CsvReport)),
+        ("文本", Box::new(TextReport::new(2).expect("每页行数大于 0"))),
+    ];
+    for (name, generator) in &generators {
+        println!("--- {} 报表 ---\n{}", name, generator.generate(&data));
+    }
+
     println!("模板方法模式的优点:");
     println!("1. 提高代码复用性，将相同部分的代码放在父类中");
     println!("2. 提高了扩展性，将不同的代码放入不同的子类中");
     println!("3. 符合开闭原则，增加新的实现只需要增加子类");
     println!("4. 符合单一职责原则，每个子类只负责自己的算法实现");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_data() -> ReportData {
+        let mut data = ReportData::new("库存 <月报>", &["商品", "数量"]);
+        data.add_row(&["螺丝, M3", "120"]);
+        data.add_row(&["垫片", "80"]);
+        data.add_row(&["\"特价\"螺母", "45"]);
+        data
+    }
+
+    #[test]
+    fn test_all_formats_share_header_body_footer_structure() {
+        let data = sample_data();
+
+        let html = HtmlReport.generate(&data);
+        assert!(html.starts_with("<h1>库存 &lt;月报&gt;</h1>\n<table>\n<tr><th>商品</th><th>数量</th></tr>\n"));
+        assert_eq!(html.matches("<tr><td>").count(), 3);
+        assert!(html.contains("<td>&quot;特价&quot;螺母</td>"));
+        assert!(html.ends_with("</table>\n<p>共 3 行</p>\n"));
+
+        let csv = CsvReport.generate(&data);
+        assert_eq!(csv, "商品,数量\n\"螺丝, M3\",120\n垫片,80\n\"\"\"特价\"\"螺母\",45\n");
+
+        let text = TextReport::new(10).unwrap().generate(&data);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "库存 <月报>");
+        assert_eq!(lines[2], "商品 | 数量");
+        assert_eq!(lines[3], "螺丝, M3 | 120");
+        assert_eq!(lines.last(), Some(&"-- 共 3 行 --"));
+    }
+
+    #[test]
+    fn test_page_break_hook_defaults_to_no_op() {
+        let data = sample_data();
+
+        // 分页钩子只在重写它的格式中生效
+        let text = TextReport::new(2).unwrap().generate(&data);
+        assert_eq!(text.matches("-- 分页 --").count(), 1);
+        let before_break = text.split("-- 分页 --").next().unwrap();
+        assert!(before_break.contains("垫片 | 80"));
+        assert!(!before_break.contains("特价"));
+
+        assert_eq!(HtmlReport.rows_per_page(), None);
+        assert_eq!(CsvReport.page_break(), "");
+        assert_eq!(CsvReport.generate(&data).lines().count(), 4);
+
+        let empty = ReportData::new("空报表", &["列"]);
+        assert_eq!(TextReport::new(1).unwrap().generate(&empty).matches("分页").count(), 0);
+        assert!(HtmlReport.generate(&empty).contains("共 0 行"));
+    }
+
+    #[test]
+    fn test_zero_rows_per_page_is_rejected() {
+        assert!(TextReport::new(0).is_none());
+
+        // 每页 1 行时每两行之间都分页
+        let text = TextReport::new(1).unwrap().generate(&sample_data());
+        assert_eq!(text.matches("-- 分页 --").count(), 2);
+    }
+}
@@ -2,6 +2,8 @@
 //! 
 //! 表示一个作用于某对象结构中的各元素的操作。它使你可以在不改变各元素的类的前提下定义作用于这些元素的新操作。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/BehavioralPatterns/visitor.rs
+//!
+//! 解释器模式的表达式树也定义了访问者（格式化输出、类型检查），见 interpreter.rs。
 
 // 访问者trait
 trait ShapeVisitor {
@@ -107,6 +107,174 @@ impl Application {
     }
 }
 
+// 具体产品 - 浅色主题按钮
+#[derive(Debug)]
+struct LightButton;
+
+impl Button for LightButton {
+    fn render(&self) {
+        println!("渲染浅色主题的按钮");
+    }
+}
+
+impl ThemedWidget for LightButton {
+    fn draw(&self, label: &str) -> String {
+        format!("[light-button bg=#ffffff fg=#222222] {}", label)
+    }
+}
+
+// 具体产品 - 浅色主题复选框
+#[derive(Debug)]
+struct LightCheckbox;
+
+impl Checkbox for LightCheckbox {
+    fn render(&self) {
+        println!("渲染浅色主题的复选框");
+    }
+}
+
+impl ThemedWidget for LightCheckbox {
+    fn draw(&self, label: &str) -> String {
+        format!("[light-checkbox ☐ border=#cccccc] {}", label)
+    }
+}
+
+// 具体产品 - 深色主题按钮
+#[derive(Debug)]
+struct DarkButton;
+
+impl Button for DarkButton {
+    fn render(&self) {
+        println!("渲染深色主题的按钮");
+    }
+}
+
+impl ThemedWidget for DarkButton {
+    fn draw(&self, label: &str) -> String {
+        format!("[dark-button bg=#1e1e1e fg=#eeeeee] {}", label)
+    }
+}
+
+// 具体产品 - 深色主题复选框
+#[derive(Debug)]
+struct DarkCheckbox;
+
+impl Checkbox for DarkCheckbox {
+    fn render(&self) {
+        println!("渲染深色主题的复选框");
+    }
+}
+
+impl ThemedWidget for DarkCheckbox {
+    fn draw(&self, label: &str) -> String {
+        format!("[dark-checkbox ■ border=#555555] {}", label)
+    }
+}
+
+// 主题控件 - 把控件绘制为带主题标记的文本
+trait ThemedWidget {
+    fn draw(&self, label: &str) -> String;
+}
+
+// 主题工厂 - 产品同时提供按钮/复选框行为和主题绘制
+trait ThemeFactory {
+    fn theme_name(&self) -> &'static str;
+    fn create_themed_button(&self) -> Box<dyn ThemedWidget>;
+    fn create_themed_checkbox(&self) -> Box<dyn ThemedWidget>;
+}
+
+// 具体工厂 - 浅色主题工厂
+struct LightThemeFactory;
+
+impl GUIFactory for LightThemeFactory {
+    fn create_button(&self) -> Box<dyn Button> {
+        Box::new(LightButton)
+    }
+
+    fn create_checkbox(&self) -> Box<dyn Checkbox> {
+        Box::new(LightCheckbox)
+    }
+}
+
+impl ThemeFactory for LightThemeFactory {
+    fn theme_name(&self) -> &'static str {
+        "light"
+    }
+
+    fn create_themed_button(&self) -> Box<dyn ThemedWidget> {
+        Box::new(LightButton)
+    }
+
+    fn create_themed_checkbox(&self) -> Box<dyn ThemedWidget> {
+        Box::new(LightCheckbox)
+    }
+}
+
+// 具体工厂 - 深色主题工厂
+struct DarkThemeFactory;
+
+impl GUIFactory for DarkThemeFactory {
+    fn create_button(&self) -> Box<dyn Button> {
+        Box::new(DarkButton)
+    }
+
+    fn create_checkbox(&self) -> Box<dyn Checkbox> {
+        Box::new(DarkCheckbox)
+    }
+}
+
+impl ThemeFactory for DarkThemeFactory {
+    fn theme_name(&self) -> &'static str {
+        "dark"
+    }
+
+    fn create_themed_button(&self) -> Box<dyn ThemedWidget> {
+        Box::new(DarkButton)
+    }
+
+    fn create_themed_checkbox(&self) -> Box<dyn ThemedWidget> {
+        Box::new(DarkCheckbox)
+    }
+}
+
+// 客户端代码 - 表单只依赖主题工厂接口，运行时可以切换主题
+struct FormRenderer {
+    factory: Box<dyn ThemeFactory>,
+}
+
+impl FormRenderer {
+    fn new(factory: Box<dyn ThemeFactory>) -> Self {
+        Self { factory }
+    }
+
+    fn switch_theme(&mut self, factory: Box<dyn ThemeFactory>) {
+        self.factory = factory;
+    }
+
+    fn theme_name(&self) -> &'static str {
+        self.factory.theme_name()
+    }
+
+    // 每个选项渲染为一个复选框，最后是提交按钮
+    fn render_form(&self, title: &str, options: &[&str], submit: &str) -> String {
+        let mut lines = vec![format!("== {} ({}) ==", title, self.factory.theme_name())];
+        for option in options {
+            lines.push(self.factory.create_themed_checkbox().draw(option));
+        }
+        lines.push(self.factory.create_themed_button().draw(submit));
+        lines.join("\n")
+    }
+}
+
+// 主题工厂选择器
+fn get_theme_factory(theme: &str) -> Option<Box<dyn ThemeFactory>> {
+    match theme {
+        "light" => Some(Box::new(LightThemeFactory)),
+        "dark" => Some(Box::new(DarkThemeFactory)),
+        _ => None,
+    }
+}
+
 // 工厂选择器
 fn get_factory(os_type: &str) -> Box<dyn GUIFactory> {
     match os_type {
@@ -136,6 +304,39 @@ mod tests {
         println!("\nMacOS应用程序:");
         mac_app.render();
     }
+
+    #[test]
+    fn test_same_form_renders_with_theme_specific_markers() {
+        let options = ["记住我", "接收通知"];
+        let light = FormRenderer::new(Box::new(LightThemeFactory)).render_form("登录", &options, "提交");
+        let dark = FormRenderer::new(Box::new(DarkThemeFactory)).render_form("登录", &options, "提交");
+
+        assert_ne!(light, dark);
+        assert!(light.contains("== 登录 (light) =="));
+        assert_eq!(light.matches("[light-checkbox").count(), 2);
+        assert!(light.contains("[light-button bg=#ffffff fg=#222222] 提交"));
+        assert!(!light.contains("dark-"));
+
+        assert_eq!(dark.matches("[dark-checkbox").count(), 2);
+        assert!(dark.contains("[dark-button bg=#1e1e1e fg=#eeeeee] 提交"));
+        assert!(!dark.contains("light-"));
+    }
+
+    #[test]
+    fn test_form_theme_switches_at_runtime() {
+        let mut form = FormRenderer::new(get_theme_factory("light").unwrap());
+        assert!(form.render_form("设置", &["自动保存"], "保存").contains("[light-checkbox"));
+
+        form.switch_theme(get_theme_factory("dark").unwrap());
+        assert_eq!(form.theme_name(), "dark");
+        assert!(form.render_form("设置", &["自动保存"], "保存").contains("[dark-checkbox ■ border=#555555] 自动保存"));
+
+        assert!(get_theme_factory("sepia").is_none());
+
+        // 主题工厂同时是普通的GUI工厂
+        let app = Application::new(&DarkThemeFactory);
+        app.render();
+    }
 }
 
 pub fn demo() {
@@ -147,4 +348,10 @@ pub fn demo() {
     
     println!("创建的GUI组件:");
     app.render();
+
+    // 运行时切换主题
+    let mut form = FormRenderer::new(Box::new(LightThemeFactory));
+    println!("\n浅色主题表单:\n{}", form.render_form("登录", &["记住我"], "提交"));
+    form.switch_theme(Box::new(DarkThemeFactory));
+    println!("\n深色主题表单:\n{}", form.render_form("登录", &["记住我"], "提交"));
 } 
\ No newline at end of file
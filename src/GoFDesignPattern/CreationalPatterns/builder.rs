@@ -19,6 +19,32 @@ impl Computer {
     }
 }
 
+/// 运行时校验发现的构建错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    MissingField(&'static str),
+    OutOfRange { field: &'static str, value: u32, min: u32, max: u32 },
+    /// 字段之间的组合约束不满足
+    Conflict(String),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::MissingField(field) => write!(f, "缺少必需字段: {}", field),
+            BuildError::OutOfRange { field, value, min, max } => {
+                write!(f, "{} 的值 {} 超出范围 [{}, {}]", field, value, min, max)
+            }
+            BuildError::Conflict(reason) => write!(f, "配置冲突: {}", reason),
+        }
+    }
+}
+
+pub const MIN_MEMORY_GB: u32 = 4;
+pub const MAX_MEMORY_GB: u32 = 1024;
+/// 配置独立显卡时要求的最小内存
+pub const MIN_GPU_MEMORY_GB: u32 = 16;
+
 pub struct ComputerBuilder {
     cpu: Option<String>,
     memory: Option<u32>,
@@ -88,6 +114,53 @@ impl ComputerBuilder {
             bluetooth: self.bluetooth,
         })
     }
+
+    /// 校验全部约束后再构建，一次返回所有违反的约束而不是遇到第一个就停止
+    pub fn build_checked(self) -> Result<Computer, Vec<BuildError>> {
+        let mut errors = Vec::new();
+
+        // 必需字段，空字符串视为未设置
+        let cpu = self.cpu.filter(|cpu| !cpu.trim().is_empty());
+        let storage = self.storage.filter(|storage| !storage.trim().is_empty());
+        if cpu.is_none() {
+            errors.push(BuildError::MissingField("cpu"));
+        }
+        if self.memory.is_none() {
+            errors.push(BuildError::MissingField("memory"));
+        }
+        if storage.is_none() {
+            errors.push(BuildError::MissingField("storage"));
+        }
+
+        // 取值范围
+        if let Some(memory) = self.memory {
+            if !(MIN_MEMORY_GB..=MAX_MEMORY_GB).contains(&memory) {
+                errors.push(BuildError::OutOfRange { field: "memory", value: memory, min: MIN_MEMORY_GB, max: MAX_MEMORY_GB });
+            }
+        }
+
+        // 跨字段约束
+        if let (Some(gpu), Some(memory)) = (&self.gpu, self.memory) {
+            if memory < MIN_GPU_MEMORY_GB {
+                errors.push(BuildError::Conflict(format!("独立显卡 {} 需要至少{}GB内存", gpu, MIN_GPU_MEMORY_GB)));
+            }
+        }
+        if self.bluetooth && !self.wifi {
+            errors.push(BuildError::Conflict("蓝牙模块与无线网卡集成，启用蓝牙必须同时启用WiFi".to_string()));
+        }
+
+        match (cpu, self.memory, storage) {
+            (Some(cpu), Some(memory), Some(storage)) if errors.is_empty() => Ok(Computer {
+                cpu,
+                memory,
+                storage,
+                gpu: self.gpu,
+                wifi: self.wifi,
+                bluetooth: self.bluetooth,
+            }),
+            _ => Err(errors),
+        }
+    }
 }
 
 // 导演类 - 负责具体的构建步骤
@@ -161,6 +234,47 @@ mod tests {
         assert!(invalid_computer.is_err());
         println!("构建失败: {}", invalid_computer.unwrap_err());
     }
+
+    #[test]
+    fn test_build_checked_reports_all_violations_together() {
+        let errors = Computer::new()
+            .memory(2)
+            .gpu("RTX 4090")
+            .bluetooth(true)
+            .build_checked()
+            .unwrap_err();
+
+        assert_eq!(errors, vec![
+            BuildError::MissingField("cpu"),
+            BuildError::MissingField("storage"),
+            BuildError::OutOfRange { field: "memory", value: 2, min: MIN_MEMORY_GB, max: MAX_MEMORY_GB },
+            BuildError::Conflict("独立显卡 RTX 4090 需要至少16GB内存".to_string()),
+            BuildError::Conflict("蓝牙模块与无线网卡集成，启用蓝牙必须同时启用WiFi".to_string()),
+        ]);
+
+        // 缺少内存时只报告缺失，不再报告依赖内存的范围和组合约束
+        let errors = Computer::new().cpu("  ").storage("1TB").gpu("RTX 3060").build_checked().unwrap_err();
+        assert_eq!(errors, vec![BuildError::MissingField("cpu"), BuildError::MissingField("memory")]);
+    }
+
+    #[test]
+    fn test_build_checked_accepts_valid_configuration() {
+        let computer = Computer::new()
+            .cpu("AMD Ryzen 9 7950X")
+            .memory(MIN_GPU_MEMORY_GB)
+            .storage("2TB NVMe SSD")
+            .gpu("RX 7900 XTX")
+            .wifi(true)
+            .bluetooth(true)
+            .build_checked()
+            .unwrap();
+        assert_eq!(computer.memory, 16);
+        assert_eq!(computer.gpu.as_deref(), Some("RX 7900 XTX"));
+
+        let errors = Computer::new().cpu("Xeon").memory(2048).storage("8TB").build_checked().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "memory 的值 2048 超出范围 [4, 1024]");
+    }
 }
 
 pub fn demo() {
@@ -192,4 +306,16 @@ pub fn demo() {
         Ok(computer) => println!("{:?}", computer),
         Err(e) => println!("构建失败: {}", e),
     }
+
+    // 运行时校验，一次列出全部问题
+    println!("\n4. 校验式构建:");
+    match Computer::new().memory(2).gpu("RTX 4080").bluetooth(true).build_checked() {
+        Ok(computer) => println!("{:?}", computer),
+        Err(errors) => {
+            println!("发现{}个问题:", errors.len());
+            for error in errors {
+                println!("  - {}", error);
+            }
+        }
+    }
 } 
\ No newline at end of file
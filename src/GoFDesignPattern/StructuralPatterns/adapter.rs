@@ -3,6 +3,11 @@
 //! 将一个类的接口转换成客户希望的另一个接口。
 //! 适配器模式使得原本由于接口不兼容而不能一起工作的那些类可以一起工作。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/StructuralPatterns/adapter.rs
+//!
+//! AdapterRegistry 按请求头中的版本号选择适配器，把不同版本旧客户端的请求
+//! 升级为新接口的调用，系统迁移期间可以同时接受多个旧版本的请求。
+
+use std::collections::HashMap;
 
 // 目标接口 - 媒体播放器
 trait MediaPlayer {
@@ -276,10 +281,174 @@ impl DatabaseManager {
     }
 }
 
+// 版本适配注册表示例 - 旧客户端请求升级到新接口
+// 携带接口版本号的请求头
+const VERSION_HEADER: &str = "X-Api-Version";
+
+// 旧客户端发来的原始请求
+struct LegacyRequest {
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+impl LegacyRequest {
+    fn new(version: &str, body: &str) -> Self {
+        let mut headers = HashMap::new();
+        headers.insert(VERSION_HEADER.to_string(), version.to_string());
+        Self { headers, body: body.to_string() }
+    }
+}
+
+// 新接口的下单命令
+#[derive(Debug, Clone, PartialEq)]
+struct CreateOrderCommand {
+    customer_id: u64,
+    items: Vec<(String, u32)>,
+    currency: String,
+}
+
+// 新接口 - 订单服务
+trait OrderApi {
+    fn create_order(&self, command: &CreateOrderCommand) -> String;
+}
+
+struct ModernOrderService;
+
+impl OrderApi for ModernOrderService {
+    fn create_order(&self, command: &CreateOrderCommand) -> String {
+        let items: Vec<String> = command.items.iter().map(|(sku, qty)| format!("{}x{}", sku, qty)).collect();
+        format!("客户{}下单 [{}] ({})", command.customer_id, items.join(", "), command.currency)
+    }
+}
+
+// 把某个旧版本的请求体升级为新接口的命令
+trait LegacyAdapter {
+    fn upgrade(&self, body: &str) -> Result<CreateOrderCommand, String>;
+}
+
+fn parse_fields(body: &str, separator: char) -> HashMap<&str, &str> {
+    body.split(separator).filter_map(|pair| pair.split_once('=')).collect()
+}
+
+// v1: "customer=42;item=apple"，每次只能买一件，币种固定为CNY
+struct V1OrderAdapter;
+
+impl LegacyAdapter for V1OrderAdapter {
+    fn upgrade(&self, body: &str) -> Result<CreateOrderCommand, String> {
+        let fields = parse_fields(body, ';');
+        let customer_id = fields.get("customer").and_then(|id| id.parse().ok())
+            .ok_or_else(|| "v1请求缺少有效的customer".to_string())?;
+        let item = fields.get("item").ok_or_else(|| "v1请求缺少item".to_string())?;
+        Ok(CreateOrderCommand {
+            customer_id,
+            items: vec![(item.to_string(), 1)],
+            currency: "CNY".to_string(),
+        })
+    }
+}
+
+// v2: "customer_id=42&items=apple:2,pear:1&currency=USD"，币种可省略
+struct V2OrderAdapter;
+
+impl LegacyAdapter for V2OrderAdapter {
+    fn upgrade(&self, body: &str) -> Result<CreateOrderCommand, String> {
+        let fields = parse_fields(body, '&');
+        let customer_id = fields.get("customer_id").and_then(|id| id.parse().ok())
+            .ok_or_else(|| "v2请求缺少有效的customer_id".to_string())?;
+        let items = fields.get("items").ok_or_else(|| "v2请求缺少items".to_string())?
+            .split(',')
+            .map(|item| {
+                let (sku, qty) = item.split_once(':').ok_or_else(|| format!("无效的商品项: {}", item))?;
+                let qty = qty.parse().map_err(|_| format!("无效的数量: {}", item))?;
+                Ok((sku.to_string(), qty))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(CreateOrderCommand {
+            customer_id,
+            items,
+            currency: fields.get("currency").unwrap_or(&"CNY").to_string(),
+        })
+    }
+}
+
+// 适配器注册表 - 版本号 -> 适配器
+struct AdapterRegistry {
+    adapters: HashMap<String, Box<dyn LegacyAdapter>>,
+}
+
+impl AdapterRegistry {
+    fn new() -> Self {
+        Self { adapters: HashMap::new() }
+    }
+
+    fn register(&mut self, version: &str, adapter: Box<dyn LegacyAdapter>) {
+        self.adapters.insert(version.to_string(), adapter);
+    }
+
+    // 按请求头中的版本号查找适配器
+    fn resolve(&self, request: &LegacyRequest) -> Result<&dyn LegacyAdapter, String> {
+        let version = request.headers.get(VERSION_HEADER)
+            .ok_or_else(|| format!("请求缺少{}头", VERSION_HEADER))?;
+        self.adapters.get(version)
+            .map(|adapter| adapter.as_ref())
+            .ok_or_else(|| format!("不支持的接口版本: {}", version))
+    }
+
+    fn upgrade(&self, request: &LegacyRequest) -> Result<CreateOrderCommand, String> {
+        self.resolve(request)?.upgrade(&request.body)
+    }
+
+    // 升级旧请求并调用新接口
+    fn handle(&self, request: &LegacyRequest, api: &dyn OrderApi) -> Result<String, String> {
+        Ok(api.create_order(&self.upgrade(request)?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn order_registry() -> AdapterRegistry {
+        let mut registry = AdapterRegistry::new();
+        registry.register("v1", Box::new(V1OrderAdapter));
+        registry.register("v2", Box::new(V2OrderAdapter));
+        registry
+    }
+
+    #[test]
+    fn test_registry_upgrades_v1_and_v2_requests() {
+        let registry = order_registry();
+
+        let v1 = LegacyRequest::new("v1", "customer=42;item=apple");
+        assert_eq!(registry.upgrade(&v1), Ok(CreateOrderCommand {
+            customer_id: 42,
+            items: vec![("apple".to_string(), 1)],
+            currency: "CNY".to_string(),
+        }));
+
+        let v2 = LegacyRequest::new("v2", "customer_id=7&items=apple:2,pear:1&currency=USD");
+        assert_eq!(registry.upgrade(&v2), Ok(CreateOrderCommand {
+            customer_id: 7,
+            items: vec![("apple".to_string(), 2), ("pear".to_string(), 1)],
+            currency: "USD".to_string(),
+        }));
+
+        // 两个版本的请求最终调用同一个新接口
+        assert_eq!(registry.handle(&v2, &ModernOrderService), Ok("客户7下单 [applex2, pearx1] (USD)".to_string()));
+        // 请求体不符合所属版本的格式
+        assert!(registry.upgrade(&LegacyRequest::new("v2", "customer=42;item=apple")).is_err());
+    }
+
+    #[test]
+    fn test_registry_rejects_unknown_or_missing_version() {
+        let registry = order_registry();
+        assert_eq!(registry.upgrade(&LegacyRequest::new("v0", "customer=1;item=apple")),
+                   Err("不支持的接口版本: v0".to_string()));
+
+        let no_header = LegacyRequest { headers: HashMap::new(), body: "customer=1;item=apple".to_string() };
+        assert_eq!(registry.handle(&no_header, &ModernOrderService), Err(format!("请求缺少{}头", VERSION_HEADER)));
+    }
+
     #[test]
     fn test_media_adapter() {
         let player = AudioPlayer::new();
@@ -344,6 +513,22 @@ pub fn demo() {
     db_manager.query_all("SELECT name, email FROM users WHERE active = 1");
     db_manager.close_all();
 
+    println!("\n3. 旧版本客户端适配注册表:");
+    let mut registry = AdapterRegistry::new();
+    registry.register("v1", Box::new(V1OrderAdapter));
+    registry.register("v2", Box::new(V2OrderAdapter));
+    let requests = [
+        LegacyRequest::new("v1", "customer=42;item=apple"),
+        LegacyRequest::new("v2", "customer_id=42&items=apple:2,pear:1&currency=USD"),
+        LegacyRequest::new("v3", "customer_id=42"),
+    ];
+    for request in &requests {
+        match registry.handle(request, &ModernOrderService) {
+            Ok(result) => println!("  {}", result),
+            Err(e) => println!("  错误: {}", e),
+        }
+    }
+
     println!("\n适配器模式的优点:");
     println!("1. 使不兼容的接口能够协同工作");
     println!("2. 提高代码的复用性");
@@ -320,6 +320,154 @@ impl APIFactory {
     }
 }
 
+// 另一个例子 - 通知
+// 实现接口 - 通知发送渠道
+trait NotificationSender {
+    // 按渠道格式发送消息，返回实际发出的内容
+    fn deliver(&self, recipient: &str, subject: &str, body: &str, high_priority: bool) -> String;
+    fn channel(&self) -> &str;
+}
+
+// 具体实现 - 邮件
+struct EmailSender {
+    from: String,
+}
+
+impl NotificationSender for EmailSender {
+    fn deliver(&self, recipient: &str, subject: &str, body: &str, high_priority: bool) -> String {
+        let priority = if high_priority { "X-Priority: 1\n" } else { "" };
+        let message = format!("From: {}\nTo: {}\n{}Subject: {}\n\n{}", self.from, recipient, priority, subject, body);
+        println!("[Email]\n{}", message);
+        message
+    }
+
+    fn channel(&self) -> &str {
+        "Email"
+    }
+}
+
+// 具体实现 - 短信，内容超过70个字符时截断
+struct SmsSender;
+
+impl SmsSender {
+    const MAX_CHARS: usize = 70;
+}
+
+impl NotificationSender for SmsSender {
+    fn deliver(&self, recipient: &str, subject: &str, body: &str, _high_priority: bool) -> String {
+        let text = format!("{}: {}", subject, body);
+        let text = if text.chars().count() > Self::MAX_CHARS {
+            let truncated: String = text.chars().take(Self::MAX_CHARS - 1).collect();
+            format!("{}…", truncated)
+        } else {
+            text
+        };
+        let message = format!("SMS to {}: {}", recipient, text);
+        println!("[SMS] {}", message);
+        message
+    }
+
+    fn channel(&self) -> &str {
+        "SMS"
+    }
+}
+
+// 具体实现 - 推送，载荷由 serde_json 生成，标题和正文中的引号、换行会被正确转义
+struct PushSender;
+
+impl NotificationSender for PushSender {
+    fn deliver(&self, recipient: &str, subject: &str, body: &str, high_priority: bool) -> String {
+        let priority = if high_priority { "high" } else { "normal" };
+        let message = serde_json::json!({
+            "device": recipient,
+            "title": subject,
+            "body": body,
+            "priority": priority,
+        }).to_string();
+        println!("[Push] {}", message);
+        message
+    }
+
+    fn channel(&self) -> &str {
+        "Push"
+    }
+}
+
+// 抽象部分 - 通知，持有发送渠道的引用
+trait Notification {
+    fn send(&self, recipient: &str) -> String;
+    fn set_sender(&mut self, sender: Box<dyn NotificationSender>);
+    fn sender_channel(&self) -> &str;
+}
+
+// 扩展抽象 - 紧急通知：标题加前缀并要求高优先级投递
+struct UrgentNotification {
+    subject: String,
+    body: String,
+    sender: Box<dyn NotificationSender>,
+}
+
+impl UrgentNotification {
+    fn new(subject: &str, body: &str, sender: Box<dyn NotificationSender>) -> Self {
+        Self { subject: subject.to_string(), body: body.to_string(), sender }
+    }
+}
+
+impl Notification for UrgentNotification {
+    fn send(&self, recipient: &str) -> String {
+        self.sender.deliver(recipient, &format!("【紧急】{}", self.subject), &self.body, true)
+    }
+
+    fn set_sender(&mut self, sender: Box<dyn NotificationSender>) {
+        self.sender = sender;
+    }
+
+    fn sender_channel(&self) -> &str {
+        self.sender.channel()
+    }
+}
+
+// 扩展抽象 - 普通通知
+struct NormalNotification {
+    subject: String,
+    body: String,
+    sender: Box<dyn NotificationSender>,
+}
+
+impl NormalNotification {
+    fn new(subject: &str, body: &str, sender: Box<dyn NotificationSender>) -> Self {
+        Self { subject: subject.to_string(), body: body.to_string(), sender }
+    }
+}
+
+impl Notification for NormalNotification {
+    fn send(&self, recipient: &str) -> String {
+        self.sender.deliver(recipient, &self.subject, &self.body, false)
+    }
+
+    fn set_sender(&mut self, sender: Box<dyn NotificationSender>) {
+        self.sender = sender;
+    }
+
+    fn sender_channel(&self) -> &str {
+        self.sender.channel()
+    }
+}
+
+// 发送渠道工厂 - 运行时按名称选择渠道
+struct SenderFactory;
+
+impl SenderFactory {
+    fn create_sender(channel: &str) -> Result<Box<dyn NotificationSender>, String> {
+        match channel.to_lowercase().as_str() {
+            "email" => Ok(Box::new(EmailSender { from: "noreply@example.com".to_string() })),
+            "sms" => Ok(Box::new(SmsSender)),
+            "push" => Ok(Box::new(PushSender)),
+            _ => Err(format!("不支持的通知渠道: {}", channel)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,6 +505,52 @@ mod tests {
             println!("测试API {}: {}", i + 1, circle.get_info());
         }
     }
+
+    #[test]
+    fn test_same_notification_through_different_senders() {
+        let email = UrgentNotification::new("服务器宕机", "db-1 无响应", SenderFactory::create_sender("email").unwrap()).send("ops@example.com");
+        assert_eq!(email, "From: noreply@example.com\nTo: ops@example.com\nX-Priority: 1\nSubject: 【紧急】服务器宕机\n\ndb-1 无响应");
+
+        let sms = UrgentNotification::new("服务器宕机", "db-1 无响应", SenderFactory::create_sender("sms").unwrap()).send("13800000000");
+        assert_eq!(sms, "SMS to 13800000000: 【紧急】服务器宕机: db-1 无响应");
+
+        // 普通通知经过推送渠道：普通优先级
+        let push = NormalNotification::new("周报", "本周进度已更新", Box::new(PushSender)).send("device-42");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&push).unwrap(),
+            serde_json::json!({"device": "device-42", "title": "周报", "body": "本周进度已更新", "priority": "normal"})
+        );
+
+        // 长短信被截断到70个字符
+        let long = NormalNotification::new("提醒", &"很长的内容".repeat(30), Box::new(SmsSender)).send("1");
+        let text = long.trim_start_matches("SMS to 1: ");
+        assert_eq!(text.chars().count(), 70);
+        assert!(text.ends_with('…'));
+    }
+
+    #[test]
+    fn test_switch_sender_at_runtime() {
+        let mut notification = NormalNotification::new("账单", "本月账单已生成", SenderFactory::create_sender("email").unwrap());
+        assert_eq!(notification.sender_channel(), "Email");
+        assert!(notification.send("a@example.com").starts_with("From: "));
+
+        notification.set_sender(SenderFactory::create_sender("PUSH").unwrap());
+        assert_eq!(notification.sender_channel(), "Push");
+        assert!(notification.send("device-1").contains(r#""title":"账单""#));
+
+        assert!(SenderFactory::create_sender("fax").is_err());
+    }
+
+    #[test]
+    fn test_push_payload_escapes_quotes_and_newlines() {
+        let push = UrgentNotification::new("\"db-1\" 宕机", "第一行\n第二行\\", Box::new(PushSender)).send("device-\"7\"");
+
+        let payload: serde_json::Value = serde_json::from_str(&push).unwrap();
+        assert_eq!(payload["device"], "device-\"7\"");
+        assert_eq!(payload["title"], "【紧急】\"db-1\" 宕机");
+        assert_eq!(payload["body"], "第一行\n第二行\\");
+        assert_eq!(payload["priority"], "high");
+    }
 }
 
 pub fn demo() {
@@ -405,6 +599,16 @@ pub fn demo() {
     editor.set_all_color("黄色");
     editor.draw_all();
 
+    // 通知与发送渠道独立变化
+    println!("\n4. 通知桥接:");
+    let mut alert = UrgentNotification::new("磁盘将满", "剩余空间不足5%", Box::new(PushSender));
+    alert.send("device-001");
+    if let Ok(sender) = SenderFactory::create_sender("sms") {
+        alert.set_sender(sender);
+        alert.send("13800000000");
+    }
+    NormalNotification::new("欢迎", "感谢注册", Box::new(EmailSender { from: "hello@example.com".to_string() })).send("user@example.com");
+
     println!("\n桥接模式的优点:");
     println!("1. 分离抽象接口和实现部分，两者可以独立变化");
     println!("2. 提高了可扩展性，可以独立扩展抽象部分和实现部分");
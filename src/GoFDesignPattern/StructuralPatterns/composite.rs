@@ -94,6 +94,83 @@ impl Component for Folder {
     }
 }
 
+// 另一种实现 - 用枚举表示文件系统节点，叶子和容器是同一类型的两个变体
+#[derive(Debug, Clone, PartialEq)]
+enum FileSystemNode {
+    File { name: String, size: u64 },
+    Directory { name: String, children: Vec<FileSystemNode> },
+}
+
+impl FileSystemNode {
+    fn file(name: &str, size: u64) -> Self {
+        FileSystemNode::File { name: name.to_string(), size }
+    }
+
+    fn directory(name: &str, children: Vec<FileSystemNode>) -> Self {
+        FileSystemNode::Directory { name: name.to_string(), children }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            FileSystemNode::File { name, .. } | FileSystemNode::Directory { name, .. } => name,
+        }
+    }
+
+    fn add_child(&mut self, child: FileSystemNode) -> Result<(), String> {
+        match self {
+            FileSystemNode::File { name, .. } => Err(format!("文件 {} 不能添加子节点", name)),
+            FileSystemNode::Directory { children, .. } => {
+                children.push(child);
+                Ok(())
+            }
+        }
+    }
+
+    // 递归汇总大小，空目录贡献0
+    fn total_size(&self) -> u64 {
+        match self {
+            FileSystemNode::File { size, .. } => *size,
+            FileSystemNode::Directory { children, .. } => children.iter().map(FileSystemNode::total_size).sum(),
+        }
+    }
+
+    // 深度优先查找第一个同名节点（包括自身）
+    fn find_by_name(&self, target: &str) -> Option<&FileSystemNode> {
+        if self.name() == target {
+            return Some(self);
+        }
+        match self {
+            FileSystemNode::File { .. } => None,
+            FileSystemNode::Directory { children, .. } => children.iter().find_map(|child| child.find_by_name(target)),
+        }
+    }
+
+    // 渲染目录树，目录名后带 / 和汇总大小
+    fn tree_string(&self) -> String {
+        let mut lines = vec![self.label()];
+        self.render_children("", &mut lines);
+        lines.join("\n")
+    }
+
+    fn label(&self) -> String {
+        match self {
+            FileSystemNode::File { name, size } => format!("{} ({}KB)", name, size),
+            FileSystemNode::Directory { name, .. } => format!("{}/ ({}KB)", name, self.total_size()),
+        }
+    }
+
+    fn render_children(&self, prefix: &str, lines: &mut Vec<String>) {
+        if let FileSystemNode::Directory { children, .. } = self {
+            for (index, child) in children.iter().enumerate() {
+                let is_last = index + 1 == children.len();
+                let (branch, indent) = if is_last { ("└── ", "    ") } else { ("├── ", "│   ") };
+                lines.push(format!("{}{}{}", prefix, branch, child.label()));
+                child.render_children(&format!("{}{}", prefix, indent), lines);
+            }
+        }
+    }
+}
+
 pub fn demo() {
     println!("=== 组合模式演示 ===");
 
@@ -116,4 +193,70 @@ pub fn demo() {
 
     // 统一操作
     root.operation();
+
+    // 枚举实现：汇总大小、查找和渲染目录树
+    println!("\n文件系统节点:");
+    let tree = FileSystemNode::directory("根目录", vec![
+        FileSystemNode::directory("文档", vec![
+            FileSystemNode::file("报告.docx", 120),
+            FileSystemNode::file("笔记.txt", 25),
+        ]),
+        FileSystemNode::directory("回收站", Vec::new()),
+        FileSystemNode::file("系统文件.sys", 500),
+    ]);
+    println!("{}", tree.tree_string());
+    println!("总大小: {}KB", tree.total_size());
+    if let Some(node) = tree.find_by_name("笔记.txt") {
+        println!("找到: {}", node.label());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tree() -> FileSystemNode {
+        FileSystemNode::directory("root", vec![
+            FileSystemNode::directory("src", vec![
+                FileSystemNode::file("main.rs", 12),
+                FileSystemNode::directory("util", vec![FileSystemNode::file("mod.rs", 3)]),
+            ]),
+            FileSystemNode::directory("empty", Vec::new()),
+            FileSystemNode::file("Cargo.toml", 1),
+        ])
+    }
+
+    #[test]
+    fn test_total_size_aggregates_recursively() {
+        let mut tree = sample_tree();
+        assert_eq!(tree.total_size(), 16);
+        assert_eq!(tree.find_by_name("empty").unwrap().total_size(), 0);
+        assert_eq!(tree.find_by_name("src").unwrap().total_size(), 15);
+
+        tree.add_child(FileSystemNode::file("README.md", 4)).unwrap();
+        assert_eq!(tree.total_size(), 20);
+        assert!(FileSystemNode::file("a", 1).add_child(FileSystemNode::file("b", 1)).is_err());
+    }
+
+    #[test]
+    fn test_find_by_name() {
+        let tree = sample_tree();
+        assert_eq!(tree.find_by_name("mod.rs"), Some(&FileSystemNode::file("mod.rs", 3)));
+        assert_eq!(tree.find_by_name("root").map(FileSystemNode::name), Some("root"));
+        assert_eq!(tree.find_by_name("missing.rs"), None);
+    }
+
+    #[test]
+    fn test_tree_string_shape() {
+        let expected = [
+            "root/ (16KB)",
+            "├── src/ (15KB)",
+            "│   ├── main.rs (12KB)",
+            "│   └── util/ (3KB)",
+            "│       └── mod.rs (3KB)",
+            "├── empty/ (0KB)",
+            "└── Cargo.toml (1KB)",
+        ];
+        assert_eq!(sample_tree().tree_string(), expected.join("\n"));
+    }
 } 
\ No newline at end of file
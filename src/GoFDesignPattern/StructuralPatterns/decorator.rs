@@ -3,6 +3,9 @@
 //! 动态地给一个对象添加一些额外的职责。就增加功能来说，装饰器模式相比生成子类更为灵活。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/StructuralPatterns/decorator.rs
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 // 组件接口
 trait Coffee {
     fn cost(&self) -> f64;
@@ -108,6 +111,167 @@ impl CoffeeDecorator for ChocolateDecorator {
     }
 }
 
+// 另一个例子 - 数据流装饰器
+// 写入时由外向内逐层变换，读取时由内向外逐层还原
+trait DataStream {
+    fn write(&mut self, bytes: &[u8]);
+    // 读取全部数据
+    fn read(&mut self) -> Vec<u8>;
+    // 把缓冲的数据写到下层，默认什么也不做
+    fn flush(&mut self) {}
+}
+
+// 具体组件 - 内存流，存储可以共享给另一个流读取
+struct MemoryStream {
+    storage: Rc<RefCell<Vec<u8>>>,
+}
+
+impl MemoryStream {
+    fn new() -> Self {
+        Self::with_storage(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    fn with_storage(storage: Rc<RefCell<Vec<u8>>>) -> Self {
+        Self { storage }
+    }
+
+    fn storage(&self) -> Rc<RefCell<Vec<u8>>> {
+        Rc::clone(&self.storage)
+    }
+}
+
+impl DataStream for MemoryStream {
+    fn write(&mut self, bytes: &[u8]) {
+        self.storage.borrow_mut().extend_from_slice(bytes);
+    }
+
+    fn read(&mut self) -> Vec<u8> {
+        self.storage.borrow().clone()
+    }
+}
+
+// 具体装饰器 - 缓冲，攒够 capacity 字节才写到下层
+struct Buffered {
+    inner: Box<dyn DataStream>,
+    buffer: Vec<u8>,
+    capacity: usize,
+}
+
+impl Buffered {
+    fn new(inner: Box<dyn DataStream>, capacity: usize) -> Self {
+        Self { inner, buffer: Vec::with_capacity(capacity), capacity }
+    }
+}
+
+impl DataStream for Buffered {
+    fn write(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        if self.buffer.len() >= self.capacity {
+            self.flush();
+        }
+    }
+
+    fn read(&mut self) -> Vec<u8> {
+        self.flush();
+        self.inner.read()
+    }
+
+    fn flush(&mut self) {
+        if !self.buffer.is_empty() {
+            let pending = std::mem::take(&mut self.buffer);
+            self.inner.write(&pending);
+        }
+        self.inner.flush();
+    }
+}
+
+// 具体装饰器 - 压缩，使用游程编码：(重复次数, 字节) 成对存储
+struct Compressed {
+    inner: Box<dyn DataStream>,
+}
+
+impl Compressed {
+    fn new(inner: Box<dyn DataStream>) -> Self {
+        Self { inner }
+    }
+
+    fn encode(bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        let mut iter = bytes.iter().peekable();
+        while let Some(&byte) = iter.next() {
+            let mut count: u8 = 1;
+            while count < u8::MAX && iter.peek() == Some(&&byte) {
+                iter.next();
+                count += 1;
+            }
+            encoded.push(count);
+            encoded.push(byte);
+        }
+        encoded
+    }
+
+    fn decode(encoded: &[u8]) -> Vec<u8> {
+        encoded
+            .chunks_exact(2)
+            .flat_map(|pair| std::iter::repeat_n(pair[1], pair[0] as usize))
+            .collect()
+    }
+}
+
+impl DataStream for Compressed {
+    // 每次写入独立编码，编码结果拼接后仍可整体解码
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(&Self::encode(bytes));
+    }
+
+    fn read(&mut self) -> Vec<u8> {
+        Self::decode(&self.inner.read())
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush();
+    }
+}
+
+// 具体装饰器 - 加密，与循环使用的密钥逐字节异或，密钥位置按流中的偏移计算
+struct Encrypted {
+    inner: Box<dyn DataStream>,
+    key: Vec<u8>,
+    written: usize,
+}
+
+impl Encrypted {
+    fn new(inner: Box<dyn DataStream>, key: &[u8]) -> Self {
+        assert!(!key.is_empty(), "密钥不能为空");
+        Self { inner, key: key.to_vec(), written: 0 }
+    }
+
+    fn apply(&self, bytes: &[u8], offset: usize) -> Vec<u8> {
+        bytes
+            .iter()
+            .enumerate()
+            .map(|(index, byte)| byte ^ self.key[(offset + index) % self.key.len()])
+            .collect()
+    }
+}
+
+impl DataStream for Encrypted {
+    fn write(&mut self, bytes: &[u8]) {
+        let encrypted = self.apply(bytes, self.written);
+        self.written += bytes.len();
+        self.inner.write(&encrypted);
+    }
+
+    fn read(&mut self) -> Vec<u8> {
+        let encrypted = self.inner.read();
+        self.apply(&encrypted, 0)
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush();
+    }
+}
+
 pub fn demo() {
     println!("=== 装饰器模式演示 ===");
 
@@ -126,4 +290,90 @@ pub fn demo() {
     // 最后添加巧克力
     let luxury_coffee = Box::new(ChocolateDecorator::new(sweet_coffee));
     println!("{}: ¥{:.2}", luxury_coffee.description(), luxury_coffee.cost());
-} 
\ No newline at end of file
+
+    // 数据流装饰器：组合顺序影响存储结果
+    println!("\n数据流装饰器:");
+    let data = b"aaaaaaaabbbbbbbbcccccccc";
+    let key = b"k3y!";
+
+    let compress_first = MemoryStream::new();
+    let compress_first_storage = compress_first.storage();
+    let mut stream = Encrypted::new(Box::new(Compressed::new(Box::new(compress_first))), key);
+    stream.write(data);
+    println!("先加密后压缩: 存储 {} 字节", compress_first_storage.borrow().len());
+
+    let encrypt_last = MemoryStream::new();
+    let encrypt_last_storage = encrypt_last.storage();
+    let mut stream = Compressed::new(Box::new(Encrypted::new(Box::new(encrypt_last), key)));
+    stream.write(data);
+    println!("先压缩后加密: 存储 {} 字节", encrypt_last_storage.borrow().len());
+    println!("读回: {}", String::from_utf8_lossy(&stream.read()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"secret";
+
+    #[test]
+    fn test_encrypted_compressed_round_trip_through_inverse_stack() {
+        let data = b"hello hello hellooooooo world!!!!".to_vec();
+
+        let base = MemoryStream::new();
+        let storage = base.storage();
+        let mut writer = Encrypted::new(Box::new(Compressed::new(Box::new(base))), KEY);
+        writer.write(&data[..10]);
+        writer.write(&data[10..]);
+        assert_ne!(*storage.borrow(), data);
+
+        // 用同样的装饰器组合打开同一份存储，读取时逐层还原
+        let mut reader = Encrypted::new(Box::new(Compressed::new(Box::new(MemoryStream::with_storage(storage)))), KEY);
+        assert_eq!(reader.read(), data);
+
+        // 密钥不对时得不到原文
+        let mut wrong_key = Encrypted::new(Box::new(Compressed::new(Box::new(MemoryStream::with_storage(writer_storage(&data))))), b"other");
+        assert_ne!(wrong_key.read(), data);
+    }
+
+    fn writer_storage(data: &[u8]) -> Rc<RefCell<Vec<u8>>> {
+        let base = MemoryStream::new();
+        let storage = base.storage();
+        Encrypted::new(Box::new(Compressed::new(Box::new(base))), KEY).write(data);
+        storage
+    }
+
+    #[test]
+    fn test_composition_order_matters() {
+        let data = vec![b'x'; 100];
+
+        // 先压缩再加密：游程完整，存储很小
+        let base = MemoryStream::new();
+        let compressed_then_encrypted = base.storage();
+        Compressed::new(Box::new(Encrypted::new(Box::new(base), KEY))).write(&data);
+
+        // 先加密再压缩：密文打断了游程，几乎无法压缩
+        let base = MemoryStream::new();
+        let encrypted_then_compressed = base.storage();
+        Encrypted::new(Box::new(Compressed::new(Box::new(base))), KEY).write(&data);
+
+        assert_eq!(compressed_then_encrypted.borrow().len(), 2);
+        assert_eq!(encrypted_then_compressed.borrow().len(), 200);
+    }
+
+    #[test]
+    fn test_buffered_stream_flushes_on_capacity_and_read() {
+        let base = MemoryStream::new();
+        let storage = base.storage();
+        let mut stream = Buffered::new(Box::new(Compressed::new(Box::new(base))), 4);
+
+        stream.write(b"ab");
+        assert!(storage.borrow().is_empty());
+        stream.write(b"cc");
+        assert_eq!(*storage.borrow(), vec![1, b'a', 1, b'b', 2, b'c']);
+
+        stream.write(b"d");
+        assert_eq!(stream.read(), b"abccd".to_vec());
+        assert_eq!(Compressed::decode(&Compressed::encode(&[7; 300])), vec![7; 300]);
+    }
+}
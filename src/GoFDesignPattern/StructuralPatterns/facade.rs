@@ -2,6 +2,9 @@
 //! 
 //! 为子系统中的一组接口提供一个一致的界面，外观模式定义了一个高层接口，这个接口使得这一子系统更加容易使用。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/StructuralPatterns/facade.rs
+//!
+//! DashboardFacade 汇总订单、用户、库存三个子系统的数据生成一个仪表盘，
+//! 某个子系统出错时该板块标记为不可用，其余板块照常返回。
 
 // 子系统 - CPU
 struct CPU;
@@ -136,6 +139,159 @@ impl HomeTheaterFacade {
     }
 }
 
+// 第三个例子 - 运营仪表盘
+// 订单统计
+#[derive(Debug, Clone, PartialEq)]
+struct OrderStats {
+    count: u32,
+    revenue: f64,
+}
+
+// 子系统接口 - 订单、用户、库存
+trait OrderService {
+    fn today_stats(&self) -> Result<OrderStats, String>;
+}
+
+trait UserService {
+    fn active_users(&self) -> Result<u32, String>;
+}
+
+trait InventoryService {
+    fn low_stock_items(&self) -> Result<Vec<String>, String>;
+}
+
+struct OrderSubsystem {
+    amounts: Vec<f64>,
+}
+
+impl OrderService for OrderSubsystem {
+    fn today_stats(&self) -> Result<OrderStats, String> {
+        Ok(OrderStats { count: self.amounts.len() as u32, revenue: self.amounts.iter().sum() })
+    }
+}
+
+struct UserSubsystem {
+    online: u32,
+}
+
+impl UserService for UserSubsystem {
+    fn active_users(&self) -> Result<u32, String> {
+        Ok(self.online)
+    }
+}
+
+struct InventorySubsystem {
+    stock: Vec<(String, u32)>,
+    threshold: u32,
+}
+
+impl InventoryService for InventorySubsystem {
+    fn low_stock_items(&self) -> Result<Vec<String>, String> {
+        Ok(self.stock.iter()
+            .filter(|(_, quantity)| *quantity < self.threshold)
+            .map(|(sku, _)| sku.clone())
+            .collect())
+    }
+}
+
+// 无法访问的子系统，任何调用都返回错误
+struct UnreachableSubsystem(&'static str);
+
+impl OrderService for UnreachableSubsystem {
+    fn today_stats(&self) -> Result<OrderStats, String> {
+        Err(format!("{}连接超时", self.0))
+    }
+}
+
+impl UserService for UnreachableSubsystem {
+    fn active_users(&self) -> Result<u32, String> {
+        Err(format!("{}连接超时", self.0))
+    }
+}
+
+impl InventoryService for UnreachableSubsystem {
+    fn low_stock_items(&self) -> Result<Vec<String>, String> {
+        Err(format!("{}连接超时", self.0))
+    }
+}
+
+// 仪表盘板块 - 可用时带数据，不可用时带原因
+#[derive(Debug, Clone, PartialEq)]
+enum Section<T> {
+    Available(T),
+    Unavailable(String),
+}
+
+impl<T> Section<T> {
+    fn from_result(result: Result<T, String>) -> Self {
+        match result {
+            Ok(data) => Section::Available(data),
+            Err(reason) => Section::Unavailable(reason),
+        }
+    }
+
+    fn is_available(&self) -> bool {
+        matches!(self, Section::Available(_))
+    }
+}
+
+// 汇总后的仪表盘
+#[derive(Debug, Clone, PartialEq)]
+struct DashboardView {
+    orders: Section<OrderStats>,
+    users: Section<u32>,
+    inventory: Section<Vec<String>>,
+}
+
+impl DashboardView {
+    fn unavailable_sections(&self) -> Vec<&'static str> {
+        [("订单", self.orders.is_available()), ("用户", self.users.is_available()), ("库存", self.inventory.is_available())]
+            .into_iter()
+            .filter(|(_, available)| !available)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn render(&self) -> String {
+        let orders = match &self.orders {
+            Section::Available(stats) => format!("订单: {}笔, 营收{:.2}", stats.count, stats.revenue),
+            Section::Unavailable(reason) => format!("订单: 暂不可用 ({})", reason),
+        };
+        let users = match &self.users {
+            Section::Available(count) => format!("活跃用户: {}", count),
+            Section::Unavailable(reason) => format!("活跃用户: 暂不可用 ({})", reason),
+        };
+        let inventory = match &self.inventory {
+            Section::Available(items) if items.is_empty() => "低库存: 无".to_string(),
+            Section::Available(items) => format!("低库存: {}", items.join(", ")),
+            Section::Unavailable(reason) => format!("低库存: 暂不可用 ({})", reason),
+        };
+        [orders, users, inventory].join("\n")
+    }
+}
+
+// 仪表盘外观 - 客户端只需调用一次dashboard
+struct DashboardFacade {
+    orders: Box<dyn OrderService>,
+    users: Box<dyn UserService>,
+    inventory: Box<dyn InventoryService>,
+}
+
+impl DashboardFacade {
+    fn new(orders: Box<dyn OrderService>, users: Box<dyn UserService>, inventory: Box<dyn InventoryService>) -> Self {
+        Self { orders, users, inventory }
+    }
+
+    // 每个子系统单独调用，一个失败不影响其他板块
+    fn dashboard(&self) -> DashboardView {
+        DashboardView {
+            orders: Section::from_result(self.orders.today_stats()),
+            users: Section::from_result(self.users.active_users()),
+            inventory: Section::from_result(self.inventory.low_stock_items()),
+        }
+    }
+}
+
 pub fn demo() {
     println!("=== 外观模式演示 ===");
 
@@ -148,8 +304,67 @@ pub fn demo() {
     theater.watch_movie("阿凡达");
     theater.end_movie();
 
+    println!("\n3. 运营仪表盘外观:");
+    let dashboard = DashboardFacade::new(
+        Box::new(OrderSubsystem { amounts: vec![99.0, 238.5, 45.0] }),
+        Box::new(UnreachableSubsystem("用户服务")),
+        Box::new(InventorySubsystem { stock: vec![("键盘".to_string(), 3), ("鼠标".to_string(), 50)], threshold: 10 }),
+    ).dashboard();
+    println!("{}", dashboard.render());
+    println!("不可用板块: {:?}", dashboard.unavailable_sections());
+
     println!("\n外观模式的优点:");
     println!("1. 简化复杂子系统的使用");
     println!("2. 降低客户端与子系统的耦合");
     println!("3. 提供统一的接口");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory() -> Box<InventorySubsystem> {
+        Box::new(InventorySubsystem {
+            stock: vec![("键盘".to_string(), 3), ("鼠标".to_string(), 50), ("显示器".to_string(), 0)],
+            threshold: 5,
+        })
+    }
+
+    #[test]
+    fn test_dashboard_aggregates_all_subsystems() {
+        let facade = DashboardFacade::new(
+            Box::new(OrderSubsystem { amounts: vec![10.0, 20.5] }),
+            Box::new(UserSubsystem { online: 42 }),
+            inventory(),
+        );
+
+        let view = facade.dashboard();
+        assert_eq!(view.orders, Section::Available(OrderStats { count: 2, revenue: 30.5 }));
+        assert_eq!(view.users, Section::Available(42));
+        assert_eq!(view.inventory, Section::Available(vec!["键盘".to_string(), "显示器".to_string()]));
+        assert!(view.unavailable_sections().is_empty());
+    }
+
+    #[test]
+    fn test_failed_subsystem_marked_unavailable() {
+        let facade = DashboardFacade::new(
+            Box::new(OrderSubsystem { amounts: vec![10.0] }),
+            Box::new(UnreachableSubsystem("用户服务")),
+            inventory(),
+        );
+
+        // 用户服务失败，仪表盘仍然返回，其余板块完整
+        let view = facade.dashboard();
+        assert_eq!(view.users, Section::Unavailable("用户服务连接超时".to_string()));
+        assert!(view.orders.is_available() && view.inventory.is_available());
+        assert_eq!(view.unavailable_sections(), vec!["用户"]);
+        assert_eq!(view.render(), "订单: 1笔, 营收10.00\n活跃用户: 暂不可用 (用户服务连接超时)\n低库存: 键盘, 显示器");
+
+        let all_down = DashboardFacade::new(
+            Box::new(UnreachableSubsystem("订单服务")),
+            Box::new(UnreachableSubsystem("用户服务")),
+            Box::new(UnreachableSubsystem("库存服务")),
+        );
+        assert_eq!(all_down.dashboard().unavailable_sections(), vec!["订单", "用户", "库存"]);
+    }
+}
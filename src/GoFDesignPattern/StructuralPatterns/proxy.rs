@@ -2,8 +2,18 @@
 //! 
 //! 为其他对象提供一种代理以控制对这个对象的访问。
 //! 文件路径：/d%3A/workspace/RustLearn/RustDesignPattern/src/GoFDesignPattern/StructuralPatterns/proxy.rs
+//!
+//! RateLimitingProxy 复用 API网关的 RateLimiter，按调用方分别计数，
+//! 某个调用方超出配额只会限制它自己，其他调用方不受影响。
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::DistributedSystemMode::CommunicationPatterns::api_gateway::{RateLimit, RateLimiter};
 
 // 主题接口
 trait Image {
@@ -151,6 +161,207 @@ impl FileSystem for FileSystemProxy {
     }
 }
 
+// 缓存代理示例
+trait DataSource: Send + Sync {
+    fn load(&self, key: &str) -> Result<String, String>;
+}
+
+struct CacheEntry {
+    value: String,
+    expires_at: Instant,
+}
+
+// 正在进行中的加载，等待者通过条件变量获取结果
+type InFlightLoad = Arc<(Mutex<Option<Result<String, String>>>, Condvar)>;
+
+// 加载者离开时（包括数据源 panic）写入兜底结果、唤醒等待者并移除进行中的登记
+struct InFlightGuard<'a> {
+    in_flight: &'a Mutex<HashMap<String, InFlightLoad>>,
+    key: &'a str,
+    load: InFlightLoad,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let (slot, ready) = &*self.load;
+        let mut result = slot.lock().unwrap_or_else(|e| e.into_inner());
+        if result.is_none() {
+            *result = Some(Err(format!("加载 {} 时数据源 panic", self.key)));
+        }
+        ready.notify_all();
+        drop(result);
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(self.key);
+    }
+}
+
+// 缓存代理 - 为数据源加上带TTL抖动的缓存，并合并对同一键的并发加载
+struct CachingProxy<S: DataSource> {
+    source: S,
+    ttl: Duration,
+    jitter: Duration,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    in_flight: Mutex<HashMap<String, InFlightLoad>>,
+    rng: Mutex<StdRng>,
+}
+
+impl<S: DataSource> CachingProxy<S> {
+    fn new(source: S, ttl: Duration) -> Self {
+        Self {
+            source,
+            ttl,
+            jitter: Duration::ZERO,
+            entries: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    // 每个条目的实际TTL为 ttl + [0, jitter)，避免同时创建的条目同时过期
+    fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    // 固定随机种子，便于复现
+    fn with_seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn get(&self, key: &str) -> Result<String, String> {
+        if let Some(entry) = self.entries.lock().unwrap().get(key) {
+            if entry.expires_at > Instant::now() {
+                return Ok(entry.value.clone());
+            }
+        }
+        self.load_coalesced(key)
+    }
+
+    // 批量获取，批次内重复的键只加载一次；不同的键在作用域线程中并发获取
+    fn get_many(&self, keys: &[&str]) -> HashMap<String, Result<String, String>> {
+        let unique: HashSet<&str> = keys.iter().copied().collect();
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = unique.into_iter()
+                .map(|key| (key, scope.spawn(move || self.get(key))))
+                .collect();
+            handles.into_iter()
+                .map(|(key, handle)| {
+                    let result = handle.join().unwrap_or_else(|_| Err(format!("加载 {} 时数据源 panic", key)));
+                    (key.to_string(), result)
+                })
+                .collect()
+        })
+    }
+
+    fn expires_at(&self, key: &str) -> Option<Instant> {
+        self.entries.lock().unwrap().get(key).map(|entry| entry.expires_at)
+    }
+
+    // 同一键的并发加载只有第一个调用者访问数据源，其余调用者等待其结果
+    fn load_coalesced(&self, key: &str) -> Result<String, String> {
+        let (load, is_leader) = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some(load) => (Arc::clone(load), false),
+                None => {
+                    // 上一个加载者可能刚刚写入缓存并退出
+                    if let Some(entry) = self.entries.lock().unwrap().get(key) {
+                        if entry.expires_at > Instant::now() {
+                            return Ok(entry.value.clone());
+                        }
+                    }
+                    let load: InFlightLoad = Arc::new((Mutex::new(None), Condvar::new()));
+                    in_flight.insert(key.to_string(), Arc::clone(&load));
+                    (load, true)
+                }
+            }
+        };
+
+        let (slot, ready) = &*load;
+        if !is_leader {
+            let mut result = slot.lock().unwrap();
+            while result.is_none() {
+                result = ready.wait(result).unwrap();
+            }
+            return result.clone().unwrap();
+        }
+
+        let _guard = InFlightGuard { in_flight: &self.in_flight, key, load: Arc::clone(&load) };
+        let result = self.source.load(key);
+        if let Ok(ref value) = result {
+            let expires_at = Instant::now() + self.ttl + self.random_jitter();
+            self.entries.lock().unwrap().insert(key.to_string(), CacheEntry { value: value.clone(), expires_at });
+        }
+
+        *slot.lock().unwrap() = Some(result.clone());
+        result
+    }
+
+    fn random_jitter(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return Duration::ZERO;
+        }
+        let nanos = self.rng.lock().unwrap().gen_range(0..self.jitter.as_nanos() as u64);
+        Duration::from_nanos(nanos)
+    }
+}
+
+// 模拟较慢的远程数据源
+struct SlowDatabase {
+    delay: Duration,
+}
+
+impl DataSource for SlowDatabase {
+    fn load(&self, key: &str) -> Result<String, String> {
+        std::thread::sleep(self.delay);
+        println!("从数据库加载: {}", key);
+        Ok(format!("{}的数据", key))
+    }
+}
+
+// 限流代理示例
+trait ApiService {
+    fn handle(&self, request: &str) -> String;
+}
+
+// 真实主题 - 报表服务
+struct ReportService;
+
+impl ApiService for ReportService {
+    fn handle(&self, request: &str) -> String {
+        format!("报表: {}", request)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ProxyError {
+    // 调用方超出了自己的请求配额
+    RateLimited(String),
+}
+
+// 限流代理 - 每个调用方独立的请求配额
+struct RateLimitingProxy<S: ApiService> {
+    subject: S,
+    limiter: RateLimiter,
+    limit: RateLimit,
+}
+
+impl<S: ApiService> RateLimitingProxy<S> {
+    fn new(subject: S, requests_per_minute: u32) -> Self {
+        Self {
+            subject,
+            limiter: RateLimiter::new(),
+            limit: RateLimit { requests_per_minute, requests_per_hour: u64::from(requests_per_minute) * 60 },
+        }
+    }
+
+    fn call(&self, caller: &str, request: &str) -> Result<String, ProxyError> {
+        self.limiter.check_rate_limit(caller, &self.limit)
+            .map_err(|_| ProxyError::RateLimited(caller.to_string()))?;
+        Ok(self.subject.handle(request))
+    }
+}
+
 pub fn demo() {
     println!("=== 代理模式演示 ===");
 
@@ -176,4 +387,203 @@ pub fn demo() {
         Ok(content) => println!("读取成功: {}", content),
         Err(e) => println!("读取失败: {}", e),
     }
-} 
\ No newline at end of file
+
+    println!("\n3. 缓存代理 - 批量获取与TTL抖动:");
+    let cache = CachingProxy::new(SlowDatabase { delay: Duration::from_millis(10) }, Duration::from_secs(60))
+        .with_jitter(Duration::from_secs(5));
+    let results = cache.get_many(&["user:1", "user:2", "user:1"]);
+    println!("批量获取 {} 个不同的键", results.len());
+    if let Ok(value) = cache.get("user:1") {
+        println!("缓存命中: {}", value);
+    }
+
+    println!("\n4. 限流代理 - 按调用方限流:");
+    let limited = RateLimitingProxy::new(ReportService, 2);
+    for caller in ["crawler", "crawler", "crawler", "dashboard"] {
+        match limited.call(caller, "月度销售") {
+            Ok(result) => println!("{} -> {}", caller, result),
+            Err(e) => println!("{} -> 被拒绝: {:?}", caller, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingSource {
+        loads: Arc<AtomicUsize>,
+        delay: Duration,
+    }
+
+    impl DataSource for CountingSource {
+        fn load(&self, key: &str) -> Result<String, String> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(self.delay);
+            Ok(format!("value-{}", key))
+        }
+    }
+
+    fn counting_proxy(delay: Duration) -> (CachingProxy<CountingSource>, Arc<AtomicUsize>) {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let source = CountingSource { loads: Arc::clone(&loads), delay };
+        (CachingProxy::new(source, Duration::from_secs(60)), loads)
+    }
+
+    #[test]
+    fn test_get_many_coalesces_duplicate_keys() {
+        let (proxy, loads) = counting_proxy(Duration::ZERO);
+
+        let results = proxy.get_many(&["a", "b", "a", "c", "b"]);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["a"], Ok("value-a".to_string()));
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+
+        // 再次批量获取全部命中缓存
+        proxy.get_many(&["a", "b", "c"]);
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+    }
+
+    // 只有预期数量的加载同时进行时才返回成功，顺序加载会等到超时
+    struct GatheringSource {
+        arrived: Mutex<usize>,
+        all_arrived: Condvar,
+        expected: usize,
+    }
+
+    impl DataSource for GatheringSource {
+        fn load(&self, key: &str) -> Result<String, String> {
+            let mut arrived = self.arrived.lock().unwrap();
+            *arrived += 1;
+            self.all_arrived.notify_all();
+            let (arrived, timeout) = self.all_arrived
+                .wait_timeout_while(arrived, Duration::from_secs(5), |arrived| *arrived < self.expected)
+                .unwrap();
+            if timeout.timed_out() {
+                return Err(format!("{} 加载时只有 {} 个并发加载", key, *arrived));
+            }
+            Ok(format!("value-{}", key))
+        }
+    }
+
+    #[test]
+    fn test_get_many_loads_distinct_keys_concurrently() {
+        let source = GatheringSource { arrived: Mutex::new(0), all_arrived: Condvar::new(), expected: 3 };
+        let proxy = CachingProxy::new(source, Duration::from_secs(60));
+
+        let results = proxy.get_many(&["a", "b", "c", "a"]);
+
+        assert_eq!(results.len(), 3);
+        for key in ["a", "b", "c"] {
+            assert_eq!(results[key], Ok(format!("value-{}", key)));
+        }
+    }
+
+    #[test]
+    fn test_concurrent_loads_of_same_key_hit_source_once() {
+        let (proxy, loads) = counting_proxy(Duration::from_millis(50));
+        let proxy = Arc::new(proxy);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let proxy = Arc::clone(&proxy);
+                std::thread::spawn(move || proxy.get_many(&["shared"]))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap()["shared"], Ok("value-shared".to_string()));
+        }
+
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    // 第一次加载在收到信号后 panic，之后的加载正常返回
+    struct PanicOnceSource {
+        loads: AtomicUsize,
+        started: Mutex<std::sync::mpsc::Sender<()>>,
+        proceed: Mutex<std::sync::mpsc::Receiver<()>>,
+    }
+
+    impl DataSource for PanicOnceSource {
+        fn load(&self, key: &str) -> Result<String, String> {
+            if self.loads.fetch_add(1, Ordering::SeqCst) == 0 {
+                self.started.lock().unwrap().send(()).unwrap();
+                self.proceed.lock().unwrap().recv().unwrap();
+                panic!("数据源加载 {} 失败", key);
+            }
+            Ok(format!("value-{}", key))
+        }
+    }
+
+    #[test]
+    fn test_panicking_leader_releases_waiters_and_key() {
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (proceed_tx, proceed_rx) = std::sync::mpsc::channel();
+        let source = PanicOnceSource { loads: AtomicUsize::new(0), started: Mutex::new(started_tx), proceed: Mutex::new(proceed_rx) };
+        let proxy = Arc::new(CachingProxy::new(source, Duration::from_secs(60)));
+
+        let leader = {
+            let proxy = Arc::clone(&proxy);
+            std::thread::spawn(move || proxy.get("k"))
+        };
+        started_rx.recv().unwrap();
+        let waiter = {
+            let proxy = Arc::clone(&proxy);
+            std::thread::spawn(move || proxy.get("k"))
+        };
+        proceed_tx.send(()).unwrap();
+
+        assert!(leader.join().is_err());
+        // 等待者要么拿到兜底错误，要么在登记移除后自己重新加载，不会一直阻塞
+        match waiter.join().unwrap() {
+            Ok(value) => assert_eq!(value, "value-k"),
+            Err(message) => assert_eq!(message, "加载 k 时数据源 panic"),
+        }
+        assert!(proxy.in_flight.lock().unwrap().is_empty());
+        assert_eq!(proxy.get("k"), Ok("value-k".to_string()));
+    }
+
+    #[test]
+    fn test_ttl_jitter_spreads_expiry() {
+        let (proxy, _) = counting_proxy(Duration::ZERO);
+        let proxy = proxy.with_jitter(Duration::from_secs(10)).with_seed(7);
+
+        let before = Instant::now();
+        proxy.get_many(&["x", "y"]);
+
+        let x = proxy.expires_at("x").unwrap();
+        let y = proxy.expires_at("y").unwrap();
+        assert_ne!(x, y);
+        for expiry in [x, y] {
+            assert!(expiry >= before + Duration::from_secs(60));
+            assert!(expiry < Instant::now() + Duration::from_secs(70));
+        }
+    }
+
+    #[test]
+    fn test_rate_limiting_proxy_throttles_only_exhausted_caller() {
+        let proxy = RateLimitingProxy::new(ReportService, 3);
+
+        for _ in 0..3 {
+            assert_eq!(proxy.call("alice", "日报"), Ok("报表: 日报".to_string()));
+        }
+        // alice用完配额后被限流
+        assert_eq!(proxy.call("alice", "日报"), Err(ProxyError::RateLimited("alice".to_string())));
+        assert_eq!(proxy.call("alice", "周报"), Err(ProxyError::RateLimited("alice".to_string())));
+
+        // bob有自己的配额，不受alice影响
+        for _ in 0..3 {
+            assert_eq!(proxy.call("bob", "周报"), Ok("报表: 周报".to_string()));
+        }
+        assert!(proxy.call("bob", "周报").is_err());
+    }
+
+    #[test]
+    fn test_hourly_quota_is_not_truncated_for_large_minute_quota() {
+        let proxy = RateLimitingProxy::new(ReportService, u32::MAX);
+        assert_eq!(proxy.limit.requests_per_hour, u64::from(u32::MAX) * 60);
+        assert!(proxy.call("carol", "日报").is_ok());
+    }
+}